@@ -18,13 +18,6 @@ _CPP_BEGIN
 #define MOLECULE_API_DECORATOR
 #endif /* MOLECULE_API_DECORATOR */
 
-/*
- * This part is not for normal users.
- */
-
-// Test if the host is big endian machine.
-#define is_le()                 (*(unsigned char *)&(uint16_t){1})
-
 /*
  * Definitions of types and simple utilities.
  */
@@ -49,15 +42,21 @@ typedef struct {
 
 /* Utilities. */
 
+// Pack a number into little-endian bytes.
+//  - All numbers go through it, so it works on any host endianness.
+//  - The bytes are not required to be aligned.
 MOLECULE_API_DECORATOR void mol_pack_number(uint8_t *dst, mol_num_t *num) {
-    const uint8_t *src = (const uint8_t *)num;
-    if (is_le()) {
-        memcpy(dst, src, MOL_NUM_T_SIZE);
-    } else {
-        dst[3] = src[0];
-        dst[2] = src[1];
-        dst[1] = src[2];
-        dst[0] = src[3];
+    dst[0] = (uint8_t)(*num);
+    dst[1] = (uint8_t)(*num >> 8);
+    dst[2] = (uint8_t)(*num >> 16);
+    dst[3] = (uint8_t)(*num >> 24);
+}
+
+// Pack the item count of a FixVec into `count_size` bytes.
+//  - The count should fit in `count_size` bytes.
+MOLECULE_API_DECORATOR void mol_pack_count(uint8_t *dst, mol_num_t *num, mol_num_t count_size) {
+    for (mol_num_t i = 0; i < count_size; i++) {
+        dst[i] = (uint8_t)(*num >> (8 * i));
     }
 }
 
@@ -243,19 +242,32 @@ MOLECULE_API_DECORATOR mol_seg_res_t mol_builder_finalize_simple(mol_builder_t b
     return res;
 }
 
-MOLECULE_API_DECORATOR mol_seg_res_t mol_fixvec_builder_finalize(mol_builder_t builder) {
+// Finalize a FixVec with a non-standard count size.
+//  - It fails with `MOL_ERR_SIZE_OVERFLOW` if the count doesn't fit in `count_size` bytes.
+MOLECULE_API_DECORATOR mol_seg_res_t mol_fixvec_builder_finalize_with_count_size(mol_builder_t builder, mol_num_t count_size) {
     mol_seg_res_t res;
+    if (count_size < MOL_NUM_T_SIZE && (builder.number_ptr[0] >> (8 * count_size)) != 0) {
+        res.errno = MOL_ERR_SIZE_OVERFLOW;
+        res.seg.ptr = NULL;
+        res.seg.size = 0;
+        mol_builder_discard(builder);
+        return res;
+    }
     res.errno = MOL_OK;
-    res.seg.size = MOL_NUM_T_SIZE + builder.data_used;
+    res.seg.size = count_size + builder.data_used;
     res.seg.ptr = (uint8_t*)malloc(res.seg.size);
-    mol_pack_number(res.seg.ptr, &builder.number_ptr[0]);
+    mol_pack_count(res.seg.ptr, &builder.number_ptr[0], count_size);
     if (builder.data_used > 0) {
-        memcpy((res.seg.ptr+MOL_NUM_T_SIZE), builder.data_ptr, builder.data_used);
+        memcpy((res.seg.ptr+count_size), builder.data_ptr, builder.data_used);
     }
     mol_builder_discard(builder);
     return res;
 }
 
+MOLECULE_API_DECORATOR mol_seg_res_t mol_fixvec_builder_finalize(mol_builder_t builder) {
+    return mol_fixvec_builder_finalize_with_count_size(builder, MOL_NUM_T_SIZE);
+}
+
 MOLECULE_API_DECORATOR mol_seg_res_t mol_dynvec_builder_finalize(mol_builder_t builder) {
     mol_seg_res_t res;
     res.errno = MOL_OK;
@@ -267,8 +279,9 @@ MOLECULE_API_DECORATOR mol_seg_res_t mol_dynvec_builder_finalize(mol_builder_t b
         mol_num_t header_size = MOL_NUM_T_SIZE + builder.number_used;
         for (mol_num_t number_index=0; number_index<number_count; number_index++) {
             builder.number_ptr[number_index] += header_size;
+            mol_pack_number(res.seg.ptr+MOL_NUM_T_SIZE*(number_index+1),
+                    &builder.number_ptr[number_index]);
         }
-        memcpy((res.seg.ptr+MOL_NUM_T_SIZE), builder.number_ptr, builder.number_used);
         memcpy((res.seg.ptr+MOL_NUM_T_SIZE+builder.number_used), builder.data_ptr, builder.data_used);
     }
     mol_builder_discard(builder);
@@ -279,8 +292,6 @@ MOLECULE_API_DECORATOR mol_seg_res_t mol_dynvec_builder_finalize(mol_builder_t b
  * Undef macros which are internal use only.
  */
 
-#undef is_le
-
 #ifdef __DEFINE_MOLECULE_API_DECORATOR
 #undef MOLECULE_API_DECORATOR
 #undef __DEFINE_MOLECULE_API_DECORATOR
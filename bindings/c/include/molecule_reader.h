@@ -15,7 +15,7 @@ _CPP_BEGIN
 #define MOLECULE_API_DECORATOR
 #endif /* MOLECULE_API_DECORATOR */
 
-#define MOLECULE_API_VERSION        4000
+#define MOLECULE_API_VERSION        4003
 #define MOLECULEC_VERSION_MIN       4000
 
 #if MOLECULE_API_VERSION < MOLECULE_API_VERSION_MIN
@@ -29,13 +29,6 @@ _CPP_BEGIN
     with a newer version of moleculec.
 #endif
 
-/*
- * This part is not for normal users.
- */
-
-// Test if the host is big endian machine.
-#define is_le()                 (*(unsigned char *)&(uint16_t){1})
-
 /*
  * Definitions of types and simple utilities.
  */
@@ -80,21 +73,37 @@ typedef struct {
 #define MOL_ERR_INDEX_OUT_OF_BOUNDS         0x05
 #define MOL_ERR_FIELD_COUNT                 0x06
 #define MOL_ERR_DATA                        0x07
+#define MOL_ERR_SIZE_OVERFLOW               0x08
+
+/* Compile-time assertions, such as the sizes of the fixed-size types. */
+
+#ifdef __cplusplus
+#define MOL_STATIC_ASSERT(expr, msg)        static_assert(expr, msg)
+#else
+#define MOL_STATIC_ASSERT(expr, msg)        _Static_assert(expr, msg)
+#endif /* __cplusplus */
 
 /* Utilities. */
 
+// Unpack a number from little-endian bytes.
+//  - All numbers go through it, so it works on any host endianness.
+//  - The bytes are not required to be aligned.
 MOLECULE_API_DECORATOR mol_num_t mol_unpack_number(const uint8_t *src) {
-    if (is_le()) {
-        return *(const uint32_t *)src;
-    } else {
-        uint32_t output = 0;
-        uint8_t *dst = (uint8_t*) &output;
-        dst[3] = src[0];
-        dst[2] = src[1];
-        dst[1] = src[2];
-        dst[0] = src[3];
-        return output;
+    return (mol_num_t)src[0]
+        | ((mol_num_t)src[1] << 8)
+        | ((mol_num_t)src[2] << 16)
+        | ((mol_num_t)src[3] << 24);
+}
+
+// Unpack the item count of a FixVec from its first `count_size` bytes.
+//  - The count size is `MOL_NUM_T_SIZE`, unless the FixVec is declared with
+//    `@count_size`, which is not a standard molecule encoding.
+MOLECULE_API_DECORATOR mol_num_t mol_unpack_count(const uint8_t *src, mol_num_t count_size) {
+    mol_num_t count = 0;
+    for (mol_num_t i = 0; i < count_size; i++) {
+        count |= (mol_num_t)src[i] << (8 * i);
     }
+    return count;
 }
 
 
@@ -109,19 +118,27 @@ MOLECULE_API_DECORATOR mol_errno mol_verify_fixed_size(const mol_seg_t *input, m
     return input->size == total_size ? MOL_OK : MOL_ERR_TOTAL_SIZE;
 }
 
-// Verify FixVec.
-MOLECULE_API_DECORATOR mol_errno mol_fixvec_verify(const mol_seg_t *input, mol_num_t item_size) {
-    if (input->size < MOL_NUM_T_SIZE) {
+// Verify FixVec with a non-standard count size.
+MOLECULE_API_DECORATOR mol_errno mol_fixvec_verify_with_count_size(const mol_seg_t *input, mol_num_t item_size, mol_num_t count_size) {
+    if (input->size < count_size) {
         return MOL_ERR_HEADER;
     }
-    mol_num_t item_count = mol_unpack_number(input->ptr);
+    mol_num_t item_count = mol_unpack_count(input->ptr, count_size);
     if (item_count == 0) {
-        return input->size == MOL_NUM_T_SIZE ? MOL_OK : MOL_ERR_TOTAL_SIZE;
+        return input->size == count_size ? MOL_OK : MOL_ERR_TOTAL_SIZE;
     }
-    mol_num_t total_size = MOL_NUM_T_SIZE + item_size * item_count;
+    if (item_count > (UINT32_MAX - count_size) / item_size) {
+        return MOL_ERR_SIZE_OVERFLOW;
+    }
+    mol_num_t total_size = count_size + item_size * item_count;
     return input->size == total_size ? MOL_OK : MOL_ERR_TOTAL_SIZE;
 }
 
+// Verify FixVec.
+MOLECULE_API_DECORATOR mol_errno mol_fixvec_verify(const mol_seg_t *input, mol_num_t item_size) {
+    return mol_fixvec_verify_with_count_size(input, item_size, MOL_NUM_T_SIZE);
+}
+
 /* Getters.
  *
  * ### Notice
@@ -146,9 +163,14 @@ MOLECULE_API_DECORATOR mol_union_t mol_union_unpack(const mol_seg_t *input) {
     return ret;
 }
 
+// Get the length of a FixVec with a non-standard count size.
+MOLECULE_API_DECORATOR mol_num_t mol_fixvec_length_with_count_size(const mol_seg_t *input, mol_num_t count_size) {
+    return mol_unpack_count(input->ptr, count_size);
+}
+
 // Get the length of a FixVec.
 MOLECULE_API_DECORATOR mol_num_t mol_fixvec_length(const mol_seg_t *input) {
-    return mol_unpack_number(input->ptr);
+    return mol_fixvec_length_with_count_size(input, MOL_NUM_T_SIZE);
 }
 
 // Get the length of a DynVec.
@@ -178,20 +200,25 @@ MOLECULE_API_DECORATOR mol_seg_t mol_slice_by_offset(const mol_seg_t *input, mol
     return seg;
 }
 
-// Slice a segment for FixVec by index.
-MOLECULE_API_DECORATOR mol_seg_res_t mol_fixvec_slice_by_index(const mol_seg_t *input, mol_num_t item_size, mol_num_t item_index) {
+// Slice a segment for FixVec with a non-standard count size by index.
+MOLECULE_API_DECORATOR mol_seg_res_t mol_fixvec_slice_by_index_with_count_size(const mol_seg_t *input, mol_num_t item_size, mol_num_t item_index, mol_num_t count_size) {
     mol_seg_res_t res;
-    mol_num_t item_count = mol_unpack_number(input->ptr);
+    mol_num_t item_count = mol_unpack_count(input->ptr, count_size);
     if (item_index >= item_count) {
         res.errno = MOL_ERR_INDEX_OUT_OF_BOUNDS;
     } else {
         res.errno = MOL_OK;
-        res.seg.ptr = input->ptr + MOL_NUM_T_SIZE + item_size * item_index;
+        res.seg.ptr = input->ptr + count_size + item_size * item_index;
         res.seg.size = item_size;
     }
     return res;
 }
 
+// Slice a segment for FixVec by index.
+MOLECULE_API_DECORATOR mol_seg_res_t mol_fixvec_slice_by_index(const mol_seg_t *input, mol_num_t item_size, mol_num_t item_index) {
+    return mol_fixvec_slice_by_index_with_count_size(input, item_size, item_index, MOL_NUM_T_SIZE);
+}
+
 // Slice a segment for DynVec by index.
 MOLECULE_API_DECORATOR mol_seg_res_t mol_dynvec_slice_by_index(const mol_seg_t *input, mol_num_t item_index) {
     mol_seg_res_t res;
@@ -226,20 +253,23 @@ MOLECULE_API_DECORATOR mol_seg_t mol_table_slice_by_index(const mol_seg_t *input
     return res.seg;
 }
 
-// Slice the raw bytes from a `vector <byte>` (FixVec, with a header).
-MOLECULE_API_DECORATOR mol_seg_t mol_fixvec_slice_raw_bytes(const mol_seg_t *input) {
+// Slice the raw bytes from a `vector <byte>` with a non-standard count size.
+MOLECULE_API_DECORATOR mol_seg_t mol_fixvec_slice_raw_bytes_with_count_size(const mol_seg_t *input, mol_num_t count_size) {
     mol_seg_t seg;
-    seg.ptr = input->ptr + MOL_NUM_T_SIZE;
-    seg.size = mol_unpack_number(input->ptr);
+    seg.ptr = input->ptr + count_size;
+    seg.size = mol_unpack_count(input->ptr, count_size);
     return seg;
 }
 
+// Slice the raw bytes from a `vector <byte>` (FixVec, with a header).
+MOLECULE_API_DECORATOR mol_seg_t mol_fixvec_slice_raw_bytes(const mol_seg_t *input) {
+    return mol_fixvec_slice_raw_bytes_with_count_size(input, MOL_NUM_T_SIZE);
+}
+
 /*
  * Undef macros which are internal use only.
  */
 
-#undef is_le
-
 #ifdef __DEFINE_MOLECULE_API_DECORATOR
 #undef MOLECULE_API_DECORATOR
 #undef __DEFINE_MOLECULE_API_DECORATOR
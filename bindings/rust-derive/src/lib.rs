@@ -0,0 +1,345 @@
+//! `#[derive(molecule::Encode, molecule::Decode)]` for the Rust structs and enums, which are
+//! encoded without a schema; the mapping of the types is documented in `molecule::codec`.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{ext::IdentExt, spanned::Spanned, Attribute, Data, DeriveInput, Fields, Ident, Type};
+
+#[proc_macro_derive(Encode, attributes(molecule))]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    Shape::parse(&input)
+        .map(|shape| shape.impl_encode())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(Decode, attributes(molecule))]
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    Shape::parse(&input)
+        .map(|shape| shape.impl_decode())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// A field of a struct, or the unnamed field of a variant of an enum.
+struct Member {
+    ident: Ident,
+    typ: Type,
+}
+
+enum Kind {
+    /// A struct, or a table if any field has no fixed size or it's forced.
+    Fields {
+        table: bool,
+    },
+    Variants,
+}
+
+struct Shape {
+    ident: Ident,
+    /// The name of the type in the schema.
+    name: String,
+    kind: Kind,
+    members: Vec<Member>,
+}
+
+#[derive(Default)]
+struct ContainerAttrs {
+    name: Option<String>,
+    table: bool,
+}
+
+fn container_attrs(attrs: &[Attribute]) -> syn::Result<ContainerAttrs> {
+    let mut ret = ContainerAttrs::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("molecule")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("table") {
+                ret.table = true;
+                Ok(())
+            } else if meta.path.is_ident("name") {
+                let name: syn::LitStr = meta.value()?.parse()?;
+                ret.name = Some(name.value());
+                Ok(())
+            } else {
+                Err(meta.error("the attribute is unknown, it should be `table` or `name`"))
+            }
+        })?;
+    }
+    Ok(ret)
+}
+
+fn member_order(attrs: &[Attribute]) -> syn::Result<Option<usize>> {
+    let mut ret = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("molecule")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("order") {
+                let order: syn::LitInt = meta.value()?.parse()?;
+                ret = Some(order.base10_parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("the attribute is unknown, it should be `order`"))
+            }
+        })?;
+    }
+    Ok(ret)
+}
+
+/// Sort the members by their orders, which should be all or none, and from 0 to N - 1.
+fn sort_by_order(members: Vec<(Option<usize>, Span, Member)>) -> syn::Result<Vec<Member>> {
+    if members.iter().all(|(order, ..)| order.is_none()) {
+        return Ok(members.into_iter().map(|(_, _, member)| member).collect());
+    }
+    if let Some((_, span, _)) = members.iter().find(|(order, ..)| order.is_none()) {
+        return Err(syn::Error::new(
+            *span,
+            "the `order` should be set for all or none of them",
+        ));
+    }
+    let count = members.len();
+    let mut sorted = members
+        .into_iter()
+        .map(|(order, span, member)| (order.unwrap_or_default(), span, member))
+        .collect::<Vec<_>>();
+    sorted.sort_by_key(|(order, ..)| *order);
+    for (index, (order, span, _)) in sorted.iter().enumerate() {
+        if *order != index {
+            return Err(syn::Error::new(
+                *span,
+                format!(
+                    "the `order` should be from 0 to {}, without duplicates",
+                    count - 1
+                ),
+            ));
+        }
+    }
+    Ok(sorted.into_iter().map(|(_, _, member)| member).collect())
+}
+
+impl Shape {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        if !input.generics.params.is_empty() {
+            return Err(syn::Error::new(
+                input.generics.span(),
+                "the generic types are not supported",
+            ));
+        }
+        let attrs = container_attrs(&input.attrs)?;
+        let (kind, members) = match input.data {
+            Data::Struct(ref data) => {
+                let fields = match data.fields {
+                    Fields::Named(ref fields) => &fields.named,
+                    Fields::Unit => {
+                        return Err(syn::Error::new(
+                            input.ident.span(),
+                            "the unit structs are not supported, use `struct Name {}`",
+                        ))
+                    }
+                    Fields::Unnamed(ref fields) => {
+                        return Err(syn::Error::new(
+                            fields.span(),
+                            "only the structs with named fields are supported",
+                        ))
+                    }
+                };
+                let members = fields
+                    .iter()
+                    .map(|field| {
+                        let member = Member {
+                            ident: field.ident.clone().expect("the field is named"),
+                            typ: field.ty.clone(),
+                        };
+                        member_order(&field.attrs).map(|order| (order, field.span(), member))
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?;
+                (Kind::Fields { table: attrs.table }, members)
+            }
+            Data::Enum(ref data) => {
+                if attrs.table {
+                    return Err(syn::Error::new(
+                        input.ident.span(),
+                        "the attribute `table` is only for structs",
+                    ));
+                }
+                if data.variants.is_empty() {
+                    return Err(syn::Error::new(
+                        input.ident.span(),
+                        "the enum should have variants",
+                    ));
+                }
+                let members = data
+                    .variants
+                    .iter()
+                    .map(|variant| {
+                        let typ = match variant.fields {
+                            Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+                                fields.unnamed[0].ty.clone()
+                            }
+                            _ => {
+                                return Err(syn::Error::new(
+                                    variant.span(),
+                                    "the variants should have exactly one unnamed field",
+                                ))
+                            }
+                        };
+                        let member = Member {
+                            ident: variant.ident.clone(),
+                            typ,
+                        };
+                        member_order(&variant.attrs).map(|order| (order, variant.span(), member))
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?;
+                (Kind::Variants, members)
+            }
+            Data::Union(_) => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "the Rust unions are not supported, use an enum",
+                ))
+            }
+        };
+        Ok(Self {
+            ident: input.ident.clone(),
+            name: attrs
+                .name
+                .unwrap_or_else(|| input.ident.unraw().to_string()),
+            kind,
+            members: sort_by_order(members)?,
+        })
+    }
+
+    fn impl_encode(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        let name = &self.name;
+        let idents = self.members.iter().map(|m| &m.ident).collect::<Vec<_>>();
+        let types = self.members.iter().map(|m| &m.typ).collect::<Vec<_>>();
+        let body = match self.kind {
+            Kind::Fields { table } => {
+                let fixed_size = if table {
+                    quote!(None)
+                } else {
+                    quote!(molecule::codec::fixed_size(&[
+                        #( <#types as molecule::codec::Encode>::FIXED_SIZE ),*
+                    ]))
+                };
+                let field_names = idents.iter().map(|ident| ident.unraw().to_string());
+                quote!(
+                    const FIXED_SIZE: Option<usize> = #fixed_size;
+
+                    fn encoded_size(&self) -> usize {
+                        let sizes: &[usize] = &[
+                            #( molecule::codec::Encode::encoded_size(&self.#idents) ),*
+                        ];
+                        match <Self as molecule::codec::Encode>::FIXED_SIZE {
+                            Some(size) => size,
+                            None => molecule::codec::dynamic_size(sizes),
+                        }
+                    }
+
+                    fn encode_to(&self, out: &mut molecule::codec::Vec<u8>) {
+                        if <Self as molecule::codec::Encode>::FIXED_SIZE.is_none() {
+                            molecule::codec::write_header(out, &[
+                                #( molecule::codec::Encode::encoded_size(&self.#idents) ),*
+                            ]);
+                        }
+                        #( molecule::codec::Encode::encode_to(&self.#idents, out); )*
+                    }
+
+                    fn describe(schema: &mut molecule::codec::Schema) -> molecule::codec::String {
+                        let fields: &[(&str, molecule::codec::String)] = &[
+                            #( (#field_names, <#types as molecule::codec::Encode>::describe(schema)) ),*
+                        ];
+                        let is_struct = <Self as molecule::codec::Encode>::FIXED_SIZE.is_some();
+                        molecule::codec::describe_fields(schema, #name, is_struct, fields)
+                    }
+
+                    fn type_name() -> molecule::codec::String {
+                        #name.into()
+                    }
+                )
+            }
+            Kind::Variants => {
+                let item_ids = (0..idents.len()).map(Literal::usize_unsuffixed);
+                quote!(
+                    const FIXED_SIZE: Option<usize> = None;
+
+                    fn encoded_size(&self) -> usize {
+                        molecule::NUMBER_SIZE + match self {
+                            #( Self::#idents(inner) => molecule::codec::Encode::encoded_size(inner), )*
+                        }
+                    }
+
+                    fn encode_to(&self, out: &mut molecule::codec::Vec<u8>) {
+                        match self {
+                            #(
+                                Self::#idents(inner) => {
+                                    molecule::codec::write_item_id(out, #item_ids);
+                                    molecule::codec::Encode::encode_to(inner, out);
+                                }
+                            )*
+                        }
+                    }
+
+                    fn describe(schema: &mut molecule::codec::Schema) -> molecule::codec::String {
+                        let items: &[molecule::codec::String] = &[
+                            #( <#types as molecule::codec::Encode>::describe(schema) ),*
+                        ];
+                        molecule::codec::describe_union(schema, #name, items)
+                    }
+
+                    fn type_name() -> molecule::codec::String {
+                        #name.into()
+                    }
+                )
+            }
+        };
+        quote!(
+            impl molecule::codec::Encode for #ident {
+                #body
+            }
+        )
+    }
+
+    fn impl_decode(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        let idents = self.members.iter().map(|m| &m.ident).collect::<Vec<_>>();
+        let types = self.members.iter().map(|m| &m.typ).collect::<Vec<_>>();
+        let indexes = (0..idents.len())
+            .map(Literal::usize_unsuffixed)
+            .collect::<Vec<_>>();
+        let count = Literal::usize_unsuffixed(idents.len());
+        let body = match self.kind {
+            Kind::Fields { .. } => quote!(
+                let sizes: &[Option<usize>] = &[
+                    #( <#types as molecule::codec::Encode>::FIXED_SIZE ),*
+                ];
+                #[allow(unused_variables)]
+                let fields = match <Self as molecule::codec::Encode>::FIXED_SIZE {
+                    Some(_) => molecule::codec::struct_fields::<Self>(slice, sizes)?,
+                    None => molecule::codec::table_fields::<Self>(slice, #count)?,
+                };
+                Ok(Self {
+                    #( #idents: molecule::codec::Decode::decode(fields[#indexes])?, )*
+                })
+            ),
+            Kind::Variants => quote!(
+                let (item_id, inner) = molecule::codec::union_item::<Self>(slice, #count)?;
+                match item_id {
+                    #( #indexes => molecule::codec::Decode::decode(inner).map(Self::#idents), )*
+                    _ => unreachable!("the item ID is checked"),
+                }
+            ),
+        };
+        quote!(
+            impl molecule::codec::Decode for #ident {
+                fn decode(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+                    #body
+                }
+            }
+        )
+    }
+}
@@ -0,0 +1,170 @@
+//! Conversions between vectors of fixed-size items and [Apache Arrow] arrays.
+//!
+//! A fixed-size item is mapped to Arrow by its [`Layout`]:
+//! - `byte` is an `UInt8`.
+//! - An array of bytes is a `FixedSizeBinary`.
+//! - Any other array is a `FixedSizeList` of its items.
+//! - A struct is a `Struct` which has all the fields.
+//!
+//! The conversions copy the data column by column, no entity or reader is constructed for items.
+//!
+//! [Apache Arrow]: https://arrow.apache.org/
+
+use std::{boxed::Box, format, sync::Arc, vec, vec::Vec};
+
+pub use arrow_array::{Array, ArrayRef};
+pub use arrow_schema::{ArrowError, DataType};
+
+use arrow_array::{FixedSizeBinaryArray, FixedSizeListArray, StructArray, UInt8Array};
+use arrow_buffer::Buffer;
+use arrow_schema::{Field, Fields};
+
+/// The name of the items of an Arrow `FixedSizeList`.
+pub const LIST_ITEM_NAME: &str = "item";
+
+/// The layout of a fixed-size type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Layout {
+    Byte,
+    Bytes(usize),
+    Array(Box<Layout>, usize),
+    Struct(Vec<(&'static str, Layout)>),
+}
+
+/// Types which have a fixed-size layout.
+pub trait FixedLayout {
+    fn layout() -> Layout;
+}
+
+impl Layout {
+    pub fn array(inner: Layout, count: usize) -> Self {
+        Layout::Array(Box::new(inner), count)
+    }
+
+    pub fn size(&self) -> usize {
+        match self {
+            Layout::Byte => 1,
+            Layout::Bytes(count) => *count,
+            Layout::Array(inner, count) => inner.size() * count,
+            Layout::Struct(fields) => fields.iter().map(|(_, f)| f.size()).sum(),
+        }
+    }
+
+    pub fn data_type(&self) -> DataType {
+        match self {
+            Layout::Byte => DataType::UInt8,
+            Layout::Bytes(count) => DataType::FixedSizeBinary(*count as i32),
+            Layout::Array(inner, count) => DataType::FixedSizeList(
+                Arc::new(Field::new(LIST_ITEM_NAME, inner.data_type(), false)),
+                *count as i32,
+            ),
+            Layout::Struct(fields) => DataType::Struct(self::fields(fields)),
+        }
+    }
+}
+
+fn fields(fields: &[(&'static str, Layout)]) -> Fields {
+    fields
+        .iter()
+        .map(|(name, f)| Field::new(*name, f.data_type(), false))
+        .collect()
+}
+
+/// Convert the concatenated items into an Arrow array.
+#[allow(clippy::manual_is_multiple_of)]
+pub fn to_arrow(layout: &Layout, raw: &[u8]) -> Result<ArrayRef, ArrowError> {
+    let size = layout.size();
+    if size == 0 || raw.len() % size != 0 {
+        return Err(ArrowError::InvalidArgumentError(format!(
+            "the length of raw data (={}) is not a multiple of the item size (={})",
+            raw.len(),
+            size
+        )));
+    }
+    let count = raw.len() / size;
+    let array: ArrayRef = match layout {
+        Layout::Byte => Arc::new(UInt8Array::from(raw.to_vec())),
+        Layout::Bytes(n) => Arc::new(FixedSizeBinaryArray::try_new_with_len(
+            *n as i32,
+            Buffer::from_vec(raw.to_vec()),
+            None,
+            count,
+        )?),
+        Layout::Array(inner, n) => Arc::new(FixedSizeListArray::try_new_with_length(
+            Arc::new(Field::new(LIST_ITEM_NAME, inner.data_type(), false)),
+            *n as i32,
+            to_arrow(inner, raw)?,
+            None,
+            count,
+        )?),
+        Layout::Struct(fields) => {
+            let mut offset = 0;
+            let mut columns = Vec::with_capacity(fields.len());
+            for (_, f) in fields {
+                let field_size = f.size();
+                let column = raw
+                    .chunks(size)
+                    .flat_map(|item| &item[offset..offset + field_size])
+                    .copied()
+                    .collect::<Vec<_>>();
+                columns.push(to_arrow(f, &column)?);
+                offset += field_size;
+            }
+            Arc::new(StructArray::try_new_with_length(
+                self::fields(fields),
+                columns,
+                None,
+                count,
+            )?)
+        }
+    };
+    Ok(array)
+}
+
+fn downcast<T: 'static>(array: &dyn Array) -> Result<&T, ArrowError> {
+    array.as_any().downcast_ref::<T>().ok_or_else(|| {
+        ArrowError::CastError(format!("unexpected array type {}", array.data_type()))
+    })
+}
+
+/// Convert an Arrow array back into the concatenated items.
+pub fn from_arrow(layout: &Layout, array: &dyn Array) -> Result<Vec<u8>, ArrowError> {
+    let data_type = layout.data_type();
+    if array.data_type() != &data_type {
+        return Err(ArrowError::SchemaError(format!(
+            "data type doesn't match, expect {}, actual {}",
+            data_type,
+            array.data_type()
+        )));
+    }
+    if array.logical_null_count() != 0 {
+        return Err(ArrowError::InvalidArgumentError(
+            "null values are not supported".into(),
+        ));
+    }
+    let raw = match layout {
+        Layout::Byte => downcast::<UInt8Array>(array)?.values().to_vec(),
+        Layout::Bytes(_) => downcast::<FixedSizeBinaryArray>(array)?
+            .value_data()
+            .to_vec(),
+        Layout::Array(inner, _) => {
+            from_arrow(inner, downcast::<FixedSizeListArray>(array)?.values())?
+        }
+        Layout::Struct(fields) => {
+            let array = downcast::<StructArray>(array)?;
+            let size = layout.size();
+            let mut raw = vec![0u8; size * array.len()];
+            let mut offset = 0;
+            for ((_, f), column) in fields.iter().zip(array.columns()) {
+                let field_size = f.size();
+                let column = from_arrow(f, column.as_ref())?;
+                for (item, value) in raw.chunks_mut(size).zip(column.chunks(field_size)) {
+                    item[offset..offset + field_size].copy_from_slice(value);
+                }
+                offset += field_size;
+            }
+            raw
+        }
+    };
+    Ok(raw)
+}
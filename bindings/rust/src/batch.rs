@@ -0,0 +1,171 @@
+//! Batches of the values of a union, such as the messages of a service which are sent together.
+//!
+//! ```text
+//! batch:  item count (4 bytes) | the items back-to-back
+//! item:   type hash (8 bytes) | size (4 bytes) | the value of the union
+//! ```
+//!
+//! All numbers are little-endian, as molecule numbers are. The type hash is the hash of the type
+//! of the item of the union, with the types in it, so a receiver whose definition of the item is
+//! different rejects it, even if the item IDs are the same. The sizes are checked against the
+//! limits before the values are verified.
+
+use alloc::vec::Vec;
+use core::{fmt, result};
+
+use crate::{
+    error::VerificationError, pack_number, prelude::Entity, unpack_number, Number, NUMBER_SIZE,
+};
+
+pub const TYPE_HASH_SIZE: usize = 8;
+
+pub type TypeHash = [u8; TYPE_HASH_SIZE];
+
+/// The header of each item: the type hash and the size.
+pub const ITEM_HEADER_SIZE: usize = TYPE_HASH_SIZE + NUMBER_SIZE;
+
+/// The unions which are batched; it's implemented for the generated entities of the unions.
+pub trait BatchItem: Entity {
+    /// The hashes of the types of the items, by their item IDs.
+    const ITEM_TYPE_HASHES: &'static [TypeHash];
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchError {
+    /// The batch ends before the items which its header declares.
+    Truncated,
+    /// There are bytes after the last item.
+    TrailingData(usize),
+    /// The item count, then the limit.
+    TooManyItems(usize, usize),
+    /// The index of the item, its size, then the limit.
+    ItemTooLarge(usize, usize, usize),
+    /// The index of the item, then its item ID, whose type hash is different.
+    TypeHashNotMatch(usize, Number),
+    /// The index of the item, then the error of its verification.
+    Invalid(usize, VerificationError),
+}
+
+pub type Result<T> = result::Result<T, BatchError>;
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BatchError::Truncated => write!(f, "the batch is truncated"),
+            BatchError::TrailingData(size) => {
+                write!(f, "the batch has {} bytes after the items", size)
+            }
+            BatchError::TooManyItems(count, max_count) => write!(
+                f,
+                "the batch has {} items, but at most {} are allowed",
+                count, max_count
+            ),
+            BatchError::ItemTooLarge(index, size, max_size) => write!(
+                f,
+                "the item {} of the batch has {} bytes, but at most {} are allowed",
+                index, size, max_size
+            ),
+            BatchError::TypeHashNotMatch(index, item_id) => write!(
+                f,
+                "the type of the item {} of the batch (item id {}) is different",
+                index, item_id
+            ),
+            BatchError::Invalid(index, err) => {
+                write!(f, "the item {} of the batch is invalid: {}", index, err)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for BatchError {}
+
+/// The limits of a batch which is decoded; they're unlimited by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchLimits {
+    pub max_count: usize,
+    /// The most bytes of the value of each item, without its header.
+    pub max_item_size: usize,
+}
+
+impl Default for BatchLimits {
+    fn default() -> Self {
+        Self {
+            max_count: usize::MAX,
+            max_item_size: usize::MAX,
+        }
+    }
+}
+
+/// Encode the values as a batch.
+///
+/// Panics if the batch is larger than 4 GiB.
+pub fn encode_batch<T: BatchItem>(items: &[T]) -> Vec<u8> {
+    let size = NUMBER_SIZE
+        + items
+            .iter()
+            .map(|item| ITEM_HEADER_SIZE + item.as_slice().len())
+            .sum::<usize>();
+    assert!(
+        size <= Number::MAX as usize,
+        "the batch is larger than 4 GiB"
+    );
+    let mut data = Vec::with_capacity(size);
+    data.extend_from_slice(&pack_number(items.len() as Number));
+    for item in items {
+        let slice = item.as_slice();
+        let item_id = unpack_number(slice) as usize;
+        data.extend_from_slice(&T::ITEM_TYPE_HASHES[item_id]);
+        data.extend_from_slice(&pack_number(slice.len() as Number));
+        data.extend_from_slice(slice);
+    }
+    data
+}
+
+/// Decode a batch; each value is verified in the strict mode, after its size and its type hash
+/// are checked.
+pub fn decode_batch<T: BatchItem>(data: &[u8], limits: &BatchLimits) -> Result<Vec<T>> {
+    if data.len() < NUMBER_SIZE {
+        return Err(BatchError::Truncated);
+    }
+    let count = unpack_number(data) as usize;
+    if count > limits.max_count {
+        return Err(BatchError::TooManyItems(count, limits.max_count));
+    }
+    // Each item takes its header at least, so a count which doesn't fit is never allocated.
+    if count > (data.len() - NUMBER_SIZE) / ITEM_HEADER_SIZE {
+        return Err(BatchError::Truncated);
+    }
+    let mut items = Vec::with_capacity(count);
+    let mut rest = &data[NUMBER_SIZE..];
+    for index in 0..count {
+        if rest.len() < ITEM_HEADER_SIZE {
+            return Err(BatchError::Truncated);
+        }
+        let (type_hash, header) = rest.split_at(TYPE_HASH_SIZE);
+        let size = unpack_number(header) as usize;
+        if size > limits.max_item_size {
+            return Err(BatchError::ItemTooLarge(index, size, limits.max_item_size));
+        }
+        let body = &rest[ITEM_HEADER_SIZE..];
+        if body.len() < size {
+            return Err(BatchError::Truncated);
+        }
+        let (slice, next) = body.split_at(size);
+        // A value without an item ID fails the verification below.
+        if slice.len() >= NUMBER_SIZE {
+            let item_id = unpack_number(slice);
+            let expected = T::ITEM_TYPE_HASHES.get(item_id as usize);
+            if expected.is_some_and(|expected| expected[..] != type_hash[..]) {
+                return Err(BatchError::TypeHashNotMatch(index, item_id));
+            }
+        }
+        let item = T::from_slice(slice).map_err(|err| BatchError::Invalid(index, err))?;
+        items.push(item);
+        rest = next;
+    }
+    if !rest.is_empty() {
+        return Err(BatchError::TrailingData(rest.len()));
+    }
+    Ok(items)
+}
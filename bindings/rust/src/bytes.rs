@@ -1,24 +1,59 @@
-use alloc::{borrow::ToOwned, vec::Vec};
-use core::{convert::From, ops::Deref};
+use alloc::vec::Vec;
+use core::{convert::From, fmt, ops::Deref};
 
-#[derive(Debug, Default, Clone)]
-pub struct Bytes(Vec<u8>);
+// The targets without atomic pointers have no threads to share the buffers with.
+#[cfg(not(target_has_atomic = "ptr"))]
+use alloc::rc::Rc as Shared;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc as Shared;
+
+/// A range of a shared buffer; cloning and slicing it don't copy the bytes.
+///
+/// The buffer is a `Vec<u8>` behind the shared pointer, so a `Vec<u8>` is moved into it without a
+/// copy, and moved out of it if the whole buffer isn't shared.
+#[derive(Clone)]
+pub struct Bytes {
+    data: Shared<Vec<u8>>,
+    start: usize,
+    end: usize,
+}
+
+impl Default for Bytes {
+    fn default() -> Self {
+        Self::from(Vec::new())
+    }
+}
+
+impl fmt::Debug for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Bytes").field(&&self[..]).finish()
+    }
+}
 
 impl From<Vec<u8>> for Bytes {
     fn from(value: Vec<u8>) -> Self {
-        Self(value)
+        let end = value.len();
+        Self {
+            data: Shared::new(value),
+            start: 0,
+            end,
+        }
     }
 }
 
 impl From<&[u8]> for Bytes {
     fn from(value: &[u8]) -> Self {
-        Self(value.to_owned())
+        Self::from(value.to_vec())
     }
 }
 
 impl From<Bytes> for Vec<u8> {
     fn from(value: Bytes) -> Self {
-        value.0
+        if value.start == 0 && value.end == value.data.len() {
+            Shared::try_unwrap(value.data).unwrap_or_else(|data| data[..].to_vec())
+        } else {
+            value[..].to_vec()
+        }
     }
 }
 
@@ -26,13 +61,18 @@ impl Deref for Bytes {
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.data[self.start..self.end]
     }
 }
 
 impl Bytes {
     pub fn slice(&self, start: usize, end: usize) -> Self {
-        Self::from(&self.0[start..end])
+        assert!(start <= end && end <= self.len(), "range out of bounds");
+        Self {
+            data: Shared::clone(&self.data),
+            start: self.start + start,
+            end: self.start + end,
+        }
     }
 
     pub fn slice_from(&self, start: usize) -> Self {
@@ -0,0 +1,228 @@
+//! A cache of the verification results, for the values which are verified again and again, such
+//! as the transactions which arrive from multiple peers.
+//!
+//! The values are keyed by the hashes of their bytes, and the least recently used ones are
+//! evicted when the cache is full. The hasher has a random key for each cache, and the bytes are
+//! kept to confirm a hit, so the result of other bytes is never returned.
+
+use std::{
+    borrow::ToOwned,
+    boxed::Box,
+    collections::{hash_map::RandomState, BTreeMap, HashMap},
+    hash::{BuildHasher, Hasher},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+
+use crate::{
+    error::{VerificationError, VerificationResult},
+    prelude::Reader,
+    unpack_number, NUMBER_SIZE,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Key {
+    type_name: &'static str,
+    compatible: bool,
+    hash: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    data: Box<[u8]>,
+    result: Result<(), VerificationError>,
+    offsets: Option<Arc<[usize]>>,
+    tick: u64,
+}
+
+/// A cache of the verification results of the values, which holds `capacity` values at most.
+#[derive(Debug)]
+pub struct VerificationCache {
+    capacity: usize,
+    hasher: RandomState,
+    entries: HashMap<Key, Entry>,
+    // The keys by the ticks when they were used last time, the least recently used is the first.
+    recent: BTreeMap<u64, Key>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl VerificationCache {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "the capacity of the cache is zero");
+        Self {
+            capacity,
+            hasher: RandomState::new(),
+            entries: HashMap::new(),
+            recent: BTreeMap::new(),
+            tick: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Verify the slice as `R`, or return the cached result if the same bytes were verified as
+    /// `R` in the same mode.
+    pub fn verify<'r, R: Reader<'r>>(
+        &mut self,
+        slice: &'r [u8],
+        compatible: bool,
+    ) -> VerificationResult<R> {
+        let key = self.key::<R>(slice, compatible);
+        self.entry::<R>(key, slice)
+            .result
+            .clone()
+            .map(|_| R::new_unchecked(slice))
+    }
+
+    /// Verify the slice as `R`, then return the offsets of its items or fields, with the total size
+    /// at the end; the item at `idx` is at `offsets[idx]..offsets[idx + 1]`.
+    ///
+    /// The offsets are read from the header once. If `R` is not a DynVec or a Table, whose bytes
+    /// have no such header, `OffsetsNotMatch` is returned.
+    pub fn offsets<'r, R: Reader<'r>>(
+        &mut self,
+        slice: &'r [u8],
+        compatible: bool,
+    ) -> VerificationResult<Arc<[usize]>> {
+        let key = self.key::<R>(slice, compatible);
+        let entry = self.entry::<R>(key, slice);
+        if let Err(ref err) = entry.result {
+            return Err(err.clone());
+        }
+        if let Some(ref offsets) = entry.offsets {
+            return Ok(offsets.clone());
+        }
+        if !R::HAS_OFFSETS {
+            return Err(VerificationError::OffsetsNotMatch(R::NAME.to_owned()));
+        }
+        let offsets = read_offsets(R::NAME, slice)?;
+        entry.offsets = Some(offsets.clone());
+        Ok(offsets)
+    }
+
+    /// The count of the values in the cache.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The count of the verifications which were found in the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// The count of the verifications which were not found in the cache.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recent.clear();
+    }
+
+    fn key<'r, R: Reader<'r>>(&self, slice: &[u8], compatible: bool) -> Key {
+        Key {
+            type_name: R::NAME,
+            compatible,
+            hash: {
+                let mut hasher = self.hasher.build_hasher();
+                hasher.write(slice);
+                hasher.finish()
+            },
+        }
+    }
+
+    fn entry<'r, R: Reader<'r>>(&mut self, key: Key, slice: &'r [u8]) -> &mut Entry {
+        self.tick += 1;
+        let tick = self.tick;
+        let hit = matches!(self.entries.get(&key), Some(entry) if &entry.data[..] == slice);
+        if hit {
+            self.hits += 1;
+            let entry = self.entries.get_mut(&key).unwrap();
+            self.recent.remove(&entry.tick);
+            self.recent.insert(tick, key);
+            entry.tick = tick;
+            return entry;
+        }
+        self.misses += 1;
+        // The same path as the readers, so the errors are traced as theirs are.
+        let result = if key.compatible {
+            R::from_compatible_slice(slice)
+        } else {
+            R::from_slice(slice)
+        }
+        .map(|_| ());
+        if let Some(entry) = self.entries.remove(&key) {
+            // The same hash of other bytes.
+            self.recent.remove(&entry.tick);
+        } else if self.entries.len() >= self.capacity {
+            let first = self.recent.keys().next().copied();
+            if let Some(lru) = first.and_then(|tick| self.recent.remove(&tick)) {
+                self.entries.remove(&lru);
+            }
+        }
+        self.recent.insert(tick, key);
+        self.entries.entry(key).or_insert(Entry {
+            data: slice.into(),
+            result,
+            offsets: None,
+            tick,
+        })
+    }
+}
+
+/// Read the header of a DynVec or a Table, whose bytes are verified.
+#[allow(clippy::manual_is_multiple_of)]
+fn read_offsets(name: &str, slice: &[u8]) -> VerificationResult<Arc<[usize]>> {
+    let slice_len = slice.len();
+    if slice_len < NUMBER_SIZE {
+        return Err(VerificationError::HeaderIsBroken(
+            name.to_owned(),
+            NUMBER_SIZE,
+            slice_len,
+        ));
+    }
+    let total_size = unpack_number(slice) as usize;
+    if total_size != slice_len {
+        return Err(VerificationError::TotalSizeNotMatch(
+            name.to_owned(),
+            total_size,
+            slice_len,
+        ));
+    }
+    if total_size == NUMBER_SIZE {
+        return Ok(Arc::from(vec![total_size]));
+    }
+    if slice_len < NUMBER_SIZE * 2 {
+        return Err(VerificationError::HeaderIsBroken(
+            name.to_owned(),
+            NUMBER_SIZE * 2,
+            slice_len,
+        ));
+    }
+    let offset_first = unpack_number(&slice[NUMBER_SIZE..]) as usize;
+    if offset_first % NUMBER_SIZE != 0 || offset_first < NUMBER_SIZE * 2 || offset_first > slice_len
+    {
+        return Err(VerificationError::OffsetsNotMatch(name.to_owned()));
+    }
+    let offsets = slice[NUMBER_SIZE..offset_first]
+        .chunks_exact(NUMBER_SIZE)
+        .map(|x| unpack_number(x) as usize)
+        .chain(Some(total_size))
+        .collect::<Vec<_>>();
+    if offsets.windows(2).any(|pair| pair[0] > pair[1]) {
+        return Err(VerificationError::OffsetsNotMatch(name.to_owned()));
+    }
+    Ok(offsets.into())
+}
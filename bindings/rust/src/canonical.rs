@@ -0,0 +1,80 @@
+//! The canonical form of the values, for the byte-level deduplication.
+//!
+//! The builders of all backends write the fields in the order of the declaration, so a value
+//! which is verified in the strict mode has only one encoding; the generators guarantee it, it's
+//! not checked by a lint. But a buffer which is verified in the compatible mode may have the extra
+//! fields of the tables from a newer schema, anywhere in it; the canonical form drops them, so the
+//! same value of the schema is always the same bytes.
+
+use alloc::vec::Vec;
+
+use crate::{
+    bytes::Bytes,
+    error::VerificationResult,
+    pack_number,
+    prelude::{Entity, Reader},
+    primitive::{Byte, ByteReader},
+    Number, NUMBER_SIZE,
+};
+
+/// Re-encode the values in the canonical form; it's implemented for the generated entities and
+/// readers.
+pub trait Canonical {
+    /// The size of the canonical bytes, which is never more than the size of the value.
+    fn canonical_size(&self) -> usize;
+    fn write_canonical(&self, out: &mut Vec<u8>);
+}
+
+impl Canonical for Byte {
+    fn canonical_size(&self) -> usize {
+        1
+    }
+
+    fn write_canonical(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.as_slice());
+    }
+}
+
+impl<'r> Canonical for ByteReader<'r> {
+    fn canonical_size(&self) -> usize {
+        1
+    }
+
+    fn write_canonical(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.as_slice());
+    }
+}
+
+/// Write the header of a table or a dynamic vector, by the canonical sizes of its items.
+#[doc(hidden)]
+pub fn write_header(out: &mut Vec<u8>, sizes: &[usize]) {
+    let header_size = NUMBER_SIZE * (sizes.len() + 1);
+    let total_size = header_size + sizes.iter().sum::<usize>();
+    out.extend_from_slice(&pack_number(total_size as Number));
+    let mut offset = header_size;
+    for size in sizes {
+        out.extend_from_slice(&pack_number(offset as Number));
+        offset += size;
+    }
+}
+
+/// Verify the slice in the compatible mode, and re-encode it in the canonical form.
+///
+/// The result is the same bytes for the same value, whatever extra fields the slice has.
+pub fn recanonicalize<'r, R>(slice: &'r [u8]) -> VerificationResult<R::Entity>
+where
+    R: Reader<'r> + Canonical,
+{
+    let reader = R::from_compatible_slice(slice)?;
+    let mut out = Vec::with_capacity(reader.canonical_size());
+    reader.write_canonical(&mut out);
+    Ok(<R::Entity as Entity>::new_unchecked(Bytes::from(out)))
+}
+
+/// Verify the slice in the compatible mode, and check if it's in the canonical form already.
+pub fn is_canonical<'r, R>(slice: &'r [u8]) -> VerificationResult<bool>
+where
+    R: Reader<'r> + Canonical,
+{
+    R::from_compatible_slice(slice).map(|reader| reader.canonical_size() == slice.len())
+}
@@ -0,0 +1,224 @@
+//! The checksum trailers of the tables which are declared in the schema by `@checksum(algorithm)`.
+//!
+//! A sealed record is the molecule encoding of the table followed by a checksum of it, which is a
+//! `u32` in little endian, so the corruption of a stored record is detected when it's decoded,
+//! without any external framing. The trailer is not a part of the molecule encoding: the entities
+//! and the readers of the table are over the payload, as the ones of any other table, and a
+//! [`Sealed`] record keeps the bytes with the trailer, which it opens in `from_slice`.
+
+use alloc::{borrow::ToOwned, vec::Vec};
+
+use crate::{
+    bytes::Bytes,
+    error::{self, VerificationError, VerificationResult},
+    prelude::Entity,
+};
+
+/// The size of the trailer.
+pub const CHECKSUM_SIZE: usize = 4;
+
+/// The algorithms of the checksums.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// CRC-32 of IEEE 802.3, as in zlib and gzip.
+    Crc32,
+    /// xxHash32 with the seed 0.
+    Xxh32,
+}
+
+impl Algorithm {
+    /// The checksum of the data.
+    pub fn checksum(self, data: &[u8]) -> u32 {
+        match self {
+            Algorithm::Crc32 => crc32(data),
+            Algorithm::Xxh32 => xxh32(data, 0),
+        }
+    }
+}
+
+/// Append the trailer to the payload.
+pub fn seal(algorithm: Algorithm, payload: &[u8]) -> Vec<u8> {
+    let mut sealed = Vec::with_capacity(payload.len() + CHECKSUM_SIZE);
+    sealed.extend_from_slice(payload);
+    sealed.extend_from_slice(&algorithm.checksum(payload).to_le_bytes());
+    sealed
+}
+
+/// Check the trailer of a sealed record, and return the payload before it.
+pub fn open<'a>(
+    type_name: &str,
+    algorithm: Algorithm,
+    sealed: &'a [u8],
+) -> VerificationResult<&'a [u8]> {
+    if sealed.len() < CHECKSUM_SIZE {
+        return Err(VerificationError::HeaderIsBroken(
+            type_name.to_owned(),
+            CHECKSUM_SIZE,
+            sealed.len(),
+        ));
+    }
+    let (payload, trailer) = sealed.split_at(sealed.len() - CHECKSUM_SIZE);
+    let mut le = [0u8; CHECKSUM_SIZE];
+    le.copy_from_slice(trailer);
+    let expected = u32::from_le_bytes(le);
+    let actual = algorithm.checksum(payload);
+    if expected != actual {
+        return Err(VerificationError::ChecksumNotMatch(
+            type_name.to_owned(),
+            expected,
+            actual,
+        ));
+    }
+    Ok(payload)
+}
+
+/// The tables which are declared with `@checksum`.
+pub trait Checksummed: Entity {
+    const ALGORITHM: Algorithm;
+}
+
+/// A record which is sealed by the checksum trailer.
+///
+/// The bytes are the payload then the trailer, and the payload is the entity of the table.
+#[derive(Debug, Clone)]
+pub struct Sealed<T> {
+    data: Bytes,
+    payload: T,
+}
+
+impl<T: Checksummed> Sealed<T> {
+    /// Seal the payload.
+    pub fn new(payload: T) -> Self {
+        let data = seal(T::ALGORITHM, payload.as_slice()).into();
+        Self { data, payload }
+    }
+
+    /// Check the trailer, then verify the payload.
+    pub fn from_slice(slice: &[u8]) -> VerificationResult<Self> {
+        Self::open(slice, false)
+    }
+
+    /// Check the trailer, then verify the payload in the compatible mode.
+    pub fn from_compatible_slice(slice: &[u8]) -> VerificationResult<Self> {
+        Self::open(slice, true)
+    }
+
+    fn open(slice: &[u8], compatible: bool) -> VerificationResult<Self> {
+        let payload = open(T::NAME, T::ALGORITHM, slice)
+            .map_err(|err| error::traced(T::NAME, slice.len(), compatible, err))?;
+        let payload = if compatible {
+            T::from_compatible_slice(payload)?
+        } else {
+            T::from_slice(payload)?
+        };
+        Ok(Self {
+            data: slice.to_vec().into(),
+            payload,
+        })
+    }
+
+    /// The bytes of the record, with the trailer.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.data[..]
+    }
+
+    pub fn as_bytes(&self) -> Bytes {
+        self.data.clone()
+    }
+
+    /// The table without the trailer.
+    pub fn payload(&self) -> &T {
+        &self.payload
+    }
+
+    pub fn into_payload(self) -> T {
+        self.payload
+    }
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, byte| {
+        CRC32_TABLE[((crc ^ u32::from(*byte)) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+const PRIME32_1: u32 = 0x9E37_79B1;
+const PRIME32_2: u32 = 0x85EB_CA77;
+const PRIME32_3: u32 = 0xC2B2_AE3D;
+const PRIME32_4: u32 = 0x27D4_EB2F;
+const PRIME32_5: u32 = 0x1656_67B1;
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    let mut le = [0u8; 4];
+    le.copy_from_slice(&bytes[..4]);
+    u32::from_le_bytes(le)
+}
+
+fn xxh32_round(acc: u32, input: u32) -> u32 {
+    acc.wrapping_add(input.wrapping_mul(PRIME32_2))
+        .rotate_left(13)
+        .wrapping_mul(PRIME32_1)
+}
+
+fn xxh32(data: &[u8], seed: u32) -> u32 {
+    let mut chunks = data.chunks_exact(16);
+    let mut hash = if data.len() >= 16 {
+        let mut acc = [
+            seed.wrapping_add(PRIME32_1).wrapping_add(PRIME32_2),
+            seed.wrapping_add(PRIME32_2),
+            seed,
+            seed.wrapping_sub(PRIME32_1),
+        ];
+        for chunk in &mut chunks {
+            for (i, acc) in acc.iter_mut().enumerate() {
+                *acc = xxh32_round(*acc, read_u32(&chunk[i * 4..]));
+            }
+        }
+        acc[0]
+            .rotate_left(1)
+            .wrapping_add(acc[1].rotate_left(7))
+            .wrapping_add(acc[2].rotate_left(12))
+            .wrapping_add(acc[3].rotate_left(18))
+    } else {
+        seed.wrapping_add(PRIME32_5)
+    };
+    hash = hash.wrapping_add(data.len() as u32);
+    let mut words = chunks.remainder().chunks_exact(4);
+    for word in &mut words {
+        hash = hash
+            .wrapping_add(read_u32(word).wrapping_mul(PRIME32_3))
+            .rotate_left(17)
+            .wrapping_mul(PRIME32_4);
+    }
+    for byte in words.remainder() {
+        hash = hash
+            .wrapping_add(u32::from(*byte).wrapping_mul(PRIME32_5))
+            .rotate_left(11)
+            .wrapping_mul(PRIME32_1);
+    }
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(PRIME32_2);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(PRIME32_3);
+    hash ^ (hash >> 16)
+}
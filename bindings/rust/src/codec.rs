@@ -0,0 +1,640 @@
+//! The molecule encoding of ordinary Rust types, without a schema.
+//!
+//! It's for the small internal messages, which are derived with `#[derive(molecule::Encode,
+//! molecule::Decode)]` by the feature `derive`. The types are mapped as:
+//!
+//! - `u8` is `byte`, and the other integers are the arrays of bytes in little endian, such as
+//!   `array Uint32 [byte; 4];`;
+//! - `[T; N]` is an array, and its items should have a fixed size and it should have a size,
+//!   otherwise its schema can't be described and it's never decoded;
+//! - `Vec<T>` is a fixed vector if `T` has a fixed size, or a dynamic vector, and its items should
+//!   have a size; `String` is `Bytes`, which is checked as UTF-8;
+//! - `Option<T>` is an option;
+//! - a struct with named fields is a struct if all its fields have fixed sizes, or a table; it's
+//!   always a table with `#[molecule(table)]`, which could get more fields later;
+//! - an enum whose variants have one unnamed field each is a union, and the variants are its
+//!   items in order.
+//!
+//! The fields and the variants are in their declared order, unless all of them have
+//! `#[molecule(order = N)]`. The type is named after the Rust type, unless it has
+//! `#[molecule(name = "...")]`.
+//!
+//! [`schema_of`] exports the schema of the type, so the code of the other languages could still
+//! be generated from it later.
+
+use alloc::{borrow::ToOwned, format};
+use core::{convert::TryInto, fmt, mem::size_of};
+
+use crate::{
+    error::{VerificationError, VerificationResult},
+    fixvec_total_size, pack_number, unpack_number, Number, NUMBER_SIZE,
+};
+
+#[doc(hidden)]
+pub use alloc::{string::String, vec::Vec};
+
+/// A type which has a molecule encoding.
+pub trait Encode {
+    /// The size of the encoding if it's the same for all values, which makes the arrays, the
+    /// structs and the fixed vectors.
+    const FIXED_SIZE: Option<usize>;
+
+    fn encoded_size(&self) -> usize;
+
+    fn encode_to(&self, out: &mut Vec<u8>);
+
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.encoded_size());
+        self.encode_to(&mut out);
+        out
+    }
+
+    /// Declare the type and the types it depends on in the schema, then return its name.
+    fn describe(schema: &mut Schema) -> String;
+
+    /// The name of the type in the schema, which is the name returned by `describe`. It never
+    /// panics, so it names the type in the errors of `decode`.
+    fn type_name() -> String;
+}
+
+/// A type which could be decoded from its molecule encoding.
+pub trait Decode: Encode + Sized {
+    /// Decode the value, the slice should be exactly the encoding of it.
+    fn decode(slice: &[u8]) -> VerificationResult<Self>;
+}
+
+/// The declarations of the types in order, which could be written as a schema file.
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    decls: Vec<(String, String)>,
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the declaration of the type, unless it's already declared.
+    ///
+    /// The types it depends on should be declared before it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another declaration has the same name.
+    pub fn declare(&mut self, name: &str, decl: String) {
+        if let Some((_, declared)) = self.decls.iter().find(|(n, _)| n == name) {
+            if *declared != decl {
+                panic!("the type ({}) is declared twice differently", name);
+            }
+        } else {
+            self.decls.push((name.to_owned(), decl));
+        }
+    }
+
+    /// The names of the declared types and their declarations in order.
+    pub fn decls(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.decls
+            .iter()
+            .map(|(name, decl)| (name.as_str(), decl.as_str()))
+    }
+}
+
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, (_, decl)) in self.decls.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "{}", decl)?;
+        }
+        Ok(())
+    }
+}
+
+/// The schema of the type and the types it depends on.
+pub fn schema_of<T: Encode>() -> Schema {
+    let mut schema = Schema::new();
+    T::describe(&mut schema);
+    schema
+}
+
+/// The name of the type in the schema.
+pub fn name_of<T: Encode>() -> String {
+    T::type_name()
+}
+
+/// The name of the array of the items, such as `Byte32`.
+fn array_name(item: &str, len: usize) -> String {
+    if item == "byte" {
+        format!("Byte{}", len)
+    } else {
+        format!("{}Array{}", item, len)
+    }
+}
+
+/// The name of the vector of the items, such as `Bytes`.
+fn vector_name(item: &str) -> String {
+    if item == "byte" {
+        "Bytes".to_owned()
+    } else {
+        format!("{}Vec", item)
+    }
+}
+
+/// The name of the type as a part of another name, such as `Byte` in `Byte32`.
+fn part_of_name(name: &str) -> String {
+    if name == "byte" {
+        "Byte".to_owned()
+    } else {
+        name.to_owned()
+    }
+}
+
+fn pack_size(size: usize) -> [u8; NUMBER_SIZE] {
+    let size: Number = size
+        .try_into()
+        .unwrap_or_else(|_| panic!("the size ({}) overflows the header", size));
+    pack_number(size)
+}
+
+/// The fixed size of the struct of the fields, which is none if any field has no fixed size or
+/// there are no fields, so it's a table.
+pub const fn fixed_size(sizes: &[Option<usize>]) -> Option<usize> {
+    if sizes.is_empty() {
+        return None;
+    }
+    let mut total = 0;
+    let mut index = 0;
+    while index < sizes.len() {
+        match sizes[index] {
+            Some(size) => total += size,
+            None => return None,
+        }
+        index += 1;
+    }
+    Some(total)
+}
+
+/// The size of a table or a dynamic vector of the values.
+pub fn dynamic_size(sizes: &[usize]) -> usize {
+    NUMBER_SIZE * (sizes.len() + 1) + sizes.iter().sum::<usize>()
+}
+
+/// Write the header of a table or a dynamic vector, which are the total size and the offsets.
+pub fn write_header(out: &mut Vec<u8>, sizes: &[usize]) {
+    let total = dynamic_size(sizes);
+    out.reserve(total);
+    out.extend_from_slice(&pack_size(total));
+    let mut offset = NUMBER_SIZE * (sizes.len() + 1);
+    for size in sizes {
+        out.extend_from_slice(&pack_size(offset));
+        offset += size;
+    }
+}
+
+/// Split a struct into its fields, by their fixed sizes.
+pub fn struct_fields<'a, T: Encode>(
+    slice: &'a [u8],
+    sizes: &[Option<usize>],
+) -> VerificationResult<Vec<&'a [u8]>> {
+    let expected = fixed_size(sizes).expect("the fields of a struct should have fixed sizes");
+    if slice.len() != expected {
+        return Err(VerificationError::TotalSizeNotMatch(
+            name_of::<T>(),
+            expected,
+            slice.len(),
+        ));
+    }
+    let mut start = 0;
+    Ok(sizes
+        .iter()
+        .map(|size| {
+            let end = start + size.unwrap_or_default();
+            let field = &slice[start..end];
+            start = end;
+            field
+        })
+        .collect())
+}
+
+/// Split a table or a dynamic vector into its items, by the offsets in the header.
+#[allow(clippy::manual_is_multiple_of)]
+fn dynamic_items<T: Encode>(slice: &[u8]) -> VerificationResult<Vec<&[u8]>> {
+    if slice.len() < NUMBER_SIZE {
+        return Err(VerificationError::HeaderIsBroken(
+            name_of::<T>(),
+            NUMBER_SIZE,
+            slice.len(),
+        ));
+    }
+    let total = unpack_number(slice) as usize;
+    if total != slice.len() {
+        return Err(VerificationError::TotalSizeNotMatch(
+            name_of::<T>(),
+            total,
+            slice.len(),
+        ));
+    }
+    if slice.len() == NUMBER_SIZE {
+        return Ok(Vec::new());
+    }
+    if slice.len() < NUMBER_SIZE * 2 {
+        return Err(VerificationError::HeaderIsBroken(
+            name_of::<T>(),
+            NUMBER_SIZE * 2,
+            slice.len(),
+        ));
+    }
+    let header = unpack_number(&slice[NUMBER_SIZE..]) as usize;
+    if header % NUMBER_SIZE != 0 || header < NUMBER_SIZE * 2 {
+        return Err(VerificationError::OffsetsNotMatch(name_of::<T>()));
+    }
+    if slice.len() < header {
+        return Err(VerificationError::HeaderIsBroken(
+            name_of::<T>(),
+            header,
+            slice.len(),
+        ));
+    }
+    let mut offsets = slice[NUMBER_SIZE..header]
+        .chunks(NUMBER_SIZE)
+        .map(|chunk| unpack_number(chunk) as usize)
+        .collect::<Vec<_>>();
+    offsets.push(total);
+    if offsets.windows(2).any(|pair| pair[0] > pair[1]) {
+        return Err(VerificationError::OffsetsNotMatch(name_of::<T>()));
+    }
+    Ok(offsets
+        .windows(2)
+        .map(|pair| &slice[pair[0]..pair[1]])
+        .collect())
+}
+
+/// Split a table into its fields, which should have the same count as the type.
+pub fn table_fields<T: Encode>(slice: &[u8], count: usize) -> VerificationResult<Vec<&[u8]>> {
+    let fields = dynamic_items::<T>(slice)?;
+    if fields.len() != count {
+        return Err(VerificationError::FieldCountNotMatch(
+            name_of::<T>(),
+            count,
+            fields.len(),
+        ));
+    }
+    Ok(fields)
+}
+
+/// Split a union into the item ID and the item, and check the ID by the count of the items.
+pub fn union_item<T: Encode>(slice: &[u8], count: usize) -> VerificationResult<(usize, &[u8])> {
+    if slice.len() < NUMBER_SIZE {
+        return Err(VerificationError::HeaderIsBroken(
+            name_of::<T>(),
+            NUMBER_SIZE,
+            slice.len(),
+        ));
+    }
+    let item_id = unpack_number(slice);
+    if item_id as usize >= count {
+        return Err(VerificationError::UnknownItem(
+            name_of::<T>(),
+            count,
+            item_id,
+        ));
+    }
+    Ok((item_id as usize, &slice[NUMBER_SIZE..]))
+}
+
+/// Write the item ID of a union.
+pub fn write_item_id(out: &mut Vec<u8>, item_id: usize) {
+    out.extend_from_slice(&pack_size(item_id));
+}
+
+/// Declare a struct, or a table if it has no fixed size, with its fields and their types.
+pub fn describe_fields(
+    schema: &mut Schema,
+    name: &str,
+    is_struct: bool,
+    fields: &[(&str, String)],
+) -> String {
+    let keyword = if is_struct { "struct" } else { "table" };
+    let mut decl = format!("{} {} {{\n", keyword, name);
+    for (field, typ) in fields {
+        decl.push_str(&format!("    {}: {},\n", field, typ));
+    }
+    decl.push('}');
+    schema.declare(name, decl);
+    name.to_owned()
+}
+
+/// Declare a union with its items in order.
+///
+/// # Panics
+///
+/// Panics if an item is duplicated, since the items are told apart by their types.
+pub fn describe_union(schema: &mut Schema, name: &str, items: &[String]) -> String {
+    let mut decl = format!("union {} {{\n", name);
+    for (index, item) in items.iter().enumerate() {
+        if items[..index].contains(item) {
+            panic!("the item ({}) of the union ({}) is duplicated", item, name);
+        }
+        decl.push_str(&format!("    {},\n", item));
+    }
+    decl.push('}');
+    schema.declare(name, decl);
+    name.to_owned()
+}
+
+impl Encode for u8 {
+    const FIXED_SIZE: Option<usize> = Some(1);
+
+    fn encoded_size(&self) -> usize {
+        1
+    }
+
+    fn encode_to(&self, out: &mut Vec<u8>) {
+        out.push(*self);
+    }
+
+    fn describe(_schema: &mut Schema) -> String {
+        "byte".to_owned()
+    }
+
+    fn type_name() -> String {
+        "byte".to_owned()
+    }
+}
+
+impl Decode for u8 {
+    fn decode(slice: &[u8]) -> VerificationResult<Self> {
+        if slice.len() != 1 {
+            return Err(VerificationError::TotalSizeNotMatch(
+                "byte".to_owned(),
+                1,
+                slice.len(),
+            ));
+        }
+        Ok(slice[0])
+    }
+}
+
+macro_rules! impl_integer {
+    ($type:ty, $name:literal) => {
+        impl Encode for $type {
+            const FIXED_SIZE: Option<usize> = Some(size_of::<$type>());
+
+            fn encoded_size(&self) -> usize {
+                size_of::<$type>()
+            }
+
+            fn encode_to(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn describe(schema: &mut Schema) -> String {
+                let decl = format!("array {} [byte; {}];", $name, size_of::<$type>());
+                schema.declare($name, decl);
+                $name.to_owned()
+            }
+
+            fn type_name() -> String {
+                $name.to_owned()
+            }
+        }
+
+        impl Decode for $type {
+            fn decode(slice: &[u8]) -> VerificationResult<Self> {
+                let bytes = slice.try_into().map_err(|_| {
+                    VerificationError::TotalSizeNotMatch(
+                        $name.to_owned(),
+                        size_of::<$type>(),
+                        slice.len(),
+                    )
+                })?;
+                Ok(<$type>::from_le_bytes(bytes))
+            }
+        }
+    };
+}
+
+impl_integer!(u16, "Uint16");
+impl_integer!(u32, "Uint32");
+impl_integer!(u64, "Uint64");
+impl_integer!(u128, "Uint128");
+impl_integer!(i8, "Int8");
+impl_integer!(i16, "Int16");
+impl_integer!(i32, "Int32");
+impl_integer!(i64, "Int64");
+impl_integer!(i128, "Int128");
+
+impl<T: Encode, const N: usize> Encode for [T; N] {
+    const FIXED_SIZE: Option<usize> = match T::FIXED_SIZE {
+        Some(size) => Some(size * N),
+        None => None,
+    };
+
+    fn encoded_size(&self) -> usize {
+        self.iter().map(Encode::encoded_size).sum()
+    }
+
+    fn encode_to(&self, out: &mut Vec<u8>) {
+        for item in self {
+            item.encode_to(out);
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the items have no fixed size or the array has no size, since such an array has
+    /// no molecule type.
+    fn describe(schema: &mut Schema) -> String {
+        let item = T::describe(schema);
+        let name = array_name(&item, N);
+        match T::FIXED_SIZE {
+            None => panic!("the items of the array ({}) should have a fixed size", name),
+            Some(size) if size * N == 0 => panic!("the array ({}) has no size", name),
+            Some(_) => {}
+        }
+        schema.declare(&name, format!("array {} [{}; {}];", name, item, N));
+        name
+    }
+
+    fn type_name() -> String {
+        array_name(&T::type_name(), N)
+    }
+}
+
+impl<T: Decode, const N: usize> Decode for [T; N] {
+    /// The array of the items which have no fixed size is never decoded, since its items can't
+    /// be told apart, and neither is the array which has no size.
+    fn decode(slice: &[u8]) -> VerificationResult<Self> {
+        let item_size = T::FIXED_SIZE.ok_or_else(|| {
+            VerificationError::InvariantNotHeld(name_of::<Self>(), "fixed_size".to_owned())
+        })?;
+        if item_size * N == 0 {
+            return Err(VerificationError::InvariantNotHeld(
+                name_of::<Self>(),
+                "non_zero_size".to_owned(),
+            ));
+        }
+        if slice.len() != item_size * N {
+            return Err(VerificationError::TotalSizeNotMatch(
+                name_of::<Self>(),
+                item_size * N,
+                slice.len(),
+            ));
+        }
+        let items = (0..N)
+            .map(|index| T::decode(&slice[item_size * index..item_size * (index + 1)]))
+            .collect::<VerificationResult<Vec<_>>>()?;
+        Ok(items
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("the array has {} items", N)))
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    const FIXED_SIZE: Option<usize> = None;
+
+    fn encoded_size(&self) -> usize {
+        if let Some(item_size) = T::FIXED_SIZE {
+            NUMBER_SIZE + item_size * self.len()
+        } else {
+            NUMBER_SIZE * (self.len() + 1) + self.iter().map(Encode::encoded_size).sum::<usize>()
+        }
+    }
+
+    fn encode_to(&self, out: &mut Vec<u8>) {
+        if T::FIXED_SIZE.is_some() {
+            out.extend_from_slice(&pack_size(self.len()));
+        } else {
+            let sizes = self.iter().map(Encode::encoded_size).collect::<Vec<_>>();
+            write_header(out, &sizes);
+        }
+        for item in self {
+            item.encode_to(out);
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the items have no size, since they can't be counted by the size of the vector.
+    fn describe(schema: &mut Schema) -> String {
+        let item = T::describe(schema);
+        let name = vector_name(&item);
+        if T::FIXED_SIZE == Some(0) {
+            panic!("the items of the vector ({}) have no size", name);
+        }
+        schema.declare(&name, format!("vector {} <{}>;", name, item));
+        name
+    }
+
+    fn type_name() -> String {
+        vector_name(&T::type_name())
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    /// The vector of the items which have no size is never decoded, since the count in its header
+    /// isn't bounded by its size.
+    fn decode(slice: &[u8]) -> VerificationResult<Self> {
+        if let Some(item_size) = T::FIXED_SIZE {
+            if item_size == 0 {
+                return Err(VerificationError::InvariantNotHeld(
+                    name_of::<Self>(),
+                    "non_zero_size".to_owned(),
+                ));
+            }
+            if slice.len() < NUMBER_SIZE {
+                return Err(VerificationError::HeaderIsBroken(
+                    name_of::<Self>(),
+                    NUMBER_SIZE,
+                    slice.len(),
+                ));
+            }
+            let count = unpack_number(slice) as usize;
+            let expected = fixvec_total_size(NUMBER_SIZE, item_size, count)
+                .ok_or_else(|| VerificationError::SizeOverflow(name_of::<Self>()))?;
+            if slice.len() != expected {
+                return Err(VerificationError::TotalSizeNotMatch(
+                    name_of::<Self>(),
+                    expected,
+                    slice.len(),
+                ));
+            }
+            (0..count)
+                .map(|index| {
+                    let start = NUMBER_SIZE + item_size * index;
+                    T::decode(&slice[start..start + item_size])
+                })
+                .collect()
+        } else {
+            dynamic_items::<Self>(slice)?
+                .into_iter()
+                .map(T::decode)
+                .collect()
+        }
+    }
+}
+
+impl Encode for String {
+    const FIXED_SIZE: Option<usize> = None;
+
+    fn encoded_size(&self) -> usize {
+        NUMBER_SIZE + self.len()
+    }
+
+    fn encode_to(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&pack_size(self.len()));
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn describe(schema: &mut Schema) -> String {
+        <Vec<u8>>::describe(schema)
+    }
+
+    fn type_name() -> String {
+        <Vec<u8>>::type_name()
+    }
+}
+
+impl Decode for String {
+    fn decode(slice: &[u8]) -> VerificationResult<Self> {
+        let bytes = <Vec<u8>>::decode(slice)?;
+        String::from_utf8(bytes)
+            .map_err(|_| VerificationError::InvariantNotHeld(name_of::<Self>(), "utf8".to_owned()))
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    const FIXED_SIZE: Option<usize> = None;
+
+    fn encoded_size(&self) -> usize {
+        self.as_ref().map(Encode::encoded_size).unwrap_or_default()
+    }
+
+    fn encode_to(&self, out: &mut Vec<u8>) {
+        if let Some(inner) = self {
+            inner.encode_to(out);
+        }
+    }
+
+    fn describe(schema: &mut Schema) -> String {
+        let inner = T::describe(schema);
+        let name = format!("{}Opt", part_of_name(&inner));
+        schema.declare(&name, format!("option {} ({});", name, inner));
+        name
+    }
+
+    fn type_name() -> String {
+        format!("{}Opt", part_of_name(&T::type_name()))
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(slice: &[u8]) -> VerificationResult<Self> {
+        if slice.is_empty() {
+            Ok(None)
+        } else {
+            T::decode(slice).map(Some)
+        }
+    }
+}
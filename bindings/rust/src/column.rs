@@ -0,0 +1,68 @@
+//! Columns of the fields of vectors of structs.
+//!
+//! A column reads one field of all items; the items are fixed-size, so it only slices the bytes
+//! of the field and never touches the other fields.
+
+use core::{iter::FusedIterator, slice::ChunksExact};
+
+/// An iterator over a field of the items, which yields the readers of the field.
+#[derive(Debug, Clone)]
+pub struct Column<'r, T> {
+    items: ChunksExact<'r, u8>,
+    start: usize,
+    end: usize,
+    reader: fn(&'r [u8]) -> T,
+}
+
+impl<'r, T> Column<'r, T> {
+    /// The field is at `start..end` of each item, and it's read by `reader`, such as the
+    /// `new_unchecked` of a reader; the trailing bytes of the items are ignored.
+    pub fn new(
+        items: &'r [u8],
+        item_size: usize,
+        start: usize,
+        end: usize,
+        reader: fn(&'r [u8]) -> T,
+    ) -> Self {
+        assert!(
+            start <= end && end <= item_size,
+            "the field is out of the item"
+        );
+        Self {
+            items: items.chunks_exact(item_size),
+            start,
+            end,
+            reader,
+        }
+    }
+
+    fn field(&self, item: &'r [u8]) -> T {
+        (self.reader)(&item[self.start..self.end])
+    }
+}
+
+impl<'r, T> Iterator for Column<'r, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.items.next().map(|item| self.field(item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.items.nth(n).map(|item| self.field(item))
+    }
+}
+
+impl<'r, T> DoubleEndedIterator for Column<'r, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.items.next_back().map(|item| self.field(item))
+    }
+}
+
+impl<'r, T> ExactSizeIterator for Column<'r, T> {}
+
+impl<'r, T> FusedIterator for Column<'r, T> {}
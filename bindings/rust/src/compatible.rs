@@ -0,0 +1,48 @@
+//! Hooks to observe what the compatible mode verification tolerates.
+//!
+//! When old software verifies data from a newer schema with `from_compatible_slice`, the extra
+//! fields of tables are ignored silently. A hook reports these events, so operators are able to
+//! measure how much newer-schema traffic is seen before turning off the compatible mode.
+
+use core::{
+    mem, ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use crate::Number;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompatibleEvent {
+    /// A table has more fields than the schema defines.
+    ExtraFields {
+        type_name: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    /// A union has an item ID which the schema doesn't define.
+    UnknownItem {
+        type_name: &'static str,
+        item_count: usize,
+        item_id: Number,
+    },
+}
+
+pub type CompatibleHook = fn(&CompatibleEvent);
+
+static HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Set the hook for the whole process, or remove it by `None`.
+pub fn set_hook(hook: Option<CompatibleHook>) {
+    let ptr = hook.map_or(ptr::null_mut(), |hook| hook as *mut ());
+    HOOK.store(ptr, Ordering::Release);
+}
+
+#[doc(hidden)]
+#[inline]
+pub fn report(event: CompatibleEvent) {
+    let ptr = HOOK.load(Ordering::Acquire);
+    if !ptr.is_null() {
+        let hook: CompatibleHook = unsafe { mem::transmute::<*mut (), CompatibleHook>(ptr) };
+        hook(&event);
+    }
+}
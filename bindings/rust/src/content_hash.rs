@@ -0,0 +1,175 @@
+//! The content identifiers of the molecule values, for content-addressed storage.
+//!
+//! A value is identified by a hash of its bytes, which are canonical: there is only one encoding
+//! of a value in the strict mode. The hash is a multihash, the code of the hash function and the
+//! digest; and the content identifier is a CIDv1 of the `raw` codec over it, so the values could
+//! be stored beside the other content-addressed data.
+//!
+//! The hash functions are registered at runtime by their multihash codes, so the runtime doesn't
+//! depend on any implementation of them; only the identity hash is built in.
+
+use alloc::{string::String, vec::Vec};
+use core::{
+    fmt, ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+/// The multihash code of the identity hash, whose digest is the bytes themselves.
+pub const IDENTITY: u64 = 0x00;
+
+/// The multicodec code of the raw bytes, which is the codec of the content identifiers.
+pub const RAW_CODEC: u64 = 0x55;
+
+/// A hash function returns the digest of the bytes.
+pub type HashFn = fn(&[u8]) -> Vec<u8>;
+
+/// The table of the hash functions, by their multihash codes, such as `0x12` for SHA2-256.
+#[derive(Debug)]
+pub struct Hashers(pub &'static [(u64, HashFn)]);
+
+impl Hashers {
+    pub fn get(&self, code: u64) -> Option<HashFn> {
+        self.0
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(_, hash)| *hash)
+    }
+}
+
+static HASHERS: AtomicPtr<Hashers> = AtomicPtr::new(ptr::null_mut());
+
+/// Set the hash functions for the whole process, or remove them by `None`.
+pub fn set_hashers(hashers: Option<&'static Hashers>) {
+    let ptr = hashers.map_or(ptr::null_mut(), |hashers| {
+        hashers as *const Hashers as *mut Hashers
+    });
+    HASHERS.store(ptr, Ordering::Release);
+}
+
+/// Hash the bytes by the hash function of the code, or `None` if it's not registered.
+pub fn hash(code: u64, data: &[u8]) -> Option<Multihash> {
+    let digest = if code == IDENTITY {
+        data.to_vec()
+    } else {
+        let ptr = HASHERS.load(Ordering::Acquire);
+        let hash = unsafe { ptr.as_ref() }.and_then(|hashers| hashers.get(code))?;
+        hash(data)
+    };
+    Some(Multihash { code, digest })
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn read_varint(data: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for (i, byte) in data.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (i * 7);
+        if byte & 0x80 == 0 {
+            *data = &data[i + 1..];
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// A digest with the code of its hash function.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Multihash {
+    pub code: u64,
+    pub digest: Vec<u8>,
+}
+
+impl Multihash {
+    /// The binary multihash: the code and the size of the digest in unsigned varints, then the
+    /// digest.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.digest.len() + 4);
+        write_varint(&mut buf, self.code);
+        write_varint(&mut buf, self.digest.len() as u64);
+        buf.extend_from_slice(&self.digest);
+        buf
+    }
+}
+
+/// A content identifier, which is a CIDv1.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cid {
+    pub codec: u64,
+    pub hash: Multihash,
+}
+
+impl Cid {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_varint(&mut buf, 1);
+        write_varint(&mut buf, self.codec);
+        buf.extend(self.hash.to_bytes());
+        buf
+    }
+
+    /// Read a binary CIDv1, or `None` if it's not one.
+    pub fn from_bytes(mut data: &[u8]) -> Option<Self> {
+        if read_varint(&mut data)? != 1 {
+            return None;
+        }
+        let codec = read_varint(&mut data)?;
+        let code = read_varint(&mut data)?;
+        let size = read_varint(&mut data)?;
+        if data.len() as u64 != size {
+            return None;
+        }
+        let hash = Multihash {
+            code,
+            digest: data.to_vec(),
+        };
+        Some(Cid { codec, hash })
+    }
+}
+
+/// The string of the content identifier in the multibase `base32`, such as `bafkrei...`.
+impl fmt::Display for Cid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+        let mut text = String::from("b");
+        let (mut buffer, mut bits) = (0u16, 0);
+        for byte in self.to_bytes() {
+            buffer = (buffer << 8) | u16::from(byte);
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                text.push(ALPHABET[usize::from((buffer >> bits) & 0x1f)] as char);
+            }
+        }
+        if bits > 0 {
+            text.push(ALPHABET[usize::from((buffer << (5 - bits)) & 0x1f)] as char);
+        }
+        write!(f, "{}", text)
+    }
+}
+
+/// The content hashes of the generated entities and readers.
+pub trait ContentHash {
+    /// The canonical bytes of the value, which are hashed.
+    fn canonical_bytes(&self) -> &[u8];
+
+    /// The multihash of the value by the hash function of the code, or `None` if it's not
+    /// registered.
+    fn content_hash(&self, code: u64) -> Option<Multihash> {
+        hash(code, self.canonical_bytes())
+    }
+
+    /// The content identifier of the value by the hash function of the code, or `None` if it's
+    /// not registered.
+    fn cid(&self, code: u64) -> Option<Cid> {
+        self.content_hash(code).map(|hash| Cid {
+            codec: RAW_CODEC,
+            hash,
+        })
+    }
+}
@@ -0,0 +1,52 @@
+//! The byte accounting of the values, which splits their bytes into the headers and the payload,
+//! and attributes them to the fields, such as for planning the capacity by the components of the
+//! messages.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::primitive::{Byte, ByteReader};
+
+/// The bytes of a value, including the values in it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SizeBreakdown {
+    /// The bytes of the headers: the total sizes, the offsets, the item counts and the item IDs.
+    pub header: usize,
+    /// The other bytes, which are the data.
+    pub payload: usize,
+    /// The breakdowns of the fields of a struct or a table, or of the item of a union, by their
+    /// names; the fields are empty for the other types, whose items are summed up.
+    pub fields: Vec<(String, SizeBreakdown)>,
+}
+
+impl SizeBreakdown {
+    /// The breakdown of the bytes without any header.
+    pub fn payload(size: usize) -> Self {
+        SizeBreakdown {
+            header: 0,
+            payload: size,
+            fields: Vec::new(),
+        }
+    }
+
+    /// The size of the value.
+    pub fn total(&self) -> usize {
+        self.header + self.payload
+    }
+}
+
+/// The byte accounting of the generated entities and readers.
+pub trait DeepSize {
+    fn deep_size_breakdown(&self) -> SizeBreakdown;
+}
+
+impl DeepSize for Byte {
+    fn deep_size_breakdown(&self) -> SizeBreakdown {
+        SizeBreakdown::payload(1)
+    }
+}
+
+impl<'r> DeepSize for ByteReader<'r> {
+    fn deep_size_breakdown(&self) -> SizeBreakdown {
+        SizeBreakdown::payload(1)
+    }
+}
@@ -0,0 +1,72 @@
+//! The formatters of the bytes of the fields, which are set by `@display` in the schemas.
+//!
+//! The built-in formatters are `hex` and `utf8`; the others, such as `bech32` for the addresses,
+//! are registered at runtime by their names, so the runtime doesn't depend on any encoding of
+//! them. The bytes are in hex if their formatter isn't registered.
+
+use core::{
+    fmt, ptr, str,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use crate::hex_string;
+
+/// A formatter writes the bytes of a field.
+pub type FormatFn = fn(&[u8], &mut fmt::Formatter) -> fmt::Result;
+
+/// The table of the formatters, by their names in the schemas.
+#[derive(Debug)]
+pub struct Formatters(pub &'static [(&'static str, FormatFn)]);
+
+impl Formatters {
+    pub fn get(&self, name: &str) -> Option<FormatFn> {
+        self.0
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, format)| *format)
+    }
+}
+
+static FORMATTERS: AtomicPtr<Formatters> = AtomicPtr::new(ptr::null_mut());
+
+/// Set the formatters for the whole process, or remove them by `None`.
+pub fn set_formatters(formatters: Option<&'static Formatters>) {
+    let ptr = formatters.map_or(ptr::null_mut(), |formatters| {
+        formatters as *const Formatters as *mut Formatters
+    });
+    FORMATTERS.store(ptr, Ordering::Release);
+}
+
+fn format_hex(data: &[u8], f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "0x{}", hex_string(data))
+}
+
+/// A quoted string if the bytes are UTF-8, otherwise they are in hex.
+fn format_utf8(data: &[u8], f: &mut fmt::Formatter) -> fmt::Result {
+    match str::from_utf8(data) {
+        Ok(text) => write!(f, "{:?}", text),
+        Err(_) => format_hex(data, f),
+    }
+}
+
+/// The bytes of a field, which are displayed by the formatter of the name.
+pub struct Formatted<'a> {
+    pub formatter: &'a str,
+    pub data: &'a [u8],
+}
+
+impl<'a> fmt::Display for Formatted<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let format = match self.formatter {
+            "hex" => format_hex,
+            "utf8" => format_utf8,
+            name => {
+                let ptr = FORMATTERS.load(Ordering::Acquire);
+                unsafe { ptr.as_ref() }
+                    .and_then(|formatters| formatters.get(name))
+                    .unwrap_or(format_hex)
+            }
+        };
+        format(self.data, f)
+    }
+}
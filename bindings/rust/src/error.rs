@@ -11,17 +11,66 @@ macro_rules! verification_error {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum VerificationError {
     TotalSizeNotMatch(String, usize, usize),
     HeaderIsBroken(String, usize, usize),
     UnknownItem(String, usize, Number),
     OffsetsNotMatch(String),
     FieldCountNotMatch(String, usize, usize),
+    SizeOverflow(String),
+    /// The data is well-formed, but the validator of the invariant rejects it.
+    InvariantNotHeld(String, String),
+    /// The type declares an invariant, but no validator is registered for it.
+    InvariantNotRegistered(String, String),
+    /// The fields don't satisfy a constraint of the schema, which is in the error as written.
+    ConstraintNotHeld(String, String),
+    /// The checksum in the trailer of a sealed record, then the checksum of its payload.
+    ChecksumNotMatch(String, u32, u32),
+    /// The item of a union is larger than its `@max_size`, then the size of the item.
+    ItemTooLarge(String, usize, usize),
 }
 
 pub type VerificationResult<T> = result::Result<T, VerificationError>;
 
+impl VerificationError {
+    /// The name of the kind of the error, which is stable for logging and metrics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            VerificationError::TotalSizeNotMatch(..) => "TotalSizeNotMatch",
+            VerificationError::HeaderIsBroken(..) => "HeaderIsBroken",
+            VerificationError::UnknownItem(..) => "UnknownItem",
+            VerificationError::OffsetsNotMatch(..) => "OffsetsNotMatch",
+            VerificationError::FieldCountNotMatch(..) => "FieldCountNotMatch",
+            VerificationError::SizeOverflow(..) => "SizeOverflow",
+            VerificationError::InvariantNotHeld(..) => "InvariantNotHeld",
+            VerificationError::InvariantNotRegistered(..) => "InvariantNotRegistered",
+            VerificationError::ConstraintNotHeld(..) => "ConstraintNotHeld",
+            VerificationError::ChecksumNotMatch(..) => "ChecksumNotMatch",
+            VerificationError::ItemTooLarge(..) => "ItemTooLarge",
+        }
+    }
+
+    /// The name of the type which failed the verification.
+    ///
+    /// It may be a type nested in the one which was decoded.
+    pub fn type_name(&self) -> &str {
+        match self {
+            VerificationError::TotalSizeNotMatch(st, ..)
+            | VerificationError::HeaderIsBroken(st, ..)
+            | VerificationError::UnknownItem(st, ..)
+            | VerificationError::OffsetsNotMatch(st)
+            | VerificationError::FieldCountNotMatch(st, ..)
+            | VerificationError::SizeOverflow(st)
+            | VerificationError::InvariantNotHeld(st, _)
+            | VerificationError::InvariantNotRegistered(st, _)
+            | VerificationError::ConstraintNotHeld(st, _)
+            | VerificationError::ChecksumNotMatch(st, ..)
+            | VerificationError::ItemTooLarge(st, ..) => st,
+        }
+    }
+}
+
 impl fmt::Display for VerificationError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -56,11 +105,80 @@ impl fmt::Display for VerificationError {
                     st, expected, actual
                 )?;
             }
+            VerificationError::SizeOverflow(st) => {
+                write!(f, "{} size overflows", st)?;
+            }
+            VerificationError::InvariantNotHeld(st, invariant) => {
+                write!(f, "{} doesn't hold the invariant {}", st, invariant)?;
+            }
+            VerificationError::InvariantNotRegistered(st, invariant) => {
+                write!(f, "{} has no validator for the invariant {}", st, invariant)?;
+            }
+            VerificationError::ConstraintNotHeld(st, constraint) => {
+                write!(f, "{} doesn't satisfy the constraint `{}`", st, constraint)?;
+            }
+            VerificationError::ChecksumNotMatch(st, expected, actual) => {
+                write!(
+                    f,
+                    "{} checksum doesn't match, expect {:#010x}, actual {:#010x}",
+                    st, expected, actual
+                )?;
+            }
+            VerificationError::ItemTooLarge(st, max_size, actual) => {
+                write!(
+                    f,
+                    "{} item is too large, expect at most {}, actual {}",
+                    st, max_size, actual
+                )?;
+            }
         }
         Ok(())
     }
 }
 
+/// A violation of the schema by the fields of a builder, which would make `build` panic or
+/// build a value which fails the verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// The item count of a FixVec, then the max count which fits in its header.
+    TooManyItems(String, usize, u64),
+    /// The size of the value doesn't fit in a header.
+    TooLarge(String, usize),
+    /// The fields don't satisfy a constraint of the schema, which is in the violation as written.
+    ConstraintNotHeld(String, String),
+    /// An option field which is marked as `@required` is none, then the name of the field.
+    MissingRequired(String, String),
+}
+
+impl Violation {
+    /// The name of the type whose builder violates the schema.
+    pub fn type_name(&self) -> &str {
+        match self {
+            Violation::TooManyItems(st, ..)
+            | Violation::TooLarge(st, _)
+            | Violation::ConstraintNotHeld(st, _)
+            | Violation::MissingRequired(st, _) => st,
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::TooManyItems(st, count, max) => {
+                write!(f, "{} has {} items, but at most {}", st, count, max)
+            }
+            Violation::TooLarge(st, size) => write!(f, "{} size ({}) is too large", st, size),
+            Violation::ConstraintNotHeld(st, constraint) => {
+                write!(f, "{} doesn't satisfy the constraint `{}`", st, constraint)
+            }
+            Violation::MissingRequired(st, field) => {
+                write!(f, "{} requires the field `{}`", st, field)
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum Error {
     Verification(VerificationError),
@@ -79,9 +197,42 @@ impl fmt::Display for Error {
     }
 }
 
+cfg_if::cfg_if! {
+    if #[cfg(feature = "tracing")] {
+        /// Emit an event for a failed verification, then return the error back.
+        #[doc(hidden)]
+        pub fn traced(
+            type_name: &str,
+            length: usize,
+            compatible: bool,
+            err: VerificationError,
+        ) -> VerificationError {
+            tracing::warn!(
+                target: "molecule::verification",
+                type_name,
+                failed_type = err.type_name(),
+                kind = err.kind(),
+                length,
+                compatible,
+                "failed to verify {}: {}",
+                type_name,
+                err
+            );
+            err
+        }
+    } else {
+        #[doc(hidden)]
+        #[inline]
+        pub fn traced(_: &str, _: usize, _: bool, err: VerificationError) -> VerificationError {
+            err
+        }
+    }
+}
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "std")] {
         impl ::std::error::Error for VerificationError {}
+        impl ::std::error::Error for Violation {}
         impl ::std::error::Error for Error {}
     }
 }
@@ -0,0 +1,87 @@
+//! The types of the C bindings, which are used by the Rust declarations of the generated C code.
+//!
+//! The names and the layouts are the same as the ones in `molecule_reader.h` and
+//! `molecule_builder.h`.
+
+#![allow(non_camel_case_types)]
+
+use core::{convert::TryFrom, slice};
+
+pub type mol_num_t = u32;
+pub type mol_errno = u8;
+
+pub const MOL_OK: mol_errno = 0x00;
+pub const MOL_ERR: mol_errno = 0xff;
+pub const MOL_ERR_TOTAL_SIZE: mol_errno = 0x01;
+pub const MOL_ERR_HEADER: mol_errno = 0x02;
+pub const MOL_ERR_OFFSET: mol_errno = 0x03;
+pub const MOL_ERR_UNKNOWN_ITEM: mol_errno = 0x04;
+pub const MOL_ERR_INDEX_OUT_OF_BOUNDS: mol_errno = 0x05;
+pub const MOL_ERR_FIELD_COUNT: mol_errno = 0x06;
+pub const MOL_ERR_DATA: mol_errno = 0x07;
+pub const MOL_ERR_SIZE_OVERFLOW: mol_errno = 0x08;
+
+/// A segment of bytes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct mol_seg_t {
+    pub ptr: *mut u8,
+    pub size: mol_num_t,
+}
+
+/// The result of the functions which return a segment.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct mol_seg_res_t {
+    pub errno: mol_errno,
+    pub seg: mol_seg_t,
+}
+
+/// The builder, whose buffers are allocated and freed by the C code.
+#[repr(C)]
+#[derive(Debug)]
+pub struct mol_builder_t {
+    pub data_ptr: *mut u8,
+    pub data_used: mol_num_t,
+    pub data_cap: mol_num_t,
+    pub number_ptr: *mut mol_num_t,
+    pub number_used: mol_num_t,
+    pub number_cap: mol_num_t,
+}
+
+/// The verify functions of the generated C code, such as `MolReader_Xxx_verify`.
+pub type VerifyFn = unsafe extern "C" fn(*const mol_seg_t, bool) -> mol_errno;
+
+impl mol_seg_t {
+    /// The segment of the slice, the C code must not write into it.
+    ///
+    /// It panics if the slice is larger than `mol_num_t::MAX`.
+    pub fn from_slice(slice: &[u8]) -> Self {
+        let size = mol_num_t::try_from(slice.len()).expect("the slice is too large");
+        Self {
+            ptr: slice.as_ptr() as *mut u8,
+            size,
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The segment must point to `size` bytes which live for `'a`.
+    pub unsafe fn as_slice<'a>(&self) -> &'a [u8] {
+        if self.size == 0 {
+            &[]
+        } else {
+            slice::from_raw_parts(self.ptr, self.size as usize)
+        }
+    }
+}
+
+/// Verify the slice by a verify function of the generated C code, and return its error number.
+///
+/// # Safety
+///
+/// The function must only read the segment, as the verify functions of the generated C code.
+pub unsafe fn verify(verify: VerifyFn, slice: &[u8], compatible: bool) -> mol_errno {
+    let seg = mol_seg_t::from_slice(slice);
+    verify(&seg, compatible)
+}
@@ -0,0 +1,266 @@
+//! The IPLD data model and its DAG-CBOR encoding, to store and traverse the molecule values in
+//! IPFS-compatible systems.
+//!
+//! The values are mapped to the nodes by their schemas, which is done by `IpldBridge` of the code
+//! generator, so the nodes are only the data model here. The encoding follows the strictness of
+//! DAG-CBOR: the integers are in their shortest forms, the keys of the maps are sorted by their
+//! lengths then their bytes, and the only tag is 42 for the links.
+
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::{cmp::Ordering, convert::TryFrom, fmt, str};
+
+use crate::content_hash::Cid;
+
+/// The multicodec code of DAG-CBOR, which is the codec of the content identifiers of the
+/// encoded nodes.
+pub const DAG_CBOR_CODEC: u64 = 0x71;
+
+const TAG_LINK: u64 = 42;
+
+/// A node of the IPLD data model.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Ipld {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    List(Vec<Ipld>),
+    Map(BTreeMap<String, Ipld>),
+    Link(Cid),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The data ends in a node.
+    UnexpectedEnd,
+    /// The data has bytes after the node, the count of which is in the error.
+    TrailingBytes(usize),
+    /// The initial byte of a node isn't supported by DAG-CBOR, such as an indefinite length.
+    Unsupported(u8),
+    /// A number isn't in its shortest form, or the keys of a map aren't sorted.
+    NotCanonical,
+    /// An integer doesn't fit in an `i64`.
+    IntegerOverflow,
+    /// A string or a key of a map isn't UTF-8.
+    InvalidString,
+    /// A key of a map isn't a string.
+    InvalidKey,
+    /// A tag isn't 42, or its content isn't a CID.
+    InvalidLink,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd => write!(f, "the data ends in a node"),
+            DecodeError::TrailingBytes(count) => {
+                write!(f, "the data has {} bytes after the node", count)
+            }
+            DecodeError::Unsupported(initial) => {
+                write!(f, "the initial byte {:#04x} is not supported", initial)
+            }
+            DecodeError::NotCanonical => write!(f, "the data is not canonical"),
+            DecodeError::IntegerOverflow => write!(f, "the integer overflows"),
+            DecodeError::InvalidString => write!(f, "the string is not UTF-8"),
+            DecodeError::InvalidKey => write!(f, "the key of the map is not a string"),
+            DecodeError::InvalidLink => write!(f, "the link is not a CID"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for DecodeError {}
+
+/// The order of the keys of the maps in DAG-CBOR.
+fn cmp_keys(a: &str, b: &str) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+fn write_head(buf: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+    if value < 24 {
+        buf.push(major | value as u8);
+    } else if value <= u64::from(u8::MAX) {
+        buf.push(major | 24);
+        buf.push(value as u8);
+    } else if value <= u64::from(u16::MAX) {
+        buf.push(major | 25);
+        buf.extend_from_slice(&(value as u16).to_be_bytes());
+    } else if value <= u64::from(u32::MAX) {
+        buf.push(major | 26);
+        buf.extend_from_slice(&(value as u32).to_be_bytes());
+    } else {
+        buf.push(major | 27);
+        buf.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+fn write_node(buf: &mut Vec<u8>, node: &Ipld) {
+    match node {
+        Ipld::Null => buf.push(0xf6),
+        Ipld::Bool(false) => buf.push(0xf4),
+        Ipld::Bool(true) => buf.push(0xf5),
+        Ipld::Integer(value) if *value >= 0 => write_head(buf, 0, *value as u64),
+        Ipld::Integer(value) => write_head(buf, 1, !(*value) as u64),
+        Ipld::Float(value) => {
+            buf.push(0xfb);
+            buf.extend_from_slice(&value.to_bits().to_be_bytes());
+        }
+        Ipld::String(value) => {
+            write_head(buf, 3, value.len() as u64);
+            buf.extend_from_slice(value.as_bytes());
+        }
+        Ipld::Bytes(value) => {
+            write_head(buf, 2, value.len() as u64);
+            buf.extend_from_slice(value);
+        }
+        Ipld::List(items) => {
+            write_head(buf, 4, items.len() as u64);
+            for item in items {
+                write_node(buf, item);
+            }
+        }
+        Ipld::Map(entries) => {
+            write_head(buf, 5, entries.len() as u64);
+            let mut entries = entries.iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| cmp_keys(a, b));
+            for (key, value) in entries {
+                write_head(buf, 3, key.len() as u64);
+                buf.extend_from_slice(key.as_bytes());
+                write_node(buf, value);
+            }
+        }
+        Ipld::Link(cid) => {
+            write_head(buf, 6, TAG_LINK);
+            // The binary CID is prefixed by the identity multibase.
+            let cid = cid.to_bytes();
+            write_head(buf, 2, cid.len() as u64 + 1);
+            buf.push(0x00);
+            buf.extend_from_slice(&cid);
+        }
+    }
+}
+
+/// Encode the node in DAG-CBOR.
+pub fn encode(node: &Ipld) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_node(&mut buf, node);
+    buf
+}
+
+struct Decoder<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, size: usize) -> Result<&'a [u8], DecodeError> {
+        if self.data.len() < size {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        let (taken, rest) = self.data.split_at(size);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    fn read_uint(&mut self, size: usize) -> Result<u64, DecodeError> {
+        let mut be = [0u8; 8];
+        be[8 - size..].copy_from_slice(self.take(size)?);
+        Ok(u64::from_be_bytes(be))
+    }
+
+    /// Read the major type and the argument of the head.
+    fn read_head(&mut self) -> Result<(u8, u8, u64), DecodeError> {
+        let initial = self.take(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        // The only simple values are false, true and null, and the only floats are 64-bit.
+        let (value, min) = match (major, info) {
+            (7, 20..=22) => (u64::from(info), 0),
+            (7, 27) => (self.read_uint(8)?, 0),
+            (7, _) => return Err(DecodeError::Unsupported(initial)),
+            (_, 0..=23) => (u64::from(info), 0),
+            (_, 24) => (self.read_uint(1)?, 24),
+            (_, 25) => (self.read_uint(2)?, 0x100),
+            (_, 26) => (self.read_uint(4)?, 0x1_0000),
+            (_, 27) => (self.read_uint(8)?, 0x1_0000_0000),
+            _ => return Err(DecodeError::Unsupported(initial)),
+        };
+        if value < min {
+            return Err(DecodeError::NotCanonical);
+        }
+        Ok((major, info, value))
+    }
+
+    fn read_string(&mut self, size: u64) -> Result<String, DecodeError> {
+        let bytes = self.take(size as usize)?;
+        str::from_utf8(bytes)
+            .map(String::from)
+            .map_err(|_| DecodeError::InvalidString)
+    }
+
+    fn read_node(&mut self) -> Result<Ipld, DecodeError> {
+        let (major, info, value) = self.read_head()?;
+        let node = match major {
+            0 => Ipld::Integer(i64::try_from(value).map_err(|_| DecodeError::IntegerOverflow)?),
+            1 => {
+                let value = i64::try_from(value).map_err(|_| DecodeError::IntegerOverflow)?;
+                Ipld::Integer(!value)
+            }
+            2 => Ipld::Bytes(self.take(value as usize)?.to_vec()),
+            3 => Ipld::String(self.read_string(value)?),
+            4 => {
+                let mut items = Vec::new();
+                for _ in 0..value {
+                    items.push(self.read_node()?);
+                }
+                Ipld::List(items)
+            }
+            5 => {
+                let mut entries = BTreeMap::new();
+                let mut last: Option<String> = None;
+                for _ in 0..value {
+                    let (major, _, size) = self.read_head()?;
+                    if major != 3 {
+                        return Err(DecodeError::InvalidKey);
+                    }
+                    let key = self.read_string(size)?;
+                    if let Some(ref last) = last {
+                        if cmp_keys(last, &key) != Ordering::Less {
+                            return Err(DecodeError::NotCanonical);
+                        }
+                    }
+                    let node = self.read_node()?;
+                    entries.insert(key.clone(), node);
+                    last = Some(key);
+                }
+                Ipld::Map(entries)
+            }
+            6 if value == TAG_LINK => match self.read_node()? {
+                Ipld::Bytes(ref bytes) if bytes.first() == Some(&0x00) => {
+                    Ipld::Link(Cid::from_bytes(&bytes[1..]).ok_or(DecodeError::InvalidLink)?)
+                }
+                _ => return Err(DecodeError::InvalidLink),
+            },
+            6 => return Err(DecodeError::InvalidLink),
+            7 => match info {
+                20 => Ipld::Bool(false),
+                21 => Ipld::Bool(true),
+                22 => Ipld::Null,
+                _ => Ipld::Float(f64::from_bits(value)),
+            },
+            _ => unreachable!(),
+        };
+        Ok(node)
+    }
+}
+
+/// Decode a node from DAG-CBOR.
+pub fn decode(data: &[u8]) -> Result<Ipld, DecodeError> {
+    let mut decoder = Decoder { data };
+    let node = decoder.read_node()?;
+    if !decoder.data.is_empty() {
+        return Err(DecodeError::TrailingBytes(decoder.data.len()));
+    }
+    Ok(node)
+}
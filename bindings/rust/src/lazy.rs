@@ -0,0 +1,117 @@
+//! Values which are initialized on the first access, such as the default values of the types.
+//!
+//! With the feature `std`, it's a `std::sync::OnceLock`; otherwise, the threads which access the
+//! value while it's being initialized spin until it's done.
+
+use core::fmt;
+
+/// A value which is initialized by a function on the first access, and shared after that.
+pub struct Lazy<T> {
+    cell: imp::Cell<T>,
+    init: fn() -> T,
+}
+
+impl<T> Lazy<T> {
+    pub const fn new(init: fn() -> T) -> Self {
+        Self {
+            cell: imp::Cell::new(),
+            init,
+        }
+    }
+
+    pub fn get(&self) -> &T {
+        self.cell.get_or_init(self.init)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Lazy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Lazy").field(self.get()).finish()
+    }
+}
+
+#[cfg(feature = "std")]
+mod imp {
+    pub(super) struct Cell<T>(std::sync::OnceLock<T>);
+
+    impl<T> Cell<T> {
+        pub(super) const fn new() -> Self {
+            Self(std::sync::OnceLock::new())
+        }
+
+        pub(super) fn get_or_init(&self, init: fn() -> T) -> &T {
+            self.0.get_or_init(init)
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+mod imp {
+    use core::{
+        cell::UnsafeCell,
+        hint,
+        mem::{self, MaybeUninit},
+        sync::atomic::{AtomicU8, Ordering},
+    };
+
+    const EMPTY: u8 = 0;
+    const RUNNING: u8 = 1;
+    const DONE: u8 = 2;
+
+    pub(super) struct Cell<T> {
+        state: AtomicU8,
+        value: UnsafeCell<MaybeUninit<T>>,
+    }
+
+    // The value is only written once, before the state is `DONE`, and only read after that.
+    unsafe impl<T: Send + Sync> Sync for Cell<T> {}
+
+    impl<T> Cell<T> {
+        pub(super) const fn new() -> Self {
+            Self {
+                state: AtomicU8::new(EMPTY),
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+            }
+        }
+
+        pub(super) fn get_or_init(&self, init: fn() -> T) -> &T {
+            loop {
+                match self.state.compare_exchange_weak(
+                    EMPTY,
+                    RUNNING,
+                    Ordering::Acquire,
+                    Ordering::Acquire,
+                ) {
+                    Ok(_) => {
+                        // If `init` panics, the state is reset, so the others don't spin forever
+                        // and the next access initializes the value again.
+                        let reset = Reset(&self.state);
+                        unsafe { (*self.value.get()).write(init()) };
+                        mem::forget(reset);
+                        self.state.store(DONE, Ordering::Release);
+                        break;
+                    }
+                    Err(DONE) => break,
+                    Err(_) => hint::spin_loop(),
+                }
+            }
+            unsafe { (*self.value.get()).assume_init_ref() }
+        }
+    }
+
+    struct Reset<'a>(&'a AtomicU8);
+
+    impl Drop for Reset<'_> {
+        fn drop(&mut self) {
+            self.0.store(EMPTY, Ordering::Release);
+        }
+    }
+
+    impl<T> Drop for Cell<T> {
+        fn drop(&mut self) {
+            if *self.state.get_mut() == DONE {
+                unsafe { self.value.get_mut().assume_init_drop() };
+            }
+        }
+    }
+}
@@ -3,7 +3,7 @@
 extern crate alloc;
 
 use alloc::string::String;
-use core::mem::size_of;
+use core::{convert::TryFrom, mem::size_of};
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "std")] {
@@ -19,20 +19,57 @@ cfg_if::cfg_if! {
     }
 }
 
+#[cfg(feature = "smallvec")]
+pub use smallvec;
+#[cfg(feature = "uuid")]
+pub use uuid;
+
+pub use codec::{Decode, Encode};
+#[cfg(feature = "derive")]
+pub use molecule_derive::{Decode, Encode};
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod batch;
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod canonical;
+pub mod checksum;
+pub mod codec;
+pub mod column;
+pub mod compatible;
+pub mod content_hash;
+pub mod deep_size;
+pub mod display;
 pub mod error;
+pub mod ffi;
+#[cfg(feature = "ipld")]
+pub mod ipld;
+pub mod lazy;
+pub mod logical;
+pub mod migration;
+pub mod molpack;
+pub mod number;
+#[cfg(feature = "rayon")]
+pub mod parallel;
 pub mod prelude;
 mod primitive;
+pub mod sequence;
+pub mod source;
+pub mod typestate;
+pub mod uint;
+pub mod validation;
 
-// Little Endian
+// Little Endian; the headers are read and written by `number::Number` in the generated code.
 pub type Number = u32;
 // Size of Number
 pub const NUMBER_SIZE: usize = size_of::<Number>();
 
 #[inline]
 pub fn unpack_number(slice: &[u8]) -> Number {
-    #[allow(clippy::cast_ptr_alignment)]
-    let le = slice.as_ptr() as *const Number;
-    Number::from_le(unsafe { *le })
+    let mut le = [0u8; NUMBER_SIZE];
+    le.copy_from_slice(&slice[..NUMBER_SIZE]);
+    Number::from_le_bytes(le)
 }
 
 #[inline]
@@ -42,10 +79,83 @@ pub fn pack_number(num: Number) -> [u8; 4] {
 
 #[inline]
 pub fn unpack_number_vec(slice: &[u8]) -> &[[u8; 4]] {
-    #[allow(clippy::cast_ptr_alignment)]
-    unsafe {
-        &*(slice as *const [u8] as *const [[u8; 4]])
+    let len = slice.len() / NUMBER_SIZE;
+    unsafe { core::slice::from_raw_parts(slice.as_ptr() as *const [u8; 4], len) }
+}
+
+/// Unpack the item count of a FixVec from its first `count_size` bytes.
+///
+/// The count size is `NUMBER_SIZE`, unless the FixVec is declared with `@count_size`, which is
+/// not a standard molecule encoding.
+#[inline]
+pub fn unpack_count(slice: &[u8], count_size: usize) -> usize {
+    let mut le = [0u8; NUMBER_SIZE];
+    le[..count_size].copy_from_slice(&slice[..count_size]);
+    Number::from_le_bytes(le) as usize
+}
+
+/// Unpack an unsigned integer in little endian from at most 8 bytes, such as a byte array which is
+/// compared by a constraint of the schema.
+#[inline]
+pub fn unpack_uint(slice: &[u8]) -> u64 {
+    let mut le = [0u8; 8];
+    le[..slice.len()].copy_from_slice(slice);
+    u64::from_le_bytes(le)
+}
+
+/// Pack the item count of a FixVec, or return `None` if it doesn't fit in `count_size` bytes.
+///
+/// Only the first `count_size` bytes of the result are the header.
+#[inline]
+pub fn pack_count(count: usize, count_size: usize) -> Option<[u8; 4]> {
+    let num = Number::try_from(count).ok()?;
+    if count_size < NUMBER_SIZE && num >> (count_size * 8) != 0 {
+        return None;
     }
+    Some(pack_number(num))
+}
+
+/// Compute the total size of a FixVec, or return `None` if it overflows.
+#[inline]
+pub fn fixvec_total_size(count_size: usize, item_size: usize, item_count: usize) -> Option<usize> {
+    item_size.checked_mul(item_count)?.checked_add(count_size)
+}
+
+/// Compute the size of the header of a DynVec or a Table, or return `None` if it overflows.
+#[inline]
+pub fn header_size(count: usize) -> Option<usize> {
+    count.checked_add(1)?.checked_mul(NUMBER_SIZE)
+}
+
+/// Split a verified Table or DynVec into the bytes before the field at the index, and the rest.
+///
+/// The header is in the first part; if the index is the count of fields, the second part is
+/// empty. It panics if the index is larger than the count of fields.
+pub fn split_at_field(slice: &[u8], idx: usize) -> (&[u8], &[u8]) {
+    let total_size = unpack_number(slice) as usize;
+    let count = if total_size == NUMBER_SIZE {
+        0
+    } else {
+        unpack_number(&slice[NUMBER_SIZE..]) as usize / 4 - 1
+    };
+    if idx > count {
+        panic!("split at {} but there are only {} fields", idx, count);
+    }
+    let start = if idx == count {
+        total_size
+    } else {
+        unpack_number(&slice[NUMBER_SIZE * (idx + 1)..]) as usize
+    };
+    slice.split_at(start)
+}
+
+/// Borrow a verified array of bytes as a Rust array.
+///
+/// It panics if the slice isn't `N` bytes, which never happens if the array is verified.
+#[inline]
+pub fn as_array<const N: usize>(slice: &[u8]) -> &[u8; N] {
+    <&[u8; N]>::try_from(slice)
+        .unwrap_or_else(|_| panic!("expect {} bytes, actual {}", N, slice.len()))
 }
 
 pub fn hex_string(input: &[u8]) -> String {
@@ -0,0 +1,109 @@
+//! The conversions of the logical types, which are set by `@logical` on the arrays of bytes.
+//!
+//! A logical type doesn't change the binary format, the generated code only converts the bytes
+//! to and from the typed values, such as the timestamps in `u64` ticks since the Unix epoch.
+
+use core::{fmt, time::Duration};
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The unit of the ticks of a time value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUnit {
+    Seconds,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl TimeUnit {
+    const fn nanos_per_tick(self) -> u128 {
+        match self {
+            TimeUnit::Seconds => 1_000_000_000,
+            TimeUnit::Millis => 1_000_000,
+            TimeUnit::Micros => 1_000,
+            TimeUnit::Nanos => 1,
+        }
+    }
+
+    pub fn to_duration(self, ticks: u64) -> Duration {
+        match self {
+            TimeUnit::Seconds => Duration::from_secs(ticks),
+            TimeUnit::Millis => Duration::from_millis(ticks),
+            TimeUnit::Micros => Duration::from_micros(ticks),
+            TimeUnit::Nanos => Duration::from_nanos(ticks),
+        }
+    }
+
+    /// The ticks of the duration, the part which is less than a tick is truncated; `None` if
+    /// they overflow `u64`.
+    pub fn to_ticks(self, duration: Duration) -> Option<u64> {
+        let ticks = duration.as_nanos() / self.nanos_per_tick();
+        if ticks > u128::from(u64::MAX) {
+            None
+        } else {
+            Some(ticks as u64)
+        }
+    }
+}
+
+/// The timestamps, which are set by `@logical(unix_<unit>)`.
+pub trait UnixTime {
+    fn since_unix_epoch(&self) -> Duration;
+
+    #[cfg(feature = "std")]
+    fn to_system_time(&self) -> SystemTime {
+        UNIX_EPOCH + self.since_unix_epoch()
+    }
+}
+
+/// The construction of the timestamps, which is only for the entities.
+pub trait FromUnixTime: Sized {
+    /// `None` if the ticks of the time overflow.
+    fn from_since_unix_epoch(since: Duration) -> Option<Self>;
+
+    /// `None` if the time is before the Unix epoch, or the ticks of it overflow.
+    #[cfg(feature = "std")]
+    fn from_system_time(time: SystemTime) -> Option<Self> {
+        time.duration_since(UNIX_EPOCH)
+            .ok()
+            .and_then(Self::from_since_unix_epoch)
+    }
+}
+
+/// The UUIDs, which are set by `@logical(uuid)`; the 16 bytes are in the order of the canonical
+/// string, as the binary format of RFC 4122.
+pub trait AsUuid {
+    fn uuid_bytes(&self) -> [u8; 16];
+
+    #[cfg(feature = "uuid")]
+    fn to_uuid(&self) -> uuid::Uuid {
+        uuid::Uuid::from_bytes(self.uuid_bytes())
+    }
+}
+
+/// The construction of the UUIDs, which is only for the entities.
+pub trait FromUuid: Sized {
+    fn from_uuid_bytes(bytes: [u8; 16]) -> Self;
+
+    #[cfg(feature = "uuid")]
+    fn from_uuid(uuid: uuid::Uuid) -> Self {
+        Self::from_uuid_bytes(uuid.into_bytes())
+    }
+}
+
+/// Display the 16 bytes as a canonical UUID string, such as
+/// `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+pub struct UuidDisplay<'a>(pub &'a [u8]);
+
+impl<'a> fmt::Display for UuidDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if i == 4 || i == 6 || i == 8 || i == 10 {
+                write!(f, "-")?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+//! The errors of the generated shims which convert values between two versions of a schema.
+//!
+//! The shims are generated by `moleculec migrate`; they re-encode the values field by field, so
+//! the only failures are the invalid inputs and the union items which the target doesn't have.
+
+use alloc::string::String;
+use core::fmt;
+
+use crate::{error::VerificationError, Number};
+
+/// A value which can't be converted to the target version.
+#[derive(Debug)]
+pub enum MigrationError {
+    /// The input isn't a valid value of the source version.
+    Verification(VerificationError),
+    /// The union in the target version doesn't have the item: the name of the union in the
+    /// source version and the item id.
+    UnmappedItem(String, Number),
+}
+
+impl From<VerificationError> for MigrationError {
+    fn from(err: VerificationError) -> Self {
+        MigrationError::Verification(err)
+    }
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MigrationError::Verification(err) => write!(f, "invalid input: {}", err),
+            MigrationError::UnmappedItem(st, item_id) => write!(
+                f,
+                "{} item (id={}) doesn't exist in the target version",
+                st, item_id
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for MigrationError {}
@@ -0,0 +1,192 @@
+//! The molpack container: records of one molecule type in a file, with the hash of the schema.
+//!
+//! ```text
+//! header:  magic (8 bytes) | schema hash (32 bytes)
+//! records: the encoded records back-to-back
+//! index:   the offset of each record in the container (4 bytes each)
+//! footer:  record count (4 bytes) | index offset (4 bytes) | magic (8 bytes)
+//! ```
+//!
+//! All numbers are little-endian, as molecule numbers are. The index is at the end, so records
+//! are appended by rewriting only the index and the footer.
+
+use alloc::vec::Vec;
+use core::{fmt, result};
+
+use crate::{pack_number, unpack_number, unpack_number_vec, Number, NUMBER_SIZE};
+
+pub const MAGIC: [u8; 8] = *b"MOLPACK\x01";
+pub const SCHEMA_HASH_SIZE: usize = 32;
+pub const HEADER_SIZE: usize = MAGIC.len() + SCHEMA_HASH_SIZE;
+pub const FOOTER_SIZE: usize = NUMBER_SIZE * 2 + MAGIC.len();
+
+pub type SchemaHash = [u8; SCHEMA_HASH_SIZE];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackError {
+    /// The header or the footer doesn't have the magic.
+    Magic,
+    /// The container is too short to have a header and a footer.
+    Truncated,
+    /// The index is out of the container, or the offsets are not in order.
+    IndexIsBroken,
+    /// The container is larger than 4 GiB.
+    SizeOverflow,
+}
+
+pub type Result<T> = result::Result<T, PackError>;
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackError::Magic => write!(f, "the magic of molpack is not found"),
+            PackError::Truncated => write!(f, "the molpack is truncated"),
+            PackError::IndexIsBroken => write!(f, "the index of molpack is broken"),
+            PackError::SizeOverflow => write!(f, "the molpack is larger than 4 GiB"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for PackError {}
+
+/// Read the records of a container, they are not verified.
+#[derive(Debug, Clone, Copy)]
+pub struct PackReader<'r> {
+    data: &'r [u8],
+    index: &'r [[u8; 4]],
+    index_offset: usize,
+}
+
+impl<'r> PackReader<'r> {
+    pub fn new(data: &'r [u8]) -> Result<Self> {
+        if data.len() < HEADER_SIZE + FOOTER_SIZE {
+            return Err(PackError::Truncated);
+        }
+        let footer = &data[data.len() - FOOTER_SIZE..];
+        if data[..MAGIC.len()] != MAGIC || footer[NUMBER_SIZE * 2..] != MAGIC {
+            return Err(PackError::Magic);
+        }
+        let count = unpack_number(footer) as usize;
+        let index_offset = unpack_number(&footer[NUMBER_SIZE..]) as usize;
+        let index_end = count
+            .checked_mul(NUMBER_SIZE)
+            .and_then(|size| size.checked_add(index_offset))
+            .ok_or(PackError::IndexIsBroken)?;
+        if index_offset < HEADER_SIZE || index_end != data.len() - FOOTER_SIZE {
+            return Err(PackError::IndexIsBroken);
+        }
+        let reader = Self {
+            data,
+            index: unpack_number_vec(&data[index_offset..index_end]),
+            index_offset,
+        };
+        // The records start right after the header, and there's nothing between them.
+        if reader.offset_at(0) != HEADER_SIZE
+            || (0..count).any(|idx| reader.offset_at(idx) > reader.offset_at(idx + 1))
+        {
+            return Err(PackError::IndexIsBroken);
+        }
+        Ok(reader)
+    }
+
+    fn offset_at(&self, idx: usize) -> usize {
+        if idx == self.index.len() {
+            self.index_offset
+        } else {
+            unpack_number(&self.index[idx][..]) as usize
+        }
+    }
+
+    pub fn schema_hash(&self) -> SchemaHash {
+        let mut hash = [0u8; SCHEMA_HASH_SIZE];
+        hash.copy_from_slice(&self.data[MAGIC.len()..HEADER_SIZE]);
+        hash
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Get the raw bytes of a record.
+    pub fn get(&self, idx: usize) -> Option<&'r [u8]> {
+        if idx >= self.len() {
+            None
+        } else {
+            Some(&self.data[self.offset_at(idx)..self.offset_at(idx + 1)])
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'r [u8]> + '_ {
+        (0..self.len()).filter_map(move |idx| self.get(idx))
+    }
+}
+
+/// Write records into a container, or append records to an existing one.
+#[derive(Debug, Clone)]
+pub struct PackWriter {
+    data: Vec<u8>,
+    offsets: Vec<usize>,
+}
+
+impl PackWriter {
+    pub fn new(schema_hash: &SchemaHash) -> Self {
+        let mut data = Vec::with_capacity(HEADER_SIZE);
+        data.extend_from_slice(&MAGIC);
+        data.extend_from_slice(schema_hash);
+        Self {
+            data,
+            offsets: Vec::new(),
+        }
+    }
+
+    /// Reopen a container to append more records.
+    pub fn from_pack(mut data: Vec<u8>) -> Result<Self> {
+        let (offsets, index_offset) = {
+            let reader = PackReader::new(&data)?;
+            let offsets = (0..reader.len()).map(|idx| reader.offset_at(idx)).collect();
+            (offsets, reader.index_offset)
+        };
+        data.truncate(index_offset);
+        Ok(Self { data, offsets })
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    pub fn append(&mut self, record: &[u8]) -> Result<()> {
+        let index_size = (self.offsets.len() + 1) * NUMBER_SIZE;
+        let total_size = self.data.len() + record.len() + index_size + FOOTER_SIZE;
+        if total_size > Number::MAX as usize {
+            return Err(PackError::SizeOverflow);
+        }
+        self.offsets.push(self.data.len());
+        self.data.extend_from_slice(record);
+        Ok(())
+    }
+
+    /// Write the index and the footer, and return the whole container.
+    pub fn finish(mut self) -> Vec<u8> {
+        let index_offset = self.data.len();
+        self.data
+            .reserve(self.offsets.len() * NUMBER_SIZE + FOOTER_SIZE);
+        for offset in &self.offsets {
+            self.data.extend_from_slice(&pack_number(*offset as Number));
+        }
+        self.data
+            .extend_from_slice(&pack_number(self.offsets.len() as Number));
+        self.data
+            .extend_from_slice(&pack_number(index_offset as Number));
+        self.data.extend_from_slice(&MAGIC);
+        self.data
+    }
+}
@@ -0,0 +1,120 @@
+//! The numbers in the headers, such as the total sizes, the offsets and the item IDs.
+//!
+//! A number is 4 bytes in little endian. [`Number`] wraps the value, so the conversions between
+//! the bytes, the value and the sizes in memory are explicit and checked; the generated Rust code
+//! reads and writes the headers with it. The alias [`crate::Number`] is still the value type of the
+//! item IDs.
+
+use core::{convert::TryFrom, fmt};
+
+use crate::NUMBER_SIZE;
+
+/// A number in a header.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Number(u32);
+
+impl Number {
+    /// The size of a number, as a number, which is the total size of an empty dynvec or table.
+    pub const SIZE: Self = Number(NUMBER_SIZE as u32);
+    /// The largest number, which is also the largest total size.
+    pub const MAX: Self = Number(u32::MAX);
+
+    #[inline]
+    pub const fn new(value: u32) -> Self {
+        Number(value)
+    }
+
+    #[inline]
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    /// Read the number from the first 4 bytes of the slice.
+    ///
+    /// It panics if the slice is shorter than 4 bytes.
+    #[inline]
+    pub fn from_le_slice(slice: &[u8]) -> Self {
+        let mut le = [0u8; NUMBER_SIZE];
+        le.copy_from_slice(&slice[..NUMBER_SIZE]);
+        Self::from_le_bytes(le)
+    }
+
+    #[inline]
+    pub const fn from_le_bytes(bytes: [u8; NUMBER_SIZE]) -> Self {
+        Number(u32::from_le_bytes(bytes))
+    }
+
+    #[inline]
+    pub const fn to_le_bytes(self) -> [u8; NUMBER_SIZE] {
+        self.0.to_le_bytes()
+    }
+
+    /// Convert a size or an offset in memory, or return `None` if it doesn't fit in 4 bytes.
+    #[inline]
+    pub fn from_usize(value: usize) -> Option<Self> {
+        u32::try_from(value).ok().map(Number)
+    }
+
+    /// Convert the number to a size or an offset in memory.
+    ///
+    /// `usize` has at least 32 bits on the targets of molecule, so it never truncates.
+    #[inline]
+    pub const fn to_usize(self) -> usize {
+        self.0 as usize
+    }
+
+    #[inline]
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Number)
+    }
+
+    #[inline]
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        self.0.checked_mul(other.0).map(Number)
+    }
+}
+
+/// The error of converting a size which doesn't fit in a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberOverflow(pub usize);
+
+impl fmt::Display for NumberOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} doesn't fit in a molecule number", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for NumberOverflow {}
+
+impl TryFrom<usize> for Number {
+    type Error = NumberOverflow;
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Self::from_usize(value).ok_or(NumberOverflow(value))
+    }
+}
+
+impl From<u32> for Number {
+    fn from(value: u32) -> Self {
+        Number(value)
+    }
+}
+
+impl From<Number> for u32 {
+    fn from(number: Number) -> Self {
+        number.0
+    }
+}
+
+impl From<Number> for usize {
+    fn from(number: Number) -> Self {
+        number.to_usize()
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
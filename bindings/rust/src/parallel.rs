@@ -0,0 +1,135 @@
+//! Parallel iterators over the items of vectors, by [rayon].
+//!
+//! The items are located by their indexes, so a vector is split into ranges of indexes for the
+//! threads without reading any item.
+//!
+//! [rayon]: https://docs.rs/rayon
+
+pub use rayon;
+
+use core::ops::Range;
+
+use rayon::iter::{
+    plumbing::{bridge, Consumer, Producer, ProducerCallback, UnindexedConsumer},
+    IndexedParallelIterator, ParallelIterator,
+};
+
+/// A parallel iterator over the items of a vector.
+#[derive(Debug, Clone)]
+pub struct ParIter<V, T> {
+    items: Items<V, T>,
+}
+
+impl<V, T> ParIter<V, T> {
+    /// The items are `get(&vector, idx)` for the indexes in `0..len`.
+    pub fn new(vector: V, len: usize, get: fn(&V, usize) -> T) -> Self {
+        Self {
+            items: Items {
+                vector,
+                range: 0..len,
+                get,
+            },
+        }
+    }
+}
+
+impl<V, T> ParallelIterator for ParIter<V, T>
+where
+    V: Copy + Send + Sync,
+    T: Send,
+{
+    type Item = T;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+impl<V, T> IndexedParallelIterator for ParIter<V, T>
+where
+    V: Copy + Send + Sync,
+    T: Send,
+{
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn drive<C>(self, consumer: C) -> C::Result
+    where
+        C: Consumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB>(self, callback: CB) -> CB::Output
+    where
+        CB: ProducerCallback<Self::Item>,
+    {
+        callback.callback(self.items)
+    }
+}
+
+/// The items in a range of indexes, which is a part of a parallel iterator for a thread.
+#[derive(Debug, Clone)]
+pub struct Items<V, T> {
+    vector: V,
+    range: Range<usize>,
+    get: fn(&V, usize) -> T,
+}
+
+impl<V, T> Iterator for Items<V, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(|idx| (self.get)(&self.vector, idx))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<V, T> DoubleEndedIterator for Items<V, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range
+            .next_back()
+            .map(|idx| (self.get)(&self.vector, idx))
+    }
+}
+
+impl<V, T> ExactSizeIterator for Items<V, T> {}
+
+impl<V, T> Producer for Items<V, T>
+where
+    V: Copy + Send + Sync,
+    T: Send,
+{
+    type Item = T;
+    type IntoIter = Self;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let mid = self.range.start + index;
+        let left = Self {
+            vector: self.vector,
+            range: self.range.start..mid,
+            get: self.get,
+        };
+        let right = Self {
+            vector: self.vector,
+            range: mid..self.range.end,
+            get: self.get,
+        };
+        (left, right)
+    }
+}
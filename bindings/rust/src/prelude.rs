@@ -1,7 +1,15 @@
-pub use alloc::{borrow::ToOwned, vec, vec::Vec};
+pub use alloc::{
+    borrow::{Cow, ToOwned},
+    vec,
+    vec::Vec,
+};
 use core::{clone::Clone, default::Default, fmt};
 
-use crate::{bytes::Bytes, error::VerificationResult, io};
+use crate::{
+    bytes::Bytes,
+    error::{self, VerificationResult},
+    io,
+};
 
 pub use crate::primitive::{Byte, ByteReader};
 
@@ -14,22 +22,52 @@ pub trait Entity: fmt::Debug + Default + Clone {
     fn from_slice(slice: &[u8]) -> VerificationResult<Self>;
     fn from_compatible_slice(slice: &[u8]) -> VerificationResult<Self>;
     fn new_builder() -> Self::Builder;
+    #[allow(clippy::wrong_self_convention)]
     fn as_builder(self) -> Self::Builder;
 }
 
 pub trait Reader<'r>: Sized + fmt::Debug + Clone + Copy {
     type Entity: Entity;
     const NAME: &'static str;
+    /// Whether the bytes start with a header of offsets, which only the ones of a DynVec or a
+    /// Table do.
+    const HAS_OFFSETS: bool = false;
+    /// Verify the slice without any temporary buffer, it never allocates if the slice is valid.
     fn verify(slice: &[u8], compatible: bool) -> VerificationResult<()>;
     fn new_unchecked(slice: &'r [u8]) -> Self;
     fn as_slice(&self) -> &'r [u8];
     fn from_slice(slice: &'r [u8]) -> VerificationResult<Self> {
-        Self::verify(slice, false).map(|_| Self::new_unchecked(slice))
+        Self::verify(slice, false)
+            .map(|_| Self::new_unchecked(slice))
+            .map_err(|err| error::traced(<Self::Entity as Entity>::NAME, slice.len(), false, err))
     }
     fn from_compatible_slice(slice: &'r [u8]) -> VerificationResult<Self> {
-        Self::verify(slice, true).map(|_| Self::new_unchecked(slice))
+        Self::verify(slice, true)
+            .map(|_| Self::new_unchecked(slice))
+            .map_err(|err| error::traced(<Self::Entity as Entity>::NAME, slice.len(), true, err))
     }
     fn to_entity(&self) -> Self::Entity;
+    /// Read the size of the value at the start of the slice from its header, the value is not
+    /// verified. An option is `None` only if the slice is empty.
+    ///
+    /// The generated readers read the headers. By default, the whole slice is the value if it's
+    /// verified, so a value with trailing bytes is not read by the prefix methods.
+    fn peek_size(slice: &[u8]) -> VerificationResult<usize> {
+        Self::verify(slice, true).map(|_| slice.len())
+    }
+    /// Verify the value at the start of the slice and return it with the count of the bytes it
+    /// takes, the trailing bytes are ignored.
+    fn from_slice_prefix(slice: &'r [u8]) -> VerificationResult<(Self, usize)> {
+        let size = Self::peek_size(slice).map_err(|err| {
+            error::traced(<Self::Entity as Entity>::NAME, slice.len(), false, err)
+        })?;
+        Self::from_slice(&slice[..size]).map(|reader| (reader, size))
+    }
+    fn from_compatible_slice_prefix(slice: &'r [u8]) -> VerificationResult<(Self, usize)> {
+        let size = Self::peek_size(slice)
+            .map_err(|err| error::traced(<Self::Entity as Entity>::NAME, slice.len(), true, err))?;
+        Self::from_compatible_slice(&slice[..size]).map(|reader| (reader, size))
+    }
 }
 
 pub trait Builder: Default {
@@ -38,4 +76,86 @@ pub trait Builder: Default {
     fn expected_length(&self) -> usize;
     fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<()>;
     fn build(&self) -> Self::Entity;
+    /// Build the value in the buffer of the context; see [`BuildContext`].
+    fn build_with(&self, context: &mut BuildContext) -> Self::Entity {
+        let data = context.build(self.expected_length(), |writer| {
+            self.write(writer)
+                .unwrap_or_else(|_| panic!("{} build should be ok", Self::NAME))
+        });
+        <Self::Entity as Entity>::new_unchecked(data)
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "std")] {
+        /// A buffer which is shared by the values built in it, so building many small values
+        /// allocates a buffer once in a while, instead of once for each of them.
+        ///
+        /// The values are the parts of the buffer which they're written in, without any copy. A
+        /// part is reused after all values in it are dropped, so a value which is kept for long
+        /// keeps its part of the buffer as well.
+        #[derive(Debug, Default)]
+        pub struct BuildContext {
+            buffer: bytes::BytesMut,
+        }
+
+        impl BuildContext {
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            pub fn with_capacity(capacity: usize) -> Self {
+                Self {
+                    buffer: bytes::BytesMut::with_capacity(capacity),
+                }
+            }
+
+            fn build(&mut self, size: usize, write: impl FnOnce(&mut Writer)) -> Bytes {
+                self.buffer.reserve(size);
+                write(&mut Writer(&mut self.buffer));
+                self.buffer.take().freeze()
+            }
+        }
+
+        struct Writer<'a>(&'a mut bytes::BytesMut);
+
+        impl io::Write for Writer<'_> {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+    } else {
+        /// A scratch buffer which is reused across builds.
+        ///
+        /// Without `std`, the bytes of each value are still copied out into a buffer of its own,
+        /// so it saves nothing over `build`; it's for the code which is shared with `std`.
+        #[derive(Debug, Default)]
+        pub struct BuildContext {
+            buffer: Vec<u8>,
+        }
+
+        impl BuildContext {
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            pub fn with_capacity(capacity: usize) -> Self {
+                Self {
+                    buffer: Vec::with_capacity(capacity),
+                }
+            }
+
+            fn build(&mut self, size: usize, write: impl FnOnce(&mut Vec<u8>)) -> Bytes {
+                self.buffer.clear();
+                self.buffer.reserve(size);
+                write(&mut self.buffer);
+                Bytes::from(&self.buffer[..])
+            }
+        }
+    }
 }
@@ -116,6 +116,25 @@ impl<'r> ByteReader<'r> {
     pub fn from_compatible_slice(slice: &'r [u8]) -> VerificationResult<Self> {
         Self::verify(slice, true).map(|_| Self::new_unchecked(slice))
     }
+
+    #[inline]
+    pub fn peek_size(slice: &[u8]) -> VerificationResult<usize> {
+        let slice_len = slice.len();
+        if slice_len < 1 {
+            return verification_error!(Self, TotalSizeNotMatch, 1, slice_len);
+        }
+        Ok(1)
+    }
+
+    #[inline]
+    pub fn from_slice_prefix(slice: &'r [u8]) -> VerificationResult<(Self, usize)> {
+        Self::peek_size(slice).map(|size| (Self::new_unchecked(&slice[..size]), size))
+    }
+
+    #[inline]
+    pub fn from_compatible_slice_prefix(slice: &'r [u8]) -> VerificationResult<(Self, usize)> {
+        Self::from_slice_prefix(slice)
+    }
 }
 
 impl Byte {
@@ -0,0 +1,153 @@
+//! Read values which are stored back-to-back in one buffer.
+//!
+//! Molecule values describe their own sizes, so a log of records is just the records one after
+//! another, without any framing. The reader iterates over them, and the recovery decides what to
+//! do after a broken record.
+
+use core::{fmt, marker::PhantomData};
+
+use crate::{error::VerificationError, prelude::Reader};
+
+/// What to do after a value fails the verification.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+    /// Stop at the broken value.
+    #[default]
+    Stop,
+    /// Skip the broken value if its header still has its size, otherwise stop.
+    SkipValue,
+    /// Skip bytes one by one until a valid value is found.
+    Resync,
+}
+
+/// A broken value in a sequence.
+#[derive(Debug)]
+pub struct SequenceError {
+    /// The offset of the broken value in the buffer.
+    pub offset: usize,
+    /// The count of the bytes which are skipped to recover; it's zero if the reader stops.
+    pub skipped: usize,
+    pub error: VerificationError,
+}
+
+impl fmt::Display for SequenceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "broken value at {} ({} bytes skipped): {}",
+            self.offset, self.skipped, self.error
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for SequenceError {}
+
+/// Iterate over the values of a type which are stored back-to-back.
+#[derive(Debug, Clone)]
+pub struct SequenceReader<'r, T> {
+    slice: &'r [u8],
+    position: usize,
+    compatible: bool,
+    recovery: Recovery,
+    finished: bool,
+    _reader: PhantomData<T>,
+}
+
+impl<'r, T: Reader<'r>> SequenceReader<'r, T> {
+    pub fn new(slice: &'r [u8]) -> Self {
+        Self {
+            slice,
+            position: 0,
+            compatible: false,
+            recovery: Recovery::default(),
+            finished: false,
+            _reader: PhantomData,
+        }
+    }
+
+    /// Verify the values as `from_compatible_slice` does.
+    pub fn compatible(mut self, enable: bool) -> Self {
+        self.compatible = enable;
+        self
+    }
+
+    pub fn recovery(mut self, recovery: Recovery) -> Self {
+        self.recovery = recovery;
+        self
+    }
+
+    /// The offset of the next value.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The bytes which are not read yet.
+    pub fn remaining(&self) -> &'r [u8] {
+        &self.slice[self.position..]
+    }
+
+    fn read_at(&self, position: usize) -> Result<(T, usize), VerificationError> {
+        let slice = &self.slice[position..];
+        if self.compatible {
+            T::from_compatible_slice_prefix(slice)
+        } else {
+            T::from_slice_prefix(slice)
+        }
+    }
+
+    /// Find the next offset where a value could be read, after the broken one.
+    fn recover(&self) -> Option<usize> {
+        match self.recovery {
+            Recovery::Stop => None,
+            Recovery::SkipValue => {
+                // The size is unknown if the header is broken or truncated.
+                T::peek_size(self.remaining())
+                    .ok()
+                    .filter(|size| *size > 0)
+                    .map(|size| self.position + size)
+            }
+            Recovery::Resync => {
+                let mut position = self.position + 1;
+                while position < self.slice.len() {
+                    if self.read_at(position).is_ok() {
+                        break;
+                    }
+                    position += 1;
+                }
+                Some(position)
+            }
+        }
+    }
+}
+
+impl<'r, T: Reader<'r>> Iterator for SequenceReader<'r, T> {
+    type Item = Result<T, SequenceError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished || self.position >= self.slice.len() {
+            return None;
+        }
+        match self.read_at(self.position) {
+            Ok((reader, size)) => {
+                self.position += size;
+                Some(Ok(reader))
+            }
+            Err(error) => {
+                let offset = self.position;
+                let skipped = if let Some(position) = self.recover() {
+                    self.position = position;
+                    position - offset
+                } else {
+                    self.finished = true;
+                    0
+                };
+                Some(Err(SequenceError {
+                    offset,
+                    skipped,
+                    error,
+                }))
+            }
+        }
+    }
+}
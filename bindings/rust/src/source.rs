@@ -0,0 +1,336 @@
+//! Random-access byte sources, which the values are decoded from without being loaded entirely.
+//!
+//! A [`Source`] could be a slice, a memory map, a file by [`ReadSeekSource`] or a paged cache.
+//! A [`SourceView`] is a range of a source; it only reads the headers to locate the parts of a
+//! value, and reads and verifies a part when it's decoded.
+//!
+//! The generated `SourceReader`s are typed wrappers of views, which are generated by the option
+//! `source_readers` of the compiler.
+
+use alloc::{borrow::Cow, borrow::ToOwned as _};
+use core::fmt;
+
+use crate::{
+    error::{VerificationError, VerificationResult},
+    io, unpack_count, unpack_number, Number, NUMBER_SIZE,
+};
+
+/// A random-access source of bytes.
+pub trait Source {
+    /// The count of the bytes in the source.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read the bytes in `start..end`, which is checked by `read`.
+    ///
+    /// Borrow the bytes if they're in memory, so they're not copied.
+    fn fetch(&self, start: usize, end: usize) -> io::Result<Cow<'_, [u8]>>;
+
+    /// Read the bytes in `start..end`, or return an error if the range is out of the source.
+    fn read(&self, start: usize, end: usize) -> SourceResult<Cow<'_, [u8]>> {
+        if start > end || end > self.len() {
+            return Err(SourceError::OutOfBounds(start, end, self.len()));
+        }
+        self.fetch(start, end).map_err(SourceError::Io)
+    }
+}
+
+impl Source for [u8] {
+    fn len(&self) -> usize {
+        <[u8]>::len(self)
+    }
+
+    fn fetch(&self, start: usize, end: usize) -> io::Result<Cow<'_, [u8]>> {
+        Ok(Cow::Borrowed(&self[start..end]))
+    }
+}
+
+impl Source for alloc::vec::Vec<u8> {
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn fetch(&self, start: usize, end: usize) -> io::Result<Cow<'_, [u8]>> {
+        Ok(Cow::Borrowed(&self[start..end]))
+    }
+}
+
+impl Source for crate::bytes::Bytes {
+    fn len(&self) -> usize {
+        self.as_ref().len()
+    }
+
+    fn fetch(&self, start: usize, end: usize) -> io::Result<Cow<'_, [u8]>> {
+        Ok(Cow::Borrowed(&self.as_ref()[start..end]))
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "std")] {
+        use alloc::vec;
+        use core::cell::RefCell;
+        use std::io::{Read, Seek, SeekFrom};
+
+        /// A source over a reader which could seek, such as a file; each read copies the bytes.
+        #[derive(Debug)]
+        pub struct ReadSeekSource<R> {
+            inner: RefCell<R>,
+            len: usize,
+        }
+
+        impl<R: Read + Seek> ReadSeekSource<R> {
+            /// The length of the source is the position of the end of the reader.
+            pub fn new(mut inner: R) -> io::Result<Self> {
+                let len = inner.seek(SeekFrom::End(0))? as usize;
+                Ok(Self {
+                    inner: RefCell::new(inner),
+                    len,
+                })
+            }
+
+            pub fn into_inner(self) -> R {
+                self.inner.into_inner()
+            }
+        }
+
+        impl<R: Read + Seek> Source for ReadSeekSource<R> {
+            fn len(&self) -> usize {
+                self.len
+            }
+
+            fn fetch(&self, start: usize, end: usize) -> io::Result<Cow<'_, [u8]>> {
+                let mut inner = self.inner.borrow_mut();
+                inner.seek(SeekFrom::Start(start as u64))?;
+                let mut data = vec![0; end - start];
+                inner.read_exact(&mut data)?;
+                Ok(Cow::Owned(data))
+            }
+        }
+    }
+}
+
+/// The errors of reading a value from a source.
+#[derive(Debug)]
+pub enum SourceError {
+    /// The range is out of the source: the start, the end and the length of the source.
+    OutOfBounds(usize, usize, usize),
+    /// The source failed to read.
+    Io(io::Error),
+    /// The headers or the value are not valid.
+    Verification(VerificationError),
+}
+
+pub type SourceResult<T> = Result<T, SourceError>;
+
+impl From<VerificationError> for SourceError {
+    fn from(err: VerificationError) -> Self {
+        SourceError::Verification(err)
+    }
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SourceError::OutOfBounds(start, end, len) => write!(
+                f,
+                "the range {}..{} is out of the source, which has {} bytes",
+                start, end, len
+            ),
+            SourceError::Io(err) => write!(f, "failed to read the source: {}", err),
+            SourceError::Verification(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for SourceError {}
+
+/// A range of a source, which holds a value.
+///
+/// The methods which locate the parts of a value take the name of the type for the errors; they
+/// only check the headers they read.
+#[derive(Debug)]
+pub struct SourceView<'s, S: ?Sized> {
+    source: &'s S,
+    start: usize,
+    end: usize,
+}
+
+impl<'s, S: ?Sized> Clone for SourceView<'s, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'s, S: ?Sized> Copy for SourceView<'s, S> {}
+
+fn header_error(name: &str, expected: usize, actual: usize) -> SourceError {
+    VerificationError::HeaderIsBroken(name.to_owned(), expected, actual).into()
+}
+
+impl<'s, S: Source + ?Sized> SourceView<'s, S> {
+    /// The whole source.
+    pub fn new(source: &'s S) -> Self {
+        Self {
+            source,
+            start: 0,
+            end: source.len(),
+        }
+    }
+
+    pub fn source(&self) -> &'s S {
+        self.source
+    }
+
+    /// The position of the view in the source.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Read all bytes of the view.
+    pub fn read(&self) -> SourceResult<Cow<'s, [u8]>> {
+        self.source.read(self.start, self.end)
+    }
+
+    /// A part of the view, which is relative to the start of it.
+    pub fn slice(&self, start: usize, end: usize) -> SourceResult<Self> {
+        if start > end || end > self.len() {
+            return Err(SourceError::OutOfBounds(
+                self.start + start,
+                self.start + end,
+                self.source.len(),
+            ));
+        }
+        Ok(Self {
+            source: self.source,
+            start: self.start + start,
+            end: self.start + end,
+        })
+    }
+
+    /// Read a number at the offset of the view.
+    pub fn number_at(&self, name: &str, offset: usize) -> SourceResult<Number> {
+        if offset + NUMBER_SIZE > self.len() {
+            return Err(header_error(name, offset + NUMBER_SIZE, self.len()));
+        }
+        let data = self
+            .source
+            .read(self.start + offset, self.start + offset + NUMBER_SIZE)?;
+        Ok(unpack_number(&data))
+    }
+
+    /// The count of the items of a vector of fixed-size items, which should match the size.
+    pub fn fixed_count(
+        &self,
+        name: &str,
+        count_size: usize,
+        item_size: usize,
+    ) -> SourceResult<usize> {
+        if count_size > self.len() {
+            return Err(header_error(name, count_size, self.len()));
+        }
+        let data = self.source.read(self.start, self.start + count_size)?;
+        let count = unpack_count(&data, count_size);
+        let expected = count
+            .checked_mul(item_size)
+            .and_then(|size| size.checked_add(count_size))
+            .ok_or_else(|| VerificationError::SizeOverflow(name.to_owned()))?;
+        if expected != self.len() {
+            let err = VerificationError::TotalSizeNotMatch(name.to_owned(), expected, self.len());
+            return Err(err.into());
+        }
+        Ok(count)
+    }
+
+    /// An item of a vector of fixed-size items, or `None` if `idx` is not less than the count.
+    pub fn fixed_item(
+        &self,
+        name: &str,
+        count_size: usize,
+        item_size: usize,
+        idx: usize,
+    ) -> SourceResult<Option<Self>> {
+        let count = self.fixed_count(name, count_size, item_size)?;
+        if idx >= count {
+            return Ok(None);
+        }
+        let start = count_size + item_size * idx;
+        self.slice(start, start + item_size).map(Some)
+    }
+
+    /// The count of the items of a vector of dynamic-size items, or the fields of a table.
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn offset_count(&self, name: &str) -> SourceResult<usize> {
+        let total_size = self.number_at(name, 0)? as usize;
+        if total_size != self.len() {
+            let err = VerificationError::TotalSizeNotMatch(name.to_owned(), total_size, self.len());
+            return Err(err.into());
+        }
+        if self.len() == NUMBER_SIZE {
+            return Ok(0);
+        }
+        let first_offset = self.number_at(name, NUMBER_SIZE)? as usize;
+        if first_offset % NUMBER_SIZE != 0 || first_offset < NUMBER_SIZE * 2 {
+            return Err(VerificationError::OffsetsNotMatch(name.to_owned()).into());
+        }
+        if first_offset > self.len() {
+            return Err(header_error(name, first_offset, self.len()));
+        }
+        Ok(first_offset / NUMBER_SIZE - 1)
+    }
+
+    /// An item of a vector of dynamic-size items, or a field of a table, or `None` if `idx` is
+    /// not less than the count.
+    pub fn offset_item(&self, name: &str, idx: usize) -> SourceResult<Option<Self>> {
+        let count = self.offset_count(name)?;
+        if idx >= count {
+            return Ok(None);
+        }
+        let start = self.number_at(name, NUMBER_SIZE * (idx + 1))? as usize;
+        let end = if idx + 1 == count {
+            self.len()
+        } else {
+            self.number_at(name, NUMBER_SIZE * (idx + 2))? as usize
+        };
+        if start > end || end > self.len() || start < NUMBER_SIZE * (count + 1) {
+            return Err(VerificationError::OffsetsNotMatch(name.to_owned()).into());
+        }
+        self.slice(start, end).map(Some)
+    }
+
+    /// A field of a table which has `field_count` fields; the extra fields are allowed.
+    pub fn field(&self, name: &str, field_count: usize, idx: usize) -> SourceResult<Self> {
+        self.offset_item(name, idx)?.ok_or_else(|| {
+            // Only the count is wrong if the field doesn't exist.
+            let actual = self.offset_count(name).unwrap_or(0);
+            VerificationError::FieldCountNotMatch(name.to_owned(), field_count, actual).into()
+        })
+    }
+
+    /// The item id and the item of a union.
+    pub fn union_item(&self, name: &str) -> SourceResult<(Number, Self)> {
+        let item_id = self.number_at(name, 0)?;
+        self.slice(NUMBER_SIZE, self.len())
+            .map(|item| (item_id, item))
+    }
+
+    /// Verify the bytes of the view, and pass them to the function.
+    pub fn with_slice<T, F>(&self, verify: F) -> SourceResult<T>
+    where
+        F: FnOnce(&[u8]) -> VerificationResult<T>,
+    {
+        let data = self.read()?;
+        verify(&data).map_err(SourceError::Verification)
+    }
+}
@@ -0,0 +1,13 @@
+//! The markers of the typestate builders, which are generated for the tables by the codegen
+//! option `typestate_builders`.
+//!
+//! A typestate builder has a marker for each field, so `build` is only available after all
+//! fields are set, and forgetting a field is a compile error instead of a default value.
+
+/// The field is set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Set;
+
+/// The field isn't set yet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Unset;
@@ -0,0 +1,48 @@
+use alloc::vec::Vec;
+use core::convert::TryInto;
+
+/// Conversions between unsigned integers and byte arrays.
+///
+/// The generated code uses it for the arrays of bytes which have well-known names, such as
+/// `Uint64` for `array Uint64 [byte; 8]`.
+pub trait Uint: Sized + Copy {
+    const SIZE: usize;
+
+    fn from_le_slice(slice: &[u8]) -> Self;
+    fn from_be_slice(slice: &[u8]) -> Self;
+    fn to_le_vec(self) -> Vec<u8>;
+    fn to_be_vec(self) -> Vec<u8>;
+}
+
+macro_rules! impl_uint {
+    ($type:ident) => {
+        impl Uint for $type {
+            const SIZE: usize = core::mem::size_of::<$type>();
+
+            #[inline]
+            fn from_le_slice(slice: &[u8]) -> Self {
+                $type::from_le_bytes(slice.try_into().unwrap())
+            }
+
+            #[inline]
+            fn from_be_slice(slice: &[u8]) -> Self {
+                $type::from_be_bytes(slice.try_into().unwrap())
+            }
+
+            #[inline]
+            fn to_le_vec(self) -> Vec<u8> {
+                self.to_le_bytes().to_vec()
+            }
+
+            #[inline]
+            fn to_be_vec(self) -> Vec<u8> {
+                self.to_be_bytes().to_vec()
+            }
+        }
+    };
+}
+
+impl_uint!(u16);
+impl_uint!(u32);
+impl_uint!(u64);
+impl_uint!(u128);
@@ -0,0 +1,62 @@
+//! The validators of the invariants which are declared in the schema by `@validate(name)`.
+//!
+//! The generated `verify` of a type with an invariant checks the structure first, then calls the
+//! validator which is registered under the name, so the semantic checks run wherever the data is
+//! verified, including as a field of another type. A type whose invariant has no registered
+//! validator is rejected, so the checks can't be skipped by forgetting to register them.
+
+use alloc::borrow::ToOwned;
+use core::{
+    ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use crate::error::{VerificationError, VerificationResult};
+
+/// A validator is given the bytes of a value which has passed the structural verification, so
+/// it could read them with `new_unchecked` of the reader; it returns whether the invariant holds.
+pub type Validator = fn(&[u8]) -> bool;
+
+/// The table of the validators, by the names of the invariants.
+#[derive(Debug)]
+pub struct Validators(pub &'static [(&'static str, Validator)]);
+
+impl Validators {
+    pub fn get(&self, invariant: &str) -> Option<Validator> {
+        self.0
+            .iter()
+            .find(|(name, _)| *name == invariant)
+            .map(|(_, validator)| *validator)
+    }
+}
+
+static VALIDATORS: AtomicPtr<Validators> = AtomicPtr::new(ptr::null_mut());
+
+/// Set the validators for the whole process, or remove them by `None`.
+pub fn set_validators(validators: Option<&'static Validators>) {
+    let ptr = validators.map_or(ptr::null_mut(), |validators| {
+        validators as *const Validators as *mut Validators
+    });
+    VALIDATORS.store(ptr, Ordering::Release);
+}
+
+#[doc(hidden)]
+pub fn validate(
+    type_name: &'static str,
+    invariant: &'static str,
+    slice: &[u8],
+) -> VerificationResult<()> {
+    let ptr = VALIDATORS.load(Ordering::Acquire);
+    let validator = unsafe { ptr.as_ref() }.and_then(|validators| validators.get(invariant));
+    match validator {
+        Some(validator) if validator(slice) => Ok(()),
+        Some(_) => Err(VerificationError::InvariantNotHeld(
+            type_name.to_owned(),
+            invariant.to_owned(),
+        )),
+        None => Err(VerificationError::InvariantNotRegistered(
+            type_name.to_owned(),
+            invariant.to_owned(),
+        )),
+    }
+}
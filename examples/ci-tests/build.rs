@@ -1,20 +1,400 @@
-use codegen::{Compiler, Language};
+use std::{env, fs, path::PathBuf, process::Command};
+
+use codegen::{CProfile, Compiler, Edition, Examples, Language, Migration, Visibility};
 
 fn compile_schema(schema: &str) {
+    let json_schema = env::var("CARGO_FEATURE_JSON_SCHEMA").is_ok();
+    let arrow = env::var("CARGO_FEATURE_ARROW").is_ok();
+    let rayon = env::var("CARGO_FEATURE_RAYON").is_ok();
+    let python = env::var("CARGO_FEATURE_PYTHON").is_ok();
     let mut compiler = Compiler::new();
     compiler
         .language(Language::Rust)
         .default_out_dir()
         .file_path(schema)
+        .json_schema(json_schema)
+        .arrow(arrow)
+        .rayon(rayon)
+        .transparent(true)
+        .source_readers(true)
+        .linear_verification(true)
+        .byte_comparisons(true)
+        .builder_validation(true)
+        .checked_getters(true)
+        .field_access(true)
+        .max_encoded_size(true)
+        .pyo3(python)
         .run();
     compiler
         .language(Language::C)
         .default_out_dir()
         .file_path(schema)
         .run();
+    compiler
+        .language(Language::OpenApi)
+        .default_out_dir()
+        .file_path(schema)
+        .run();
+    compiler
+        .language(Language::Sql)
+        .default_out_dir()
+        .file_path(schema)
+        .run();
+    compiler
+        .language(Language::Elixir)
+        .default_out_dir()
+        .file_path(schema)
+        .run();
+    compiler
+        .language(Language::Solidity)
+        .default_out_dir()
+        .file_path(schema)
+        .run();
+    compiler
+        .language(Language::Zig)
+        .default_out_dir()
+        .file_path(schema)
+        .run();
+    compiler
+        .language(Language::Lua)
+        .default_out_dir()
+        .file_path(schema)
+        .run();
+    compiler
+        .language(Language::OCaml)
+        .default_out_dir()
+        .file_path(schema)
+        .run();
+    compiler
+        .language(Language::Haskell)
+        .default_out_dir()
+        .file_path(schema)
+        .run();
+    compiler
+        .language(Language::Scala)
+        .default_out_dir()
+        .file_path(schema)
+        .run();
+    compiler
+        .language(Language::Tla)
+        .default_out_dir()
+        .file_path(schema)
+        .run();
+    compiler
+        .language(Language::Kaitai)
+        .default_out_dir()
+        .file_path(schema)
+        .run();
+    compiler
+        .language(Language::Wireshark)
+        .default_out_dir()
+        .file_path(schema)
+        .dissector_port(7891)
+        .dissector_heuristic(true)
+        .run();
+    compiler
+        .language(Language::ImHex)
+        .default_out_dir()
+        .file_path(schema)
+        .run();
     println!("cargo:rerun-if-changed={}", schema);
 }
 
+// The common types are generated once, and the schemas which import them use them from the
+// module of the common types, as if it's in another crate.
+fn compile_linked_schemas() {
+    let json_schema = env::var("CARGO_FEATURE_JSON_SCHEMA").is_ok();
+    let arrow = env::var("CARGO_FEATURE_ARROW").is_ok();
+    for schema in &["schemas/linking/common.mol", "schemas/linking/app.mol"] {
+        Compiler::new()
+            .language(Language::Rust)
+            .default_out_dir()
+            .file_path(schema)
+            .json_schema(json_schema)
+            .arrow(arrow)
+            .extern_schema("common", "crate::common")
+            .run();
+        println!("cargo:rerun-if-changed={}", schema);
+    }
+}
+
+// The same schemas, but the generated types are not public.
+fn compile_restricted_schemas() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap()).join("restricted");
+    fs::create_dir_all(&out_dir).unwrap();
+    Compiler::new()
+        .language(Language::Rust)
+        .out_dir(&out_dir)
+        .file_path("schemas/ci_tests.mol")
+        .visibility(Visibility::Crate)
+        .run();
+    Compiler::new()
+        .language(Language::Rust)
+        .out_dir(&out_dir)
+        .file_path("schemas/numbers.mol")
+        .visibility(Visibility::Restricted("crate::restricted".to_owned()))
+        .run();
+}
+
+// The same schema, but the items of the arrays are only accessed by the index.
+fn compile_compact_schemas() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap()).join("compact");
+    fs::create_dir_all(&out_dir).unwrap();
+    Compiler::new()
+        .language(Language::Rust)
+        .out_dir(&out_dir)
+        .file_path("schemas/ci_tests.mol")
+        .compact_arrays(true)
+        .run();
+}
+
+// The same schema, but the tables also have the typestate builders.
+fn compile_typestate_schemas() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap()).join("typestate");
+    fs::create_dir_all(&out_dir).unwrap();
+    Compiler::new()
+        .language(Language::Rust)
+        .out_dir(&out_dir)
+        .file_path("schemas/constraints.mol")
+        .typestate_builders(true)
+        .builder_validation(true)
+        .run();
+}
+
+// The generated code which is extended by the hooks, such as extra methods of the types.
+fn compile_hooks() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap()).join("hooks");
+    fs::create_dir_all(&out_dir).unwrap();
+    for lang in &[Language::Rust, Language::C] {
+        Compiler::new()
+            .language(*lang)
+            .out_dir(&out_dir)
+            .file_path("schemas/hooks.mol")
+            .hooks_dir("schemas/hooks")
+            .run();
+    }
+    println!("cargo:rerun-if-changed=schemas/hooks.mol");
+    println!("cargo:rerun-if-changed=schemas/hooks");
+}
+
+// The same schema in another code style, and the reserved identifiers of the latest edition.
+fn compile_styled_schemas() {
+    let json_schema = env::var("CARGO_FEATURE_JSON_SCHEMA").is_ok();
+    let arrow = env::var("CARGO_FEATURE_ARROW").is_ok();
+    let rayon = env::var("CARGO_FEATURE_RAYON").is_ok();
+    let small_vectors = env::var("CARGO_FEATURE_SMALLVEC").is_ok();
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap()).join("styled");
+    fs::create_dir_all(&out_dir).unwrap();
+    let mut compiler = Compiler::new();
+    if small_vectors {
+        compiler.small_vectors(2);
+    }
+    compiler
+        .language(Language::Rust)
+        .out_dir(&out_dir)
+        .file_path("schemas/ci_tests.mol")
+        .json_schema(json_schema)
+        .arrow(arrow)
+        .rayon(rayon)
+        .transparent(true)
+        .rust_edition(Edition::Edition2015)
+        .rust_allow("clippy::all")
+        .rust_allow("dead_code")
+        .rust_format(true)
+        .run();
+    Compiler::new()
+        .language(Language::Rust)
+        .out_dir(&out_dir)
+        .file_path("schemas/style/keywords.mol")
+        .rust_edition(Edition::Edition2024)
+        .rust_allow("clippy::all")
+        .run();
+    Compiler::new()
+        .language(Language::OCaml)
+        .out_dir(&out_dir)
+        .file_path("schemas/style/keywords.mol")
+        .run();
+    Compiler::new()
+        .language(Language::Scala)
+        .out_dir(&out_dir)
+        .file_path("schemas/style/keywords.mol")
+        .run();
+    println!("cargo:rerun-if-changed=schemas/style/keywords.mol");
+}
+
+// The schema with a license header, in every language.
+fn compile_schema_with_header() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap()).join("header");
+    fs::create_dir_all(&out_dir).unwrap();
+    for lang in &[
+        Language::Rust,
+        Language::C,
+        Language::OpenApi,
+        Language::Sql,
+        Language::Elixir,
+        Language::Solidity,
+        Language::Zig,
+        Language::Lua,
+        Language::OCaml,
+        Language::Haskell,
+        Language::Scala,
+        Language::Tla,
+        Language::Kaitai,
+        Language::Wireshark,
+        Language::ImHex,
+    ] {
+        Compiler::new()
+            .language(*lang)
+            .out_dir(&out_dir)
+            .file_path("schemas/numbers.mol")
+            .header_file("schemas/style/header.txt")
+            .run();
+    }
+    println!("cargo:rerun-if-changed=schemas/style/header.txt");
+}
+
+// Two versions of a schema, and the shims between them.
+fn compile_migration_schemas() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap()).join("migration");
+    fs::create_dir_all(&out_dir).unwrap();
+    for schema in &["schemas/migration/v1.mol", "schemas/migration/v2.mol"] {
+        Compiler::new()
+            .language(Language::Rust)
+            .out_dir(&out_dir)
+            .file_path(schema)
+            .run();
+        println!("cargo:rerun-if-changed={}", schema);
+    }
+    let mut shims = fs::File::create(out_dir.join("shims.rs")).unwrap();
+    Migration::new()
+        .old_file_path("schemas/migration/v1.mol")
+        .new_file_path("schemas/migration/v2.mol")
+        .mapping_file("schemas/migration/mapping.json")
+        .generate(&mut shims)
+        .unwrap();
+    println!("cargo:rerun-if-changed=schemas/migration/mapping.json");
+}
+
+// The Rust declarations of the generated C code, which is compiled into a static library.
+fn compile_ffi() {
+    if env::var("CARGO_FEATURE_FFI").is_err() {
+        return;
+    }
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    Compiler::new()
+        .language(Language::RustFfi)
+        .default_out_dir()
+        .file_path("schemas/ci_tests.mol")
+        .run();
+    let source = out_dir.join("ci_tests_ffi.c");
+    fs::write(&source, "#include \"ci_tests.h\"\n").unwrap();
+    let object = out_dir.join("ci_tests_ffi.o");
+    let cc = env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+    let status = Command::new(cc)
+        .args(["-c", "-fPIC", "-I"])
+        .arg(&out_dir)
+        .args(["-I", "../../bindings/c/include"])
+        .arg(&source)
+        .arg("-o")
+        .arg(&object)
+        .status()
+        .unwrap();
+    assert!(status.success(), "failed to compile the generated C code");
+    let status = Command::new("ar")
+        .arg("crs")
+        .arg(out_dir.join("libci_tests_ffi.a"))
+        .arg(&object)
+        .status()
+        .unwrap();
+    assert!(status.success(), "failed to archive the generated C code");
+    println!("cargo:rustc-link-search=native={}", out_dir.display());
+    println!("cargo:rustc-link-lib=static=ci_tests_ffi");
+}
+
+// The C readers for on-chain scripts, which are renamed, since `c/ci_tests.h` is another header.
+fn compile_script_profile() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap()).join("script");
+    fs::create_dir_all(&out_dir).unwrap();
+    Compiler::new()
+        .language(Language::C)
+        .c_profile(CProfile::Script)
+        .out_dir(&out_dir)
+        .file_path("schemas/ci_tests.mol")
+        .run();
+    fs::rename(
+        out_dir.join("ci_tests.h"),
+        out_dir.join("ci_tests_script.h"),
+    )
+    .unwrap();
+}
+
+// The example snippets are included by the tests, so all of them are listed in `all.rs`.
+fn generate_examples() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap()).join("examples");
+    Examples::new()
+        .file_path("schemas/ci_tests.mol")
+        .out_dir(&out_dir)
+        .module("molecule_ci_tests::types")
+        .generate()
+        .unwrap();
+    let mut names = fs::read_dir(&out_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter_map(|path| {
+            path.file_stem()
+                .map(|stem| stem.to_str().unwrap().to_owned())
+        })
+        .filter(|name| name != "mod" && name != "all")
+        .collect::<Vec<_>>();
+    names.sort();
+    let mut all = String::new();
+    for name in &names {
+        all.push_str(&format!(
+            "pub mod {} {{ include!(\"{}.rs\"); }}\n",
+            name, name
+        ));
+    }
+    all.push_str("pub const RUNS: &[fn()] = &[\n");
+    for name in &names {
+        all.push_str(&format!("    {}::run,\n", name));
+    }
+    all.push_str("];\n");
+    fs::write(out_dir.join("all.rs"), all).unwrap();
+}
+
+/// The round trips of the code generated for the other languages are ignored, unless they're
+/// enabled by `ROUND_TRIP=1`, since they require the toolchains of the languages.
+fn enable_round_trips() {
+    println!("cargo:rerun-if-env-changed=ROUND_TRIP");
+    println!("cargo:rustc-check-cfg=cfg(round_trip)");
+    if env::var_os("ROUND_TRIP").is_some() {
+        println!("cargo:rustc-cfg=round_trip");
+    }
+}
+
 fn main() {
     compile_schema("schemas/ci_tests.mol");
+    compile_schema("schemas/numbers.mol");
+    compile_schema("schemas/count_size.mol");
+    compile_schema("schemas/flatten.mol");
+    compile_schema("schemas/validation.mol");
+    compile_schema("schemas/constraints.mol");
+    compile_schema("schemas/aliases.mol");
+    compile_schema("schemas/checksum.mol");
+    compile_schema("schemas/logical.mol");
+    compile_schema("schemas/display.mol");
+    compile_schema("schemas/required.mol");
+    compile_schema("schemas/limits.mol");
+    compile_linked_schemas();
+    compile_restricted_schemas();
+    compile_compact_schemas();
+    compile_typestate_schemas();
+    compile_hooks();
+    compile_styled_schemas();
+    compile_schema_with_header();
+    compile_migration_schemas();
+    compile_ffi();
+    compile_script_profile();
+    generate_examples();
+    enable_round_trips();
 }
@@ -1,5 +1,10 @@
 #include "ci_tests.h"
 
+// The size macros could be checked by the hand-written code at compile time.
+MOL_STATIC_ASSERT(MolConst_StructA_size == 6, "the size of StructA is changed");
+MOL_STATIC_ASSERT(MolConst_StructIx3_item_count == 3, "the item count of StructIx3 is changed");
+MOL_STATIC_ASSERT(MolConst_Byte3Vec_item_size == 3, "the item size of Byte3Vec is changed");
+
 #define test_build_default_for(Name)                                    \
     {                                                                   \
         total_cnt += 1;                                                 \
@@ -45,7 +50,24 @@ void test_build_default() {
     }
 }
 
+void test_verify_size_overflow() {
+    test_start("Verify Size Overflow");
+
+    // `4 + 2 * 0x80000002` wraps to 8 in 32 bits.
+    uint8_t words[] = {0x02, ____, ____, 0x80, ____, ____, ____, ____};
+    mol_seg_t seg;
+    seg.ptr = words;
+    seg.size = sizeof(words);
+    mol_errno errno = MolReader_Words_verify(&seg, false);
+    if (errno != MOL_ERR_SIZE_OVERFLOW) {
+        printf("Error Words: the size overflow is not detected (%d)\n", errno);
+        exit(1);
+    }
+    printf("ALL checks are passed (1).\n");
+}
+
 int main(int argc, char *argv[]) {
     test_build_default();
+    test_verify_size_overflow();
     return 0;
 }
@@ -0,0 +1,110 @@
+#include <stdio.h>
+
+#include "count_size_api.h"
+
+// The vectors with `@count_size` are not in the standard molecule encoding.
+MOL_STATIC_ASSERT(MolConst_TinyBytes_count_size == 1, "the count size of TinyBytes is changed");
+MOL_STATIC_ASSERT(MolConst_ShortByte2Vec_count_size == 2, "the count size of ShortByte2Vec is changed");
+MOL_STATIC_ASSERT(MolConst_TinyBytes_max_encoded_size == 256, "the max size of TinyBytes is changed");
+MOL_STATIC_ASSERT(MolConst_Header_max_encoded_size == 528, "the max size of Header is changed");
+
+#define ____ 0x00
+
+uint32_t failed_cnt = 0;
+uint32_t total_cnt = 0;
+
+#define check(cond, message)                                            \
+    {                                                                   \
+        total_cnt += 1;                                                 \
+        if (!(cond)) {                                                  \
+            printf("Error: %s\n", message);                             \
+            failed_cnt += 1;                                            \
+        }                                                               \
+    }
+
+void test_start(char *title) {
+    char line[80+1];
+    memset(line, '-', 80);
+    line[80] = '\0';
+    printf("%s\n", line);
+    memset(line, ' ', 80);
+    line[32] = '\0';
+    printf("%s%s\n", line, title);
+}
+
+void test_build_and_read() {
+    test_start("Build and Read");
+
+    mol_builder_t b;
+    MolBuilder_TinyBytes_init(&b);
+    MolBuilder_TinyBytes_push(&b, 0x12);
+    MolBuilder_TinyBytes_push(&b, 0x34);
+    mol_seg_res_t res = MolBuilder_TinyBytes_build(b);
+    uint8_t expected[] = {0x02, 0x12, 0x34};
+    check(res.errno == MOL_OK, "failed to build TinyBytes");
+    check(res.seg.size == sizeof(expected), "the size of TinyBytes is not match");
+    check(memcmp(res.seg.ptr, expected, sizeof(expected)) == 0,
+            "the content of TinyBytes is not match");
+    check(MolReader_TinyBytes_verify(&res.seg, false) == MOL_OK, "failed to verify TinyBytes");
+    check(MolReader_TinyBytes_length(&res.seg) == 2, "the length of TinyBytes is not match");
+    mol_seg_t raw = MolReader_TinyBytes_raw_bytes(&res.seg);
+    check(raw.size == 2 && raw.ptr[1] == 0x34, "the raw bytes of TinyBytes are not match");
+    mol_seg_res_t item = MolReader_TinyBytes_get(&res.seg, 1);
+    check(item.errno == MOL_OK && item.seg.ptr[0] == 0x34, "failed to get from TinyBytes");
+    free(res.seg.ptr);
+
+    MolBuilder_ShortByte2Vec_init(&b);
+    uint8_t item_data[] = {0x01, 0x02};
+    MolBuilder_ShortByte2Vec_push(&b, item_data);
+    res = MolBuilder_ShortByte2Vec_build(b);
+    uint8_t expected_vec[] = {0x01, ____, 0x01, 0x02};
+    check(res.errno == MOL_OK, "failed to build ShortByte2Vec");
+    check(res.seg.size == sizeof(expected_vec)
+            && memcmp(res.seg.ptr, expected_vec, sizeof(expected_vec)) == 0,
+            "the content of ShortByte2Vec is not match");
+    check(MolReader_ShortByte2Vec_verify(&res.seg, false) == MOL_OK,
+            "failed to verify ShortByte2Vec");
+    free(res.seg.ptr);
+
+    if (failed_cnt == 0) {
+        printf("ALL checks are passed (%d).\n", total_cnt);
+    } else {
+        printf("%d/%d checks are failed.\n", failed_cnt, total_cnt);
+        exit(1);
+    }
+}
+
+void test_verify_and_overflow() {
+    test_start("Verify and Overflow");
+
+    mol_seg_t seg;
+    uint8_t broken[] = {0x03, 0x12, 0x34};
+    seg.ptr = broken;
+    seg.size = sizeof(broken);
+    check(MolReader_TinyBytes_verify(&seg, false) == MOL_ERR_TOTAL_SIZE,
+            "the broken TinyBytes is not detected");
+    seg.size = 0;
+    check(MolReader_TinyBytes_verify(&seg, false) == MOL_ERR_HEADER,
+            "the broken header of TinyBytes is not detected");
+
+    mol_builder_t b;
+    MolBuilder_TinyBytes_init(&b);
+    for (int i = 0; i < 256; i++) {
+        MolBuilder_TinyBytes_push(&b, 0x00);
+    }
+    mol_seg_res_t res = MolBuilder_TinyBytes_build(b);
+    check(res.errno == MOL_ERR_SIZE_OVERFLOW, "the count overflow is not detected");
+
+    if (failed_cnt == 0) {
+        printf("ALL checks are passed (%d).\n", total_cnt);
+    } else {
+        printf("%d/%d checks are failed.\n", failed_cnt, total_cnt);
+        exit(1);
+    }
+}
+
+int main(int argc, char *argv[]) {
+    test_build_and_read();
+    test_verify_and_overflow();
+    return 0;
+}
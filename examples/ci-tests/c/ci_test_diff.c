@@ -0,0 +1,79 @@
+#include "ci_tests.h"
+
+#define MAX_SIZE 4096
+#define MAX_LINE_SIZE (64 + 2 + MAX_SIZE * 2 + 16)
+
+#define test_diff_for(Name)                                             \
+    if (strcmp(name, #Name) == 0) {                                     \
+        found = true;                                                   \
+        strict = MolReader_ ## Name ## _verify(&seg, false);            \
+        compatible = MolReader_ ## Name ## _verify(&seg, true);         \
+    }
+
+int hex_value(char c) {
+    if (c >= '0' && c <= '9') {
+        return c - '0';
+    } else if (c >= 'a' && c <= 'f') {
+        return c - 'a' + 10;
+    } else if (c >= 'A' && c <= 'F') {
+        return c - 'A' + 10;
+    }
+    return -1;
+}
+
+// Decode "0x..." into the buffer, and return the size, or -1 if it's broken.
+int decode_hex(const char *hex, uint8_t *buf) {
+    if (hex[0] != '0' || hex[1] != 'x') {
+        return -1;
+    }
+    hex += 2;
+    size_t len = strlen(hex);
+    if (len % 2 != 0 || len / 2 > MAX_SIZE) {
+        return -1;
+    }
+    for (size_t i = 0; i < len / 2; i++) {
+        int hi = hex_value(hex[i * 2]);
+        int lo = hex_value(hex[i * 2 + 1]);
+        if (hi < 0 || lo < 0) {
+            return -1;
+        }
+        buf[i] = (uint8_t)(hi * 16 + lo);
+    }
+    return (int)(len / 2);
+}
+
+const char *verdict(mol_errno errno) {
+    return errno == MOL_OK ? "ok" : "err";
+}
+
+// Read lines of "<Type> <Hex> ...", and print "<Type> <Hex> <Strict> <Compatible>".
+int main() {
+    static char line[MAX_LINE_SIZE];
+    static char name[64];
+    static char hex[MAX_SIZE * 2 + 3];
+    static uint8_t buf[MAX_SIZE];
+    while (fgets(line, sizeof(line), stdin) != NULL) {
+        if (sscanf(line, "%63s %8194s", name, hex) != 2) {
+            fprintf(stderr, "Error: broken line: %s", line);
+            return 1;
+        }
+        int size = decode_hex(hex, buf);
+        if (size < 0) {
+            fprintf(stderr, "Error: broken hex for %s\n", name);
+            return 1;
+        }
+        mol_seg_t seg;
+        seg.ptr = buf;
+        seg.size = (mol_num_t)size;
+        bool found = false;
+        mol_errno strict = MOL_OK;
+        mol_errno compatible = MOL_OK;
+        testset_apply_all(test_diff_for);
+        if (!found) {
+            fprintf(stderr, "Error: unknown type %s\n", name);
+            return 1;
+        }
+        printf("%s %s %s %s\n", name, hex, verdict(strict), verdict(compatible));
+    }
+    return 0;
+}
@@ -0,0 +1,72 @@
+// The harness of the C readers which are generated with `--c-profile script`.
+//
+// It doesn't call any libc function, so the same file runs natively, where the exit code is the
+// number of the failed checks, and as a CKB script on RISC-V, where ckb-debugger reports the
+// cycles of the verification; see `make test-c-script` and `make cycles-c-script`.
+//
+// The checks are repeated `ITERATIONS` times, so the cycles of the verification dominate the
+// cycles of the script.
+
+#include "ci_tests_script.h"
+
+#ifndef ITERATIONS
+#define ITERATIONS 1
+#endif /* ITERATIONS */
+
+#define check_default(Name)                                             \
+    {                                                                   \
+        mol_seg_t seg;                                                  \
+        seg.ptr = (uint8_t*)MolDefault_ ## Name;                        \
+        seg.size = sizeof(MolDefault_ ## Name);                         \
+        failed += MolReader_ ## Name ## _verify(&seg, false) != MOL_OK; \
+        failed += MolReader_ ## Name ## _verify(&seg, true) != MOL_OK;  \
+    }
+
+#define check_broken(Name, Errno, ...)                                  \
+    {                                                                   \
+        uint8_t data[] = {__VA_ARGS__};                                 \
+        mol_seg_t seg;                                                  \
+        seg.ptr = data;                                                 \
+        seg.size = sizeof(data);                                        \
+        failed += MolReader_ ## Name ## _verify(&seg, false) != Errno;  \
+    }
+
+int main() {
+    int failed = 0;
+    for (int i = 0; i < ITERATIONS; i++) {
+        check_default(StructA);
+        check_default(Bytes);
+        check_default(BytesVec);
+        check_default(WordsVec);
+        check_default(Table0);
+        check_default(Table6);
+        check_default(Table6Opt);
+        check_default(UnionA);
+        check_default(TableA);
+        check_default(AllInOne);
+        // The total size is not the size of the data.
+        check_broken(Table1, MOL_ERR_TOTAL_SIZE, 0x0a, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00);
+        // The first offset is not aligned.
+        check_broken(Table1, MOL_ERR_OFFSET, 0x09, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x00, 0x00);
+        // The table has an extra field in the strict mode.
+        check_broken(Table1, MOL_ERR_FIELD_COUNT,
+                0x0e, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x0d, 0x00, 0x00, 0x00, 0x00, 0x00);
+        // The offsets are not in order.
+        check_broken(Table2, MOL_ERR_OFFSET,
+                0x0e, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x0b, 0x00, 0x00, 0x00, 0x00, 0x00);
+        // The byte field has two bytes.
+        check_broken(Table1, MOL_ERR_DATA, 0x0a, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00);
+    }
+    return failed;
+}
+
+#ifdef __riscv
+// The entry of a CKB script, which exits with the result of `main` by the `exit` syscall.
+void _start(void) {
+    register long a0 asm("a0") = main();
+    register long a7 asm("a7") = 93;
+    asm volatile("ecall" : "+r"(a0) : "r"(a7));
+    for (;;) {
+    }
+}
+#endif /* __riscv */
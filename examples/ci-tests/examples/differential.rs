@@ -0,0 +1,117 @@
+//! Generate random buffers for all types, and print the verification results of the Rust readers.
+//!
+//! Each line is `<Type> <Hex> <Strict> <Compatible>`; `ci_test_diff` reads the same lines and
+//! prints the results of the C readers, so any different line is a divergence. Use
+//! `moleculec minimize` to shrink the buffer in a divergence before reporting it.
+//!
+//! Usage: `cargo run --example differential -- [seed] [count]`.
+
+use std::env;
+
+use molecule::{pack_number, prelude::*, Number, NUMBER_SIZE};
+
+use molecule_ci_tests::testset;
+
+const MAX_SIZE: usize = 4096;
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        // xorshift64*
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+/// Apply a random mutation; the numbers in the headers are mutated more often, since most
+/// verification rules are about them.
+fn mutate(rng: &mut Rng, data: &mut Vec<u8>) {
+    match rng.below(6) {
+        0 if !data.is_empty() => {
+            let index = rng.below(data.len());
+            data[index] = rng.next() as u8;
+        }
+        1 if !data.is_empty() => {
+            let len = rng.below(data.len());
+            data.truncate(len);
+        }
+        2 => {
+            for _ in 0..=rng.below(16) {
+                data.push(rng.next() as u8);
+            }
+        }
+        3 if data.len() >= NUMBER_SIZE => {
+            let start = rng.below(data.len() / NUMBER_SIZE) * NUMBER_SIZE;
+            let number = match rng.below(3) {
+                0 => data.len() as Number,
+                1 => rng.below(data.len() + 1) as Number,
+                _ => rng.next() as Number,
+            };
+            data[start..start + NUMBER_SIZE].copy_from_slice(&pack_number(number));
+        }
+        4 if !data.is_empty() => {
+            let start = rng.below(data.len());
+            let end = start + rng.below(data.len() - start) + 1;
+            let chunk = data[start..end].to_vec();
+            let index = rng.below(data.len() + 1);
+            data.splice(index..index, chunk);
+        }
+        _ => {
+            let total_size = data.len() as Number;
+            if data.len() >= NUMBER_SIZE {
+                data[..NUMBER_SIZE].copy_from_slice(&pack_number(total_size));
+            }
+        }
+    }
+    data.truncate(MAX_SIZE);
+}
+
+fn verdict(result: bool) -> &'static str {
+    if result {
+        "ok"
+    } else {
+        "err"
+    }
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let seed = args.next().map(|x| x.parse().unwrap()).unwrap_or(1);
+    let count: usize = args.next().map(|x| x.parse().unwrap()).unwrap_or(256);
+    let mut rng = Rng(seed | 1);
+
+    macro_rules! differential {
+        ($type:ident) => {
+            // The valid buffers are kept as seeds, to reach the nested types.
+            let mut seeds = vec![$type::default().as_slice().to_vec()];
+            for _ in 0..count {
+                let mut data = seeds[rng.below(seeds.len())].clone();
+                for _ in 0..=rng.below(3) {
+                    mutate(&mut rng, &mut data);
+                }
+                let strict = $type::from_slice(&data).is_ok();
+                let compatible = $type::from_compatible_slice(&data).is_ok();
+                let hex: String = data.iter().map(|b| format!("{:02x}", b)).collect();
+                println!(
+                    "{} 0x{} {} {}",
+                    stringify!($type),
+                    hex,
+                    verdict(strict),
+                    verdict(compatible)
+                );
+                if compatible {
+                    seeds.push(data);
+                }
+            }
+        };
+    }
+
+    testset!(all, differential);
+}
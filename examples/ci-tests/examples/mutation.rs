@@ -0,0 +1,43 @@
+//! Print the mutations of the default values of all types, in the same lines as `differential`.
+//!
+//! The results are from the schema, not from the Rust readers, so `ci_test_diff` checks the C
+//! readers against the labels; `tests/mutation.rs` checks the Rust readers.
+//!
+//! Usage: `cargo run --example mutation`.
+
+use codegen::Mutator;
+use molecule::prelude::*;
+
+use molecule_ci_tests::testset;
+
+fn verdict(result: bool) -> &'static str {
+    if result {
+        "ok"
+    } else {
+        "err"
+    }
+}
+
+fn main() {
+    macro_rules! mutation {
+        ($type:ident) => {
+            let mutations = Mutator::new()
+                .file_path("schemas/ci_tests.mol")
+                .type_name(stringify!($type))
+                .mutate($type::default().as_slice())
+                .unwrap();
+            for mutation in mutations {
+                let hex: String = mutation.data.iter().map(|b| format!("{:02x}", b)).collect();
+                println!(
+                    "{} 0x{} {} {}",
+                    stringify!($type),
+                    hex,
+                    verdict(false),
+                    verdict(mutation.compatible)
+                );
+            }
+        };
+    }
+
+    testset!(all, mutation);
+}
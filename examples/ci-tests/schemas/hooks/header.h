@@ -0,0 +1 @@
+#define GREETING_MAX_COUNT 3
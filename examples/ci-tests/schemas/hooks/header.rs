@@ -0,0 +1,6 @@
+extern crate alloc;
+
+use alloc::{format, string::String, vec};
+use core::convert::TryInto as _;
+
+pub const GREETING_WORD: &str = "Hello";
@@ -0,0 +1,5 @@
+static inline uint32_t MolExt_Greeting_count(const mol_seg_t *input) {
+    mol_seg_t count = MolReader_Greeting_get_count(input);
+    uint32_t value = mol_unpack_number(count.ptr);
+    return value > GREETING_MAX_COUNT ? GREETING_MAX_COUNT : value;
+}
@@ -0,0 +1,17 @@
+impl Greeting {
+    /// Greet the name as many times as the count.
+    pub fn greet(&self) -> String {
+        self.as_reader().greet()
+    }
+}
+
+impl<'r> GreetingReader<'r> {
+    pub fn count_value(&self) -> u32 {
+        u32::from_le_bytes(self.count().as_slice().try_into().unwrap())
+    }
+
+    pub fn greet(&self) -> String {
+        let name = String::from_utf8_lossy(self.name().raw_data()).into_owned();
+        vec![format!("{}, {}!", GREETING_WORD, name); self.count_value() as usize].join(" ")
+    }
+}
@@ -1,11 +1,167 @@
 #![no_std]
 
+#[cfg(feature = "python")]
+extern crate std;
+
 pub mod types {
     #![allow(clippy::all)]
     pub use molecule::prelude::{Byte, ByteReader};
     include!(concat!(env!("OUT_DIR"), "/", "ci_tests", ".rs"));
 }
 
+pub mod numbers {
+    #![allow(clippy::all)]
+    pub use molecule::prelude::{Byte, ByteReader};
+    include!(concat!(env!("OUT_DIR"), "/", "numbers", ".rs"));
+}
+
+pub mod count_size {
+    #![allow(clippy::all)]
+    pub use molecule::prelude::{Byte, ByteReader};
+    include!(concat!(env!("OUT_DIR"), "/", "count_size", ".rs"));
+}
+
+pub mod flatten {
+    #![allow(clippy::all)]
+    pub use molecule::prelude::{Byte, ByteReader};
+    include!(concat!(env!("OUT_DIR"), "/", "flatten", ".rs"));
+}
+
+pub mod validation {
+    #![allow(clippy::all)]
+    pub use molecule::prelude::{Byte, ByteReader};
+    include!(concat!(env!("OUT_DIR"), "/", "validation", ".rs"));
+}
+
+pub mod aliases {
+    #![allow(clippy::all)]
+    pub use molecule::prelude::{Byte, ByteReader};
+    include!(concat!(env!("OUT_DIR"), "/", "aliases", ".rs"));
+}
+
+pub mod constraints {
+    #![allow(clippy::all)]
+    pub use molecule::prelude::{Byte, ByteReader};
+    include!(concat!(env!("OUT_DIR"), "/", "constraints", ".rs"));
+}
+
+pub mod checksum {
+    #![allow(clippy::all)]
+    pub use molecule::prelude::{Byte, ByteReader};
+    include!(concat!(env!("OUT_DIR"), "/", "checksum", ".rs"));
+}
+
+pub mod logical {
+    #![allow(clippy::all)]
+    pub use molecule::prelude::{Byte, ByteReader};
+    include!(concat!(env!("OUT_DIR"), "/", "logical", ".rs"));
+}
+
+pub mod display {
+    #![allow(clippy::all)]
+    pub use molecule::prelude::{Byte, ByteReader};
+    include!(concat!(env!("OUT_DIR"), "/", "display", ".rs"));
+}
+
+pub mod required {
+    #![allow(clippy::all)]
+    pub use molecule::prelude::{Byte, ByteReader};
+    include!(concat!(env!("OUT_DIR"), "/", "required", ".rs"));
+}
+
+pub mod limits {
+    #![allow(clippy::all)]
+    pub use molecule::prelude::{Byte, ByteReader};
+    include!(concat!(env!("OUT_DIR"), "/", "limits", ".rs"));
+}
+
+pub mod common {
+    #![allow(clippy::all)]
+    pub use molecule::prelude::{Byte, ByteReader};
+    include!(concat!(env!("OUT_DIR"), "/", "common", ".rs"));
+}
+
+pub mod app {
+    #![allow(clippy::all)]
+    pub use molecule::prelude::{Byte, ByteReader};
+    include!(concat!(env!("OUT_DIR"), "/", "app", ".rs"));
+}
+
+mod restricted {
+    mod types {
+        #![allow(dead_code, clippy::all)]
+        include!(concat!(env!("OUT_DIR"), "/restricted/", "ci_tests", ".rs"));
+    }
+
+    mod numbers {
+        #![allow(dead_code, clippy::all)]
+        include!(concat!(env!("OUT_DIR"), "/restricted/", "numbers", ".rs"));
+    }
+}
+
+pub mod compact {
+    pub mod types {
+        #![allow(clippy::all)]
+        pub use molecule::prelude::{Byte, ByteReader};
+        include!(concat!(env!("OUT_DIR"), "/compact/", "ci_tests", ".rs"));
+    }
+}
+
+pub mod typestate {
+    pub mod constraints {
+        #![allow(clippy::all)]
+        pub use molecule::prelude::{Byte, ByteReader};
+        include!(concat!(
+            env!("OUT_DIR"),
+            "/typestate/",
+            "constraints",
+            ".rs"
+        ));
+    }
+}
+
+pub mod hooks {
+    #![allow(clippy::all)]
+    pub use molecule::prelude::{Byte, ByteReader};
+    include!(concat!(env!("OUT_DIR"), "/hooks/", "hooks", ".rs"));
+}
+
+pub mod styled {
+    pub mod types {
+        pub use molecule::prelude::{Byte, ByteReader};
+        include!(concat!(env!("OUT_DIR"), "/styled/", "ci_tests", ".rs"));
+    }
+
+    pub mod keywords {
+        pub use molecule::prelude::{Byte, ByteReader};
+        include!(concat!(env!("OUT_DIR"), "/styled/", "keywords", ".rs"));
+    }
+}
+
+pub mod migration {
+    pub mod old {
+        #![allow(clippy::all)]
+        pub use molecule::prelude::{Byte, ByteReader};
+        include!(concat!(env!("OUT_DIR"), "/migration/", "v1", ".rs"));
+    }
+
+    pub mod new {
+        #![allow(clippy::all)]
+        pub use molecule::prelude::{Byte, ByteReader};
+        include!(concat!(env!("OUT_DIR"), "/migration/", "v2", ".rs"));
+    }
+
+    pub mod shims {
+        #![allow(clippy::all)]
+        include!(concat!(env!("OUT_DIR"), "/migration/", "shims", ".rs"));
+    }
+}
+
+#[cfg(feature = "ffi")]
+pub mod ffi {
+    include!(concat!(env!("OUT_DIR"), "/", "ci_tests", ".ffi.rs"));
+}
+
 #[macro_export]
 macro_rules! testset {
     (array, $callback:ident) => {
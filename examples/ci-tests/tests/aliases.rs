@@ -0,0 +1,138 @@
+use std::{env, fs, panic, path::Path, process::Command};
+
+use codegen::{schema_hash, schema_stats, Compiler, Language, Verifier};
+use molecule::prelude::*;
+
+use molecule_ci_tests::aliases::*;
+
+const C: &str = include_str!(concat!(env!("OUT_DIR"), "/aliases.h"));
+
+fn schema_file(name: &str, content: &str) -> std::path::PathBuf {
+    let dir = env::temp_dir().join("molecule-ci-tests-aliases");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    fs::write(&path, content).unwrap();
+    path
+}
+
+fn panic_message(schema: &str) -> String {
+    let path = schema_file("invalid.mol", schema);
+    let err = panic::catch_unwind(|| schema_hash(&path)).unwrap_err();
+    err.downcast_ref::<String>()
+        .cloned()
+        .or_else(|| err.downcast_ref::<&str>().map(|s| (*s).to_owned()))
+        .unwrap()
+}
+
+fn address(data: &[u8]) -> Address {
+    Address::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+#[test]
+fn aliases_are_the_types() {
+    let amount: Amount = 7u64.to_le_bytes().into();
+    let memo: Message = MessageBuilder::default().set(address(&[1])).build();
+    let transfer = Transfer::new_builder()
+        .from(address(&[2]))
+        .to(address(&[3]))
+        .amount(amount.clone())
+        .memo(memo)
+        .build();
+    // The fields of the aliases are the types.
+    let from: Bytes = transfer.from();
+    assert_eq!(from.raw_data().as_ref(), &[2]);
+    let reader: AmountReader = transfer.as_reader().amount();
+    assert_eq!(reader.as_slice(), amount.as_slice());
+    match transfer.memo().to_enum() {
+        MessageUnion::Bytes(bytes) => assert_eq!(bytes.raw_data().as_ref(), &[1]),
+        MessageUnion::Uint64(_) => panic!("the memo should be bytes"),
+    }
+    let recipient: Recipient = transfer.to();
+    Address::from_slice(recipient.as_slice()).unwrap();
+}
+
+#[test]
+fn aliases_are_not_types() {
+    let names: Vec<_> = schema_stats("schemas/aliases.mol")
+        .into_iter()
+        .map(|stats| stats.name)
+        .collect();
+    assert_eq!(
+        names,
+        vec!["Uint64", "Bytes", "Payload", "Transfer", "Transfers"]
+    );
+    // The binary format is the same as the schema without the aliases.
+    let path = schema_file(
+        "resolved.mol",
+        "array Uint64 [byte; 8];\nvector Bytes <byte>;\nunion Payload { Bytes, Uint64, }\n\
+         table Transfer { from: Bytes, to: Bytes, amount: Uint64, memo: Payload, }\n\
+         vector Transfers <Transfer>;\n",
+    );
+    assert_eq!(schema_hash("schemas/aliases.mol"), schema_hash(&path));
+}
+
+#[test]
+fn verify_as_alias() {
+    let amount: Amount = 7u64.to_le_bytes().into();
+    let verifier = Verifier::new("schemas/aliases.mol", "Amount");
+    verifier.verify(amount.as_slice()).unwrap();
+    assert!(verifier.verify(&[0; 7]).is_err());
+}
+
+#[test]
+fn c_aliases() {
+    for (alias, target) in &[
+        ("MolReader_Amount_verify", "MolReader_Uint64_verify"),
+        ("MolBuilder_Address_push", "MolBuilder_Bytes_push"),
+        ("MolConst_Message_item_Bytes", "MolConst_Payload_item_Bytes"),
+        ("MolReader_Recipient_length", "MolReader_Bytes_length"),
+        ("MolDefault_Amount", "MolDefault_Uint64"),
+    ] {
+        let line = format!("{:39} {:47} {}\n", "#define", alias, target);
+        assert!(C.contains(&line), "{}", line);
+    }
+    let out_dir = Path::new(env!("OUT_DIR"));
+    let source = out_dir.join("aliases_test.c");
+    fs::write(
+        &source,
+        "#include \"aliases.h\"\n\
+         int main(void) {\n\
+         mol_seg_t seg = { (uint8_t *)MolDefault_Amount, MolConst_Amount_size };\n\
+         return MolReader_Amount_verify(&seg, false) != MOL_OK;\n\
+         }\n",
+    )
+    .unwrap();
+    let status = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_owned()))
+        .args(["-Wall", "-Werror", "-fsyntax-only", "-I"])
+        .arg(out_dir)
+        .args(["-I", "../../bindings/c/include"])
+        .arg(&source)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[test]
+fn invalid_aliases() {
+    let message = panic_message("alias Flag = byte;\n");
+    assert!(message.contains("the alias (Flag) should be of a declared type"));
+    let message = panic_message("vector Bytes <byte>;\nalias Bytes = Bytes;\n");
+    assert!(message.contains("the name `Bytes` is used more than once"));
+    let message = panic_message("vector Bytes <byte>;\nalias A = B;\nalias B = A;\n");
+    assert!(message.contains("unable to be completed"), "{}", message);
+}
+
+#[test]
+fn rust_aliases_are_documented() {
+    let dir = env::temp_dir().join("molecule-ci-tests-aliases-rust");
+    fs::create_dir_all(&dir).unwrap();
+    Compiler::new()
+        .language(Language::Rust)
+        .file_path("schemas/aliases.mol")
+        .out_dir(&dir)
+        .run();
+    let code = fs::read_to_string(dir.join("aliases.rs")).unwrap();
+    assert!(code.contains("Another name of [`Uint64`]."));
+}
@@ -0,0 +1,48 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use molecule::prelude::*;
+
+use molecule_ci_tests::types;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// The allocator is global, so there is only one test in this file.
+#[test]
+fn verification_does_not_allocate() {
+    let bytes = types::Bytes::new_builder().push(1.into()).build();
+    let bytes_vec = types::BytesVec::new_builder()
+        .push(bytes.clone())
+        .push(bytes)
+        .build();
+    let table = types::Table6::new_builder().build();
+    let union = types::UnionA::new_builder().set(table.clone()).build();
+    let all_in_one = types::AllInOne::new_builder().build();
+
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    assert!(types::BytesVecReader::verify(bytes_vec.as_slice(), false).is_ok());
+    assert!(types::Table6Reader::verify(table.as_slice(), false).is_ok());
+    assert!(types::Table6Reader::verify(table.as_slice(), true).is_ok());
+    assert!(types::UnionAReader::verify(union.as_slice(), false).is_ok());
+    assert!(types::AllInOneReader::from_slice(all_in_one.as_slice()).is_ok());
+    assert!(types::AllInOneReader::from_compatible_slice(all_in_one.as_slice()).is_ok());
+    assert_eq!(ALLOCATIONS.load(Ordering::SeqCst), before);
+}
@@ -0,0 +1,27 @@
+use molecule::prelude::*;
+
+use molecule_ci_tests::types;
+
+fn word(value: u8) -> types::Word {
+    [value, 0].into()
+}
+
+#[test]
+fn array_items() {
+    let words = types::Word3::new_builder()
+        .set([word(1), word(2), word(3)])
+        .build();
+    assert_eq!(words.get(2).unwrap().as_slice(), &[3, 0][..]);
+    assert!(words.get(types::Word3::ITEM_COUNT).is_none());
+    let items = words
+        .clone()
+        .into_iter()
+        .map(|w| w.nth0())
+        .collect::<Vec<_>>();
+    assert_eq!(items, vec![1.into(), 2.into(), 3.into()]);
+    let reader = words.as_reader();
+    assert_eq!(reader.iter().len(), 3);
+    let items = reader.iter().map(|w| w.as_array()[0]).collect::<Vec<_>>();
+    assert_eq!(items, vec![1, 2, 3]);
+    assert_eq!(reader.get(1).unwrap().as_slice(), words.nth1().as_slice());
+}
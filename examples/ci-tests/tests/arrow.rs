@@ -0,0 +1,68 @@
+#![cfg(feature = "arrow")]
+
+use arrow_array::{FixedSizeBinaryArray, StructArray, UInt8Array};
+use molecule::{
+    arrow::{Array, DataType},
+    prelude::*,
+};
+use slices::u8_slice as s;
+
+use molecule_ci_tests::types;
+
+#[test]
+fn arrow_struct_vec() {
+    let slice = s!("0x02000000_010203_04_050607_08");
+    let vector = types::StructIVec::from_slice(slice).unwrap();
+    let array = vector.to_arrow().unwrap();
+    assert_eq!(array.len(), 2);
+    let array = array.as_any().downcast_ref::<StructArray>().unwrap();
+    let f1 = array
+        .column_by_name("f1")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<FixedSizeBinaryArray>()
+        .unwrap();
+    assert_eq!(f1.value(0), &s!("0x010203")[..]);
+    assert_eq!(f1.value(1), &s!("0x050607")[..]);
+    let f2 = array
+        .column_by_name("f2")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<UInt8Array>()
+        .unwrap();
+    assert_eq!(f2.values(), &[4, 8]);
+    let result = types::StructIVec::from_arrow(array).unwrap();
+    assert_eq!(result.as_slice(), &slice[..]);
+}
+
+#[test]
+fn arrow_nested_struct_vec() {
+    let vector = types::StructPVec::new_builder()
+        .push(
+            types::StructP::new_builder()
+                .f1(types::StructJ::new_builder().f2(Byte::new(1)).build())
+                .f2(Byte::new(2))
+                .build(),
+        )
+        .push(Default::default())
+        .build();
+    let array = vector.as_reader().to_arrow().unwrap();
+    let result = types::StructPVec::from_arrow(array.as_ref()).unwrap();
+    assert_eq!(result.as_slice(), vector.as_slice());
+}
+
+#[test]
+fn arrow_bytes_vec() {
+    let vector = types::Byte3Vec::default();
+    let array = vector.to_arrow().unwrap();
+    assert_eq!(array.len(), 0);
+    assert_eq!(array.data_type(), &DataType::FixedSizeBinary(3));
+    let result = types::Byte3Vec::from_arrow(array.as_ref()).unwrap();
+    assert_eq!(result.as_slice(), vector.as_slice());
+}
+
+#[test]
+fn arrow_type_mismatch() {
+    let array = types::StructIVec::default().to_arrow().unwrap();
+    assert!(types::StructJVec::from_arrow(array.as_ref()).is_err());
+}
@@ -0,0 +1,100 @@
+use molecule::{
+    batch::{decode_batch, encode_batch, BatchError, BatchItem, BatchLimits, ITEM_HEADER_SIZE},
+    prelude::*,
+    NUMBER_SIZE,
+};
+
+use molecule_ci_tests::{limits::*, types};
+
+fn messages() -> Vec<Message> {
+    let bytes = Bytes::new_builder()
+        .extend([0x12, 0x34].iter().copied().map(Byte::new))
+        .build();
+    vec![
+        Message::new_builder().set(Ping::default()).build(),
+        Message::new_builder().set(bytes).build(),
+    ]
+}
+
+#[test]
+fn round_trip() {
+    let messages = messages();
+    let data = encode_batch(&messages);
+    let decoded = decode_batch::<Message>(&data, &BatchLimits::default()).unwrap();
+    assert_eq!(decoded.len(), 2);
+    for (decoded, message) in decoded.iter().zip(&messages) {
+        assert_eq!(decoded.as_slice(), message.as_slice());
+    }
+    let empty = encode_batch::<Message>(&[]);
+    assert_eq!(empty, vec![0; NUMBER_SIZE]);
+    assert!(decode_batch::<Message>(&empty, &BatchLimits::default())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn type_hashes() {
+    // The hash is of the type of the item only, so it's the same in the other schemas.
+    let bytes_hash = Message::ITEM_TYPE_HASHES[1];
+    assert_eq!(types::UnionA::ITEM_TYPE_HASHES[3], bytes_hash);
+    assert_ne!(Message::ITEM_TYPE_HASHES[0], bytes_hash);
+
+    let mut data = encode_batch(&messages());
+    let second = NUMBER_SIZE + ITEM_HEADER_SIZE + messages()[0].as_slice().len();
+    data[second] ^= 0xff;
+    assert_eq!(
+        decode_batch::<Message>(&data, &BatchLimits::default()).unwrap_err(),
+        BatchError::TypeHashNotMatch(1, 1)
+    );
+}
+
+#[test]
+fn limits() {
+    let data = encode_batch(&messages());
+    let limits = BatchLimits {
+        max_count: 1,
+        ..Default::default()
+    };
+    assert_eq!(
+        decode_batch::<Message>(&data, &limits).unwrap_err(),
+        BatchError::TooManyItems(2, 1)
+    );
+    let limits = BatchLimits {
+        max_item_size: 8,
+        ..Default::default()
+    };
+    let err = decode_batch::<Message>(&data, &limits).unwrap_err();
+    assert_eq!(err, BatchError::ItemTooLarge(0, 20, 8));
+    assert_eq!(
+        err.to_string(),
+        "the item 0 of the batch has 20 bytes, but at most 8 are allowed"
+    );
+}
+
+#[test]
+fn broken_batches() {
+    let limits = BatchLimits::default();
+    let data = encode_batch(&messages());
+    assert_eq!(
+        decode_batch::<Message>(&data[..data.len() - 1], &limits).unwrap_err(),
+        BatchError::Truncated
+    );
+    let mut trailing = data.clone();
+    trailing.push(0);
+    assert_eq!(
+        decode_batch::<Message>(&trailing, &limits).unwrap_err(),
+        BatchError::TrailingData(1)
+    );
+    // A huge count is rejected before anything is allocated.
+    assert_eq!(
+        decode_batch::<Message>(&[0xff; NUMBER_SIZE], &limits).unwrap_err(),
+        BatchError::Truncated
+    );
+
+    let mut unknown = data;
+    unknown[NUMBER_SIZE + ITEM_HEADER_SIZE] = 0x09;
+    match decode_batch::<Message>(&unknown, &limits).unwrap_err() {
+        BatchError::Invalid(0, err) => assert_eq!(err.kind(), "UnknownItem"),
+        err => panic!("unexpected error: {}", err),
+    }
+}
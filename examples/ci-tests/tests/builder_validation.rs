@@ -0,0 +1,73 @@
+use molecule::{error::Violation, prelude::*};
+
+use molecule_ci_tests::{constraints, count_size};
+
+fn uint32(value: u32) -> constraints::Uint32 {
+    value.to_le_bytes().into()
+}
+
+fn bytes(data: &[u8]) -> constraints::Bytes {
+    constraints::Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+#[test]
+fn valid_builders() {
+    let builder = constraints::Frame::new_builder()
+        .declared_len(uint32(1))
+        .payload(bytes(&[1]))
+        .body(constraints::Payload::new_builder().set(bytes(&[])).build());
+    assert_eq!(builder.validate(), Ok(()));
+    assert_eq!(constraints::Range::new_builder().validate(), Ok(()));
+    assert_eq!(constraints::Names::new_builder().validate(), Ok(()));
+}
+
+#[test]
+fn all_violations() {
+    let range = constraints::Range::new_builder()
+        .start(uint32(2))
+        .end(uint32(1))
+        .build();
+    let names = constraints::Names::new_builder()
+        .extend((0..4).map(|_| bytes(&[])))
+        .build();
+    let builder = constraints::Frame::new_builder()
+        .declared_len(uint32(2))
+        .payload(bytes(&[1]))
+        .kind(1.into())
+        .names(names)
+        .range(range.clone());
+    let violations = builder.validate().unwrap_err();
+    let constraint = |text: &str| Violation::ConstraintNotHeld("Frame".to_owned(), text.to_owned());
+    assert_eq!(
+        violations,
+        vec![
+            constraint("len(payload) == declared_len"),
+            constraint("item_id(body) == kind"),
+            constraint("len(names) <= 3"),
+        ]
+    );
+    // The nested values are validated by their own builders.
+    assert_eq!(
+        range.as_builder().validate().unwrap_err(),
+        vec![Violation::ConstraintNotHeld(
+            "Range".to_owned(),
+            "start <= end".to_owned()
+        )]
+    );
+}
+
+#[test]
+fn too_many_items() {
+    let builder = count_size::TinyBytes::new_builder().extend((0..256).map(|_| Byte::new(0)));
+    let violations = builder.validate().unwrap_err();
+    assert_eq!(
+        violations,
+        vec![Violation::TooManyItems("TinyBytes".to_owned(), 256, 255)]
+    );
+    assert_eq!(
+        violations[0].to_string(),
+        "TinyBytes has 256 items, but at most 255"
+    );
+}
@@ -0,0 +1,20 @@
+use molecule::prelude::*;
+
+use molecule_ci_tests::types;
+
+fn digest(_: &[u8; 16]) -> u8 {
+    0
+}
+
+#[test]
+fn byte_array_conversions() {
+    let bytes = [7u8; 16];
+    let value = types::Byte16::from(bytes);
+    assert_eq!(value.as_slice(), &bytes[..]);
+    assert_eq!(value.as_array(), &bytes);
+    assert_eq!(digest(value.as_reader().as_array()), 0);
+    assert_eq!(<[u8; 16]>::from(value), bytes);
+    let value: types::Byte3 = [1, 2, 3].into();
+    assert_eq!(value.to_array(), [1, 2, 3]);
+    assert_eq!(value.nth1().as_slice(), &[2][..]);
+}
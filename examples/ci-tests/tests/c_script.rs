@@ -0,0 +1,62 @@
+#![cfg(feature = "ffi")]
+
+use std::{env, fs, path::Path, process::Command};
+
+const SCRIPT: &str = include_str!(concat!(env!("OUT_DIR"), "/script/ci_tests_script.h"));
+
+fn cc() -> Command {
+    let mut command = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_owned()));
+    command
+        .args(["-Wall", "-Werror", "-Wvla", "-I"])
+        .arg(Path::new(env!("OUT_DIR")).join("script"))
+        .args(["-I", "../../bindings/c/include"]);
+    command
+}
+
+#[test]
+fn script_profile_only_has_readers() {
+    assert!(SCRIPT.contains(r#"#include "molecule_reader.h""#));
+    assert!(!SCRIPT.contains("molecule_builder.h"));
+    assert!(!SCRIPT.contains("MolBuilder_"));
+    assert!(!SCRIPT.contains("memcpy"));
+    // The offsets of tables are not copied into variable-length arrays.
+    assert!(!SCRIPT.contains("offsets["));
+    assert!(SCRIPT.contains("MolReader_TableA_verify"));
+}
+
+#[test]
+fn script_profile_is_freestanding() {
+    let out_dir = Path::new(env!("OUT_DIR")).join("script");
+    let object = out_dir.join("ci_test_script.o");
+    let status = cc()
+        .args(["-ffreestanding", "-fno-builtin", "-fstack-usage", "-c"])
+        .arg("c/ci_test_script.c")
+        .arg("-o")
+        .arg(&object)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    // No symbols of libc are required.
+    let output = Command::new("nm").arg("-u").arg(&object).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "");
+    // The stack usage of every function is known at compile time.
+    let usage = fs::read_to_string(out_dir.join("ci_test_script.su")).unwrap();
+    assert!(usage.contains("MolReader_TableA_verify"));
+    assert!(!usage.contains("dynamic"), "{}", usage);
+}
+
+#[test]
+fn script_profile_harness() {
+    let out_dir = Path::new(env!("OUT_DIR")).join("script");
+    let binary = out_dir.join("ci_test_script");
+    let status = cc()
+        .arg("c/ci_test_script.c")
+        .arg("-o")
+        .arg(&binary)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let status = Command::new(&binary).status().unwrap();
+    assert_eq!(status.code(), Some(0));
+}
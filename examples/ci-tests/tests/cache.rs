@@ -0,0 +1,172 @@
+#![cfg(feature = "cache")]
+
+use molecule::{cache::VerificationCache, error::VerificationError, prelude::*};
+
+use molecule_ci_tests::{checksum, types};
+
+fn bytes_vec(items: &[&[u8]]) -> types::BytesVec {
+    types::BytesVec::new_builder()
+        .extend(items.iter().map(|item| {
+            types::Bytes::new_builder()
+                .extend(item.iter().copied().map(Byte::new))
+                .build()
+        }))
+        .build()
+}
+
+#[test]
+fn cache_results() {
+    let mut cache = VerificationCache::new(8);
+    let value = bytes_vec(&[&[1, 2], &[3]]);
+    let copy = value.as_slice().to_vec();
+    let reader = cache
+        .verify::<types::BytesVecReader>(value.as_slice(), false)
+        .unwrap();
+    assert_eq!(reader.len(), 2);
+    // The same bytes in another buffer are found in the cache.
+    assert!(cache.verify::<types::BytesVecReader>(&copy, false).is_ok());
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 1, 1));
+    // Another type or mode is verified again.
+    assert!(cache.verify::<types::BytesReader>(&copy, false).is_err());
+    assert!(cache.verify::<types::BytesVecReader>(&copy, true).is_ok());
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 3, 3));
+
+    let broken = &copy[..copy.len() - 1];
+    let err = cache
+        .verify::<types::BytesVecReader>(broken, false)
+        .unwrap_err();
+    assert_eq!(
+        cache
+            .verify::<types::BytesVecReader>(broken, false)
+            .unwrap_err(),
+        err
+    );
+    assert_eq!(cache.hits(), 2);
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn evict_least_recently_used() {
+    let mut cache = VerificationCache::new(2);
+    let values = (0..3u8).map(|n| bytes_vec(&[&[n]])).collect::<Vec<_>>();
+    cache
+        .verify::<types::BytesVecReader>(values[0].as_slice(), false)
+        .unwrap();
+    cache
+        .verify::<types::BytesVecReader>(values[1].as_slice(), false)
+        .unwrap();
+    // The first one is used again, so the second one is evicted.
+    cache
+        .verify::<types::BytesVecReader>(values[0].as_slice(), false)
+        .unwrap();
+    cache
+        .verify::<types::BytesVecReader>(values[2].as_slice(), false)
+        .unwrap();
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.capacity(), 2);
+    let misses = cache.misses();
+    cache
+        .verify::<types::BytesVecReader>(values[0].as_slice(), false)
+        .unwrap();
+    assert_eq!(cache.misses(), misses);
+    cache
+        .verify::<types::BytesVecReader>(values[1].as_slice(), false)
+        .unwrap();
+    assert_eq!(cache.misses(), misses + 1);
+}
+
+#[test]
+fn cache_offsets() {
+    let mut cache = VerificationCache::new(4);
+    let value = bytes_vec(&[&[1, 2], &[], &[3]]);
+    let offsets = cache
+        .offsets::<types::BytesVecReader>(value.as_slice(), false)
+        .unwrap();
+    let reader = value.as_reader();
+    for idx in 0..reader.len() {
+        assert_eq!(offsets[idx]..offsets[idx + 1], reader.item_range(idx));
+    }
+    assert_eq!(offsets.len(), reader.len() + 1);
+    let again = cache
+        .offsets::<types::BytesVecReader>(value.as_slice(), false)
+        .unwrap();
+    assert!(std::sync::Arc::ptr_eq(&offsets, &again));
+
+    let empty = types::BytesVec::default();
+    let offsets = cache
+        .offsets::<types::BytesVecReader>(empty.as_slice(), false)
+        .unwrap();
+    assert_eq!(&offsets[..], &[4]);
+    assert!(cache
+        .offsets::<types::BytesVecReader>(&value.as_slice()[1..], false)
+        .is_err());
+}
+
+#[test]
+fn offsets_of_types_without_header() {
+    let mut cache = VerificationCache::new(8);
+    // The bytes of an array, a struct, a FixVec and an Option are verified, but they have no
+    // offsets, even if they look like a header.
+    let byte2 = types::Byte2::default();
+    let err = cache
+        .offsets::<types::Byte2Reader>(byte2.as_slice(), false)
+        .unwrap_err();
+    assert!(matches!(err, VerificationError::OffsetsNotMatch(..)));
+    let byte4 = [4, 0, 0, 0];
+    let err = cache
+        .offsets::<types::Byte4Reader>(&byte4, false)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        VerificationError::OffsetsNotMatch("Byte4Reader".to_owned())
+    );
+    let struct_c = [8, 0, 0, 0, 8, 0, 0, 0];
+    let err = cache
+        .offsets::<types::StructCReader>(&struct_c, false)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        VerificationError::OffsetsNotMatch("StructCReader".to_owned())
+    );
+    let bytes = types::Bytes::new_builder()
+        .extend([1, 2].iter().copied().map(Byte::new))
+        .build();
+    let err = cache
+        .offsets::<types::BytesReader>(bytes.as_slice(), false)
+        .unwrap_err();
+    assert!(matches!(err, VerificationError::OffsetsNotMatch(..)));
+    let none = types::BytesOpt::default();
+    assert!(cache
+        .offsets::<types::BytesOptReader>(none.as_slice(), false)
+        .is_err());
+    // The results of the verifications are still cached.
+    assert!(cache
+        .verify::<types::BytesReader>(bytes.as_slice(), false)
+        .is_ok());
+    assert_eq!(cache.hits(), 1);
+}
+
+#[test]
+fn checksum_records() {
+    let mut cache = VerificationCache::new(8);
+    let record = checksum::Record::new_builder()
+        .payload(checksum::Bytes::new_builder().push(Byte::new(1)).build())
+        .build();
+    // The cache verifies as `from_slice` does, so the reader is over the payload.
+    for _ in 0..2 {
+        let reader = cache
+            .verify::<checksum::RecordReader>(record.as_slice(), false)
+            .unwrap();
+        assert_eq!(reader.as_slice(), record.as_slice());
+        let offsets = cache
+            .offsets::<checksum::RecordReader>(record.as_slice(), false)
+            .unwrap();
+        assert_eq!(offsets.last(), Some(&record.as_slice().len()));
+    }
+    let sealed = record.to_bytes_with_checksum();
+    assert!(matches!(
+        cache.verify::<checksum::RecordReader>(&sealed, false),
+        Err(VerificationError::TotalSizeNotMatch(..))
+    ));
+}
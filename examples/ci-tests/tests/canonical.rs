@@ -0,0 +1,100 @@
+use codegen::Verifier;
+use molecule::{
+    canonical::{self, Canonical},
+    prelude::*,
+};
+
+use molecule_ci_tests::{testset, types::*};
+
+fn bytes(data: &[u8]) -> Bytes {
+    Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+/// A table of the fields, which is only verified in the compatible mode if there are extra
+/// fields.
+fn table(fields: &[&[u8]]) -> Vec<u8> {
+    let sizes = fields.iter().map(|field| field.len()).collect::<Vec<_>>();
+    let mut data = Vec::new();
+    canonical::write_header(&mut data, &sizes);
+    for field in fields {
+        data.extend_from_slice(field);
+    }
+    data
+}
+
+// The bytes which are built are canonical, in the generated code and in the dynamic layer.
+fn check_built<'r, R: Reader<'r> + Canonical>(reader: R) {
+    let data = reader.as_slice();
+    assert!(canonical::is_canonical::<R>(data).unwrap());
+    let entity = canonical::recanonicalize::<R>(data).unwrap();
+    assert_eq!(entity.as_slice(), data);
+    let verifier = Verifier::new("schemas/ci_tests.mol", <R::Entity as Entity>::NAME);
+    assert!(verifier.is_canonical(data).unwrap());
+    assert_eq!(verifier.recanonicalize(data).unwrap(), data);
+}
+
+#[test]
+fn built_values_are_canonical() {
+    macro_rules! check_default {
+        ($type:ident) => {
+            check_built($type::default().as_reader());
+        };
+    }
+    testset!(all, check_default);
+
+    let table5 = Table5::new_builder()
+        .f4(bytes(&[0x12, 0x34]))
+        .f5(BytesVec::new_builder().push(bytes(&[0x56])).build())
+        .build();
+    check_built(table5.as_reader());
+    let union = UnionA::new_builder()
+        .set(Table6::new_builder().f6(table5).build())
+        .build();
+    check_built(union.as_reader());
+}
+
+#[test]
+fn recanonicalize_drops_extra_fields() {
+    let table2 = Table2::new_builder().f1(0x12.into()).build();
+    let table1 = Table1::new_builder().f1(0x12.into()).build();
+    let default = TableA::default();
+    let reader = default.as_reader();
+    // The field `f1` of `TableA` is a `Table1` from a newer schema, then an extra field.
+    let fields: Vec<Vec<u8>> = vec![
+        reader.f0().as_slice().to_vec(),
+        table2.as_slice().to_vec(),
+        reader.f2().as_slice().to_vec(),
+        reader.f3().as_slice().to_vec(),
+        reader.f4().as_slice().to_vec(),
+        reader.f5().as_slice().to_vec(),
+        reader.f6().as_slice().to_vec(),
+        reader.f7().as_slice().to_vec(),
+        vec![0xff; 3],
+    ];
+    let fields = fields.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    let data = table(&fields);
+    assert!(TableAReader::from_slice(&data).is_err());
+    assert!(!canonical::is_canonical::<TableAReader>(&data).unwrap());
+
+    let expected = default.as_builder().f1(table1).build();
+    let canonical = canonical::recanonicalize::<TableAReader>(&data).unwrap();
+    assert_eq!(canonical.as_slice(), expected.as_slice());
+    assert_eq!(canonical.canonical_size(), expected.as_slice().len());
+    assert!(TableAReader::from_slice(canonical.as_slice()).is_ok());
+
+    let mut verifier = Verifier::new("schemas/ci_tests.mol", "TableA");
+    assert!(verifier.recanonicalize(&data).is_err());
+    verifier.compatible(true);
+    assert!(!verifier.is_canonical(&data).unwrap());
+    assert_eq!(verifier.recanonicalize(&data).unwrap(), expected.as_slice());
+}
+
+#[test]
+fn recanonicalize_rejects_unknown_items() {
+    let mut data = UnionA::default().as_slice().to_vec();
+    data[0] = 0xff;
+    assert!(canonical::is_canonical::<UnionAReader>(&data).is_err());
+    assert!(canonical::recanonicalize::<UnionAReader>(&data).is_err());
+}
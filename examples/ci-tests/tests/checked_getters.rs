@@ -0,0 +1,72 @@
+use molecule::{error::VerificationError, prelude::*};
+
+use molecule_ci_tests::types;
+
+fn table3() -> Vec<u8> {
+    types::Table3::new_builder()
+        .f1(0x12.into())
+        .build()
+        .as_slice()
+        .to_vec()
+}
+
+#[test]
+fn valid_fields() {
+    let data = table3();
+    let reader = types::Table3Reader::new_unchecked(&data);
+    assert_eq!(reader.try_f1().unwrap().as_slice(), &[0x12][..]);
+    assert_eq!(reader.try_f3().unwrap().as_slice(), reader.f3().as_slice());
+    let entity = types::Table3::new_unchecked(data.clone().into());
+    assert_eq!(entity.try_f2().unwrap().as_slice(), entity.f2().as_slice());
+    // The extra fields are accepted.
+    let reader = types::Table1Reader::new_unchecked(&data);
+    assert_eq!(reader.try_f1().unwrap().as_slice(), &[0x12][..]);
+}
+
+#[test]
+fn broken_fields() {
+    let mut data = table3();
+    // Move the offset of `f3`, so `f2` is too long and `f3` is too short.
+    data[12] += 1;
+    let reader = types::Table3Reader::new_unchecked(&data);
+    assert_eq!(reader.try_f1().unwrap().as_slice(), &[0x12][..]);
+    assert!(matches!(
+        reader.try_f2(),
+        Err(VerificationError::TotalSizeNotMatch(ref name, 4, 5)) if name == "Word2Reader"
+    ));
+    assert!(matches!(
+        reader.try_f3(),
+        Err(VerificationError::TotalSizeNotMatch(ref name, 6, 5)) if name == "StructAReader"
+    ));
+}
+
+#[test]
+fn truncated_table() {
+    let data = table3();
+    let reader = types::Table3Reader::new_unchecked(&data[..10]);
+    assert!(matches!(
+        reader.try_f1(),
+        Err(VerificationError::TotalSizeNotMatch(_, 27, 10))
+    ));
+    let reader = types::Table3Reader::new_unchecked(&data[..2]);
+    assert!(matches!(
+        reader.try_f3(),
+        Err(VerificationError::HeaderIsBroken(_, 4, 2))
+    ));
+}
+
+#[test]
+fn truncated_struct() {
+    let reader = types::StructAReader::new_unchecked(&[0x01, 0x02, 0x03]);
+    assert_eq!(reader.f1().as_slice(), &[0x01][..]);
+    assert!(matches!(
+        reader.try_f1(),
+        Err(VerificationError::TotalSizeNotMatch(_, 6, 3))
+    ));
+    let entity = types::StructA::new_unchecked(vec![0x01, 0x02, 0x03].into());
+    assert!(entity.try_f4().is_err());
+    assert_eq!(
+        types::StructA::default().try_f4().unwrap().as_slice(),
+        &[0, 0][..]
+    );
+}
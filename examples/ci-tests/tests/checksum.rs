@@ -0,0 +1,156 @@
+use std::{env, fs, panic};
+
+use codegen::{Compiler, Language};
+use molecule::{
+    checksum::{self, Algorithm, Sealed, CHECKSUM_SIZE},
+    error::VerificationError,
+    prelude::*,
+};
+
+use molecule_ci_tests::checksum::*;
+
+fn record() -> Record {
+    Record::new_builder()
+        .id(Uint32::new_unchecked(vec![1, 0, 0, 0].into()))
+        .payload(
+            Bytes::new_builder()
+                .extend([1, 2, 3].map(Byte::new))
+                .build(),
+        )
+        .build()
+}
+
+#[test]
+fn algorithms() {
+    assert_eq!(Algorithm::Crc32.checksum(b""), 0);
+    assert_eq!(Algorithm::Crc32.checksum(b"123456789"), 0xcbf4_3926);
+    assert_eq!(Algorithm::Xxh32.checksum(b""), 0x02cc_5d05);
+    assert_eq!(Algorithm::Xxh32.checksum(b"abc"), 0x32d1_53ff);
+    assert_eq!(
+        Algorithm::Xxh32.checksum(b"Nobody inspects the spammish repetition"),
+        0xe229_3b2f
+    );
+}
+
+#[test]
+fn sealed_records() {
+    let record = record();
+    let sealed = record.to_bytes_with_checksum();
+    assert_eq!(sealed.len(), record.as_slice().len() + CHECKSUM_SIZE);
+    assert_eq!(&sealed[..record.as_slice().len()], record.as_slice());
+    assert_eq!(
+        &sealed[record.as_slice().len()..],
+        &Algorithm::Crc32.checksum(record.as_slice()).to_le_bytes()
+    );
+    assert_eq!(
+        &record.clone().as_builder().build_with_checksum()[..],
+        &sealed[..]
+    );
+    assert_eq!(&Sealed::new(record.clone()).as_bytes()[..], &sealed[..]);
+
+    // The entities and the readers are over the payload, so they round trip.
+    let decoded = Record::from_slice(record.as_slice()).unwrap();
+    assert_eq!(decoded.as_slice(), record.as_slice());
+    let payload = checksum::open("Record", Algorithm::Crc32, &sealed).unwrap();
+    assert_eq!(
+        RecordReader::from_slice(payload).unwrap().as_slice(),
+        record.as_slice()
+    );
+
+    // The sealed bytes are kept, and the trailer is opened by `Sealed`.
+    let opened = Sealed::<Record>::from_slice(&sealed).unwrap();
+    assert_eq!(opened.as_slice(), &sealed[..]);
+    assert_eq!(opened.payload().as_slice(), record.as_slice());
+    assert_eq!(&opened.payload().payload().raw_data()[..], &[1, 2, 3][..]);
+    assert_eq!(opened.into_payload().as_slice(), record.as_slice());
+    assert!(Sealed::<Record>::from_compatible_slice(&sealed).is_ok());
+
+    let snapshot = Snapshot::new_builder().build_with_checksum();
+    assert_eq!(
+        checksum::open("Snapshot", Algorithm::Xxh32, &snapshot).unwrap(),
+        Snapshot::default().as_slice()
+    );
+    assert!(Sealed::<Snapshot>::from_slice(&snapshot).is_ok());
+}
+
+#[test]
+fn corrupted_records() {
+    let record = record();
+    let sealed = record.to_bytes_with_checksum();
+    let expected = Algorithm::Crc32.checksum(record.as_slice());
+
+    let mut corrupted = sealed.to_vec();
+    corrupted[record.as_slice().len() - 1] ^= 0x01;
+    let actual = Algorithm::Crc32.checksum(&corrupted[..record.as_slice().len()]);
+    assert_eq!(
+        Sealed::<Record>::from_slice(&corrupted).unwrap_err(),
+        VerificationError::ChecksumNotMatch("Record".to_owned(), expected, actual)
+    );
+
+    // The payload without the trailer is not a sealed record.
+    assert!(matches!(
+        Sealed::<Record>::from_slice(record.as_slice()),
+        Err(VerificationError::ChecksumNotMatch(..))
+    ));
+    assert_eq!(
+        Sealed::<Record>::from_slice(&sealed[..2]).unwrap_err(),
+        VerificationError::HeaderIsBroken("Record".to_owned(), CHECKSUM_SIZE, 2)
+    );
+
+    // A valid trailer of a broken payload fails the verification of the structure.
+    let sealed = checksum::seal(Algorithm::Crc32, &record.as_slice()[1..]);
+    assert!(matches!(
+        Sealed::<Record>::from_slice(&sealed),
+        Err(VerificationError::TotalSizeNotMatch(..))
+    ));
+}
+
+#[test]
+fn invalid_checksums() {
+    let dir = env::temp_dir().join("molecule-ci-tests-checksum");
+    fs::create_dir_all(&dir).unwrap();
+    for (name, schema, expected) in &[
+        (
+            "unknown",
+            "@checksum(md5) table T {}",
+            "the checksum should be crc32 or xxh32, but it is md5",
+        ),
+        (
+            "no-algorithm",
+            "@checksum table T {}",
+            "the attribute `@checksum` requires an algorithm",
+        ),
+        (
+            "vector",
+            "@checksum(crc32) vector Bytes <byte>;",
+            "the attribute `@checksum` is only for tables",
+        ),
+        (
+            "struct",
+            "@checksum(crc32) struct S { a: byte, }",
+            "the attribute `@checksum` is only for tables",
+        ),
+        (
+            "nested",
+            "@checksum(crc32) table T {} vector Ts <T>;",
+            "the table (T) has a checksum trailer, so it can't be used in (Ts)",
+        ),
+    ] {
+        let path = dir.join(format!("{}.mol", name));
+        fs::write(&path, schema).unwrap();
+        let err = panic::catch_unwind(|| {
+            Compiler::new()
+                .language(Language::Rust)
+                .file_path(&path)
+                .out_dir(&dir)
+                .run();
+        })
+        .unwrap_err();
+        let message = err
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| err.downcast_ref::<&str>().map(|s| (*s).to_owned()))
+            .unwrap();
+        assert_eq!(&message, expected);
+    }
+}
@@ -0,0 +1,58 @@
+use molecule::prelude::*;
+
+use molecule_ci_tests::types;
+
+fn struct_i(n: u8) -> types::StructI {
+    types::StructI::new_builder()
+        .f1(types::Byte3::new_builder()
+            .set([n, n + 1, n + 2].map(Byte::new))
+            .build())
+        .f2(Byte::new(n * 10))
+        .build()
+}
+
+fn struct_i_vec() -> types::StructIVec {
+    types::StructIVec::new_builder()
+        .extend((1..=3).map(struct_i))
+        .build()
+}
+
+#[test]
+fn iterate_fields() {
+    let vec = struct_i_vec();
+    let reader = vec.as_reader();
+    let f1 = reader
+        .iter_f1()
+        .map(|field| field.as_slice().to_vec())
+        .collect::<Vec<_>>();
+    assert_eq!(f1, vec![vec![1, 2, 3], vec![2, 3, 4], vec![3, 4, 5]]);
+    let f2 = reader.iter_f2().map(u8::from).collect::<Vec<_>>();
+    assert_eq!(f2, vec![10, 20, 30]);
+    // Each field is the same as the field of the item.
+    for (item, field) in reader.iter().zip(reader.iter_f1()) {
+        assert_eq!(item.f1().as_slice(), field.as_slice());
+    }
+    assert_eq!(vec.iter_f2().count(), 3);
+}
+
+#[test]
+fn column_is_exact_and_double_ended() {
+    let vec = struct_i_vec();
+    let reader = vec.as_reader();
+    let mut column = reader.iter_f2();
+    assert_eq!(column.len(), 3);
+    assert_eq!(column.next_back().map(u8::from), Some(30));
+    assert_eq!(column.next().map(u8::from), Some(10));
+    assert_eq!(column.len(), 1);
+    assert_eq!(column.next().map(u8::from), Some(20));
+    assert!(column.next().is_none());
+    assert!(column.next_back().is_none());
+    assert_eq!(reader.iter_f2().nth(2).map(u8::from), Some(30));
+}
+
+#[test]
+fn empty_vector() {
+    let vec = types::StructIVec::default();
+    assert_eq!(vec.as_reader().iter_f1().len(), 0);
+    assert!(vec.iter_f2().next().is_none());
+}
@@ -0,0 +1,102 @@
+//! The helpers of the tests of the code generated for the other languages, which run the code
+//! by the toolchains of the languages.
+//!
+//! The round trips are ignored by default, since the toolchains are not installed everywhere;
+//! run with `ROUND_TRIP=1` to run them, then a missing toolchain fails the test.
+
+#![allow(dead_code)]
+
+use std::{
+    collections::BTreeSet,
+    fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use molecule::prelude::*;
+use molecule_ci_tests::types::*;
+
+/// The bytes which are decoded by the generated code.
+pub struct Samples {
+    /// A `Table6` whose fields are not empty.
+    pub table6: Vec<u8>,
+    /// A `UnionA` of `Bytes`.
+    pub union_a: Vec<u8>,
+    /// The `Table6` without its last byte, which should be rejected.
+    pub broken: Vec<u8>,
+}
+
+fn bytes(data: &[u8]) -> Bytes {
+    Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+pub fn samples() -> Samples {
+    let table5 = Table5::new_builder()
+        .f1(Byte::new(5))
+        .f4(bytes(&[6]))
+        .build();
+    let table6 = Table6::new_builder()
+        .f1(Byte::new(0x12))
+        .f2(Word2::from_slice(&[0x34, 0x12, 0x78, 0x56]).unwrap())
+        .f3(StructA::from_slice(&[1, 2, 3, 4, 5, 6]).unwrap())
+        .f4(bytes(&[7, 8, 9]))
+        .f5(BytesVec::new_builder()
+            .push(bytes(&[0x0a]))
+            .push(bytes(&[0x0b, 0x0c]))
+            .build())
+        .f6(table5)
+        .build();
+    let union_a = UnionA::new_builder().set(bytes(&[1, 2, 3])).build();
+    let table6 = table6.as_slice().to_vec();
+    Samples {
+        broken: table6[..table6.len() - 1].to_vec(),
+        table6,
+        union_a: union_a.as_slice().to_vec(),
+    }
+}
+
+pub fn hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Panics if a name, such as a declaration found in the generated code, appears twice.
+pub fn assert_unique<'a>(names: impl IntoIterator<Item = &'a str>) {
+    let mut seen = BTreeSet::new();
+    for name in names {
+        assert!(seen.insert(name), "`{}` is declared twice", name);
+    }
+}
+
+/// Creates an empty directory for the language in the output of the build script, with the
+/// files in it.
+pub fn workspace(language: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = Path::new(env!("OUT_DIR")).join("round_trip").join(language);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for (name, content) in files {
+        fs::write(dir.join(name), content).unwrap();
+    }
+    dir
+}
+
+/// Runs the program in the directory, and returns what it prints.
+pub fn run(dir: &Path, program: &str, args: &[&str]) -> String {
+    let output = match Command::new(program).args(args).current_dir(dir).output() {
+        Ok(output) => output,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            panic!("{} isn't installed, it's required by ROUND_TRIP=1", program)
+        }
+        Err(err) => panic!("failed to run {}: {}", program, err),
+    };
+    assert!(
+        output.status.success(),
+        "{} {:?} failed:\n{}{}",
+        program,
+        args,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
@@ -0,0 +1,42 @@
+use molecule::prelude::*;
+
+use molecule_ci_tests::{compact::types, types as full};
+
+const COMPACT_TYPES: &str = include_str!(concat!(env!("OUT_DIR"), "/compact/ci_tests.rs"));
+
+#[test]
+fn no_nth_accessors() {
+    assert!(!COMPACT_TYPES.contains("fn nth0"));
+    assert!(COMPACT_TYPES.contains("fn set_nth"));
+}
+
+#[test]
+fn get_and_set_by_index() {
+    let word = types::Word::new_builder()
+        .set_nth(0, 1.into())
+        .set_nth(1, 2.into())
+        .build();
+    assert_eq!(word.as_slice(), &[1, 2][..]);
+    let words = types::Word3::new_builder().set_nth(2, word.clone()).build();
+    assert_eq!(words.get(2).unwrap().as_slice(), word.as_slice());
+    assert_eq!(words.as_reader().get_unchecked(0).as_slice(), &[0, 0][..]);
+    assert!(words.get(types::Word3::ITEM_COUNT).is_none());
+    assert_eq!(
+        format!("{}", words),
+        "Word3 [Word(0x0000), Word(0x0000), Word(0x0102)]"
+    );
+}
+
+#[test]
+fn same_bytes_as_full() {
+    let words = types::Word3::new_builder()
+        .set_nth(1, [3, 4].into())
+        .build();
+    let full = full::Word3::new_builder().nth1([3, 4].into()).build();
+    assert_eq!(words.as_slice(), full.as_slice());
+    assert_eq!(full.nth1().as_slice(), words.get_unchecked(1).as_slice());
+    assert_eq!(
+        words.clone().as_builder().build().as_slice(),
+        words.as_slice()
+    );
+}
@@ -96,3 +96,13 @@ fn compatible_table() {
     compatible_table_test!(compatible, T5, [t0, t1, t2, t3, t4, (), t5, t6]);
     compatible_table_test!(compatible, T6, [t0, t1, t2, t3, t4, t5, (), t6]);
 }
+
+#[test]
+fn compatible_empty_table_with_broken_header() {
+    use slices::u8_slice as s;
+    use types::Table0 as T0;
+
+    assert!(T0::from_compatible_slice(s!("0x0c000000_08000000_0c000000")).is_ok());
+    assert!(T0::from_compatible_slice(s!("0x0b000000_dc1348c0_3164d1")).is_err());
+    assert!(T0::from_compatible_slice(s!("0x06000000_0800")).is_err());
+}
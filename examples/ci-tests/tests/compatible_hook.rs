@@ -0,0 +1,59 @@
+use std::sync::Mutex;
+
+use molecule::{
+    compatible::{self, CompatibleEvent},
+    prelude::*,
+};
+
+use molecule_ci_tests::types;
+
+static EVENTS: Mutex<Vec<CompatibleEvent>> = Mutex::new(Vec::new());
+
+fn record(event: &CompatibleEvent) {
+    EVENTS.lock().unwrap().push(*event);
+}
+
+// The hook is global, so all checks are in one test.
+#[test]
+fn compatible_hook() {
+    let t2 = types::Table2::default();
+    let t3 = types::Table3::default();
+    let mut unknown_item = 8u32.to_le_bytes().to_vec();
+    unknown_item.push(0);
+
+    types::Table1::from_compatible_slice(t3.as_slice()).unwrap();
+    assert!(EVENTS.lock().unwrap().is_empty());
+
+    compatible::set_hook(Some(record));
+    types::Table1::from_slice(t3.as_slice()).unwrap_err();
+    types::Table2::from_compatible_slice(t2.as_slice()).unwrap();
+    assert!(EVENTS.lock().unwrap().is_empty());
+
+    types::Table1::from_compatible_slice(t3.as_slice()).unwrap();
+    types::Table0::from_compatible_slice(t2.as_slice()).unwrap();
+    types::UnionA::from_compatible_slice(&unknown_item).unwrap_err();
+    compatible::set_hook(None);
+    types::Table1::from_compatible_slice(t3.as_slice()).unwrap();
+
+    let events = EVENTS.lock().unwrap();
+    assert_eq!(
+        *events,
+        vec![
+            CompatibleEvent::ExtraFields {
+                type_name: "Table1",
+                expected: 1,
+                actual: 3,
+            },
+            CompatibleEvent::ExtraFields {
+                type_name: "Table0",
+                expected: 0,
+                actual: 2,
+            },
+            CompatibleEvent::UnknownItem {
+                type_name: "UnionA",
+                item_count: 8,
+                item_id: 8,
+            },
+        ]
+    );
+}
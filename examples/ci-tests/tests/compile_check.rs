@@ -0,0 +1,247 @@
+//! Compile the generated code of each language by its toolchain, so the generated code has no
+//! errors and no warnings.
+//!
+//! A toolchain which isn't installed is skipped, so the checks only run where the toolchains are
+//! present, such as in CI; the languages without a compiler, such as SQL, are only covered by
+//! the snapshots.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use codegen::{CProfile, Compiler, Language};
+
+const SCHEMAS: &[&str] = &["schemas/snapshots/corpus.mol", "schemas/ci_tests.mol"];
+
+struct Toolchain {
+    name: &'static str,
+    language: Language,
+    extension: &'static str,
+    /// The command which checks a generated file, from the file and a directory for the outputs.
+    command: fn(&Path, &Path) -> Command,
+}
+
+fn c_command(file: &Path, _: &Path) -> Command {
+    let mut command = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_owned()));
+    command
+        .args(["-Wall", "-Wextra", "-Werror", "-fsyntax-only", "-I"])
+        .arg(file.parent().unwrap())
+        .args(["-I", "../../bindings/c/include", "-x", "c"])
+        .arg(file);
+    command
+}
+
+fn rust_command(file: &Path, out_dir: &Path) -> Command {
+    // The test is in the directory of the dependencies, which has the built molecule crate.
+    let deps = env::current_exe().unwrap().parent().unwrap().to_owned();
+    let molecule = fs::read_dir(&deps)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.starts_with("libmolecule-") && name.ends_with(".rlib")
+        })
+        .max_by_key(|path| path.metadata().unwrap().modified().unwrap())
+        .expect("the molecule crate is built");
+    let mut command = Command::new("rustc");
+    command
+        .args([
+            "--edition",
+            "2018",
+            "--crate-type",
+            "lib",
+            "--emit",
+            "metadata",
+        ])
+        .args(["-D", "warnings", "-L"])
+        .arg(format!("dependency={}", deps.display()))
+        .arg("--extern")
+        .arg(format!("molecule={}", molecule.display()))
+        .arg("--out-dir")
+        .arg(out_dir)
+        .arg(file);
+    command
+}
+
+fn lua_command(file: &Path, _: &Path) -> Command {
+    let mut command = Command::new("luac");
+    command.arg("-p").arg(file);
+    command
+}
+
+fn ocaml_command(file: &Path, out_dir: &Path) -> Command {
+    let mut command = Command::new("ocamlc");
+    command
+        .args(["-c", "-o"])
+        .arg(out_dir.join(file.file_stem().unwrap()))
+        .arg(file);
+    command
+}
+
+fn haskell_command(file: &Path, out_dir: &Path) -> Command {
+    let mut command = Command::new("ghc");
+    command
+        .args(["-fno-code", "-Wall", "-Werror", "-outputdir"])
+        .arg(out_dir)
+        .arg(file);
+    command
+}
+
+fn zig_command(file: &Path, _: &Path) -> Command {
+    let mut command = Command::new("zig");
+    command.arg("ast-check").arg(file);
+    command
+}
+
+fn scala_command(file: &Path, out_dir: &Path) -> Command {
+    let mut command = Command::new("scalac");
+    command.arg("-d").arg(out_dir).arg(file);
+    command
+}
+
+fn elixir_command(file: &Path, out_dir: &Path) -> Command {
+    let mut command = Command::new("elixirc");
+    command
+        .args(["--warnings-as-errors", "-o"])
+        .arg(out_dir)
+        .arg(file);
+    command
+}
+
+fn solidity_command(file: &Path, _: &Path) -> Command {
+    let mut command = Command::new("solc");
+    command.arg(file);
+    command
+}
+
+const TOOLCHAINS: &[Toolchain] = &[
+    Toolchain {
+        name: "cc",
+        language: Language::C,
+        extension: "h",
+        command: c_command,
+    },
+    Toolchain {
+        name: "rustc",
+        language: Language::Rust,
+        extension: "rs",
+        command: rust_command,
+    },
+    Toolchain {
+        name: "luac",
+        language: Language::Lua,
+        extension: "lua",
+        command: lua_command,
+    },
+    Toolchain {
+        name: "luac",
+        language: Language::Wireshark,
+        extension: "dissector.lua",
+        command: lua_command,
+    },
+    Toolchain {
+        name: "ocamlc",
+        language: Language::OCaml,
+        extension: "ml",
+        command: ocaml_command,
+    },
+    Toolchain {
+        name: "ghc",
+        language: Language::Haskell,
+        extension: "hs",
+        command: haskell_command,
+    },
+    Toolchain {
+        name: "zig",
+        language: Language::Zig,
+        extension: "zig",
+        command: zig_command,
+    },
+    Toolchain {
+        name: "scalac",
+        language: Language::Scala,
+        extension: "scala",
+        command: scala_command,
+    },
+    Toolchain {
+        name: "elixirc",
+        language: Language::Elixir,
+        extension: "ex",
+        command: elixir_command,
+    },
+    Toolchain {
+        name: "solc",
+        language: Language::Solidity,
+        extension: "sol",
+        command: solidity_command,
+    },
+];
+
+fn is_installed(toolchain: &Toolchain) -> bool {
+    let name = if toolchain.name == "cc" {
+        env::var("CC").unwrap_or_else(|_| "cc".to_owned())
+    } else {
+        toolchain.name.to_owned()
+    };
+    Command::new(name).arg("--version").output().is_ok()
+}
+
+/// Generate the code of the schema, and return the path of the generated file.
+fn generate(compiler: &mut Compiler, schema: &str, out_dir: &Path, extension: &str) -> PathBuf {
+    fs::create_dir_all(out_dir).unwrap();
+    compiler.out_dir(out_dir).file_path(schema).run();
+    let stem = Path::new(schema).file_stem().unwrap().to_str().unwrap();
+    out_dir.join(format!("{}.{}", stem, extension))
+}
+
+/// Check the generated file, and return the errors and the warnings if there are any.
+fn check(toolchain: &Toolchain, file: &Path, out_dir: &Path) -> Option<String> {
+    let output = (toolchain.command)(file, out_dir).output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if output.status.success() && stderr.trim().is_empty() {
+        None
+    } else {
+        Some(format!(
+            "{} failed on {}:\n{}{}",
+            toolchain.name,
+            file.display(),
+            String::from_utf8_lossy(&output.stdout),
+            stderr
+        ))
+    }
+}
+
+#[test]
+fn generated_code_compiles_without_warnings() {
+    let out_dir = Path::new(env!("OUT_DIR")).join("compile_check");
+    let mut failures = Vec::new();
+    for toolchain in TOOLCHAINS {
+        if !is_installed(toolchain) {
+            eprintln!(
+                "skip the {} code, since {} isn't installed",
+                toolchain.extension, toolchain.name
+            );
+            continue;
+        }
+        for schema in SCHEMAS {
+            let dir = out_dir.join(toolchain.extension);
+            let mut compiler = Compiler::new();
+            compiler.language(toolchain.language);
+            let file = generate(&mut compiler, schema, &dir, toolchain.extension);
+            failures.extend(check(toolchain, &file, &dir));
+            if matches!(toolchain.language, Language::C) {
+                // The script profile is another set of functions.
+                let dir = out_dir.join("script");
+                let mut compiler = Compiler::new();
+                compiler.language(Language::C).c_profile(CProfile::Script);
+                let file = generate(&mut compiler, schema, &dir, toolchain.extension);
+                failures.extend(check(toolchain, &file, &dir));
+            }
+        }
+    }
+    if !failures.is_empty() {
+        panic!("{}", failures.join("\n"));
+    }
+}
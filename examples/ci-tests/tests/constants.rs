@@ -0,0 +1,26 @@
+use molecule::prelude::*;
+
+use molecule_ci_tests::types::{self, constants};
+
+#[test]
+fn union_item_ids() {
+    assert_eq!(constants::union_a::NAME, "UnionA");
+    assert_eq!(constants::union_a::ITEM_BYTE, 0);
+    assert_eq!(constants::union_a::ITEM_TABLE6_OPT, 7);
+    let union = types::UnionA::new_builder()
+        .set(types::Table6Opt::default())
+        .build();
+    assert_eq!(union.item_id(), constants::union_a::ITEM_TABLE6_OPT);
+}
+
+#[test]
+fn field_indexes() {
+    assert_eq!(constants::struct_a::FIELD_F1, 0);
+    assert_eq!(constants::struct_a::FIELD_F4, 3);
+    assert_eq!(constants::table_a::FIELD_F7, 7);
+    assert_eq!(
+        constants::all_in_one::FIELD_F73 + 1,
+        types::AllInOne::FIELD_COUNT
+    );
+    assert_eq!(constants::bytes::NAME, types::Bytes::NAME);
+}
@@ -0,0 +1,151 @@
+use std::{env, fs, panic};
+
+use codegen::{Compiler, Language};
+use molecule::{error::VerificationError, prelude::*};
+
+use molecule_ci_tests::constraints::*;
+
+fn uint32(value: u32) -> Uint32 {
+    value.to_le_bytes().into()
+}
+
+fn range(start: u32, end: u32) -> Range {
+    Range::new_builder()
+        .start(uint32(start))
+        .end(uint32(end))
+        .build()
+}
+
+fn bytes(data: &[u8]) -> Bytes {
+    Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+fn frame_builder() -> FrameBuilder {
+    Frame::new_builder()
+        .declared_len(uint32(3))
+        .payload(bytes(&[1, 2, 3]))
+        .kind(0.into())
+        .body(Payload::new_builder().set(bytes(&[4])).build())
+        .range(range(1, 2))
+}
+
+fn constraint_not_held<T: std::fmt::Debug>(
+    result: Result<T, VerificationError>,
+    reader: &str,
+    constraint: &str,
+) {
+    match result {
+        Err(VerificationError::ConstraintNotHeld(ref name, ref text))
+            if name == reader && text == constraint => {}
+        _ => panic!("{:?} should break the constraint `{}`", result, constraint),
+    }
+}
+
+#[test]
+fn valid() {
+    let frame = frame_builder().build();
+    Frame::from_slice(frame.as_slice()).unwrap();
+    FrameReader::from_slice_linear(frame.as_slice()).unwrap();
+    Range::from_slice(range(2, 2).as_slice()).unwrap();
+}
+
+#[test]
+fn struct_constraints() {
+    let range = range(2, 1);
+    constraint_not_held(
+        Range::from_slice(range.as_slice()),
+        "RangeReader",
+        "start <= end",
+    );
+    let frame = frame_builder().range(range).build();
+    constraint_not_held(
+        Frame::from_slice(frame.as_slice()),
+        "RangeReader",
+        "start <= end",
+    );
+}
+
+#[test]
+fn table_constraints() {
+    let frame = frame_builder().declared_len(uint32(4)).build();
+    constraint_not_held(
+        Frame::from_slice(frame.as_slice()),
+        "FrameReader",
+        "len(payload) == declared_len",
+    );
+    constraint_not_held(
+        FrameReader::from_slice_linear(frame.as_slice()),
+        "FrameReader",
+        "len(payload) == declared_len",
+    );
+    let err = Frame::from_slice(frame.as_slice()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "FrameReader doesn't satisfy the constraint `len(payload) == declared_len`"
+    );
+
+    let words = Words::new_builder().push([0, 0].into()).build();
+    let frame = frame_builder()
+        .body(Payload::new_builder().set(words).build())
+        .build();
+    constraint_not_held(
+        Frame::from_compatible_slice(frame.as_slice()),
+        "FrameReader",
+        "item_id(body) == kind",
+    );
+
+    let names = Names::new_builder()
+        .extend((0..4).map(|i| bytes(&[i])))
+        .build();
+    let frame = frame_builder().names(names).build();
+    constraint_not_held(
+        Frame::from_slice(frame.as_slice()),
+        "FrameReader",
+        "len(names) <= 3",
+    );
+}
+
+#[test]
+fn invalid_constraints() {
+    let dir = env::temp_dir().join("molecule-ci-tests-constraints");
+    fs::create_dir_all(&dir).unwrap();
+    for (name, constraint, expected) in &[
+        (
+            "unknown",
+            "assert len(data) == 1;",
+            "refers to an unknown field (data)",
+        ),
+        (
+            "len",
+            "assert len(size) == 1;",
+            "isn't applicable to Uint32",
+        ),
+        (
+            "item_id",
+            "assert item_id(bytes) == 0;",
+            "isn't applicable to Bytes",
+        ),
+        ("value", "assert bytes == 0;", "isn't applicable to Bytes"),
+        ("literals", "assert 1 == 1;", "doesn't refer to any field"),
+    ] {
+        let path = dir.join(format!("{}.mol", name));
+        let schema = format!(
+            "array Uint32 [byte; 4];\nvector Bytes <byte>;\n\
+             table T {{ size: Uint32, bytes: Bytes, {} }}\n",
+            constraint
+        );
+        fs::write(&path, schema).unwrap();
+        let err = panic::catch_unwind(|| {
+            Compiler::new()
+                .language(Language::Rust)
+                .file_path(&path)
+                .out_dir(&dir)
+                .run();
+        })
+        .unwrap_err();
+        let message = err.downcast_ref::<String>().unwrap();
+        assert!(message.contains(expected), "{}", message);
+    }
+}
@@ -0,0 +1,61 @@
+use molecule::{
+    checksum::Algorithm,
+    content_hash::{self, ContentHash, Hashers, Multihash, IDENTITY},
+    prelude::*,
+};
+
+use molecule_ci_tests::types::*;
+
+/// The multihash code of CRC-32, which is only for the tests.
+const CRC32: u64 = 0x0132;
+
+fn crc32(data: &[u8]) -> Vec<u8> {
+    Algorithm::Crc32.checksum(data).to_be_bytes().to_vec()
+}
+
+static HASHERS: Hashers = Hashers(&[(CRC32, crc32)]);
+
+fn bytes() -> Bytes {
+    Bytes::new_builder()
+        .extend([1, 2, 3].map(Byte::new))
+        .build()
+}
+
+#[test]
+fn identity() {
+    let bytes = bytes();
+    let hash = bytes.content_hash(IDENTITY).unwrap();
+    assert_eq!(hash.digest, bytes.as_slice());
+    assert_eq!(hash.to_bytes(), [&[0, 7][..], bytes.as_slice()].concat());
+    let cid = bytes.cid(IDENTITY).unwrap();
+    assert_eq!(cid.to_string(), "bafkqabydaaaaaaicam");
+    assert_eq!(bytes.as_reader().cid(IDENTITY), Some(cid));
+}
+
+#[test]
+fn registered_hashers() {
+    let bytes = bytes();
+    content_hash::set_hashers(None);
+    assert_eq!(bytes.content_hash(CRC32), None);
+    content_hash::set_hashers(Some(&HASHERS));
+    assert_eq!(
+        bytes.content_hash(CRC32),
+        Some(Multihash {
+            code: CRC32,
+            digest: vec![0x06, 0x79, 0x9c, 0xec],
+        })
+    );
+    // The code is longer than a byte in the varint.
+    assert_eq!(
+        bytes.content_hash(CRC32).unwrap().to_bytes(),
+        [0xb2, 0x02, 0x04, 0x06, 0x79, 0x9c, 0xec]
+    );
+    assert_eq!(bytes.cid(CRC32).unwrap().to_string(), "bafk3eaqeaz4zz3a");
+    // The values of the same bytes have the same hash, whatever their types are.
+    let bytes_opt = BytesOpt::new_builder().set(Some(bytes.clone())).build();
+    assert_eq!(
+        bytes_opt.as_reader().content_hash(CRC32),
+        bytes.content_hash(CRC32)
+    );
+    content_hash::set_hashers(None);
+}
@@ -0,0 +1,128 @@
+use codegen::Minimizer;
+use molecule::{error::VerificationError, prelude::*};
+use slices::u8_slice as s;
+
+use molecule_ci_tests::count_size::*;
+
+const OPENAPI: &str = include_str!(concat!(env!("OUT_DIR"), "/count_size.json"));
+
+fn tiny_bytes(data: &[u8]) -> TinyBytes {
+    TinyBytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+#[test]
+fn constants() {
+    assert_eq!(TinyBytes::COUNT_SIZE, 1);
+    assert_eq!(ShortByte2Vec::COUNT_SIZE, 2);
+    assert_eq!(Bytes::COUNT_SIZE, molecule::NUMBER_SIZE);
+}
+
+#[test]
+fn default_has_short_header() {
+    assert_eq!(TinyBytes::default().as_slice(), s!("0x00"));
+    assert_eq!(ShortByte2Vec::default().as_slice(), s!("0x0000"));
+    assert_eq!(
+        Packet::default().as_slice(),
+        s!("0x17000000_10000000_11000000_13000000_00_0000_00000000")
+    );
+}
+
+#[test]
+fn build_and_read() {
+    let tag = tiny_bytes(&[0x12, 0x34, 0x56]);
+    assert_eq!(tag.as_slice(), s!("0x03_123456"));
+    assert_eq!(tag.total_size(), 4);
+    assert_eq!(tag.len(), 3);
+    assert_eq!(tag.as_reader().raw_data(), &[0x12, 0x34, 0x56][..]);
+    assert_eq!(tag.get(2).map(Into::<u8>::into), Some(0x56));
+
+    let items = ShortByte2Vec::new_builder()
+        .push(Byte2::new_unchecked(vec![1, 2].into()))
+        .push(Byte2::new_unchecked(vec![3, 4].into()))
+        .build();
+    assert_eq!(items.as_slice(), s!("0x0200_0102_0304"));
+    assert_eq!(items.total_size(), 6);
+    assert_eq!(items.item_range(1), 4..6);
+
+    let packet = Packet::new_builder().tag(tag).items(items).build();
+    let reader = PacketReader::from_slice(packet.as_slice()).unwrap();
+    assert_eq!(reader.tag().raw_data(), &[0x12, 0x34, 0x56][..]);
+    assert_eq!(reader.items().len(), 2);
+    assert_eq!(reader.items().get(1).unwrap().as_slice(), &[3, 4][..]);
+    assert!(reader.payload().is_empty());
+}
+
+#[test]
+fn verify_short_header() {
+    assert!(TinyBytesReader::verify(s!("0x00"), false).is_ok());
+    assert!(TinyBytesReader::verify(s!("0x02_0102"), false).is_ok());
+    assert!(matches!(
+        TinyBytesReader::verify(&[], false),
+        Err(VerificationError::HeaderIsBroken(_, 1, 0))
+    ));
+    assert!(matches!(
+        TinyBytesReader::verify(s!("0x02_01"), false),
+        Err(VerificationError::TotalSizeNotMatch(_, 3, 2))
+    ));
+    // A standard header is not accepted.
+    assert!(TinyBytesReader::verify(s!("0x02000000_0102"), false).is_err());
+    assert!(matches!(
+        ShortByte2VecReader::verify(s!("0x0100_01"), false),
+        Err(VerificationError::TotalSizeNotMatch(_, 4, 3))
+    ));
+}
+
+#[test]
+fn verify_by_schema() {
+    let input = s!("0x05_0102");
+    let (output, err) = Minimizer::new()
+        .file_path("schemas/count_size.mol")
+        .type_name("TinyBytes")
+        .minimize(input)
+        .unwrap();
+    let expected = TinyBytesReader::verify(&output, false).unwrap_err();
+    assert_eq!(err.kind(), expected.kind());
+    assert_eq!(err.type_name(), expected.type_name());
+    assert!(Minimizer::new()
+        .file_path("schemas/count_size.mol")
+        .type_name("ShortByte2Vec")
+        .minimize(s!("0x0100_0102"))
+        .is_err());
+}
+
+#[test]
+fn the_largest_count_fits() {
+    let data = vec![0xff; 255];
+    let tag = tiny_bytes(&data);
+    assert_eq!(tag.as_slice()[0], 0xff);
+    assert_eq!(tag.as_reader().raw_data(), &data[..]);
+    assert!(TinyBytesReader::verify(tag.as_slice(), false).is_ok());
+}
+
+#[test]
+#[should_panic(expected = "too many items")]
+fn too_many_items() {
+    tiny_bytes(&[0; 256]);
+}
+
+#[test]
+fn pack_and_unpack_count() {
+    assert_eq!(molecule::unpack_count(s!("0x0102"), 1), 0x01);
+    assert_eq!(molecule::unpack_count(s!("0x0102"), 2), 0x0201);
+    assert_eq!(molecule::pack_count(0x0201, 2), Some([1, 2, 0, 0]));
+    assert_eq!(molecule::pack_count(0x0100, 1), None);
+    assert_eq!(molecule::pack_count(usize::MAX, 4), None);
+}
+
+#[test]
+fn openapi_marks_non_standard() {
+    let document: serde_json::Value = serde_json::from_str(OPENAPI).unwrap();
+    let schemas = &document["components"]["schemas"];
+    assert_eq!(schemas["TinyBytes"]["maxLength"], 2 + 2 * 255);
+    assert_eq!(schemas["TinyBytes"]["x-molecule-count-size"], 1);
+    assert_eq!(schemas["ShortByte2Vec"]["maxItems"], 65535);
+    assert_eq!(schemas["ShortByte2Vec"]["x-molecule-count-size"], 2);
+    assert!(schemas["Bytes"].get("x-molecule-count-size").is_none());
+}
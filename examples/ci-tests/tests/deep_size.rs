@@ -0,0 +1,82 @@
+use codegen::Explorer;
+use molecule::{deep_size::DeepSize, prelude::*};
+
+use molecule_ci_tests::{testset, types::*};
+
+fn bytes(data: &[u8]) -> Bytes {
+    Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+// The breakdown of the dynamic layer is the same as the generated one, and it covers all bytes.
+macro_rules! check_breakdown {
+    ($explorer:ident, $type:ident, $value:expr) => {
+        let value = $value;
+        let breakdown = value.deep_size_breakdown();
+        assert_eq!(breakdown.total(), value.as_slice().len());
+        assert_eq!(
+            $explorer
+                .size_breakdown(stringify!($type), value.as_slice(), false)
+                .unwrap(),
+            breakdown
+        );
+    };
+}
+
+#[test]
+fn same_as_explorer() {
+    let explorer = Explorer::new("schemas/ci_tests.mol");
+    macro_rules! check_default {
+        ($type:ident) => {
+            check_breakdown!(explorer, $type, $type::default());
+        };
+    }
+    testset!(all, check_default);
+
+    let bytes_vec = BytesVec::new_builder()
+        .push(bytes(&[1, 2]))
+        .push(bytes(&[]))
+        .build();
+    check_breakdown!(explorer, BytesVec, bytes_vec);
+    check_breakdown!(
+        explorer,
+        UnionA,
+        UnionA::new_builder().set(bytes(&[1, 2, 3])).build()
+    );
+    check_breakdown!(
+        explorer,
+        Table6,
+        Table6::new_builder()
+            .f4(bytes(&[1]))
+            .f5(BytesVec::new_builder().push(bytes(&[2, 3])).build())
+            .build()
+    );
+}
+
+#[test]
+fn headers_and_payload() {
+    let table = Table4::new_builder().f4(bytes(&[4, 5, 6])).build();
+    let breakdown = table.deep_size_breakdown();
+    // The total size and 4 offsets, then the item count of `f4`.
+    assert_eq!(breakdown.header, 4 * 5 + 4);
+    assert_eq!(breakdown.payload, 1 + 4 + 6 + 3);
+    let names = breakdown
+        .fields
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["f1", "f2", "f3", "f4"]);
+    let f4 = &breakdown.fields[3].1;
+    assert_eq!((f4.header, f4.payload), (4, 3));
+    let f3 = &breakdown.fields[2].1;
+    assert_eq!((f3.header, f3.payload, f3.fields.len()), (0, 6, 4));
+
+    let union = UnionA::new_builder().set(bytes(&[1, 2])).build();
+    let breakdown = union.deep_size_breakdown();
+    assert_eq!((breakdown.header, breakdown.payload), (8, 2));
+    assert_eq!(breakdown.fields[0].0, "Bytes");
+
+    let breakdown = BytesOpt::default().deep_size_breakdown();
+    assert_eq!(breakdown, Default::default());
+}
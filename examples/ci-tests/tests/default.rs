@@ -20,3 +20,35 @@ macro_rules! verify_default {
 fn default_can_verify() {
     testset!(all, verify_default);
 }
+
+macro_rules! default_ref {
+    ($type:ident) => {
+        let default = $type::default_ref();
+        assert_eq!(
+            default.as_slice(),
+            $type::default().as_slice(),
+            "failed to share default for {}",
+            $type::NAME
+        );
+        assert!(default.is_default());
+        // The value is initialized once.
+        assert!(::core::ptr::eq(default, $type::default_ref()));
+    };
+}
+
+#[test]
+fn default_ref_is_default() {
+    testset!(all, default_ref);
+}
+
+#[test]
+fn default_ref_across_threads() {
+    use molecule_ci_tests::types::Table6;
+    let addresses = (0..4)
+        .map(|_| std::thread::spawn(|| Table6::default_ref() as *const Table6 as usize))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect::<Vec<_>>();
+    assert!(addresses.windows(2).all(|pair| pair[0] == pair[1]));
+}
@@ -0,0 +1,245 @@
+#![cfg(feature = "derive")]
+
+use std::{env, fs};
+
+use codegen::{Compiler, Language};
+use molecule::{
+    codec::{self, Decode as _, Encode as _},
+    error::VerificationError,
+    prelude::*,
+};
+
+use molecule_ci_tests::{constraints, required};
+
+#[derive(Debug, Clone, PartialEq, Eq, molecule::Encode, molecule::Decode)]
+struct Range {
+    start: u32,
+    end: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, molecule::Encode, molecule::Decode)]
+struct Order {
+    id: u32,
+    amount: Option<u32>,
+    memo: Option<Vec<u8>>,
+    note: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, molecule::Encode, molecule::Decode)]
+enum Payload {
+    #[molecule(order = 1)]
+    Words(Vec<u16>),
+    #[molecule(order = 0)]
+    Bytes(Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, molecule::Encode, molecule::Decode)]
+#[molecule(table, name = "Message")]
+struct Envelope {
+    #[molecule(order = 1)]
+    range: Range,
+    #[molecule(order = 0)]
+    payloads: Vec<Payload>,
+    #[molecule(order = 2)]
+    tag: [u8; 2],
+}
+
+fn uint32(value: u32) -> constraints::Uint32 {
+    value.to_le_bytes().into()
+}
+
+fn bytes(data: &[u8]) -> required::Bytes {
+    required::Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+#[test]
+fn same_bytes_as_generated() {
+    let range = Range { start: 1, end: 2 };
+    let expected = constraints::Range::new_builder()
+        .start(uint32(1))
+        .end(uint32(2))
+        .build();
+    assert_eq!(range.encode(), expected.as_slice());
+    assert_eq!(Range::FIXED_SIZE, Some(8));
+
+    let order = Order {
+        id: 7,
+        amount: None,
+        memo: Some(b"memo".to_vec()),
+        note: Some("note".to_owned()),
+    };
+    let expected = required::Order::new_builder()
+        .id(7u32.to_le_bytes().into())
+        .memo(
+            required::BytesOpt::new_builder()
+                .set(Some(bytes(b"memo")))
+                .build(),
+        )
+        .note(
+            required::BytesOpt::new_builder()
+                .set(Some(bytes(b"note")))
+                .build(),
+        )
+        .build();
+    assert_eq!(order.encode(), expected.as_slice());
+    assert_eq!(order.encoded_size(), expected.as_slice().len());
+
+    let payload = Payload::Words(vec![1, 2]);
+    let words = constraints::Words::new_builder()
+        .push([1, 0].into())
+        .push([2, 0].into())
+        .build();
+    let expected = constraints::Payload::new_builder().set(words).build();
+    assert_eq!(payload.encode(), expected.as_slice());
+}
+
+#[test]
+fn round_trip() {
+    let envelope = Envelope {
+        range: Range { start: 3, end: 4 },
+        payloads: vec![Payload::Bytes(vec![1, 2, 3]), Payload::Words(vec![])],
+        tag: [5, 6],
+    };
+    let data = envelope.encode();
+    assert_eq!(Envelope::decode(&data), Ok(envelope));
+    assert_eq!(Envelope::FIXED_SIZE, None);
+    let order = Order {
+        id: 1,
+        amount: Some(2),
+        memo: None,
+        note: None,
+    };
+    assert_eq!(Order::decode(&order.encode()), Ok(order));
+}
+
+#[test]
+fn decode_errors() {
+    let data = Range { start: 1, end: 2 }.encode();
+    assert_eq!(
+        Range::decode(&data[..7]),
+        Err(VerificationError::TotalSizeNotMatch(
+            "Range".to_owned(),
+            8,
+            7
+        ))
+    );
+    assert_eq!(
+        Payload::decode(&[2, 0, 0, 0]),
+        Err(VerificationError::UnknownItem("Payload".to_owned(), 2, 2))
+    );
+    let data = String::new().encode();
+    assert_eq!(String::decode(&data), Ok(String::new()));
+    assert_eq!(
+        String::decode(&[1, 0, 0, 0, 0xff]),
+        Err(VerificationError::InvariantNotHeld(
+            "Bytes".to_owned(),
+            "utf8".to_owned()
+        ))
+    );
+    // The total size of the huge items overflows, instead of wrapping around.
+    assert_eq!(
+        <Vec<[u128; 1 << 29]>>::decode(&[0xff; 4]),
+        Err(VerificationError::SizeOverflow(
+            "Uint128Array536870912Vec".to_owned()
+        ))
+    );
+    // The items of an array should have a fixed size.
+    assert_eq!(
+        <[String; 2]>::decode(&[4, 0, 0, 0]),
+        Err(VerificationError::InvariantNotHeld(
+            "BytesArray2".to_owned(),
+            "fixed_size".to_owned()
+        ))
+    );
+    // The names of the types in the errors are known even if they have no schema.
+    assert_eq!(
+        <Vec<[String; 2]>>::decode(&[4, 0, 0, 0, 0]),
+        Err(VerificationError::TotalSizeNotMatch(
+            "BytesArray2Vec".to_owned(),
+            4,
+            5
+        ))
+    );
+    // The items which have no size are rejected, instead of being counted from the header.
+    assert_eq!(
+        <[u8; 0]>::decode(&[]),
+        Err(VerificationError::InvariantNotHeld(
+            "Byte0".to_owned(),
+            "non_zero_size".to_owned()
+        ))
+    );
+    assert_eq!(
+        <Vec<[u8; 0]>>::decode(&[0xff, 0xff, 0xff, 0xf0]),
+        Err(VerificationError::InvariantNotHeld(
+            "Byte0Vec".to_owned(),
+            "non_zero_size".to_owned()
+        ))
+    );
+    // The generated readers reject the same bytes.
+    let data = Order {
+        id: 1,
+        amount: None,
+        memo: None,
+        note: None,
+    }
+    .encode();
+    assert!(Order::decode(&data[..data.len() - 1]).is_err());
+    assert!(required::OrderReader::verify(&data[..data.len() - 1], false).is_err());
+}
+
+#[test]
+#[should_panic(expected = "the array (Byte0) has no size")]
+fn export_schema_of_array_without_size() {
+    codec::schema_of::<Vec<[u8; 0]>>();
+}
+
+#[test]
+fn export_schema() {
+    let schema = codec::schema_of::<Envelope>();
+    assert_eq!(
+        schema.to_string(),
+        "\
+vector Bytes <byte>;
+
+array Uint16 [byte; 2];
+
+vector Uint16Vec <Uint16>;
+
+union Payload {
+    Bytes,
+    Uint16Vec,
+}
+
+vector PayloadVec <Payload>;
+
+array Uint32 [byte; 4];
+
+struct Range {
+    start: Uint32,
+    end: Uint32,
+}
+
+array Byte2 [byte; 2];
+
+table Message {
+    payloads: PayloadVec,
+    range: Range,
+    tag: Byte2,
+}
+"
+    );
+    // The exported schema could be compiled, for the other languages.
+    let dir = env::temp_dir().join("molecule-ci-tests-derive");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("message.mol");
+    fs::write(&path, schema.to_string()).unwrap();
+    for lang in &[Language::Rust, Language::C] {
+        Compiler::new()
+            .language(*lang)
+            .file_path(&path)
+            .out_dir(&dir)
+            .run();
+    }
+}
@@ -0,0 +1,116 @@
+use std::{env, fmt, fs, panic};
+
+use codegen::{Compiler, Explorer, Language};
+use molecule::{
+    display::{self, Formatters},
+    prelude::*,
+};
+
+use molecule_ci_tests::display::*;
+
+fn bytes(data: &[u8]) -> Bytes {
+    Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+fn profile(name: &[u8]) -> Profile {
+    let address = Address::new_unchecked(vec![0xab; 20].into());
+    Profile::new_builder()
+        .name(bytes(name))
+        .avatar(bytes(&[1, 2]))
+        .account(Account::new_builder().address(address).build())
+        .build()
+}
+
+// Not a real bech32 encoding, only to check that the registered formatter is used.
+fn fake_bech32(data: &[u8], f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "addr1{}", data.len())
+}
+
+static FORMATTERS: Formatters = Formatters(&[("bech32", fake_bech32)]);
+
+// The formatters are global, so they are checked in one test.
+#[test]
+fn formatters() {
+    let explorer = Explorer::new("schemas/display.mol");
+    let profile = profile(b"alice");
+    let address = format!("0x{}", "ab".repeat(20));
+    let expected = format!(
+        "Profile {{ name: \"alice\", avatar: 0x0102, account: Account {{ \
+         address: {}, nonce: Byte4(0x00000000) }} }}",
+        address
+    );
+    assert_eq!(profile.to_string(), expected);
+    assert!(profile
+        .as_reader()
+        .to_string()
+        .starts_with("ProfileReader { name: \"alice\", avatar: 0x0102, "));
+    assert_eq!(
+        explorer
+            .decode("Profile", profile.as_slice(), false)
+            .unwrap(),
+        expected
+    );
+
+    // The bytes which aren't UTF-8 are in hex.
+    let profile = self::profile(&[0xff]);
+    assert!(profile.to_string().starts_with("Profile { name: 0xff, "));
+
+    display::set_formatters(Some(&FORMATTERS));
+    let account = profile.account();
+    assert_eq!(
+        account.to_string(),
+        "Account { address: addr120, nonce: Byte4(0x00000000) }"
+    );
+    assert_eq!(
+        explorer
+            .decode("Account", account.as_slice(), false)
+            .unwrap(),
+        account.to_string()
+    );
+    display::set_formatters(None);
+    assert!(account.to_string().contains(&address));
+}
+
+#[test]
+fn invalid_display_fields() {
+    let dir = env::temp_dir().join("molecule-ci-tests-display");
+    fs::create_dir_all(&dir).unwrap();
+    for (name, schema, expected) in &[
+        (
+            "no-formatter",
+            "vector Bytes <byte>; table T { @display a: Bytes, }",
+            "the attribute `@display` requires a formatter",
+        ),
+        (
+            "byte",
+            "struct S { @display(hex) a: byte, }",
+            "the attribute `@display` is only for the arrays or the vectors of bytes, \
+             but the field (a) in (S) is byte",
+        ),
+        (
+            "vector",
+            "vector Bytes <byte>; vector BytesVec <Bytes>; table T { @display(hex) a: BytesVec, }",
+            "the attribute `@display` is only for the arrays or the vectors of bytes, \
+             but the field (a) in (T) is BytesVec",
+        ),
+    ] {
+        let path = dir.join(format!("{}.mol", name));
+        fs::write(&path, schema).unwrap();
+        let err = panic::catch_unwind(|| {
+            Compiler::new()
+                .language(Language::Rust)
+                .file_path(&path)
+                .out_dir(&dir)
+                .run();
+        })
+        .unwrap_err();
+        let message = err
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| err.downcast_ref::<&str>().map(|s| (*s).to_owned()))
+            .unwrap();
+        assert_eq!(&message, expected);
+    }
+}
@@ -0,0 +1,133 @@
+use std::{env, fs};
+
+use serde_json::Value;
+
+use codegen::{editor_files, schema_stats, syntax};
+
+fn editor_text(path: &str) -> String {
+    editor_files()
+        .into_iter()
+        .find(|(name, _)| *name == path)
+        .map(|(_, content)| content)
+        .unwrap_or_else(|| panic!("the file `{}` is not generated", path))
+}
+
+fn editor_file(path: &str) -> Value {
+    serde_json::from_str(&editor_text(path)).unwrap()
+}
+
+// Replace the placeholders of a snippet with their defaults, and name the type after the keyword.
+fn expand(keyword: &str, body: &str) -> String {
+    let name = format!("{}{}", keyword[..1].to_uppercase(), &keyword[1..]);
+    let mut expanded = body.replace("${1:Name}", &name);
+    while let Some(start) = expanded.find("${") {
+        let colon = start + expanded[start..].find(':').unwrap();
+        let end = start + expanded[start..].find('}').unwrap();
+        let default = expanded[colon + 1..end].to_owned();
+        expanded.replace_range(start..=end, &default);
+    }
+    expanded
+}
+
+#[test]
+fn syntax_from_grammar() {
+    let syntax = syntax();
+    assert_eq!(
+        syntax.keywords,
+        vec!["option", "union", "array", "struct", "vector", "table", "alias", "import"]
+    );
+    assert_eq!(syntax.line_comments, vec!["//", "#"]);
+    assert_eq!(syntax.block_comment, ("/*".to_owned(), "*/".to_owned()));
+    assert_eq!(syntax.attribute_prefix, "@");
+}
+
+#[test]
+fn textmate_grammar_has_all_keywords() {
+    let grammar = editor_file("syntaxes/molecule.tmLanguage.json");
+    assert_eq!(grammar["scopeName"], "source.molecule");
+    let keywords = grammar["repository"]["keywords"]["patterns"][0]["match"]
+        .as_str()
+        .unwrap();
+    let alternatives = keywords
+        .trim_start_matches("\\b(")
+        .split(')')
+        .next()
+        .unwrap()
+        .split('|')
+        .collect::<Vec<_>>();
+    assert_eq!(alternatives, syntax().keywords);
+    let config = editor_file("language-configuration.json");
+    assert_eq!(config["comments"]["lineComment"], "//");
+    let package = editor_file("package.json");
+    assert_eq!(
+        package["contributes"]["languages"][0]["extensions"][0],
+        ".mol"
+    );
+}
+
+#[test]
+fn snippets_are_accepted_by_compiler() {
+    let snippets = editor_file("snippets/molecule.code-snippets");
+    let snippets = snippets.as_object().unwrap();
+    assert_eq!(snippets.len(), syntax().keywords.len());
+    let mut schema = String::from("vector Bytes <byte>;\n");
+    for (keyword, snippet) in snippets {
+        if keyword == "import" {
+            continue;
+        }
+        let body: Vec<_> = snippet["body"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|line| line.as_str().unwrap())
+            .collect();
+        schema.push_str(&expand(keyword, &body.join("\n")));
+        schema.push('\n');
+    }
+    let dir = env::temp_dir().join("molecule-ci-tests-editor");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("snippets.mol");
+    fs::write(&path, &schema).unwrap();
+    let names: Vec<_> = schema_stats(&path)
+        .into_iter()
+        .map(|stats| stats.name)
+        .collect();
+    assert_eq!(
+        names,
+        vec!["Bytes", "Option", "Union", "Array", "Struct", "Vector", "Table"]
+    );
+}
+
+#[test]
+fn tree_sitter_grammar_from_rules() {
+    let grammar = editor_text("tree-sitter/grammar.js");
+    let rules: Vec<_> = grammar
+        .lines()
+        .filter_map(|line| line.strip_prefix("    "))
+        .filter_map(|line| line.split(": $ =>").next())
+        .collect();
+    assert_eq!(rules[0], "grammar");
+    for rule in &[
+        "import_stmt",
+        "_decl_stmt",
+        "table_decl",
+        "attribute",
+        "comment",
+    ] {
+        assert!(rules.contains(rule), "{}", rule);
+    }
+    // The breaks are the extras, and the rules of characters are inlined into the tokens.
+    for rule in &["brk", "_brk", "_whitespace", "_letter", "_digit"] {
+        assert!(!rules.contains(rule), "{}", rule);
+    }
+    assert!(grammar.contains(
+        "    option_decl: $ => seq(repeat($.attribute), \"option\", $.identifier, \"(\", $.identifier, \")\", $._stmt_end),\n"
+    ));
+    assert!(
+        grammar.contains("    number: $ => token(seq(/[1-9]/, repeat(choice(\"0\", /[1-9]/)))),\n")
+    );
+    let highlights = editor_text("tree-sitter/queries/highlights.scm");
+    assert!(highlights.starts_with(
+        "[\"option\" \"union\" \"array\" \"struct\" \"vector\" \"table\" \"alias\" \"import\"] @keyword\n"
+    ));
+}
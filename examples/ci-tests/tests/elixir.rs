@@ -0,0 +1,75 @@
+mod common;
+
+const ELIXIR: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.ex"));
+const NUMBERS: &str = include_str!(concat!(env!("OUT_DIR"), "/numbers.ex"));
+
+#[test]
+fn elixir_modules() {
+    assert!(ELIXIR.contains("\ndefmodule CiTests do\n"));
+    assert!(ELIXIR.ends_with("\nend\n"));
+    assert!(ELIXIR.contains(
+        "  defmodule StructA do
+    @moduledoc \"The struct `StructA`.\"
+
+    def size, do: 6
+
+    def decode(<<v0::binary-size(1), v1::binary-size(1), v2::binary-size(2), v3::binary-size(2)>>) do"
+    ));
+    assert!(ELIXIR.contains(
+        "      with {:ok, [v0, v1]} <- CiTests.split_fields(bin, 2),
+           {:ok, v0} <- CiTests.decode_byte(v0),
+           {:ok, v1} <- CiTests.Word2.decode(v1) do
+        {:ok, %{f1: v0, f2: v1}}
+      end"
+    ));
+    assert!(ELIXIR.contains(
+        "    def decode(<<count::little-32, items::binary>>) when byte_size(items) == count * 2 do"
+    ));
+    assert!(ELIXIR.contains(
+        "    def encode({:Word, value}), do: <<1::little-32, CiTests.Word.encode(value)::binary>>"
+    ));
+}
+
+#[test]
+fn elixir_union_aliases() {
+    assert!(NUMBERS.contains("{:ok, {:Uint64, value}}"));
+    for name in &["Uint64", "Small"] {
+        let encode = format!(
+            "    def encode({{:{}, value}}), do: <<0::little-32, Numbers.Uint64.encode(value)::binary>>",
+            name
+        );
+        assert!(NUMBERS.contains(&encode), "{}", encode);
+    }
+}
+
+#[test]
+fn elixir_modules_are_unique() {
+    common::assert_unique(
+        ELIXIR
+            .lines()
+            .filter_map(|line| line.strip_prefix("  defmodule ")),
+    );
+}
+
+#[test]
+#[cfg_attr(not(round_trip), ignore = "requires Elixir, run with ROUND_TRIP=1")]
+fn elixir_round_trip() {
+    let samples = common::samples();
+    let dir = common::workspace(
+        "elixir",
+        &[
+            ("ci_tests.ex", ELIXIR),
+            ("round_trip.exs", include_str!("round_trip/round_trip.exs")),
+        ],
+    );
+    let args = [
+        common::hex(&samples.table6),
+        common::hex(&samples.union_a),
+        common::hex(&samples.broken),
+    ];
+    let mut command = vec!["-r", "ci_tests.ex", "round_trip.exs"];
+    command.extend(args.iter().map(String::as_str));
+    let output = common::run(&dir, "elixir", &command);
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines, [args[0].as_str(), args[1].as_str(), "error"]);
+}
@@ -0,0 +1,29 @@
+use molecule::{pack_number, prelude::*, unpack_number};
+use slices::u8_slice as s;
+
+use molecule_ci_tests::types;
+
+// The numbers are always little-endian, whatever the host is; run it on a big-endian target with
+// `make test-rust-big-endian`.
+#[test]
+fn numbers_are_little_endian() {
+    assert_eq!(pack_number(0x0403_0201), [0x01, 0x02, 0x03, 0x04]);
+    assert_eq!(unpack_number(&[0x01, 0x02, 0x03, 0x04]), 0x0403_0201);
+}
+
+#[test]
+fn headers_are_little_endian() {
+    let bytes = types::Bytes::new_builder()
+        .push(0x12.into())
+        .push(0x34.into())
+        .build();
+    assert_eq!(bytes.as_slice(), s!("0x02000000_1234"));
+    let bytes_vec = types::BytesVec::new_builder().push(bytes).build();
+    assert_eq!(
+        bytes_vec.as_slice(),
+        s!("0x0e000000_08000000_02000000_1234")
+    );
+    let reader = types::BytesVecReader::from_slice(bytes_vec.as_slice()).unwrap();
+    assert_eq!(reader.len(), 1);
+    assert_eq!(reader.get(0).unwrap().raw_data(), &[0x12, 0x34][..]);
+}
@@ -0,0 +1,66 @@
+use molecule::prelude::*;
+
+use molecule_ci_tests::{testset, types};
+
+macro_rules! default_is_default {
+    ($type:ident) => {
+        let value = $type::default();
+        assert!(value.is_default(), "{}", $type::NAME);
+        assert!(value.as_reader().is_default(), "{}", $type::NAME);
+        assert!(
+            value
+                .as_reader()
+                .eq_bytes(&$type::new_builder().build().as_reader()),
+            "{}",
+            $type::NAME
+        );
+    };
+}
+
+#[test]
+fn defaults() {
+    testset!(all, default_is_default);
+}
+
+#[test]
+fn compare_readers() {
+    let bytes = |data: &[u8]| {
+        types::Bytes::new_builder()
+            .extend(data.iter().copied().map(Byte::new))
+            .build()
+    };
+    let a = bytes(&[1, 2, 3]);
+    let b = bytes(&[1, 2, 3]);
+    let c = bytes(&[1, 2, 4]);
+    let d = bytes(&[1, 2]);
+    assert!(a.as_reader().eq_bytes(&b.as_reader()));
+    assert!(a.eq_bytes(&b.as_reader()));
+    assert!(!a.as_reader().eq_bytes(&c.as_reader()));
+    assert!(!a.as_reader().eq_bytes(&d.as_reader()));
+    assert!(a.as_reader().eq_slice(b.as_slice()));
+    assert!(!a.as_reader().eq_slice(&b.as_slice()[1..]));
+    assert!(!a.is_default());
+    assert!(types::Bytes::default().is_default());
+}
+
+#[test]
+fn compare_nested() {
+    let table = types::Table2::new_builder().f1(0x12.into()).build();
+    assert!(!table.is_default());
+    assert!(!table
+        .as_reader()
+        .eq_bytes(&types::Table2::default().as_reader()));
+    let same = types::Table2::from_slice(table.as_slice()).unwrap();
+    assert!(table.eq_bytes(&same.as_reader()));
+
+    let opt = types::BytesOpt::new_builder()
+        .set(Some(types::Bytes::default()))
+        .build();
+    // An option of a default value is not the default, which is none.
+    assert!(!opt.is_default());
+    assert!(opt.to_opt().unwrap().is_default());
+    assert_eq!(
+        types::Table2Reader::DEFAULT_ENCODING,
+        types::Table2::default().as_slice()
+    );
+}
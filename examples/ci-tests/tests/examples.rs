@@ -0,0 +1,108 @@
+use std::{env, fs};
+
+use codegen::{ExampleLanguage, Examples};
+use molecule::prelude::*;
+use molecule_ci_tests::types;
+
+mod examples {
+    include!(concat!(env!("OUT_DIR"), "/examples/all.rs"));
+}
+
+#[test]
+fn run_rust_examples() {
+    for run in examples::RUNS {
+        run();
+    }
+    let value = examples::table3::build();
+    assert!(examples::table3::parse(value.as_slice()).is_ok());
+    assert!(examples::table3::parse(&value.as_slice()[1..]).is_err());
+}
+
+#[test]
+fn realistic_values() {
+    let dir = env::temp_dir().join("molecule-ci-tests-examples-numbers");
+    let _ = fs::remove_dir_all(&dir);
+    Examples::new()
+        .file_path("schemas/numbers.mol")
+        .out_dir(&dir)
+        .generate()
+        .unwrap();
+    let balance = fs::read_to_string(dir.join("balance.rs")).unwrap();
+    assert!(balance.contains("Uint64::from(1000u64)"));
+    let mods = fs::read_to_string(dir.join("mod.rs")).unwrap();
+    assert!(mods.contains("pub mod balance;\n"));
+}
+
+#[test]
+fn go_examples() {
+    let dir = env::temp_dir().join("molecule-ci-tests-examples-go");
+    let _ = fs::remove_dir_all(&dir);
+    Examples::new()
+        .file_path("schemas/ci_tests.mol")
+        .language(ExampleLanguage::Go)
+        .module("example.com/app/types")
+        .out_dir(&dir)
+        .generate()
+        .unwrap();
+    let table3 = fs::read_to_string(dir.join("table3.go")).unwrap();
+    assert!(table3.contains("package examples\n"));
+    assert!(table3.contains("\ttypes \"example.com/app/types\"\n"));
+    assert!(table3.contains("func BuildTable3() types.Table3 {\n\treturn types.NewTable3Builder().\n\t\tF1(types.NewByte(0x01)).\n"));
+    assert!(table3.contains("\tvalue, err := types.Table3FromSlice(slice, false)\n"));
+    assert!(table3.contains("\tfmt.Printf(\"f3: %x\\n\", value.F3().AsSlice())\n"));
+    let union = fs::read_to_string(dir.join("union_a.go")).unwrap();
+    assert!(union.contains("types.NewUnionABuilder().\n\t\tSet(types.UnionAUnionFromByte(types.NewByte(0x01))).\n\t\tBuild()"));
+    let helpers = fs::read_to_string(dir.join("helpers.go")).unwrap();
+    assert!(helpers.contains("func bytesOf(data []byte) []types.Byte {"));
+    let fuzz = fs::read_to_string(dir.join("fuzz_test.go")).unwrap();
+    assert!(fuzz.contains("func FuzzFromSliceTable3(f *testing.F) {\n"));
+    assert!(fuzz.contains("\t\tvalue, err := types.Table3FromSlice(data, false)\n"));
+
+    // The seeds are the same bytes as the Rust snippets build.
+    let seed = |typ: &str, name: &str| {
+        let path = dir
+            .join("testdata/fuzz")
+            .join(format!("FuzzFromSlice{}", typ));
+        let corpus = fs::read_to_string(path.join(name)).unwrap();
+        let escaped = corpus
+            .strip_prefix("go test fuzz v1\n[]byte(\"")
+            .and_then(|rest| rest.strip_suffix("\")\n"))
+            .unwrap();
+        escaped
+            .split("\\x")
+            .skip(1)
+            .map(|byte| u8::from_str_radix(byte, 16).unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        seed("Table3", "default"),
+        types::Table3::default().as_slice()
+    );
+    assert_eq!(
+        seed("Table3", "sample"),
+        examples::table3::build().as_slice()
+    );
+    assert_eq!(
+        seed("UnionA", "sample"),
+        examples::union_a::build().as_slice()
+    );
+    assert_eq!(
+        seed("BytesVec", "sample"),
+        examples::bytes_vec::build().as_slice()
+    );
+    assert_eq!(
+        seed("AllInOne", "sample"),
+        examples::all_in_one::build().as_slice()
+    );
+
+    // The snippets are in the package of the types without a module.
+    Examples::new()
+        .file_path("schemas/ci_tests.mol")
+        .language(ExampleLanguage::Go)
+        .out_dir(&dir)
+        .generate()
+        .unwrap();
+    let table3 = fs::read_to_string(dir.join("table3.go")).unwrap();
+    assert!(table3.contains("package types\n"));
+    assert!(!table3.contains("types."));
+}
@@ -0,0 +1,109 @@
+use codegen::Explorer;
+use molecule::prelude::*;
+
+use molecule_ci_tests::{testset, types};
+
+fn explorer() -> Explorer {
+    Explorer::new("schemas/ci_tests.mol")
+}
+
+// The decoded text is the same as the `Display` of the entity.
+macro_rules! check_decode {
+    ($explorer:ident, $type:ident, $value:expr) => {
+        let value = $value;
+        assert_eq!(
+            $explorer
+                .decode(stringify!($type), value.as_slice(), false)
+                .unwrap(),
+            value.to_string()
+        );
+    };
+}
+
+#[test]
+fn decode_as_display() {
+    let explorer = explorer();
+    macro_rules! check_default {
+        ($type:ident) => {
+            check_decode!(explorer, $type, $type::default());
+        };
+    }
+    testset!(all, check_default);
+
+    use types::*;
+    let bytes = Bytes::new_builder().push(0x12.into()).build();
+    let bytes_vec = BytesVec::new_builder()
+        .push(bytes.clone())
+        .push(Bytes::default())
+        .build();
+    check_decode!(explorer, BytesVec, bytes_vec.clone());
+    check_decode!(
+        explorer,
+        BytesVecOpt,
+        BytesVecOpt::new_builder().set(Some(bytes_vec)).build()
+    );
+    check_decode!(explorer, UnionA, UnionA::new_builder().set(bytes).build());
+}
+
+#[test]
+fn decode_extra_fields() {
+    let explorer = explorer();
+    let data = types::Table2::new_builder()
+        .f1(0x12.into())
+        .build()
+        .as_slice()
+        .to_vec();
+    assert!(explorer.decode("Table1", &data, false).is_err());
+    let expected = types::Table1::new_unchecked(data.clone().into()).to_string();
+    assert_eq!(explorer.decode("Table1", &data, true).unwrap(), expected);
+    assert!(expected.ends_with(", .. (1 fields) }"));
+}
+
+#[test]
+fn describe_types() {
+    let explorer = explorer();
+    assert_eq!(
+        explorer.describe("Table3").unwrap(),
+        "table Table3 { f1: byte, f2: Word2, f3: StructA, }"
+    );
+    assert_eq!(
+        explorer.describe("Byte3").unwrap(),
+        "array Byte3 [byte; 3];"
+    );
+    assert!(explorer.describe("Unknown").is_err());
+    assert!(explorer.type_names().contains(&"AllInOne".to_owned()));
+    let stats = explorer.stats("Table3").unwrap();
+    assert_eq!(stats.max_size, Some(types::Table3::MAX_ENCODED_SIZE));
+}
+
+#[test]
+fn explain_layout() {
+    let explorer = explorer();
+    // The examples in the explanations are valid.
+    for name in explorer.type_names() {
+        let text = explorer.explain(&name).unwrap();
+        let hex = text
+            .lines()
+            .find_map(|line| {
+                line.split(": 0x")
+                    .nth(1)
+                    .filter(|_| line.starts_with("Example"))
+            })
+            .unwrap();
+        let data = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect::<Vec<_>>();
+        assert!(explorer.decode(&name, &data, false).is_ok(), "{}", name);
+    }
+    let text = explorer.explain("Table3").unwrap();
+    assert!(text.starts_with("table Table3 { f1: byte, f2: Word2, f3: StructA, }\n"));
+    assert!(text.contains("  2: f3: StructA (fixed-size, 6 bytes)\n"));
+    assert!(text.contains("Size: min 27 bytes, max 27 bytes."));
+    assert!(text.contains("  000c  15 00 00 00                offset of f3: 21\n"));
+    assert!(text.ends_with("  0015  06 07 08 09 0a 0b          f3: StructA"));
+    let text = explorer.explain("StructA").unwrap();
+    assert!(text.contains("  offset 4: f4: Byte2 (2 bytes)\n"));
+    assert!(!text.contains("headers"));
+    assert!(explorer.explain("Unknown").is_err());
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "ffi")]
+
+use codegen::Mutator;
+use molecule::{
+    ffi::{self, mol_errno, VerifyFn},
+    prelude::*,
+};
+
+use molecule_ci_tests::{ffi as c, types};
+
+// The C code should accept and reject the same data as the Rust code.
+fn check_verify<E: Entity>(verify: VerifyFn, value: &E) {
+    let data = value.as_slice();
+    assert_eq!(unsafe { ffi::verify(verify, data, false) }, ffi::MOL_OK);
+    let mutations = Mutator::new()
+        .file_path("schemas/ci_tests.mol")
+        .type_name(E::NAME)
+        .mutate(data)
+        .unwrap();
+    for mutation in &mutations {
+        let errno: mol_errno = unsafe { ffi::verify(verify, &mutation.data, false) };
+        assert_ne!(errno, ffi::MOL_OK, "{}", mutation.label);
+        assert!(E::from_slice(&mutation.data).is_err(), "{}", mutation.label);
+        let errno = unsafe { ffi::verify(verify, &mutation.data, true) };
+        assert_eq!(
+            errno == ffi::MOL_OK,
+            mutation.compatible,
+            "{}",
+            mutation.label
+        );
+    }
+}
+
+#[test]
+fn verify_by_c() {
+    let bytes = types::Bytes::new_builder().push(0x12.into()).build();
+    let bytes_vec = types::BytesVec::new_builder().push(bytes.clone()).build();
+    check_verify(c::MolReader_BytesVec_verify, &bytes_vec);
+    check_verify(
+        c::MolReader_BytesOpt_verify,
+        &types::BytesOpt::new_builder()
+            .set(Some(bytes.clone()))
+            .build(),
+    );
+    check_verify(
+        c::MolReader_UnionA_verify,
+        &types::UnionA::new_builder().set(bytes.clone()).build(),
+    );
+    let table5 = types::Table5::new_builder().f4(bytes).f5(bytes_vec).build();
+    check_verify(
+        c::MolReader_Table6_verify,
+        &types::Table6::new_builder().f6(table5).build(),
+    );
+    check_verify(c::MolReader_Table0_verify, &types::Table0::default());
+}
+
+#[test]
+fn same_defaults() {
+    unsafe {
+        assert_eq!(&c::MolDefault_Byte3[..], types::Byte3::default().as_slice());
+        assert_eq!(
+            &c::MolDefault_Table6[..],
+            types::Table6::default().as_slice()
+        );
+        assert_eq!(
+            &c::MolDefault_BytesOptVec[..],
+            types::BytesOptVec::default().as_slice()
+        );
+        assert_eq!(
+            &c::MolDefault_UnionA[..],
+            types::UnionA::default().as_slice()
+        );
+    }
+}
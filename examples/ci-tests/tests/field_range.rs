@@ -0,0 +1,53 @@
+use molecule::prelude::*;
+
+use molecule_ci_tests::types::{self, constants};
+
+#[test]
+fn table_field_range() {
+    let f2 = types::Word2::new_builder()
+        .nth1(types::Word::new_builder().nth0(0x12.into()).build())
+        .build();
+    let table = types::Table2::new_builder()
+        .f1(0x34.into())
+        .f2(f2.clone())
+        .build();
+    let reader = table.as_reader();
+    assert_eq!(reader.field_range(constants::table2::FIELD_F1), 12..13);
+    assert_eq!(reader.field_range(constants::table2::FIELD_F2), 13..17);
+    let range = table.field_range(constants::table2::FIELD_F2);
+    assert_eq!(&table.as_slice()[range], f2.as_slice());
+}
+
+#[test]
+fn table_field_range_with_extra_fields() {
+    let table = types::Table3::default();
+    let reader = types::Table2Reader::from_compatible_slice(table.as_slice()).unwrap();
+    let last = reader.field_range(constants::table2::FIELD_F2);
+    assert_eq!(last.end, reader.field_range(2).start);
+    assert_eq!(reader.field_range(2).end, table.as_slice().len());
+}
+
+#[test]
+#[should_panic]
+fn table_field_range_out_of_bounds() {
+    types::Table2::default().field_range(2);
+}
+
+#[test]
+fn vector_item_range() {
+    let bytes = types::Bytes::new_builder()
+        .push(0x12.into())
+        .push(0x34.into())
+        .build();
+    let bytes_vec = types::BytesVec::new_builder()
+        .push(bytes.clone())
+        .push(types::Bytes::default())
+        .build();
+    assert_eq!(bytes.item_range(1), 5..6);
+    let reader = bytes_vec.as_reader();
+    assert_eq!(
+        &bytes_vec.as_slice()[reader.item_range(0)],
+        bytes.as_slice()
+    );
+    assert_eq!(reader.item_range(1), 18..22);
+}
@@ -0,0 +1,105 @@
+use std::{env, fs, panic};
+
+use codegen::{Compiler, Language};
+use molecule::prelude::*;
+
+use molecule_ci_tests::flatten;
+
+fn uint32(value: u32) -> flatten::Uint32 {
+    value.to_le_bytes().into()
+}
+
+fn point(x: u32, y: u32) -> flatten::Point {
+    flatten::Point::new_builder()
+        .x(uint32(x))
+        .y(uint32(y))
+        .build()
+}
+
+#[test]
+fn struct_fields() {
+    let sprite = flatten::Sprite::new_builder()
+        .position(point(1, 2))
+        .velocity(point(3, 4))
+        .layer(5.into())
+        .build();
+    assert_eq!(sprite.position_x().as_slice(), uint32(1).as_slice());
+    assert_eq!(sprite.position_y().as_slice(), uint32(2).as_slice());
+    assert_eq!(sprite.velocity_x().as_slice(), uint32(3).as_slice());
+    let reader = sprite.as_reader();
+    assert_eq!(reader.velocity_y().as_slice(), uint32(4).as_slice());
+    assert_eq!(
+        reader.position_x().as_slice(),
+        reader.position().x().as_slice()
+    );
+}
+
+#[test]
+fn table_fields() {
+    let node = flatten::Node::new_builder()
+        .name(point(1, 2))
+        .anchor(point(3, 4))
+        .build();
+    assert_eq!(node.anchor_x().as_slice(), uint32(3).as_slice());
+    assert_eq!(node.as_reader().anchor_y().as_slice(), uint32(4).as_slice());
+}
+
+#[test]
+fn same_encoding() {
+    let schema = fs::read_to_string("schemas/flatten.mol").unwrap();
+    let plain = schema.replace("@flatten", "");
+    let dir = env::temp_dir().join("molecule-ci-tests-flatten");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("plain.mol");
+    fs::write(&path, plain).unwrap();
+    assert_eq!(
+        codegen::schema_hash(&path),
+        codegen::schema_hash("schemas/flatten.mol")
+    );
+}
+
+#[test]
+fn invalid_attributes() {
+    let dir = env::temp_dir().join("molecule-ci-tests-flatten");
+    fs::create_dir_all(&dir).unwrap();
+    for (name, schema, expected) in &[
+        (
+            "array",
+            "array Uint32 [byte; 4];\ntable T { @flatten a: Uint32, }\n",
+            "the attribute `@flatten` is only for structs",
+        ),
+        (
+            "duplicated",
+            "array Uint32 [byte; 4];\nstruct P { x: Uint32, }\n\
+             struct S { @flatten a: P, a_x: Uint32, }\n",
+            "the flattened field (a_x) in (S) is duplicated",
+        ),
+        (
+            "value",
+            "array Uint32 [byte; 4];\nstruct P { x: Uint32, }\nstruct S { @flatten(1) a: P, }\n",
+            "the attribute `@flatten` has no value",
+        ),
+        (
+            "unknown",
+            "array Uint32 [byte; 4];\nstruct S { @count_size(1) a: Uint32, }\n",
+            "the attribute `@count_size` is unknown",
+        ),
+    ] {
+        let path = dir.join(format!("{}.mol", name));
+        fs::write(&path, schema).unwrap();
+        let err = panic::catch_unwind(|| {
+            Compiler::new()
+                .language(Language::Rust)
+                .file_path(&path)
+                .out_dir(&dir)
+                .run();
+        })
+        .unwrap_err();
+        let message = err
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| err.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap();
+        assert!(message.starts_with(expected), "{}", message);
+    }
+}
@@ -0,0 +1,78 @@
+mod common;
+
+const HASKELL: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.hs"));
+
+#[test]
+fn haskell_module() {
+    assert!(HASKELL.contains("\nmodule CiTests where\n"));
+    assert!(HASKELL.contains("  fromSlice :: P.Bool -> BS.ByteString -> P.Either VerifyError a\n"));
+    assert!(HASKELL.contains(
+        "structAF3 :: StructA -> Byte2
+structAF3 (StructA bs) = Byte2 (molSlice bs 2 2)"
+    ));
+    assert!(HASKELL.contains(
+        "mkStructA :: W.Word8 -> W.Word8 -> Byte2 -> Byte2 -> StructA
+mkStructA v0 v1 v2 v3 = StructA (BS.concat [toBytes v0, toBytes v1, toBytes v2, toBytes v3])"
+    ));
+    assert!(HASKELL.contains(
+        "instance Molecule Table2 where
+  fromSlice compatible bs = do
+    count <- molVerifyDynamic bs
+    molCheck (count >= 2 && (compatible || count == 2)) ErrFieldCount
+    molVerify (fromSlice compatible (molDynamicAt bs 0) :: P.Either VerifyError W.Word8)
+    molVerify (fromSlice compatible (molDynamicAt bs 1) :: P.Either VerifyError Word2)
+    P.Right (Table2 bs)"
+    ));
+    assert!(HASKELL.contains("  | UnionAWord Word\n"));
+    assert!(HASKELL.contains(
+        "mkUnionA (UnionAStructA v) = UnionA (BS.append (molPackNumber 4 2) (toBytes v))\n"
+    ));
+    assert!(HASKELL.contains("mkBytesOpt :: P.Maybe Bytes -> BytesOpt\n"));
+}
+
+#[test]
+fn haskell_unused_arguments() {
+    assert!(HASKELL.contains("byte2Length _ = 2\n"));
+    assert!(HASKELL.contains("instance Molecule Bytes where\n  fromSlice _ bs = do\n"));
+    assert!(HASKELL.contains("    molCheck (compatible || count == 0) ErrFieldCount\n"));
+}
+
+#[test]
+fn haskell_types_are_unique() {
+    common::assert_unique(
+        HASKELL
+            .lines()
+            .filter_map(|line| line.strip_prefix("newtype ")),
+    );
+}
+
+#[test]
+#[cfg_attr(not(round_trip), ignore = "requires GHC, run with ROUND_TRIP=1")]
+fn haskell_round_trip() {
+    let samples = common::samples();
+    let dir = common::workspace(
+        "haskell",
+        &[
+            ("CiTests.hs", HASKELL),
+            ("round_trip.hs", include_str!("round_trip/round_trip.hs")),
+        ],
+    );
+    common::run(
+        &dir,
+        "ghc",
+        &["-outputdir", "build", "-o", "round_trip", "round_trip.hs"],
+    );
+    let args = [
+        common::hex(&samples.table6),
+        common::hex(&samples.union_a),
+        common::hex(&samples.broken),
+    ];
+    let program = dir.join("round_trip");
+    let output = common::run(
+        &dir,
+        program.to_str().unwrap(),
+        &[&args[0], &args[1], &args[2]],
+    );
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines, [args[0].as_str(), args[1].as_str(), "error"]);
+}
@@ -0,0 +1,104 @@
+use serde_json::Value;
+
+const RUST: &str = include_str!(concat!(env!("OUT_DIR"), "/header/numbers.rs"));
+const C: &str = include_str!(concat!(env!("OUT_DIR"), "/header/numbers.h"));
+const OPENAPI: &str = include_str!(concat!(env!("OUT_DIR"), "/header/numbers.json"));
+const SQL: &str = include_str!(concat!(env!("OUT_DIR"), "/header/numbers.sql"));
+const ELIXIR: &str = include_str!(concat!(env!("OUT_DIR"), "/header/numbers.ex"));
+const SOLIDITY: &str = include_str!(concat!(env!("OUT_DIR"), "/header/numbers.sol"));
+const ZIG: &str = include_str!(concat!(env!("OUT_DIR"), "/header/numbers.zig"));
+const LUA: &str = include_str!(concat!(env!("OUT_DIR"), "/header/numbers.lua"));
+const OCAML: &str = include_str!(concat!(env!("OUT_DIR"), "/header/numbers.ml"));
+const HASKELL: &str = include_str!(concat!(env!("OUT_DIR"), "/header/numbers.hs"));
+const SCALA: &str = include_str!(concat!(env!("OUT_DIR"), "/header/numbers.scala"));
+const TLA: &str = include_str!(concat!(env!("OUT_DIR"), "/header/numbers.tla"));
+const KAITAI: &str = include_str!(concat!(env!("OUT_DIR"), "/header/numbers.ksy"));
+const WIRESHARK: &str = include_str!(concat!(env!("OUT_DIR"), "/header/numbers.dissector.lua"));
+const IMHEX: &str = include_str!(concat!(env!("OUT_DIR"), "/header/numbers.hexpat"));
+
+fn banner() -> String {
+    let hash: String = codegen::schema_hash("schemas/numbers.mol")
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!(
+        "Copyright (c) The Molecule Authors\n\
+         SPDX-License-Identifier: MIT\n\
+         \n\
+         Generated by moleculec {} from numbers.mol.\n\
+         Schema hash: {}",
+        codegen::VERSION,
+        hash
+    )
+}
+
+fn commented(prefix: &str) -> String {
+    let mut text = String::new();
+    for line in banner().lines() {
+        if line.is_empty() {
+            text.push_str(prefix);
+        } else {
+            text.push_str(&format!("{} {}", prefix, line));
+        }
+        text.push('\n');
+    }
+    text.push('\n');
+    text
+}
+
+#[test]
+fn line_comments() {
+    for (code, prefix) in &[
+        (RUST, "//"),
+        (C, "//"),
+        (SQL, "--"),
+        (ELIXIR, "#"),
+        (SOLIDITY, "//"),
+        (ZIG, "//"),
+        (LUA, "--"),
+        (HASKELL, "--"),
+        (SCALA, "//"),
+        (TLA, "\\*"),
+        (KAITAI, "#"),
+        (WIRESHARK, "--"),
+        (IMHEX, "//"),
+    ] {
+        let header = commented(prefix);
+        assert!(code.starts_with(&header), "{}", code);
+        let rest = &code[header.len()..];
+        let generated = format!("{} Generated by Molecule {}\n", prefix, codegen::VERSION);
+        assert!(rest.starts_with(&generated));
+    }
+}
+
+#[test]
+fn block_comments() {
+    let mut header = String::new();
+    for line in banner().lines() {
+        if line.is_empty() {
+            header.push_str("(* *)\n");
+        } else {
+            header.push_str(&format!("(* {} *)\n", line));
+        }
+    }
+    header.push('\n');
+    assert!(OCAML.starts_with(&header), "{}", OCAML);
+    let generated = format!("(* Generated by Molecule {} *)\n", codegen::VERSION);
+    assert!(OCAML[header.len()..].starts_with(&generated));
+}
+
+#[test]
+fn openapi_extension() {
+    assert!(OPENAPI.starts_with("{\n  \"x-header\": "));
+    let document: Value = serde_json::from_str(OPENAPI).unwrap();
+    assert_eq!(document["x-header"].as_str().unwrap(), banner());
+    assert_eq!(document["info"]["title"].as_str().unwrap(), "numbers");
+}
+
+#[test]
+fn without_header() {
+    let plain = include_str!(concat!(env!("OUT_DIR"), "/numbers.rs"));
+    assert!(plain.starts_with("// Generated by Molecule "));
+    let plain = include_str!(concat!(env!("OUT_DIR"), "/numbers.json"));
+    assert!(!plain.contains("x-header"));
+}
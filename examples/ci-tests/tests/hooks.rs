@@ -0,0 +1,120 @@
+use std::{env, fs, panic, path::Path, process::Command};
+
+use codegen::{Compiler, Language};
+use molecule::prelude::*;
+
+use molecule_ci_tests::hooks::*;
+
+const C: &str = include_str!(concat!(env!("OUT_DIR"), "/hooks/hooks.h"));
+
+fn greeting(count: u32, name: &str) -> Greeting {
+    Greeting::new_builder()
+        .count(count.to_le_bytes().into())
+        .name(
+            Bytes::new_builder()
+                .extend(name.bytes().map(Byte::new))
+                .build(),
+        )
+        .build()
+}
+
+fn panic_message(lang: Language, hooks: &[(&str, &str)]) -> String {
+    let dir = env::temp_dir().join("molecule-ci-tests-hooks");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("types")).unwrap();
+    for (path, content) in hooks {
+        fs::write(dir.join(path), content).unwrap();
+    }
+    let err = panic::catch_unwind(|| {
+        Compiler::new()
+            .language(lang)
+            .file_path("schemas/hooks.mol")
+            .out_dir(env::temp_dir())
+            .hooks_dir(&dir)
+            .run();
+    })
+    .unwrap_err();
+    err.downcast_ref::<String>()
+        .cloned()
+        .or_else(|| err.downcast_ref::<&str>().map(|s| (*s).to_owned()))
+        .unwrap()
+}
+
+#[test]
+fn rust_hooks() {
+    assert_eq!(GREETING_WORD, "Hello");
+    let greeting = greeting(2, "molecule");
+    assert_eq!(greeting.as_reader().count_value(), 2);
+    assert_eq!(greeting.greet(), "Hello, molecule! Hello, molecule!");
+}
+
+#[test]
+fn c_hooks() {
+    let header = C.find("#define GREETING_MAX_COUNT 3").unwrap();
+    assert!(C.find("#include \"molecule_reader.h\"").unwrap() < header);
+    assert!(header < C.find(" * Constants").unwrap());
+    let extension = C.find("MolExt_Greeting_count").unwrap();
+    assert!(C.find(" * Extensions").unwrap() < extension);
+    assert!(
+        C.find("MolBuilder_Greeting_build (mol_builder_t builder) {")
+            .unwrap()
+            < extension
+    );
+
+    let bytes: Vec<_> = greeting(5, "molecule")
+        .as_slice()
+        .iter()
+        .map(|byte| format!("0x{:02x}", byte))
+        .collect();
+    let out_dir = Path::new(env!("OUT_DIR")).join("hooks");
+    let source = out_dir.join("hooks_test.c");
+    fs::write(
+        &source,
+        format!(
+            "#include \"hooks.h\"\n\
+             int main(void) {{\n\
+             uint8_t bytes[] = {{ {} }};\n\
+             mol_seg_t seg = {{ bytes, sizeof(bytes) }};\n\
+             if (MolReader_Greeting_verify(&seg, false) != MOL_OK) return 100;\n\
+             return (int)MolExt_Greeting_count(&seg);\n\
+             }}\n",
+            bytes.join(", ")
+        ),
+    )
+    .unwrap();
+    let binary = out_dir.join("hooks_test");
+    let status = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_owned()))
+        .args(["-Wall", "-Werror", "-I"])
+        .arg(&out_dir)
+        .args(["-I", "../../bindings/c/include"])
+        .arg(&source)
+        .arg("-o")
+        .arg(&binary)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    // The count is capped by the constant in the header hook.
+    let status = Command::new(&binary).status().unwrap();
+    assert_eq!(status.code(), Some(3));
+}
+
+#[test]
+fn invalid_hooks() {
+    let message = panic_message(Language::Rust, &[("types/Greetings.rs", "")]);
+    assert_eq!(
+        message,
+        "the hook of the type (Greetings) is not a type in the schema"
+    );
+    let message = panic_message(Language::Lua, &[("header.lua", "")]);
+    assert_eq!(
+        message,
+        "the hooks are only supported by the C and the Rust generators"
+    );
+    // The hooks of other backends are ignored, such as the dissector of `.dissector.lua`.
+    Compiler::new()
+        .language(Language::Lua)
+        .file_path("schemas/hooks.mol")
+        .out_dir(env::temp_dir())
+        .hooks_dir("schemas/hooks")
+        .run();
+}
@@ -0,0 +1,54 @@
+const IMHEX: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.hexpat"));
+const COUNT_SIZE: &str = include_str!(concat!(env!("OUT_DIR"), "/count_size.hexpat"));
+
+fn position(name: &str) -> usize {
+    IMHEX
+        .find(&format!("    struct {}", name))
+        .unwrap_or_else(|| panic!("{}", name))
+}
+
+#[test]
+fn imhex_pattern() {
+    assert!(IMHEX.contains("\n#pragma endian little\n"));
+    assert!(IMHEX.contains("\nnamespace ci_tests {\n"));
+    assert!(IMHEX.ends_with("    };\n}\n"));
+    assert!(IMHEX.contains(
+        "    struct StructA {
+        u8 f1;
+        u8 f2;
+        Byte2 f3;
+        Byte2 f4;
+    };
+"
+    ));
+    assert!(IMHEX.contains(
+        "        u32 offsets[count];
+        u8 f1;
+        Word2 f2;
+        if (count > 2) u8 unknown_fields[total_size - offsets[2]];
+"
+    ));
+    assert!(COUNT_SIZE.contains("        u8 length;\n"));
+}
+
+#[test]
+fn imhex_sized_types() {
+    assert!(
+        IMHEX.contains("    struct BytesOpt<auto size> {\n        if (size > 0) Bytes value;\n")
+    );
+    assert!(IMHEX.contains("            (7): Table6Opt<size - 4> item;\n"));
+    assert!(IMHEX.contains(
+        "        BytesOpt<(parent.count == index + 1 ? parent.total_size : parent.offsets[index + 1]) - parent.offsets[index]> value [[inline]];\n"
+    ));
+}
+
+#[test]
+fn imhex_types_are_defined_before_use() {
+    for (dep, user) in &[
+        ("Byte2", "StructA"),
+        ("Table6", "Table6Opt"),
+        ("Table6Opt", "UnionA"),
+    ] {
+        assert!(position(dep) < position(user), "{}", user);
+    }
+}
@@ -0,0 +1,117 @@
+use std::{env, fs};
+
+use codegen::{Inferrer, Verifier};
+use molecule::prelude::*;
+
+use molecule_ci_tests::{constraints, required};
+
+fn bytes(data: &[u8]) -> required::Bytes {
+    required::Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+fn order(id: u32, amount: Option<u32>, memo: Option<&[u8]>) -> Vec<u8> {
+    let amount = amount.map(|amount| amount.to_le_bytes().into());
+    let memo = memo.map(bytes);
+    required::Order::new_builder()
+        .id(id.to_le_bytes().into())
+        .amount(required::Uint32Opt::new_builder().set(amount).build())
+        .memo(required::BytesOpt::new_builder().set(memo).build())
+        .build()
+        .as_slice()
+        .to_owned()
+}
+
+/// The inferred schema should accept all samples.
+fn check_samples(name: &str, schema: &str, samples: &[Vec<u8>]) {
+    let dir = env::temp_dir().join("molecule-ci-tests-inference");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{}.mol", name));
+    fs::write(&path, schema).unwrap();
+    let verifier = Verifier::new(&path, "Root");
+    for sample in samples {
+        assert_eq!(verifier.verify(sample), Ok(()));
+    }
+}
+
+#[test]
+fn infer_table() {
+    let samples = vec![
+        order(1, Some(7), Some(b"ab")),
+        order(2, None, Some(b"hello")),
+        order(3, Some(9), None),
+    ];
+    let schema = Inferrer::new().infer(&samples).unwrap();
+    assert_eq!(
+        schema,
+        "\
+// Inferred from 3 samples of Root; the names and the types should be reviewed.
+// Warning: Root.field3 is always none in the samples.
+
+array Byte4 [byte; 4];
+
+option RootField1 (Byte4);
+
+vector Bytes <byte>;
+
+option RootField2 (Bytes);
+
+option RootField3 (Bytes);
+
+table Root {
+    field0: Byte4,
+    field1: RootField1,
+    field2: RootField2,
+    field3: RootField3,
+}
+"
+    );
+    check_samples("table", &schema, &samples);
+}
+
+#[test]
+fn infer_vectors_and_unions() {
+    let names = |items: &[&[u8]]| {
+        constraints::Names::new_builder()
+            .extend(items.iter().map(|item| {
+                constraints::Bytes::new_builder()
+                    .extend(item.iter().copied().map(Byte::new))
+                    .build()
+            }))
+            .build()
+            .as_slice()
+            .to_owned()
+    };
+    let samples = vec![names(&[b"a", b"bc"]), names(&[]), names(&[b"def"])];
+    let schema = Inferrer::new().infer(&samples).unwrap();
+    assert!(schema.ends_with("\nvector Bytes <byte>;\n\nvector Root <Bytes>;\n"));
+    check_samples("dynvec", &schema, &samples);
+
+    let words = constraints::Words::new_builder()
+        .push([1, 0].into())
+        .push([2, 0].into())
+        .build();
+    let samples = vec![
+        constraints::Payload::new_builder()
+            .set(constraints::Bytes::new_builder().push(Byte::new(1)).build())
+            .build(),
+        constraints::Payload::new_builder().set(words).build(),
+    ]
+    .into_iter()
+    .map(|payload| payload.as_slice().to_owned())
+    .collect::<Vec<_>>();
+    let schema = Inferrer::new().infer(&samples).unwrap();
+    assert!(schema.contains("\nunion Root {\n    RootItem0,\n    RootItem1,\n}\n"));
+    assert!(schema.contains("\nvector RootItem1 <Byte2>;\n"));
+    check_samples("union", &schema, &samples);
+}
+
+#[test]
+fn no_samples() {
+    let samples: Vec<Vec<u8>> = Vec::new();
+    assert_eq!(
+        Inferrer::new().type_name("Blob").infer(&samples),
+        Err("there are no samples".to_owned())
+    );
+}
@@ -0,0 +1,210 @@
+use std::collections::BTreeMap;
+
+use codegen::IpldBridge;
+use molecule::{
+    content_hash::{self, IDENTITY},
+    ipld::{self, DecodeError, Ipld, DAG_CBOR_CODEC},
+    prelude::*,
+};
+
+use molecule_ci_tests::types::*;
+
+const SCHEMA: &str = "schemas/ci_tests.mol";
+
+fn map(entries: &[(&str, Ipld)]) -> Ipld {
+    Ipld::Map(
+        entries
+            .iter()
+            .map(|(key, value)| ((*key).to_owned(), value.clone()))
+            .collect::<BTreeMap<_, _>>(),
+    )
+}
+
+fn bytes(data: &[u8]) -> Bytes {
+    Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+fn table4() -> Table4 {
+    let word = Word::new_unchecked(vec![1, 2].into());
+    Table4::new_builder()
+        .f1(Byte::new(7))
+        .f2(Word2::new_builder().nth1(word).build())
+        .f3(StructA::new_builder().f1(Byte::new(3)).build())
+        .f4(bytes(&[4, 5]))
+        .build()
+}
+
+#[test]
+fn molecule_to_ipld() {
+    let node = IpldBridge::new(SCHEMA, "Table4")
+        .to_ipld(table4().as_slice())
+        .unwrap();
+    let expected = map(&[
+        ("f1", Ipld::Integer(7)),
+        (
+            "f2",
+            Ipld::List(vec![Ipld::Bytes(vec![0, 0]), Ipld::Bytes(vec![1, 2])]),
+        ),
+        (
+            "f3",
+            map(&[
+                ("f1", Ipld::Integer(3)),
+                ("f2", Ipld::Integer(0)),
+                ("f3", Ipld::Bytes(vec![0, 0])),
+                ("f4", Ipld::Bytes(vec![0, 0])),
+            ]),
+        ),
+        ("f4", Ipld::Bytes(vec![4, 5])),
+    ]);
+    assert_eq!(node, expected);
+
+    let union = UnionA::new_builder().set(bytes(&[1])).build();
+    let node = IpldBridge::new(SCHEMA, "UnionA")
+        .to_ipld(union.as_slice())
+        .unwrap();
+    assert_eq!(node, map(&[("Bytes", Ipld::Bytes(vec![1]))]));
+    let node = IpldBridge::new(SCHEMA, "BytesOpt")
+        .to_ipld(BytesOpt::default().as_slice())
+        .unwrap();
+    assert_eq!(node, Ipld::Null);
+
+    // The data is verified first.
+    assert!(IpldBridge::new(SCHEMA, "Table4")
+        .to_ipld(&table4().as_slice()[1..])
+        .is_err());
+}
+
+#[test]
+fn round_trips() {
+    for (type_name, data) in &[
+        ("AllInOne", AllInOne::default().as_bytes()),
+        ("Table4", table4().as_bytes()),
+        (
+            "UnionA",
+            UnionA::new_builder()
+                .set(Table6Opt::default())
+                .build()
+                .as_bytes(),
+        ),
+        (
+            "BytesVec",
+            BytesVec::new_builder()
+                .push(bytes(&[1, 2]))
+                .push(bytes(&[]))
+                .build()
+                .as_bytes(),
+        ),
+    ] {
+        let bridge = IpldBridge::new(SCHEMA, type_name);
+        let node = bridge.to_ipld(data).unwrap();
+        let encoded = ipld::encode(&node);
+        let decoded = ipld::decode(&encoded).unwrap();
+        assert_eq!(decoded, node, "{}", type_name);
+        assert_eq!(
+            bridge.from_ipld(&decoded).unwrap(),
+            data.to_vec(),
+            "{}",
+            type_name
+        );
+    }
+}
+
+#[test]
+fn invalid_nodes() {
+    let bridge = IpldBridge::new(SCHEMA, "Table4");
+    let node = bridge.to_ipld(table4().as_slice()).unwrap();
+    let mut entries = match node {
+        Ipld::Map(entries) => entries,
+        _ => unreachable!(),
+    };
+    entries.insert("f1".to_owned(), Ipld::Integer(256));
+    assert_eq!(
+        bridge.from_ipld(&Ipld::Map(entries.clone())).unwrap_err(),
+        "Table4.f1: byte expects an integer in 0..=255, but it is 256"
+    );
+    entries.insert("f1".to_owned(), Ipld::Integer(1));
+    entries.insert("f2".to_owned(), Ipld::List(vec![Ipld::Bytes(vec![0, 0])]));
+    assert_eq!(
+        bridge.from_ipld(&Ipld::Map(entries.clone())).unwrap_err(),
+        "Table4.f2: Word2 expects 2 items, but it has 1"
+    );
+    entries.remove("f2");
+    assert_eq!(
+        bridge.from_ipld(&Ipld::Map(entries.clone())).unwrap_err(),
+        "Table4 misses the field (f2)"
+    );
+    entries.insert("f5".to_owned(), Ipld::Null);
+    assert_eq!(
+        bridge.from_ipld(&Ipld::Map(entries)).unwrap_err(),
+        "Table4 has no field (f5)"
+    );
+    assert_eq!(
+        IpldBridge::new(SCHEMA, "UnionA")
+            .from_ipld(&map(&[("Table1", Ipld::Null)]))
+            .unwrap_err(),
+        "UnionA has no item (Table1)"
+    );
+    assert_eq!(
+        IpldBridge::new(SCHEMA, "BytesVec")
+            .from_ipld(&Ipld::List(vec![Ipld::List(Vec::new())]))
+            .unwrap_err(),
+        "BytesVec[0]: Bytes expects bytes, but it is a list"
+    );
+}
+
+#[test]
+fn dag_cbor() {
+    let node = map(&[
+        ("bb", Ipld::List(vec![Ipld::Bool(true), Ipld::Null])),
+        ("c", Ipld::Integer(-500)),
+        ("a", Ipld::String("x".to_owned())),
+        ("f", Ipld::Float(1.5)),
+    ]);
+    let encoded = ipld::encode(&node);
+    // The keys are sorted by their lengths first.
+    assert_eq!(
+        encoded,
+        [
+            &[0xa4, 0x61, b'a', 0x61, b'x', 0x61, b'c', 0x39, 0x01, 0xf3][..],
+            &[0x61, b'f', 0xfb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0],
+            &[0x62, b'b', b'b', 0x82, 0xf5, 0xf6],
+        ]
+        .concat()
+    );
+    assert_eq!(ipld::decode(&encoded).unwrap(), node);
+
+    let data = Table0::default().as_bytes();
+    let cid = content_hash::hash(IDENTITY, &data)
+        .map(|hash| content_hash::Cid {
+            codec: DAG_CBOR_CODEC,
+            hash,
+        })
+        .unwrap();
+    let link = Ipld::Link(cid.clone());
+    let encoded = ipld::encode(&link);
+    assert_eq!(&encoded[..4], &[0xd8, 0x2a, 0x49, 0x00]);
+    assert_eq!(&encoded[4..], &cid.to_bytes()[..]);
+    assert_eq!(ipld::decode(&encoded).unwrap(), link);
+
+    for (data, err) in &[
+        (&[0x18, 0x01][..], DecodeError::NotCanonical),
+        (
+            &[0xa2, 0x62, b'a', b'a', 0x00, 0x61, b'b', 0x00],
+            DecodeError::NotCanonical,
+        ),
+        (&[0xa1, 0x01, 0x00], DecodeError::InvalidKey),
+        (&[0x5f], DecodeError::Unsupported(0x5f)),
+        (&[0xf9, 0x3c, 0x00], DecodeError::Unsupported(0xf9)),
+        (&[0xc1, 0x00], DecodeError::InvalidLink),
+        (&[0x82, 0x00], DecodeError::UnexpectedEnd),
+        (&[0x00, 0x00], DecodeError::TrailingBytes(1)),
+        (
+            &[0x1b, 0x80, 0, 0, 0, 0, 0, 0, 0],
+            DecodeError::IntegerOverflow,
+        ),
+    ] {
+        assert_eq!(ipld::decode(data).unwrap_err(), *err, "{:02x?}", data);
+    }
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "json-schema")]
+
+use schemars::{json_schema, schema_for, Schema};
+
+use molecule_ci_tests::types;
+
+fn get_str<'a>(schema: &'a Schema, key: &str) -> Option<&'a str> {
+    schema.get(key).and_then(|v| v.as_str())
+}
+
+#[test]
+fn json_schema_for_bytes() {
+    let schema = schema_for!(types::Byte3);
+    assert_eq!(get_str(&schema, "title"), Some("Byte3"));
+    assert_eq!(get_str(&schema, "type"), Some("string"));
+    assert_eq!(get_str(&schema, "pattern"), Some("^0x([0-9a-fA-F]{2}){3}$"));
+    let schema = schema_for!(types::Bytes);
+    assert_eq!(get_str(&schema, "pattern"), Some("^0x([0-9a-fA-F]{2})*$"));
+}
+
+#[test]
+fn json_schema_for_struct() {
+    let schema = schema_for!(types::StructA);
+    assert_eq!(get_str(&schema, "type"), Some("object"));
+    assert_eq!(
+        json_schema!({ "required": ["f1", "f2", "f3", "f4"] }),
+        schema
+            .get("required")
+            .map(|v| json_schema!({ "required": v }))
+            .unwrap()
+    );
+    assert_eq!(
+        schema.get("additionalProperties").and_then(|v| v.as_bool()),
+        Some(false)
+    );
+    let properties = schema.get("properties").unwrap();
+    assert_eq!(
+        json_schema!({ "type": "integer", "minimum": 0, "maximum": 255 }),
+        properties["f1"]
+    );
+    assert_eq!(json_schema!({ "$ref": "#/$defs/Byte2" }), properties["f3"]);
+    assert!(schema.get("$defs").unwrap().get("Byte2").is_some());
+}
+
+#[test]
+fn json_schema_for_empty_table() {
+    let schema = schema_for!(types::Table0);
+    let properties = schema.get("properties").unwrap();
+    assert!(properties.as_object().unwrap().is_empty());
+}
+
+#[test]
+fn json_schema_for_option() {
+    let schema = schema_for!(types::WordOpt);
+    assert_eq!(
+        json_schema!({ "anyOf": [{ "$ref": "#/$defs/Word" }, { "type": "null" }] }),
+        schema
+            .get("anyOf")
+            .map(|v| json_schema!({ "anyOf": v }))
+            .unwrap()
+    );
+}
+
+#[test]
+fn json_schema_for_union() {
+    let schema = schema_for!(types::UnionA);
+    let items = schema.get("oneOf").unwrap().as_array().unwrap();
+    assert_eq!(items.len(), 8);
+    assert_eq!(
+        json_schema!({ "const": "byte" }),
+        items[0]["properties"]["type"]
+    );
+    assert_eq!(
+        json_schema!({ "$ref": "#/$defs/StructA" }),
+        items[2]["properties"]["value"]
+    );
+}
+
+#[test]
+fn json_schema_for_all_in_one() {
+    let schema = schema_for!(types::AllInOne);
+    let defs = schema.get("$defs").unwrap().as_object().unwrap();
+    for name in &["Table6OptOpt", "UnionA", "BytesOptVec", "StructIx3"] {
+        assert!(defs.contains_key(*name), "{} is not defined", name);
+    }
+}
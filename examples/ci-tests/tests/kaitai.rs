@@ -0,0 +1,36 @@
+const KAITAI: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.ksy"));
+const COUNT_SIZE: &str = include_str!(concat!(env!("OUT_DIR"), "/count_size.ksy"));
+
+#[test]
+fn kaitai_types() {
+    assert!(KAITAI.contains("\nmeta:\n  id: ci_tests\n"));
+    assert!(KAITAI.contains(
+        "  struct_a:
+    doc: The struct StructA.
+    seq:
+      - id: f1
+        type: u1
+      - id: f2
+        type: u1
+      - id: f3
+        type: byte2
+"
+    ));
+    assert!(KAITAI.contains(
+        "      - id: f2
+        type: word2
+        size: '(count == 2 ? total_size : next_offsets[1]) - next_offsets[0]'
+      - id: unknown_fields
+        size-eos: true
+"
+    ));
+    assert!(KAITAI.contains("        repeat-expr: count\n    instances:\n      count:\n"));
+    assert!(KAITAI.contains("          switch-on: item_id\n          cases:\n            0: u1\n"));
+    assert!(KAITAI.contains("        type: bytes\n        if: 'not _io.eof'\n"));
+}
+
+#[test]
+fn kaitai_count_size() {
+    assert!(COUNT_SIZE.contains("  tiny_bytes:\n    doc: The fixvec TinyBytes.\n    seq:\n      - id: length\n        type: u1\n"));
+    assert!(COUNT_SIZE.contains("      - id: items_field\n"));
+}
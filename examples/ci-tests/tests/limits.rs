@@ -0,0 +1,116 @@
+use std::{env, fs, panic};
+
+use codegen::{Compiler, Explorer, Language, Verifier};
+use molecule::{error::VerificationError, prelude::*};
+
+use molecule_ci_tests::limits::*;
+
+const C: &str = include_str!(concat!(env!("OUT_DIR"), "/limits.h"));
+
+fn bytes(size: usize) -> Bytes {
+    Bytes::new_builder()
+        .extend((0..size).map(|x| Byte::new(x as u8)))
+        .build()
+}
+
+fn message(size: usize) -> Message {
+    Message::new_builder().set(bytes(size)).build()
+}
+
+#[test]
+fn max_size_of_items() {
+    // The header of `Bytes` is 4 bytes, so it has 12 bytes at most.
+    let data = message(12).as_slice().to_vec();
+    assert!(Message::from_slice(&data).is_ok());
+    assert!(MessageReader::from_slice_linear(&data).is_ok());
+
+    let data = message(13).as_slice().to_vec();
+    let err = VerificationError::ItemTooLarge("MessageReader".to_owned(), 16, 17);
+    assert_eq!(MessageReader::verify(&data, false), Err(err.clone()));
+    assert_eq!(MessageReader::verify(&data, true), Err(err));
+    assert!(MessageReader::from_slice_linear(&data).is_err());
+    assert_eq!(
+        MessageReader::verify(&data, false).unwrap_err().to_string(),
+        "MessageReader item is too large, expect at most 16, actual 17"
+    );
+
+    // The other items are not limited.
+    let ping = Message::new_builder().set(Ping::default()).build();
+    assert!(Message::from_slice(ping.as_slice()).is_ok());
+}
+
+#[test]
+fn max_encoded_size() {
+    // The larger one of `Ping` and the limited `Bytes`.
+    assert_eq!(Message::MAX_ENCODED_SIZE, 4 + 16);
+}
+
+#[test]
+fn dynamic_layer() {
+    let verifier = Verifier::new("schemas/limits.mol", "Message");
+    assert!(verifier.verify(message(12).as_slice()).is_ok());
+    assert_eq!(
+        verifier.verify(message(13).as_slice()),
+        Err(VerificationError::ItemTooLarge(
+            "MessageReader".to_owned(),
+            16,
+            17
+        ))
+    );
+
+    let explorer = Explorer::new("schemas/limits.mol");
+    assert_eq!(
+        explorer.describe("Message").unwrap(),
+        "union Message { Ping, @max_size(16) Bytes, }"
+    );
+    let value = explorer
+        .salvage("Message", message(13).as_slice(), false)
+        .unwrap();
+    assert_eq!(value.errors()[0].0, "Message");
+    assert_eq!(value.errors()[0].1.kind(), "ItemTooLarge");
+}
+
+#[test]
+fn c_verification() {
+    assert!(C.contains("if (inner.size > 16)"));
+}
+
+#[test]
+fn invalid_max_size() {
+    let dir = env::temp_dir().join("molecule-ci-tests-limits");
+    fs::create_dir_all(&dir).unwrap();
+    for (name, schema, expected) in &[
+        (
+            "value",
+            "vector B <byte>; union U { @max_size B, }",
+            "the attribute `@max_size` requires a size",
+        ),
+        (
+            "name",
+            "vector B <byte>; union U { @max_size(large) B, }",
+            "the max size should be a number, but it is large",
+        ),
+        (
+            "small",
+            "vector B <byte>; union U { @max_size(2) B, }",
+            "the max size 2 of the item (B) in union (U) is less than its min size 4",
+        ),
+    ] {
+        let path = dir.join(format!("{}.mol", name));
+        fs::write(&path, schema).unwrap();
+        let err = panic::catch_unwind(|| {
+            Compiler::new()
+                .language(Language::Rust)
+                .file_path(&path)
+                .out_dir(&dir)
+                .run();
+        })
+        .unwrap_err();
+        let message = err
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| err.downcast_ref::<&str>().map(|s| (*s).to_owned()))
+            .unwrap();
+        assert_eq!(&message, expected);
+    }
+}
@@ -0,0 +1,80 @@
+use codegen::Mutator;
+use molecule::prelude::*;
+
+use molecule_ci_tests::{testset, types};
+
+// The linear verification should accept and reject the same data as the default one.
+macro_rules! check_linear {
+    ($type:ident) => {
+        check_linear!($type, $type::default());
+    };
+    ($type:ident, $value:expr) => {
+        let value = $value;
+        let data = value.as_slice();
+        assert!($type::from_slice_linear(data).is_ok());
+        let mutations = Mutator::new()
+            .file_path("schemas/ci_tests.mol")
+            .type_name(stringify!($type))
+            .mutate(data)
+            .unwrap();
+        for mutation in &mutations {
+            assert!(
+                $type::from_slice_linear(&mutation.data).is_err(),
+                "{}",
+                mutation.label
+            );
+            assert_eq!(
+                $type::from_compatible_slice_linear(&mutation.data).is_ok(),
+                mutation.compatible,
+                "{}",
+                mutation.label
+            );
+        }
+    };
+}
+
+#[test]
+fn linear_defaults() {
+    testset!(all, check_linear);
+}
+
+#[test]
+fn linear_nested() {
+    use types::*;
+    let bytes = Bytes::new_builder().push(0x12.into()).build();
+    let bytes_vec = BytesVec::new_builder()
+        .push(bytes.clone())
+        .push(Bytes::default())
+        .build();
+    check_linear!(BytesVec, bytes_vec.clone());
+    check_linear!(
+        BytesVecOpt,
+        BytesVecOpt::new_builder()
+            .set(Some(bytes_vec.clone()))
+            .build()
+    );
+    check_linear!(UnionA, UnionA::new_builder().set(bytes.clone()).build());
+    let table5 = Table5::new_builder().f4(bytes).f5(bytes_vec).build();
+    check_linear!(Table6, Table6::new_builder().f6(table5).build());
+}
+
+#[test]
+fn linear_reader() {
+    let table = types::Table2::new_builder().f1(0x12.into()).build();
+    let reader = types::Table2Reader::from_slice_linear(table.as_slice()).unwrap();
+    assert_eq!(reader.f1().as_slice(), &[0x12]);
+
+    // An offset which is out of the value is found before the fields after it are verified.
+    let mut data = table.as_slice().to_vec();
+    data[8] = 0xff;
+    let err = types::Table2Reader::from_slice_linear(&data).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        types::Table2Reader::from_slice(&data).unwrap_err().kind()
+    );
+
+    // Extra fields are only allowed in the compatible mode.
+    let extra = types::Table3::default();
+    assert!(types::Table2::from_slice_linear(extra.as_slice()).is_err());
+    assert!(types::Table2::from_compatible_slice_linear(extra.as_slice()).is_ok());
+}
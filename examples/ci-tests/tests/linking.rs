@@ -0,0 +1,25 @@
+use molecule::prelude::*;
+
+use molecule_ci_tests::{app, common};
+
+#[test]
+fn imported_types_are_shared() {
+    let hash = common::Byte32::new_builder().nth0(1.into()).build();
+    let payload = common::Bytes::new_builder().push(2.into()).build();
+    let message = app::Message::new_builder()
+        .hash(hash.clone())
+        .payload(payload.clone())
+        .build();
+    let hash_from_message: common::Byte32 = message.hash();
+    assert_eq!(hash_from_message.as_slice(), hash.as_slice());
+    let payload_from_message: common::BytesReader = message.as_reader().payload();
+    assert_eq!(payload_from_message.raw_data(), &[2][..]);
+}
+
+#[test]
+fn imported_types_are_not_generated_again() {
+    let code = include_str!(concat!(env!("OUT_DIR"), "/", "app", ".rs"));
+    assert!(code.contains("use crate :: common :: * ;"));
+    assert!(!code.contains("pub struct Byte32 ("));
+    assert!(!code.contains("pub struct Bytes ("));
+}
@@ -0,0 +1,100 @@
+use std::{env, fs, path::PathBuf};
+
+use codegen::{find_lint, lint, Compiler, Language, Level, LintLevels, LINTS};
+
+const SCHEMA: &str = r#"
+vector Bytes <byte>;
+
+table bad_name {
+    fieldA: Bytes,
+}
+
+union Wrapped {
+    Bytes,
+}
+"#;
+
+fn schema_file() -> PathBuf {
+    let dir = env::temp_dir().join("molecule-ci-tests-lints");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("lints.mol");
+    fs::write(&path, SCHEMA).unwrap();
+    path
+}
+
+fn codes(levels: &LintLevels) -> Vec<(&'static str, Level)> {
+    lint(schema_file(), levels)
+        .into_iter()
+        .map(|x| (x.lint.code, x.level))
+        .collect()
+}
+
+#[test]
+fn stable_lint_codes() {
+    let mut codes: Vec<_> = LINTS.iter().map(|lint| lint.code).collect();
+    codes.sort_unstable();
+    codes.dedup();
+    assert_eq!(codes.len(), LINTS.len());
+    assert_eq!(find_lint("non-camel-case-types").unwrap().code, "M0001");
+    assert_eq!(find_lint("M0003").unwrap().name, "union-single-item");
+    assert!(find_lint("warnings").is_none());
+}
+
+#[test]
+fn default_levels() {
+    assert_eq!(
+        codes(&LintLevels::new()),
+        vec![("M0001", Level::Warn), ("M0002", Level::Warn)]
+    );
+    assert!(lint("schemas/ci_tests.mol", &LintLevels::new()).is_empty());
+}
+
+#[test]
+fn override_levels() {
+    let mut levels = LintLevels::new();
+    levels
+        .deny_warnings(true)
+        .set("non-snake-case-fields", Level::Allow)
+        .set("M0003", Level::Warn);
+    // The warnings are denied, including the ones which are enabled explicitly.
+    assert_eq!(
+        codes(&levels),
+        vec![("M0001", Level::Deny), ("M0003", Level::Deny)]
+    );
+    let diagnostics = lint(schema_file(), &levels);
+    assert_eq!(
+        diagnostics[0].to_string(),
+        "error[M0001]: bad_name: the type name `bad_name` is not in UpperCamelCase \
+         [non-camel-case-types]"
+    );
+}
+
+#[test]
+fn diagnostics_as_json_messages() {
+    let diagnostics = lint(schema_file(), &LintLevels::new());
+    assert_eq!(diagnostics[1].position, Some((4, 7)));
+    let message: serde_json::Value = serde_json::from_str(&diagnostics[1].json_message()).unwrap();
+    assert_eq!(message["reason"], "compiler-message");
+    assert_eq!(message["message"]["level"], "warning");
+    assert_eq!(message["message"]["code"]["code"], "M0002");
+    assert_eq!(
+        message["message"]["message"],
+        "the field name `fieldA` is not in snake_case"
+    );
+    let span = &message["message"]["spans"][0];
+    assert_eq!(span["file_name"], schema_file().to_string_lossy().as_ref());
+    assert_eq!(span["line_start"], 4);
+}
+
+#[test]
+#[should_panic(expected = "aborting due to 2 denied diagnostics")]
+fn compiler_aborts_on_denied_lints() {
+    let mut levels = LintLevels::new();
+    levels.deny_warnings(true);
+    Compiler::new()
+        .language(Language::Rust)
+        .file_path(schema_file())
+        .out_dir(env::temp_dir())
+        .lint_levels(levels)
+        .run();
+}
@@ -0,0 +1,164 @@
+#[cfg(feature = "std")]
+use std::time::UNIX_EPOCH;
+use std::{env, fs, panic, time::Duration};
+
+#[cfg(feature = "uuid")]
+use codegen::Explorer;
+use codegen::{Compiler, Language};
+#[cfg(feature = "uuid")]
+use molecule::{
+    logical::{AsUuid, FromUuid},
+    uuid::Uuid,
+};
+use molecule::{
+    logical::{FromUnixTime, TimeUnit, UnixTime},
+    prelude::*,
+};
+use slices::u8_slice as s;
+
+use molecule_ci_tests::logical::*;
+
+#[test]
+fn timestamps() {
+    let since = Duration::from_millis(1_700_000_000_123);
+    let timestamp = Timestamp::from_since_unix_epoch(since).unwrap();
+    // The milliseconds in a little-endian `u64`.
+    assert_eq!(timestamp.as_slice(), &s!("0x7b68e5cf8b010000")[..]);
+    assert_eq!(timestamp.since_unix_epoch(), since);
+    assert_eq!(timestamp.as_reader().since_unix_epoch(), since);
+    assert!(Timestamp::from_since_unix_epoch(Duration::MAX).is_none());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn system_times() {
+    let since = Duration::from_millis(1_700_000_000_123);
+    let timestamp = Timestamp::from_since_unix_epoch(since).unwrap();
+    assert_eq!(timestamp.to_system_time(), UNIX_EPOCH + since);
+
+    // The part which is less than a tick is truncated.
+    let seconds = UnixSeconds::from_system_time(UNIX_EPOCH + Duration::from_millis(2_500)).unwrap();
+    assert_eq!(seconds.since_unix_epoch(), Duration::from_secs(2));
+    assert!(UnixSeconds::from_system_time(UNIX_EPOCH - Duration::from_secs(1)).is_none());
+}
+
+#[test]
+fn durations() {
+    let timeout = Timeout::from_duration(Duration::from_micros(1_500)).unwrap();
+    assert_eq!(timeout.as_slice(), &s!("0x60e3160000000000")[..]);
+    assert_eq!(
+        timeout.as_reader().to_duration(),
+        Duration::from_micros(1_500)
+    );
+    assert_eq!(
+        Duration::from(timeout.clone()),
+        Duration::from_micros(1_500)
+    );
+
+    let event = Event::new_builder()
+        .created_at(Timestamp::from_since_unix_epoch(Duration::from_secs(1)).unwrap())
+        .expires_in(timeout)
+        .build();
+    let reader = EventReader::from_slice(event.as_slice()).unwrap();
+    assert_eq!(
+        reader.created_at().since_unix_epoch(),
+        Duration::from_secs(1)
+    );
+    assert_eq!(
+        reader.expires_in().to_duration(),
+        Duration::from_micros(1_500)
+    );
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn uuids() {
+    let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+    let id = EventId::from_uuid(uuid);
+    // The bytes are in the order of the canonical string.
+    assert_eq!(id.as_slice(), &s!("0x67e5504410b1426f9247bb680e5fe0c8")[..]);
+    assert_eq!(id.to_uuid(), uuid);
+    assert_eq!(id.as_reader().uuid_bytes(), *uuid.as_bytes());
+    assert_eq!(
+        EventId::from_uuid_bytes(*uuid.as_bytes()).as_slice(),
+        id.as_slice()
+    );
+
+    let text = "EventId(67e55044-10b1-426f-9247-bb680e5fe0c8)";
+    assert_eq!(id.to_string(), text);
+    assert_eq!(format!("{:?}", id), text);
+    assert_eq!(
+        format!("{:?}", id.as_reader()),
+        "EventIdReader(67e55044-10b1-426f-9247-bb680e5fe0c8)"
+    );
+    let event = Event::new_builder().id(id).build();
+    assert!(event.to_string().contains(text));
+    let decoded = Explorer::new("schemas/logical.mol")
+        .decode("Event", event.as_slice(), false)
+        .unwrap();
+    assert_eq!(decoded, event.to_string());
+}
+
+#[test]
+fn time_units() {
+    assert_eq!(TimeUnit::Micros.to_duration(3), Duration::from_micros(3));
+    assert_eq!(
+        TimeUnit::Seconds.to_ticks(Duration::from_millis(1_999)),
+        Some(1)
+    );
+    assert_eq!(
+        TimeUnit::Nanos.to_ticks(Duration::from_secs(u64::MAX)),
+        None
+    );
+}
+
+#[test]
+fn invalid_logical_types() {
+    let dir = env::temp_dir().join("molecule-ci-tests-logical");
+    fs::create_dir_all(&dir).unwrap();
+    for (name, schema, expected) in &[
+        (
+            "unknown",
+            "@logical(unix_days) array T [byte; 8];",
+            "the logical type should be unix_{seconds,millis,micros,nanos}, \
+             duration_{seconds,millis,micros,nanos} or uuid, but it is unix_days",
+        ),
+        (
+            "no-type",
+            "@logical array T [byte; 8];",
+            "the attribute `@logical` requires a type",
+        ),
+        (
+            "size",
+            "@logical(unix_millis) array T [byte; 4];",
+            "the logical type of array (T) requires 8 bytes, but it is [byte; 4]",
+        ),
+        (
+            "uuid",
+            "@logical(uuid) array T [byte; 8];",
+            "the logical type of array (T) requires 16 bytes, but it is [byte; 8]",
+        ),
+        (
+            "table",
+            "@logical(unix_millis) table T {}",
+            "the attribute `@logical` is only for arrays",
+        ),
+    ] {
+        let path = dir.join(format!("{}.mol", name));
+        fs::write(&path, schema).unwrap();
+        let err = panic::catch_unwind(|| {
+            Compiler::new()
+                .language(Language::Rust)
+                .file_path(&path)
+                .out_dir(&dir)
+                .run();
+        })
+        .unwrap_err();
+        let message = err
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| err.downcast_ref::<&str>().map(|s| (*s).to_owned()))
+            .unwrap();
+        assert_eq!(&message, expected);
+    }
+}
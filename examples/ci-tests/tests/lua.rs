@@ -0,0 +1,65 @@
+mod common;
+
+const LUA: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.lua"));
+
+#[test]
+fn lua_module() {
+    assert!(LUA.contains("\nlocal M = {}\n"));
+    assert!(LUA.ends_with("\nreturn M\n"));
+    assert!(LUA.contains(
+        "M.StructA = { size = 6 }
+function M.StructA.decode(data)
+  if #data ~= 6 then return nil, \"total_size\" end
+  return decode_fields({ data:sub(1, 1), data:sub(2, 2), data:sub(3, 4), data:sub(5, 6) }, {"
+    ));
+    assert!(LUA.contains(
+        "  if #slices ~= 2 then return nil, \"field_count\" end
+  return decode_fields(slices, {
+    { \"f1\", decode_byte },
+    { \"f2\", M.Word2.decode },
+  })"
+    ));
+    assert!(LUA.contains("  return decode_items(data, 5, count, 2, M.Word.decode)\n"));
+    assert!(LUA.contains("    return union_item(id, \"StructA\", M.StructA.decode(body))\n"));
+}
+
+#[test]
+fn lua_tables_are_unique() {
+    common::assert_unique(
+        LUA.lines()
+            .filter(|line| line.starts_with("M.") && line.contains(" = {"))
+            .map(|line| line.split(' ').next().unwrap()),
+    );
+}
+
+#[test]
+#[cfg_attr(not(round_trip), ignore = "requires Lua, run with ROUND_TRIP=1")]
+fn lua_round_trip() {
+    let samples = common::samples();
+    let dir = common::workspace(
+        "lua",
+        &[
+            ("ci_tests.lua", LUA),
+            ("round_trip.lua", include_str!("round_trip/round_trip.lua")),
+        ],
+    );
+    let args = [
+        "round_trip.lua",
+        &common::hex(&samples.table6),
+        &common::hex(&samples.union_a),
+        &common::hex(&samples.broken),
+    ];
+    let output = common::run(&dir, "lua", &args);
+    // The module only decodes, so the decoded values are compared with the ones of the samples,
+    // whose bytes are in hex and whose fields are sorted by their names.
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            "{f1=18,f2=[3412,7856],f3={f1=1,f2=2,f3=0304,f4=0506},f4=070809,f5=[0a,0b0c],\
+             f6={f1=5,f2=[0000,0000],f3={f1=0,f2=0,f3=0000,f4=0000},f4=06,f5=[]}}",
+            "Bytes 010203",
+            "total_size",
+        ]
+    );
+}
@@ -0,0 +1,65 @@
+use molecule::prelude::*;
+
+use molecule_ci_tests::{count_size, types};
+
+#[test]
+fn fixed_size_types() {
+    assert_eq!(types::Byte3::MAX_ENCODED_SIZE, types::Byte3::TOTAL_SIZE);
+    assert_eq!(types::StructA::MAX_ENCODED_SIZE, types::StructA::TOTAL_SIZE);
+    // A table of fixed-size fields has only one size in the strict mode.
+    assert_eq!(
+        types::Table3::MAX_ENCODED_SIZE,
+        types::Table3::default().as_slice().len()
+    );
+    assert_eq!(types::Table0::MAX_ENCODED_SIZE, molecule::NUMBER_SIZE);
+    assert_eq!(
+        types::StructAOpt::MAX_ENCODED_SIZE,
+        types::StructA::TOTAL_SIZE
+    );
+}
+
+#[test]
+fn bounded_vectors() {
+    use count_size::*;
+    assert_eq!(TinyBytes::MAX_ENCODED_SIZE, 1 + 255);
+    assert_eq!(ShortByte2Vec::MAX_ENCODED_SIZE, 2 + 2 * 65535);
+    assert_eq!(TinyBytesOpt::MAX_ENCODED_SIZE, TinyBytes::MAX_ENCODED_SIZE);
+    assert_eq!(
+        TinyUnion::MAX_ENCODED_SIZE,
+        molecule::NUMBER_SIZE + TinyBytes::MAX_ENCODED_SIZE
+    );
+    assert_eq!(
+        Header::MAX_ENCODED_SIZE,
+        molecule::NUMBER_SIZE * 3 + TinyBytesOpt::MAX_ENCODED_SIZE + TinyUnion::MAX_ENCODED_SIZE
+    );
+    assert_eq!(HeaderReader::MAX_ENCODED_SIZE, Header::MAX_ENCODED_SIZE);
+
+    // The largest value is as large as the bound.
+    let tag = TinyBytes::new_builder()
+        .extend((0..255).map(Byte::new))
+        .build();
+    let header = Header::new_builder()
+        .tag(TinyBytesOpt::new_builder().set(Some(tag.clone())).build())
+        .kind(TinyUnion::new_builder().set(tag).build())
+        .build();
+    assert_eq!(header.as_slice().len(), Header::MAX_ENCODED_SIZE);
+}
+
+#[test]
+fn list_bounds() {
+    let sizes = codegen::max_encoded_sizes("schemas/count_size.mol");
+    let unbounded: Vec<_> = sizes
+        .iter()
+        .filter(|(_, size)| size.is_none())
+        .map(|(name, _)| name.as_str())
+        .collect();
+    assert_eq!(unbounded, vec!["Bytes", "Packet"]);
+    assert!(sizes.contains(&(
+        "Header".to_owned(),
+        Some(count_size::Header::MAX_ENCODED_SIZE)
+    )));
+    let sizes = codegen::max_encoded_sizes("schemas/ci_tests.mol");
+    assert!(sizes.contains(&("Bytes".to_owned(), None)));
+    assert!(sizes.contains(&("BytesVec".to_owned(), None)));
+    assert!(sizes.contains(&("Table3".to_owned(), Some(types::Table3::MAX_ENCODED_SIZE))));
+}
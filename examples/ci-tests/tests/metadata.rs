@@ -0,0 +1,75 @@
+use serde_json::Value;
+
+use molecule_ci_tests::{numbers, styled, types};
+
+const RUST: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.rs"));
+const C: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.h"));
+const OPENAPI: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.json"));
+const SQL: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.sql"));
+
+fn schema_version(schema: &str) -> String {
+    codegen::schema_hash(schema)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn metadata_comment(code: &str, prefix: &str) -> Value {
+    let marker = format!("{} molecule-metadata: ", prefix);
+    let line = code
+        .lines()
+        .find(|line| line.starts_with(&marker))
+        .unwrap_or_else(|| panic!("no metadata in {}", code));
+    serde_json::from_str(&line[marker.len()..]).unwrap()
+}
+
+fn expected() -> Value {
+    serde_json::json!({
+        "namespace": "ci_tests",
+        "generator_version": codegen::VERSION,
+        "schema_version": schema_version("schemas/ci_tests.mol"),
+    })
+}
+
+#[test]
+fn rust_constants() {
+    assert_eq!(types::constants::GENERATOR_VERSION, codegen::VERSION);
+    assert_eq!(
+        types::constants::SCHEMA_VERSION,
+        schema_version("schemas/ci_tests.mol")
+    );
+    assert_eq!(
+        numbers::constants::SCHEMA_VERSION,
+        schema_version("schemas/numbers.mol")
+    );
+    // The style doesn't change the metadata.
+    assert_eq!(
+        styled::types::constants::SCHEMA_VERSION,
+        types::constants::SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn metadata_comments() {
+    assert_eq!(metadata_comment(RUST, "//"), expected());
+    assert_eq!(metadata_comment(C, "//"), expected());
+    assert_eq!(metadata_comment(SQL, "--"), expected());
+    let document: Value = serde_json::from_str(OPENAPI).unwrap();
+    assert_eq!(document["info"]["x-molecule"], expected());
+}
+
+#[test]
+fn c_defines() {
+    for define in &[
+        format!(
+            "#define CI_TESTS_GENERATOR_VERSION \"{}\"\n",
+            codegen::VERSION
+        ),
+        format!(
+            "#define CI_TESTS_SCHEMA_VERSION \"{}\"\n",
+            schema_version("schemas/ci_tests.mol")
+        ),
+    ] {
+        assert!(C.contains(define.as_str()), "{} is not found", define);
+    }
+}
@@ -0,0 +1,201 @@
+use std::{env, fs, panic, path::PathBuf};
+
+use codegen::Migration;
+use molecule::{migration::MigrationError, prelude::*};
+
+use molecule_ci_tests::migration::{new, old, shims};
+
+const SHIMS: &str = include_str!(concat!(env!("OUT_DIR"), "/migration/shims.rs"));
+
+fn uint32(value: u32) -> old::Uint32 {
+    old::Uint32::new_unchecked(value.to_le_bytes().to_vec().into())
+}
+
+fn bytes(data: &[u8]) -> old::Bytes {
+    old::Bytes::new_builder()
+        .set(data.iter().copied().map(Byte::new).collect())
+        .build()
+}
+
+fn old_account(id: u32, legacy: u8) -> old::Account {
+    let point = old::Point::new_builder().x(uint32(1)).y(uint32(2)).build();
+    old::Account::new_builder()
+        .id(uint32(id))
+        .name(bytes(b"alice"))
+        .legacy(Byte::new(legacy))
+        .point(point)
+        .build()
+}
+
+#[test]
+fn upgrade_fills_defaults() {
+    let account = shims::upgrade::account(old_account(9, 3).as_slice()).unwrap();
+    assert_eq!(account.id().as_slice(), &[9, 0, 0, 0]);
+    assert_eq!(&account.display_name().raw_data()[..], &b"alice"[..]);
+    assert_eq!(account.point().x().as_slice(), &[1, 0, 0, 0]);
+    assert_eq!(account.point().z().as_slice(), &[7, 0, 0, 0]);
+    assert!(account.nickname().is_none());
+    assert_eq!(account.level(), Byte::new(1));
+}
+
+#[test]
+fn downgrade_drops_fields() {
+    let upgraded = shims::upgrade::account(old_account(9, 3).as_slice()).unwrap();
+    let account = shims::downgrade::account(upgraded.as_slice()).unwrap();
+    assert_eq!(account.legacy(), Byte::new(0x2a));
+    assert_eq!(
+        account.point().as_slice(),
+        old_account(9, 3).point().as_slice()
+    );
+
+    let original = old_account(9, 0x2a);
+    let upgraded = shims::upgrade::account(original.as_slice()).unwrap();
+    let downgraded = shims::downgrade::account(upgraded.as_slice()).unwrap();
+    assert_eq!(downgraded.as_slice(), original.as_slice());
+}
+
+#[test]
+fn renamed_fields() {
+    let range = old::Range::new_builder()
+        .start(uint32(3))
+        .end(uint32(5))
+        .build();
+    let upgraded = shims::upgrade::range(range.as_slice()).unwrap();
+    assert_eq!(upgraded.begin().as_slice(), &[3, 0, 0, 0]);
+    assert_eq!(upgraded.as_slice(), range.as_slice());
+    let downgraded = shims::downgrade::range(upgraded.as_slice()).unwrap();
+    assert_eq!(downgraded.start().as_slice(), &[3, 0, 0, 0]);
+
+    // Only the names are changed, so the bytes are copied instead of re-encoded.
+    let shim = SHIMS
+        .split("pub fn range_reader")
+        .nth(1)
+        .unwrap()
+        .split("pub fn")
+        .next()
+        .unwrap();
+    assert!(shim.contains("new_unchecked"), "{}", shim);
+    assert!(!shim.contains("new_builder"), "{}", shim);
+}
+
+#[test]
+fn renamed_types() {
+    let accounts = old::Accounts::new_builder()
+        .push(old_account(1, 0))
+        .push(old_account(2, 0))
+        .build();
+    let events = old::Events::new_builder()
+        .push(old::Event::new_builder().set(old_account(3, 0)).build())
+        .build();
+    let ledger = old::Ledger::new_builder()
+        .accounts(accounts)
+        .events(events)
+        .build();
+    let book: new::Book = shims::upgrade::ledger(ledger.as_slice()).unwrap();
+    assert_eq!(book.accounts().len(), 2);
+    assert_eq!(
+        book.accounts().get(1).unwrap().id().as_slice(),
+        &[2, 0, 0, 0]
+    );
+    match book.events().get(0).unwrap().to_enum() {
+        new::EventUnion::Account(account) => assert_eq!(account.level(), Byte::new(1)),
+        item => panic!("unexpected item {}", item),
+    }
+
+    let ledger: old::Ledger = shims::downgrade::book(book.as_slice()).unwrap();
+    assert_eq!(ledger.accounts().len(), 2);
+    assert!(ledger
+        .obsolete()
+        .point()
+        .as_slice()
+        .iter()
+        .all(|byte| *byte == 0));
+}
+
+#[test]
+fn unmapped_union_items() {
+    let event = old::Event::new_builder().set(bytes(b"removed")).build();
+    match shims::upgrade::event(event.as_slice()) {
+        Err(MigrationError::UnmappedItem(name, item_id)) => {
+            assert_eq!(name, "Event");
+            assert_eq!(item_id, 2);
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+    let note = new::Note::new_builder()
+        .text(new::Bytes::new_unchecked(bytes(b"added").as_bytes()))
+        .build();
+    let event = new::Event::new_builder().set(note).build();
+    assert!(matches!(
+        shims::downgrade::event(event.as_slice()),
+        Err(MigrationError::UnmappedItem(..))
+    ));
+}
+
+#[test]
+fn invalid_input() {
+    let err = shims::upgrade::account(&[0, 1, 2]).unwrap_err();
+    assert!(matches!(err, MigrationError::Verification(_)));
+    assert!(err.to_string().starts_with("invalid input: Account"));
+}
+
+fn generate(name: &str, old: &str, new: &str, mapping: &str) -> Result<String, String> {
+    let dir = env::temp_dir()
+        .join("molecule-ci-tests-migration")
+        .join(name);
+    fs::create_dir_all(&dir).unwrap();
+    let write = |file: &str, content: &str| -> PathBuf {
+        let path = dir.join(file);
+        fs::write(&path, content).unwrap();
+        path
+    };
+    let old = write("old.mol", old);
+    let new = write("new.mol", new);
+    let mapping = write("mapping.json", mapping);
+    panic::catch_unwind(|| {
+        let mut output = Vec::new();
+        Migration::new()
+            .old_file_path(&old)
+            .new_file_path(&new)
+            .mapping_file(&mapping)
+            .generate(&mut output)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    })
+    .map_err(|err| err.downcast_ref::<String>().unwrap().to_owned())
+}
+
+#[test]
+fn mapping_errors() {
+    let old = "array Uint32 [byte; 4];\ntable T { a: Uint32, }\n";
+    let new = "array Uint64 [byte; 8];\ntable T { a: Uint64, }\n";
+    assert_eq!(
+        generate("changed", old, new, "{}").unwrap_err(),
+        "`T.a` changes from `Uint32` to `Uint64`, which can't be migrated"
+    );
+    let mapping = r#"{ "types": { "T": null } }"#;
+    let code = generate("removed", old, new, mapping).unwrap();
+    assert!(!code.contains("pub fn t ("));
+
+    let new = "array Uint32 [byte; 4];\ntable T { a: Uint32, b: byte, }\n";
+    let mapping = r#"{ "defaults": { "T.c": "00" } }"#;
+    assert_eq!(
+        generate("unused", old, new, mapping).unwrap_err(),
+        "the default of `T.c` is not used"
+    );
+    let mapping = r#"{ "defaults": { "T.b": "0000" } }"#;
+    assert!(generate("invalid", old, new, mapping)
+        .unwrap_err()
+        .starts_with("the default of `T.b` is invalid: "));
+
+    let mapping = r#"{ "fields": { "T.a": "c" } }"#;
+    assert_eq!(
+        generate("missing", old, new, mapping).unwrap_err(),
+        "the field `T.c` doesn't exist in the new schema"
+    );
+    let mapping = r#"{ "fields": { "T.a": "b" } }"#;
+    assert_eq!(
+        generate("mismatched", old, new, mapping).unwrap_err(),
+        "`T.b` changes from `Uint32` to `byte`, which can't be migrated"
+    );
+}
@@ -0,0 +1,50 @@
+use codegen::Minimizer;
+use molecule::prelude::*;
+use slices::u8_slice as s;
+
+use molecule_ci_tests::types;
+
+fn minimize(type_name: &str, data: &[u8]) -> Vec<u8> {
+    let (output, _) = Minimizer::new()
+        .file_path("schemas/ci_tests.mol")
+        .type_name(type_name)
+        .minimize(data)
+        .unwrap();
+    output
+}
+
+macro_rules! assert_same_failure {
+    ($type:ident, $input:expr, $output:expr) => {
+        let expected = types::$type::from_slice($input).unwrap_err();
+        let actual = types::$type::from_slice($output).unwrap_err();
+        assert_eq!(actual.kind(), expected.kind());
+        assert_eq!(actual.type_name(), expected.type_name());
+    };
+}
+
+#[test]
+fn minimize_header() {
+    let input = s!("0x09000000_0102030405");
+    let output = minimize("Bytes", input);
+    assert!(output.len() <= molecule::NUMBER_SIZE);
+    assert_same_failure!(Bytes, input, &output);
+}
+
+#[test]
+fn minimize_nested() {
+    // The second field of `Table2` is a `Word2` which has only 3 bytes.
+    let input = s!("0x10000000_0c000000_0d000000_01_020304");
+    let output = minimize("Table2", input);
+    assert!(output.len() < input.len());
+    assert_same_failure!(Table2, input, &output);
+}
+
+#[test]
+fn minimize_valid() {
+    let table = types::Table2::default();
+    let result = Minimizer::new()
+        .file_path("schemas/ci_tests.mol")
+        .type_name("Table2")
+        .minimize(table.as_slice());
+    assert!(result.is_err());
+}
@@ -0,0 +1,136 @@
+use molecule::{
+    molpack::{PackError, PackReader, PackWriter, FOOTER_SIZE, HEADER_SIZE, MAGIC},
+    prelude::*,
+};
+
+use molecule_ci_tests::types;
+
+const SCHEMA_HASH: [u8; 32] = [0x12; 32];
+
+fn bytes(data: &[u8]) -> types::Bytes {
+    types::Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+fn pack(records: &[&[u8]]) -> Vec<u8> {
+    let mut writer = PackWriter::new(&SCHEMA_HASH);
+    for record in records {
+        writer.append(bytes(record).as_slice()).unwrap();
+    }
+    writer.finish()
+}
+
+fn unpack(data: &[u8]) -> Vec<Vec<u8>> {
+    PackReader::new(data)
+        .unwrap()
+        .iter()
+        .map(|record| {
+            types::BytesReader::from_slice(record)
+                .unwrap()
+                .raw_data()
+                .to_vec()
+        })
+        .collect()
+}
+
+#[test]
+fn pack_and_unpack() {
+    let data = pack(&[&[0x01], &[], &[0x02, 0x03]]);
+    assert_eq!(&data[..MAGIC.len()], &MAGIC[..]);
+    assert_eq!(&data[data.len() - MAGIC.len()..], &MAGIC[..]);
+    let reader = PackReader::new(&data).unwrap();
+    assert_eq!(reader.schema_hash(), SCHEMA_HASH);
+    assert_eq!(reader.len(), 3);
+    assert_eq!(reader.get(1), Some(bytes(&[]).as_slice()));
+    assert!(reader.get(3).is_none());
+    assert_eq!(unpack(&data), vec![vec![0x01], vec![], vec![0x02, 0x03]]);
+}
+
+#[test]
+fn empty_pack() {
+    let data = pack(&[]);
+    assert_eq!(data.len(), HEADER_SIZE + FOOTER_SIZE);
+    let reader = PackReader::new(&data).unwrap();
+    assert!(reader.is_empty());
+    assert!(reader.iter().next().is_none());
+}
+
+#[test]
+fn append_to_pack() {
+    let data = pack(&[&[0x01]]);
+    let mut writer = PackWriter::from_pack(data).unwrap();
+    assert_eq!(writer.len(), 1);
+    writer.append(bytes(&[0x02, 0x03]).as_slice()).unwrap();
+    let data = writer.finish();
+    assert_eq!(data, pack(&[&[0x01], &[0x02, 0x03]]));
+    assert_eq!(unpack(&data), vec![vec![0x01], vec![0x02, 0x03]]);
+}
+
+#[test]
+fn broken_pack() {
+    let data = pack(&[&[0x01], &[0x02]]);
+    assert_eq!(
+        PackReader::new(&data[..HEADER_SIZE]).unwrap_err(),
+        PackError::Truncated
+    );
+    assert_eq!(
+        PackReader::new(&data[..data.len() - 1]).unwrap_err(),
+        PackError::Magic
+    );
+    let mut broken = data.clone();
+    broken[0] = 0;
+    assert_eq!(PackReader::new(&broken).unwrap_err(), PackError::Magic);
+    // The record count doesn't match the index.
+    let mut broken = data.clone();
+    broken[data.len() - FOOTER_SIZE] = 3;
+    assert_eq!(
+        PackReader::new(&broken).unwrap_err(),
+        PackError::IndexIsBroken
+    );
+    // The offsets are not in order.
+    let index_offset = data.len() - FOOTER_SIZE - 8;
+    let mut broken = data.clone();
+    broken[index_offset + 4] = 0xff;
+    assert_eq!(
+        PackReader::new(&broken).unwrap_err(),
+        PackError::IndexIsBroken
+    );
+    assert!(PackWriter::from_pack(broken).is_err());
+}
+
+#[test]
+fn schema_hash_ignores_comments() {
+    let dir = std::env::temp_dir().join("molecule-ci-tests-schema-hash");
+    std::fs::create_dir_all(&dir).unwrap();
+    let plain = dir.join("plain.mol");
+    let commented = dir.join("commented.mol");
+    let changed = dir.join("changed.mol");
+    std::fs::write(&plain, "array Byte2 [byte; 2];\ntable T { a: Byte2, }\n").unwrap();
+    std::fs::write(
+        &commented,
+        "// A pair of bytes.\narray Byte2 [byte;2];\n\ntable T {\n    a: Byte2, // the first field\n}\n",
+    )
+    .unwrap();
+    std::fs::write(&changed, "array Byte2 [byte; 2];\ntable T { b: Byte2, }\n").unwrap();
+    let hash = codegen::schema_hash(&plain);
+    assert_eq!(hash, codegen::schema_hash(&commented));
+    assert_ne!(hash, codegen::schema_hash(&changed));
+    assert_ne!(
+        codegen::schema_hash("schemas/count_size.mol"),
+        codegen::schema_hash("schemas/ci_tests.mol")
+    );
+}
+
+#[test]
+fn verify_by_schema() {
+    let verifier = codegen::Verifier::new("schemas/count_size.mol", "TinyBytes");
+    assert!(verifier.verify(&[0x01, 0x12]).is_ok());
+    assert!(verifier.verify(&[0x02, 0x12]).is_err());
+    // A table with more fields is only accepted in the compatible mode.
+    let table = types::Table1::new_builder().f1(0x12.into()).build();
+    let mut verifier = codegen::Verifier::new("schemas/ci_tests.mol", "Table0");
+    assert!(verifier.verify(table.as_slice()).is_err());
+    verifier.compatible(true);
+    assert!(verifier.verify(table.as_slice()).is_ok());
+}
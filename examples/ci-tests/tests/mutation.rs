@@ -0,0 +1,85 @@
+use codegen::{Mutation, Mutator};
+use molecule::prelude::*;
+
+use molecule_ci_tests::{testset, types};
+
+fn mutate(type_name: &str, data: &[u8]) -> Vec<Mutation> {
+    Mutator::new()
+        .file_path("schemas/ci_tests.mol")
+        .type_name(type_name)
+        .mutate(data)
+        .unwrap()
+}
+
+// The generated readers should fail as the labels say.
+macro_rules! check_mutations {
+    ($type:ident) => {
+        check_mutations!($type, $type::default());
+    };
+    ($type:ident, $value:expr) => {
+        let value = $value;
+        let mutations = mutate(stringify!($type), value.as_slice());
+        for mutation in &mutations {
+            let err = $type::from_slice(&mutation.data)
+                .map(|_| ())
+                .expect_err(&mutation.label);
+            assert_eq!(err.kind(), mutation.error.kind(), "{}", mutation.label);
+            assert_eq!(
+                err.type_name(),
+                mutation.error.type_name(),
+                "{}",
+                mutation.label
+            );
+            assert_eq!(
+                $type::from_compatible_slice(&mutation.data).is_ok(),
+                mutation.compatible,
+                "{}",
+                mutation.label
+            );
+        }
+    };
+}
+
+#[test]
+fn mutate_defaults() {
+    testset!(all, check_mutations);
+}
+
+#[test]
+fn mutate_nested() {
+    use types::*;
+    let bytes = Bytes::new_builder().push(0x12.into()).build();
+    let bytes_vec = BytesVec::new_builder()
+        .push(bytes.clone())
+        .push(Bytes::default())
+        .build();
+    check_mutations!(BytesVec, bytes_vec.clone());
+    check_mutations!(
+        BytesVecOpt,
+        BytesVecOpt::new_builder().set(Some(bytes_vec)).build()
+    );
+    check_mutations!(UnionA, UnionA::new_builder().set(bytes).build());
+}
+
+#[test]
+fn labels_are_stable() {
+    let data = types::Table1::new_builder().f1(0x12.into()).build();
+    let mutations = mutate("Table1", data.as_slice());
+    let labels: Vec<_> = mutations.iter().map(|x| x.label.as_str()).collect();
+    assert_eq!(labels[0], "total size of Table1 at 0: 9 -> 8");
+    assert!(labels.contains(&"offset 0 of Table1 at 4: 8 -> 9"));
+    assert!(labels.contains(&"append a byte"));
+    // Only the failures are kept.
+    assert!(mutations
+        .iter()
+        .all(|x| types::Table1Reader::verify(&x.data, false).is_err()));
+}
+
+#[test]
+fn mutate_invalid_input() {
+    assert!(Mutator::new()
+        .file_path("schemas/ci_tests.mol")
+        .type_name("Table1")
+        .mutate(&[0x00])
+        .is_err());
+}
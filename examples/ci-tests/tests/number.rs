@@ -0,0 +1,38 @@
+use std::convert::TryFrom;
+
+use molecule::{number::Number, prelude::*};
+use slices::u8_slice as s;
+
+use molecule_ci_tests::types;
+
+#[test]
+fn number_conversions() {
+    let number = Number::from_le_slice(&s!("0x0403020100")[..]);
+    assert_eq!(number.get(), 0x0102_0304);
+    assert_eq!(number.to_le_bytes(), [4, 3, 2, 1]);
+    assert_eq!(usize::from(number), 0x0102_0304);
+    assert_eq!(Number::SIZE.to_usize(), molecule::NUMBER_SIZE);
+    assert_eq!(Number::try_from(8usize), Ok(Number::new(8)));
+    assert_eq!(Number::MAX.checked_add(Number::new(1)), None);
+}
+
+#[cfg(target_pointer_width = "64")]
+#[test]
+fn number_overflow() {
+    let size = u32::MAX as usize + 1;
+    assert_eq!(Number::from_usize(size), None);
+    let err = Number::try_from(size).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "4294967296 doesn't fit in a molecule number"
+    );
+}
+
+#[test]
+fn number_headers() {
+    let table = types::Table2::new_builder().build();
+    let header = Number::from_le_slice(table.as_slice());
+    assert_eq!(header.to_usize(), table.as_slice().len());
+    let offset = Number::from_le_slice(&table.as_slice()[molecule::NUMBER_SIZE..]);
+    assert_eq!(offset, Number::new(12));
+}
@@ -0,0 +1,88 @@
+mod common;
+
+const OCAML: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.ml"));
+const KEYWORDS: &str = include_str!(concat!(env!("OUT_DIR"), "/styled/keywords.ml"));
+
+fn module_position(code: &str, name: &str) -> usize {
+    code.find(&format!("\nmodule {} = struct\n", name))
+        .unwrap_or_else(|| panic!("module {}", name))
+}
+
+#[test]
+fn ocaml_modules() {
+    assert!(OCAML.contains("\nmodule Mol = struct\n"));
+    assert!(OCAML.contains(
+        "  let f3 (T s) = Byte2.T (Mol.sub s 2 2)
+
+  let f4 (T s) = Byte2.T (Mol.sub s 4 2)
+
+  let build ~f1 ~f2 ~f3 ~f4 = T (Mol.of_bytes (Mol.concat [ Mol.byte f1; Mol.byte f2; Byte2.to_bytes f3; Byte2.to_bytes f4 ]))"
+    ));
+    assert!(OCAML.contains(
+        "    if count < 2 || ((not compatible) && count > 2) then Error Field_count
+    else
+      let* () = Mol.verify_byte (dynamic_at s 0) in
+      Word2.verify ~compatible (dynamic_at s 1)"
+    ));
+    assert!(OCAML.contains("    | StructA of StructA.t\n"));
+    assert!(OCAML.contains("  let build () = T (Mol.of_bytes (Mol.pack_dynamic []))\n"));
+}
+
+#[test]
+fn ocaml_modules_are_sorted() {
+    // `Bytes` is declared after `Keywords` in the schema.
+    assert!(module_position(KEYWORDS, "Bytes") < module_position(KEYWORDS, "Keywords"));
+    for (dep, typ) in &[("Byte2", "StructA"), ("Table6", "Table6Opt")] {
+        assert!(
+            module_position(OCAML, dep) < module_position(OCAML, typ),
+            "{}",
+            typ
+        );
+    }
+}
+
+#[test]
+fn ocaml_keywords() {
+    assert!(KEYWORDS.contains("  let try_ (T s) = Mol.get_byte (Mol.sub s 0 1)\n"));
+    assert!(KEYWORDS.contains("  let build ~try_ ~async = "));
+}
+
+#[test]
+fn ocaml_modules_are_unique() {
+    common::assert_unique(
+        OCAML
+            .lines()
+            .filter_map(|line| line.strip_prefix("module ")),
+    );
+}
+
+#[test]
+#[cfg_attr(not(round_trip), ignore = "requires OCaml, run with ROUND_TRIP=1")]
+fn ocaml_round_trip() {
+    let samples = common::samples();
+    let dir = common::workspace(
+        "ocaml",
+        &[
+            ("ci_tests.ml", OCAML),
+            ("round_trip.ml", include_str!("round_trip/round_trip.ml")),
+        ],
+    );
+    common::run(
+        &dir,
+        "ocamlc",
+        &["-o", "round_trip", "ci_tests.ml", "round_trip.ml"],
+    );
+    let args = [
+        common::hex(&samples.table6),
+        common::hex(&samples.union_a),
+        common::hex(&samples.broken),
+    ];
+    let program = dir.join("round_trip");
+    let output = common::run(
+        &dir,
+        program.to_str().unwrap(),
+        &[&args[0], &args[1], &args[2]],
+    );
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines, [args[0].as_str(), args[1].as_str(), "error"]);
+}
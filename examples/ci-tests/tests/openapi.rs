@@ -0,0 +1,75 @@
+use codegen::{Compiler, Language};
+use serde_json::{json, Value};
+
+const OPENAPI: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.json"));
+
+fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                if let ("$ref", Value::String(r)) = (key.as_str(), value) {
+                    refs.push(r);
+                } else {
+                    collect_refs(value, refs);
+                }
+            }
+        }
+        Value::Array(array) => array.iter().for_each(|v| collect_refs(v, refs)),
+        _ => {}
+    }
+}
+
+#[test]
+fn openapi_components() {
+    let document: Value = serde_json::from_str(OPENAPI).unwrap();
+    assert_eq!(document["openapi"], json!("3.1.0"));
+    assert_eq!(document["info"]["title"], json!("ci_tests"));
+    let schemas = document["components"]["schemas"].as_object().unwrap();
+    assert!(schemas.contains_key("AllInOne"));
+    assert_eq!(
+        schemas["Byte3"],
+        json!({ "type": "string", "pattern": "^0x([0-9a-fA-F]{2}){3}$" })
+    );
+    assert_eq!(
+        schemas["WordOpt"],
+        json!({ "anyOf": [{ "$ref": "#/components/schemas/Word" }, { "type": "null" }] })
+    );
+    let mut refs = Vec::new();
+    collect_refs(&document, &mut refs);
+    assert!(!refs.is_empty());
+    for r in refs {
+        let name = r.trim_start_matches("#/components/schemas/");
+        assert!(schemas.contains_key(name), "{} is not defined", r);
+    }
+}
+
+#[cfg(feature = "json-schema")]
+#[test]
+fn openapi_matches_json_schema() {
+    use schemars::schema_for;
+
+    let document: Value = serde_json::from_str(OPENAPI).unwrap();
+    let schemas = &document["components"]["schemas"];
+    let schema = serde_json::to_value(schema_for!(molecule_ci_tests::types::UnionA)).unwrap();
+    let expected = serde_json::to_string(&schemas["UnionA"])
+        .unwrap()
+        .replace("#/components/schemas/", "#/$defs/");
+    let expected: Value = serde_json::from_str(&expected).unwrap();
+    assert_eq!(schema["oneOf"], expected["oneOf"]);
+}
+
+#[test]
+fn openapi_exported_types() {
+    let check = |name: &str| {
+        Compiler::new()
+            .language(Language::OpenApi)
+            .file_path("schemas/ci_tests.mol")
+            .export_type(name)
+            .check_types()
+    };
+    assert_eq!(check("UnionA"), Ok(()));
+    assert_eq!(
+        check("Table9"),
+        Err("the type `Table9` is not defined".to_owned())
+    );
+}
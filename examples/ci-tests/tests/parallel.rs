@@ -0,0 +1,60 @@
+#![cfg(feature = "rayon")]
+
+use molecule::{parallel::rayon::prelude::*, prelude::*};
+
+use molecule_ci_tests::types;
+
+fn bytes(data: &[u8]) -> types::Bytes {
+    types::Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+#[test]
+fn dynvec_par_iter() {
+    let vec = types::BytesVec::new_builder()
+        .extend((0..100u8).map(|n| bytes(&vec![n; n as usize])))
+        .build();
+    let reader = vec.as_reader();
+    let sizes = reader
+        .par_iter()
+        .map(|item| item.raw_data().len())
+        .collect::<Vec<_>>();
+    assert_eq!(sizes, (0..100).collect::<Vec<_>>());
+    let sum: usize = reader
+        .into_par_iter()
+        .map(|item| item.raw_data().iter().map(|b| *b as usize).sum::<usize>())
+        .sum();
+    assert_eq!(sum, (0..100).map(|n| n * n).sum::<usize>());
+}
+
+#[test]
+fn fixvec_par_iter() {
+    let vec = types::Words::new_builder()
+        .extend((0..1000u16).map(|n| {
+            types::Word::new_builder()
+                .set(n.to_le_bytes().map(Byte::new))
+                .build()
+        }))
+        .build();
+    let reader = vec.as_reader();
+    assert_eq!(reader.par_iter().len(), 1000);
+    let words = reader
+        .par_iter()
+        .map(|item| u16::from_le_bytes([item.nth0().into(), item.nth1().into()]))
+        .collect::<Vec<_>>();
+    assert_eq!(words, (0..1000).collect::<Vec<_>>());
+    let rev = reader
+        .par_iter()
+        .rev()
+        .take(2)
+        .map(|item| item.as_slice().to_vec())
+        .collect::<Vec<_>>();
+    assert_eq!(rev, vec![vec![0xe7, 0x03], vec![0xe6, 0x03]]);
+}
+
+#[test]
+fn empty_par_iter() {
+    let vec = types::BytesVec::default();
+    assert_eq!(vec.as_reader().par_iter().count(), 0);
+}
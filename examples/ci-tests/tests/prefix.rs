@@ -0,0 +1,136 @@
+use molecule::{
+    error::{VerificationError, VerificationResult},
+    prelude::*,
+};
+use slices::u8_slice as s;
+
+use molecule_ci_tests::{testset, types};
+
+const TRAILING: &[u8] = &[0xff, 0x00, 0x12];
+
+// Read a prefix as the same type as the reader.
+fn from_slice_prefix<'r, R: Reader<'r>>(_: R, slice: &'r [u8]) -> (R, usize) {
+    R::from_slice_prefix(slice)
+        .unwrap_or_else(|err| panic!("failed to read the prefix of {}: {}", R::NAME, err))
+}
+
+macro_rules! read_prefix {
+    ($type:ident) => {
+        let expected = $type::default();
+        let mut data = expected.as_slice().to_vec();
+        data.extend_from_slice(TRAILING);
+        let (reader, consumed) = from_slice_prefix(expected.as_reader(), &data);
+        assert_eq!(consumed, expected.as_slice().len(), "{}", $type::NAME);
+        assert_eq!(reader.as_slice(), expected.as_slice(), "{}", $type::NAME);
+    };
+}
+
+#[test]
+fn read_prefix_with_trailing_bytes() {
+    testset!(array, read_prefix);
+    testset!(struct, read_prefix);
+    testset!(fixvec, read_prefix);
+    testset!(dynvec, read_prefix);
+    testset!(table, read_prefix);
+    testset!(union, read_prefix);
+    read_prefix!(AllInOne);
+}
+
+#[test]
+fn read_prefix_of_values() {
+    let bytes = types::Bytes::new_builder()
+        .push(0x12.into())
+        .push(0x34.into())
+        .build();
+    let union = types::UnionA::new_builder().set(bytes.clone()).build();
+    let mut data = union.as_slice().to_vec();
+    data.extend_from_slice(bytes.as_slice());
+    let (reader, consumed) = types::UnionAReader::from_slice_prefix(&data).unwrap();
+    assert_eq!(consumed, union.as_slice().len());
+    assert_eq!(reader.as_slice(), union.as_slice());
+    let rest = &data[consumed..];
+    let (reader, consumed) = types::BytesReader::from_compatible_slice_prefix(rest).unwrap();
+    assert_eq!(consumed, rest.len());
+    assert_eq!(reader.raw_data(), &[0x12, 0x34][..]);
+
+    let (reader, consumed) = ByteReader::from_slice_prefix(&data).unwrap();
+    assert_eq!(consumed, 1);
+    assert_eq!(reader.as_slice(), &data[..1]);
+}
+
+#[test]
+fn option_is_none_only_if_empty() {
+    let (reader, consumed) = types::BytesOptReader::from_slice_prefix(&[]).unwrap();
+    assert!(reader.is_none());
+    assert_eq!(consumed, 0);
+    let (reader, consumed) =
+        types::BytesOptReader::from_slice_prefix(s!("0x01000000_12_34")).unwrap();
+    assert!(reader.is_some());
+    assert_eq!(consumed, 5);
+}
+
+#[test]
+fn peek_size_does_not_verify() {
+    // The offsets are broken, but the total size is still in the header.
+    let data = s!("0x0c000000_0d000000_00000000_ff");
+    assert_eq!(types::BytesVecReader::peek_size(data).unwrap(), 12);
+    assert!(types::BytesVecReader::from_slice_prefix(data).is_err());
+}
+
+#[test]
+fn prefix_is_too_short() {
+    assert!(matches!(
+        types::BytesReader::from_slice_prefix(s!("0x02000000_12")),
+        Err(VerificationError::TotalSizeNotMatch(_, 6, 5))
+    ));
+    assert!(matches!(
+        types::Table0Reader::from_slice_prefix(s!("0x0400")),
+        Err(VerificationError::HeaderIsBroken(_, 4, 2))
+    ));
+    assert!(matches!(
+        types::StructAReader::from_slice_prefix(s!("0x0102")),
+        Err(VerificationError::TotalSizeNotMatch(_, 6, 2))
+    ));
+    assert!(matches!(
+        types::UnionAReader::from_slice_prefix(s!("0xff000000_00")),
+        Err(VerificationError::UnknownItem(_, _, 0xff))
+    ));
+    assert!(ByteReader::from_slice_prefix(&[]).is_err());
+}
+
+// A reader which is written by hand, without `peek_size`.
+#[derive(Debug, Clone, Copy)]
+struct Utf8Reader<'r>(types::BytesReader<'r>);
+
+impl<'r> Reader<'r> for Utf8Reader<'r> {
+    type Entity = types::Bytes;
+    const NAME: &'static str = "Utf8";
+    fn verify(slice: &[u8], compatible: bool) -> VerificationResult<()> {
+        types::BytesReader::verify(slice, compatible)?;
+        if std::str::from_utf8(&slice[molecule::NUMBER_SIZE..]).is_err() {
+            return Err(VerificationError::InvariantNotHeld(
+                Self::NAME.to_owned(),
+                "utf8".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+    fn new_unchecked(slice: &'r [u8]) -> Self {
+        Self(types::BytesReader::new_unchecked(slice))
+    }
+    fn as_slice(&self) -> &'r [u8] {
+        self.0.as_slice()
+    }
+    fn to_entity(&self) -> Self::Entity {
+        self.0.to_entity()
+    }
+}
+
+#[test]
+fn default_peek_size_takes_whole_slice() {
+    let data = s!("0x02000000_6f6b");
+    let (reader, consumed) = Utf8Reader::from_slice_prefix(data).unwrap();
+    assert_eq!(consumed, data.len());
+    assert_eq!(reader.as_slice(), data);
+    assert!(Utf8Reader::from_slice_prefix(s!("0x02000000_6f6b_ff")).is_err());
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "python")]
+
+use molecule::prelude::*;
+use pyo3::{prelude::*, types::PyDict, wrap_pymodule};
+
+use molecule_ci_tests::types;
+
+fn run(code: &str) {
+    Python::with_gil(|py| {
+        let globals = PyDict::new_bound(py);
+        globals
+            .set_item("ci_tests", wrap_pymodule!(types::python_module)(py))
+            .unwrap();
+        py.run_bound(code, Some(&globals), None)
+            .unwrap_or_else(|err| panic!("{}", err));
+    });
+}
+
+#[test]
+fn bytes_in_and_out() {
+    let table = types::Table2::new_builder().f1(0x12.into()).build();
+    let data = table
+        .as_slice()
+        .iter()
+        .map(|b| format!("\\x{:02x}", b))
+        .collect::<String>();
+    run(&format!(
+        r#"
+data = b"{data}"
+table = ci_tests.Table2(data)
+assert bytes(table) == data
+assert table.f1 == 0x12
+assert table == ci_tests.Table2.build(f1=0x12)
+assert hash(table) == hash(ci_tests.Table2(data))
+assert not table.is_default()
+assert ci_tests.Table2().is_default()
+try:
+    ci_tests.Table2(data[:-1])
+    raise AssertionError("the broken data is verified")
+except ValueError:
+    pass
+compatible = ci_tests.Table2.from_compatible_bytes(bytes(ci_tests.Table3()))
+assert compatible.f1 == 0 and not compatible.is_default()
+"#
+    ));
+}
+
+#[test]
+fn nested_values() {
+    run(r#"
+b = ci_tests.Bytes.build(b"\x01\x02")
+assert len(b) == 2 and b[1] == 2
+v = ci_tests.BytesVec.build([b, ci_tests.Bytes()])
+assert len(v) == 2 and v[0] == b
+try:
+    v[2]
+    raise AssertionError("the index is out of range")
+except IndexError:
+    pass
+opt = ci_tests.BytesOpt.build(b)
+assert opt.get() == b and not opt.is_none()
+assert ci_tests.BytesOpt().get() is None
+union = ci_tests.UnionA.build(b)
+assert union.item() == b and union.item_id() == 3
+assert ci_tests.UnionA.build(7).item() == 7
+byte3 = ci_tests.Byte3.build([1, 2, 3])
+assert [byte3[i] for i in range(len(byte3))] == [1, 2, 3]
+try:
+    ci_tests.Byte3.build([1, 2])
+    raise AssertionError("the count of items is wrong")
+except ValueError:
+    pass
+"#);
+}
@@ -0,0 +1,77 @@
+use std::{env, fs, panic, path::PathBuf};
+
+use codegen::{Compiler, Language};
+
+const LANGUAGES: &[(Language, &str)] = &[
+    (Language::Rust, "rs"),
+    (Language::C, "h"),
+    (Language::OpenApi, "json"),
+    (Language::Sql, "sql"),
+];
+
+fn out_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir()
+        .join("molecule-ci-tests-reproducible")
+        .join(name);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn verify_every_language() {
+    let verified = out_dir("verified");
+    let plain = out_dir("plain");
+    for (lang, extension) in LANGUAGES {
+        Compiler::new()
+            .language(*lang)
+            .file_path("schemas/ci_tests.mol")
+            .out_dir(&verified)
+            .json_schema(true)
+            .transparent(true)
+            .verify_reproducible(true)
+            .run();
+        Compiler::new()
+            .language(*lang)
+            .file_path("schemas/ci_tests.mol")
+            .out_dir(&plain)
+            .json_schema(true)
+            .transparent(true)
+            .run();
+        let file_name = format!("ci_tests.{}", extension);
+        let verified = fs::read(verified.join(&file_name)).unwrap();
+        let plain = fs::read(plain.join(&file_name)).unwrap();
+        assert_eq!(verified, plain, "{} is different", file_name);
+    }
+}
+
+// The environment variable is shared by the tests, so all timestamps are checked in one test.
+#[test]
+fn timestamp_in_header() {
+    let dir = out_dir("timestamp");
+    let template = dir.join("header.txt");
+    fs::write(&template, "Generated at {timestamp}.\n").unwrap();
+    let generate = |reproducible: bool| {
+        Compiler::new()
+            .language(Language::Sql)
+            .file_path("schemas/numbers.mol")
+            .out_dir(&dir)
+            .header_file(&template)
+            .verify_reproducible(reproducible)
+            .run();
+        fs::read_to_string(dir.join("numbers.sql")).unwrap()
+    };
+
+    env::set_var("SOURCE_DATE_EPOCH", "1700000000");
+    assert!(generate(true).starts_with("-- Generated at 2023-11-14T22:13:20Z.\n\n"));
+    env::set_var("SOURCE_DATE_EPOCH", "0");
+    assert!(generate(false).starts_with("-- Generated at 1970-01-01T00:00:00Z.\n\n"));
+
+    env::remove_var("SOURCE_DATE_EPOCH");
+    assert!(generate(false).starts_with("-- Generated at 20"));
+    let err = panic::catch_unwind(|| generate(true)).unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert_eq!(
+        message,
+        "`{timestamp}` requires SOURCE_DATE_EPOCH in the reproducible mode"
+    );
+}
@@ -0,0 +1,85 @@
+use std::{env, fs, panic};
+
+use codegen::{Compiler, Language};
+use molecule::{error::Violation, prelude::*};
+
+use molecule_ci_tests::required::*;
+
+fn uint32(value: u32) -> Uint32 {
+    value.to_le_bytes().into()
+}
+
+fn bytes(data: &[u8]) -> Bytes {
+    Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+#[test]
+fn strict_constructor() {
+    let order = OrderBuilder::new_strict(uint32(7), bytes(b"memo"))
+        .id(uint32(1))
+        .build();
+    assert_eq!(
+        order.amount().to_opt().unwrap().as_slice(),
+        &7u32.to_le_bytes()
+    );
+    assert_eq!(&order.memo().to_opt().unwrap().raw_data()[..], &b"memo"[..]);
+    assert!(order.note().is_none());
+    assert_eq!(order.as_builder().validate(), Ok(()));
+}
+
+#[test]
+fn missing_required() {
+    let builder = Order::new_builder().memo(BytesOpt::new_builder().set(Some(bytes(b""))).build());
+    let violations = builder.validate().unwrap_err();
+    assert_eq!(
+        violations,
+        vec![Violation::MissingRequired(
+            "Order".to_owned(),
+            "amount".to_owned()
+        )]
+    );
+    assert_eq!(
+        violations[0].to_string(),
+        "Order requires the field `amount`"
+    );
+    // The encoding keeps them optional, so the values without them are still valid.
+    let order = builder.build();
+    assert!(Order::from_slice(order.as_slice()).is_ok());
+}
+
+#[test]
+fn invalid_required_fields() {
+    let dir = env::temp_dir().join("molecule-ci-tests-required");
+    fs::create_dir_all(&dir).unwrap();
+    for (name, schema, expected) in &[
+        (
+            "value",
+            "array A [byte; 4]; option O (A); table T { @required(1) a: O, }",
+            "the attribute `@required` has no value",
+        ),
+        (
+            "array",
+            "array A [byte; 4]; table T { @required a: A, }",
+            "the attribute `@required` is only for options, but the field (a) in (T) is A",
+        ),
+    ] {
+        let path = dir.join(format!("{}.mol", name));
+        fs::write(&path, schema).unwrap();
+        let err = panic::catch_unwind(|| {
+            Compiler::new()
+                .language(Language::Rust)
+                .file_path(&path)
+                .out_dir(&dir)
+                .run();
+        })
+        .unwrap_err();
+        let message = err
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| err.downcast_ref::<&str>().map(|s| (*s).to_owned()))
+            .unwrap();
+        assert_eq!(&message, expected);
+    }
+}
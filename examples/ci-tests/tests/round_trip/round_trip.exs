@@ -0,0 +1,13 @@
+# Decodes the samples in the arguments by the generated modules, then prints the encoded values.
+[table6, union_a, broken] = Enum.map(System.argv(), &Base.decode16!(&1, case: :lower))
+
+round_trip = fn module, bin ->
+  case module.decode(bin) do
+    {:ok, value} -> Base.encode16(module.encode(value), case: :lower)
+    {:error, _} -> "error"
+  end
+end
+
+IO.puts(round_trip.(CiTests.Table6, table6))
+IO.puts(round_trip.(CiTests.UnionA, union_a))
+IO.puts(round_trip.(CiTests.Table6, broken))
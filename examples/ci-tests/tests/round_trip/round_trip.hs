@@ -0,0 +1,37 @@
+-- | Decodes the samples in the arguments by the generated module, then prints the built values.
+module Main (main) where
+
+import qualified CiTests as M
+import qualified Data.ByteString as BS
+import Numeric (readHex, showHex)
+import System.Environment (getArgs)
+import System.Exit (die)
+
+fromHex :: String -> BS.ByteString
+fromHex = BS.pack . go
+  where
+    go (a : b : rest) = case readHex [a, b] of
+      [(byte, "")] -> byte : go rest
+      _ -> error ("not a hex byte: " ++ [a, b])
+    go _ = []
+
+toHex :: BS.ByteString -> String
+toHex = concatMap (\byte -> (if byte < 16 then "0" else "") ++ showHex byte "") . BS.unpack
+
+table6 :: BS.ByteString -> String
+table6 bs = case M.fromSlice False bs of
+  Right t ->
+    toHex (M.toBytes (M.mkTable6 (M.table6F1 t) (M.table6F2 t) (M.table6F3 t) (M.table6F4 t) (M.table6F5 t) (M.table6F6 t)))
+  Left _ -> "error"
+
+unionA :: BS.ByteString -> String
+unionA bs = case M.fromSlice False bs of
+  Right u -> toHex (M.toBytes (M.mkUnionA (M.unionAItem u)))
+  Left _ -> "error"
+
+main :: IO ()
+main = do
+  args <- getArgs
+  case map fromHex args of
+    [t, u, broken] -> mapM_ putStrLn [table6 t, unionA u, table6 broken]
+    _ -> die "usage: round_trip TABLE6 UNION_A BROKEN"
@@ -0,0 +1,31 @@
+-- Decodes the samples in the arguments by the generated module, then prints the decoded values.
+local M = dofile("ci_tests.lua")
+
+local function from_hex(hex)
+  return (hex:gsub("..", function(pair) return string.char(tonumber(pair, 16)) end))
+end
+
+-- The bytes are in hex, the lists in brackets, and the fields sorted by their names.
+local function dump(value)
+  if type(value) == "number" then return tostring(value) end
+  if type(value) == "string" then
+    return (value:gsub(".", function(c) return string.format("%02x", c:byte()) end))
+  end
+  local parts = {}
+  if #value > 0 or next(value) == nil then
+    for i, item in ipairs(value) do parts[i] = dump(item) end
+    return "[" .. table.concat(parts, ",") .. "]"
+  end
+  local keys = {}
+  for key in pairs(value) do keys[#keys + 1] = key end
+  table.sort(keys)
+  for i, key in ipairs(keys) do parts[i] = key .. "=" .. dump(value[key]) end
+  return "{" .. table.concat(parts, ",") .. "}"
+end
+
+local table6, err = M.Table6.decode(from_hex(arg[1]))
+print(err or dump(table6))
+local union_a, err = M.UnionA.decode(from_hex(arg[2]))
+print(err or (union_a.name .. " " .. dump(union_a.value)))
+local _, err = M.Table6.decode(from_hex(arg[3]))
+print(err)
@@ -0,0 +1,29 @@
+(* Decodes the samples in the arguments by the generated modules, then prints the built values. *)
+
+let of_hex s =
+  Bytes.init (String.length s / 2) (fun i -> Char.chr (int_of_string ("0x" ^ String.sub s (2 * i) 2)))
+
+let to_hex b =
+  String.concat "" (List.init (Bytes.length b) (fun i -> Printf.sprintf "%02x" (Bytes.get_uint8 b i)))
+
+let table6 b =
+  match Ci_tests.Table6.of_bytes b with
+  | Ok t ->
+      let open Ci_tests.Table6 in
+      to_hex (to_bytes (build ~f1:(f1 t) ~f2:(f2 t) ~f3:(f3 t) ~f4:(f4 t) ~f5:(f5 t) ~f6:(f6 t)))
+  | Error _ -> "error"
+
+let union_a b =
+  match Ci_tests.UnionA.of_bytes b with
+  | Ok u -> to_hex Ci_tests.UnionA.(to_bytes (build (item u)))
+  | Error _ -> "error"
+
+let () =
+  match Array.to_list Sys.argv with
+  | [ _; t; u; broken ] ->
+      print_endline (table6 (of_hex t));
+      print_endline (union_a (of_hex u));
+      print_endline (table6 (of_hex broken))
+  | _ ->
+      prerr_endline "usage: round_trip TABLE6 UNION_A BROKEN";
+      exit 2
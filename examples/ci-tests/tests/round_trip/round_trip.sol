@@ -0,0 +1,28 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+import "./ci_tests.sol";
+
+/// @notice Verifies the input by the generated library, then returns `0x01` and the fields read
+/// from it, or `0x00` if it's rejected. The first byte of the input selects the type.
+contract RoundTrip {
+    fallback(bytes calldata input) external returns (bytes memory) {
+        bytes calldata data = input[1:];
+        if (input[0] == 0x00) {
+            if (!CiTests.verifyTable6(data)) return hex"00";
+            bytes memory head = abi.encodePacked(
+                CiTests.table6F1(data),
+                CiTests.table6F2(data),
+                CiTests.table6F3(data)
+            );
+            bytes memory tail = abi.encodePacked(
+                CiTests.table6F4(data),
+                CiTests.table6F5(data),
+                CiTests.table6F6(data)
+            );
+            return abi.encodePacked(hex"01", head, tail);
+        }
+        if (!CiTests.verifyUnionA(data)) return hex"00";
+        return abi.encodePacked(hex"01", uint8(CiTests.unionAItemId(data)), CiTests.unionAItem(data));
+    }
+}
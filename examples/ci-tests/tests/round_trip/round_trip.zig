@@ -0,0 +1,30 @@
+//! Decodes the samples by the generated types, then builds them back to the same bytes.
+
+const std = @import("std");
+const mol = @import("ci_tests.zig");
+const samples = @import("samples.zig");
+
+test "Table6" {
+    const value = try mol.Table6.fromSlice(&samples.table6);
+    const built = try mol.Table6.build(std.testing.allocator, .{
+        .f1 = value.getF1(),
+        .f2 = value.getF2(),
+        .f3 = value.getF3(),
+        .f4 = value.getF4(),
+        .f5 = value.getF5(),
+        .f6 = value.getF6(),
+    });
+    defer std.testing.allocator.free(built);
+    try std.testing.expectEqualSlices(u8, &samples.table6, built);
+}
+
+test "UnionA" {
+    const value = try mol.UnionA.fromSlice(&samples.union_a);
+    const built = try mol.UnionA.build(std.testing.allocator, value.item());
+    defer std.testing.allocator.free(built);
+    try std.testing.expectEqualSlices(u8, &samples.union_a, built);
+}
+
+test "truncated Table6" {
+    try std.testing.expectError(error.TotalSize, mol.Table6.fromSlice(&samples.broken));
+}
@@ -0,0 +1,90 @@
+use molecule::prelude::*;
+
+use molecule_ci_tests::{styled, types};
+
+const STYLED_TYPES: &str = include_str!(concat!(env!("OUT_DIR"), "/styled/ci_tests.rs"));
+const STYLED_KEYWORDS: &str = include_str!(concat!(env!("OUT_DIR"), "/styled/keywords.rs"));
+
+#[test]
+fn allow_on_each_item() {
+    let items = STYLED_TYPES
+        .split("\n\n")
+        .skip(1)
+        .filter(|item| !item.trim().is_empty());
+    for item in items {
+        assert!(
+            item.starts_with("#[allow(clippy::all, dead_code)]\n"),
+            "{} is not allowed",
+            item.lines().next().unwrap()
+        );
+    }
+    assert!(!STYLED_TYPES.contains("#!["));
+}
+
+#[test]
+fn formatted() {
+    for item in &[
+        "#[allow(clippy::all, dead_code)]\nuse ::molecule::prelude::*;\n",
+        "pub struct Byte2(::molecule::bytes::Bytes);\n",
+        "impl<'r> Byte2Reader<'r> {\n    pub const TOTAL_SIZE: usize = 2;\n",
+        "    fn new_unchecked(slice: &'r [u8]) -> Self { Byte2Reader(slice) }\n",
+        "    pub fn nth0(mut self, v: Byte) -> Self {\n        self.0[0] = v;\n        self\n    }\n",
+    ] {
+        assert!(STYLED_TYPES.contains(item), "{} is not found", item);
+    }
+    assert!(!STYLED_TYPES.contains(" :: "));
+    assert!(STYLED_TYPES.lines().all(|line| line.trim_end() == line));
+}
+
+#[test]
+fn edition_2015_paths() {
+    let unprefixed = STYLED_TYPES
+        .match_indices("molecule::")
+        .filter(|(idx, _)| !STYLED_TYPES[..*idx].ends_with("::"))
+        .count();
+    assert_eq!(unprefixed, 0);
+}
+
+#[test]
+fn reserved_identifiers() {
+    for item in &[
+        "pub fn r#try ( & self )",
+        "pub fn r#async ( & self )",
+        "pub fn r#gen ( & self )",
+        "pub fn r#await ( & self )",
+        "pub fn r#dyn ( & self )",
+    ] {
+        assert!(STYLED_KEYWORDS.contains(item), "{} is not found", item);
+    }
+    assert!(!STYLED_KEYWORDS.contains(" try "));
+
+    let flags = styled::keywords::Flags::new_builder()
+        .r#try(1.into())
+        .r#async(2.into())
+        .build();
+    assert_eq!(flags.as_slice(), &[1, 2]);
+    let keywords = styled::keywords::Keywords::new_builder()
+        .r#await(flags.clone())
+        .build();
+    assert_eq!(keywords.r#await().as_slice(), flags.as_slice());
+    assert!(keywords.r#dyn().is_empty());
+}
+
+#[test]
+fn same_encoding() {
+    let styled = styled::types::Table6::default();
+    let table = types::Table6::default();
+    assert_eq!(styled.as_slice(), table.as_slice());
+    let styled = styled::types::AllInOne::new_builder()
+        .f0(Byte::new(7))
+        .build();
+    let all = types::AllInOne::new_builder().f0(Byte::new(7)).build();
+    assert_eq!(styled.as_slice(), all.as_slice());
+    assert_eq!(
+        styled::types::AllInOneReader::from_slice(all.as_slice())
+            .unwrap()
+            .f0()
+            .as_slice(),
+        &[7]
+    );
+}
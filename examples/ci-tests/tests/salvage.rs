@@ -0,0 +1,128 @@
+use codegen::{Explorer, PartialContent};
+use molecule::{error::VerificationError, prelude::*};
+
+use molecule_ci_tests::types;
+
+fn explorer() -> Explorer {
+    Explorer::new("schemas/ci_tests.mol")
+}
+
+fn bytes(data: &[u8]) -> types::Bytes {
+    types::Bytes::new_builder()
+        .extend(data.iter().map(|x| (*x).into()))
+        .build()
+}
+
+fn children(content: &PartialContent) -> Vec<&str> {
+    match content {
+        PartialContent::Salvaged(children) => {
+            children.iter().map(|(name, _)| name.as_str()).collect()
+        }
+        _ => panic!("the value is not salvaged: {:?}", content),
+    }
+}
+
+#[test]
+fn salvage_complete_value() {
+    let explorer = explorer();
+    let data = types::BytesVec::new_builder()
+        .push(bytes(&[0x12, 0x34]))
+        .build()
+        .as_slice()
+        .to_vec();
+    let value = explorer.salvage("BytesVec", &data, false).unwrap();
+    assert!(value.is_complete());
+    assert!(value.errors().is_empty());
+    assert_eq!(
+        value.content,
+        PartialContent::Decoded(explorer.decode("BytesVec", &data, false).unwrap())
+    );
+}
+
+#[test]
+fn salvage_broken_item() {
+    let explorer = explorer();
+    let mut data = types::BytesVec::new_builder()
+        .push(bytes(&[0x12, 0x34]))
+        .push(bytes(&[0x56]))
+        .build()
+        .as_slice()
+        .to_vec();
+    // The item count of the second item, after the header and the first item.
+    let second = 4 * 3 + 4 + 2;
+    data[second] = 0x09;
+    assert!(explorer.decode("BytesVec", &data, false).is_err());
+
+    let value = explorer.salvage("BytesVec", &data, false).unwrap();
+    assert!(!value.is_complete());
+    assert_eq!(value.error, None);
+    assert_eq!(children(&value.content), vec!["0", "1"]);
+    let errors = value.errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, "BytesVec.1");
+    assert_eq!(
+        errors[0].1,
+        &VerificationError::TotalSizeNotMatch("BytesReader".to_owned(), 13, 5)
+    );
+    let text = value.to_string();
+    assert!(text.contains("\n  0: Bytes(0x1234) [12..18]\n"), "{}", text);
+    assert!(
+        text.contains("\n  1: Bytes <broken> [18..23] error: "),
+        "{}",
+        text
+    );
+}
+
+#[test]
+fn salvage_broken_offsets() {
+    let explorer = explorer();
+    let table = types::Table3::new_builder()
+        .f1(0x12.into())
+        .f3(types::StructA::new_builder().f1(0x34.into()).build())
+        .build();
+    let mut data = table.as_slice().to_vec();
+    // Move the offset of `f2` after the offset of `f3`.
+    let offset = data.len() as u32 - 1;
+    data[8..12].copy_from_slice(&offset.to_le_bytes());
+
+    let value = explorer.salvage("Table3", &data, false).unwrap();
+    assert_eq!(
+        value.error,
+        Some(VerificationError::OffsetsNotMatch(
+            "Table3Reader".to_owned()
+        ))
+    );
+    assert_eq!(children(&value.content), vec!["f1", "f2", "f3"]);
+    let paths = value
+        .errors()
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect::<Vec<_>>();
+    assert_eq!(paths, vec!["Table3", "Table3.f1", "Table3.f2"]);
+    if let PartialContent::Salvaged(ref fields) = value.content {
+        assert_eq!(
+            fields[2].1.content,
+            PartialContent::Decoded(table.f3().to_string())
+        );
+    }
+}
+
+#[test]
+fn salvage_broken_header() {
+    let explorer = explorer();
+    let value = explorer.salvage("Table3", &[0x05, 0, 0], false).unwrap();
+    assert_eq!(value.content, PartialContent::Broken);
+    assert_eq!(
+        value.error,
+        Some(VerificationError::HeaderIsBroken(
+            "Table3Reader".to_owned(),
+            4,
+            3
+        ))
+    );
+
+    let value = explorer.salvage("UnionA", &[0xff, 0, 0, 0], false).unwrap();
+    assert_eq!(value.content, PartialContent::Broken);
+    assert_eq!(value.errors()[0].1.kind(), "UnknownItem");
+    assert!(explorer.salvage("Unknown", &[], false).is_err());
+}
@@ -0,0 +1,46 @@
+const SCALA: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.scala"));
+const KEYWORDS: &str = include_str!(concat!(env!("OUT_DIR"), "/styled/keywords.scala"));
+
+#[test]
+fn scala_object() {
+    assert!(SCALA.contains("\nobject CiTests {\n  object Mol {\n"));
+    assert!(SCALA.ends_with("\n  }\n}\n"));
+    assert!(SCALA.contains(
+        "    def apply(f1: Byte, f2: Byte, f3: Byte2, f4: Byte2): StructA =
+      new StructA(Mol.Slice(Array.concat(Array(f1), Array(f2), f3.toBytes, f4.toBytes)))"
+    ));
+    assert!(SCALA.contains(
+        "      for {
+        count <- Mol.verifyDynamic(s)
+        _ <- Mol.check(count >= 2 && (compatible || count == 2), Mol.VerifyError.FieldCount)
+        _ <- Mol.verifyByte(Mol.dynamicAt(s, 0))
+        _ <- Word2.verify(Mol.dynamicAt(s, 1), compatible)
+      } yield new Table2(s)"
+    ));
+}
+
+#[test]
+fn scala_options() {
+    assert!(SCALA.contains("  type BytesOpt = Option[Bytes]\n"));
+    assert!(SCALA.contains("      if (s.len == 0) None else Some(new Bytes(s))\n"));
+}
+
+#[test]
+fn scala_unions() {
+    assert!(SCALA.contains("  sealed trait UnionA {\n"));
+    // The item types are shadowed by the case classes in the companion object.
+    assert!(SCALA.contains(
+        "    final case class StructA(value: CiTests.StructA) extends UnionA {
+      def itemId: Long = 2L"
+    ));
+    assert!(SCALA.contains("          case 0L => Mol.verifyByte(body).map(Byte(_))\n"));
+    assert!(SCALA.contains(
+        "    def fromBytes(bytes: Array[scala.Byte], compatible: Boolean = false): Either[Mol.VerifyError, UnionA] ="
+    ));
+}
+
+#[test]
+fn scala_keywords() {
+    assert!(KEYWORDS.contains("    def `try`: Byte = slice.sub(0, 1)(0)\n"));
+    assert!(KEYWORDS.contains("    def apply(`try`: Byte, async: Byte): Flags =\n"));
+}
@@ -0,0 +1,114 @@
+use molecule::{
+    prelude::*,
+    sequence::{Recovery, SequenceReader},
+};
+
+use molecule_ci_tests::types;
+
+fn bytes(data: &[u8]) -> types::Bytes {
+    types::Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+fn log(records: &[&[u8]]) -> Vec<u8> {
+    records
+        .iter()
+        .flat_map(|record| bytes(record).as_slice().to_vec())
+        .collect()
+}
+
+fn read_all(data: &[u8], recovery: Recovery) -> Vec<Result<Vec<u8>, (usize, usize)>> {
+    SequenceReader::<types::BytesReader>::new(data)
+        .recovery(recovery)
+        .map(|result| {
+            result
+                .map(|reader| reader.raw_data().to_vec())
+                .map_err(|err| (err.offset, err.skipped))
+        })
+        .collect()
+}
+
+#[test]
+fn read_back_to_back() {
+    let data = log(&[&[0x01], &[], &[0x02, 0x03]]);
+    let mut reader = SequenceReader::<types::BytesReader>::new(&data);
+    assert_eq!(reader.next().unwrap().unwrap().raw_data(), &[0x01][..]);
+    assert_eq!(reader.position(), 5);
+    assert!(reader.next().unwrap().unwrap().is_empty());
+    assert_eq!(
+        reader.next().unwrap().unwrap().raw_data(),
+        &[0x02, 0x03][..]
+    );
+    assert!(reader.remaining().is_empty());
+    assert!(reader.next().is_none());
+    assert!(SequenceReader::<types::BytesReader>::new(&[])
+        .next()
+        .is_none());
+}
+
+#[test]
+fn read_tables() {
+    let table = types::Table1::new_builder().f1(0x12.into()).build();
+    let mut data = table.as_slice().to_vec();
+    data.extend_from_slice(table.as_slice());
+    let tables = SequenceReader::<types::Table1Reader>::new(&data)
+        .compatible(true)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(tables.len(), 2);
+    assert_eq!(tables[1].as_slice(), table.as_slice());
+}
+
+#[test]
+fn stop_at_truncated_value() {
+    let mut data = log(&[&[0x01], &[0x02, 0x03]]);
+    data.pop();
+    assert_eq!(
+        read_all(&data, Recovery::Stop),
+        vec![Ok(vec![0x01]), Err((5, 0))]
+    );
+    // The size of the truncated value is larger than the rest.
+    assert_eq!(
+        read_all(&data, Recovery::SkipValue),
+        vec![Ok(vec![0x01]), Err((5, 0))]
+    );
+}
+
+#[test]
+fn resync_after_garbage() {
+    let mut data = log(&[&[0x01]]);
+    data.extend_from_slice(&[0xff, 0xff, 0xff]);
+    data.extend_from_slice(&log(&[&[0x02, 0x03]]));
+    assert_eq!(
+        read_all(&data, Recovery::Stop),
+        vec![Ok(vec![0x01]), Err((5, 0))]
+    );
+    assert_eq!(
+        read_all(&data, Recovery::Resync),
+        vec![Ok(vec![0x01]), Err((5, 3)), Ok(vec![0x02, 0x03])]
+    );
+}
+
+#[test]
+fn skip_value_with_valid_size() {
+    // A `Table1` whose field is broken, but whose total size is still right.
+    let table = types::Table1::new_builder().f1(0x12.into()).build();
+    let mut broken = table.as_slice().to_vec();
+    // Move the offset of the field, so the header is shorter than the offsets say.
+    broken[4] = 0x0c;
+    let mut data = broken.clone();
+    data.extend_from_slice(table.as_slice());
+    let results = SequenceReader::<types::Table1Reader>::new(&data)
+        .recovery(Recovery::SkipValue)
+        .map(|result| {
+            result
+                .map(|reader| reader.as_slice().len())
+                .map_err(|err| (err.offset, err.skipped))
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        results,
+        vec![Err((0, broken.len())), Ok(table.as_slice().len())]
+    );
+}
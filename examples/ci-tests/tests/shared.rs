@@ -0,0 +1,43 @@
+use std::{sync::Arc, thread};
+
+use molecule::prelude::*;
+
+use molecule_ci_tests::types;
+
+#[test]
+fn clone_shares_bytes() {
+    let bytes = types::Bytes::new_builder()
+        .extend((0..64).map(Byte::new))
+        .build();
+    let table = types::Table5::new_builder().f4(bytes).build();
+    let cloned = table.clone();
+    assert_eq!(table.as_slice().as_ptr(), cloned.as_slice().as_ptr());
+    // The fields are slices of the same bytes too.
+    let field = table.f4();
+    let start = table.field_range(3).start + molecule::NUMBER_SIZE;
+    assert_eq!(
+        field.raw_data().as_ptr(),
+        table.as_slice()[start..].as_ptr()
+    );
+}
+
+#[test]
+fn send_between_threads() {
+    let table = Arc::new(types::Table2::new_builder().f1(0x12.into()).build());
+    let handles = (0..4)
+        .map(|_| {
+            let table = Arc::clone(&table);
+            let entity = types::Table6::default();
+            thread::spawn(move || (table.f1().as_slice().to_vec(), entity.as_slice().len()))
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        let (f1, len) = handle.join().unwrap();
+        assert_eq!(f1, vec![0x12]);
+        assert_eq!(len, types::Table6::default().as_slice().len());
+    }
+    let reader = table.as_reader();
+    thread::scope(|scope| {
+        scope.spawn(|| assert_eq!(reader.f1().as_slice(), &[0x12]));
+    });
+}
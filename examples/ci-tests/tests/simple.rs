@@ -81,7 +81,7 @@ macro_rules! test_vector_push_default {
 
 #[test]
 fn option_default() {
-    let slice = s!("0x");
+    let slice: &[u8] = s!("0x");
     test_default!(ByteOpt, slice);
     test_default!(WordOpt, slice);
     test_default!(StructAOpt, slice);
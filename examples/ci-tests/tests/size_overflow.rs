@@ -0,0 +1,28 @@
+use molecule::{error::VerificationError, fixvec_total_size, header_size, prelude::*, NUMBER_SIZE};
+use slices::u8_slice as s;
+
+use molecule_ci_tests::types;
+
+#[test]
+fn checked_sizes() {
+    assert_eq!(fixvec_total_size(NUMBER_SIZE, 2, 3), Some(10));
+    assert_eq!(fixvec_total_size(NUMBER_SIZE, usize::MAX / 2, 3), None);
+    assert_eq!(fixvec_total_size(NUMBER_SIZE, 1, usize::MAX - 3), None);
+    assert_eq!(fixvec_total_size(1, 2, 3), Some(7));
+    assert_eq!(header_size(2), Some(12));
+    assert_eq!(header_size(usize::MAX / 4), None);
+    assert_eq!(header_size(usize::MAX), None);
+}
+
+#[test]
+fn huge_item_count() {
+    // `4 + 2 * 0x80000002` wraps to 8 in 32 bits.
+    let result = types::Words::from_slice(s!("0x02000080_00000000"));
+    assert!(result.is_err());
+    let err = types::Byte3Vec::from_slice(s!("0xffffffff_000000")).unwrap_err();
+    if usize::MAX as u64 == u32::MAX as u64 {
+        assert!(matches!(err, VerificationError::SizeOverflow(_)));
+    } else {
+        assert!(matches!(err, VerificationError::TotalSizeNotMatch(..)));
+    }
+}
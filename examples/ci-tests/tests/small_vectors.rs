@@ -0,0 +1,61 @@
+use molecule::prelude::*;
+
+use molecule_ci_tests::{styled, types};
+
+fn bytes(data: &[u8]) -> types::Bytes {
+    types::Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+fn styled_bytes(data: &[u8]) -> styled::types::Bytes {
+    styled::types::Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+#[test]
+fn small_vector_builders() {
+    // The items are inline if there are no more than 2 of them.
+    for count in 0..5u8 {
+        let data = (0..count).collect::<Vec<_>>();
+        assert_eq!(styled_bytes(&data).as_slice(), bytes(&data).as_slice());
+        let vec = styled::types::BytesVec::new_builder()
+            .set(vec![styled_bytes(&data); count as usize])
+            .build();
+        let expected = types::BytesVec::new_builder()
+            .extend((0..count).map(|_| bytes(&data)))
+            .build();
+        assert_eq!(vec.as_slice(), expected.as_slice());
+    }
+    let words = styled::types::Words::default().as_builder().build();
+    assert_eq!(words.as_slice(), types::Words::default().as_slice());
+}
+
+#[test]
+fn build_with_context() {
+    let mut context = BuildContext::new();
+    let builders = (0..10u8)
+        .map(|count| {
+            let data = (0..count).collect::<Vec<_>>();
+            types::BytesVec::new_builder()
+                .push(bytes(&data))
+                .push(bytes(&[]))
+        })
+        .collect::<Vec<_>>();
+    let values = builders
+        .iter()
+        .map(|builder| builder.build_with(&mut context))
+        .collect::<Vec<_>>();
+    // The values which are kept are not overwritten by the later builds.
+    for (builder, value) in builders.iter().zip(&values) {
+        assert_eq!(value.as_slice(), builder.build().as_slice());
+        types::BytesVecReader::verify(value.as_slice(), false).unwrap();
+    }
+    let mut context = BuildContext::with_capacity(64);
+    let table = types::Table1::new_builder().f1(1.into());
+    assert_eq!(
+        table.build_with(&mut context).as_slice(),
+        table.build().as_slice()
+    );
+}
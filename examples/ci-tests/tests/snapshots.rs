@@ -0,0 +1,164 @@
+//! Snapshots of the generated code, to review how a change of the generators alters the output.
+//!
+//! Run with `UPDATE_SNAPSHOTS=1` to write the new snapshots, then review them with `git diff`.
+
+use std::{env, fs, path::Path};
+
+use codegen::{Compiler, Language};
+
+const SCHEMAS: &[&str] = &["schemas/snapshots/corpus.mol", "schemas/import/b/b.mol"];
+const LANGUAGES: &[(Language, &str)] = &[
+    (Language::Rust, "rs"),
+    (Language::C, "h"),
+    (Language::OpenApi, "json"),
+    (Language::Sql, "sql"),
+    (Language::Elixir, "ex"),
+    (Language::Solidity, "sol"),
+    (Language::Zig, "zig"),
+    (Language::Lua, "lua"),
+    (Language::OCaml, "ml"),
+    (Language::Haskell, "hs"),
+    (Language::Scala, "scala"),
+    (Language::Tla, "tla"),
+    (Language::Kaitai, "ksy"),
+    (Language::Wireshark, "dissector.lua"),
+    (Language::ImHex, "hexpat"),
+];
+const CONTEXT: usize = 3;
+
+// The generated Rust code is in a few long lines, so split it for readable diffs.
+fn normalize(output: &str, extension: &str) -> String {
+    let output = output.replace(codegen::VERSION, "<VERSION>");
+    if extension != "rs" {
+        return output;
+    }
+    let mut normalized = String::with_capacity(output.len() * 2);
+    for ch in output.chars() {
+        normalized.push(ch);
+        if ch == '{' || ch == '}' || ch == ';' {
+            normalized.push('\n');
+        }
+    }
+    normalized
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
+
+enum Change<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+// A line-based diff by the longest common subsequence.
+fn diff<'a>(expected: &[&'a str], actual: &[&'a str]) -> Vec<Change<'a>> {
+    let (n, m) = (expected.len(), actual.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut changes = Vec::new();
+    while i < n || j < m {
+        if i < n && j < m && expected[i] == actual[j] {
+            changes.push(Change::Same(expected[i]));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            changes.push(Change::Removed(expected[i]));
+            i += 1;
+        } else {
+            changes.push(Change::Added(actual[j]));
+            j += 1;
+        }
+    }
+    changes
+}
+
+// Print the changed lines with a few lines around them.
+fn render(changes: &[Change]) -> String {
+    let changed = changes
+        .iter()
+        .map(|change| !matches!(change, Change::Same(_)))
+        .collect::<Vec<_>>();
+    let mut rendered = String::new();
+    let mut skipped = false;
+    for (index, change) in changes.iter().enumerate() {
+        let start = index.saturating_sub(CONTEXT);
+        let end = (index + CONTEXT + 1).min(changes.len());
+        if !changed[start..end].iter().any(|x| *x) {
+            skipped = true;
+            continue;
+        }
+        if skipped {
+            rendered.push_str("@@\n");
+            skipped = false;
+        }
+        let (sign, line) = match change {
+            Change::Same(line) => (' ', line),
+            Change::Removed(line) => ('-', line),
+            Change::Added(line) => ('+', line),
+        };
+        rendered.push(sign);
+        rendered.push_str(line);
+        rendered.push('\n');
+    }
+    rendered
+}
+
+#[test]
+fn generated_code_matches_snapshots() {
+    let update = env::var_os("UPDATE_SNAPSHOTS").is_some();
+    let snapshots_dir = Path::new("snapshots");
+    let out_dir = Path::new(env!("OUT_DIR")).join("snapshots");
+    fs::create_dir_all(&out_dir).unwrap();
+    let mut failures = Vec::new();
+    for schema in SCHEMAS {
+        let stem = Path::new(schema).file_stem().unwrap().to_str().unwrap();
+        for (language, extension) in LANGUAGES {
+            Compiler::new()
+                .language(*language)
+                .out_dir(&out_dir)
+                .file_path(schema)
+                .run();
+            let file_name = format!("{}.{}", stem, extension);
+            let output = fs::read_to_string(out_dir.join(&file_name)).unwrap();
+            let actual = normalize(&output, extension);
+            let snapshot = snapshots_dir.join(format!("{}.snap", file_name));
+            if update {
+                fs::create_dir_all(snapshots_dir).unwrap();
+                fs::write(&snapshot, &actual).unwrap();
+                continue;
+            }
+            let expected = fs::read_to_string(&snapshot).unwrap_or_default();
+            if expected != actual {
+                let expected_lines = expected.lines().collect::<Vec<_>>();
+                let actual_lines = actual.lines().collect::<Vec<_>>();
+                let changes = diff(&expected_lines, &actual_lines);
+                failures.push(format!(
+                    "--- {}\n+++ {} ({})\n{}",
+                    snapshot.display(),
+                    schema,
+                    extension,
+                    render(&changes)
+                ));
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "the generated code doesn't match the snapshots, run with `UPDATE_SNAPSHOTS=1` to \
+         update them:\n{}",
+        failures.join("\n")
+    );
+}
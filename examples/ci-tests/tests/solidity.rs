@@ -0,0 +1,81 @@
+mod common;
+
+const SOLIDITY: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.sol"));
+
+#[test]
+fn solidity_library() {
+    assert!(SOLIDITY.contains("\npragma solidity ^0.8.0;\n"));
+    assert!(SOLIDITY.contains("\nlibrary CiTests {\n"));
+    assert!(SOLIDITY.ends_with("\n}\n"));
+    assert!(SOLIDITY.contains(
+        "    function verifyStructA(bytes calldata data) internal pure returns (bool) {
+        return data.length == 6;
+    }"
+    ));
+    assert!(SOLIDITY.contains(
+        "    function structAF2(bytes calldata data) internal pure returns (uint8) {
+        return uint8(data[1]);
+    }"
+    ));
+    assert!(SOLIDITY.contains(
+        "    function verifyTable2(bytes calldata data) internal pure returns (bool) {
+        (bool ok, uint256 count) = verifyDynamic(data);
+        if (!ok || count != 2) return false;
+        if (!verifyByte(dynamicAt(data, 0))) return false;
+        if (!verifyWord2(dynamicAt(data, 1))) return false;
+        return true;
+    }"
+    ));
+    assert!(SOLIDITY.contains("        if (itemId == 2) return verifyStructA(data[4:]);\n"));
+}
+
+#[test]
+fn solidity_functions_are_unique() {
+    common::assert_unique(
+        SOLIDITY
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("function "))
+            .map(|line| line.split('(').next().unwrap()),
+    );
+}
+
+#[test]
+#[cfg_attr(
+    not(round_trip),
+    ignore = "requires solc and evm, run with ROUND_TRIP=1"
+)]
+fn solidity_round_trip() {
+    let samples = common::samples();
+    let dir = common::workspace(
+        "solidity",
+        &[
+            ("ci_tests.sol", SOLIDITY),
+            ("round_trip.sol", include_str!("round_trip/round_trip.sol")),
+        ],
+    );
+    let output = common::run(
+        &dir,
+        "solc",
+        &["--combined-json", "bin-runtime", "round_trip.sol"],
+    );
+    let contracts: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let code = contracts["contracts"]["round_trip.sol:RoundTrip"]["bin-runtime"]
+        .as_str()
+        .unwrap();
+    // The first byte of the input selects the type, and the contract returns the fields it reads.
+    let call = |selector: u8, data: &[u8]| {
+        let input = common::hex(&[&[selector], data].concat());
+        let output = common::run(&dir, "evm", &["run", "--code", code, "--input", &input]);
+        let output = output.trim();
+        output.strip_prefix("0x").unwrap_or(output).to_owned()
+    };
+    let table6 = call(0, &samples.table6);
+    // The fields of a table follow the total size and the 6 offsets.
+    assert_eq!(table6, format!("01{}", common::hex(&samples.table6[28..])));
+    let union_a = call(1, &samples.union_a);
+    assert_eq!(
+        union_a,
+        format!("0103{}", common::hex(&samples.union_a[4..]))
+    );
+    assert_eq!(call(0, &samples.broken), "00");
+}
@@ -0,0 +1,160 @@
+#[cfg(feature = "std")]
+use std::io::Cursor;
+
+#[cfg(feature = "std")]
+use molecule::source::ReadSeekSource;
+use molecule::{
+    error::VerificationError,
+    prelude::*,
+    source::{Source, SourceError, SourceView},
+};
+
+use molecule_ci_tests::types;
+
+fn bytes(data: &[u8]) -> types::Bytes {
+    types::Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+fn table5() -> types::Table5 {
+    let struct_a = types::StructA::new_builder()
+        .f1(1.into())
+        .f3(types::Byte2::new_builder().nth1(3.into()).build())
+        .build();
+    let bytes_vec = types::BytesVec::new_builder()
+        .push(bytes(&[4, 5]))
+        .push(bytes(&[]))
+        .push(bytes(&[6]))
+        .build();
+    types::Table5::new_builder()
+        .f1(7.into())
+        .f3(struct_a)
+        .f4(bytes(b"molecule"))
+        .f5(bytes_vec)
+        .build()
+}
+
+fn check_table5<S: Source + ?Sized>(source: &S) {
+    let table = types::Table5SourceReader::new(source);
+    assert_eq!(table.field_count().unwrap(), 5);
+    assert_eq!(table.f1().unwrap(), Byte::new(7));
+    let struct_a = table.f3().unwrap();
+    assert_eq!(struct_a.f1().unwrap(), Byte::new(1));
+    assert_eq!(struct_a.f3().unwrap().nth1().unwrap(), Byte::new(3));
+    assert_eq!(&struct_a.f4().unwrap().raw_data().unwrap()[..], &[0, 0]);
+    assert_eq!(&table.f4().unwrap().raw_data().unwrap()[..], b"molecule");
+    let bytes_vec = table.f5().unwrap();
+    assert_eq!(bytes_vec.len().unwrap(), 3);
+    assert_eq!(
+        &bytes_vec.get(0).unwrap().unwrap().raw_data().unwrap()[..],
+        &[4, 5]
+    );
+    assert!(bytes_vec.get(1).unwrap().unwrap().is_empty().unwrap());
+    assert_eq!(
+        bytes_vec.get(2).unwrap().unwrap().get(0).unwrap(),
+        Some(Byte::new(6))
+    );
+    assert!(bytes_vec.get(3).unwrap().is_none());
+    assert_eq!(table.to_entity().unwrap().as_slice(), table5().as_slice());
+}
+
+#[test]
+fn navigate_in_memory() {
+    let table = table5();
+    check_table5(table.as_slice());
+    check_table5(&table.as_slice().to_vec());
+    check_table5(&table.as_bytes());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn navigate_read_seek() {
+    let table = table5();
+    let source = ReadSeekSource::new(Cursor::new(table.as_slice().to_vec())).unwrap();
+    check_table5(&source);
+    assert_eq!(source.into_inner().into_inner(), table.as_slice());
+}
+
+#[test]
+fn part_of_source() {
+    let table = table5();
+    let mut data = vec![0xff; 3];
+    data.extend_from_slice(table.as_slice());
+    data.push(0xff);
+    let view = SourceView::new(data.as_slice());
+    let table_view = view.slice(3, 3 + table.as_slice().len()).unwrap();
+    assert_eq!(table_view.start(), 3);
+    let reader = types::Table5SourceReader::from_view(table_view);
+    assert_eq!(&reader.f4().unwrap().raw_data().unwrap()[..], b"molecule");
+    let size = reader
+        .with_reader(|reader| reader.f4().raw_data().len())
+        .unwrap();
+    assert_eq!(size, 8);
+}
+
+#[test]
+fn option_and_union() {
+    let none = types::BytesOpt::default();
+    let opt = types::BytesOptSourceReader::new(none.as_slice());
+    assert!(opt.to_opt().unwrap().is_none());
+    let some = types::BytesOpt::new_builder()
+        .set(Some(bytes(&[1, 2])))
+        .build();
+    let opt = types::BytesOptSourceReader::new(some.as_slice());
+    let inner = opt.to_opt().unwrap().unwrap();
+    assert_eq!(&inner.raw_data().unwrap()[..], &[1, 2]);
+
+    let union = types::UnionA::new_builder().set(bytes(&[9])).build();
+    let reader = types::UnionASourceReader::new(union.as_slice());
+    assert_eq!(reader.item_id().unwrap(), 3);
+    let item = types::BytesSourceReader::from_view(reader.item().unwrap());
+    assert_eq!(&item.raw_data().unwrap()[..], &[9]);
+}
+
+#[test]
+fn errors() {
+    let table = table5();
+    let data = table.as_slice();
+
+    // The total size in the header doesn't match the source.
+    let truncated = &data[..data.len() - 1];
+    match types::Table5SourceReader::new(truncated).f1() {
+        Err(SourceError::Verification(VerificationError::TotalSizeNotMatch(name, ..))) => {
+            assert_eq!(name, "Table5")
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    match types::Table5SourceReader::new(&data[..3]).f1() {
+        Err(SourceError::Verification(VerificationError::HeaderIsBroken(..))) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+
+    // A struct which is shorter than its fields.
+    let struct_a = types::StructASourceReader::new(&[1u8, 2][..]);
+    assert_eq!(struct_a.f2().unwrap(), Byte::new(2));
+    match struct_a.f3() {
+        Err(SourceError::OutOfBounds(2, 4, 2)) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+    assert!(struct_a.to_entity().is_err());
+
+    // A table with fewer fields.
+    let table1 = types::Table1::new_builder().f1(1.into()).build();
+    match types::Table2SourceReader::new(table1.as_slice()).f2() {
+        Err(SourceError::Verification(VerificationError::FieldCountNotMatch(_, 2, 1))) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+
+    // The count of the items doesn't match the size.
+    let words = [2u8, 0, 0, 0, 1, 2];
+    match types::WordsSourceReader::new(&words[..]).get(0) {
+        Err(SourceError::Verification(VerificationError::TotalSizeNotMatch(_, 8, 6))) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+
+    assert!(matches!(
+        data.read(1, data.len() + 1),
+        Err(SourceError::OutOfBounds(..))
+    ));
+}
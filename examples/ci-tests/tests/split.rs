@@ -0,0 +1,52 @@
+use molecule::prelude::*;
+
+use molecule_ci_tests::types::{self, constants};
+
+fn table() -> types::Table3 {
+    types::Table3::new_builder()
+        .f1(0x12.into())
+        .f3(types::StructA::new_builder().f1(0x34.into()).build())
+        .build()
+}
+
+#[test]
+fn split_at_field() {
+    let table = table();
+    let reader = table.as_reader();
+    let (prefix, suffix) = reader.split_at_field(constants::table3::FIELD_F3);
+    assert_eq!(
+        prefix.len(),
+        reader.field_range(constants::table3::FIELD_F3).start
+    );
+    assert_eq!(suffix, &table.as_slice()[reader.field_range(2)]);
+    let (prefix, suffix) = reader.split_at_field(3);
+    assert_eq!(prefix, table.as_slice());
+    assert!(suffix.is_empty());
+    let (prefix, suffix) = molecule::split_at_field(table.as_slice(), 0);
+    assert_eq!(prefix.len(), molecule::NUMBER_SIZE * 4);
+    assert_eq!(suffix.len(), table.as_slice().len() - prefix.len());
+}
+
+#[test]
+#[should_panic]
+fn split_at_field_out_of_bounds() {
+    table().as_reader().split_at_field(4);
+}
+
+#[test]
+fn verify_each_half() {
+    let table = table();
+    let mut data = table.as_slice().to_vec();
+    // Move the start of the third field back by one byte, so only the first field is still valid.
+    let f3_start = table.field_range(constants::table3::FIELD_F3).start;
+    data[molecule::NUMBER_SIZE * 3..molecule::NUMBER_SIZE * 4]
+        .copy_from_slice(&molecule::pack_number(f3_start as u32 - 1));
+    assert!(types::Table3Reader::verify(&data, false).is_err());
+    assert!(types::Table3Reader::verify_fields(&data, 0..1, false).is_ok());
+    assert!(types::Table3Reader::verify_fields(&data, 1..2, false).is_err());
+    assert!(types::Table3Reader::verify_fields(&data, 2..3, false).is_err());
+
+    let data = table.as_slice();
+    assert!(types::Table3Reader::verify_fields(data, 0..2, false).is_ok());
+    assert!(types::Table3Reader::verify_fields(data, 2..3, false).is_ok());
+}
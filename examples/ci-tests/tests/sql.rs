@@ -0,0 +1,59 @@
+use std::collections::HashSet;
+
+use codegen::{Compiler, Language};
+
+const SQL: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.sql"));
+
+#[test]
+fn sql_tables() {
+    assert!(SQL.contains(
+        "CREATE TABLE struct_a (
+    f1 SMALLINT NOT NULL,
+    f2 SMALLINT NOT NULL,
+    f3 BLOB NOT NULL,
+    f4 BLOB NOT NULL
+);"
+    ));
+    assert!(SQL.contains("-- Table0 has no columns."));
+    assert!(SQL.contains(
+        "CREATE TABLE table2 (
+    f1 SMALLINT NOT NULL,
+    f2 JSON NOT NULL
+);"
+    ));
+    assert!(SQL.contains("    f57 SMALLINT NULL,\n"));
+}
+
+#[test]
+fn sql_columns_are_unique() {
+    for statement in SQL.split("CREATE TABLE ").skip(1) {
+        let mut names = HashSet::new();
+        for line in statement.lines().skip(1).take_while(|line| *line != ");") {
+            let name = line.trim().split(' ').next().unwrap();
+            assert!(names.insert(name), "column {} is duplicated", name);
+        }
+    }
+}
+
+#[test]
+fn sql_exported_types() {
+    let check = |name: &str| {
+        Compiler::new()
+            .language(Language::Sql)
+            .file_path("schemas/ci_tests.mol")
+            .export_type(name)
+            .check_types()
+    };
+    assert_eq!(check("Table2"), Ok(()));
+    assert_eq!(
+        check("Table9"),
+        Err("the type `Table9` is not defined".to_owned())
+    );
+    assert_eq!(
+        check("UnionA"),
+        Err(
+            "the type `UnionA` is not a struct or a table, so it can't be exported as a SQL table"
+                .to_owned()
+        )
+    );
+}
@@ -0,0 +1,68 @@
+use codegen::{schema_stats, TypeStats};
+use molecule::prelude::*;
+
+use molecule_ci_tests::types;
+
+fn stats_of(schema: &str, name: &str) -> TypeStats {
+    schema_stats(schema)
+        .into_iter()
+        .find(|stats| stats.name == name)
+        .unwrap()
+}
+
+#[test]
+fn fixed_size_types() {
+    let stats = stats_of("schemas/ci_tests.mol", "StructA");
+    assert_eq!(stats.kind, "Struct");
+    assert_eq!(stats.fixed_size, Some(types::StructA::TOTAL_SIZE));
+    assert_eq!(stats.min_size, types::StructA::TOTAL_SIZE);
+    assert_eq!(stats.max_size, Some(types::StructA::TOTAL_SIZE));
+    assert_eq!(stats.header_overhead(), 0.0);
+    assert_eq!(stats.depth, 2);
+    assert_eq!(stats.fan_out, 4);
+}
+
+#[test]
+fn smallest_values() {
+    // The smallest values are the defaults, except the unions.
+    macro_rules! check_min_size {
+        ($type:ident) => {
+            let stats = stats_of("schemas/ci_tests.mol", stringify!($type));
+            assert_eq!(
+                stats.min_size,
+                types::$type::default().as_slice().len(),
+                "{}",
+                stringify!($type)
+            );
+        };
+    }
+    check_min_size!(Bytes);
+    check_min_size!(BytesVec);
+    check_min_size!(ByteOpt);
+    check_min_size!(Table0);
+    check_min_size!(Table6);
+
+    let stats = stats_of("schemas/ci_tests.mol", "Table3");
+    assert_eq!(stats.fixed_size, None);
+    assert_eq!(stats.min_header_size, molecule::NUMBER_SIZE * 4);
+    assert_eq!(stats.fan_out, 3);
+}
+
+#[test]
+fn headers_and_depth() {
+    let stats = stats_of("schemas/count_size.mol", "Packet");
+    // The header of the table, and the headers of the three empty vectors.
+    assert_eq!(stats.min_size, 16 + 1 + 2 + 4);
+    assert_eq!(stats.min_header_size, stats.min_size);
+    assert_eq!(stats.header_overhead(), 100.0);
+    assert_eq!(stats.max_size, None);
+    // `Packet` -> `ShortByte2Vec` -> `Byte2` -> `byte`.
+    assert_eq!(stats.depth, 3);
+    let stats = stats_of("schemas/count_size.mol", "TinyBytesOpt");
+    assert_eq!(stats.min_size, 0);
+    assert_eq!(stats.header_overhead(), 0.0);
+    // The imported types are not listed.
+    assert!(schema_stats("schemas/linking/app.mol")
+        .iter()
+        .all(|stats| stats.name != "Byte32"));
+}
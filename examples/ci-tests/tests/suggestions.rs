@@ -0,0 +1,65 @@
+use std::{env, fs, path::PathBuf};
+
+use codegen::{suggest, Suggestion};
+
+const SCHEMA: &str = r#"
+array Byte4 [byte; 4];
+vector Bytes <byte>;
+
+table Point {
+    x: Byte4,
+    y: Byte4,
+}
+vector Points <Point>;
+
+union Wrapped {
+    Bytes,
+}
+
+table Named {
+    name: Bytes,
+}
+
+table Message {
+    name: Bytes,
+    point: Point,
+}
+"#;
+
+fn schema_file() -> PathBuf {
+    let dir = env::temp_dir().join("molecule-ci-tests-suggestions");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("suggestions.mol");
+    fs::write(&path, SCHEMA).unwrap();
+    path
+}
+
+fn rules(suggestions: &[Suggestion]) -> Vec<(&str, &str, usize)> {
+    suggestions
+        .iter()
+        .map(|x| (x.type_name.as_str(), x.rule, x.saving))
+        .collect()
+}
+
+#[test]
+fn suggest_refactors() {
+    let suggestions = suggest(schema_file());
+    assert_eq!(
+        rules(&suggestions),
+        vec![
+            ("Point", "table-to-struct", 12),
+            ("Points", "dynvec-of-constant-size", 16),
+            ("Wrapped", "union-single-item", 4),
+            ("Named", "table-single-field", 8),
+        ]
+    );
+    assert_eq!(
+        suggestions[0].message,
+        "all fields are fixed-size, the table could be a struct, saving 12 bytes per value"
+    );
+}
+
+#[test]
+fn no_suggestions_for_compact_schema() {
+    assert!(suggest("schemas/count_size.mol").is_empty());
+}
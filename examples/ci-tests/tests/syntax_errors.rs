@@ -0,0 +1,93 @@
+use std::{env, fs, panic, path::PathBuf};
+
+use codegen::{build_finished_message, check_syntax, schema_hash};
+
+const BROKEN: &str = r#"
+array Byte4 [byte; 4];
+/* A comment, which mentions the table keyword
+   table in the middle of a line. */
+tabel Broken {
+    a: byte,
+}
+struct Point {
+    x: Byte4,
+    y Byte4,
+}
+vector Bytes <byte>;
+option BytesOpt (Bytes)
+union Either {
+    Bytes,
+}
+"#;
+
+fn schema_file(name: &str, content: &str) -> PathBuf {
+    let dir = env::temp_dir().join("molecule-ci-tests-syntax-errors");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    fs::write(&path, content).unwrap();
+    path
+}
+
+fn syntax_errors(path: PathBuf) -> String {
+    let err = panic::catch_unwind(|| schema_hash(&path)).unwrap_err();
+    err.downcast_ref::<String>().unwrap().to_owned()
+}
+
+#[test]
+fn report_all_syntax_errors() {
+    let path = schema_file("broken.mol", BROKEN);
+    let message = syntax_errors(path.clone());
+    assert!(message.starts_with("found 3 syntax errors:"), "{}", message);
+    for (line, col) in &[(5, 1), (10, 5), (13, 1)] {
+        let location = format!("{}:{}:{}\n", path.display(), line, col);
+        assert!(message.contains(&location), "{}", message);
+    }
+    assert!(message.contains("expected field_decl"), "{}", message);
+}
+
+#[test]
+fn report_statements_in_wrong_order() {
+    let path = schema_file("order.mol", "vector Bytes <byte>;\nimport types;\n");
+    let message = syntax_errors(path.clone());
+    assert!(message.starts_with("found 1 syntax errors:"), "{}", message);
+    assert!(message.contains(&format!("{}:2:1\n", path.display())));
+}
+
+#[test]
+fn check_syntax_without_panic() {
+    let path = schema_file("check.mol", BROKEN);
+    let errors = check_syntax(&path);
+    let positions: Vec<_> = errors.iter().map(|err| (err.line, err.column)).collect();
+    assert_eq!(positions, vec![(5, 1), (10, 5), (13, 1)]);
+    assert_eq!(errors[1].message, "expected field_decl or constraint");
+    assert_eq!(errors[1].file_name, path.to_string_lossy());
+    assert!(check_syntax("schemas/ci_tests.mol").is_empty());
+}
+
+#[test]
+fn syntax_errors_as_json_messages() {
+    let path = schema_file("json.mol", BROKEN);
+    let errors = check_syntax(&path);
+    let message: serde_json::Value = serde_json::from_str(&errors[1].json_message()).unwrap();
+    assert_eq!(message["reason"], "compiler-message");
+    assert_eq!(message["message"]["level"], "error");
+    assert_eq!(
+        message["message"]["message"],
+        "expected field_decl or constraint"
+    );
+    assert!(message["message"]["code"].is_null());
+    let span = &message["message"]["spans"][0];
+    assert_eq!(span["file_name"], path.to_string_lossy().as_ref());
+    assert_eq!(
+        (span["line_start"].as_u64(), span["column_start"].as_u64()),
+        (Some(10), Some(5))
+    );
+    assert_eq!(
+        message["message"]["rendered"].as_str().unwrap(),
+        format!("{}\n", errors[1])
+    );
+    assert_eq!(
+        build_finished_message(false),
+        r#"{"reason":"build-finished","success":false}"#
+    );
+}
@@ -0,0 +1,90 @@
+use std::{env, fs, panic};
+
+use codegen::{Compiler, Language};
+
+/// Generate the ImHex pattern of the schema with the templates, which are written into a new
+/// templates directory.
+fn generate(name: &str, templates: &[(&str, &str)]) -> String {
+    let dir = env::temp_dir().join(format!("molecule-ci-tests-templates-{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    let templates_dir = dir.join("templates");
+    fs::create_dir_all(templates_dir.join("imhex")).unwrap();
+    for (path, content) in templates {
+        fs::write(templates_dir.join(path), content).unwrap();
+    }
+    Compiler::new()
+        .language(Language::ImHex)
+        .file_path("schemas/hooks.mol")
+        .out_dir(&dir)
+        .templates_dir(&templates_dir)
+        .run();
+    fs::read_to_string(dir.join("hooks.hexpat")).unwrap()
+}
+
+fn panic_message(name: &str, templates: &[(&str, &str)]) -> String {
+    let err = panic::catch_unwind(|| {
+        generate(name, templates);
+    })
+    .unwrap_err();
+    err.downcast_ref::<String>()
+        .cloned()
+        .or_else(|| err.downcast_ref::<&str>().map(|s| (*s).to_owned()))
+        .unwrap()
+}
+
+#[test]
+fn builtin_templates() {
+    let pattern = generate("builtin", &[]);
+    assert!(
+        pattern.contains("\nnamespace hooks {\n    // The array Uint32, of 4 items in 4 bytes.\n")
+    );
+    assert!(pattern.ends_with(
+        "        if (count > 2) u8 unknown_fields[total_size - offsets[2]];\n    };\n}\n"
+    ));
+}
+
+#[test]
+fn overridden_templates() {
+    let pattern = generate(
+        "overridden",
+        &[(
+            "imhex/table.hexpat",
+            "    // {{ decl.name }} has {{ decl.field_count }} fields:\n\
+             {% for field in decl.fields %}\n\
+             {% if not loop.first %}\n\
+             \x20   //\n\
+             {% endif %}\n\
+             \x20   // {{ loop.index0 }}. {{ field.type }} {{ field.name }}\n\
+             {% endfor %}\n",
+        )],
+    );
+    assert!(pattern
+        .contains("    };\n\n    // Greeting has 2 fields:\n    // 0. Uint32 count_\n    //\n    // 1. Bytes name\n}\n"));
+    // The other templates are still the built-in ones.
+    assert!(pattern.contains("    // The fixvec Bytes, of 1-byte items.\n"));
+}
+
+#[test]
+fn invalid_templates() {
+    let message = panic_message(
+        "unknown-value",
+        &[("imhex/table.hexpat", "{{ decl.size }}")],
+    );
+    assert_eq!(
+        message,
+        "the template [imhex/table.hexpat] refers to an unknown value `decl.size`"
+    );
+    let message = panic_message(
+        "unknown-template",
+        &[("imhex/table.hexpat", "{% include \"imhex/field.hexpat\" %}")],
+    );
+    assert_eq!(message, "the template [imhex/field.hexpat] is not found");
+    let message = panic_message(
+        "unclosed-block",
+        &[("imhex/table.hexpat", "{% if decl.name %}")],
+    );
+    assert_eq!(
+        message,
+        "the template [imhex/table.hexpat] doesn't close the block by `endif`"
+    );
+}
@@ -0,0 +1,41 @@
+const TLA: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.tla"));
+
+fn position(name: &str) -> usize {
+    TLA.find(&format!("\n{}(", name))
+        .unwrap_or_else(|| panic!("{}", name))
+}
+
+#[test]
+fn tla_module() {
+    assert!(TLA.contains("\n---- MODULE ci_tests ----\nEXTENDS Integers, Sequences\n"));
+    assert!(TLA.ends_with("\n====\n"));
+    assert!(TLA.contains(
+        "StructA_f3(b) == MolSub(b, 2, 2)
+StructA_f4(b) == MolSub(b, 4, 2)
+IsStructA(b, compatible) == Len(b) = 6
+"
+    ));
+    assert!(TLA.contains(
+        "IsTable2(b, compatible) ==
+  /\\ MolIsDynamic(b)
+  /\\ MolDynCount(b) >= 2
+  /\\ compatible \\/ MolDynCount(b) = 2
+  /\\ IsByte(Table2_f1(b), compatible)
+  /\\ IsWord2(Table2_f2(b), compatible)
+"
+    ));
+    assert!(TLA.contains("  /\\ Len(b) = 4 + Words_length(b) * 2\n"));
+    assert!(TLA.contains("       [] UnionA_id(b) = 2 -> IsStructA(UnionA_item(b), compatible)\n"));
+}
+
+#[test]
+fn tla_operators_are_defined_before_use() {
+    for (dep, user) in &[
+        ("IsByte2", "IsStructA"),
+        ("IsTable6", "IsTable6Opt"),
+        ("IsTable6Opt", "IsUnionA"),
+        ("MolIsDynamic", "IsBytesVec"),
+    ] {
+        assert!(position(dep) < position(user), "{}", user);
+    }
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "tracing")]
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use molecule::prelude::*;
+use slices::u8_slice as s;
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+use molecule_ci_tests::types;
+
+type Fields = HashMap<String, String>;
+
+#[derive(Default)]
+struct Collector(Arc<Mutex<Vec<(String, Fields)>>>);
+
+struct FieldVisitor<'a>(&'a mut Fields);
+
+impl<'a> Visit for FieldVisitor<'a> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_owned(), format!("{:?}", value));
+    }
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), value.to_owned());
+    }
+}
+
+impl Subscriber for Collector {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::new();
+        event.record(&mut FieldVisitor(&mut fields));
+        let target = event.metadata().target().to_owned();
+        self.0.lock().unwrap().push((target, fields));
+    }
+    fn enter(&self, _: &span::Id) {}
+    fn exit(&self, _: &span::Id) {}
+}
+
+#[test]
+fn trace_verification_failures() {
+    let collector = Collector::default();
+    let events = Arc::clone(&collector.0);
+    tracing::subscriber::with_default(collector, || {
+        types::Bytes::from_slice(s!("0x03000000_0102")).unwrap_err();
+        types::Table1::from_compatible_slice(s!("0x0c000000_08000000_0c000000")).unwrap_err();
+        types::Bytes::from_slice(s!("0x02000000_0102")).unwrap();
+    });
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 2);
+    let (target, fields) = &events[0];
+    assert_eq!(target, "molecule::verification");
+    assert_eq!(fields["type_name"], "Bytes");
+    assert_eq!(fields["failed_type"], "BytesReader");
+    assert_eq!(fields["kind"], "TotalSizeNotMatch");
+    assert_eq!(fields["length"], "6");
+    assert_eq!(fields["compatible"], "false");
+    let (_, fields) = &events[1];
+    assert_eq!(fields["type_name"], "Table1");
+    assert_eq!(fields["compatible"], "true");
+}
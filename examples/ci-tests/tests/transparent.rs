@@ -0,0 +1,53 @@
+use std::{convert::TryFrom, mem};
+
+use molecule::{error::VerificationError, prelude::*};
+use slices::u8_slice as s;
+
+use molecule_ci_tests::types;
+
+#[test]
+fn from_verified_slice() {
+    let table = types::Table2::new_builder().f1(0x12.into()).build();
+    let slice = <&types::Table2Slice>::try_from(table.as_slice()).unwrap();
+    assert_eq!(slice.as_slice(), table.as_slice());
+    assert_eq!(slice.as_reader().f1().as_slice(), &[0x12]);
+    assert_eq!(slice.to_entity().as_slice(), table.as_slice());
+    assert_eq!(format!("{}", slice), format!("{}", table.as_reader()));
+    assert_eq!(format!("{:?}", slice), format!("{:?}", table.as_reader()));
+
+    let reader = types::Table2Reader::from_slice(table.as_slice()).unwrap();
+    let from_reader: &types::Table2Slice = reader.into();
+    assert_eq!(from_reader.as_slice().as_ptr(), table.as_slice().as_ptr());
+}
+
+#[test]
+fn from_invalid_slice() {
+    let data = [0u8; 3];
+    match <&types::Table2Slice>::try_from(&data[..]) {
+        Err(VerificationError::HeaderIsBroken(..)) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+    assert!(types::Byte2Slice::from_slice(&data[..]).is_err());
+    assert!(types::Byte2Slice::from_slice(&data[..2]).is_ok());
+}
+
+#[test]
+fn compatible_slice() {
+    // A `Table1` with an extra field which is empty.
+    let data = s!("0x0d000000_0c000000_0d000000_12");
+    assert!(types::Table1Slice::from_slice(data).is_err());
+    let slice = types::Table1Slice::from_compatible_slice(data).unwrap();
+    assert_eq!(slice.as_reader().count_extra_fields(), 1);
+}
+
+#[test]
+fn same_layout_as_bytes() {
+    assert_eq!(
+        mem::size_of::<&types::Table2Slice>(),
+        mem::size_of::<&[u8]>()
+    );
+    assert_eq!(
+        mem::align_of::<&types::Table2Slice>(),
+        mem::align_of::<&[u8]>()
+    );
+}
@@ -0,0 +1,57 @@
+use molecule::prelude::*;
+
+use molecule_ci_tests::typestate::constraints::*;
+
+const TYPESTATE_TYPES: &str = include_str!(concat!(env!("OUT_DIR"), "/typestate/constraints.rs"));
+
+fn uint32(value: u32) -> Uint32 {
+    value.to_le_bytes().into()
+}
+
+fn bytes(data: &[u8]) -> Bytes {
+    Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+#[test]
+fn only_for_tables() {
+    assert!(TYPESTATE_TYPES.contains("FrameTypedBuilder"));
+    assert!(!TYPESTATE_TYPES.contains("RangeTypedBuilder"));
+}
+
+#[test]
+fn same_bytes_as_builder() {
+    let payload = Payload::new_builder().set(bytes(&[])).build();
+    let range = Range::new_builder().end(uint32(1)).build();
+    // The fields could be set in any order.
+    let frame = Frame::new_typed_builder()
+        .range(range.clone())
+        .payload(bytes(&[1, 2]))
+        .declared_len(uint32(2))
+        .names(Names::default())
+        .body(payload.clone())
+        .kind(0.into())
+        .build();
+    let expected = Frame::new_builder()
+        .declared_len(uint32(2))
+        .payload(bytes(&[1, 2]))
+        .body(payload)
+        .range(range)
+        .build();
+    assert_eq!(frame.as_slice(), expected.as_slice());
+    assert!(FrameReader::verify(frame.as_slice(), false).is_ok());
+}
+
+#[test]
+fn into_builder() {
+    let builder = Frame::new_typed_builder()
+        .declared_len(uint32(1))
+        .payload(bytes(&[1]))
+        .kind(0.into())
+        .body(Payload::default())
+        .names(Names::default())
+        .range(Range::default())
+        .into_builder();
+    assert_eq!(builder.validate(), Ok(()));
+}
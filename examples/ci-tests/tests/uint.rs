@@ -0,0 +1,30 @@
+use molecule::prelude::*;
+use slices::u8_slice as s;
+
+use molecule_ci_tests::numbers;
+
+#[test]
+fn uint_conversions() {
+    let value = numbers::Uint16::from_u16(0x0102);
+    assert_eq!(value.as_slice(), &s!("0x0201")[..]);
+    assert_eq!(value.to_u16(), 0x0102);
+    assert_eq!(value.to_u16_be(), 0x0201);
+    let value = numbers::Uint32::from_u32_be(0x0102_0304);
+    assert_eq!(value.as_slice(), &s!("0x01020304")[..]);
+    assert_eq!(value.as_reader().to_u32(), 0x0403_0201);
+    let value: numbers::Uint64 = u64::MAX.into();
+    assert_eq!(u64::from(value), u64::MAX);
+    let value = numbers::Uint128::from_u128(1);
+    assert_eq!(value.to_u128(), 1);
+    assert_eq!(value.as_slice()[0], 1);
+}
+
+#[test]
+fn uint_fields() {
+    let amounts = numbers::Amounts::new_builder()
+        .min(1_000u64.into())
+        .max(u128::MAX.into())
+        .build();
+    assert_eq!(amounts.min().to_u64(), 1_000);
+    assert_eq!(amounts.as_reader().max().to_u128(), u128::MAX);
+}
@@ -0,0 +1,27 @@
+use molecule::{pack_number, unpack_number, unpack_number_vec};
+
+#[test]
+fn unpack_number_at_unaligned_offsets() {
+    let buffer = [0xffu8, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07];
+    for start in 0..=4 {
+        let slice = &buffer[start..];
+        let expected = u32::from_le_bytes([slice[0], slice[1], slice[2], slice[3]]);
+        assert_eq!(unpack_number(slice), expected);
+    }
+    assert_eq!(unpack_number(&pack_number(0x1234_5678)), 0x1234_5678);
+}
+
+#[test]
+#[should_panic]
+fn unpack_number_from_short_slice() {
+    unpack_number(&[0x01, 0x02, 0x03]);
+}
+
+#[test]
+fn unpack_number_vec_counts_numbers() {
+    let buffer = [0u8, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0];
+    let numbers = unpack_number_vec(&buffer[1..]);
+    assert_eq!(numbers.len(), 3);
+    assert_eq!(numbers, &[[1, 0, 0, 0], [2, 0, 0, 0], [3, 0, 0, 0]]);
+    assert!(unpack_number_vec(&[]).is_empty());
+}
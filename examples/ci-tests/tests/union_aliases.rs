@@ -0,0 +1,68 @@
+#![allow(deprecated)]
+
+use std::{env, fs, panic};
+
+use codegen::{Compiler, Language};
+use molecule::prelude::*;
+
+use molecule_ci_tests::numbers::{self, constants::balance};
+
+const C: &str = include_str!(concat!(env!("OUT_DIR"), "/numbers.h"));
+
+#[test]
+fn constants() {
+    assert_eq!(balance::ITEM_SMALL, balance::ITEM_UINT64);
+    assert_eq!(balance::ITEM_LARGE, balance::ITEM_UINT128);
+}
+
+#[test]
+fn constructors() {
+    let value = numbers::Uint64::from_u64(7);
+    let item = numbers::BalanceUnion::Small(value.clone());
+    assert!(matches!(item, numbers::BalanceUnion::Uint64(_)));
+    assert_eq!(item.item_id(), balance::ITEM_UINT64);
+    assert_eq!(item.item_name(), "Uint64");
+
+    let balance = numbers::Balance::new_builder().set(item).build();
+    let reader = numbers::BalanceUnionReader::Small(value.as_reader());
+    assert_eq!(balance.as_reader().to_enum().as_slice(), reader.as_slice());
+}
+
+#[test]
+fn c_macros() {
+    for line in &[
+        "#define                                 MolConst_Balance_item_Small                     0",
+        "#define                                 MolBuilder_Balance_set_Large(b, p, l)           mol_union_builder_set(b, 1, p, l)",
+    ] {
+        assert!(C.contains(line), "{} is not found", line);
+    }
+}
+
+#[test]
+fn conflicts() {
+    let dir = env::temp_dir().join("molecule-ci-tests-union-aliases");
+    fs::create_dir_all(&dir).unwrap();
+    for (name, union, conflict) in &[
+        ("item", "Uint32 as Uint64, Uint64,", "(Uint64)"),
+        ("alias", "Uint32 as Old, Uint64 as Old,", "(Old)"),
+        ("case", "Uint32 as uint_64, Uint64,", "(Uint64)"),
+    ] {
+        let path = dir.join(format!("{}.mol", name));
+        let schema = format!(
+            "array Uint32 [byte; 4];\narray Uint64 [byte; 8];\nunion U {{ {} }}\n",
+            union
+        );
+        fs::write(&path, schema).unwrap();
+        let err = panic::catch_unwind(|| {
+            Compiler::new()
+                .language(Language::Rust)
+                .file_path(&path)
+                .out_dir(&dir)
+                .run();
+        })
+        .unwrap_err();
+        let message = err.downcast_ref::<String>().unwrap();
+        assert!(message.starts_with("the alias ("), "{}", message);
+        assert!(message.ends_with(conflict), "{}", message);
+    }
+}
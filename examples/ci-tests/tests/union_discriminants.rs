@@ -0,0 +1,38 @@
+use molecule::{prelude::*, Number};
+
+use molecule_ci_tests::types::{self, constants::union_a};
+
+// It only compiles if `item_id` is a `const fn`.
+const fn const_item_id(item: &types::UnionAUnion) -> Number {
+    item.item_id()
+}
+
+// The discriminant is the first field of a `#[repr(u32)]` enum.
+fn discriminant<T>(item: &T) -> u32 {
+    unsafe { *(item as *const T as *const u32) }
+}
+
+#[test]
+fn discriminants_are_item_ids() {
+    let items: Vec<(types::UnionAUnion, Number)> = vec![
+        (types::Byte::default().into(), union_a::ITEM_BYTE),
+        (types::Word::default().into(), union_a::ITEM_WORD),
+        (types::StructA::default().into(), union_a::ITEM_STRUCT_A),
+        (types::Bytes::default().into(), union_a::ITEM_BYTES),
+        (types::Words::default().into(), union_a::ITEM_WORDS),
+        (types::Table0::default().into(), union_a::ITEM_TABLE0),
+        (types::Table6::default().into(), union_a::ITEM_TABLE6),
+        (types::Table6Opt::default().into(), union_a::ITEM_TABLE6_OPT),
+    ];
+    for (item, id) in items {
+        assert_eq!(discriminant(&item), id);
+        assert_eq!(const_item_id(&item), id);
+        let reader = item.as_reader();
+        assert_eq!(discriminant(&reader), id);
+        assert_eq!(reader.item_id(), id);
+
+        let union = types::UnionA::new_builder().set(item).build();
+        assert_eq!(discriminant(&union.to_enum()), id);
+        assert_eq!(discriminant(&union.as_reader().to_enum()), id);
+    }
+}
@@ -0,0 +1,137 @@
+use std::{env, fs, panic};
+
+use molecule::{
+    error::VerificationError,
+    prelude::*,
+    validation::{self, Validators},
+};
+
+use molecule_ci_tests::validation::*;
+
+fn uint64(value: u64) -> Uint64 {
+    Uint64::new_unchecked(value.to_le_bytes().to_vec().into())
+}
+
+fn value(uint64: Uint64Reader) -> u64 {
+    let mut le = [0u8; 8];
+    le.copy_from_slice(uint64.as_slice());
+    u64::from_le_bytes(le)
+}
+
+fn span(start: u64, end: u64) -> Span {
+    Span::new_builder()
+        .start(uint64(start))
+        .end(uint64(end))
+        .build()
+}
+
+fn timestamp_monotonic(slice: &[u8]) -> bool {
+    let span = SpanReader::new_unchecked(slice);
+    value(span.start()) <= value(span.end())
+}
+
+fn sorted(slice: &[u8]) -> bool {
+    let timestamps = TimestampsReader::new_unchecked(slice);
+    let values = timestamps.iter().map(value).collect::<Vec<_>>();
+    values.windows(2).all(|pair| pair[0] <= pair[1])
+}
+
+fn non_empty(slice: &[u8]) -> bool {
+    !EventsReader::new_unchecked(slice).is_empty()
+}
+
+static VALIDATORS: Validators = Validators(&[
+    ("timestamp_monotonic", timestamp_monotonic),
+    ("sorted", sorted),
+    ("non_empty", non_empty),
+]);
+
+fn invariant_not_held<T: std::fmt::Debug>(result: Result<T, VerificationError>, invariant: &str) {
+    match result {
+        Err(VerificationError::InvariantNotHeld(_, ref name)) if name == invariant => {}
+        _ => panic!("{:?} should break the invariant {}", result, invariant),
+    }
+}
+
+// The validators are global, so all checks are in one test.
+#[test]
+fn validators() {
+    let valid = span(1, 2);
+    let err = Span::from_slice(valid.as_slice()).unwrap_err();
+    assert_eq!(err.kind(), "InvariantNotRegistered");
+    assert_eq!(err.type_name(), "SpanReader");
+
+    validation::set_validators(Some(&VALIDATORS));
+    Span::from_slice(valid.as_slice()).unwrap();
+    invariant_not_held(
+        Span::from_slice(span(2, 1).as_slice()),
+        "timestamp_monotonic",
+    );
+
+    // The invariants of the fields are validated with the parent.
+    let event = Event::new_builder().span(span(2, 1)).build();
+    invariant_not_held(Event::from_slice(event.as_slice()), "timestamp_monotonic");
+    invariant_not_held(
+        Event::from_compatible_slice(event.as_slice()),
+        "timestamp_monotonic",
+    );
+    invariant_not_held(
+        EventReader::from_slice_linear(event.as_slice()),
+        "timestamp_monotonic",
+    );
+
+    let timestamps = Timestamps::new_builder()
+        .push(uint64(3))
+        .push(uint64(1))
+        .build();
+    invariant_not_held(Timestamps::from_slice(timestamps.as_slice()), "sorted");
+
+    let events = Events::default();
+    invariant_not_held(Events::from_slice(events.as_slice()), "non_empty");
+    invariant_not_held(
+        EventsReader::from_slice_linear(events.as_slice()),
+        "non_empty",
+    );
+    let events = Events::new_builder()
+        .push(Event::new_builder().span(valid).build())
+        .build();
+    Events::from_slice(events.as_slice()).unwrap();
+    EventsReader::from_slice_linear(events.as_slice()).unwrap();
+
+    validation::set_validators(None);
+    let err = Events::from_slice(events.as_slice()).unwrap_err();
+    assert_eq!(err.kind(), "InvariantNotRegistered");
+}
+
+#[test]
+fn invalid_attributes() {
+    let dir = env::temp_dir().join("molecule-ci-tests-validation");
+    fs::create_dir_all(&dir).unwrap();
+    for (name, schema, expected) in &[
+        (
+            "count_size",
+            "@count_size(1) array Uint32 [byte; 4];\n",
+            "the attribute `@count_size` is only for vectors",
+        ),
+        (
+            "number",
+            "@validate(1) array Uint32 [byte; 4];\n",
+            "the invariant should be a name",
+        ),
+        (
+            "missing",
+            "@validate array Uint32 [byte; 4];\n",
+            "the attribute `@validate` requires a name",
+        ),
+    ] {
+        let path = dir.join(format!("{}.mol", name));
+        fs::write(&path, schema).unwrap();
+        let err = panic::catch_unwind(|| codegen::schema_hash(&path)).unwrap_err();
+        let message = err
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| err.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap();
+        assert!(message.starts_with(expected), "{}", message);
+    }
+}
@@ -0,0 +1,29 @@
+const RESTRICTED_TYPES: &str = include_str!(concat!(env!("OUT_DIR"), "/restricted/ci_tests.rs"));
+const RESTRICTED_NUMBERS: &str = include_str!(concat!(env!("OUT_DIR"), "/restricted/numbers.rs"));
+
+#[test]
+fn crate_visibility() {
+    for item in &[
+        "pub ( crate ) struct Table1 (",
+        "pub ( crate ) struct Table1Reader <",
+        "pub ( crate ) struct Table1Builder {",
+        "pub ( crate ) struct BytesIterator (",
+        "pub ( crate ) struct BytesVecReaderIterator <",
+        "pub ( crate ) enum UnionAUnion {",
+        "pub ( crate ) enum UnionAUnionReader <",
+        "pub ( crate ) mod constants {",
+    ] {
+        assert!(RESTRICTED_TYPES.contains(item), "{} is not found", item);
+    }
+    assert!(!RESTRICTED_TYPES.contains("pub struct"));
+    assert!(!RESTRICTED_TYPES.contains("pub enum"));
+}
+
+#[test]
+fn restricted_visibility() {
+    assert!(RESTRICTED_NUMBERS.contains("pub ( in crate :: restricted ) struct "));
+    // The aliases of the union items as well.
+    assert!(RESTRICTED_NUMBERS.contains("pub ( in crate :: restricted ) fn Small ("));
+    assert!(!RESTRICTED_NUMBERS.contains("pub struct"));
+    assert!(!RESTRICTED_NUMBERS.contains("pub fn Small"));
+}
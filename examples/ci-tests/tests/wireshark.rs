@@ -0,0 +1,36 @@
+const WIRESHARK: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.dissector.lua"));
+const COUNT_SIZE: &str = include_str!(concat!(env!("OUT_DIR"), "/count_size.dissector.lua"));
+
+#[test]
+fn wireshark_dissectors() {
+    assert!(WIRESHARK.contains("\nlocal proto = Proto(\"ci_tests\", \"Molecule ci_tests\")\n"));
+    assert!(WIRESHARK.contains(
+        "    dissect_byte(range(1, 1), item, f[\"struct_a.f2\"])
+    dissect.Byte2(range(2, 2), add(item, f[\"struct_a.f3\"], range(2, 2)))
+"
+    ));
+    assert!(WIRESHARK.contains(
+        "  if #fields < 2 or (#fields > 2 and not proto.prefs.compatible) then
+    return malformed(item, \"field count\")
+  end
+"
+    ));
+    assert!(WIRESHARK.contains("framing.Words = { count_size = 4, item_size = 2 }\n"));
+    // The bytes of an array of bytes aren't added as items.
+    assert!(!WIRESHARK.contains("f[\"byte2.item\"]"));
+}
+
+#[test]
+fn wireshark_unions() {
+    assert!(WIRESHARK.contains("\"item_id\", base.DEC, { [0] = \"byte\", [1] = \"Word\","));
+    assert!(WIRESHARK.contains(
+        "  if id == 2 then return dissect.StructA(body, add(item, f[\"union_a.item\"], body)) end\n"
+    ));
+}
+
+#[test]
+fn wireshark_registration() {
+    assert!(WIRESHARK.contains("proto.prefs.port = Pref.uint(\"TCP port\", 7891, "));
+    assert!(WIRESHARK.ends_with("\nproto:register_heuristic(\"tcp\", heuristic)\n"));
+    assert!(COUNT_SIZE.contains("local count = range(0, 1):le_uint()\n"));
+}
@@ -0,0 +1,23 @@
+use molecule::prelude::*;
+use slices::u8_slice as s;
+
+use molecule_ci_tests::types::{self, constants};
+
+#[test]
+fn encode_with_zeroed_field() {
+    let table = types::Table3::new_builder()
+        .f1(0x56.into())
+        .f3(types::StructA::new_builder().f1(0x78.into()).build())
+        .build();
+    let data = table.encode_with_zeroed_field(constants::table3::FIELD_F3);
+    assert_eq!(data.len(), table.as_slice().len());
+    assert_eq!(
+        &data[..],
+        s!("0x1b000000_10000000_11000000_15000000_56_00000000_000000000000")
+    );
+    let reader = table.as_reader();
+    assert_eq!(
+        reader.encode_with_zeroed_field(constants::table3::FIELD_F1),
+        s!("0x1b000000_10000000_11000000_15000000_00_00000000_780000000000")
+    );
+}
@@ -0,0 +1,86 @@
+mod common;
+
+const ZIG: &str = include_str!(concat!(env!("OUT_DIR"), "/ci_tests.zig"));
+
+#[test]
+fn zig_fixed_types() {
+    assert!(ZIG.contains("\nconst std = @import(\"std\");\n"));
+    assert!(ZIG.contains(
+        "pub const StructA = struct {
+    raw: [size]u8,
+
+    pub const size = 6;
+"
+    ));
+    assert!(ZIG.contains(
+        "    pub fn getF3(self: StructA) Byte2 {
+        return .{ .raw = self.raw[2..4].* };
+    }"
+    ));
+    assert!(ZIG.contains("        @memcpy(raw[2..4], &fields.f3.raw);\n"));
+    assert!(ZIG.contains("    pub fn init(items: [len]u8) Byte2 {\n"));
+}
+
+#[test]
+fn zig_dynamic_types() {
+    assert!(ZIG.contains(
+        "    /// Verifies the bytes in the strict mode.
+    pub fn fromSlice(slice: []const u8) Error!Table2 {
+        const count = try verifyDynamic(slice);
+        if (count != 2) return error.FieldCount;
+        try verifyByte(dynamicAt(slice, 0));
+        _ = try Word2.fromSlice(dynamicAt(slice, 1));
+        return .{ .raw = slice };
+    }"
+    ));
+    assert!(ZIG.contains(
+        "    pub fn build(allocator: std.mem.Allocator, fields: Fields) std.mem.Allocator.Error![]u8 {
+        return packDynamic(allocator, &.{ std.mem.asBytes(&fields.f1), &fields.f2.raw });
+    }"
+    ));
+    assert!(ZIG.contains(
+        "    pub fn build(allocator: std.mem.Allocator, _: Fields) std.mem.Allocator.Error![]u8 {"
+    ));
+    assert!(
+        ZIG.contains("            2 => .{ .struct_a = .{ .raw = body[0..StructA.size].* } },\n")
+    );
+    assert!(ZIG.contains("            .bytes => |v| packUnion(allocator, 3, v.raw),\n"));
+}
+
+#[test]
+fn zig_types_are_unique() {
+    common::assert_unique(
+        ZIG.lines()
+            .filter_map(|line| line.strip_prefix("pub const "))
+            .map(|line| line.split(' ').next().unwrap()),
+    );
+}
+
+fn zig_array(name: &str, data: &[u8]) -> String {
+    let items = data
+        .iter()
+        .map(|byte| format!("0x{:02x}", byte))
+        .collect::<Vec<_>>();
+    format!("pub const {} = [_]u8{{ {} }};\n", name, items.join(", "))
+}
+
+#[test]
+#[cfg_attr(not(round_trip), ignore = "requires Zig, run with ROUND_TRIP=1")]
+fn zig_round_trip() {
+    let samples = common::samples();
+    let arrays = [
+        zig_array("table6", &samples.table6),
+        zig_array("union_a", &samples.union_a),
+        zig_array("broken", &samples.broken),
+    ];
+    let dir = common::workspace(
+        "zig",
+        &[
+            ("ci_tests.zig", ZIG),
+            ("samples.zig", &arrays.concat()),
+            ("round_trip.zig", include_str!("round_trip/round_trip.zig")),
+        ],
+    );
+    // The assertions are in the tests of the Zig file, which fail the command.
+    common::run(&dir, "zig", &["test", "round_trip.zig"]);
+}
@@ -1,20 +1,94 @@
-use std::{path::PathBuf, rc::Rc};
+use std::{fmt, path::PathBuf, rc::Rc};
 
 #[derive(Debug)]
 pub(crate) struct ItemDecl {
     pub(crate) typ: String,
+    pub(crate) alias: Option<String>,
+    pub(crate) max_size: Option<usize>,
 }
 
 #[derive(Debug)]
 pub(crate) struct FieldDecl {
     pub(crate) name: String,
     pub(crate) typ: String,
+    pub(crate) flatten: bool,
+    pub(crate) display: Option<String>,
+    pub(crate) required: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Operand {
+    /// The item count of a vector field.
+    Len(String),
+    /// The item ID of a union field.
+    ItemId(String),
+    /// The value of a byte field, or a byte array field in little endian.
+    Value(String),
+    Integer(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// A constraint between the fields, such as `assert len(payload) == declared_len;`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Constraint {
+    pub(crate) lhs: Operand,
+    pub(crate) comparison: Comparison,
+    pub(crate) rhs: Operand,
+}
+
+/// The algorithm of the checksum trailer of a table, which is set by `@checksum`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Checksum {
+    Crc32,
+    Xxh32,
+}
+
+/// The unit of the logical time types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TimeUnit {
+    Seconds,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+/// The logical type of an array of bytes, which is set by `@logical`.
+///
+/// It only adds the conversions to the generated code, the binary format is the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Logical {
+    /// The time since the Unix epoch, in a little-endian `u64`.
+    UnixTime(TimeUnit),
+    /// A duration, in a little-endian `u64`.
+    Duration(TimeUnit),
+    /// A UUID, in the order of the bytes of the canonical string.
+    Uuid,
+}
+
+impl Logical {
+    /// The size of the arrays of the logical type.
+    pub(crate) fn size(self) -> usize {
+        match self {
+            Logical::UnixTime(_) | Logical::Duration(_) => 8,
+            Logical::Uuid => 16,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub(crate) struct OptionDecl {
     pub(crate) name: String,
     pub(crate) typ: String,
+    pub(crate) invariant: Option<String>,
     pub(crate) imported_depth: usize,
 }
 
@@ -22,6 +96,7 @@ pub(crate) struct OptionDecl {
 pub(crate) struct UnionDecl {
     pub(crate) name: String,
     pub(crate) inner: Vec<ItemDecl>,
+    pub(crate) invariant: Option<String>,
     pub(crate) imported_depth: usize,
 }
 
@@ -30,6 +105,8 @@ pub(crate) struct ArrayDecl {
     pub(crate) name: String,
     pub(crate) typ: String,
     pub(crate) length: usize,
+    pub(crate) invariant: Option<String>,
+    pub(crate) logical: Option<Logical>,
     pub(crate) imported_depth: usize,
 }
 
@@ -37,6 +114,8 @@ pub(crate) struct ArrayDecl {
 pub(crate) struct StructDecl {
     pub(crate) name: String,
     pub(crate) inner: Vec<FieldDecl>,
+    pub(crate) constraints: Vec<Constraint>,
+    pub(crate) invariant: Option<String>,
     pub(crate) imported_depth: usize,
 }
 
@@ -44,6 +123,8 @@ pub(crate) struct StructDecl {
 pub(crate) struct VectorDecl {
     pub(crate) name: String,
     pub(crate) typ: String,
+    pub(crate) count_size: Option<usize>,
+    pub(crate) invariant: Option<String>,
     pub(crate) imported_depth: usize,
 }
 
@@ -51,6 +132,17 @@ pub(crate) struct VectorDecl {
 pub(crate) struct TableDecl {
     pub(crate) name: String,
     pub(crate) inner: Vec<FieldDecl>,
+    pub(crate) constraints: Vec<Constraint>,
+    pub(crate) invariant: Option<String>,
+    pub(crate) checksum: Option<Checksum>,
+    pub(crate) imported_depth: usize,
+}
+
+/// Another name of a declared type, such as `alias Amount = Uint64;`.
+#[derive(Debug)]
+pub(crate) struct AliasDecl {
+    pub(crate) name: String,
+    pub(crate) typ: String,
     pub(crate) imported_depth: usize,
 }
 
@@ -62,6 +154,7 @@ pub(crate) enum TopDecl {
     Struct(StructDecl),
     Vector(VectorDecl),
     Table(TableDecl),
+    Alias(AliasDecl),
 }
 
 #[derive(Debug, Clone)]
@@ -96,6 +189,7 @@ impl_into_top_decl_for!(Array, ArrayDecl);
 impl_into_top_decl_for!(Struct, StructDecl);
 impl_into_top_decl_for!(Vector, VectorDecl);
 impl_into_top_decl_for!(Table, TableDecl);
+impl_into_top_decl_for!(Alias, AliasDecl);
 
 macro_rules! impl_methods_for_decl {
     ($decl:ident) => {
@@ -114,6 +208,7 @@ impl_methods_for_decl!(ArrayDecl);
 impl_methods_for_decl!(StructDecl);
 impl_methods_for_decl!(VectorDecl);
 impl_methods_for_decl!(TableDecl);
+impl_methods_for_decl!(AliasDecl);
 
 impl TopDecl {
     pub(crate) fn name(&self) -> &str {
@@ -124,6 +219,7 @@ impl TopDecl {
             TopDecl::Struct(inner) => inner.name(),
             TopDecl::Vector(inner) => inner.name(),
             TopDecl::Table(inner) => inner.name(),
+            TopDecl::Alias(inner) => inner.name(),
         }
     }
 }
@@ -137,3 +233,42 @@ impl Ast {
         self.decls.push(decl.into());
     }
 }
+
+impl Operand {
+    pub(crate) fn field(&self) -> Option<&str> {
+        match self {
+            Operand::Len(field) | Operand::ItemId(field) | Operand::Value(field) => Some(field),
+            Operand::Integer(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::Len(field) => write!(f, "len({})", field),
+            Operand::ItemId(field) => write!(f, "item_id({})", field),
+            Operand::Value(field) => write!(f, "{}", field),
+            Operand::Integer(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl Comparison {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Comparison::Eq => "==",
+            Comparison::Ne => "!=",
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
+        }
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.lhs, self.comparison.as_str(), self.rhs)
+    }
+}
@@ -1,6 +1,12 @@
-use std::{collections::HashMap, rc::Rc};
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
-use super::{super::raw, TopDecl};
+use case::CaseExt;
+use molecule::NUMBER_SIZE;
+
+use super::{super::raw, HasName, TopDecl};
 
 macro_rules! impl_into_top_decl_for {
     ($type:ident) => {
@@ -33,6 +39,7 @@ impl CompleteRawDecl for raw::OptionDecl {
             super::Option_ {
                 name,
                 typ,
+                invariant: self.invariant.clone(),
                 imported_depth: self.imported_depth,
             }
             .into()
@@ -45,19 +52,48 @@ impl CompleteRawDecl for raw::UnionDecl {
         if self.inner.is_empty() {
             panic!("the union ({}) is empty", self.name());
         }
+        // The generated names are derived from the snake case and the camel case, so an alias
+        // shouldn't be the same as any other name in either case.
+        let cases = |name: &str| vec![name.to_snake(), name.to_camel()];
+        let mut names = HashMap::new();
+        for raw_item in &self.inner {
+            for case in cases(&raw_item.typ) {
+                names.insert(case, raw_item.typ.as_str());
+            }
+        }
+        for raw_item in &self.inner {
+            if let Some(ref alias) = raw_item.alias {
+                let other = cases(alias)
+                    .into_iter()
+                    .find_map(|case| names.insert(case, alias));
+                if let Some(other) = other {
+                    panic!(
+                        "the alias ({}) of the item ({}) in union ({}) conflicts with ({})",
+                        alias,
+                        raw_item.typ,
+                        self.name(),
+                        other
+                    );
+                }
+            }
+        }
         self.inner
             .iter()
             .map(|raw_item| {
                 deps.get(raw_item.typ.as_str()).map(|dep| super::ItemDecl {
                     typ: Rc::clone(dep),
+                    alias: raw_item.alias.clone(),
+                    max_size: raw_item.max_size,
                 })
             })
             .collect::<Option<Vec<_>>>()
             .map(|inner| {
+                check_item_max_sizes(self.name(), &inner);
                 let name = self.name().to_owned();
                 super::Union {
                     name,
                     inner,
+                    invariant: self.invariant.clone(),
                     imported_depth: self.imported_depth,
                 }
                 .into()
@@ -81,11 +117,24 @@ impl CompleteRawDecl for raw::ArrayDecl {
             let name = self.name().to_owned();
             let typ = Rc::clone(dep);
             let item_count = self.length;
+            if let Some(logical) = self.logical {
+                if !typ.is_atom() || item_count != logical.size() {
+                    panic!(
+                        "the logical type of array ({}) requires {} bytes, but it is [{}; {}]",
+                        self.name(),
+                        logical.size(),
+                        self.typ,
+                        item_count
+                    );
+                }
+            }
             super::Array {
                 name,
                 item_size,
                 item_count,
                 typ,
+                invariant: self.invariant.clone(),
+                logical: self.logical,
                 imported_depth: self.imported_depth,
             }
             .into()
@@ -112,6 +161,9 @@ impl CompleteRawDecl for raw::StructDecl {
                 let field = super::FieldDecl {
                     name: field_name,
                     typ: Rc::clone(dep),
+                    flatten: raw_field.flatten,
+                    display: raw_field.display.clone(),
+                    required: raw_field.required,
                 };
                 inner.push(field);
             } else {
@@ -124,12 +176,17 @@ impl CompleteRawDecl for raw::StructDecl {
         if field_size.iter().sum::<usize>() == 0 {
             panic!("the struct ({}) has no size", self.name());
         }
+        check_flattened_fields(self.name(), &inner);
+        check_display_fields(self.name(), &inner);
+        check_constraints(self.name(), &inner, &self.constraints);
         let name = self.name().to_owned();
         Some(
             super::Struct {
                 name,
                 field_size,
                 inner,
+                constraints: self.constraints.clone(),
+                invariant: self.invariant.clone(),
                 imported_depth: self.imported_depth,
             }
             .into(),
@@ -146,14 +203,23 @@ impl CompleteRawDecl for raw::VectorDecl {
                 super::FixVec {
                     name,
                     item_size,
+                    count_size: self.count_size.unwrap_or(NUMBER_SIZE),
                     typ,
+                    invariant: self.invariant.clone(),
                     imported_depth: self.imported_depth,
                 }
                 .into()
             } else {
+                if self.count_size.is_some() {
+                    panic!(
+                        "the attribute `@count_size` is only for fixed vectors, but {} is dynamic",
+                        name
+                    );
+                }
                 super::DynVec {
                     name,
                     typ,
+                    invariant: self.invariant.clone(),
                     imported_depth: self.imported_depth,
                 }
                 .into()
@@ -172,17 +238,150 @@ impl CompleteRawDecl for raw::TableDecl {
                     .map(|dep| super::FieldDecl {
                         name: field_name,
                         typ: Rc::clone(dep),
+                        flatten: raw_field.flatten,
+                        display: raw_field.display.clone(),
+                        required: raw_field.required,
                     })
             })
             .collect::<Option<Vec<_>>>()
             .map(|inner| {
+                check_flattened_fields(self.name(), &inner);
+                check_display_fields(self.name(), &inner);
+                check_required_fields(self.name(), &inner);
+                check_constraints(self.name(), &inner, &self.constraints);
                 let name = self.name().to_owned();
                 super::Table {
                     name,
                     inner,
+                    constraints: self.constraints.clone(),
+                    invariant: self.invariant.clone(),
+                    checksum: self.checksum,
                     imported_depth: self.imported_depth,
                 }
                 .into()
             })
     }
 }
+
+/// The flattened fields should be structs, and their fields shouldn't collide with the other
+/// fields of the parent.
+fn check_flattened_fields(parent: &str, inner: &[super::FieldDecl]) {
+    let mut names = inner
+        .iter()
+        .map(|field| field.name.clone())
+        .collect::<HashSet<_>>();
+    for field in inner.iter().filter(|field| field.flatten) {
+        let flattened = match field.typ.as_ref() {
+            TopDecl::Struct(ref typ) => &typ.inner,
+            _ => panic!(
+                "the attribute `@flatten` is only for structs, but the field ({}) in ({}) is {}",
+                field.name,
+                parent,
+                field.typ.name()
+            ),
+        };
+        for inner_field in flattened {
+            let name = format!("{}_{}", field.name, inner_field.name);
+            if !names.insert(name.clone()) {
+                panic!(
+                    "the flattened field ({}) in ({}) is duplicated",
+                    name, parent
+                );
+            }
+        }
+    }
+}
+
+/// The required fields should be options.
+fn check_required_fields(parent: &str, inner: &[super::FieldDecl]) {
+    for field in inner.iter().filter(|field| field.required) {
+        if !matches!(field.typ.as_ref(), TopDecl::Option_(_)) {
+            panic!(
+                "the attribute `@required` is only for options, but the field ({}) in ({}) is {}",
+                field.name,
+                parent,
+                field.typ.name()
+            );
+        }
+    }
+}
+
+/// The max size of an item should fit its smallest value.
+fn check_item_max_sizes(parent: &str, inner: &[super::ItemDecl]) {
+    for item in inner {
+        if let Some(max_size) = item.max_size {
+            let (min_size, _) = crate::stats::min_size(&item.typ);
+            if max_size < min_size {
+                panic!(
+                    "the max size {} of the item ({}) in union ({}) is less than its min size {}",
+                    max_size,
+                    item.typ.name(),
+                    parent,
+                    min_size
+                );
+            }
+        }
+    }
+}
+
+/// The fields with a formatter should be bytes.
+fn check_display_fields(parent: &str, inner: &[super::FieldDecl]) {
+    for field in inner.iter().filter(|field| field.display.is_some()) {
+        if field.typ.raw_bytes_offset().is_none() {
+            panic!(
+                "the attribute `@display` is only for the arrays or the vectors of bytes, \
+                 but the field ({}) in ({}) is {}",
+                field.name,
+                parent,
+                field.typ.name()
+            );
+        }
+    }
+}
+
+/// The fields in the constraints should exist, and the operands should be applicable to them.
+fn check_constraints(parent: &str, inner: &[super::FieldDecl], constraints: &[raw::Constraint]) {
+    for constraint in constraints {
+        let operands = [&constraint.lhs, &constraint.rhs];
+        if operands.iter().all(|operand| operand.field().is_none()) {
+            panic!(
+                "the constraint `{}` in ({}) doesn't refer to any field",
+                constraint, parent
+            );
+        }
+        for operand in &operands {
+            let name = match operand.field() {
+                Some(name) => name,
+                None => continue,
+            };
+            let field = inner
+                .iter()
+                .find(|field| field.name == name)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "the constraint `{}` in ({}) refers to an unknown field ({})",
+                        constraint, parent, name
+                    )
+                });
+            let applicable = match (operand, field.typ.as_ref()) {
+                (raw::Operand::Len(_), TopDecl::FixVec(_))
+                | (raw::Operand::Len(_), TopDecl::DynVec(_))
+                | (raw::Operand::ItemId(_), TopDecl::Union(_))
+                | (raw::Operand::Value(_), TopDecl::Atom(_)) => true,
+                (raw::Operand::Value(_), TopDecl::Array(ref typ)) => {
+                    typ.typ.is_atom() && typ.item_count <= 8
+                }
+                _ => false,
+            };
+            if !applicable {
+                panic!(
+                    "the operand `{}` of the constraint `{}` in ({}) isn't applicable to {}",
+                    operand,
+                    constraint,
+                    parent,
+                    field.typ.name()
+                );
+            }
+        }
+    }
+}
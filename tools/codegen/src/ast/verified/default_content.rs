@@ -42,10 +42,7 @@ impl DefaultContent for super::Struct {
 
 impl DefaultContent for super::FixVec {
     fn default_content(&self) -> Vec<u8> {
-        let item_count = 0;
-        let mut content = Vec::with_capacity(NUMBER_SIZE);
-        content.extend_from_slice(&pack_number(item_count as Number));
-        content
+        vec![0; self.count_size]
     }
 }
 
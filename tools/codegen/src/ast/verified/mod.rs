@@ -1,10 +1,13 @@
 use std::{
     collections::{HashMap, HashSet},
+    convert::TryFrom,
     rc::Rc,
 };
 
-pub(crate) use super::raw::ImportStmt;
 use super::raw::{Ast as RawAst, TopDecl as RawTopDecl};
+pub(crate) use super::raw::{
+    Checksum, Comparison, Constraint, ImportStmt, Logical, Operand, TimeUnit,
+};
 
 mod complete;
 mod default_content;
@@ -21,8 +24,9 @@ pub(crate) const ATOM_PRIMITIVE_NAME: &str = "Byte";
 #[derive(Debug)]
 pub(crate) struct Ast {
     pub(crate) namespace: String,
-    pub(self) imports: Vec<Rc<ImportStmt>>,
-    pub(self) decls: Vec<Rc<TopDecl>>,
+    imports: Vec<Rc<ImportStmt>>,
+    decls: Vec<Rc<TopDecl>>,
+    aliases: Vec<Rc<Alias>>,
 }
 
 #[derive(Debug)]
@@ -47,6 +51,8 @@ pub(crate) struct Atom {
 pub(crate) struct Option_ {
     pub(crate) name: String,
     pub(crate) typ: Rc<TopDecl>,
+    /// The name of the invariant which is set by `@validate`.
+    pub(crate) invariant: Option<String>,
     pub(crate) imported_depth: usize,
 }
 
@@ -54,6 +60,8 @@ pub(crate) struct Option_ {
 pub(crate) struct Union {
     pub(crate) name: String,
     pub(crate) inner: Vec<ItemDecl>,
+    /// The name of the invariant which is set by `@validate`.
+    pub(crate) invariant: Option<String>,
     pub(crate) imported_depth: usize,
 }
 
@@ -63,6 +71,10 @@ pub(crate) struct Array {
     pub(crate) item_size: usize,
     pub(crate) item_count: usize,
     pub(crate) typ: Rc<TopDecl>,
+    /// The name of the invariant which is set by `@validate`.
+    pub(crate) invariant: Option<String>,
+    /// The logical type which is set by `@logical`, only for the arrays of bytes.
+    pub(crate) logical: Option<Logical>,
     pub(crate) imported_depth: usize,
 }
 
@@ -71,6 +83,9 @@ pub(crate) struct Struct {
     pub(crate) name: String,
     pub(crate) field_size: Vec<usize>,
     pub(crate) inner: Vec<FieldDecl>,
+    pub(crate) constraints: Vec<Constraint>,
+    /// The name of the invariant which is set by `@validate`.
+    pub(crate) invariant: Option<String>,
     pub(crate) imported_depth: usize,
 }
 
@@ -78,7 +93,10 @@ pub(crate) struct Struct {
 pub(crate) struct FixVec {
     pub(crate) name: String,
     pub(crate) item_size: usize,
+    pub(crate) count_size: usize,
     pub(crate) typ: Rc<TopDecl>,
+    /// The name of the invariant which is set by `@validate`.
+    pub(crate) invariant: Option<String>,
     pub(crate) imported_depth: usize,
 }
 
@@ -86,6 +104,8 @@ pub(crate) struct FixVec {
 pub(crate) struct DynVec {
     pub(crate) name: String,
     pub(crate) typ: Rc<TopDecl>,
+    /// The name of the invariant which is set by `@validate`.
+    pub(crate) invariant: Option<String>,
     pub(crate) imported_depth: usize,
 }
 
@@ -93,18 +113,47 @@ pub(crate) struct DynVec {
 pub(crate) struct Table {
     pub(crate) name: String,
     pub(crate) inner: Vec<FieldDecl>,
+    pub(crate) constraints: Vec<Constraint>,
+    /// The name of the invariant which is set by `@validate`.
+    pub(crate) invariant: Option<String>,
+    /// The algorithm of the checksum trailer which is set by `@checksum`, the table is only a
+    /// record then, so it can't be used in another type.
+    pub(crate) checksum: Option<Checksum>,
+    pub(crate) imported_depth: usize,
+}
+
+/// Another name of a declared type.
+///
+/// The references to an alias are resolved to the type, so it's only a name in the generated code.
+#[derive(Debug)]
+pub(crate) struct Alias {
+    pub(crate) name: String,
+    pub(crate) typ: Rc<TopDecl>,
     pub(crate) imported_depth: usize,
 }
 
 #[derive(Debug)]
 pub(crate) struct ItemDecl {
     pub(crate) typ: Rc<TopDecl>,
+    /// Another name of the item, which is deprecated, such as the name before the type is renamed.
+    pub(crate) alias: Option<String>,
+    /// The most bytes of the item, which is set by `@max_size`; the other items are not limited.
+    pub(crate) max_size: Option<usize>,
 }
 
 #[derive(Debug)]
 pub(crate) struct FieldDecl {
     pub(crate) name: String,
     pub(crate) typ: Rc<TopDecl>,
+    /// The fields of the struct are also accessed from the parent, such as `position_x()` for
+    /// `position().x()`, which is set by `@flatten`.
+    pub(crate) flatten: bool,
+    /// The name of the formatter of the bytes in the display of the parent, which is set by
+    /// `@display`, such as `hex` or a formatter which is registered at runtime.
+    pub(crate) display: Option<String>,
+    /// The option field is required in practice, which is set by `@required`, so the builder
+    /// reports it if it's none, but it's still optional in the encoding for compatibility.
+    pub(crate) required: bool,
 }
 
 impl Array {
@@ -119,6 +168,66 @@ impl Struct {
     }
 }
 
+impl FixVec {
+    /// The count size is not `NUMBER_SIZE` only if it's set by `@count_size`, which is not a
+    /// standard molecule encoding.
+    pub(crate) fn is_standard(&self) -> bool {
+        self.count_size == molecule::NUMBER_SIZE
+    }
+
+    pub(crate) fn max_item_count(&self) -> u64 {
+        (1 << (self.count_size * 8)) - 1
+    }
+}
+
+fn bounded(size: Option<usize>) -> Option<usize> {
+    size.filter(|size| *size <= molecule::Number::MAX as usize)
+}
+
+impl Option_ {
+    pub(crate) fn max_size(&self) -> Option<usize> {
+        self.typ.max_size()
+    }
+}
+
+impl Union {
+    pub(crate) fn max_size(&self) -> Option<usize> {
+        let size = self
+            .inner
+            .iter()
+            .map(|item| match (item.typ.max_size(), item.max_size) {
+                (Some(size), Some(max_size)) => Some(size.min(max_size)),
+                (size, max_size) => size.or(max_size),
+            })
+            .try_fold(0, |max, size| size.map(|size| max.max(size)))
+            .and_then(|size| size.checked_add(molecule::NUMBER_SIZE));
+        bounded(size)
+    }
+}
+
+impl FixVec {
+    pub(crate) fn max_size(&self) -> Option<usize> {
+        let size = usize::try_from(self.max_item_count())
+            .ok()
+            .and_then(|count| molecule::fixvec_total_size(self.count_size, self.item_size, count));
+        bounded(size)
+    }
+}
+
+impl Table {
+    pub(crate) fn max_size(&self) -> Option<usize> {
+        let size = self
+            .inner
+            .iter()
+            .map(|field| field.typ.max_size())
+            .try_fold(0, |sum: usize, size| {
+                size.and_then(|size| sum.checked_add(size))
+            })
+            .and_then(|size| size.checked_add(molecule::header_size(self.inner.len())?));
+        bounded(size)
+    }
+}
+
 impl TopDecl {
     fn atom() -> Self {
         let atom = Atom {
@@ -129,13 +238,34 @@ impl TopDecl {
     }
 
     pub(crate) fn is_atom(&self) -> bool {
+        matches!(self, TopDecl::Atom(_))
+    }
+
+    /// The offset of the raw data of an array or a FixVec of bytes, which is after the item
+    /// count of a FixVec; `None` for the other types.
+    pub(crate) fn raw_bytes_offset(&self) -> Option<usize> {
         match self {
-            TopDecl::Atom(_) => true,
-            _ => false,
+            TopDecl::Array(ref typ) if typ.typ.is_atom() => Some(0),
+            TopDecl::FixVec(ref typ) if typ.typ.is_atom() => Some(typ.count_size),
+            _ => None,
         }
     }
 
-    fn imported_depth(&self) -> usize {
+    /// The types which this type is composed of.
+    pub(crate) fn dependencies(&self) -> Vec<&Rc<TopDecl>> {
+        match self {
+            TopDecl::Atom(_) => Vec::new(),
+            TopDecl::Option_(ref typ) => vec![&typ.typ],
+            TopDecl::Union(ref typ) => typ.inner.iter().map(|x| &x.typ).collect(),
+            TopDecl::Array(ref typ) => vec![&typ.typ],
+            TopDecl::Struct(ref typ) => typ.inner.iter().map(|x| &x.typ).collect(),
+            TopDecl::FixVec(ref typ) => vec![&typ.typ],
+            TopDecl::DynVec(ref typ) => vec![&typ.typ],
+            TopDecl::Table(ref typ) => typ.inner.iter().map(|x| &x.typ).collect(),
+        }
+    }
+
+    pub(crate) fn imported_depth(&self) -> usize {
         match self {
             TopDecl::Atom(_) => unreachable!(),
             TopDecl::Option_(ref typ) => typ.imported_depth,
@@ -148,7 +278,7 @@ impl TopDecl {
         }
     }
 
-    fn total_size(&self) -> Option<usize> {
+    pub(crate) fn total_size(&self) -> Option<usize> {
         match self {
             TopDecl::Atom(ref typ) => Some(typ.size),
             TopDecl::Option_(_) => None,
@@ -161,6 +291,23 @@ impl TopDecl {
         }
     }
 
+    /// The maximum size of the values in the strict mode, or `None` if it's unbounded.
+    ///
+    /// A type is bounded if it's fixed-size, or all the vectors in it have `@count_size`; a
+    /// size which doesn't fit in a `Number` is unbounded as well.
+    pub(crate) fn max_size(&self) -> Option<usize> {
+        match self {
+            TopDecl::Atom(ref typ) => Some(typ.size),
+            TopDecl::Option_(ref typ) => typ.max_size(),
+            TopDecl::Union(ref typ) => typ.max_size(),
+            TopDecl::Array(ref typ) => Some(typ.total_size()),
+            TopDecl::Struct(ref typ) => Some(typ.total_size()),
+            TopDecl::FixVec(ref typ) => typ.max_size(),
+            TopDecl::DynVec(_) => None,
+            TopDecl::Table(ref typ) => typ.max_size(),
+        }
+    }
+
     fn complete(raw: &RawTopDecl, deps: &HashMap<&str, Rc<Self>>) -> Option<Self> {
         match raw {
             RawTopDecl::Option_(raw_decl) => raw_decl.complete(deps),
@@ -169,6 +316,7 @@ impl TopDecl {
             RawTopDecl::Struct(raw_decl) => raw_decl.complete(deps),
             RawTopDecl::Vector(raw_decl) => raw_decl.complete(deps),
             RawTopDecl::Table(raw_decl) => raw_decl.complete(deps),
+            RawTopDecl::Alias(_) => unreachable!(),
         }
     }
 }
@@ -195,29 +343,55 @@ impl Ast {
             let incompleted = decls_keys.len();
             decls_keys.retain(|&name| {
                 let decl_raw = decls_idx.get(name).unwrap();
-                if let Some(decl) = TopDecl::complete(decl_raw, &decls_result) {
-                    decls_result.insert(name, Rc::new(decl));
+                // An alias is completed as the type, so the references to it are resolved.
+                let decl = if let RawTopDecl::Alias(raw_alias) = decl_raw {
+                    decls_result.get(raw_alias.typ.as_str()).map(Rc::clone)
+                } else {
+                    TopDecl::complete(decl_raw, &decls_result).map(Rc::new)
+                };
+                if let Some(decl) = decl {
+                    decls_result.insert(name, decl);
                     false
                 } else {
                     true
                 }
             });
             if decls_keys.len() == incompleted {
+                let mut names: Vec<_> = decls_keys.into_iter().collect();
+                names.sort_unstable();
                 panic!(
                     "there are {} types which are unable to be completed: {:?}",
-                    incompleted, decls_keys
+                    incompleted, names
                 );
             }
         }
         let mut decls = Vec::with_capacity(raw.decls.len());
+        let mut aliases = Vec::new();
         for decl in &raw.decls[..] {
             let result = decls_result.get(decl.name()).unwrap();
-            decls.push(Rc::clone(result));
+            if let RawTopDecl::Alias(raw_alias) = decl {
+                if result.is_atom() {
+                    panic!(
+                        "the alias ({}) should be of a declared type, but it is of {}",
+                        raw_alias.name, ATOM_NAME
+                    );
+                }
+                let alias = Alias {
+                    name: raw_alias.name.clone(),
+                    typ: Rc::clone(result),
+                    imported_depth: raw_alias.imported_depth,
+                };
+                aliases.push(Rc::new(alias));
+            } else {
+                decls.push(Rc::clone(result));
+            }
         }
+        check_sealed_records(&decls);
         Self {
             namespace: raw.namespace,
             imports: raw.imports,
             decls,
+            aliases,
         }
     }
 
@@ -229,6 +403,32 @@ impl Ast {
             .collect()
     }
 
+    pub(crate) fn decls(&self) -> &[Rc<TopDecl>] {
+        &self.decls
+    }
+
+    /// Find a type by its name, or by the name of an alias of it.
+    pub(crate) fn get_decl(&self, name: &str) -> Option<Rc<TopDecl>> {
+        self.decls
+            .iter()
+            .find(|decl| decl.name() == name)
+            .or_else(|| {
+                self.aliases
+                    .iter()
+                    .find(|alias| alias.name == name)
+                    .map(|alias| &alias.typ)
+            })
+            .map(Rc::clone)
+    }
+
+    pub(crate) fn major_aliases(&self) -> Vec<Rc<Alias>> {
+        self.aliases
+            .iter()
+            .filter(|x| x.imported_depth == 0)
+            .map(Rc::clone)
+            .collect()
+    }
+
     pub(crate) fn major_imports(&self) -> Vec<Rc<ImportStmt>> {
         self.imports
             .iter()
@@ -237,3 +437,83 @@ impl Ast {
             .collect()
     }
 }
+
+/// The tables with a checksum trailer are only records, the trailer isn't a part of the molecule
+/// encoding, so they can't be the fields or the items of the other types.
+fn check_sealed_records(decls: &[Rc<TopDecl>]) {
+    for decl in decls {
+        for dep in decl.dependencies() {
+            if let TopDecl::Table(ref table) = dep.as_ref() {
+                if table.checksum.is_some() {
+                    panic!(
+                        "the table ({}) has a checksum trailer, so it can't be used in ({})",
+                        table.name,
+                        decl.name()
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// The declarations which could have an invariant, which is set by `@validate`, and the structs
+/// and the tables which could also have constraints between their fields.
+pub(crate) trait HasInvariant {
+    fn invariant(&self) -> Option<&str>;
+
+    fn constraints(&self) -> &[Constraint] {
+        &[]
+    }
+}
+
+macro_rules! impl_has_invariant_for_decl {
+    ($decl:ident) => {
+        impl HasInvariant for $decl {
+            fn invariant(&self) -> Option<&str> {
+                self.invariant.as_deref()
+            }
+        }
+    };
+    ($decl:ident, constraints) => {
+        impl HasInvariant for $decl {
+            fn invariant(&self) -> Option<&str> {
+                self.invariant.as_deref()
+            }
+
+            fn constraints(&self) -> &[Constraint] {
+                &self.constraints
+            }
+        }
+    };
+}
+
+impl_has_invariant_for_decl!(Option_);
+impl_has_invariant_for_decl!(Union);
+impl_has_invariant_for_decl!(Array);
+impl_has_invariant_for_decl!(Struct, constraints);
+impl_has_invariant_for_decl!(FixVec);
+impl_has_invariant_for_decl!(DynVec);
+impl_has_invariant_for_decl!(Table, constraints);
+
+impl HasInvariant for TopDecl {
+    fn invariant(&self) -> Option<&str> {
+        match self {
+            TopDecl::Atom(_) => None,
+            TopDecl::Option_(ref typ) => typ.invariant(),
+            TopDecl::Union(ref typ) => typ.invariant(),
+            TopDecl::Array(ref typ) => typ.invariant(),
+            TopDecl::Struct(ref typ) => typ.invariant(),
+            TopDecl::FixVec(ref typ) => typ.invariant(),
+            TopDecl::DynVec(ref typ) => typ.invariant(),
+            TopDecl::Table(ref typ) => typ.invariant(),
+        }
+    }
+
+    fn constraints(&self) -> &[Constraint] {
+        match self {
+            TopDecl::Struct(ref typ) => typ.constraints(),
+            TopDecl::Table(ref typ) => typ.constraints(),
+            _ => &[],
+        }
+    }
+}
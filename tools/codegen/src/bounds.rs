@@ -0,0 +1,15 @@
+use std::path::Path;
+
+use crate::{ast::verified::HasName, Parser};
+
+/// List the maximum encoded sizes of the types in a schema file, in the order of the
+/// declarations; it's `None` if the type is unbounded.
+///
+/// The imported types are not listed.
+pub fn max_encoded_sizes<P: AsRef<Path>>(path: P) -> Vec<(String, Option<usize>)> {
+    let ast = Parser::parse(&path);
+    ast.major_decls()
+        .iter()
+        .map(|decl| (decl.name().to_owned(), decl.max_size()))
+        .collect()
+}
@@ -4,7 +4,10 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use crate::{Generator, Language};
+use crate::{
+    generator::{header, hooks::Hooks, Options},
+    lint, CProfile, Edition, Flatten, Generator, Language, Level, LintLevels, Visibility,
+};
 
 pub enum Output {
     Stdout,
@@ -15,6 +18,12 @@ pub struct Compiler {
     language: Option<Language>,
     file_path: Option<PathBuf>,
     output: Output,
+    options: Options,
+    lint_levels: Option<LintLevels>,
+    header_file: Option<PathBuf>,
+    hooks_dir: Option<PathBuf>,
+    reproducible: bool,
+    verify_reproducible: bool,
 }
 
 impl Default for Compiler {
@@ -29,6 +38,12 @@ impl Compiler {
             language: None,
             file_path: None,
             output: Output::Stdout,
+            options: Options::default(),
+            lint_levels: None,
+            header_file: None,
+            hooks_dir: None,
+            reproducible: false,
+            verify_reproducible: false,
         }
     }
 
@@ -53,9 +68,322 @@ impl Compiler {
         self
     }
 
+    /// Implement `schemars::JsonSchema` for the generated Rust entities.
+    ///
+    /// The crate which includes the generated code should depend on `schemars` 1.x.
+    pub fn json_schema(&mut self, enable: bool) -> &mut Self {
+        self.options.json_schema = enable;
+        self
+    }
+
+    /// Implement conversions between the generated Rust vectors of fixed-size items and Apache
+    /// Arrow arrays.
+    ///
+    /// The `arrow` feature of `molecule` should be enabled for the generated code.
+    pub fn arrow(&mut self, enable: bool) -> &mut Self {
+        self.options.arrow = enable;
+        self
+    }
+
+    /// Implement `rayon::iter::IntoParallelIterator` for the readers of vectors, whose items are
+    /// not bytes, so the items could be processed in parallel.
+    ///
+    /// The `rayon` feature of `molecule` should be enabled for the generated code.
+    pub fn rayon(&mut self, enable: bool) -> &mut Self {
+        self.options.rayon = enable;
+        self
+    }
+
+    /// Store the items of the vector builders in `SmallVec`s, so a vector which has no more than
+    /// `inline_capacity` items doesn't allocate.
+    ///
+    /// The `smallvec` feature of `molecule` should be enabled for the generated code.
+    pub fn small_vectors(&mut self, inline_capacity: usize) -> &mut Self {
+        self.options.small_vectors = Some(inline_capacity);
+        self
+    }
+
+    /// Generate `#[repr(transparent)]` readers over `[u8]`, such as `TableSlice`, besides the
+    /// readers which hold `&[u8]`.
+    ///
+    /// A reference to them could be converted from a byte slice by `TryFrom`, and they have the
+    /// same layout as `[u8]`.
+    pub fn transparent(&mut self, enable: bool) -> &mut Self {
+        self.options.transparent = enable;
+        self
+    }
+
+    /// Generate readers over a `molecule::source::Source`, such as `TableSourceReader`, which
+    /// read the parts of a value from the source only when they're accessed.
+    ///
+    /// The source could be a memory map, a file or a paged cache, so the value is not loaded
+    /// entirely.
+    pub fn source_readers(&mut self, enable: bool) -> &mut Self {
+        self.options.source_readers = enable;
+        self
+    }
+
+    /// Generate `from_slice_linear` for the readers, which verifies a value in a single forward
+    /// pass: each offset is read once, and the parts are verified in the order of their bytes.
+    ///
+    /// It's friendlier to the cache than `from_slice` for large values.
+    pub fn linear_verification(&mut self, enable: bool) -> &mut Self {
+        self.options.linear_verification = enable;
+        self
+    }
+
+    /// Generate a PyO3 class for each type, which wraps the entity, and a `#[pymodule]` which is
+    /// named after the schema, so the crate could be built by maturin.
+    ///
+    /// The crate which includes the generated code should depend on `pyo3`.
+    pub fn pyo3(&mut self, enable: bool) -> &mut Self {
+        self.options.pyo3 = enable;
+        self
+    }
+
+    /// Access the items of the generated Rust arrays only by the index, with `get(idx)` and
+    /// `set_nth(idx, item)`, instead of also generating `nthN` and `set_nthN` for each item.
+    ///
+    /// The code of large arrays is much smaller, but the indexes are checked at runtime.
+    pub fn compact_arrays(&mut self, enable: bool) -> &mut Self {
+        self.options.compact_arrays = enable;
+        self
+    }
+
+    /// Generate `{ Table }TypedBuilder` for each table, which is created by
+    /// `{ Table }::new_typed_builder()` and has a marker type for each field, so forgetting to set
+    /// a field is a compile error instead of encoding its default value.
+    ///
+    /// It's opt-in, since the setters of each field are generated for all states of the others.
+    pub fn typestate_builders(&mut self, enable: bool) -> &mut Self {
+        self.options.typestate_builders = enable;
+        self
+    }
+
+    /// Generate `eq_bytes` and `is_default` for the entities, and `eq_bytes`, `eq_slice`,
+    /// `is_default` and `DEFAULT_ENCODING` for the readers, which compare the encoded bytes
+    /// without building an entity.
+    ///
+    /// The entities also have `default_ref`, which returns the default value shared in a lazy
+    /// static.
+    pub fn byte_comparisons(&mut self, enable: bool) -> &mut Self {
+        self.options.byte_comparisons = enable;
+        self
+    }
+
+    /// Generate `validate` for the builders, which checks the fields against the schema, such as
+    /// the sizes, the required fields and the constraints, and returns all violations.
+    pub fn builder_validation(&mut self, enable: bool) -> &mut Self {
+        self.options.builder_validation = enable;
+        self
+    }
+
+    /// Generate `try_{ field }` for the structs and the tables, which verify the bytes of the
+    /// field before reading it, so an unchecked value of semi-trusted data returns an error
+    /// instead of panicking.
+    pub fn checked_getters(&mut self, enable: bool) -> &mut Self {
+        self.options.checked_getters = enable;
+        self
+    }
+
+    /// Generate `field_range` for the tables and `item_range` for the vectors, which return the
+    /// range of the bytes of a field or an item, so it could be sliced without decoding it.
+    ///
+    /// The tables also have `encode_with_zeroed_field`, which copies the bytes with a field
+    /// filled by zeros, such as the signature field of the message which is signed, and the
+    /// readers of them have `split_at_field` and `verify_fields`, so the two parts of a table
+    /// could be verified independently.
+    pub fn field_access(&mut self, enable: bool) -> &mut Self {
+        self.options.field_access = enable;
+        self
+    }
+
+    /// Generate `MAX_ENCODED_SIZE` for the entities and the readers of the bounded types, which
+    /// are fixed-size, or all the vectors in them have `@count_size`, so a buffer for them could
+    /// be sized at compile time.
+    pub fn max_encoded_size(&mut self, enable: bool) -> &mut Self {
+        self.options.max_encoded_size = enable;
+        self
+    }
+
+    /// Only export the type and the types it depends on.
+    ///
+    /// It could be called more than once; it's only used by the exporters, such as OpenAPI.
+    pub fn export_type<S: AsRef<str>>(&mut self, name: S) -> &mut Self {
+        self.options.types.push(name.as_ref().to_owned());
+        self
+    }
+
+    /// Set how to store the nested structures when exporting SQL tables.
+    pub fn flatten(&mut self, flatten: Flatten) -> &mut Self {
+        self.options.flatten = flatten;
+        self
+    }
+
+    /// Set the profile of the generated C code.
+    ///
+    /// The script profile is for on-chain scripts, such as CKB scripts on RISC-V: only the readers
+    /// are generated, they don't call libc functions or allocate variable-length arrays, and the
+    /// offsets of tables are checked with fewer branches.
+    pub fn c_profile(&mut self, profile: CProfile) -> &mut Self {
+        self.options.c_profile = profile;
+        self
+    }
+
+    /// Register the generated Wireshark dissector on the TCP port by default.
+    ///
+    /// The port could be changed in the preferences of the protocol in Wireshark.
+    pub fn dissector_port(&mut self, port: u16) -> &mut Self {
+        self.options.dissector_port = Some(port);
+        self
+    }
+
+    /// Register the generated Wireshark dissector as a heuristic dissector of TCP, which accepts
+    /// a segment if it's exactly one valid message.
+    pub fn dissector_heuristic(&mut self, heuristic: bool) -> &mut Self {
+        self.options.dissector_heuristic = heuristic;
+        self
+    }
+
+    /// Use the types of an imported schema from another crate, instead of from a sibling module.
+    ///
+    /// The schema is specified by the name in the `import` statement, without the directories;
+    /// the path is the Rust path of the module which includes the generated code of the schema,
+    /// such as `common_types::packed`. It's only used by the Rust generator.
+    pub fn extern_schema<S: AsRef<str>, P: AsRef<str>>(&mut self, name: S, path: P) -> &mut Self {
+        self.options
+            .extern_schemas
+            .insert(name.as_ref().to_owned(), path.as_ref().to_owned());
+        self
+    }
+
+    /// Set the visibility of the types, the builders and the constants in the generated Rust code.
+    ///
+    /// The methods are still `pub`, but they are not reachable outside the visibility of the types.
+    pub fn visibility(&mut self, visibility: Visibility) -> &mut Self {
+        self.options.visibility = visibility;
+        self
+    }
+
+    /// Set the edition of the crate which includes the generated Rust code.
+    ///
+    /// The reserved identifiers of the edition are escaped as raw identifiers.
+    pub fn rust_edition(&mut self, edition: Edition) -> &mut Self {
+        self.options.edition = edition;
+        self
+    }
+
+    /// Allow a lint on each item of the generated Rust code, such as `clippy::all`.
+    ///
+    /// The code could be included into a module by `include!`, which doesn't accept inner
+    /// attributes, so the lints are allowed on the items.
+    pub fn rust_allow<S: AsRef<str>>(&mut self, lint: S) -> &mut Self {
+        self.options.allows.push(lint.as_ref().to_owned());
+        self
+    }
+
+    /// Format the generated Rust code, one statement per line.
+    pub fn rust_format(&mut self, enable: bool) -> &mut Self {
+        self.options.format = enable;
+        self
+    }
+
+    /// Insert a banner at the top of the generated files, such as a license header.
+    ///
+    /// The banner is read from a template file, which supports the placeholders `{version}`,
+    /// `{schema}`, `{schema_hash}` and `{timestamp}`. It's written as line comments, or as the
+    /// `x-header` extension of an OpenAPI document.
+    pub fn header_file<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.header_file.replace(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Inject the code from the files of a directory into the generated code, such as extra
+    /// methods of the generated types, so they don't have to be wrapped.
+    ///
+    /// The files are named after the extension of the generated file: `header.<ext>` is injected
+    /// after the imports, and `types/<Name>.<ext>` after the code of the type, or at the end of a
+    /// C header. Only the C and the Rust generators support them; the Rust code is re-parsed if
+    /// it's restyled, so it should be valid Rust.
+    pub fn hooks_dir<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.hooks_dir.replace(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Render the generated code from the templates in a directory, which override the built-in
+    /// templates at the same paths, such as `imhex/table.hexpat`.
+    ///
+    /// The templates are a subset of Jinja, and they are rendered with a data model of the
+    /// schema. Only the ImHex generator is rendered from templates for now.
+    pub fn templates_dir<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.options
+            .templates_dir
+            .replace(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Guarantee the same output for the same inputs, on any platform and at any time.
+    ///
+    /// The output never depends on the platform, the locale or the order of hash maps; the only
+    /// varying input is the `{timestamp}` of the header, which requires `SOURCE_DATE_EPOCH` in
+    /// this mode.
+    pub fn reproducible(&mut self, enable: bool) -> &mut Self {
+        self.reproducible = enable;
+        self
+    }
+
+    /// Generate the code twice from scratch and panic if the outputs are different.
+    ///
+    /// It implies the reproducible mode.
+    pub fn verify_reproducible(&mut self, enable: bool) -> &mut Self {
+        self.verify_reproducible = enable;
+        if enable {
+            self.reproducible = true;
+        }
+        self
+    }
+
+    /// Check the schema with the lints before generating the code.
+    ///
+    /// The diagnostics are printed to the standard error, and it panics if any lint is denied.
+    pub fn lint_levels(&mut self, levels: LintLevels) -> &mut Self {
+        self.lint_levels.replace(levels);
+        self
+    }
+
+    /// Check the exported types, which are set by `export_type`.
+    ///
+    /// The exporters panic in `run` if a type is not defined or is not supported by them, so the
+    /// tools should call this first to report the error.
+    pub fn check_types(&self) -> Result<(), String> {
+        let lang = self.language.unwrap();
+        let generator = Generator::new(&self.file_path.as_ref().unwrap());
+        generator.check_types(lang, &self.options.types)
+    }
+
     pub fn run(&mut self) {
         let lang = self.language.unwrap();
 
+        if let Some(ref levels) = self.lint_levels {
+            let diagnostics = lint(self.file_path.as_ref().unwrap(), levels);
+            for diagnostic in &diagnostics {
+                eprintln!("{}", diagnostic);
+            }
+            let denied = diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.level == Level::Deny)
+                .count();
+            if denied > 0 {
+                panic!("aborting due to {} denied diagnostics", denied);
+            }
+        }
+
+        if self.verify_reproducible {
+            self.check_reproducible(lang);
+        }
+
+        self.options.header = self.render_header();
+        self.options.hooks = self.load_hooks(lang);
         let generator = Generator::new(&self.file_path.as_ref().unwrap());
 
         match self.output {
@@ -79,15 +407,66 @@ impl Compiler {
                     .open(&out_file)
                     .unwrap();
 
-                generator.generate(lang, &mut file_out).unwrap();
+                generator
+                    .generate(lang, &mut file_out, &self.options)
+                    .unwrap();
                 file_out.flush().unwrap();
             }
             Output::Stdout => {
                 let stdout = io::stdout();
                 let mut stdout_handle = stdout.lock();
-                generator.generate(lang, &mut stdout_handle).unwrap();
+                generator
+                    .generate(lang, &mut stdout_handle, &self.options)
+                    .unwrap();
                 stdout_handle.flush().unwrap();
             }
         }
     }
+
+    fn render_header(&self) -> Option<String> {
+        let header_file = self.header_file.as_ref()?;
+        let template = fs::read_to_string(header_file).unwrap_or_else(|err| {
+            panic!(
+                "failed to read the header file [{}]: {}",
+                header_file.display(),
+                err
+            )
+        });
+        let schema_file = self.file_path.as_ref().unwrap();
+        Some(header::render(&template, schema_file, self.reproducible))
+    }
+
+    fn load_hooks(&self, lang: Language) -> Hooks {
+        self.hooks_dir
+            .as_ref()
+            .map(|dir| Hooks::load(dir, lang.extension()))
+            .unwrap_or_default()
+    }
+
+    /// Parse the schema and generate the code twice, and compare the outputs.
+    fn check_reproducible(&self, lang: Language) {
+        let outputs: Vec<_> = (0..2)
+            .map(|_| {
+                let mut options = self.options.clone();
+                options.header = self.render_header();
+                options.hooks = self.load_hooks(lang);
+                let mut output = Vec::new();
+                Generator::new(&self.file_path.as_ref().unwrap())
+                    .generate(lang, &mut output, &options)
+                    .unwrap();
+                output
+            })
+            .collect();
+        if outputs[0] != outputs[1] {
+            let line = outputs[0]
+                .split(|byte| *byte == b'\n')
+                .zip(outputs[1].split(|byte| *byte == b'\n'))
+                .position(|(first, second)| first != second)
+                .unwrap_or_else(|| outputs[0].split(|byte| *byte == b'\n').count());
+            panic!(
+                "the generated code is not reproducible, the outputs differ at line {}",
+                line + 1
+            );
+        }
+    }
 }
@@ -0,0 +1,88 @@
+//! Machine-readable diagnostics, as the JSON messages of cargo, one message per line.
+
+use std::{fmt, path::Path};
+
+use serde_json::json;
+
+use crate::Parser;
+
+/// A syntax error in a schema file or in an imported file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub file_name: String,
+    pub line: usize,
+    pub column: usize,
+    /// The message without the location, such as `expected field_decl`.
+    pub message: String,
+    /// The message with the location and the source line.
+    pub rendered: String,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.rendered)
+    }
+}
+
+impl SyntaxError {
+    pub fn json_message(&self) -> String {
+        let span = Span {
+            file_name: &self.file_name,
+            line: self.line,
+            column: self.column,
+        };
+        compiler_message("error", None, &self.message, Some(span), &self.rendered)
+    }
+}
+
+/// Parse a schema file and the imported files, and return all the syntax errors.
+pub fn check_syntax<P: AsRef<Path>>(path: P) -> Vec<SyntaxError> {
+    Parser::check(&path)
+}
+
+/// The last message, as cargo emits after a build.
+pub fn build_finished_message(success: bool) -> String {
+    json!({ "reason": "build-finished", "success": success }).to_string()
+}
+
+pub(crate) struct Span<'a> {
+    pub(crate) file_name: &'a str,
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+pub(crate) fn compiler_message(
+    level: &str,
+    code: Option<&str>,
+    message: &str,
+    span: Option<Span>,
+    rendered: &str,
+) -> String {
+    let spans: Vec<_> = span
+        .into_iter()
+        .map(|span| {
+            json!({
+                "file_name": span.file_name,
+                "line_start": span.line,
+                "line_end": span.line,
+                "column_start": span.column,
+                "column_end": span.column,
+                "is_primary": true,
+                "label": null,
+            })
+        })
+        .collect();
+    let code = code.map(|code| json!({ "code": code, "explanation": null }));
+    json!({
+        "reason": "compiler-message",
+        "message": {
+            "message": message,
+            "code": code,
+            "level": level,
+            "spans": spans,
+            "children": [],
+            "rendered": format!("{}\n", rendered),
+        },
+    })
+    .to_string()
+}
@@ -0,0 +1,296 @@
+//! Support files of an editor extension, which are derived from the rules of the parser.
+//!
+//! The keywords, the comments and the attribute prefix are read from `grammar.pest`, so the
+//! highlighting can't drift from what the compiler accepts. A tree-sitter grammar is generated
+//! as well, for the editors which support it.
+
+use serde_json::{json, Value};
+
+use crate::{ast::verified as ast, tree_sitter, VERSION};
+
+const GRAMMAR: &str = include_str!("grammar.pest");
+
+const LANGUAGE_ID: &str = "molecule";
+const SCOPE_NAME: &str = "source.molecule";
+const FILE_EXTENSION: &str = ".mol";
+
+/// The syntax elements which are read from the grammar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Syntax {
+    /// The keywords of the declarations and the statements, in the order of the grammar.
+    pub keywords: Vec<String>,
+    pub line_comments: Vec<String>,
+    pub block_comment: (String, String),
+    pub attribute_prefix: String,
+}
+
+/// The files of a VS Code extension and a tree-sitter grammar, as pairs of the relative paths and
+/// the contents.
+pub fn editor_files() -> Vec<(&'static str, String)> {
+    let syntax = syntax();
+    vec![
+        ("package.json", pretty(&package_json())),
+        (
+            "language-configuration.json",
+            pretty(&language_configuration(&syntax)),
+        ),
+        (
+            "syntaxes/molecule.tmLanguage.json",
+            pretty(&textmate_grammar(&syntax)),
+        ),
+        (
+            "snippets/molecule.code-snippets",
+            pretty(&snippets(&syntax)),
+        ),
+        ("tree-sitter/grammar.js", tree_sitter::grammar_js(&syntax)),
+        (
+            "tree-sitter/queries/highlights.scm",
+            tree_sitter::highlights_scm(&syntax),
+        ),
+    ]
+}
+
+/// Read the syntax elements from the rules of the parser.
+pub fn syntax() -> Syntax {
+    let rules = rules();
+    let keywords = rules
+        .iter()
+        .filter(|(name, _)| name.ends_with("_decl") || name.ends_with("_stmt"))
+        .flat_map(|(_, body)| literals(body))
+        .filter(|x| x.chars().all(|c| c.is_ascii_lowercase()))
+        .collect();
+    let rule = |name: &str| {
+        rules
+            .iter()
+            .find(|(rule, _)| *rule == name)
+            .map(|(_, body)| literals(body))
+            .unwrap_or_else(|| panic!("the rule `{}` is not in the grammar", name))
+    };
+    let line_comments = rule("line_comment");
+    let block_comment = rule("block_comment");
+    let attribute = rule("attribute");
+    Syntax {
+        keywords,
+        line_comments,
+        block_comment: (
+            block_comment.first().unwrap().to_owned(),
+            block_comment.last().unwrap().to_owned(),
+        ),
+        attribute_prefix: attribute.first().unwrap().to_owned(),
+    }
+}
+
+/// Split the grammar into the names and the bodies of the rules.
+///
+/// A rule starts at a line which is not indented, and lasts until the next rule.
+pub(crate) fn rules() -> Vec<(&'static str, &'static str)> {
+    let mut starts = Vec::new();
+    let mut offset = 0;
+    for line in GRAMMAR.split('\n') {
+        if !line.starts_with(char::is_whitespace) {
+            if let Some(eq) = line.find('=') {
+                starts.push((line[..eq].trim(), offset, offset + eq + 1));
+            }
+        }
+        offset += line.len() + 1;
+    }
+    let ends = starts
+        .iter()
+        .skip(1)
+        .map(|(_, line_start, _)| *line_start)
+        .chain(Some(GRAMMAR.len()));
+    starts
+        .iter()
+        .zip(ends)
+        .map(|((name, _, start), end)| (*name, &GRAMMAR[*start..end]))
+        .collect()
+}
+
+/// The string literals in the body of a rule, escapes are not supported.
+fn literals(body: &str) -> Vec<String> {
+    body.split('"')
+        .skip(1)
+        .step_by(2)
+        .filter(|x| !x.contains('\\'))
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+fn package_json() -> Value {
+    json!({
+        "name": "molecule",
+        "displayName": "Molecule",
+        "description": "Syntax highlighting and snippets for molecule schema files.",
+        "version": VERSION,
+        "engines": { "vscode": "^1.40.0" },
+        "categories": ["Programming Languages", "Snippets"],
+        "contributes": {
+            "languages": [{
+                "id": LANGUAGE_ID,
+                "aliases": ["Molecule", LANGUAGE_ID],
+                "extensions": [FILE_EXTENSION],
+                "configuration": "./language-configuration.json",
+            }],
+            "grammars": [{
+                "language": LANGUAGE_ID,
+                "scopeName": SCOPE_NAME,
+                "path": "./syntaxes/molecule.tmLanguage.json",
+            }],
+            "snippets": [{
+                "language": LANGUAGE_ID,
+                "path": "./snippets/molecule.code-snippets",
+            }],
+        },
+    })
+}
+
+fn language_configuration(syntax: &Syntax) -> Value {
+    let brackets = [["{", "}"], ["[", "]"], ["<", ">"], ["(", ")"]];
+    let pairs: Vec<_> = brackets
+        .iter()
+        .map(|[open, close]| json!({ "open": open, "close": close }))
+        .collect();
+    json!({
+        "comments": {
+            "lineComment": syntax.line_comments[0],
+            "blockComment": [syntax.block_comment.0, syntax.block_comment.1],
+        },
+        "brackets": brackets,
+        "autoClosingPairs": pairs,
+        "surroundingPairs": pairs,
+    })
+}
+
+/// Escape a literal for the regular expressions of TextMate.
+fn escape(literal: &str) -> String {
+    literal
+        .chars()
+        .flat_map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                vec![c]
+            } else {
+                vec!['\\', c]
+            }
+        })
+        .collect()
+}
+
+fn alternatives(literals: &[String]) -> String {
+    literals
+        .iter()
+        .map(|x| escape(x))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+fn textmate_grammar(syntax: &Syntax) -> Value {
+    let (open, close) = &syntax.block_comment;
+    json!({
+        "$schema": "https://raw.githubusercontent.com/martinring/tmlanguage/master/tmlanguage.json",
+        "name": "Molecule",
+        "scopeName": SCOPE_NAME,
+        "fileTypes": [FILE_EXTENSION.trim_start_matches('.')],
+        "patterns": [
+            { "include": "#comments" },
+            { "include": "#attributes" },
+            { "include": "#keywords" },
+            { "include": "#types" },
+            { "include": "#numbers" },
+            { "include": "#punctuation" },
+        ],
+        "repository": {
+            "comments": {
+                "patterns": [
+                    {
+                        "name": "comment.block.molecule",
+                        "begin": escape(open),
+                        "end": escape(close),
+                        "patterns": [{ "include": "#comments" }],
+                    },
+                    {
+                        "name": "comment.line.molecule",
+                        "match": format!("(?:{}).*$", alternatives(&syntax.line_comments)),
+                    },
+                ],
+            },
+            "attributes": {
+                "match": format!(
+                    "({})([A-Za-z][A-Za-z0-9_]*)",
+                    escape(&syntax.attribute_prefix)
+                ),
+                "captures": {
+                    "1": { "name": "punctuation.definition.annotation.molecule" },
+                    "2": { "name": "storage.type.annotation.molecule" },
+                },
+            },
+            "keywords": {
+                "patterns": [{
+                    "match": format!(
+                        "\\b({})\\s+([A-Za-z][A-Za-z0-9_]*)",
+                        alternatives(&syntax.keywords)
+                    ),
+                    "captures": {
+                        "1": { "name": "keyword.other.molecule" },
+                        "2": { "name": "entity.name.type.molecule" },
+                    },
+                }],
+            },
+            "types": {
+                "patterns": [
+                    {
+                        "name": "support.type.primitive.molecule",
+                        "match": format!("\\b{}\\b", ast::ATOM_NAME),
+                    },
+                    {
+                        "name": "entity.name.type.molecule",
+                        "match": "\\b[A-Za-z][A-Za-z0-9_]*\\b",
+                    },
+                ],
+            },
+            "numbers": {
+                "name": "constant.numeric.decimal.molecule",
+                "match": "\\b[1-9][0-9]*\\b",
+            },
+            "punctuation": {
+                "patterns": [
+                    { "name": "punctuation.separator.molecule", "match": "[,:]" },
+                    { "name": "punctuation.terminator.molecule", "match": ";" },
+                ],
+            },
+        },
+    })
+}
+
+/// The body of the snippet for a keyword.
+fn snippet_body(keyword: &str) -> Vec<&'static str> {
+    match keyword {
+        "import" => vec!["import ${1:path};"],
+        "array" => vec!["array ${1:Name} [${2:byte}; ${3:32}];"],
+        "struct" => vec!["struct ${1:Name} {", "\t${2:field}: ${3:byte},", "}"],
+        "vector" => vec!["vector ${1:Name} <${2:byte}>;"],
+        "table" => vec!["table ${1:Name} {", "\t${2:field}: ${3:Bytes},", "}"],
+        "option" => vec!["option ${1:Name} (${2:Bytes});"],
+        "union" => vec!["union ${1:Name} {", "\t${2:Bytes},", "}"],
+        "alias" => vec!["alias ${1:Name} = ${2:Bytes};"],
+        _ => panic!("the keyword `{}` doesn't have a snippet", keyword),
+    }
+}
+
+fn snippets(syntax: &Syntax) -> Value {
+    let mut snippets = serde_json::Map::new();
+    for keyword in &syntax.keywords {
+        let snippet = json!({
+            "prefix": keyword,
+            "body": snippet_body(keyword),
+            "description": format!("Declare `{}`", keyword),
+        });
+        snippets.insert(keyword.to_owned(), snippet);
+    }
+    Value::Object(snippets)
+}
+
+fn pretty(value: &Value) -> String {
+    let mut content = serde_json::to_string_pretty(value).unwrap();
+    content.push('\n');
+    content
+}
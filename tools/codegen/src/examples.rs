@@ -0,0 +1,322 @@
+use std::{
+    convert::TryFrom,
+    fs, io,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use case::CaseExt;
+use molecule::{pack_count, pack_number, Number, NUMBER_SIZE};
+
+use crate::{
+    ast::verified::{self as ast, DefaultContent, HasName},
+    generator::generate_examples,
+    Parser,
+};
+
+/// The count of the items in the vectors of the samples.
+const SAMPLE_ITEM_COUNT: usize = 2;
+/// The words in the names of the fields which are text, such as `display_name`.
+const TEXT_HINTS: &[&str] = &[
+    "name",
+    "title",
+    "label",
+    "text",
+    "description",
+    "message",
+    "memo",
+    "symbol",
+    "url",
+    "uri",
+];
+/// The words in the names of the fields which are digests or identifiers, such as `code_hash`.
+const DIGEST_HINTS: &[&str] = &[
+    "hash",
+    "id",
+    "key",
+    "address",
+    "root",
+    "digest",
+    "signature",
+];
+
+/// The languages of the example snippets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExampleLanguage {
+    Rust,
+    /// The API generated by `moleculec-go`.
+    Go,
+}
+
+impl TryFrom<&str> for ExampleLanguage {
+    type Error = String;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "rust" => Ok(ExampleLanguage::Rust),
+            "go" => Ok(ExampleLanguage::Go),
+            lang => Err(format!("unsupport language: [{}]", lang)),
+        }
+    }
+}
+
+/// Generate an example snippet for each type of a schema, which builds a value with sample data
+/// and parses it.
+pub struct Examples {
+    language: ExampleLanguage,
+    file_path: Option<PathBuf>,
+    out_dir: PathBuf,
+    module: Option<String>,
+}
+
+impl Default for Examples {
+    fn default() -> Self {
+        Examples::new()
+    }
+}
+
+impl Examples {
+    pub fn new() -> Self {
+        Self {
+            language: ExampleLanguage::Rust,
+            file_path: None,
+            out_dir: PathBuf::from("examples"),
+            module: None,
+        }
+    }
+
+    pub fn language(&mut self, language: ExampleLanguage) -> &mut Self {
+        self.language = language;
+        self
+    }
+
+    pub fn file_path<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.file_path.replace(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// The directory of the snippets, `examples` by default.
+    pub fn out_dir<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.out_dir = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// The module of the generated types.
+    ///
+    /// It's a Rust path, `crate` by default; or the import path of a Go package, and the Go
+    /// snippets are in the package `types` of the generated types by default.
+    pub fn module<S: AsRef<str>>(&mut self, module: S) -> &mut Self {
+        self.module.replace(module.as_ref().to_owned());
+        self
+    }
+
+    /// Write the snippets into the directory, one file for each type.
+    ///
+    /// Besides the snippets, a Rust `mod.rs` declares the modules of them; the Go snippets share
+    /// the helpers in `helpers.go`, and `fuzz_test.go` has a fuzz target for each type, whose
+    /// seeds are the default value and the sample value in `testdata/fuzz`.
+    pub fn generate(&self) -> io::Result<()> {
+        let ast = Parser::parse(self.file_path.as_ref().unwrap());
+        let samples = ast
+            .major_decls()
+            .iter()
+            .map(|decl| Sample::new(decl, &decl.name().to_snake(), &mut 0))
+            .collect::<Vec<_>>();
+        fs::create_dir_all(&self.out_dir)?;
+        for (name, content) in generate_examples(self.language, &samples, self.module.as_deref()) {
+            let path = self.out_dir.join(name);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, content)?;
+        }
+        Ok(())
+    }
+}
+
+/// The file name of the snippet of a type, without the extension.
+pub(crate) fn file_stem(decl: &ast::TopDecl) -> String {
+    decl.name().to_snake()
+}
+
+/// A sample value of a type.
+#[derive(Debug)]
+pub(crate) struct Sample {
+    pub(crate) decl: Rc<ast::TopDecl>,
+    pub(crate) value: Value,
+}
+
+#[derive(Debug)]
+pub(crate) enum Value {
+    Byte(u8),
+    /// An array or a vector of bytes.
+    Bytes(Vec<u8>),
+    /// An array of bytes which has the well-known name of an unsigned integer, such as `Uint64`.
+    Uint(u128),
+    /// The fields of a struct or a table.
+    Fields(Vec<(String, Sample)>),
+    /// The items of an array or a vector.
+    Items(Vec<Sample>),
+    /// An option with an inner value.
+    Some(Box<Sample>),
+    /// A union with the first item.
+    Item(Box<Sample>),
+    /// A value of an imported type, which is not reachable in the snippet.
+    Default,
+}
+
+impl Sample {
+    /// The hint is the name of the field or the type, which is used to pick realistic bytes.
+    fn new(decl: &Rc<ast::TopDecl>, hint: &str, counter: &mut u8) -> Self {
+        let value = if !decl.is_atom() && decl.imported_depth() > 0 {
+            Value::Default
+        } else {
+            match decl.as_ref() {
+                ast::TopDecl::Atom(_) => {
+                    *counter = counter.wrapping_add(1);
+                    Value::Byte(*counter)
+                }
+                ast::TopDecl::Option_(ref typ) => {
+                    Value::Some(Box::new(Self::new(&typ.typ, hint, counter)))
+                }
+                ast::TopDecl::Union(ref typ) => {
+                    let item = &typ.inner[0].typ;
+                    let hint = item.name().to_snake();
+                    Value::Item(Box::new(Self::new(item, &hint, counter)))
+                }
+                ast::TopDecl::Array(ref typ) => {
+                    if typ.typ.is_atom() {
+                        if is_uint(typ) {
+                            Value::Uint(1000)
+                        } else {
+                            Value::Bytes(sample_bytes(hint, typ.item_count))
+                        }
+                    } else {
+                        let items = (0..typ.item_count)
+                            .map(|_| Self::new(&typ.typ, hint, counter))
+                            .collect();
+                        Value::Items(items)
+                    }
+                }
+                ast::TopDecl::Struct(ref typ) => Value::Fields(fields(&typ.inner, counter)),
+                ast::TopDecl::FixVec(ref typ) if typ.typ.is_atom() => {
+                    if has_hint(hint, TEXT_HINTS) {
+                        Value::Bytes(format!("example {}", hint.replace('_', " ")).into_bytes())
+                    } else {
+                        Value::Bytes(sample_bytes(hint, SAMPLE_ITEM_COUNT * 2))
+                    }
+                }
+                ast::TopDecl::FixVec(ref typ) => Value::Items(items(&typ.typ, hint, counter)),
+                ast::TopDecl::DynVec(ref typ) => Value::Items(items(&typ.typ, hint, counter)),
+                ast::TopDecl::Table(ref typ) => Value::Fields(fields(&typ.inner, counter)),
+            }
+        };
+        Self {
+            decl: Rc::clone(decl),
+            value,
+        }
+    }
+
+    /// The bytes of the sample value, which are the same as the snippets build.
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        match self.value {
+            Value::Byte(byte) => vec![byte],
+            Value::Bytes(ref data) => match self.decl.as_ref() {
+                ast::TopDecl::FixVec(ref typ) => fixvec_content(typ, data.len(), data),
+                _ => data.clone(),
+            },
+            Value::Uint(number) => {
+                let size = self.decl.total_size().unwrap();
+                number.to_le_bytes()[..size].to_vec()
+            }
+            Value::Fields(ref fields) => {
+                let parts = fields
+                    .iter()
+                    .map(|(_, sample)| sample.encode())
+                    .collect::<Vec<_>>();
+                match self.decl.as_ref() {
+                    ast::TopDecl::Table(_) => dynamic_content(&parts),
+                    _ => parts.concat(),
+                }
+            }
+            Value::Items(ref items) => {
+                let parts = items.iter().map(Sample::encode).collect::<Vec<_>>();
+                match self.decl.as_ref() {
+                    ast::TopDecl::FixVec(ref typ) => {
+                        fixvec_content(typ, parts.len(), &parts.concat())
+                    }
+                    ast::TopDecl::DynVec(_) => dynamic_content(&parts),
+                    _ => parts.concat(),
+                }
+            }
+            Value::Some(ref inner) => inner.encode(),
+            Value::Item(ref item) => {
+                // The sample of a union is always the first item.
+                let mut content = pack_number(0).to_vec();
+                content.extend_from_slice(&item.encode());
+                content
+            }
+            Value::Default => self.decl.default_content(),
+        }
+    }
+}
+
+fn fixvec_content(typ: &ast::FixVec, count: usize, data: &[u8]) -> Vec<u8> {
+    let header = pack_count(count, typ.count_size).unwrap();
+    let mut content = header[..typ.count_size].to_vec();
+    content.extend_from_slice(data);
+    content
+}
+
+/// The content of a table or a dynamic vector, which starts with the total size and the offsets.
+fn dynamic_content(parts: &[Vec<u8>]) -> Vec<u8> {
+    let header_size = NUMBER_SIZE * (parts.len() + 1);
+    let total_size = header_size + parts.iter().map(Vec::len).sum::<usize>();
+    let mut content = Vec::with_capacity(total_size);
+    content.extend_from_slice(&pack_number(total_size as Number));
+    let mut offset = header_size;
+    for part in parts {
+        content.extend_from_slice(&pack_number(offset as Number));
+        offset += part.len();
+    }
+    for part in parts {
+        content.extend_from_slice(part);
+    }
+    content
+}
+
+fn fields(inner: &[ast::FieldDecl], counter: &mut u8) -> Vec<(String, Sample)> {
+    inner
+        .iter()
+        .map(|field| {
+            let sample = Sample::new(&field.typ, &field.name.to_snake(), counter);
+            (field.name.clone(), sample)
+        })
+        .collect()
+}
+
+fn items(typ: &Rc<ast::TopDecl>, hint: &str, counter: &mut u8) -> Vec<Sample> {
+    (0..SAMPLE_ITEM_COUNT)
+        .map(|_| Sample::new(typ, hint, counter))
+        .collect()
+}
+
+/// Whether it's an array of bytes which has the well-known name of an unsigned integer.
+pub(crate) fn is_uint(typ: &ast::Array) -> bool {
+    [2, 4, 8, 16].contains(&typ.item_count) && typ.name == format!("Uint{}", typ.item_count * 8)
+}
+
+fn has_hint(hint: &str, words: &[&str]) -> bool {
+    hint.split('_').any(|word| words.contains(&word))
+}
+
+/// The bytes look random for digests and identifiers, otherwise they're in order.
+fn sample_bytes(hint: &str, size: usize) -> Vec<u8> {
+    if has_hint(hint, DIGEST_HINTS) || size == 20 || size == 32 {
+        let seed = hint.bytes().fold(0u8, |seed, byte| seed.wrapping_add(byte));
+        (0..size)
+            .map(|idx| (idx as u8).wrapping_mul(167).wrapping_add(seed) ^ 0x5a)
+            .collect()
+    } else {
+        (1..=size).map(|idx| idx as u8).collect()
+    }
+}
@@ -0,0 +1,461 @@
+use std::fmt::Write as _;
+
+use molecule::{hex_string, pack_count, pack_number, unpack_count, unpack_number, NUMBER_SIZE};
+
+use crate::{
+    ast::verified::{self as ast, HasName},
+    schema_hash::canonical_decl,
+    stats,
+};
+
+/// The count of the items in the vectors of the example.
+const EXAMPLE_ITEM_COUNT: usize = 2;
+/// The count of the bytes in each line of the hex dump.
+const BYTES_PER_LINE: usize = 8;
+
+/// Explain the wire layout of a type, with an annotated example.
+pub(crate) fn explain(decl: &ast::TopDecl) -> String {
+    let mut text = String::new();
+    writeln!(text, "{}", canonical_decl(decl)).unwrap();
+    text.push('\n');
+    layout(decl, &mut text);
+    text.push('\n');
+    let stats = stats::type_stats(decl);
+    let max_size = stats
+        .max_size
+        .map_or_else(|| "unbounded".to_owned(), |size| format!("{} bytes", size));
+    writeln!(
+        text,
+        "Size: min {} bytes, max {}.",
+        stats.min_size, max_size
+    )
+    .unwrap();
+    text.push('\n');
+    let data = example(decl, &mut 0);
+    writeln!(
+        text,
+        "Example ({} bytes): 0x{}",
+        data.len(),
+        hex_string(&data)
+    )
+    .unwrap();
+    let mut lines = Vec::new();
+    annotate(decl, &data, 0, "", 0, &mut lines);
+    for line in &lines {
+        let bytes = &data[line.offset..line.offset + line.size];
+        let mut chunks = bytes.chunks(BYTES_PER_LINE);
+        let first = chunks.next().map(hex_bytes).unwrap_or_default();
+        let indent = "  ".repeat(line.depth);
+        writeln!(
+            text,
+            "  {:04x}  {:width$}  {}{}",
+            line.offset,
+            first,
+            indent,
+            line.text,
+            width = BYTES_PER_LINE * 3 - 1
+        )
+        .unwrap();
+        for (idx, chunk) in chunks.enumerate() {
+            let offset = line.offset + (idx + 1) * BYTES_PER_LINE;
+            writeln!(text, "  {:04x}  {}", offset, hex_bytes(chunk)).unwrap();
+        }
+    }
+    text.truncate(text.trim_end().len());
+    text
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn type_name(decl: &ast::TopDecl) -> &str {
+    if decl.is_atom() {
+        ast::ATOM_NAME
+    } else {
+        decl.name()
+    }
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, word)
+    } else {
+        format!("{} {}s", count, word)
+    }
+}
+
+/// Whether a type is fixed-size, and the size of it.
+fn classify(decl: &ast::TopDecl) -> String {
+    match decl.total_size() {
+        Some(size) => format!("fixed-size, {}", plural(size, "byte")),
+        None => "dynamic-size".to_owned(),
+    }
+}
+
+fn layout(decl: &ast::TopDecl, text: &mut String) {
+    match decl {
+        ast::TopDecl::Atom(ref typ) => {
+            writeln!(text, "A {} without any header.", plural(typ.size, "byte")).unwrap();
+        }
+        ast::TopDecl::Option_(ref typ) => {
+            writeln!(
+                text,
+                "An option without any header: it's empty for `None`, or the bytes of `{}` ({}) \
+                 for `Some`.",
+                type_name(&typ.typ),
+                classify(&typ.typ)
+            )
+            .unwrap();
+        }
+        ast::TopDecl::Union(ref typ) => {
+            writeln!(
+                text,
+                "A union: the header is the item id ({}), then the bytes of the item.",
+                plural(NUMBER_SIZE, "byte")
+            )
+            .unwrap();
+            writeln!(text, "Items:").unwrap();
+            for (id, item) in typ.inner.iter().enumerate() {
+                write!(
+                    text,
+                    "  id {}: {} ({})",
+                    id,
+                    type_name(&item.typ),
+                    classify(&item.typ)
+                )
+                .unwrap();
+                if let Some(ref alias) = item.alias {
+                    write!(text, ", alias `{}`", alias).unwrap();
+                }
+                text.push('\n');
+            }
+        }
+        ast::TopDecl::Array(ref typ) => {
+            writeln!(
+                text,
+                "A fixed-size array without any header: {} of `{}` ({} each), {} in total.",
+                plural(typ.item_count, "item"),
+                type_name(&typ.typ),
+                plural(typ.item_size, "byte"),
+                plural(typ.total_size(), "byte")
+            )
+            .unwrap();
+        }
+        ast::TopDecl::Struct(ref typ) => {
+            writeln!(
+                text,
+                "A fixed-size struct without any header: the fields are packed in order, {} in \
+                 total.",
+                plural(typ.total_size(), "byte")
+            )
+            .unwrap();
+            writeln!(text, "Fields:").unwrap();
+            let mut offset = 0;
+            for (field, size) in typ.inner.iter().zip(&typ.field_size) {
+                writeln!(
+                    text,
+                    "  offset {}: {}: {} ({})",
+                    offset,
+                    field.name,
+                    type_name(&field.typ),
+                    plural(*size, "byte")
+                )
+                .unwrap();
+                offset += size;
+            }
+        }
+        ast::TopDecl::FixVec(ref typ) => {
+            writeln!(
+                text,
+                "A vector of fixed-size items: the header is the item count ({}), then the items \
+                 of `{}` ({} each) are packed in order.",
+                plural(typ.count_size, "byte"),
+                type_name(&typ.typ),
+                plural(typ.item_size, "byte")
+            )
+            .unwrap();
+            if !typ.is_standard() {
+                writeln!(
+                    text,
+                    "The size of the item count is set by `@count_size`, which is not a standard \
+                     molecule encoding."
+                )
+                .unwrap();
+            }
+        }
+        ast::TopDecl::DynVec(ref typ) => {
+            writeln!(
+                text,
+                "A vector of dynamic-size items: the header is the total size and the offset of \
+                 each item ({} each), then the items of `{}` in order.",
+                plural(NUMBER_SIZE, "byte"),
+                type_name(&typ.typ)
+            )
+            .unwrap();
+        }
+        ast::TopDecl::Table(ref typ) => {
+            writeln!(
+                text,
+                "A table: the header is the total size and the offset of each field ({} in \
+                 total), then the fields in order.",
+                plural(NUMBER_SIZE * (typ.inner.len() + 1), "byte")
+            )
+            .unwrap();
+            writeln!(
+                text,
+                "The field count is the first offset divided by {}, minus one; in the compatible \
+                 mode, the extra fields are ignored.",
+                NUMBER_SIZE
+            )
+            .unwrap();
+            writeln!(text, "Fields:").unwrap();
+            for (idx, field) in typ.inner.iter().enumerate() {
+                writeln!(
+                    text,
+                    "  {}: {}: {} ({})",
+                    idx,
+                    field.name,
+                    type_name(&field.typ),
+                    classify(&field.typ)
+                )
+                .unwrap();
+            }
+        }
+    }
+    let has_header = matches!(
+        decl,
+        ast::TopDecl::Union(_)
+            | ast::TopDecl::FixVec(_)
+            | ast::TopDecl::DynVec(_)
+            | ast::TopDecl::Table(_)
+    );
+    if has_header {
+        writeln!(
+            text,
+            "The numbers in the headers are little-endian unsigned integers."
+        )
+        .unwrap();
+    }
+}
+
+/// Build an example value, the bytes are numbered to be distinguished from the headers.
+fn example(decl: &ast::TopDecl, counter: &mut u8) -> Vec<u8> {
+    match decl {
+        ast::TopDecl::Atom(ref typ) => (0..typ.size)
+            .map(|_| {
+                *counter = counter.wrapping_add(1);
+                *counter
+            })
+            .collect(),
+        ast::TopDecl::Option_(ref typ) => example(&typ.typ, counter),
+        ast::TopDecl::Union(ref typ) => {
+            let mut data = pack_number(0).to_vec();
+            data.extend(example(&typ.inner[0].typ, counter));
+            data
+        }
+        ast::TopDecl::Array(ref typ) => (0..typ.item_count)
+            .flat_map(|_| example(&typ.typ, counter))
+            .collect(),
+        ast::TopDecl::Struct(ref typ) => typ
+            .inner
+            .iter()
+            .flat_map(|field| example(&field.typ, counter))
+            .collect(),
+        ast::TopDecl::FixVec(ref typ) => {
+            let mut data =
+                pack_count(EXAMPLE_ITEM_COUNT, typ.count_size).unwrap()[..typ.count_size].to_vec();
+            for _ in 0..EXAMPLE_ITEM_COUNT {
+                data.extend(example(&typ.typ, counter));
+            }
+            data
+        }
+        ast::TopDecl::DynVec(ref typ) => {
+            let items = (0..EXAMPLE_ITEM_COUNT)
+                .map(|_| example(&typ.typ, counter))
+                .collect::<Vec<_>>();
+            with_offsets(items)
+        }
+        ast::TopDecl::Table(ref typ) => {
+            let fields = typ
+                .inner
+                .iter()
+                .map(|field| example(&field.typ, counter))
+                .collect::<Vec<_>>();
+            with_offsets(fields)
+        }
+    }
+}
+
+/// Prepend the header of the total size and the offsets.
+fn with_offsets(items: Vec<Vec<u8>>) -> Vec<u8> {
+    let header_size = NUMBER_SIZE * (items.len() + 1);
+    let total_size = header_size + items.iter().map(Vec::len).sum::<usize>();
+    let mut data = pack_number(total_size as molecule::Number).to_vec();
+    let mut offset = header_size;
+    for item in &items {
+        data.extend_from_slice(&pack_number(offset as molecule::Number));
+        offset += item.len();
+    }
+    for item in items {
+        data.extend(item);
+    }
+    data
+}
+
+struct Line {
+    offset: usize,
+    size: usize,
+    depth: usize,
+    text: String,
+}
+
+fn line(offset: usize, size: usize, depth: usize, text: String) -> Line {
+    Line {
+        offset,
+        size,
+        depth,
+        text,
+    }
+}
+
+/// Annotate the parts of an example value; the fixed-size values which are nested are not split.
+fn annotate(
+    decl: &ast::TopDecl,
+    slice: &[u8],
+    offset: usize,
+    label: &str,
+    depth: usize,
+    lines: &mut Vec<Line>,
+) {
+    let title = if depth == 0 {
+        type_name(decl).to_owned()
+    } else {
+        format!("{}: {}", label, type_name(decl))
+    };
+    if decl.is_atom() || (depth > 0 && decl.total_size().is_some()) {
+        lines.push(line(offset, slice.len(), depth, title));
+        return;
+    }
+    let header = |lines: &mut Vec<Line>| lines.push(line(offset, 0, depth, title.clone()));
+    let depth = depth + 1;
+    match decl {
+        ast::TopDecl::Atom(_) => unreachable!(),
+        ast::TopDecl::Option_(ref typ) => {
+            header(lines);
+            annotate(&typ.typ, slice, offset, "Some", depth, lines);
+        }
+        ast::TopDecl::Union(ref typ) => {
+            header(lines);
+            let id = unpack_number(slice) as usize;
+            let item = &typ.inner[id];
+            let text = format!("item id: {}", id);
+            lines.push(line(offset, NUMBER_SIZE, depth, text));
+            let slice = &slice[NUMBER_SIZE..];
+            annotate(&item.typ, slice, offset + NUMBER_SIZE, "item", depth, lines);
+        }
+        ast::TopDecl::Array(ref typ) => {
+            header(lines);
+            for (idx, item) in slice.chunks(typ.item_size).enumerate() {
+                let text = format!("item {}: {}", idx, type_name(&typ.typ));
+                lines.push(line(offset + idx * typ.item_size, item.len(), depth, text));
+            }
+        }
+        ast::TopDecl::Struct(ref typ) => {
+            header(lines);
+            let mut start = 0;
+            for (field, size) in typ.inner.iter().zip(&typ.field_size) {
+                let text = format!("{}: {}", field.name, type_name(&field.typ));
+                lines.push(line(offset + start, *size, depth, text));
+                start += size;
+            }
+        }
+        ast::TopDecl::FixVec(ref typ) => {
+            header(lines);
+            let count = unpack_count(slice, typ.count_size);
+            let text = format!("item count: {}", count);
+            lines.push(line(offset, typ.count_size, depth, text));
+            let start = offset + typ.count_size;
+            let items = &slice[typ.count_size..];
+            if typ.typ.is_atom() {
+                lines.push(line(start, items.len(), depth, "items: byte".to_owned()));
+            } else {
+                for (idx, item) in items.chunks(typ.item_size).enumerate() {
+                    let text = format!("item {}: {}", idx, type_name(&typ.typ));
+                    lines.push(line(start + idx * typ.item_size, item.len(), depth, text));
+                }
+            }
+        }
+        ast::TopDecl::DynVec(ref typ) => {
+            header(lines);
+            let labels = (0..)
+                .map(|idx| (format!("item {}", idx), typ.typ.as_ref()))
+                .take(offset_count(slice));
+            annotate_offsets(slice, offset, depth, labels, lines);
+        }
+        ast::TopDecl::Table(ref typ) => {
+            header(lines);
+            let labels = typ
+                .inner
+                .iter()
+                .map(|field| (field.name.clone(), field.typ.as_ref()));
+            annotate_offsets(slice, offset, depth, labels, lines);
+        }
+    }
+}
+
+fn offset_count(slice: &[u8]) -> usize {
+    if slice.len() == NUMBER_SIZE {
+        0
+    } else {
+        unpack_number(&slice[NUMBER_SIZE..]) as usize / NUMBER_SIZE - 1
+    }
+}
+
+/// Annotate the header of a DynVec or a Table, and the items after it.
+fn annotate_offsets<'a, I>(
+    slice: &[u8],
+    offset: usize,
+    depth: usize,
+    labels: I,
+    lines: &mut Vec<Line>,
+) where
+    I: Iterator<Item = (String, &'a ast::TopDecl)>,
+{
+    let text = format!("total size: {}", unpack_number(slice));
+    lines.push(line(offset, NUMBER_SIZE, depth, text));
+    let labels = labels.collect::<Vec<_>>();
+    let offsets = (0..labels.len())
+        .map(|idx| unpack_number(&slice[NUMBER_SIZE * (idx + 1)..]) as usize)
+        .chain(Some(slice.len()))
+        .collect::<Vec<_>>();
+    for (idx, (label, _)) in labels.iter().enumerate() {
+        let text = format!("offset of {}: {}", label, offsets[idx]);
+        lines.push(line(
+            offset + NUMBER_SIZE * (idx + 1),
+            NUMBER_SIZE,
+            depth,
+            text,
+        ));
+    }
+    for (idx, (label, decl)) in labels.iter().enumerate() {
+        let (start, end) = (offsets[idx], offsets[idx + 1]);
+        if start == end {
+            let text = format!("{}: {} (empty)", label, type_name(decl));
+            lines.push(line(offset + start, 0, depth, text));
+        } else {
+            annotate(
+                decl,
+                &slice[start..end],
+                offset + start,
+                label,
+                depth,
+                lines,
+            );
+        }
+    }
+}
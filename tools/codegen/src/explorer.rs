@@ -0,0 +1,310 @@
+use std::{fmt::Write as _, path::Path, rc::Rc};
+
+use molecule::{
+    deep_size::SizeBreakdown, display::Formatted, hex_string, logical::UuidDisplay, unpack_count,
+    unpack_number, NUMBER_SIZE,
+};
+
+use crate::{
+    ast::verified::{self as ast, HasName},
+    explain,
+    salvage::{self, PartialValue},
+    schema_hash::canonical_decl,
+    stats::{self, TypeStats},
+    verifier, Ast, Parser,
+};
+
+/// Explore the types of a schema file without generating code, such as in a REPL.
+pub struct Explorer {
+    ast: Ast,
+}
+
+impl Explorer {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            ast: Parser::parse(&path),
+        }
+    }
+
+    /// The names of all types, including the imported ones.
+    pub fn type_names(&self) -> Vec<String> {
+        self.ast
+            .decls()
+            .iter()
+            .map(|decl| decl.name().to_owned())
+            .collect()
+    }
+
+    fn decl(&self, name: &str) -> Result<Rc<ast::TopDecl>, String> {
+        self.ast
+            .get_decl(name)
+            .ok_or_else(|| format!("the type `{}` is not found", name))
+    }
+
+    /// The declaration of a type, in one line.
+    pub fn describe(&self, name: &str) -> Result<String, String> {
+        self.decl(name).map(|decl| canonical_decl(&decl))
+    }
+
+    pub fn stats(&self, name: &str) -> Result<TypeStats, String> {
+        self.decl(name).map(|decl| stats::type_stats(&decl))
+    }
+
+    /// The wire layout of a type, with an annotated example.
+    pub fn explain(&self, name: &str) -> Result<String, String> {
+        self.decl(name).map(|decl| explain::explain(&decl))
+    }
+
+    /// Verify the data, and format it as the `Display` of the generated Rust entities.
+    pub fn decode(&self, name: &str, data: &[u8], compatible: bool) -> Result<String, String> {
+        let decl = self.decl(name)?;
+        verifier::verify(&decl, data, compatible).map_err(|err| err.to_string())?;
+        let mut text = String::new();
+        display(&decl, data, &mut text);
+        Ok(text)
+    }
+
+    /// Decode as much of the data as possible, instead of failing at the first error, such as for
+    /// the forensic analysis of a damaged buffer.
+    ///
+    /// Return an error only if the type is not found.
+    pub fn salvage(
+        &self,
+        name: &str,
+        data: &[u8],
+        compatible: bool,
+    ) -> Result<PartialValue, String> {
+        let decl = self.decl(name)?;
+        Ok(salvage::salvage(&decl, 0, data, compatible, None))
+    }
+
+    /// Verify the data, and split its bytes into the headers and the payload by the fields, as
+    /// `deep_size_breakdown` of the generated Rust readers.
+    pub fn size_breakdown(
+        &self,
+        name: &str,
+        data: &[u8],
+        compatible: bool,
+    ) -> Result<SizeBreakdown, String> {
+        let decl = self.decl(name)?;
+        verifier::verify(&decl, data, compatible).map_err(|err| err.to_string())?;
+        Ok(size_breakdown(&decl, data))
+    }
+}
+
+fn display_name(decl: &ast::TopDecl) -> &str {
+    if decl.is_atom() {
+        ast::ATOM_PRIMITIVE_NAME
+    } else {
+        decl.name()
+    }
+}
+
+fn display_list<'a, I>(name: &str, items: I, text: &mut String)
+where
+    I: Iterator<Item = (&'a ast::TopDecl, &'a [u8])>,
+{
+    write!(text, "{} [", name).unwrap();
+    for (i, (decl, slice)) in items.enumerate() {
+        if i != 0 {
+            text.push_str(", ");
+        }
+        display(decl, slice, text);
+    }
+    text.push(']');
+}
+
+/// The slices of the items of a verified DynVec or Table, including the extra fields.
+fn split_offsets(slice: &[u8]) -> Vec<&[u8]> {
+    if slice.len() == NUMBER_SIZE {
+        return Vec::new();
+    }
+    let count = unpack_number(&slice[NUMBER_SIZE..]) as usize / NUMBER_SIZE - 1;
+    let offset_at = |idx: usize| {
+        if idx == count {
+            slice.len()
+        } else {
+            unpack_number(&slice[NUMBER_SIZE * (idx + 1)..]) as usize
+        }
+    };
+    (0..count)
+        .map(|idx| &slice[offset_at(idx)..offset_at(idx + 1)])
+        .collect()
+}
+
+fn display_field(field: &ast::FieldDecl, slice: &[u8], text: &mut String) {
+    write!(text, "{}: ", field.name).unwrap();
+    display_field_value(field, slice, text);
+}
+
+/// The field is displayed by its formatter which is set by `@display`, if any.
+pub(crate) fn display_field_value(field: &ast::FieldDecl, slice: &[u8], text: &mut String) {
+    if let Some(ref formatter) = field.display {
+        let data = &slice[field.typ.raw_bytes_offset().unwrap()..];
+        write!(text, "{}", Formatted { formatter, data }).unwrap();
+    } else {
+        display(&field.typ, slice, text);
+    }
+}
+
+pub(crate) fn display(decl: &ast::TopDecl, slice: &[u8], text: &mut String) {
+    let name = display_name(decl);
+    match decl {
+        ast::TopDecl::Atom(_) => write!(text, "{}(0x{:02x})", name, slice[0]).unwrap(),
+        ast::TopDecl::Option_(ref typ) => {
+            if slice.is_empty() {
+                write!(text, "{}(None)", name).unwrap();
+            } else {
+                write!(text, "{}(Some(", name).unwrap();
+                display(&typ.typ, slice, text);
+                text.push_str("))");
+            }
+        }
+        ast::TopDecl::Union(ref typ) => {
+            let item = &typ.inner[unpack_number(slice) as usize];
+            write!(text, "{}(", name).unwrap();
+            display(&item.typ, &slice[NUMBER_SIZE..], text);
+            text.push(')');
+        }
+        ast::TopDecl::Array(ref typ) => {
+            if typ.logical == Some(ast::Logical::Uuid) {
+                write!(text, "{}({})", name, UuidDisplay(slice)).unwrap();
+            } else if typ.typ.is_atom() {
+                write!(text, "{}(0x{})", name, hex_string(slice)).unwrap();
+            } else {
+                let items = slice.chunks(typ.item_size).map(|x| (typ.typ.as_ref(), x));
+                display_list(name, items, text);
+            }
+        }
+        ast::TopDecl::Struct(ref typ) => {
+            write!(text, "{} {{ ", name).unwrap();
+            let mut start = 0;
+            for (i, (field, size)) in typ.inner.iter().zip(&typ.field_size).enumerate() {
+                if i != 0 {
+                    text.push_str(", ");
+                }
+                display_field(field, &slice[start..start + size], text);
+                start += size;
+            }
+            text.push_str(" }");
+        }
+        ast::TopDecl::FixVec(ref typ) => {
+            let data = &slice[typ.count_size..];
+            if typ.typ.is_atom() {
+                write!(text, "{}(0x{})", name, hex_string(data)).unwrap();
+            } else {
+                let count = unpack_count(slice, typ.count_size);
+                let items = data
+                    .chunks(typ.item_size)
+                    .take(count)
+                    .map(|x| (typ.typ.as_ref(), x));
+                display_list(name, items, text);
+            }
+        }
+        ast::TopDecl::DynVec(ref typ) => {
+            let items = split_offsets(slice)
+                .into_iter()
+                .map(|x| (typ.typ.as_ref(), x));
+            display_list(name, items, text);
+        }
+        ast::TopDecl::Table(ref typ) => {
+            let fields = split_offsets(slice);
+            write!(text, "{} {{ ", name).unwrap();
+            for (i, field) in typ.inner.iter().enumerate() {
+                if i != 0 {
+                    text.push_str(", ");
+                }
+                display_field(field, fields[i], text);
+            }
+            let extra_count = fields.len() - typ.inner.len();
+            if extra_count != 0 {
+                if !typ.inner.is_empty() {
+                    text.push_str(", ");
+                }
+                write!(text, ".. ({} fields)", extra_count).unwrap();
+            }
+            text.push_str(" }");
+        }
+    }
+}
+
+fn size_breakdown(decl: &ast::TopDecl, slice: &[u8]) -> SizeBreakdown {
+    let fields_header = |fields: &[(String, SizeBreakdown)]| {
+        fields.iter().map(|(_, field)| field.header).sum::<usize>()
+    };
+    let header = match decl {
+        ast::TopDecl::Atom(_) | ast::TopDecl::Array(_) => 0,
+        ast::TopDecl::Option_(ref typ) => {
+            return if slice.is_empty() {
+                SizeBreakdown::default()
+            } else {
+                size_breakdown(&typ.typ, slice)
+            };
+        }
+        ast::TopDecl::Union(ref typ) => {
+            // An unknown item in the compatible mode is only a payload.
+            let fields = typ
+                .inner
+                .get(unpack_number(slice) as usize)
+                .map(|item| {
+                    let name = item.typ.name().to_owned();
+                    vec![(name, size_breakdown(&item.typ, &slice[NUMBER_SIZE..]))]
+                })
+                .unwrap_or_default();
+            let header = NUMBER_SIZE + fields_header(&fields);
+            return SizeBreakdown {
+                header,
+                payload: slice.len() - header,
+                fields,
+            };
+        }
+        ast::TopDecl::Struct(ref typ) => {
+            let mut start = 0;
+            let fields = typ
+                .inner
+                .iter()
+                .zip(&typ.field_size)
+                .map(|(field, size)| {
+                    start += size;
+                    let field_slice = &slice[start - size..start];
+                    (field.name.clone(), size_breakdown(&field.typ, field_slice))
+                })
+                .collect();
+            return SizeBreakdown {
+                header: 0,
+                payload: slice.len(),
+                fields,
+            };
+        }
+        ast::TopDecl::FixVec(ref typ) => typ.count_size,
+        ast::TopDecl::DynVec(ref typ) => {
+            let items = split_offsets(slice);
+            NUMBER_SIZE * (items.len() + 1)
+                + items
+                    .into_iter()
+                    .map(|item| size_breakdown(&typ.typ, item).header)
+                    .sum::<usize>()
+        }
+        ast::TopDecl::Table(ref typ) => {
+            // The extra fields in the compatible mode are counted, but not in the fields.
+            let items = split_offsets(slice);
+            let fields = typ
+                .inner
+                .iter()
+                .zip(&items)
+                .map(|(field, item)| (field.name.clone(), size_breakdown(&field.typ, item)))
+                .collect::<Vec<_>>();
+            let header = NUMBER_SIZE * (items.len() + 1) + fields_header(&fields);
+            return SizeBreakdown {
+                header,
+                payload: slice.len() - header,
+                fields,
+            };
+        }
+    };
+    SizeBreakdown {
+        header,
+        payload: slice.len() - header,
+        fields: Vec::new(),
+    }
+}
@@ -0,0 +1,116 @@
+//! The banner at the top of the generated files, such as a license header.
+//!
+//! The template supports the placeholders `{version}`, `{schema}`, `{schema_hash}` and
+//! `{timestamp}`; `{{` and `}}` are literal braces. The timestamp is taken from `SOURCE_DATE_EPOCH`
+//! if it's set, as other reproducible builds do.
+
+use std::{
+    env, io,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{schema_hash, VERSION};
+
+const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
+
+/// Fill the placeholders of a template; the timestamp is only taken if it's used.
+///
+/// In the reproducible mode, the timestamp requires `SOURCE_DATE_EPOCH`.
+pub(crate) fn render<P: AsRef<Path>>(template: &str, schema_file: P, reproducible: bool) -> String {
+    let mut banner = String::new();
+    let mut rest = template;
+    while let Some(idx) = rest.find(['{', '}']) {
+        banner.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            banner.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+        let end = match (rest.starts_with('{'), rest.find('}')) {
+            (true, Some(end)) => end,
+            _ => panic!("unmatched brace in the header template"),
+        };
+        let value = match &rest[1..end] {
+            "version" => VERSION.to_owned(),
+            "schema" => schema_file
+                .as_ref()
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned(),
+            "schema_hash" => schema_hash(&schema_file)
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+            "timestamp" => timestamp(reproducible),
+            placeholder => panic!(
+                "unknown placeholder `{{{}}}` in the header template",
+                placeholder
+            ),
+        };
+        banner.push_str(&value);
+        rest = &rest[end + 1..];
+    }
+    banner.push_str(rest);
+    banner.truncate(banner.trim_end().len());
+    banner
+}
+
+/// Write the banner as line comments, and an empty line after it.
+pub(crate) fn write_banner<W: io::Write>(
+    writer: &mut W,
+    banner: &str,
+    prefix: &str,
+) -> io::Result<()> {
+    for line in banner.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            writeln!(writer, "{}", prefix)?;
+        } else {
+            writeln!(writer, "{} {}", prefix, line)?;
+        }
+    }
+    writeln!(writer)
+}
+
+/// The current time in UTC, such as `2020-01-31T08:00:00Z`.
+fn timestamp(reproducible: bool) -> String {
+    let secs = match env::var(SOURCE_DATE_EPOCH) {
+        Ok(value) => value.parse().unwrap_or_else(|_| {
+            panic!(
+                "{} [{}] should be the seconds since the epoch",
+                SOURCE_DATE_EPOCH, value
+            )
+        }),
+        Err(_) if reproducible => panic!(
+            "`{{timestamp}}` requires {} in the reproducible mode",
+            SOURCE_DATE_EPOCH
+        ),
+        Err(_) => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs(),
+    };
+    let (days, secs) = (secs / 86400, secs % 86400);
+    // The civil date from the days since the epoch, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
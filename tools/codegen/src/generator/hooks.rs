@@ -0,0 +1,81 @@
+//! The code which is injected into the generated files at the anchors, such as extra methods of
+//! the generated types.
+//!
+//! The hooks of a backend are the files in a directory, whose extension is the extension of the
+//! generated file: `header.<ext>` is injected after the preamble, such as the imports, and
+//! `types/<Name>.<ext>` is injected after the code of the type. The files are copied as is.
+
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use crate::ast::verified::{self as ast, HasName};
+
+const HEADER: &str = "header";
+const TYPES: &str = "types";
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Hooks {
+    header: Option<String>,
+    types: BTreeMap<String, String>,
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("failed to read the hook [{}]: {}", path.display(), err))
+}
+
+impl Hooks {
+    /// Read the hooks of the extension from the directory; the files of other extensions are
+    /// ignored.
+    pub(crate) fn load(dir: &Path, extension: &str) -> Self {
+        let file_name = |name: &str| format!("{}.{}", name, extension);
+        let header = dir.join(file_name(HEADER));
+        let header = header.is_file().then(|| read(&header));
+        let mut types = BTreeMap::new();
+        if let Ok(entries) = fs::read_dir(dir.join(TYPES)) {
+            for entry in entries {
+                let path = entry.unwrap().path();
+                let name = path.file_name().unwrap().to_string_lossy();
+                let suffix = format!(".{}", extension);
+                // A longer extension, such as `.dissector.lua` for `.lua`, is another backend.
+                if let Some(type_name) = name
+                    .strip_suffix(&suffix)
+                    .filter(|type_name| !type_name.contains('.'))
+                {
+                    types.insert(type_name.to_owned(), read(&path));
+                }
+            }
+        }
+        Self { header, types }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.header.is_none() && self.types.is_empty()
+    }
+
+    /// Each type hook should be of a type in the schema, so a misspelled file isn't ignored.
+    pub(crate) fn check(&self, ast: &ast::Ast) {
+        let decls = ast.major_decls();
+        for name in self.types.keys() {
+            if !decls.iter().any(|decl| decl.name() == name) {
+                panic!(
+                    "the hook of the type ({}) is not a type in the schema",
+                    name
+                );
+            }
+        }
+    }
+
+    pub(crate) fn write_header<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        if let Some(ref code) = self.header {
+            writeln!(writer, "{}", code.trim_end())?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write_type<W: io::Write>(&self, writer: &mut W, name: &str) -> io::Result<()> {
+        if let Some(code) = self.types.get(name) {
+            writeln!(writer, "{}", code.trim_end())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use std::io;
+
+use super::{
+    builder::GenBuilder, constants::GenConstants, reader::GenReader, utilities::API_DECORATOR,
+};
+use crate::ast::verified::{self as ast, HasName};
+
+const PREFIXES: &[&str] = &["MolConst_", "MolReader_", "MolBuilder_"];
+
+pub(super) trait GenAlias {
+    fn gen_alias<W: io::Write>(&self, writer: &mut W, builders: bool) -> io::Result<()>;
+}
+
+/// The names of the macros and the functions in the interfaces, without the arguments.
+fn symbols(interfaces: &str) -> Vec<&str> {
+    interfaces
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            match words.next()? {
+                "#define" => words.next(),
+                API_DECORATOR => words.nth(1),
+                _ => None,
+            }
+        })
+        .map(|symbol| symbol.split('(').next().unwrap())
+        .collect()
+}
+
+impl GenAlias for ast::Alias {
+    fn gen_alias<W: io::Write>(&self, writer: &mut W, builders: bool) -> io::Result<()> {
+        // C has no types for the molecule types, so each symbol of the type is renamed.
+        let mut buffer = Vec::new();
+        self.typ.gen_constants(&mut buffer)?;
+        self.typ.gen_reader_interfaces(&mut buffer)?;
+        if builders {
+            self.typ.gen_builder_interfaces(&mut buffer)?;
+        }
+        let interfaces = String::from_utf8(buffer).unwrap();
+        let mut renamed = vec![(
+            format!("MolDefault_{}", self.name()),
+            format!("MolDefault_{}", self.typ.name()),
+        )];
+        for symbol in symbols(&interfaces) {
+            for prefix in PREFIXES {
+                let target_prefix = format!("{}{}_", prefix, self.typ.name());
+                if let Some(tail) = symbol.strip_prefix(&target_prefix) {
+                    let alias = format!("{}{}_{}", prefix, self.name(), tail);
+                    renamed.push((alias, symbol.to_owned()));
+                }
+            }
+        }
+        for (alias, symbol) in renamed {
+            writeln!(writer, "{:39} {:47} {}", "#define", alias, symbol)?;
+        }
+        Ok(())
+    }
+}
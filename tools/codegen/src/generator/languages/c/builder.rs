@@ -1,5 +1,6 @@
 use std::io;
 
+use super::super::model::struct_fields;
 use super::utilities::IdentPrefix;
 use crate::ast::verified::{self as ast, DefaultContent, HasName};
 
@@ -83,6 +84,11 @@ fn calculate_capacity(used: usize) -> usize {
     used.next_power_of_two()
 }
 
+// The buffer of a bounded type is never larger than its maximum size.
+fn bounded_capacity(capacity: usize, max_size: Option<usize>) -> usize {
+    max_size.map_or(capacity, |max_size| capacity.min(max_size))
+}
+
 impl GenBuilder for ast::Option_ {
     fn gen_builder_interfaces_internal<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
         {
@@ -112,7 +118,10 @@ impl GenBuilder for ast::Union {
                 let name = default.default_constant();
                 (len, format!("&{}", name))
             };
-            let data_capacity = calculate_capacity(molecule::NUMBER_SIZE + len);
+            let data_capacity = bounded_capacity(
+                calculate_capacity(molecule::NUMBER_SIZE + len),
+                self.max_size(),
+            );
             let macro_content = format!(
                 "mol_union_builder_initialize(b, {}, {}, {}, {})",
                 data_capacity, id, name, len
@@ -120,18 +129,23 @@ impl GenBuilder for ast::Union {
             self.define_builder_macro(writer, "_init(b)", &macro_content)?;
         }
         for (item_id, item) in self.inner.iter().enumerate() {
-            let (macro_sig_tail, macro_content) = if item.typ.is_atom() {
-                (
-                    format!("_set_{}(b, p)", item.typ.name()),
-                    format!("mol_union_builder_set_byte(b, {}, p)", item_id),
-                )
-            } else {
-                (
-                    format!("_set_{}(b, p, l)", item.typ.name()),
-                    format!("mol_union_builder_set(b, {}, p, l)", item_id),
-                )
-            };
-            self.define_builder_macro(writer, &macro_sig_tail, &macro_content)?;
+            let names = Some(item.typ.name())
+                .into_iter()
+                .chain(item.alias.as_deref());
+            for name in names {
+                let (macro_sig_tail, macro_content) = if item.typ.is_atom() {
+                    (
+                        format!("_set_{}(b, p)", name),
+                        format!("mol_union_builder_set_byte(b, {}, p)", item_id),
+                    )
+                } else {
+                    (
+                        format!("_set_{}(b, p, l)", name),
+                        format!("mol_union_builder_set(b, {}, p, l)", item_id),
+                    )
+                };
+                self.define_builder_macro(writer, &macro_sig_tail, &macro_content)?;
+            }
         }
         self.gen_builder_interface_build(writer, Some("mol_builder_finalize_simple"))?;
         Ok(())
@@ -174,19 +188,18 @@ impl GenBuilder for ast::Struct {
             );
             self.define_builder_macro(writer, "_init(b)", &macro_content)?;
         }
-        let mut field_offset = 0;
-        for (f, field_size) in self.inner.iter().zip(self.field_size.iter()) {
+        for f in struct_fields(self) {
             let macro_sig_tail = format!("_set_{}(b, p)", f.name);
             let macro_content = if f.typ.is_atom() {
-                format!("mol_builder_set_byte_by_offset(b, {}, p)", field_offset)
+                format!("mol_builder_set_byte_by_offset(b, {}, p)", f.offset())
             } else {
                 format!(
                     "mol_builder_set_by_offset(b, {}, p, {})",
-                    field_offset, field_size
+                    f.offset(),
+                    f.size()
                 )
             };
             self.define_builder_macro(writer, &macro_sig_tail, &macro_content)?;
-            field_offset += field_size;
         }
         self.gen_builder_interface_build(writer, Some("mol_builder_finalize_simple"))?;
         Ok(())
@@ -196,7 +209,8 @@ impl GenBuilder for ast::Struct {
 impl GenBuilder for ast::FixVec {
     fn gen_builder_interfaces_internal<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
         {
-            let data_capacity = calculate_capacity(self.item_size * 16);
+            let data_capacity =
+                bounded_capacity(calculate_capacity(self.item_size * 16), self.max_size());
             let macro_content = format!("mol_fixvec_builder_initialize(b, {})", data_capacity);
             self.define_builder_macro(writer, "_init(b)", &macro_content)?;
         }
@@ -208,7 +222,15 @@ impl GenBuilder for ast::FixVec {
             };
             self.define_builder_macro(writer, "_push(b, p)", &macro_content)?;
         }
-        self.gen_builder_interface_build(writer, Some("mol_fixvec_builder_finalize"))?;
+        if self.is_standard() {
+            self.gen_builder_interface_build(writer, Some("mol_fixvec_builder_finalize"))?;
+        } else {
+            let macro_content = format!(
+                "mol_fixvec_builder_finalize_with_count_size(b, {})",
+                self.count_size
+            );
+            self.define_builder_macro(writer, "_build(b)", &macro_content)?;
+        }
         Ok(())
     }
 }
@@ -239,7 +261,10 @@ impl GenBuilder for ast::DynVec {
 impl GenBuilder for ast::Table {
     fn gen_builder_interfaces_internal<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
         {
-            let data_capacity = calculate_capacity(self.default_content().len() * 4);
+            let data_capacity = bounded_capacity(
+                calculate_capacity(self.default_content().len() * 4),
+                self.max_size(),
+            );
             let macro_content = format!(
                 "mol_table_builder_initialize(b, {}, {})",
                 data_capacity,
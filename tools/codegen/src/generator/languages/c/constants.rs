@@ -0,0 +1,122 @@
+use std::io;
+
+use super::utilities::IdentPrefix;
+use crate::ast::verified::{self as ast, HasName};
+
+pub(super) trait GenConstants: IdentPrefix {
+    fn constant_prefix(&self) -> String {
+        format!("MolConst_{}", self.name())
+    }
+
+    fn define_constant<W: io::Write>(
+        &self,
+        writer: &mut W,
+        constant_tail: &str,
+        value: &str,
+    ) -> io::Result<()> {
+        let constant = format!("{}{}", self.constant_prefix(), constant_tail);
+        writeln!(writer, "{:39} {:47} {}", "#define", constant, value)
+    }
+
+    fn gen_constants<W: io::Write>(&self, writer: &mut W) -> io::Result<()>;
+
+    fn gen_size_checks<W: io::Write>(&self, writer: &mut W) -> io::Result<()>;
+}
+
+// The size of a fixed-size type, as a C expression.
+fn size_of(typ: &ast::TopDecl) -> String {
+    if typ.is_atom() {
+        "1".to_owned()
+    } else {
+        format!("MolConst_{}_size", typ.name())
+    }
+}
+
+fn static_assert<W: io::Write>(writer: &mut W, expr: &str, message: &str) -> io::Result<()> {
+    writeln!(writer, "MOL_STATIC_ASSERT({}, \"{}\");", expr, message)
+}
+
+impl GenConstants for ast::TopDecl {
+    fn gen_constants<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.define_constant(writer, "_name", &format!("\"{}\"", self.name()))?;
+        match self {
+            ast::TopDecl::Union(ref i) => {
+                for (item_id, item) in i.inner.iter().enumerate() {
+                    let tail = format!("_item_{}", item.typ.name());
+                    self.define_constant(writer, &tail, &item_id.to_string())?;
+                    if let Some(ref alias) = item.alias {
+                        let tail = format!("_item_{}", alias);
+                        self.define_constant(writer, &tail, &item_id.to_string())?;
+                    }
+                }
+            }
+            ast::TopDecl::Array(ref i) => {
+                self.define_constant(writer, "_size", &i.total_size().to_string())?;
+                self.define_constant(writer, "_item_size", &i.item_size.to_string())?;
+                self.define_constant(writer, "_item_count", &i.item_count.to_string())?;
+            }
+            ast::TopDecl::Struct(ref i) => {
+                self.define_constant(writer, "_size", &i.total_size().to_string())?;
+                for (index, field) in i.inner.iter().enumerate() {
+                    let tail = format!("_field_{}", field.name);
+                    self.define_constant(writer, &tail, &index.to_string())?;
+                }
+            }
+            ast::TopDecl::FixVec(ref i) => {
+                self.define_constant(writer, "_item_size", &i.item_size.to_string())?;
+                if !i.is_standard() {
+                    self.define_constant(writer, "_count_size", &i.count_size.to_string())?;
+                }
+            }
+            ast::TopDecl::Table(ref i) => {
+                for (index, field) in i.inner.iter().enumerate() {
+                    let tail = format!("_field_{}", field.name);
+                    self.define_constant(writer, &tail, &index.to_string())?;
+                }
+            }
+            _ => {}
+        }
+        if let Some(max_size) = self.max_size() {
+            self.define_constant(writer, "_max_encoded_size", &max_size.to_string())?;
+        }
+        Ok(())
+    }
+
+    fn gen_size_checks<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        let prefix = self.constant_prefix();
+        match self {
+            ast::TopDecl::Array(ref i) => {
+                let message = format!("the size of {} is changed", self.name());
+                let expr = format!("sizeof({}) == {}_size", self.default_constant(), prefix);
+                static_assert(writer, &expr, &message)?;
+                let expr = format!("{}_item_size == {}", prefix, size_of(&i.typ));
+                static_assert(writer, &expr, &message)?;
+                let expr = format!(
+                    "{}_size == {}_item_size * {}_item_count",
+                    prefix, prefix, prefix
+                );
+                static_assert(writer, &expr, &message)?;
+            }
+            ast::TopDecl::Struct(ref i) => {
+                let message = format!("the size of {} is changed", self.name());
+                let expr = format!("sizeof({}) == {}_size", self.default_constant(), prefix);
+                static_assert(writer, &expr, &message)?;
+                let fields = i
+                    .inner
+                    .iter()
+                    .map(|f| size_of(&f.typ))
+                    .collect::<Vec<_>>()
+                    .join(" + ");
+                let expr = format!("{}_size == {}", prefix, fields);
+                static_assert(writer, &expr, &message)?;
+            }
+            ast::TopDecl::FixVec(ref i) => {
+                let message = format!("the item size of {} is changed", self.name());
+                let expr = format!("{}_item_size == {}", prefix, size_of(&i.typ));
+                static_assert(writer, &expr, &message)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+//! The Rust declarations of the symbols which are defined by the generated C code.
+//!
+//! Most of the C APIs are macros, only the functions and the constants of the default values
+//! are symbols; the C header should be compiled into the same binary.
+
+use std::io;
+
+use super::utilities::IdentPrefix;
+use crate::{
+    ast::verified::{self as ast, DefaultContent},
+    generator::{metadata::Metadata, Options},
+    VERSION,
+};
+
+pub(crate) struct FfiGenerator;
+
+fn has_verify_function(decl: &ast::TopDecl) -> bool {
+    matches!(
+        decl,
+        ast::TopDecl::Option_(_)
+            | ast::TopDecl::Union(_)
+            | ast::TopDecl::DynVec(_)
+            | ast::TopDecl::Table(_)
+    )
+}
+
+fn has_build_function(decl: &ast::TopDecl) -> bool {
+    matches!(decl, ast::TopDecl::Table(_))
+}
+
+impl super::super::LanguageGenerator for FfiGenerator {
+    fn generate<W: io::Write>(
+        writer: &mut W,
+        ast: &ast::Ast,
+        _options: &Options,
+    ) -> io::Result<()> {
+        let metadata = Metadata::new(ast);
+        writeln!(writer, "// Generated by Molecule {}", VERSION)?;
+        writeln!(writer, "// {}", metadata.comment())?;
+        writeln!(writer)?;
+        writeln!(writer, "#[allow(non_snake_case, non_upper_case_globals)]")?;
+        writeln!(writer, r#"extern "C" {{"#)?;
+        for decl in ast.major_decls() {
+            writeln!(
+                writer,
+                "    pub static {}: [u8; {}];",
+                decl.default_constant(),
+                decl.default_content().len()
+            )?;
+        }
+        for decl in ast.major_decls().iter().filter(|d| has_verify_function(d)) {
+            writeln!(
+                writer,
+                "    pub fn {}_verify(input: *const molecule::ffi::mol_seg_t, compatible: bool) -> molecule::ffi::mol_errno;",
+                decl.reader_prefix()
+            )?;
+        }
+        for decl in ast.major_decls().iter().filter(|d| has_build_function(d)) {
+            writeln!(
+                writer,
+                "    pub fn {}_build(builder: molecule::ffi::mol_builder_t) -> molecule::ffi::mol_seg_res_t;",
+                decl.builder_prefix()
+            )?;
+        }
+        writeln!(writer, "}}")
+    }
+}
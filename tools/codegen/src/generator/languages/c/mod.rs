@@ -2,17 +2,29 @@ use std::io;
 
 use case::CaseExt;
 
-use crate::{ast::verified as ast, C_API_VERSION_MIN, VERSION};
+use crate::{
+    ast::verified::{self as ast, HasName as _},
+    generator::{metadata::Metadata, CProfile, Options},
+    C_API_VERSION_MIN, VERSION,
+};
 
 #[macro_use]
-pub(self) mod utilities;
+mod utilities;
 
 mod import;
 
+mod alias;
 mod builder;
+mod constants;
+mod ffi;
 mod reader;
 
-use self::{builder::GenBuilder, import::GenImport, reader::GenReader};
+pub(crate) use self::ffi::FfiGenerator;
+
+use self::{
+    alias::GenAlias, builder::GenBuilder, constants::GenConstants, import::GenImport,
+    reader::GenReader,
+};
 
 pub(crate) struct Generator;
 
@@ -25,7 +37,7 @@ impl Generator {
     }
 
     fn ifndef<W: io::Write>(o: &mut W, name: &str) -> io::Result<()> {
-        let n = name.to_snake().to_uppercase();
+        let n = name.to_snake().to_ascii_uppercase();
         let api_decorator = utilities::API_DECORATOR;
         w!(o, "#ifndef {}_H                                        ", n);
         w!(o, "#define {}_H                                        ", n);
@@ -44,7 +56,7 @@ impl Generator {
     }
 
     fn endif<W: io::Write>(o: &mut W, name: &str) -> io::Result<()> {
-        let n = name.to_snake().to_uppercase();
+        let n = name.to_snake().to_ascii_uppercase();
         let api_decorator = utilities::API_DECORATOR;
         w!(o, "                                                       ");
         w!(o, "#ifdef __DEFINE_MOLECULE_API_DECORATOR                 ");
@@ -73,16 +85,42 @@ impl Generator {
         w!(o, "#define MOLECULE_API_VERSION_MIN {}       ", api_ver_min);
         Ok(())
     }
+
+    fn define_metadata<W: io::Write>(o: &mut W, metadata: &Metadata) -> io::Result<()> {
+        let n = metadata.namespace.to_snake().to_ascii_uppercase();
+        let generator_version = metadata.generator_version;
+        let schema_version = &metadata.schema_version;
+        w!(
+            o,
+            "#define {}_GENERATOR_VERSION \"{}\"",
+            n,
+            generator_version
+        );
+        w!(o, "#define {}_SCHEMA_VERSION \"{}\"", n, schema_version);
+        writeln!(o)
+    }
 }
 
 impl super::LanguageGenerator for Generator {
-    fn generate<W: io::Write>(writer: &mut W, ast: &ast::Ast) -> io::Result<()> {
+    fn generate<W: io::Write>(writer: &mut W, ast: &ast::Ast, options: &Options) -> io::Result<()> {
+        let metadata = Metadata::new(ast);
+        // The builders allocate and copy memory by libc, so scripts only have the readers.
+        let builders = options.c_profile != CProfile::Script;
         writeln!(writer, "// Generated by Molecule {}", VERSION)?;
+        writeln!(writer, "// {}", metadata.comment())?;
+        if !builders {
+            writeln!(
+                writer,
+                "// The script profile: only the readers, without libc functions or variable-length arrays."
+            )?;
+        }
         writeln!(writer)?;
         Self::define_version(writer)?;
         writeln!(writer)?;
         writeln!(writer, r#"#include "molecule_reader.h""#)?;
-        writeln!(writer, r#"#include "molecule_builder.h""#)?;
+        if builders {
+            writeln!(writer, r#"#include "molecule_builder.h""#)?;
+        }
         writeln!(writer)?;
         Self::ifndef(writer, &ast.namespace)?;
         let major_imports = ast.major_imports();
@@ -92,15 +130,35 @@ impl super::LanguageGenerator for Generator {
                 import.gen_import(writer)?;
             }
         }
+        if !options.hooks.is_empty() {
+            writeln!(writer)?;
+            options.hooks.write_header(writer)?;
+        }
         writeln!(writer)?;
-        Self::title(writer, "Reader APIs")?;
+        Self::title(writer, "Constants")?;
+        Self::define_metadata(writer, &metadata)?;
         for decl in ast.major_decls() {
-            decl.gen_reader_interfaces(writer)?;
+            decl.gen_constants(writer)?;
         }
         writeln!(writer)?;
-        Self::title(writer, "Builder APIs")?;
+        Self::title(writer, "Reader APIs")?;
         for decl in ast.major_decls() {
-            decl.gen_builder_interfaces(writer)?;
+            decl.gen_reader_interfaces(writer)?;
+        }
+        if builders {
+            writeln!(writer)?;
+            Self::title(writer, "Builder APIs")?;
+            for decl in ast.major_decls() {
+                decl.gen_builder_interfaces(writer)?;
+            }
+        }
+        let aliases = ast.major_aliases();
+        if !aliases.is_empty() {
+            writeln!(writer)?;
+            Self::title(writer, "Aliases")?;
+            for alias in aliases {
+                alias.gen_alias(writer, builders)?;
+            }
         }
         writeln!(writer)?;
         Self::title(writer, "Default Value")?;
@@ -112,14 +170,29 @@ impl super::LanguageGenerator for Generator {
         writeln!(writer)?;
         writeln!(writer, r#"#undef ____"#)?;
         writeln!(writer)?;
-        Self::title(writer, "Reader Functions")?;
+        Self::title(writer, "Size Checks")?;
         for decl in ast.major_decls() {
-            decl.gen_reader_functions(writer)?;
+            decl.gen_size_checks(writer)?;
         }
         writeln!(writer)?;
-        Self::title(writer, "Builder Functions")?;
+        Self::title(writer, "Reader Functions")?;
         for decl in ast.major_decls() {
-            decl.gen_builder_functions(writer)?;
+            decl.gen_reader_functions(writer, options.c_profile)?;
+        }
+        if builders {
+            writeln!(writer)?;
+            Self::title(writer, "Builder Functions")?;
+            for decl in ast.major_decls() {
+                decl.gen_builder_functions(writer)?;
+            }
+        }
+        if !options.hooks.is_empty() {
+            // The extensions could call all the readers and the builders.
+            writeln!(writer)?;
+            Self::title(writer, "Extensions")?;
+            for decl in ast.major_decls() {
+                options.hooks.write_type(writer, decl.name())?;
+            }
         }
         Self::endif(writer, &ast.namespace)?;
         Ok(())
@@ -1,7 +1,24 @@
 use std::io;
 
+use super::super::model::struct_fields;
 use super::utilities::IdentPrefix;
-use crate::ast::verified::{self as ast};
+use crate::{
+    ast::verified::{self as ast},
+    generator::CProfile,
+};
+
+/// Whether the verifier of the type is a function which takes `compatible`; the verifiers of the
+/// fixed-size types and the fixvecs are macros which drop it, so a caller which only calls them
+/// has to mark it as used.
+fn uses_compatible(typ: &ast::TopDecl) -> bool {
+    matches!(
+        typ,
+        ast::TopDecl::Option_(_)
+            | ast::TopDecl::Union(_)
+            | ast::TopDecl::DynVec(_)
+            | ast::TopDecl::Table(_)
+    )
+}
 
 pub(super) trait GenReader: IdentPrefix {
     fn gen_reader_interfaces_internal<W: io::Write>(&self, writer: &mut W) -> io::Result<()>;
@@ -11,7 +28,11 @@ pub(super) trait GenReader: IdentPrefix {
         Ok(())
     }
 
-    fn gen_reader_functions<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+    fn gen_reader_functions<W: io::Write>(
+        &self,
+        writer: &mut W,
+        _profile: CProfile,
+    ) -> io::Result<()> {
         self.gen_reader_function_verify(writer)?;
         Ok(())
     }
@@ -46,6 +67,9 @@ impl GenReader for ast::Option_ {
             api_decorator,
             func_name
         );
+        if !uses_compatible(&self.typ) {
+            w!(o, "    (void)compatible;                                  ");
+        }
         if self.typ.is_atom() {
             w!(o, "    if (input->size > 1) {{                            ");
             w!(o, "        return MOL_ERR;                                ");
@@ -87,6 +111,9 @@ impl GenReader for ast::Union {
             api_decorator,
             func_name
         );
+        if !self.inner.iter().any(|item| uses_compatible(&item.typ)) {
+            w!(o, "    (void)compatible;                                  ");
+        }
         w!(o, "    if (input->size < MOL_NUM_T_SIZE) {{               ");
         w!(o, "        return MOL_ERR_HEADER;                         ");
         w!(o, "    }}                                                 ");
@@ -97,6 +124,15 @@ impl GenReader for ast::Union {
         w!(o, "    switch(item_id) {{                                 ");
         for (item_id, item) in self.inner.iter().enumerate() {
             w!(o, "        case {}:                              ", item_id);
+            if let Some(max_size) = item.max_size {
+                w!(
+                    o,
+                    "            if (inner.size > {}) {{            ",
+                    max_size
+                );
+                w!(o, "                return MOL_ERR_TOTAL_SIZE;     ");
+                w!(o, "            }}                                 ");
+            }
             if item.typ.is_atom() {
                 w!(o, "            return inner.size == 1 ? MOL_OK : MOL_ERR; ");
             } else {
@@ -137,12 +173,10 @@ impl GenReader for ast::Struct {
             let macro_content = format!("mol_verify_fixed_size(s, {})", self.total_size());
             self.define_reader_macro(writer, "_verify(s, c)", &macro_content)?;
         }
-        let mut field_offset = 0;
-        for (f, field_size) in self.inner.iter().zip(self.field_size.iter()) {
+        for f in struct_fields(self) {
             let macro_sig_tail = format!("_get_{}(s)", f.name);
-            let macro_content = format!("mol_slice_by_offset(s, {}, {})", field_offset, field_size);
+            let macro_content = format!("mol_slice_by_offset(s, {}, {})", f.offset(), f.size());
             self.define_reader_macro(writer, &macro_sig_tail, &macro_content)?;
-            field_offset += field_size;
         }
         Ok(())
     }
@@ -150,19 +184,33 @@ impl GenReader for ast::Struct {
 
 impl GenReader for ast::FixVec {
     fn gen_reader_interfaces_internal<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        // A non-standard count size is passed to the `_with_count_size` variants.
+        let (suffix, count_size) = if self.is_standard() {
+            ("", "".to_owned())
+        } else {
+            ("_with_count_size", format!(", {}", self.count_size))
+        };
         {
-            let macro_content = format!("mol_fixvec_verify(s, {})", self.item_size);
+            let macro_content = format!(
+                "mol_fixvec_verify{}(s, {}{})",
+                suffix, self.item_size, count_size
+            );
             self.define_reader_macro(writer, "_verify(s, c)", &macro_content)?;
         }
         {
-            self.define_reader_macro(writer, "_length(s)", "mol_fixvec_length(s)")?;
+            let macro_content = format!("mol_fixvec_length{}(s{})", suffix, count_size);
+            self.define_reader_macro(writer, "_length(s)", &macro_content)?;
         }
         {
-            let macro_content = format!("mol_fixvec_slice_by_index(s, {}, i)", self.item_size);
+            let macro_content = format!(
+                "mol_fixvec_slice_by_index{}(s, {}, i{})",
+                suffix, self.item_size, count_size
+            );
             self.define_reader_macro(writer, "_get(s, i)", &macro_content)?;
         }
         if self.typ.is_atom() {
-            self.define_reader_macro(writer, "_raw_bytes(s)", "mol_fixvec_slice_raw_bytes(s)")?;
+            let macro_content = format!("mol_fixvec_slice_raw_bytes{}(s{})", suffix, count_size);
+            self.define_reader_macro(writer, "_raw_bytes(s)", &macro_content)?;
         }
         Ok(())
     }
@@ -197,6 +245,9 @@ impl GenReader for ast::DynVec {
             api_decorator,
             func_name
         );
+        if !uses_compatible(&self.typ) {
+            w!(o, "    (void)compatible;                                  ");
+        }
         w!(o, "    if (input->size < MOL_NUM_T_SIZE) {{               ");
         w!(o, "        return MOL_ERR_HEADER;                         ");
         w!(o, "    }}                                                 ");
@@ -309,9 +360,14 @@ impl GenReader for ast::Table {
         w!(o, "        return MOL_ERR_OFFSET;                         ");
         w!(o, "    }}                                                 ");
         w!(o, "    mol_num_t field_count = offset / 4 - 1;            ");
-        w!(o, "    if (field_count < {}) {{                       ", fc);
-        w!(o, "        return MOL_ERR_FIELD_COUNT;                    ");
-        w!(o, "    }} else if (!compatible && field_count > {}) {{", fc);
+        // The count is unsigned, so it's never less than no fields.
+        if fc > 0 {
+            w!(o, "    if (field_count < {}) {{                       ", fc);
+            w!(o, "        return MOL_ERR_FIELD_COUNT;                    ");
+            w!(o, "    }} else if (!compatible && field_count > {}) {{", fc);
+        } else {
+            w!(o, "    if (!compatible && field_count > 0) {{             ");
+        }
         w!(o, "        return MOL_ERR_FIELD_COUNT;                    ");
         w!(o, "    }}                                                 ");
         w!(o, "    if (input->size < MOL_NUM_T_SIZE*(field_count+1)){{");
@@ -358,6 +414,118 @@ impl GenReader for ast::Table {
     }
 }
 
+impl ast::Table {
+    // The verify function of the script profile.
+    //
+    // The offsets are not copied into a variable-length array, they're read from the header
+    // again when the fields are sliced, so the stack usage doesn't depend on the input; and the
+    // order of the offsets is checked without branches in the loop.
+    fn gen_reader_function_verify_bounded<W: io::Write>(&self, o: &mut W) -> io::Result<()> {
+        let func_name = format!("{}_verify", self.reader_prefix());
+        let api_decorator = self.api_decorator();
+        let fc = self.inner.len();
+        w!(
+            o,
+            "{} mol_errno {} (const mol_seg_t *input, bool compatible) {{",
+            api_decorator,
+            func_name
+        );
+        w!(o, "    if (input->size < MOL_NUM_T_SIZE) {{               ");
+        w!(o, "        return MOL_ERR_HEADER;                         ");
+        w!(o, "    }}                                                 ");
+        w!(o, "    uint8_t *ptr = input->ptr;                         ");
+        w!(o, "    mol_num_t total_size = mol_unpack_number(ptr);     ");
+        w!(o, "    if (input->size != total_size) {{                  ");
+        w!(o, "        return MOL_ERR_TOTAL_SIZE;                     ");
+        w!(o, "    }}                                                 ");
+        if self.inner.is_empty() {
+            w!(o, "    if (input->size == MOL_NUM_T_SIZE) {{              ");
+            w!(o, "        return MOL_OK;                                 ");
+            w!(o, "    }}                                                 ");
+        }
+        w!(o, "    if (input->size < MOL_NUM_T_SIZE * 2) {{           ");
+        w!(o, "        return MOL_ERR_HEADER;                         ");
+        w!(o, "    }}                                                 ");
+        w!(
+            o,
+            "    mol_num_t offset = mol_unpack_number(ptr + MOL_NUM_T_SIZE);"
+        );
+        w!(o, "    if ((offset & 3) | (offset < MOL_NUM_T_SIZE*2)) {{ ");
+        w!(o, "        return MOL_ERR_OFFSET;                         ");
+        w!(o, "    }}                                                 ");
+        w!(o, "    mol_num_t field_count = offset / 4 - 1;            ");
+        if fc > 0 {
+            w!(
+                o,
+                "    if ((field_count < {}) | (!compatible & (field_count > {}))) {{",
+                fc,
+                fc
+            );
+        } else {
+            w!(o, "    if (!compatible & (field_count > 0)) {{            ");
+        }
+        w!(o, "        return MOL_ERR_FIELD_COUNT;                    ");
+        w!(o, "    }}                                                 ");
+        w!(o, "    if (input->size < MOL_NUM_T_SIZE*(field_count+1)){{");
+        w!(o, "        return MOL_ERR_HEADER;                         ");
+        w!(o, "    }}                                                 ");
+        w!(o, "    mol_num_t invalid = 0;                             ");
+        w!(o, "    mol_num_t end;                                     ");
+        w!(o, "    for (mol_num_t i=2; i<=field_count; i++) {{        ");
+        w!(
+            o,
+            "        end = mol_unpack_number(ptr + MOL_NUM_T_SIZE*i);"
+        );
+        w!(o, "        invalid |= offset > end;                       ");
+        w!(o, "        offset = end;                                  ");
+        w!(o, "    }}                                                 ");
+        w!(o, "    invalid |= offset > total_size;                    ");
+        w!(o, "    if (invalid) {{                                    ");
+        w!(o, "        return MOL_ERR_OFFSET;                         ");
+        w!(o, "    }}                                                 ");
+        if !self.inner.is_empty() {
+            w!(o, "    mol_num_t start;                                   ");
+            if self.inner.iter().any(|field| !field.typ.is_atom()) {
+                w!(o, "    mol_seg_t inner;                                   ");
+                w!(o, "    mol_errno errno;                                   ");
+            }
+            for (i, field) in self.inner.iter().enumerate() {
+                let j = i + 1;
+                w!(
+                    o,
+                    "    start = mol_unpack_number(ptr + MOL_NUM_T_SIZE*{});",
+                    j
+                );
+                if j == fc {
+                    w!(o, "    end = field_count == {} ? total_size : mol_unpack_number(ptr + MOL_NUM_T_SIZE*{});", fc, j + 1);
+                } else {
+                    w!(
+                        o,
+                        "    end = mol_unpack_number(ptr + MOL_NUM_T_SIZE*{});",
+                        j + 1
+                    );
+                }
+                if field.typ.is_atom() {
+                    w!(o, "    if (end - start != 1) {{                           ");
+                    w!(o, "        return MOL_ERR_DATA;                           ");
+                    w!(o, "    }}                                                 ");
+                } else {
+                    let f = format!("{}_verify", field.typ.reader_prefix());
+                    w!(o, "    inner.ptr = input->ptr + start;                    ");
+                    w!(o, "    inner.size = end - start;                          ");
+                    w!(o, "    errno = {}(&inner, compatible);                 ", f);
+                    w!(o, "    if (errno != MOL_OK) {{                            ");
+                    w!(o, "        return MOL_ERR_DATA;                           ");
+                    w!(o, "    }}                                                 ");
+                }
+            }
+        }
+        w!(o, "    return MOL_OK;                                     ");
+        w!(o, "}}                                                     ");
+        Ok(())
+    }
+}
+
 impl GenReader for ast::TopDecl {
     fn gen_reader_interfaces_internal<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
         match self {
@@ -372,6 +540,19 @@ impl GenReader for ast::TopDecl {
         }
     }
 
+    fn gen_reader_functions<W: io::Write>(
+        &self,
+        writer: &mut W,
+        profile: CProfile,
+    ) -> io::Result<()> {
+        match self {
+            ast::TopDecl::Table(ref i) if profile == CProfile::Script => {
+                i.gen_reader_function_verify_bounded(writer)
+            }
+            _ => self.gen_reader_function_verify(writer),
+        }
+    }
+
     fn gen_reader_function_verify<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
         match self {
             ast::TopDecl::Option_(ref i) => i.gen_reader_function_verify(writer),
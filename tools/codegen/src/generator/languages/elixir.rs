@@ -0,0 +1,443 @@
+//! Pure Elixir modules which decode and encode the types.
+//!
+//! The namespace of the schema is the root module, and each type is a module in it, which has
+//! `decode/1` and `encode/1`. The values are mapped as below:
+//!
+//! - A byte is an integer, and an array or a fixvec of bytes is a binary.
+//! - Other arrays and vectors are lists.
+//! - A struct or a table is a map with atom keys, which are the field names.
+//! - An option is the value or `nil`.
+//! - A union is a tuple of the atom of the item name and the value.
+//!
+//! The imported types are generated into the same root module, so the file doesn't depend on the
+//! files of the imported schemas. The verification is in the strict mode.
+
+use std::io;
+
+use case::CaseExt;
+
+use super::model::{Decl, Model};
+use crate::{
+    ast::verified::{self as ast, HasName},
+    generator::{metadata::Metadata, Options},
+    VERSION,
+};
+
+pub(crate) struct Generator;
+
+// The helpers which are shared by the modules of the types.
+const RUNTIME: &str = r#"
+  @doc false
+  def decode_byte(<<value>>), do: {:ok, value}
+  def decode_byte(_), do: {:error, :total_size}
+
+  @doc false
+  def encode_byte(value) when is_integer(value) and value in 0..255, do: <<value>>
+
+  @doc false
+  def decode_all(items, decode) do
+    items
+    |> Enum.reduce_while({:ok, []}, fn item, {:ok, acc} ->
+      case decode.(item) do
+        {:ok, value} -> {:cont, {:ok, [value | acc]}}
+        error -> {:halt, error}
+      end
+    end)
+    |> case do
+      {:ok, values} -> {:ok, Enum.reverse(values)}
+      error -> error
+    end
+  end
+
+  @doc false
+  def decode_items(bin, size, decode) do
+    decode_all(for(<<item::binary-size(size) <- bin>>, do: item), decode)
+  end
+
+  @doc false
+  def split_dynamic(<<total::little-32>>) when total == 4, do: {:ok, []}
+
+  def split_dynamic(<<total::little-32, first::little-32, _::binary>> = bin)
+      when byte_size(bin) == total and first >= 8 and rem(first, 4) == 0 and first <= total do
+    header = binary_part(bin, 4, first - 4)
+    offsets = for <<offset::little-32 <- header>>, do: offset
+    slice_items(bin, offsets ++ [total], [])
+  end
+
+  def split_dynamic(<<total::little-32, _::binary>> = bin) when byte_size(bin) != total,
+    do: {:error, :total_size}
+
+  def split_dynamic(_), do: {:error, :header}
+
+  @doc false
+  def split_fields(bin, count) do
+    case split_dynamic(bin) do
+      {:ok, fields} when length(fields) == count -> {:ok, fields}
+      {:ok, _} -> {:error, :field_count}
+      error -> error
+    end
+  end
+
+  defp slice_items(bin, [start, stop | rest], acc) when start <= stop do
+    slice_items(bin, [stop | rest], [binary_part(bin, start, stop - start) | acc])
+  end
+
+  defp slice_items(_, [_], acc), do: {:ok, Enum.reverse(acc)}
+  defp slice_items(_, _, _), do: {:error, :offsets}
+
+  @doc false
+  def encode_dynamic(items) do
+    header_size = 4 * (length(items) + 1)
+
+    {offsets, total} =
+      Enum.map_reduce(items, header_size, fn item, offset ->
+        {<<offset::little-32>>, offset + byte_size(item)}
+      end)
+
+    IO.iodata_to_binary([<<total::little-32>>, offsets | items])
+  end
+"#;
+
+fn module_name(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+struct Context {
+    root: String,
+}
+
+impl Context {
+    fn module(&self, typ: &ast::TopDecl) -> String {
+        format!("{}.{}", self.root, module_name(typ.name()))
+    }
+
+    fn decode_call(&self, typ: &ast::TopDecl, arg: &str) -> String {
+        if typ.is_atom() {
+            format!("{}.decode_byte({})", self.root, arg)
+        } else {
+            format!("{}.decode({})", self.module(typ), arg)
+        }
+    }
+
+    fn encode_call(&self, typ: &ast::TopDecl, arg: &str) -> String {
+        if typ.is_atom() {
+            format!("{}.encode_byte({})", self.root, arg)
+        } else {
+            format!("{}.encode({})", self.module(typ), arg)
+        }
+    }
+
+    fn decoder(&self, typ: &ast::TopDecl) -> String {
+        if typ.is_atom() {
+            format!("&{}.decode_byte/1", self.root)
+        } else {
+            format!("&{}.decode/1", self.module(typ))
+        }
+    }
+
+    fn encoder(&self, typ: &ast::TopDecl) -> String {
+        if typ.is_atom() {
+            format!("&{}.encode_byte/1", self.root)
+        } else {
+            format!("&{}.encode/1", self.module(typ))
+        }
+    }
+
+    /// Decode the slices of the fields, which are `v0`, `v1` and so on, into a map.
+    fn decode_fields<W: io::Write>(
+        &self,
+        writer: &mut W,
+        fields: &[ast::FieldDecl],
+        split: Option<&str>,
+    ) -> io::Result<()> {
+        let vars = (0..fields.len())
+            .map(|idx| format!("v{}", idx))
+            .collect::<Vec<_>>();
+        let clauses =
+            split
+                .map(|split| format!("{{:ok, [{}]}} <- {}", vars.join(", "), split))
+                .into_iter()
+                .chain(vars.iter().zip(fields).map(|(var, f)| {
+                    format!("{{:ok, {}}} <- {}", var, self.decode_call(&f.typ, var))
+                }))
+                .collect::<Vec<_>>();
+        let map = fields
+            .iter()
+            .zip(&vars)
+            .map(|(f, var)| format!("{}: {}", f.name, var))
+            .collect::<Vec<_>>();
+        writeln!(writer, "      with {} do", clauses.join(",\n           "))?;
+        writeln!(writer, "        {{:ok, %{{{}}}}}", map.join(", "))?;
+        writeln!(writer, "      end")
+    }
+
+    fn encode_fields(&self, fields: &[ast::FieldDecl]) -> (String, Vec<String>) {
+        let pattern = fields
+            .iter()
+            .enumerate()
+            .map(|(idx, f)| format!("{}: v{}", f.name, idx))
+            .collect::<Vec<_>>();
+        let encoded = fields
+            .iter()
+            .enumerate()
+            .map(|(idx, f)| self.encode_call(&f.typ, &format!("v{}", idx)))
+            .collect();
+        (format!("%{{{}}}", pattern.join(", ")), encoded)
+    }
+
+    fn generate_decl<W: io::Write>(&self, writer: &mut W, decl: &Decl) -> io::Result<()> {
+        let (decl, fields) = (decl.decl, &decl.fields);
+        writeln!(writer, "  defmodule {} do", module_name(decl.name()))?;
+        match decl {
+            ast::TopDecl::Atom(_) => unreachable!(),
+            ast::TopDecl::Option_(ref i) => {
+                writeln!(writer, "    @moduledoc \"The option `{}`.\"", i.name)?;
+                writeln!(writer)?;
+                writeln!(writer, "    def decode(<<>>), do: {{:ok, nil}}")?;
+                writeln!(
+                    writer,
+                    "    def decode(bin), do: {}",
+                    self.decode_call(&i.typ, "bin")
+                )?;
+                writeln!(writer)?;
+                writeln!(writer, "    def encode(nil), do: <<>>")?;
+                writeln!(
+                    writer,
+                    "    def encode(value), do: {}",
+                    self.encode_call(&i.typ, "value")
+                )?;
+            }
+            ast::TopDecl::Union(ref i) => {
+                writeln!(writer, "    @moduledoc \"The union `{}`.\"", i.name)?;
+                for (idx, item) in i.inner.iter().enumerate() {
+                    writeln!(writer)?;
+                    writeln!(
+                        writer,
+                        "    def decode(<<{}::little-32, bin::binary>>) do",
+                        idx
+                    )?;
+                    writeln!(
+                        writer,
+                        "      with {{:ok, value}} <- {}, do: {{:ok, {{:{}, value}}}}",
+                        self.decode_call(&item.typ, "bin"),
+                        item.typ.name()
+                    )?;
+                    writeln!(writer, "    end")?;
+                }
+                writeln!(writer)?;
+                writeln!(
+                    writer,
+                    "    def decode(<<_::little-32, _::binary>>), do: {{:error, :item_id}}"
+                )?;
+                writeln!(writer, "    def decode(_), do: {{:error, :header}}")?;
+                writeln!(writer)?;
+                for (idx, item) in i.inner.iter().enumerate() {
+                    let names = Some(item.typ.name())
+                        .into_iter()
+                        .chain(item.alias.as_deref());
+                    for name in names {
+                        writeln!(
+                            writer,
+                            "    def encode({{:{}, value}}), do: <<{}::little-32, {}::binary>>",
+                            name,
+                            idx,
+                            self.encode_call(&item.typ, "value")
+                        )?;
+                    }
+                }
+            }
+            ast::TopDecl::Array(ref i) => {
+                writeln!(writer, "    @moduledoc \"The array `{}`.\"", i.name)?;
+                writeln!(writer)?;
+                writeln!(writer, "    def size, do: {}", i.total_size())?;
+                writeln!(writer)?;
+                if i.typ.is_atom() {
+                    writeln!(
+                        writer,
+                        "    def decode(<<value::binary-size({})>>), do: {{:ok, value}}",
+                        i.item_count
+                    )?;
+                    writeln!(writer, "    def decode(_), do: {{:error, :total_size}}")?;
+                    writeln!(writer)?;
+                    writeln!(
+                        writer,
+                        "    def encode(value) when byte_size(value) == {}, do: value",
+                        i.item_count
+                    )?;
+                } else {
+                    writeln!(
+                        writer,
+                        "    def decode(bin) when byte_size(bin) == {},",
+                        i.total_size()
+                    )?;
+                    writeln!(
+                        writer,
+                        "      do: {}.decode_items(bin, {}, {})",
+                        self.root,
+                        i.item_size,
+                        self.decoder(&i.typ)
+                    )?;
+                    writeln!(writer)?;
+                    writeln!(writer, "    def decode(_), do: {{:error, :total_size}}")?;
+                    writeln!(writer)?;
+                    writeln!(
+                        writer,
+                        "    def encode(items) when length(items) == {},",
+                        i.item_count
+                    )?;
+                    writeln!(
+                        writer,
+                        "      do: IO.iodata_to_binary(Enum.map(items, {}))",
+                        self.encoder(&i.typ)
+                    )?;
+                }
+            }
+            ast::TopDecl::Struct(ref i) => {
+                writeln!(writer, "    @moduledoc \"The struct `{}`.\"", i.name)?;
+                writeln!(writer)?;
+                writeln!(writer, "    def size, do: {}", i.total_size())?;
+                writeln!(writer)?;
+                let pattern = fields
+                    .iter()
+                    .map(|f| format!("v{}::binary-size({})", f.index, f.size()))
+                    .collect::<Vec<_>>();
+                writeln!(writer, "    def decode(<<{}>>) do", pattern.join(", "))?;
+                self.decode_fields(writer, &i.inner, None)?;
+                writeln!(writer, "    end")?;
+                writeln!(writer)?;
+                writeln!(writer, "    def decode(_), do: {{:error, :total_size}}")?;
+                writeln!(writer)?;
+                let (pattern, encoded) = self.encode_fields(&i.inner);
+                let encoded = encoded
+                    .iter()
+                    .map(|call| format!("{}::binary", call))
+                    .collect::<Vec<_>>();
+                writeln!(writer, "    def encode({}) do", pattern)?;
+                writeln!(writer, "      <<{}>>", encoded.join(", "))?;
+                writeln!(writer, "    end")?;
+            }
+            ast::TopDecl::FixVec(ref i) => {
+                writeln!(writer, "    @moduledoc \"The fixvec `{}`.\"", i.name)?;
+                writeln!(writer)?;
+                let bits = i.count_size * 8;
+                writeln!(
+                    writer,
+                    "    def decode(<<count::little-{}, items::binary>>) when byte_size(items) == count * {} do",
+                    bits, i.item_size
+                )?;
+                if i.typ.is_atom() {
+                    writeln!(writer, "      {{:ok, items}}")?;
+                } else {
+                    writeln!(
+                        writer,
+                        "      {}.decode_items(items, {}, {})",
+                        self.root,
+                        i.item_size,
+                        self.decoder(&i.typ)
+                    )?;
+                }
+                writeln!(writer, "    end")?;
+                writeln!(writer)?;
+                writeln!(
+                    writer,
+                    "    def decode(<<_::little-{}, _::binary>>), do: {{:error, :total_size}}",
+                    bits
+                )?;
+                writeln!(writer, "    def decode(_), do: {{:error, :header}}")?;
+                writeln!(writer)?;
+                if i.typ.is_atom() {
+                    writeln!(
+                        writer,
+                        "    def encode(value) when is_binary(value), do: <<byte_size(value)::little-{}, value::binary>>",
+                        bits
+                    )?;
+                } else {
+                    writeln!(writer, "    def encode(items) when is_list(items) do")?;
+                    writeln!(
+                        writer,
+                        "      IO.iodata_to_binary([<<length(items)::little-{}>> | Enum.map(items, {})])",
+                        bits,
+                        self.encoder(&i.typ)
+                    )?;
+                    writeln!(writer, "    end")?;
+                }
+            }
+            ast::TopDecl::DynVec(ref i) => {
+                writeln!(writer, "    @moduledoc \"The dynvec `{}`.\"", i.name)?;
+                writeln!(writer)?;
+                writeln!(writer, "    def decode(bin) do")?;
+                writeln!(
+                    writer,
+                    "      with {{:ok, items}} <- {}.split_dynamic(bin),",
+                    self.root
+                )?;
+                writeln!(
+                    writer,
+                    "           do: {}.decode_all(items, {})",
+                    self.root,
+                    self.decoder(&i.typ)
+                )?;
+                writeln!(writer, "    end")?;
+                writeln!(writer)?;
+                writeln!(writer, "    def encode(items) when is_list(items) do")?;
+                writeln!(
+                    writer,
+                    "      {}.encode_dynamic(Enum.map(items, {}))",
+                    self.root,
+                    self.encoder(&i.typ)
+                )?;
+                writeln!(writer, "    end")?;
+            }
+            ast::TopDecl::Table(ref i) => {
+                writeln!(writer, "    @moduledoc \"The table `{}`.\"", i.name)?;
+                writeln!(writer)?;
+                writeln!(writer, "    def decode(bin) do")?;
+                let split = format!("{}.split_fields(bin, {})", self.root, i.inner.len());
+                self.decode_fields(writer, &i.inner, Some(&split))?;
+                writeln!(writer, "    end")?;
+                writeln!(writer)?;
+                let (pattern, encoded) = self.encode_fields(&i.inner);
+                writeln!(writer, "    def encode({}) do", pattern)?;
+                writeln!(
+                    writer,
+                    "      {}.encode_dynamic([{}])",
+                    self.root,
+                    encoded.join(", ")
+                )?;
+                writeln!(writer, "    end")?;
+            }
+        }
+        writeln!(writer, "  end")
+    }
+}
+
+impl super::LanguageGenerator for Generator {
+    fn generate<W: io::Write>(
+        writer: &mut W,
+        ast: &ast::Ast,
+        _options: &Options,
+    ) -> io::Result<()> {
+        writeln!(writer, "# Generated by Molecule {}", VERSION)?;
+        writeln!(writer, "# {}", Metadata::new(ast).comment())?;
+        writeln!(writer)?;
+        let context = Context {
+            root: module_name(&ast.namespace.to_camel()),
+        };
+        writeln!(writer, "defmodule {} do", context.root)?;
+        writeln!(
+            writer,
+            "  @moduledoc \"The types of the molecule schema `{}`.\"",
+            ast.namespace
+        )?;
+        write!(writer, "{}", RUNTIME)?;
+        for decl in &Model::new(ast).decls {
+            writeln!(writer)?;
+            context.generate_decl(writer, decl)?;
+        }
+        writeln!(writer, "end")
+    }
+}
@@ -0,0 +1,352 @@
+//! Example snippets for the Go code which is generated by `moleculec-go`.
+
+use std::fmt::Write as _;
+
+use case::CaseExt;
+
+use crate::{
+    ast::verified::{self as ast, DefaultContent, HasName},
+    examples::{file_stem, Sample, Value},
+    VERSION,
+};
+
+/// The package of the generated types if the snippets are in it.
+const DEFAULT_PACKAGE: &str = "types";
+
+pub(crate) fn generate_examples(samples: &[Sample], module: Option<&str>) -> Vec<(String, String)> {
+    let (package, prefix) = if module.is_some() {
+        ("examples", "types.")
+    } else {
+        (DEFAULT_PACKAGE, "")
+    };
+    let imports = |extra: &str| {
+        let mut imports = String::from("import (\n");
+        imports.push_str(extra);
+        if let Some(module) = module {
+            if !extra.is_empty() {
+                imports.push('\n');
+            }
+            writeln!(imports, "\ttypes \"{}\"", module).unwrap();
+        }
+        imports.push_str(")\n");
+        imports
+    };
+    let mut files = samples
+        .iter()
+        .map(|sample| {
+            let mut code = String::new();
+            writeln!(code, "// Generated by Molecule {}", VERSION).unwrap();
+            writeln!(code).unwrap();
+            writeln!(code, "package {}", package).unwrap();
+            writeln!(code).unwrap();
+            code.push_str(&imports("\t\"fmt\"\n"));
+            gen_example(sample, prefix, &mut code);
+            (format!("{}.go", file_stem(&sample.decl)), code)
+        })
+        .collect::<Vec<_>>();
+    let mut helpers = String::new();
+    writeln!(helpers, "// Generated by Molecule {}", VERSION).unwrap();
+    writeln!(helpers).unwrap();
+    writeln!(helpers, "package {}", package).unwrap();
+    if module.is_some() {
+        writeln!(helpers).unwrap();
+        helpers.push_str(&imports(""));
+    }
+    write!(
+        helpers,
+        "
+// bytesOf converts the raw bytes to the items of a vector.
+func bytesOf(data []byte) []{p}Byte {{
+\tbytes := make([]{p}Byte, len(data))
+\tfor i, b := range data {{
+\t\tbytes[i] = {p}NewByte(b)
+\t}}
+\treturn bytes
+}}
+",
+        p = prefix
+    )
+    .unwrap();
+    files.push(("helpers.go".to_owned(), helpers));
+    let mut fuzz = String::new();
+    writeln!(fuzz, "// Generated by Molecule {}", VERSION).unwrap();
+    writeln!(fuzz).unwrap();
+    writeln!(fuzz, "package {}", package).unwrap();
+    writeln!(fuzz).unwrap();
+    fuzz.push_str(&imports("\t\"bytes\"\n\t\"testing\"\n"));
+    for sample in samples {
+        gen_fuzz(sample, prefix, &mut fuzz);
+        let dir = format!("testdata/fuzz/FuzzFromSlice{}", type_name(&sample.decl));
+        let seeds = [
+            ("default", sample.decl.default_content()),
+            ("sample", sample.encode()),
+        ];
+        for (name, data) in &seeds {
+            files.push((format!("{}/{}", dir, name), seed_corpus(data)));
+        }
+    }
+    files.push(("fuzz_test.go".to_owned(), fuzz));
+    files
+}
+
+/// A fuzz target of the native fuzzing in Go 1.18, which reads a `{name}` from any bytes.
+///
+/// The seeds are the default value and the sample value, in `testdata/fuzz`.
+fn gen_fuzz(sample: &Sample, prefix: &str, code: &mut String) {
+    write!(
+        code,
+        "
+// FuzzFromSlice{name} checks that `{name}FromSlice` doesn't panic on any bytes, and that the
+// bytes it accepts are read back as they are.
+func FuzzFromSlice{name}(f *testing.F) {{
+\tf.Fuzz(func(t *testing.T, data []byte) {{
+\t\tvalue, err := {p}{name}FromSlice(data, false)
+\t\tif err != nil {{
+\t\t\treturn
+\t\t}}
+\t\tif !bytes.Equal(value.AsSlice(), data) {{
+\t\t\tt.Fatalf(\"read back %x from %x\", value.AsSlice(), data)
+\t\t}}
+\t}})
+}}
+",
+        name = type_name(&sample.decl),
+        p = prefix
+    )
+    .unwrap();
+}
+
+/// A file of the seed corpus, in the format of `go test`.
+fn seed_corpus(data: &[u8]) -> String {
+    let escaped = data
+        .iter()
+        .map(|byte| format!("\\x{:02x}", byte))
+        .collect::<String>();
+    format!("go test fuzz v1\n[]byte(\"{}\")\n", escaped)
+}
+
+fn gen_example(sample: &Sample, prefix: &str, code: &mut String) {
+    let name = type_name(&sample.decl);
+    let value = split_chain(&gen_value(sample, prefix));
+    write!(
+        code,
+        "
+// Build{name} builds a `{name}` with sample values.
+func Build{name}() {p}{name} {{
+\treturn {value}
+}}
+
+// Parse{name} parses a `{name}`, and prints the parts of it.
+func Parse{name}(slice []byte) error {{
+\tvalue, err := {p}{name}FromSlice(slice, false)
+\tif err != nil {{
+\t\treturn err
+\t}}
+",
+        name = name,
+        p = prefix,
+        value = value
+    )
+    .unwrap();
+    gen_parts(&sample.decl, code);
+    write!(
+        code,
+        "\treturn nil
+}}
+
+// Run{name} builds a `{name}`, and parses it back.
+func Run{name}() {{
+\tvalue := Build{name}()
+\tfmt.Printf(\"%x\\n\", value.AsSlice())
+\tif err := Parse{name}(value.AsSlice()); err != nil {{
+\t\tpanic(err)
+\t}}
+}}
+",
+        name = name
+    )
+    .unwrap();
+}
+
+/// Put each top-level method of a chain, such as the setters of a builder, in a line.
+fn split_chain(value: &str) -> String {
+    let mut output = String::new();
+    let mut depth = 0;
+    let mut prev = ' ';
+    for c in value.chars() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            '.' if depth == 0 && prev == ')' => {
+                output.push_str(".\n\t\t");
+                prev = c;
+                continue;
+            }
+            _ => {}
+        }
+        output.push(c);
+        prev = c;
+    }
+    output
+}
+
+fn type_name(decl: &ast::TopDecl) -> String {
+    if decl.is_atom() {
+        ast::ATOM_PRIMITIVE_NAME.to_owned()
+    } else {
+        decl.name().to_camel()
+    }
+}
+
+fn hex_bytes(data: &[u8]) -> String {
+    data.iter()
+        .map(|byte| format!("0x{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The value in one line; only the top-level setters are split into lines.
+fn gen_value(sample: &Sample, p: &str) -> String {
+    let name = type_name(&sample.decl);
+    match sample.value {
+        Value::Byte(byte) => format!("{}NewByte(0x{:02x})", p, byte),
+        Value::Bytes(ref data) => match sample.decl.as_ref() {
+            ast::TopDecl::Array(_) => format!(
+                "*{}{}FromSliceUnchecked([]byte{{{}}})",
+                p,
+                name,
+                hex_bytes(data)
+            ),
+            _ => {
+                let bytes = if data
+                    .iter()
+                    .all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+                {
+                    // The escapes of the printable ASCII are the same in Go.
+                    format!("[]byte({:?})", String::from_utf8_lossy(data))
+                } else {
+                    format!("[]byte{{{}}}", hex_bytes(data))
+                };
+                format!(
+                    "{}New{}Builder().Extend(bytesOf({})).Build()",
+                    p, name, bytes
+                )
+            }
+        },
+        Value::Uint(number) => {
+            let size = sample.decl.total_size().unwrap();
+            format!(
+                "*{}{}FromSliceUnchecked([]byte{{{}}})",
+                p,
+                name,
+                hex_bytes(&number.to_le_bytes()[..size])
+            )
+        }
+        Value::Fields(ref fields) => {
+            let mut value = format!("{}New{}Builder()", p, name);
+            for (field, sample) in fields {
+                write!(value, ".{}({})", field.to_camel(), gen_value(sample, p)).unwrap();
+            }
+            value.push_str(".Build()");
+            value
+        }
+        Value::Items(ref items) => {
+            let items = items
+                .iter()
+                .map(|item| gen_value(item, p))
+                .collect::<Vec<_>>();
+            match sample.decl.as_ref() {
+                ast::TopDecl::Array(ref typ) => format!(
+                    "{}New{}Builder().Set([{}]{}{}{{{}}}).Build()",
+                    p,
+                    name,
+                    typ.item_count,
+                    p,
+                    type_name(&typ.typ),
+                    items.join(", ")
+                ),
+                _ => {
+                    let pushes = items
+                        .iter()
+                        .map(|item| format!(".Push({})", item))
+                        .collect::<String>();
+                    format!("{}New{}Builder(){}.Build()", p, name, pushes)
+                }
+            }
+        }
+        Value::Some(ref inner) => {
+            format!(
+                "{}New{}Builder().Set({}).Build()",
+                p,
+                name,
+                gen_value(inner, p)
+            )
+        }
+        Value::Item(ref item) => format!(
+            "{}New{}Builder().Set({}{}UnionFrom{}({})).Build()",
+            p,
+            name,
+            p,
+            name,
+            type_name(&item.decl),
+            gen_value(item, p)
+        ),
+        Value::Default => format!("{}{}Default()", p, name),
+    }
+}
+
+/// Print the parts of a value.
+fn gen_parts(decl: &ast::TopDecl, code: &mut String) {
+    match decl {
+        ast::TopDecl::Atom(_) => {}
+        ast::TopDecl::Option_(_) => code.push_str(
+            "\tif value.IsNone() {
+\t\tfmt.Println(\"None\")
+\t} else {
+\t\tfmt.Printf(\"Some: %x\\n\", value.AsSlice())
+\t}
+",
+        ),
+        ast::TopDecl::Union(_) => code.push_str(
+            "\tfmt.Println(\"item id:\", value.ItemID())
+\tfmt.Printf(\"item: %x\\n\", value.ToUnion().AsSlice())
+",
+        ),
+        ast::TopDecl::Array(ref typ) if typ.typ.is_atom() => {
+            code.push_str("\tfmt.Printf(\"raw data: %x\\n\", value.RawData())\n");
+        }
+        ast::TopDecl::Array(ref typ) => {
+            for idx in 0..typ.item_count {
+                writeln!(
+                    code,
+                    "\tfmt.Printf(\"item {}: %x\\n\", value.Nth{}().AsSlice())",
+                    idx, idx
+                )
+                .unwrap();
+            }
+        }
+        ast::TopDecl::Struct(ref typ) => gen_fields(&typ.inner, code),
+        ast::TopDecl::Table(ref typ) => gen_fields(&typ.inner, code),
+        ast::TopDecl::FixVec(ref typ) if typ.typ.is_atom() => {
+            code.push_str("\tfmt.Printf(\"raw data: %x\\n\", value.RawData())\n");
+        }
+        ast::TopDecl::FixVec(_) | ast::TopDecl::DynVec(_) => code.push_str(
+            "\tfor i := uint(0); i < value.Len(); i++ {
+\t\tfmt.Printf(\"item %d: %x\\n\", i, value.Get(i).AsSlice())
+\t}
+",
+        ),
+    }
+}
+
+fn gen_fields(inner: &[ast::FieldDecl], code: &mut String) {
+    for field in inner {
+        writeln!(
+            code,
+            "\tfmt.Printf(\"{}: %x\\n\", value.{}().AsSlice())",
+            field.name,
+            field.name.to_camel()
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,497 @@
+//! A Haskell module of `Data.ByteString`-backed newtypes, which verify, read and build the types.
+//!
+//! Each type is a newtype which is an instance of the class `Molecule`, so it has
+//! `fromSlice :: Bool -> ByteString -> Either VerifyError T`, where the flag is the compatible
+//! mode, and `toBytes`. The getters, such as `<type><Field>`, `<type>Length` and `<type>At`, don't
+//! check the bytes again, and the builders are `mk<Type>`. A byte is a `Word8`.
+//!
+//! The module is named after the namespace, so the file should be renamed after it for GHC. The
+//! Prelude is imported qualified, so the types can have the names of the types in the Prelude,
+//! such as `Word`. The imported types are generated into the same module, so the file doesn't
+//! depend on the files of the imported schemas.
+
+use std::io;
+
+use case::CaseExt;
+
+use super::model::{Decl, Model};
+use crate::{
+    ast::verified::{self as ast, HasName},
+    generator::{metadata::Metadata, Options},
+    VERSION,
+};
+
+pub(crate) struct Generator;
+
+const IMPORTS: &str = r#"import Data.Bits (shiftL, shiftR, (.&.), (.|.))
+import qualified Data.ByteString as BS
+import qualified Data.Word as W
+import Prelude ((&&), (*), (+), (++), (-), (/=), (<), (<=), (==), (>), (>=), (||))
+import qualified Prelude as P
+"#;
+
+// The helpers which are shared by the types.
+const RUNTIME: &str = r#"data VerifyError = ErrTotalSize | ErrHeader | ErrOffset | ErrFieldCount | ErrUnknownItem
+  deriving (P.Eq, P.Show)
+
+class Molecule a where
+  -- | Verifies the bytes, in the compatible mode if the flag is set.
+  fromSlice :: P.Bool -> BS.ByteString -> P.Either VerifyError a
+  toBytes :: a -> BS.ByteString
+
+instance Molecule W.Word8 where
+  fromSlice _ bs
+    | BS.length bs == 1 = P.Right (BS.index bs 0)
+    | P.otherwise = P.Left ErrTotalSize
+  toBytes = BS.singleton
+
+molVerify :: P.Either VerifyError a -> P.Either VerifyError ()
+molVerify = P.fmap (P.const ())
+
+molCheck :: P.Bool -> VerifyError -> P.Either VerifyError ()
+molCheck ok err = if ok then P.Right () else P.Left err
+
+molReadNumber :: BS.ByteString -> P.Int -> P.Int -> P.Int
+molReadNumber bs pos size =
+  P.foldr (\i acc -> shiftL acc 8 .|. P.fromIntegral (BS.index bs (pos + i))) 0 [0 .. size - 1]
+
+molPackNumber :: P.Int -> P.Int -> BS.ByteString
+molPackNumber size value = BS.pack [P.fromIntegral (shiftR value (8 * i) .&. 0xff) | i <- [0 .. size - 1]]
+
+molSlice :: BS.ByteString -> P.Int -> P.Int -> BS.ByteString
+molSlice bs start size = BS.take size (BS.drop start bs)
+
+-- | Verifies the header of a dynvec or a table, and returns the count of the items.
+molVerifyDynamic :: BS.ByteString -> P.Either VerifyError P.Int
+molVerifyDynamic bs
+  | size < 4 = P.Left ErrHeader
+  | total /= size = P.Left ErrTotalSize
+  | total == 4 = P.Right 0
+  | total < 8 = P.Left ErrHeader
+  | P.rem first 4 /= 0 || first < 8 || first > total = P.Left ErrOffset
+  | P.and (P.zipWith (<=) offsets (P.tail offsets ++ [total])) = P.Right count
+  | P.otherwise = P.Left ErrOffset
+  where
+    size = BS.length bs
+    total = molReadNumber bs 0 4
+    first = molReadNumber bs 4 4
+    count = P.quot first 4 - 1
+    offsets = [molReadNumber bs (4 + 4 * i) 4 | i <- [0 .. count - 1]]
+
+molDynamicLength :: BS.ByteString -> P.Int
+molDynamicLength bs = if BS.length bs == 4 then 0 else P.quot (molReadNumber bs 4 4) 4 - 1
+
+molDynamicAt :: BS.ByteString -> P.Int -> BS.ByteString
+molDynamicAt bs i = molSlice bs start (end - start)
+  where
+    start = molReadNumber bs (4 + 4 * i) 4
+    end = if i + 1 == molDynamicLength bs then BS.length bs else molReadNumber bs (8 + 4 * i) 4
+
+molPackDynamic :: [BS.ByteString] -> BS.ByteString
+molPackDynamic items = BS.concat (molPackNumber 4 total : offsets ++ items)
+  where
+    starts = P.scanl (+) (4 * (P.length items + 1)) (P.map BS.length items)
+    total = P.last starts
+    offsets = P.map (molPackNumber 4) (P.init starts)
+"#;
+
+fn type_name(typ: &ast::TopDecl) -> String {
+    if typ.is_atom() {
+        "W.Word8".to_owned()
+    } else {
+        typ.name().to_camel()
+    }
+}
+
+/// The prefix of the functions of the type, which is the name of the type in lower camel case.
+fn function_prefix(typ: &ast::TopDecl) -> String {
+    let name = typ.name().to_camel();
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+fn verify_stmt(typ: &ast::TopDecl, slice: &str) -> String {
+    format!(
+        "molVerify (fromSlice compatible {} :: P.Either VerifyError {})",
+        slice,
+        type_name(typ)
+    )
+}
+
+/// The value of the type in a slice, which has been verified.
+fn read_expr(typ: &ast::TopDecl, slice: &str) -> String {
+    let slice = if slice.contains(' ') {
+        format!("({})", slice)
+    } else {
+        slice.to_owned()
+    };
+    if typ.is_atom() {
+        format!("BS.index {} 0", slice)
+    } else {
+        format!("{} {}", type_name(typ), slice)
+    }
+}
+
+impl Generator {
+    fn write_newtype<W: io::Write>(
+        writer: &mut W,
+        kind: &str,
+        decl: &ast::TopDecl,
+    ) -> io::Result<()> {
+        let name = type_name(decl);
+        writeln!(writer)?;
+        writeln!(writer, "-- | The {} @{}@.", kind, decl.name())?;
+        writeln!(writer, "newtype {0} = {0} BS.ByteString", name)?;
+        writeln!(writer, "  deriving (P.Eq, P.Show)")
+    }
+
+    fn write_instance<W: io::Write>(
+        writer: &mut W,
+        decl: &ast::TopDecl,
+        stmts: &[String],
+    ) -> io::Result<()> {
+        let name = type_name(decl);
+        writeln!(writer)?;
+        writeln!(writer, "instance Molecule {} where", name)?;
+        let compatible = if stmts.iter().any(|stmt| stmt.contains("compatible")) {
+            "compatible"
+        } else {
+            "_"
+        };
+        writeln!(writer, "  fromSlice {} bs = do", compatible)?;
+        for stmt in stmts {
+            writeln!(writer, "    {}", stmt)?;
+        }
+        writeln!(writer, "    P.Right ({} bs)", name)?;
+        writeln!(writer, "  toBytes ({} bs) = bs", name)
+    }
+
+    fn write_fixed_instance<W: io::Write>(
+        writer: &mut W,
+        decl: &ast::TopDecl,
+        size: usize,
+    ) -> io::Result<()> {
+        let name = type_name(decl);
+        writeln!(writer)?;
+        writeln!(writer, "instance Molecule {} where", name)?;
+        writeln!(writer, "  fromSlice _ bs")?;
+        writeln!(
+            writer,
+            "    | BS.length bs == {} = P.Right ({} bs)",
+            size, name
+        )?;
+        writeln!(writer, "    | P.otherwise = P.Left ErrTotalSize")?;
+        writeln!(writer, "  toBytes ({} bs) = bs", name)
+    }
+
+    fn write_fn<W: io::Write>(
+        writer: &mut W,
+        name: &str,
+        signature: &str,
+        equation: &str,
+    ) -> io::Result<()> {
+        writeln!(writer)?;
+        writeln!(writer, "{} :: {}", name, signature)?;
+        writeln!(writer, "{} {}", name, equation)
+    }
+
+    /// The length, the indexing and the list of the items of a vector.
+    fn write_items<W: io::Write>(
+        writer: &mut W,
+        decl: &ast::TopDecl,
+        item: &ast::TopDecl,
+        length: &str,
+        at: &str,
+    ) -> io::Result<()> {
+        let name = type_name(decl);
+        let prefix = function_prefix(decl);
+        Self::write_fn(
+            writer,
+            &format!("{}Length", prefix),
+            &format!("{} -> P.Int", name),
+            &if length.contains("bs") {
+                format!("({} bs) = {}", name, length)
+            } else {
+                format!("_ = {}", length)
+            },
+        )?;
+        Self::write_fn(
+            writer,
+            &format!("{}At", prefix),
+            &format!("{} -> P.Int -> {}", name, type_name(item)),
+            &format!("({} bs) i = {}", name, read_expr(item, at)),
+        )?;
+        Self::write_fn(
+            writer,
+            &format!("{}Items", prefix),
+            &format!("{} -> [{}]", name, type_name(item)),
+            &format!("v = P.map ({}At v) [0 .. {}Length v - 1]", prefix, prefix),
+        )
+    }
+
+    /// The getters and the builder of a struct or a table.
+    fn write_fields<W: io::Write>(
+        writer: &mut W,
+        decl: &ast::TopDecl,
+        fields: &[ast::FieldDecl],
+        slices: &[String],
+        pack: &str,
+    ) -> io::Result<()> {
+        let name = type_name(decl);
+        let prefix = function_prefix(decl);
+        for (f, slice) in fields.iter().zip(slices) {
+            Self::write_fn(
+                writer,
+                &format!("{}{}", prefix, f.name.to_camel()),
+                &format!("{} -> {}", name, type_name(&f.typ)),
+                &format!("({} bs) = {}", name, read_expr(&f.typ, slice)),
+            )?;
+        }
+        let types = fields
+            .iter()
+            .map(|f| format!("{} -> ", type_name(&f.typ)))
+            .collect::<String>();
+        let params = (0..fields.len())
+            .map(|idx| format!("v{} ", idx))
+            .collect::<String>();
+        let bytes = (0..fields.len())
+            .map(|idx| format!("toBytes v{}", idx))
+            .collect::<Vec<_>>();
+        Self::write_fn(
+            writer,
+            &format!("mk{}", name),
+            &format!("{}{}", types, name),
+            &format!("{}= {} ({} [{}])", params, name, pack, bytes.join(", ")),
+        )
+    }
+
+    fn generate_decl<W: io::Write>(writer: &mut W, decl: &Decl) -> io::Result<()> {
+        let (decl, fields) = (decl.decl, &decl.fields);
+        let name = type_name(decl);
+        let prefix = function_prefix(decl);
+        match decl {
+            ast::TopDecl::Atom(_) => unreachable!(),
+            ast::TopDecl::Option_(ref i) => {
+                Self::write_newtype(writer, "option", decl)?;
+                let stmts = vec![format!(
+                    "if BS.null bs then P.Right () else {}",
+                    verify_stmt(&i.typ, "bs")
+                )];
+                Self::write_instance(writer, decl, &stmts)?;
+                Self::write_fn(
+                    writer,
+                    &format!("{}Get", prefix),
+                    &format!("{} -> P.Maybe {}", name, type_name(&i.typ)),
+                    &format!(
+                        "({} bs) = if BS.null bs then P.Nothing else P.Just ({})",
+                        name,
+                        read_expr(&i.typ, "bs")
+                    ),
+                )?;
+                Self::write_fn(
+                    writer,
+                    &format!("mk{}", name),
+                    &format!("P.Maybe {} -> {}", type_name(&i.typ), name),
+                    &format!("v = {} (P.maybe BS.empty toBytes v)", name),
+                )?;
+            }
+            ast::TopDecl::Union(ref i) => {
+                let item = format!("{}Item", name);
+                let constructors = i
+                    .inner
+                    .iter()
+                    .map(|item| format!("{}{}", name, item.typ.name().to_camel()))
+                    .collect::<Vec<_>>();
+                Self::write_newtype(writer, "union", decl)?;
+                writeln!(writer)?;
+                writeln!(writer, "-- | The items of the union @{}@.", i.name)?;
+                writeln!(writer, "data {}", item)?;
+                for (idx, (c, inner)) in constructors.iter().zip(&i.inner).enumerate() {
+                    let sep = if idx == 0 { '=' } else { '|' };
+                    writeln!(writer, "  {} {} {}", sep, c, type_name(&inner.typ))?;
+                }
+                writeln!(writer, "  deriving (P.Eq, P.Show)")?;
+                let mut stmts = vec![
+                    "molCheck (BS.length bs >= 4) ErrHeader".to_owned(),
+                    "let body = BS.drop 4 bs".to_owned(),
+                    "case molReadNumber bs 0 4 of".to_owned(),
+                ];
+                for (idx, inner) in i.inner.iter().enumerate() {
+                    stmts.push(format!("  {} -> {}", idx, verify_stmt(&inner.typ, "body")));
+                }
+                stmts.push("  _ -> P.Left ErrUnknownItem".to_owned());
+                Self::write_instance(writer, decl, &stmts)?;
+                Self::write_fn(
+                    writer,
+                    &format!("{}ItemId", prefix),
+                    &format!("{} -> P.Int", name),
+                    &format!("({} bs) = molReadNumber bs 0 4", name),
+                )?;
+                writeln!(writer)?;
+                writeln!(writer, "{}Item :: {} -> {}", prefix, name, item)?;
+                writeln!(
+                    writer,
+                    "{}Item ({} bs) = case molReadNumber bs 0 4 of",
+                    prefix, name
+                )?;
+                for (idx, (c, inner)) in constructors.iter().zip(&i.inner).enumerate() {
+                    writeln!(
+                        writer,
+                        "  {} -> {} ({})",
+                        idx,
+                        c,
+                        read_expr(&inner.typ, "BS.drop 4 bs")
+                    )?;
+                }
+                writeln!(
+                    writer,
+                    "  _ -> P.error \"{}Item: the bytes are not verified\"",
+                    prefix
+                )?;
+                writeln!(writer)?;
+                writeln!(writer, "mk{} :: {} -> {}", name, item, name)?;
+                for (idx, c) in constructors.iter().enumerate() {
+                    writeln!(
+                        writer,
+                        "mk{} ({} v) = {} (BS.append (molPackNumber 4 {}) (toBytes v))",
+                        name, c, name, idx
+                    )?;
+                }
+            }
+            ast::TopDecl::Array(ref i) => {
+                Self::write_newtype(writer, "array", decl)?;
+                Self::write_fixed_instance(writer, decl, i.total_size())?;
+                Self::write_items(
+                    writer,
+                    decl,
+                    &i.typ,
+                    &i.item_count.to_string(),
+                    &format!("molSlice bs (i * {0}) {0}", i.item_size),
+                )?;
+                Self::write_fn(
+                    writer,
+                    &format!("mk{}", name),
+                    &format!("[{}] -> P.Maybe {}", type_name(&i.typ), name),
+                    &format!(
+                        "vs = if P.length vs == {} then P.Just ({} (BS.concat (P.map toBytes vs))) else P.Nothing",
+                        i.item_count, name
+                    ),
+                )?;
+            }
+            ast::TopDecl::Struct(ref i) => {
+                Self::write_newtype(writer, "struct", decl)?;
+                Self::write_fixed_instance(writer, decl, i.total_size())?;
+                let slices = fields
+                    .iter()
+                    .map(|f| format!("molSlice bs {} {}", f.offset(), f.size()))
+                    .collect::<Vec<_>>();
+                Self::write_fields(writer, decl, &i.inner, &slices, "BS.concat")?;
+            }
+            ast::TopDecl::FixVec(ref i) => {
+                Self::write_newtype(writer, "fixvec", decl)?;
+                let stmts = vec![
+                    format!("molCheck (BS.length bs >= {}) ErrHeader", i.count_size),
+                    format!(
+                        "molCheck (BS.length bs == {} + molReadNumber bs 0 {} * {}) ErrTotalSize",
+                        i.count_size, i.count_size, i.item_size
+                    ),
+                ];
+                Self::write_instance(writer, decl, &stmts)?;
+                Self::write_items(
+                    writer,
+                    decl,
+                    &i.typ,
+                    &format!("molReadNumber bs 0 {}", i.count_size),
+                    &format!(
+                        "molSlice bs ({} + i * {}) {}",
+                        i.count_size, i.item_size, i.item_size
+                    ),
+                )?;
+                Self::write_fn(
+                    writer,
+                    &format!("mk{}", name),
+                    &format!("[{}] -> {}", type_name(&i.typ), name),
+                    &format!(
+                        "vs = {} (BS.concat (molPackNumber {} (P.length vs) : P.map toBytes vs))",
+                        name, i.count_size
+                    ),
+                )?;
+            }
+            ast::TopDecl::DynVec(ref i) => {
+                Self::write_newtype(writer, "dynvec", decl)?;
+                let stmts = vec![
+                    "count <- molVerifyDynamic bs".to_owned(),
+                    format!(
+                        "P.mapM_ (\\i -> {}) [0 .. count - 1]",
+                        verify_stmt(&i.typ, "(molDynamicAt bs i)")
+                    ),
+                ];
+                Self::write_instance(writer, decl, &stmts)?;
+                Self::write_items(
+                    writer,
+                    decl,
+                    &i.typ,
+                    "molDynamicLength bs",
+                    "molDynamicAt bs i",
+                )?;
+                Self::write_fn(
+                    writer,
+                    &format!("mk{}", name),
+                    &format!("[{}] -> {}", type_name(&i.typ), name),
+                    &format!("vs = {} (molPackDynamic (P.map toBytes vs))", name),
+                )?;
+            }
+            ast::TopDecl::Table(ref i) => {
+                let count = i.inner.len();
+                Self::write_newtype(writer, "table", decl)?;
+                let check = if count == 0 {
+                    "compatible || count == 0".to_owned()
+                } else {
+                    format!("count >= {0} && (compatible || count == {0})", count)
+                };
+                let mut stmts = vec![
+                    "count <- molVerifyDynamic bs".to_owned(),
+                    format!("molCheck ({}) ErrFieldCount", check),
+                ];
+                for (idx, f) in i.inner.iter().enumerate() {
+                    stmts.push(verify_stmt(&f.typ, &format!("(molDynamicAt bs {})", idx)));
+                }
+                Self::write_instance(writer, decl, &stmts)?;
+                let slices = (0..count)
+                    .map(|idx| format!("molDynamicAt bs {}", idx))
+                    .collect::<Vec<_>>();
+                Self::write_fields(writer, decl, &i.inner, &slices, "molPackDynamic")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl super::LanguageGenerator for Generator {
+    fn generate<W: io::Write>(
+        writer: &mut W,
+        ast: &ast::Ast,
+        _options: &Options,
+    ) -> io::Result<()> {
+        writeln!(writer, "-- Generated by Molecule {}", VERSION)?;
+        writeln!(writer, "-- {}", Metadata::new(ast).comment())?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "-- | The types of the molecule schema @{}@.",
+            ast.namespace
+        )?;
+        writeln!(writer, "module {} where", ast.namespace.to_camel())?;
+        writeln!(writer)?;
+        write!(writer, "{}", IMPORTS)?;
+        writeln!(writer)?;
+        write!(writer, "{}", RUNTIME)?;
+        for decl in &Model::new(ast).decls {
+            Self::generate_decl(writer, decl)?;
+        }
+        Ok(())
+    }
+}
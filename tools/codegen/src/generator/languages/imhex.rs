@@ -0,0 +1,194 @@
+//! An ImHex pattern of the types, to inspect molecule files in the pattern editor of ImHex 1.28
+//! or later.
+//!
+//! The types are in the namespace of the schema, and a buffer is viewed by placing a type at its
+//! address, such as `ci_tests::Table2 value @ 0x00;`. The items and the fields of dynvecs and
+//! tables are read in order after their headers, and an option or a union takes its size as a
+//! template parameter, which is given by the offsets of the dynvec or the table which contains it,
+//! or by the size of the buffer at the top level, such as
+//! `ci_tests::UnionA<std::mem::size()> value @ 0x00;`. The pattern doesn't verify the buffers.
+//!
+//! The pattern is rendered from the templates in `templates/imhex`, with a data model of each
+//! type whose template is named by its kind.
+
+use std::io;
+
+use serde_json::{json, Value};
+
+use super::model::{Decl, Model};
+use crate::{
+    ast::verified::{self as ast, HasName},
+    generator::{metadata::Metadata, template::Templates, Options},
+    VERSION,
+};
+
+pub(crate) struct Generator;
+
+// The keywords of the pattern language and the locals of the headers, which the fields can't be
+// named after.
+const RESERVED: &[&str] = &[
+    "auto",
+    "be",
+    "bitfield",
+    "bool",
+    "break",
+    "char",
+    "continue",
+    "count",
+    "else",
+    "enum",
+    "false",
+    "fn",
+    "for",
+    "if",
+    "import",
+    "in",
+    "index",
+    "item",
+    "item_id",
+    "items",
+    "le",
+    "length",
+    "match",
+    "namespace",
+    "offsets",
+    "out",
+    "padding",
+    "parent",
+    "ref",
+    "return",
+    "size",
+    "struct",
+    "this",
+    "total_size",
+    "true",
+    "union",
+    "unknown_fields",
+    "using",
+    "value",
+    "while",
+];
+
+fn field_name(name: &str) -> String {
+    if RESERVED.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_owned()
+    }
+}
+
+/// The type of a count of a fixvec, which could be set by `@count_size`.
+fn count_type(count_size: usize) -> String {
+    format!("u{}", count_size * 8)
+}
+
+/// Whether the type is sized by its container, since its size isn't in its bytes.
+fn is_sized(typ: &ast::TopDecl) -> bool {
+    matches!(typ, ast::TopDecl::Option_(_) | ast::TopDecl::Union(_))
+}
+
+/// The type of a value; the size of the value is only given to the sized types.
+fn type_name(typ: &ast::TopDecl, size: &str) -> String {
+    if typ.is_atom() {
+        "u8".to_owned()
+    } else if is_sized(typ) {
+        format!("{}<{}>", typ.name(), size)
+    } else {
+        typ.name().to_owned()
+    }
+}
+
+/// The size of an item of a dynvec or a field of a table, from the offsets of `header`.
+fn item_size(header: &str, index: &str, next: &str) -> String {
+    format!(
+        "({0}count == {2} ? {0}total_size : {0}offsets[{2}]) - {0}offsets[{1}]",
+        header, index, next
+    )
+}
+
+/// The data model of a type, which is rendered by the template of its kind.
+fn decl_model(decl: &Decl) -> Value {
+    let name = decl.name();
+    let fields = &decl.fields;
+    let (kind, mut model) = match decl.decl {
+        ast::TopDecl::Atom(_) => unreachable!(),
+        ast::TopDecl::Option_(ref i) => {
+            ("option", json!({ "item_type": type_name(&i.typ, "size") }))
+        }
+        ast::TopDecl::Union(ref i) => {
+            let item_types: Vec<_> = i
+                .inner
+                .iter()
+                .map(|inner| type_name(&inner.typ, "size - 4"))
+                .collect();
+            ("union", json!({ "item_types": item_types }))
+        }
+        ast::TopDecl::Array(ref i) => (
+            "array",
+            json!({
+                "item_type": type_name(&i.typ, ""),
+                "item_count": i.item_count,
+                "total_size": i.total_size(),
+            }),
+        ),
+        ast::TopDecl::Struct(ref i) => {
+            let fields: Vec<_> = fields
+                .iter()
+                .map(|f| json!({ "type": type_name(f.typ, ""), "name": field_name(f.name) }))
+                .collect();
+            (
+                "struct",
+                json!({ "fields": fields, "total_size": i.total_size() }),
+            )
+        }
+        ast::TopDecl::FixVec(ref i) => (
+            "fixvec",
+            json!({
+                "item_type": type_name(&i.typ, ""),
+                "item_size": i.item_size,
+                "count_type": count_type(i.count_size),
+            }),
+        ),
+        ast::TopDecl::DynVec(ref i) => {
+            // An array has items of the same type, so each item is sized by a wrapper.
+            let model = if is_sized(&i.typ) {
+                json!({
+                    "item_type": format!("{}_Item", name),
+                    "sized_item_type": type_name(&i.typ, &item_size("parent.", "index", "index + 1")),
+                })
+            } else {
+                json!({ "item_type": type_name(&i.typ, ""), "sized_item_type": null })
+            };
+            ("dynvec", model)
+        }
+        ast::TopDecl::Table(ref i) => {
+            let fields: Vec<_> = fields
+                .iter()
+                .map(|f| {
+                    let size = item_size("", &f.index.to_string(), &(f.index + 1).to_string());
+                    json!({ "type": type_name(f.typ, &size), "name": field_name(f.name) })
+                })
+                .collect();
+            (
+                "table",
+                json!({ "fields": fields, "field_count": i.inner.len() }),
+            )
+        }
+    };
+    model["name"] = json!(name);
+    model["template"] = json!(format!("imhex/{}.hexpat", kind));
+    model
+}
+
+impl super::LanguageGenerator for Generator {
+    fn generate<W: io::Write>(writer: &mut W, ast: &ast::Ast, options: &Options) -> io::Result<()> {
+        let decls: Vec<_> = Model::sorted(ast).decls.iter().map(decl_model).collect();
+        let model = json!({
+            "version": VERSION,
+            "metadata": Metadata::new(ast).comment(),
+            "namespace": ast.namespace,
+            "decls": decls,
+        });
+        Templates::new(options.templates_dir.as_deref()).render(writer, "imhex/file.hexpat", &model)
+    }
+}
@@ -0,0 +1,266 @@
+//! Kaitai Struct definitions of the types, for the Kaitai WebIDE and the other tools which read
+//! `.ksy` files, with Kaitai Struct 0.9 or later.
+//!
+//! The types are under `types`, in snake case, and the root type is empty, so a buffer is viewed
+//! by a `.ksy` which imports this file and reads the type, such as `ci_tests::struct_a`. The items
+//! and the fields of dynvecs and tables are read as substreams of the sizes in their headers, and
+//! the unknown fields of a table in the compatible mode are kept as `unknown_fields`. The
+//! definitions don't verify the buffers.
+
+use std::io;
+
+use case::CaseExt;
+
+use crate::{
+    ast::verified::{self as ast, HasName},
+    generator::{metadata::Metadata, Options},
+    VERSION,
+};
+
+pub(crate) struct Generator;
+
+// The ids of the headers, which the fields can't be named after.
+const HEADERS: &[&str] = &[
+    "count",
+    "first_offset",
+    "item",
+    "item_id",
+    "items",
+    "length",
+    "next_offsets",
+    "total_size",
+    "unknown_fields",
+];
+
+fn type_name(typ: &ast::TopDecl) -> String {
+    if typ.is_atom() {
+        "u1".to_owned()
+    } else {
+        typ.name().to_snake()
+    }
+}
+
+/// The type of the count of a fixvec, which has 3 bytes only if it's set by `@count_size`.
+fn count_type(count_size: usize) -> String {
+    if count_size == 3 {
+        "b24le".to_owned()
+    } else {
+        format!("u{}", count_size)
+    }
+}
+
+fn field_id(name: &str) -> String {
+    let id = name.to_snake();
+    if HEADERS.contains(&id.as_str()) {
+        format!("{}_field", id)
+    } else {
+        id
+    }
+}
+
+/// The size of an item of a dynvec, from the offsets in its header.
+fn item_size() -> &'static str {
+    "(_index + 1 == count ? total_size : next_offsets[_index]) - (_index == 0 ? first_offset : next_offsets[_index - 1])"
+}
+
+/// The size of a field of a table, from the offsets in its header.
+fn field_size(idx: usize) -> String {
+    let start = if idx == 0 {
+        "first_offset".to_owned()
+    } else {
+        format!("next_offsets[{}]", idx - 1)
+    };
+    format!(
+        "(count == {} ? total_size : next_offsets[{}]) - {}",
+        idx + 1,
+        idx,
+        start
+    )
+}
+
+/// The attributes of a sized item; the size of a byte can't be given.
+fn sized(typ: &ast::TopDecl, size: &str) -> Vec<String> {
+    let mut attrs = vec![attr("type", &type_name(typ))];
+    if !typ.is_atom() {
+        attrs.push(attr("size", size));
+    }
+    attrs
+}
+
+/// An attribute of a `seq` item, which is quoted if it's an expression.
+fn attr(key: &str, value: &str) -> String {
+    if value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+    {
+        format!("{}: {}", key, value)
+    } else {
+        format!("{}: '{}'", key, value)
+    }
+}
+
+impl Generator {
+    fn write_seq_item<W: io::Write>(writer: &mut W, id: &str, attrs: &[String]) -> io::Result<()> {
+        writeln!(writer, "      - id: {}", id)?;
+        for attr in attrs {
+            writeln!(writer, "        {}", attr)?;
+        }
+        Ok(())
+    }
+
+    /// The header of a dynvec or a table, and the instance of the count of its items.
+    fn write_dynamic_header<W: io::Write>(writer: &mut W) -> io::Result<()> {
+        Self::write_seq_item(writer, "total_size", &[attr("type", "u4")])?;
+        Self::write_seq_item(
+            writer,
+            "first_offset",
+            &[attr("type", "u4"), attr("if", "total_size > 4")],
+        )?;
+        Self::write_seq_item(
+            writer,
+            "next_offsets",
+            &[
+                attr("type", "u4"),
+                attr("repeat", "expr"),
+                attr("repeat-expr", "count - 1"),
+                attr("if", "total_size > 4"),
+            ],
+        )
+    }
+
+    fn write_count<W: io::Write>(writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "    instances:")?;
+        writeln!(writer, "      count:")?;
+        writeln!(
+            writer,
+            "        {}",
+            attr("value", "total_size > 4 ? first_offset / 4 - 1 : 0")
+        )
+    }
+
+    fn generate_decl<W: io::Write>(writer: &mut W, decl: &ast::TopDecl) -> io::Result<()> {
+        writeln!(writer, "  {}:", type_name(decl))?;
+        match decl {
+            ast::TopDecl::Atom(_) => unreachable!(),
+            ast::TopDecl::Option_(ref i) => {
+                writeln!(
+                    writer,
+                    "    doc: The option {}, which is empty for None.",
+                    i.name
+                )?;
+                writeln!(writer, "    seq:")?;
+                Self::write_seq_item(
+                    writer,
+                    "value",
+                    &[attr("type", &type_name(&i.typ)), attr("if", "not _io.eof")],
+                )?;
+            }
+            ast::TopDecl::Union(ref i) => {
+                writeln!(
+                    writer,
+                    "    doc: The union {}, where the item ID is the index.",
+                    i.name
+                )?;
+                writeln!(writer, "    seq:")?;
+                Self::write_seq_item(writer, "item_id", &[attr("type", "u4")])?;
+                writeln!(writer, "      - id: item")?;
+                writeln!(writer, "        type:")?;
+                writeln!(writer, "          switch-on: item_id")?;
+                writeln!(writer, "          cases:")?;
+                for (idx, inner) in i.inner.iter().enumerate() {
+                    writeln!(writer, "            {}: {}", idx, type_name(&inner.typ))?;
+                }
+            }
+            ast::TopDecl::Array(ref i) => {
+                writeln!(writer, "    doc: The array {}.", i.name)?;
+                writeln!(writer, "    seq:")?;
+                Self::write_seq_item(
+                    writer,
+                    "items",
+                    &[
+                        attr("type", &type_name(&i.typ)),
+                        attr("repeat", "expr"),
+                        attr("repeat-expr", &i.item_count.to_string()),
+                    ],
+                )?;
+            }
+            ast::TopDecl::Struct(ref i) => {
+                writeln!(writer, "    doc: The struct {}.", i.name)?;
+                writeln!(writer, "    seq:")?;
+                for f in &i.inner {
+                    Self::write_seq_item(
+                        writer,
+                        &field_id(&f.name),
+                        &[attr("type", &type_name(&f.typ))],
+                    )?;
+                }
+            }
+            ast::TopDecl::FixVec(ref i) => {
+                writeln!(writer, "    doc: The fixvec {}.", i.name)?;
+                writeln!(writer, "    seq:")?;
+                Self::write_seq_item(writer, "length", &[attr("type", &count_type(i.count_size))])?;
+                Self::write_seq_item(
+                    writer,
+                    "items",
+                    &[
+                        attr("type", &type_name(&i.typ)),
+                        attr("repeat", "expr"),
+                        attr("repeat-expr", "length"),
+                    ],
+                )?;
+            }
+            ast::TopDecl::DynVec(ref i) => {
+                writeln!(writer, "    doc: The dynvec {}.", i.name)?;
+                writeln!(writer, "    seq:")?;
+                Self::write_dynamic_header(writer)?;
+                Self::write_seq_item(
+                    writer,
+                    "items",
+                    &[
+                        sized(&i.typ, item_size()),
+                        vec![attr("repeat", "expr"), attr("repeat-expr", "count")],
+                    ]
+                    .concat(),
+                )?;
+                Self::write_count(writer)?;
+            }
+            ast::TopDecl::Table(ref i) => {
+                writeln!(writer, "    doc: The table {}.", i.name)?;
+                writeln!(writer, "    seq:")?;
+                Self::write_dynamic_header(writer)?;
+                for (idx, f) in i.inner.iter().enumerate() {
+                    Self::write_seq_item(
+                        writer,
+                        &field_id(&f.name),
+                        &sized(&f.typ, &field_size(idx)),
+                    )?;
+                }
+                Self::write_seq_item(writer, "unknown_fields", &[attr("size-eos", "true")])?;
+                Self::write_count(writer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl super::LanguageGenerator for Generator {
+    fn generate<W: io::Write>(
+        writer: &mut W,
+        ast: &ast::Ast,
+        _options: &Options,
+    ) -> io::Result<()> {
+        writeln!(writer, "# Generated by Molecule {}", VERSION)?;
+        writeln!(writer, "# {}", Metadata::new(ast).comment())?;
+        writeln!(writer)?;
+        writeln!(writer, "meta:")?;
+        writeln!(writer, "  id: {}", ast.namespace.to_snake())?;
+        writeln!(writer, "  title: The molecule schema {}", ast.namespace)?;
+        writeln!(writer, "  ks-version: 0.9")?;
+        writeln!(writer, "  endian: le")?;
+        writeln!(writer, "types:")?;
+        for decl in ast.decls().iter().filter(|decl| !decl.is_atom()) {
+            Self::generate_decl(writer, decl)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,277 @@
+//! A Lua module which decodes the types, in pure Lua 5.1, so it runs on LuaJIT as well.
+//!
+//! Each type is a table in the module, which has `decode(data)`; it verifies the string in the
+//! strict mode, and returns the value, or `nil` and the error. The values are mapped as below:
+//!
+//! - A byte is a number, and an array or a fixvec of bytes is a string.
+//! - Other arrays and vectors are sequences.
+//! - A struct or a table is a table of the field names.
+//! - An option is the value or `nil`.
+//! - A union is a table of `id`, `name` and `value`.
+//!
+//! The imported types are generated into the same module, so the file doesn't depend on the
+//! files of the imported schemas.
+
+use std::io;
+
+use case::CaseExt;
+
+use super::model::{Decl, Model};
+use crate::{
+    ast::verified::{self as ast, HasName},
+    generator::{metadata::Metadata, Options},
+    VERSION,
+};
+
+pub(crate) struct Generator;
+
+// The helpers which are shared by the types.
+const RUNTIME: &str = r#"local M = {}
+
+local function read_number(data, pos, size)
+  local value = 0
+  for i = size, 1, -1 do
+    value = value * 256 + data:byte(pos + i - 1)
+  end
+  return value
+end
+
+local function decode_byte(data)
+  if #data ~= 1 then return nil, "total_size" end
+  return data:byte(1)
+end
+
+local function decode_all(slices, decode)
+  local items = {}
+  for i, slice in ipairs(slices) do
+    local item, err = decode(slice)
+    if err then return nil, err end
+    items[i] = item
+  end
+  return items
+end
+
+local function decode_items(data, pos, count, size, decode)
+  local slices = {}
+  for i = 1, count do
+    local start = pos + (i - 1) * size
+    slices[i] = data:sub(start, start + size - 1)
+  end
+  return decode_all(slices, decode)
+end
+
+local function decode_fields(slices, fields)
+  local value = {}
+  for i, field in ipairs(fields) do
+    local item, err = field[2](slices[i])
+    if err then return nil, err end
+    value[field[1]] = item
+  end
+  return value
+end
+
+local function split_dynamic(data)
+  if #data < 4 then return nil, "header" end
+  local total = read_number(data, 1, 4)
+  if total ~= #data then return nil, "total_size" end
+  if total == 4 then return {} end
+  if total < 8 then return nil, "header" end
+  local first = read_number(data, 5, 4)
+  if first % 4 ~= 0 or first < 8 or first > total then return nil, "offset" end
+  local count = math.floor(first / 4) - 1
+  local offsets = {}
+  for i = 1, count do
+    offsets[i] = read_number(data, 1 + 4 * i, 4)
+  end
+  offsets[count + 1] = total
+  local slices = {}
+  for i = 1, count do
+    if offsets[i] > offsets[i + 1] then return nil, "offset" end
+    slices[i] = data:sub(offsets[i] + 1, offsets[i + 1])
+  end
+  return slices
+end
+
+local function union_item(id, name, value, err)
+  if err then return nil, err end
+  return { id = id, name = name, value = value }
+end
+"#;
+
+fn table_name(typ: &ast::TopDecl) -> String {
+    format!("M.{}", typ.name().to_camel())
+}
+
+fn decoder(typ: &ast::TopDecl) -> String {
+    if typ.is_atom() {
+        "decode_byte".to_owned()
+    } else {
+        format!("{}.decode", table_name(typ))
+    }
+}
+
+fn fields_list(fields: &[ast::FieldDecl]) -> String {
+    let fields = fields
+        .iter()
+        .map(|f| format!("    {{ \"{}\", {} }},\n", f.name, decoder(&f.typ)))
+        .collect::<String>();
+    format!("{{\n{}  }}", fields)
+}
+
+impl Generator {
+    fn generate_decl<W: io::Write>(writer: &mut W, decl: &Decl) -> io::Result<()> {
+        let (decl, fields) = (decl.decl, &decl.fields);
+        let name = table_name(decl);
+        writeln!(writer)?;
+        match decl {
+            ast::TopDecl::Atom(_) => unreachable!(),
+            ast::TopDecl::Option_(ref i) => {
+                writeln!(writer, "--- The option `{}`.", i.name)?;
+                writeln!(writer, "{} = {{}}", name)?;
+                writeln!(writer, "function {}.decode(data)", name)?;
+                writeln!(writer, "  if #data == 0 then return nil end")?;
+                writeln!(writer, "  return {}(data)", decoder(&i.typ))?;
+            }
+            ast::TopDecl::Union(ref i) => {
+                writeln!(writer, "--- The union `{}`.", i.name)?;
+                writeln!(writer, "{} = {{}}", name)?;
+                writeln!(writer, "function {}.decode(data)", name)?;
+                writeln!(writer, "  if #data < 4 then return nil, \"header\" end")?;
+                writeln!(writer, "  local id = read_number(data, 1, 4)")?;
+                writeln!(writer, "  local body = data:sub(5)")?;
+                for (idx, item) in i.inner.iter().enumerate() {
+                    writeln!(writer, "  if id == {} then", idx)?;
+                    writeln!(
+                        writer,
+                        "    return union_item(id, \"{}\", {}(body))",
+                        item.typ.name(),
+                        decoder(&item.typ)
+                    )?;
+                    writeln!(writer, "  end")?;
+                }
+                writeln!(writer, "  return nil, \"item_id\"")?;
+            }
+            ast::TopDecl::Array(ref i) => {
+                writeln!(writer, "--- The array `{}`.", i.name)?;
+                writeln!(writer, "{} = {{ size = {} }}", name, i.total_size())?;
+                writeln!(writer, "function {}.decode(data)", name)?;
+                writeln!(
+                    writer,
+                    "  if #data ~= {} then return nil, \"total_size\" end",
+                    i.total_size()
+                )?;
+                if i.typ.is_atom() {
+                    writeln!(writer, "  return data")?;
+                } else {
+                    writeln!(
+                        writer,
+                        "  return decode_items(data, 1, {}, {}, {})",
+                        i.item_count,
+                        i.item_size,
+                        decoder(&i.typ)
+                    )?;
+                }
+            }
+            ast::TopDecl::Struct(ref i) => {
+                writeln!(writer, "--- The struct `{}`.", i.name)?;
+                writeln!(writer, "{} = {{ size = {} }}", name, i.total_size())?;
+                writeln!(writer, "function {}.decode(data)", name)?;
+                writeln!(
+                    writer,
+                    "  if #data ~= {} then return nil, \"total_size\" end",
+                    i.total_size()
+                )?;
+                let slices = fields
+                    .iter()
+                    .map(|f| format!("data:sub({}, {})", f.offset() + 1, f.end()))
+                    .collect::<Vec<_>>();
+                writeln!(
+                    writer,
+                    "  return decode_fields({{ {} }}, {})",
+                    slices.join(", "),
+                    fields_list(&i.inner)
+                )?;
+            }
+            ast::TopDecl::FixVec(ref i) => {
+                writeln!(writer, "--- The fixvec `{}`.", i.name)?;
+                writeln!(writer, "{} = {{}}", name)?;
+                writeln!(writer, "function {}.decode(data)", name)?;
+                writeln!(
+                    writer,
+                    "  if #data < {} then return nil, \"header\" end",
+                    i.count_size
+                )?;
+                writeln!(
+                    writer,
+                    "  local count = read_number(data, 1, {})",
+                    i.count_size
+                )?;
+                writeln!(
+                    writer,
+                    "  if #data ~= {} + count * {} then return nil, \"total_size\" end",
+                    i.count_size, i.item_size
+                )?;
+                if i.typ.is_atom() {
+                    writeln!(writer, "  return data:sub({})", i.count_size + 1)?;
+                } else {
+                    writeln!(
+                        writer,
+                        "  return decode_items(data, {}, count, {}, {})",
+                        i.count_size + 1,
+                        i.item_size,
+                        decoder(&i.typ)
+                    )?;
+                }
+            }
+            ast::TopDecl::DynVec(ref i) => {
+                writeln!(writer, "--- The dynvec `{}`.", i.name)?;
+                writeln!(writer, "{} = {{}}", name)?;
+                writeln!(writer, "function {}.decode(data)", name)?;
+                writeln!(writer, "  local slices, err = split_dynamic(data)")?;
+                writeln!(writer, "  if not slices then return nil, err end")?;
+                writeln!(writer, "  return decode_all(slices, {})", decoder(&i.typ))?;
+            }
+            ast::TopDecl::Table(ref i) => {
+                writeln!(writer, "--- The table `{}`.", i.name)?;
+                writeln!(writer, "{} = {{}}", name)?;
+                writeln!(writer, "function {}.decode(data)", name)?;
+                writeln!(writer, "  local slices, err = split_dynamic(data)")?;
+                writeln!(writer, "  if not slices then return nil, err end")?;
+                writeln!(
+                    writer,
+                    "  if #slices ~= {} then return nil, \"field_count\" end",
+                    i.inner.len()
+                )?;
+                writeln!(
+                    writer,
+                    "  return decode_fields(slices, {})",
+                    fields_list(&i.inner)
+                )?;
+            }
+        }
+        writeln!(writer, "end")
+    }
+}
+
+impl super::LanguageGenerator for Generator {
+    fn generate<W: io::Write>(
+        writer: &mut W,
+        ast: &ast::Ast,
+        _options: &Options,
+    ) -> io::Result<()> {
+        writeln!(writer, "-- Generated by Molecule {}", VERSION)?;
+        writeln!(writer, "-- {}", Metadata::new(ast).comment())?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "--- The types of the molecule schema `{}`.",
+            ast.namespace
+        )?;
+        write!(writer, "{}", RUNTIME)?;
+        for decl in &Model::new(ast).decls {
+            Self::generate_decl(writer, decl)?;
+        }
+        writeln!(writer)?;
+        writeln!(writer, "return M")
+    }
+}
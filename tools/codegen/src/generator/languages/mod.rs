@@ -1,18 +1,80 @@
 use std::{convert::TryFrom, io};
 
-use crate::ast::verified as ast;
+use super::{header::write_banner, Options};
+use crate::{
+    ast::verified as ast,
+    examples::{ExampleLanguage, Sample},
+};
 
 mod c;
+mod elixir;
+mod go;
+mod haskell;
+mod imhex;
+mod kaitai;
+mod lua;
+mod model;
+mod ocaml;
+mod openapi;
 mod rust;
+mod scala;
+mod solidity;
+mod sql;
+mod tla;
+mod wireshark;
+mod zig;
+
+pub(crate) use rust::generate_migration;
+
+/// Generate the example snippets, and return the names and the contents of the files.
+pub(crate) fn generate_examples(
+    language: ExampleLanguage,
+    samples: &[Sample],
+    module: Option<&str>,
+) -> Vec<(String, String)> {
+    match language {
+        ExampleLanguage::Rust => rust::generate_examples(samples, module),
+        ExampleLanguage::Go => go::generate_examples(samples, module),
+    }
+}
 
 #[derive(Debug, Clone, Copy)]
 pub enum Language {
     C,
     Rust,
+    /// OpenAPI 3 component schemas, in JSON.
+    OpenApi,
+    /// SQL `CREATE TABLE` statements for structs and tables.
+    Sql,
+    /// Rust `extern "C"` declarations of the symbols which are defined by the generated C code.
+    RustFfi,
+    /// Pure Elixir modules which decode and encode the types.
+    Elixir,
+    /// A Solidity library which verifies and reads the types from calldata.
+    Solidity,
+    /// Zig structs with comptime-known sizes and builders which take an allocator.
+    Zig,
+    /// A Lua module which decodes the types, in pure Lua for LuaJIT as well.
+    Lua,
+    /// OCaml modules which read and build the types over `Bytes.t`.
+    OCaml,
+    /// A Haskell module of `Data.ByteString`-backed newtypes, which verify, read and build the
+    /// types.
+    Haskell,
+    /// A Scala object of the types, with options as `Option` and unions as sealed traits.
+    Scala,
+    /// A TLA+ module of the encoding predicates and the offsets, for theorem provers.
+    Tla,
+    /// Kaitai Struct definitions, for hex viewers and binary analysis tools.
+    Kaitai,
+    /// A Lua dissector for Wireshark, to inspect the messages over TCP.
+    Wireshark,
+    /// An ImHex pattern, to inspect the files in the hex editor.
+    ImHex,
 }
 
 pub(super) trait LanguageGenerator {
-    fn generate<W: io::Write>(writer: &mut W, ast: &ast::Ast) -> io::Result<()>;
+    fn generate<W: io::Write>(writer: &mut W, ast: &ast::Ast, options: &Options) -> io::Result<()>;
 }
 
 impl TryFrom<&str> for Language {
@@ -21,6 +83,18 @@ impl TryFrom<&str> for Language {
         match value {
             "c" => Ok(Language::C),
             "rust" => Ok(Language::Rust),
+            "rust-ffi" => Ok(Language::RustFfi),
+            "elixir" => Ok(Language::Elixir),
+            "solidity" => Ok(Language::Solidity),
+            "zig" => Ok(Language::Zig),
+            "lua" => Ok(Language::Lua),
+            "ocaml" => Ok(Language::OCaml),
+            "haskell" => Ok(Language::Haskell),
+            "scala" => Ok(Language::Scala),
+            "tla" => Ok(Language::Tla),
+            "kaitai" => Ok(Language::Kaitai),
+            "wireshark" => Ok(Language::Wireshark),
+            "imhex" => Ok(Language::ImHex),
             lang => Err(format!("unsupport language: [{}]", lang)),
         }
     }
@@ -31,13 +105,83 @@ impl Language {
         match *self {
             Language::C => "h",
             Language::Rust => "rs",
+            Language::OpenApi => "json",
+            Language::Sql => "sql",
+            Language::RustFfi => "ffi.rs",
+            Language::Elixir => "ex",
+            Language::Solidity => "sol",
+            Language::Zig => "zig",
+            Language::Lua => "lua",
+            Language::OCaml => "ml",
+            Language::Haskell => "hs",
+            Language::Scala => "scala",
+            Language::Tla => "tla",
+            Language::Kaitai => "ksy",
+            Language::Wireshark => "dissector.lua",
+            Language::ImHex => "hexpat",
+        }
+    }
+
+    /// The prefix of line comments; JSON doesn't have comments, and OCaml only has block
+    /// comments.
+    fn line_comment(&self) -> Option<&str> {
+        match *self {
+            Language::C
+            | Language::Rust
+            | Language::RustFfi
+            | Language::Solidity
+            | Language::Zig
+            | Language::Scala
+            | Language::ImHex => Some("//"),
+            Language::OpenApi | Language::OCaml => None,
+            Language::Sql | Language::Lua | Language::Haskell | Language::Wireshark => Some("--"),
+            Language::Elixir | Language::Kaitai => Some("#"),
+            Language::Tla => Some("\\*"),
         }
     }
 
-    pub(crate) fn generate<W: io::Write>(self, writer: &mut W, ast: &ast::Ast) -> io::Result<()> {
+    /// Check the exported types before generating the code, so an unknown or unsupported type
+    /// could be reported as an error.
+    pub(crate) fn check_types(self, ast: &ast::Ast, types: &[String]) -> Result<(), String> {
+        match self {
+            Language::OpenApi => openapi::Generator::collect_decls(ast, types).map(|_| ()),
+            Language::Sql => sql::Generator::exported_decls(ast, types).map(|_| ()),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn generate<W: io::Write>(
+        self,
+        writer: &mut W,
+        ast: &ast::Ast,
+        options: &Options,
+    ) -> io::Result<()> {
+        if !options.hooks.is_empty() {
+            if !matches!(self, Language::C | Language::Rust) {
+                panic!("the hooks are only supported by the C and the Rust generators");
+            }
+            options.hooks.check(ast);
+        }
+        if let (Some(banner), Some(prefix)) = (&options.header, self.line_comment()) {
+            write_banner(writer, banner, prefix)?;
+        }
         match self {
-            Language::C => c::Generator::generate(writer, ast),
-            Language::Rust => rust::Generator::generate(writer, ast),
+            Language::C => c::Generator::generate(writer, ast, options),
+            Language::Rust => rust::Generator::generate(writer, ast, options),
+            Language::OpenApi => openapi::Generator::generate(writer, ast, options),
+            Language::Sql => sql::Generator::generate(writer, ast, options),
+            Language::RustFfi => c::FfiGenerator::generate(writer, ast, options),
+            Language::Elixir => elixir::Generator::generate(writer, ast, options),
+            Language::Solidity => solidity::Generator::generate(writer, ast, options),
+            Language::Zig => zig::Generator::generate(writer, ast, options),
+            Language::Lua => lua::Generator::generate(writer, ast, options),
+            Language::OCaml => ocaml::Generator::generate(writer, ast, options),
+            Language::Haskell => haskell::Generator::generate(writer, ast, options),
+            Language::Scala => scala::Generator::generate(writer, ast, options),
+            Language::Tla => tla::Generator::generate(writer, ast, options),
+            Language::Kaitai => kaitai::Generator::generate(writer, ast, options),
+            Language::Wireshark => wireshark::Generator::generate(writer, ast, options),
+            Language::ImHex => imhex::Generator::generate(writer, ast, options),
         }
     }
 }
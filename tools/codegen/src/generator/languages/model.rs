@@ -0,0 +1,131 @@
+//! The codegen model of a schema, which is computed once from the verified AST and shared by the
+//! backends.
+//!
+//! The backends read the order of the types and the layouts of their fields from the model, so
+//! the offsets of the fields of a struct are only computed here.
+
+use std::{collections::HashSet, ops::Range, rc::Rc};
+
+use crate::ast::verified::{self as ast, HasName};
+
+/// A field of a struct or a table.
+#[derive(Debug)]
+pub(super) struct Field<'a> {
+    pub(super) index: usize,
+    pub(super) name: &'a str,
+    pub(super) typ: &'a ast::TopDecl,
+    /// The bytes of the field in a struct; the fields of a table are located by the offsets in
+    /// its header, so they don't have a range.
+    pub(super) range: Option<Range<usize>>,
+}
+
+impl<'a> Field<'a> {
+    pub(super) fn offset(&self) -> usize {
+        self.range.as_ref().expect("a field of a struct").start
+    }
+
+    pub(super) fn end(&self) -> usize {
+        self.range.as_ref().expect("a field of a struct").end
+    }
+
+    pub(super) fn size(&self) -> usize {
+        self.range.as_ref().expect("a field of a struct").len()
+    }
+}
+
+/// The fields of a struct, each of which is at the end of the previous one.
+pub(super) fn struct_fields(info: &ast::Struct) -> Vec<Field<'_>> {
+    let mut offset = 0;
+    info.inner
+        .iter()
+        .zip(info.field_size.iter())
+        .enumerate()
+        .map(|(index, (field, size))| {
+            let range = offset..offset + size;
+            offset += size;
+            Field {
+                index,
+                name: &field.name,
+                typ: &field.typ,
+                range: Some(range),
+            }
+        })
+        .collect()
+}
+
+fn table_fields(info: &ast::Table) -> Vec<Field<'_>> {
+    info.inner
+        .iter()
+        .enumerate()
+        .map(|(index, field)| Field {
+            index,
+            name: &field.name,
+            typ: &field.typ,
+            range: None,
+        })
+        .collect()
+}
+
+/// A type of the schema, with its fields if it's a struct or a table.
+#[derive(Debug)]
+pub(super) struct Decl<'a> {
+    pub(super) decl: &'a ast::TopDecl,
+    pub(super) fields: Vec<Field<'a>>,
+}
+
+impl<'a> Decl<'a> {
+    fn new(decl: &'a ast::TopDecl) -> Self {
+        let fields = match decl {
+            ast::TopDecl::Struct(ref info) => struct_fields(info),
+            ast::TopDecl::Table(ref info) => table_fields(info),
+            _ => Vec::new(),
+        };
+        Self { decl, fields }
+    }
+
+    pub(super) fn name(&self) -> &'a str {
+        self.decl.name()
+    }
+}
+
+/// The types of a schema and the types which it imports, without the atom.
+#[derive(Debug)]
+pub(super) struct Model<'a> {
+    pub(super) decls: Vec<Decl<'a>>,
+}
+
+impl<'a> Model<'a> {
+    /// The types in the order of the schema.
+    pub(super) fn new(ast: &'a ast::Ast) -> Self {
+        let decls = ast
+            .decls()
+            .iter()
+            .filter(|decl| !decl.is_atom())
+            .map(|decl| Decl::new(decl))
+            .collect();
+        Self { decls }
+    }
+
+    /// The types in an order where each type comes after the types which it depends on.
+    pub(super) fn sorted(ast: &'a ast::Ast) -> Self {
+        fn visit<'a>(
+            decl: &'a ast::TopDecl,
+            visited: &mut HashSet<&'a str>,
+            sorted: &mut Vec<Decl<'a>>,
+        ) {
+            if decl.is_atom() || !visited.insert(decl.name()) {
+                return;
+            }
+            for dep in decl.dependencies() {
+                visit(dep, visited, sorted);
+            }
+            sorted.push(Decl::new(decl));
+        }
+        let mut visited = HashSet::new();
+        let mut decls = Vec::with_capacity(ast.decls().len());
+        for decl in ast.decls() {
+            visit(Rc::as_ref(decl), &mut visited, &mut decls);
+        }
+        Self { decls }
+    }
+}
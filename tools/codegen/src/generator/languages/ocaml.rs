@@ -0,0 +1,644 @@
+//! OCaml modules which read and build the types over `Bytes.t`, for OCaml 4.08 or later.
+//!
+//! Each type is a module with `type t`, which is a slice of the bytes behind a constructor, so
+//! the types can't be mixed up. `from_slice` and `of_bytes` verify the bytes, in the strict mode
+//! unless `~compatible:true` is given; the getters don't check the bytes again. `build` makes a
+//! new value from the typed fields or items, and `to_bytes` copies the bytes out.
+//!
+//! OCaml doesn't allow forward references, so the modules are sorted by their dependencies. The
+//! imported types are generated into the same file, so the file doesn't depend on the files of
+//! the imported schemas.
+
+use std::io;
+
+use case::CaseExt;
+
+use super::model::{Decl, Model};
+use crate::{
+    ast::verified::{self as ast, HasName},
+    generator::{metadata::Metadata, Options},
+    VERSION,
+};
+
+pub(crate) struct Generator;
+
+// The helpers which are shared by the modules of the types.
+const RUNTIME: &str = r#"module Mol = struct
+  type error = Total_size | Header | Offset | Field_count | Unknown_item
+
+  type slice = { buf : Bytes.t; pos : int; len : int }
+
+  let ( let* ) = Result.bind
+
+  let of_bytes buf = { buf; pos = 0; len = Bytes.length buf }
+
+  let to_bytes s = Bytes.sub s.buf s.pos s.len
+
+  let sub s pos len = { s with pos = s.pos + pos; len }
+
+  let get_byte s = Bytes.get_uint8 s.buf s.pos
+
+  let byte value = Bytes.make 1 (Char.chr value)
+
+  let length = Bytes.length
+
+  let concat = Bytes.concat Bytes.empty
+
+  let read_number s pos size =
+    let rec go i acc =
+      if i < 0 then acc else go (i - 1) ((acc lsl 8) lor Bytes.get_uint8 s.buf (s.pos + pos + i))
+    in
+    go (size - 1) 0
+
+  let pack_number size value =
+    Bytes.init size (fun i -> Char.chr ((value lsr (8 * i)) land 0xff))
+
+  let verify_byte s = if s.len = 1 then Ok () else Error Total_size
+
+  (* Verifies the header of a dynvec or a table, and returns the count of the items. *)
+  let verify_dynamic s =
+    if s.len < 4 then Error Header
+    else
+      let total = read_number s 0 4 in
+      if total <> s.len then Error Total_size
+      else if total = 4 then Ok 0
+      else if total < 8 then Error Header
+      else
+        let first = read_number s 4 4 in
+        if first mod 4 <> 0 || first < 8 || first > total then Error Offset
+        else
+          let count = (first / 4) - 1 in
+          let rec check i previous =
+            if i >= count then Ok count
+            else
+              let offset = read_number s (4 + (4 * i)) 4 in
+              if offset < previous || offset > total then Error Offset else check (i + 1) offset
+          in
+          check 1 first
+
+  let dynamic_length s = if s.len = 4 then 0 else (read_number s 4 4 / 4) - 1
+
+  let dynamic_at s i =
+    let start = read_number s (4 + (4 * i)) 4 in
+    let stop = if i + 1 = dynamic_length s then s.len else read_number s (8 + (4 * i)) 4 in
+    sub s start (stop - start)
+
+  let verify_items s count verify =
+    let rec go i = if i >= count then Ok () else let* () = verify (dynamic_at s i) in go (i + 1) in
+    go 0
+
+  let pack_dynamic items =
+    let header = 4 * (List.length items + 1) in
+    let total = List.fold_left (fun acc item -> acc + Bytes.length item) header items in
+    let offsets, _ =
+      List.fold_left
+        (fun (acc, offset) item -> (pack_number 4 offset :: acc, offset + Bytes.length item))
+        ([], header) items
+    in
+    concat ((pack_number 4 total :: List.rev offsets) @ items)
+end
+"#;
+
+const KEYWORDS: &[&str] = &[
+    "and",
+    "as",
+    "assert",
+    "asr",
+    "begin",
+    "class",
+    "constraint",
+    "do",
+    "done",
+    "downto",
+    "else",
+    "end",
+    "exception",
+    "external",
+    "false",
+    "for",
+    "fun",
+    "function",
+    "functor",
+    "if",
+    "in",
+    "include",
+    "inherit",
+    "initializer",
+    "land",
+    "lazy",
+    "let",
+    "lor",
+    "lsl",
+    "lsr",
+    "lxor",
+    "match",
+    "method",
+    "mod",
+    "module",
+    "mutable",
+    "new",
+    "nonrec",
+    "object",
+    "of",
+    "open",
+    "or",
+    "private",
+    "rec",
+    "sig",
+    "struct",
+    "then",
+    "to",
+    "true",
+    "try",
+    "type",
+    "val",
+    "virtual",
+    "when",
+    "while",
+    "with",
+];
+
+// The values which are defined in the modules of the types, besides the getters.
+const MEMBERS: &[&str] = &[
+    "size",
+    "verify",
+    "from_slice",
+    "of_bytes",
+    "to_bytes",
+    "build",
+    "length",
+    "get",
+    "item_id",
+    "item",
+];
+
+/// The name of a getter or a label, with a trailing underscore if it's reserved.
+fn ident(name: &str) -> String {
+    if KEYWORDS.contains(&name) || MEMBERS.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_owned()
+    }
+}
+
+fn module_name(typ: &ast::TopDecl) -> String {
+    typ.name().to_camel()
+}
+
+fn value_type(typ: &ast::TopDecl) -> String {
+    if typ.is_atom() {
+        "int".to_owned()
+    } else {
+        format!("{}.t", module_name(typ))
+    }
+}
+
+fn verify_call(typ: &ast::TopDecl, slice: &str) -> String {
+    if typ.is_atom() {
+        format!("Mol.verify_byte {}", slice)
+    } else {
+        format!("{}.verify ~compatible {}", module_name(typ), slice)
+    }
+}
+
+/// The optional parameter of `verify`, which is ignored if the items are all bytes.
+fn compatible_param(items: &[&ast::TopDecl]) -> &'static str {
+    if items.iter().all(|typ| typ.is_atom()) {
+        "?compatible:_"
+    } else {
+        "?(compatible = false)"
+    }
+}
+
+/// The verifier of the type, as a function of a slice.
+fn verifier(typ: &ast::TopDecl) -> String {
+    if typ.is_atom() {
+        "Mol.verify_byte".to_owned()
+    } else {
+        format!("{}.verify ~compatible", module_name(typ))
+    }
+}
+
+/// The value of the type in a slice, which has been verified.
+fn read_expr(typ: &ast::TopDecl, slice: &str) -> String {
+    let slice = if slice.contains(' ') {
+        format!("({})", slice)
+    } else {
+        slice.to_owned()
+    };
+    if typ.is_atom() {
+        format!("Mol.get_byte {}", slice)
+    } else {
+        format!("{}.T {}", module_name(typ), slice)
+    }
+}
+
+/// The bytes of a value of the type.
+fn bytes_expr(typ: &ast::TopDecl, value: &str) -> String {
+    if typ.is_atom() {
+        format!("Mol.byte {}", value)
+    } else {
+        format!("{}.to_bytes {}", module_name(typ), value)
+    }
+}
+
+impl Generator {
+    fn write_common<W: io::Write>(writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "  type t = T of Mol.slice [@@unboxed]")?;
+        writeln!(writer)?;
+        writeln!(writer, "  let to_bytes (T s) = Mol.to_bytes s")
+    }
+
+    fn write_from_slice<W: io::Write>(writer: &mut W) -> io::Result<()> {
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "  let from_slice ?(compatible = false) s = Stdlib.Result.map (fun () -> T s) (verify ~compatible s)"
+        )?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "  let of_bytes ?compatible b = from_slice ?compatible (Mol.of_bytes b)"
+        )
+    }
+
+    fn write_build<W: io::Write>(writer: &mut W, params: &str, expr: &str) -> io::Result<()> {
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "  let build {} = T (Mol.of_bytes ({}))",
+            params, expr
+        )
+    }
+
+    fn generate_decl<W: io::Write>(writer: &mut W, decl: &Decl) -> io::Result<()> {
+        let (decl, fields) = (decl.decl, &decl.fields);
+        writeln!(writer)?;
+        match decl {
+            ast::TopDecl::Atom(_) => unreachable!(),
+            ast::TopDecl::Option_(ref i) => {
+                writeln!(writer, "(** The option [{}]. *)", i.name)?;
+                writeln!(writer, "module {} = struct", module_name(decl))?;
+                Self::write_common(writer)?;
+                writeln!(writer)?;
+                writeln!(writer, "  let verify {} s =", compatible_param(&[&i.typ]))?;
+                writeln!(
+                    writer,
+                    "    if s.Mol.len = 0 then Ok () else {}",
+                    verify_call(&i.typ, "s")
+                )?;
+                Self::write_from_slice(writer)?;
+                writeln!(writer)?;
+                writeln!(
+                    writer,
+                    "  let get (T s) : {} option = if s.Mol.len = 0 then None else Some ({})",
+                    value_type(&i.typ),
+                    read_expr(&i.typ, "s")
+                )?;
+                writeln!(writer)?;
+                writeln!(writer, "  let build = function")?;
+                writeln!(writer, "    | None -> T (Mol.of_bytes (Mol.concat []))")?;
+                writeln!(
+                    writer,
+                    "    | Some v -> T (Mol.of_bytes ({}))",
+                    bytes_expr(&i.typ, "v")
+                )?;
+            }
+            ast::TopDecl::Union(ref i) => {
+                let constructors = i
+                    .inner
+                    .iter()
+                    .map(|item| item.typ.name().to_camel())
+                    .collect::<Vec<_>>();
+                writeln!(writer, "(** The union [{}]. *)", i.name)?;
+                writeln!(writer, "module {} = struct", module_name(decl))?;
+                Self::write_common(writer)?;
+                writeln!(writer)?;
+                write!(writer, "  type item =")?;
+                for (c, item) in constructors.iter().zip(&i.inner) {
+                    write!(writer, "\n    | {} of {}", c, value_type(&item.typ))?;
+                }
+                writeln!(writer)?;
+                writeln!(writer)?;
+                let items = i.inner.iter().map(|item| &*item.typ).collect::<Vec<_>>();
+                writeln!(writer, "  let verify {} s =", compatible_param(&items))?;
+                writeln!(writer, "    if s.Mol.len < 4 then Error Mol.Header")?;
+                writeln!(writer, "    else")?;
+                writeln!(writer, "      let body = Mol.sub s 4 (s.Mol.len - 4) in")?;
+                writeln!(writer, "      match Mol.read_number s 0 4 with")?;
+                for (idx, item) in i.inner.iter().enumerate() {
+                    writeln!(
+                        writer,
+                        "      | {} -> {}",
+                        idx,
+                        verify_call(&item.typ, "body")
+                    )?;
+                }
+                writeln!(writer, "      | _ -> Error Mol.Unknown_item")?;
+                Self::write_from_slice(writer)?;
+                writeln!(writer)?;
+                writeln!(writer, "  let item_id (T s) = Mol.read_number s 0 4")?;
+                writeln!(writer)?;
+                writeln!(writer, "  let item (T s) =")?;
+                writeln!(writer, "    let body = Mol.sub s 4 (s.Mol.len - 4) in")?;
+                writeln!(writer, "    match Mol.read_number s 0 4 with")?;
+                for (idx, (c, item)) in constructors.iter().zip(&i.inner).enumerate() {
+                    writeln!(
+                        writer,
+                        "    | {} -> {} ({})",
+                        idx,
+                        c,
+                        read_expr(&item.typ, "body")
+                    )?;
+                }
+                writeln!(writer, "    | _ -> assert false")?;
+                writeln!(writer)?;
+                writeln!(writer, "  let build item =")?;
+                writeln!(writer, "    let id, body =")?;
+                writeln!(writer, "      match item with")?;
+                for (idx, (c, item)) in constructors.iter().zip(&i.inner).enumerate() {
+                    writeln!(
+                        writer,
+                        "      | {} v -> ({}, {})",
+                        c,
+                        idx,
+                        bytes_expr(&item.typ, "v")
+                    )?;
+                }
+                writeln!(writer, "    in")?;
+                writeln!(
+                    writer,
+                    "    T (Mol.of_bytes (Mol.concat [ Mol.pack_number 4 id; body ]))"
+                )?;
+            }
+            ast::TopDecl::Array(ref i) => {
+                writeln!(writer, "(** The array [{}]. *)", i.name)?;
+                writeln!(writer, "module {} = struct", module_name(decl))?;
+                Self::write_common(writer)?;
+                writeln!(writer)?;
+                writeln!(writer, "  let size = {}", i.total_size())?;
+                writeln!(writer)?;
+                writeln!(writer, "  let length = {}", i.item_count)?;
+                writeln!(writer)?;
+                writeln!(
+                    writer,
+                    "  let verify ?compatible:_ s = if s.Mol.len = size then Ok () else Error Mol.Total_size"
+                )?;
+                Self::write_from_slice(writer)?;
+                writeln!(writer)?;
+                writeln!(
+                    writer,
+                    "  let get (T s) i = {}",
+                    read_expr(&i.typ, &format!("Mol.sub s (i * {0}) {0}", i.item_size))
+                )?;
+                if i.typ.is_atom() {
+                    writeln!(writer)?;
+                    writeln!(writer, "  let build b =")?;
+                    writeln!(
+                        writer,
+                        "    if Mol.length b <> size then invalid_arg \"{}.build\";",
+                        module_name(decl)
+                    )?;
+                    writeln!(writer, "    T (Mol.of_bytes (Mol.concat [ b ]))")?;
+                } else {
+                    writeln!(writer)?;
+                    writeln!(writer, "  let build items =")?;
+                    writeln!(
+                        writer,
+                        "    if Stdlib.List.length items <> length then invalid_arg \"{}.build\";",
+                        module_name(decl)
+                    )?;
+                    writeln!(
+                        writer,
+                        "    T (Mol.of_bytes (Mol.concat (Stdlib.List.map {}.to_bytes items)))",
+                        module_name(&i.typ)
+                    )?;
+                }
+            }
+            ast::TopDecl::Struct(ref i) => {
+                writeln!(writer, "(** The struct [{}]. *)", i.name)?;
+                writeln!(writer, "module {} = struct", module_name(decl))?;
+                Self::write_common(writer)?;
+                writeln!(writer)?;
+                writeln!(writer, "  let size = {}", i.total_size())?;
+                writeln!(writer)?;
+                writeln!(
+                    writer,
+                    "  let verify ?compatible:_ s = if s.Mol.len = size then Ok () else Error Mol.Total_size"
+                )?;
+                Self::write_from_slice(writer)?;
+                for f in fields {
+                    let (offset, size) = (f.offset(), f.size());
+                    writeln!(writer)?;
+                    writeln!(
+                        writer,
+                        "  let {} (T s) = {}",
+                        ident(f.name),
+                        read_expr(f.typ, &format!("Mol.sub s {} {}", offset, size))
+                    )?;
+                }
+                let params = i
+                    .inner
+                    .iter()
+                    .map(|f| format!("~{}", ident(&f.name)))
+                    .collect::<Vec<_>>();
+                let fields = i
+                    .inner
+                    .iter()
+                    .map(|f| bytes_expr(&f.typ, &ident(&f.name)))
+                    .collect::<Vec<_>>();
+                Self::write_build(
+                    writer,
+                    &params.join(" "),
+                    &format!("Mol.concat [ {} ]", fields.join("; ")),
+                )?;
+            }
+            ast::TopDecl::FixVec(ref i) => {
+                writeln!(writer, "(** The fixvec [{}]. *)", i.name)?;
+                writeln!(writer, "module {} = struct", module_name(decl))?;
+                Self::write_common(writer)?;
+                writeln!(writer)?;
+                writeln!(writer, "  let verify ?compatible:_ s =")?;
+                writeln!(
+                    writer,
+                    "    if s.Mol.len < {} then Error Mol.Header",
+                    i.count_size
+                )?;
+                writeln!(
+                    writer,
+                    "    else if s.Mol.len <> {} + (Mol.read_number s 0 {} * {}) then Error Mol.Total_size",
+                    i.count_size, i.count_size, i.item_size
+                )?;
+                writeln!(writer, "    else Ok ()")?;
+                Self::write_from_slice(writer)?;
+                writeln!(writer)?;
+                writeln!(
+                    writer,
+                    "  let length (T s) = Mol.read_number s 0 {}",
+                    i.count_size
+                )?;
+                writeln!(writer)?;
+                writeln!(
+                    writer,
+                    "  let get (T s) i = {}",
+                    read_expr(
+                        &i.typ,
+                        &format!(
+                            "Mol.sub s ({} + (i * {})) {}",
+                            i.count_size, i.item_size, i.item_size
+                        )
+                    )
+                )?;
+                if i.typ.is_atom() {
+                    writeln!(writer)?;
+                    writeln!(
+                        writer,
+                        "  let data (T s) = Mol.to_bytes (Mol.sub s {0} (s.Mol.len - {0}))",
+                        i.count_size
+                    )?;
+                    Self::write_build(
+                        writer,
+                        "b",
+                        &format!(
+                            "Mol.concat [ Mol.pack_number {} (Mol.length b); b ]",
+                            i.count_size
+                        ),
+                    )?;
+                } else {
+                    Self::write_build(
+                        writer,
+                        "items",
+                        &format!(
+                            "Mol.concat (Mol.pack_number {} (Stdlib.List.length items) :: Stdlib.List.map {}.to_bytes items)",
+                            i.count_size,
+                            module_name(&i.typ)
+                        ),
+                    )?;
+                }
+            }
+            ast::TopDecl::DynVec(ref i) => {
+                writeln!(writer, "(** The dynvec [{}]. *)", i.name)?;
+                writeln!(writer, "module {} = struct", module_name(decl))?;
+                Self::write_common(writer)?;
+                writeln!(writer)?;
+                writeln!(writer, "  let verify ?(compatible = false) s =")?;
+                writeln!(writer, "    let open Mol in")?;
+                writeln!(writer, "    let* count = verify_dynamic s in")?;
+                writeln!(writer, "    verify_items s count ({})", verifier(&i.typ))?;
+                Self::write_from_slice(writer)?;
+                writeln!(writer)?;
+                writeln!(writer, "  let length (T s) = Mol.dynamic_length s")?;
+                writeln!(writer)?;
+                writeln!(
+                    writer,
+                    "  let get (T s) i = {}",
+                    read_expr(&i.typ, "Mol.dynamic_at s i")
+                )?;
+                Self::write_build(
+                    writer,
+                    "items",
+                    &format!(
+                        "Mol.pack_dynamic (Stdlib.List.map {}.to_bytes items)",
+                        module_name(&i.typ)
+                    ),
+                )?;
+            }
+            ast::TopDecl::Table(ref i) => {
+                let count = i.inner.len();
+                writeln!(writer, "(** The table [{}]. *)", i.name)?;
+                writeln!(writer, "module {} = struct", module_name(decl))?;
+                Self::write_common(writer)?;
+                writeln!(writer)?;
+                writeln!(writer, "  let verify ?(compatible = false) s =")?;
+                writeln!(writer, "    let open Mol in")?;
+                writeln!(writer, "    let* count = verify_dynamic s in")?;
+                if count == 0 {
+                    writeln!(
+                        writer,
+                        "    if (not compatible) && count > 0 then Error Field_count"
+                    )?;
+                } else {
+                    writeln!(
+                        writer,
+                        "    if count < {0} || ((not compatible) && count > {0}) then Error Field_count",
+                        count
+                    )?;
+                }
+                if i.inner.is_empty() {
+                    writeln!(writer, "    else Ok ()")?;
+                } else {
+                    writeln!(writer, "    else")?;
+                    for (idx, f) in i.inner.iter().enumerate() {
+                        let call = verify_call(&f.typ, &format!("(dynamic_at s {})", idx));
+                        if idx + 1 == count {
+                            writeln!(writer, "      {}", call)?;
+                        } else {
+                            writeln!(writer, "      let* () = {} in", call)?;
+                        }
+                    }
+                }
+                Self::write_from_slice(writer)?;
+                for (idx, f) in i.inner.iter().enumerate() {
+                    writeln!(writer)?;
+                    writeln!(
+                        writer,
+                        "  let {} (T s) = {}",
+                        ident(&f.name),
+                        read_expr(&f.typ, &format!("Mol.dynamic_at s {}", idx))
+                    )?;
+                }
+                let params = if i.inner.is_empty() {
+                    "()".to_owned()
+                } else {
+                    i.inner
+                        .iter()
+                        .map(|f| format!("~{}", ident(&f.name)))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                };
+                let fields = i
+                    .inner
+                    .iter()
+                    .map(|f| bytes_expr(&f.typ, &ident(&f.name)))
+                    .collect::<Vec<_>>();
+                let fields = if fields.is_empty() {
+                    "[]".to_owned()
+                } else {
+                    format!("[ {} ]", fields.join("; "))
+                };
+                Self::write_build(writer, &params, &format!("Mol.pack_dynamic {}", fields))?;
+            }
+        }
+        writeln!(writer, "end")
+    }
+}
+
+impl super::LanguageGenerator for Generator {
+    fn generate<W: io::Write>(writer: &mut W, ast: &ast::Ast, options: &Options) -> io::Result<()> {
+        // OCaml only has block comments, so the banner isn't written by the caller.
+        if let Some(ref banner) = options.header {
+            for line in banner.lines().map(str::trim_end) {
+                if line.is_empty() {
+                    writeln!(writer, "(* *)")?;
+                } else {
+                    writeln!(writer, "(* {} *)", line)?;
+                }
+            }
+            writeln!(writer)?;
+        }
+        writeln!(writer, "(* Generated by Molecule {} *)", VERSION)?;
+        writeln!(writer, "(* {} *)", Metadata::new(ast).comment())?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "(** The types of the molecule schema [{}]. *)",
+            ast.namespace
+        )?;
+        writeln!(writer)?;
+        write!(writer, "{}", RUNTIME)?;
+        for decl in &Model::sorted(ast).decls {
+            Self::generate_decl(writer, decl)?;
+        }
+        Ok(())
+    }
+}
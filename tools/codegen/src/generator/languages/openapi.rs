@@ -0,0 +1,179 @@
+use std::{collections::HashSet, io, rc::Rc};
+
+use serde_json::{json, Map, Value};
+
+use crate::{
+    ast::verified::{self as ast, HasName},
+    generator::{metadata::Metadata, Options},
+    VERSION,
+};
+
+const OPENAPI_VERSION: &str = "3.1.0";
+
+pub(crate) struct Generator;
+
+// Use the same JSON mapping as the `schemars::JsonSchema` implementations of the Rust code.
+trait GenSchema {
+    fn gen_schema(&self) -> Value;
+}
+
+fn schema_ref(typ: &ast::TopDecl) -> Value {
+    if typ.is_atom() {
+        json!({ "type": "integer", "minimum": 0, "maximum": 255 })
+    } else {
+        json!({ "$ref": format!("#/components/schemas/{}", typ.name()) })
+    }
+}
+
+fn schema_for_bytes(count: Option<usize>) -> Value {
+    let pattern = if let Some(count) = count {
+        format!("^0x([0-9a-fA-F]{{2}}){{{}}}$", count)
+    } else {
+        "^0x([0-9a-fA-F]{2})*$".to_owned()
+    };
+    json!({ "type": "string", "pattern": pattern })
+}
+
+fn schema_for_fields(fields: &[ast::FieldDecl]) -> Value {
+    let properties = fields
+        .iter()
+        .map(|f| (f.name.clone(), schema_ref(&f.typ)))
+        .collect::<Map<_, _>>();
+    let required = fields.iter().map(|f| f.name.as_str()).collect::<Vec<_>>();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false
+    })
+}
+
+impl GenSchema for ast::TopDecl {
+    fn gen_schema(&self) -> Value {
+        match self {
+            ast::TopDecl::Atom(_) => unreachable!(),
+            ast::TopDecl::Option_(ref i) => json!({
+                "anyOf": [schema_ref(&i.typ), { "type": "null" }]
+            }),
+            ast::TopDecl::Union(ref i) => {
+                let items = i
+                    .inner
+                    .iter()
+                    .map(|inner| {
+                        json!({
+                            "type": "object",
+                            "properties": {
+                                "type": { "const": inner.typ.name() },
+                                "value": schema_ref(&inner.typ)
+                            },
+                            "required": ["type", "value"],
+                            "additionalProperties": false
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                json!({ "oneOf": items })
+            }
+            ast::TopDecl::Array(ref i) => {
+                if i.typ.is_atom() {
+                    schema_for_bytes(Some(i.item_count))
+                } else {
+                    json!({
+                        "type": "array",
+                        "items": schema_ref(&i.typ),
+                        "minItems": i.item_count,
+                        "maxItems": i.item_count
+                    })
+                }
+            }
+            ast::TopDecl::Struct(ref i) => schema_for_fields(&i.inner),
+            ast::TopDecl::FixVec(ref i) => {
+                let mut schema = if i.typ.is_atom() {
+                    schema_for_bytes(None)
+                } else {
+                    json!({ "type": "array", "items": schema_ref(&i.typ) })
+                };
+                // A non-standard count size limits the item count, and readers of the schema
+                // should know that the encoding is not the standard one.
+                if !i.is_standard() {
+                    let max_item_count = i.max_item_count();
+                    if i.typ.is_atom() {
+                        schema["maxLength"] = json!(2 + 2 * max_item_count);
+                    } else {
+                        schema["maxItems"] = json!(max_item_count);
+                    }
+                    schema["x-molecule-count-size"] = json!(i.count_size);
+                }
+                schema
+            }
+            ast::TopDecl::DynVec(ref i) => json!({ "type": "array", "items": schema_ref(&i.typ) }),
+            ast::TopDecl::Table(ref i) => schema_for_fields(&i.inner),
+        }
+    }
+}
+
+impl Generator {
+    /// Collect the selected types and all types they depend on.
+    ///
+    /// If no types are selected, all types which are defined in the schema file are selected.
+    pub(super) fn collect_decls(
+        ast: &ast::Ast,
+        types: &[String],
+    ) -> Result<Vec<Rc<ast::TopDecl>>, String> {
+        let mut pending = if types.is_empty() {
+            ast.major_decls()
+        } else {
+            types
+                .iter()
+                .map(|name| {
+                    ast.get_decl(name)
+                        .ok_or_else(|| format!("the type `{}` is not defined", name))
+                })
+                .collect::<Result<_, _>>()?
+        };
+        let mut selected = HashSet::new();
+        while let Some(decl) = pending.pop() {
+            if decl.is_atom() || !selected.insert(decl.name().to_owned()) {
+                continue;
+            }
+            pending.extend(decl.dependencies().into_iter().map(Rc::clone));
+        }
+        Ok(ast
+            .decls()
+            .iter()
+            .filter(|decl| selected.contains(decl.name()))
+            .map(Rc::clone)
+            .collect())
+    }
+}
+
+impl super::LanguageGenerator for Generator {
+    fn generate<W: io::Write>(writer: &mut W, ast: &ast::Ast, options: &Options) -> io::Result<()> {
+        let schemas = Self::collect_decls(ast, &options.types)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?
+            .into_iter()
+            .map(|decl| (decl.name().to_owned(), decl.gen_schema()))
+            .collect::<Map<_, _>>();
+        let mut document = Map::new();
+        if let Some(ref banner) = options.header {
+            // The banner is an extension at the top, since JSON doesn't have comments.
+            document.insert("x-header".to_owned(), json!(banner));
+        }
+        let fields = json!({
+            "openapi": OPENAPI_VERSION,
+            "info": {
+                "title": ast.namespace,
+                "version": "0.0.0",
+                "description": format!("Generated by Molecule {}", VERSION),
+                "x-molecule": Metadata::new(ast).json()
+            },
+            "components": {
+                "schemas": schemas
+            }
+        });
+        if let Value::Object(fields) = fields {
+            document.extend(fields);
+        }
+        serde_json::to_writer_pretty(&mut *writer, &document)?;
+        writeln!(writer)
+    }
+}
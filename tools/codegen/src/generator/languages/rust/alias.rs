@@ -0,0 +1,44 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{
+    builder_name, entity_name, entity_union_name, reader_name, reader_union_name,
+};
+use crate::ast::verified::{self as ast, HasName};
+
+pub(super) trait GenAlias {
+    fn gen_alias(&self, vis: &m4::TokenStream) -> m4::TokenStream;
+}
+
+impl GenAlias for ast::Alias {
+    fn gen_alias(&self, vis: &m4::TokenStream) -> m4::TokenStream {
+        let alias = self.name();
+        let target = self.typ.name();
+        let doc = format!(" Another name of [`{}`].", entity_name(target));
+        let entity = entity_name(alias);
+        let reader = reader_name(alias);
+        let builder = builder_name(alias);
+        let target_entity = entity_name(target);
+        let target_reader = reader_name(target);
+        let target_builder = builder_name(target);
+        let unions = if let ast::TopDecl::Union(_) = self.typ.as_ref() {
+            let entity_union = entity_union_name(alias);
+            let reader_union = reader_union_name(alias);
+            let target_entity_union = entity_union_name(target);
+            let target_reader_union = reader_union_name(target);
+            quote!(
+                #vis type #entity_union = #target_entity_union;
+                #vis type #reader_union<'r> = #target_reader_union<'r>;
+            )
+        } else {
+            quote!()
+        };
+        quote!(
+            #[doc = #doc]
+            #vis type #entity = #target_entity;
+            #vis type #reader<'r> = #target_reader<'r>;
+            #vis type #builder = #target_builder;
+            #unions
+        )
+    }
+}
@@ -0,0 +1,99 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{entity_name, reader_name, usize_lit};
+use crate::ast::verified::{self as ast, HasName};
+
+pub(in super::super) trait GenArrow {
+    fn gen_arrow(&self) -> m4::TokenStream;
+}
+
+fn layout_for_type(typ: &ast::TopDecl) -> m4::TokenStream {
+    if typ.is_atom() {
+        quote!(molecule::arrow::Layout::Byte)
+    } else {
+        let inner = entity_name(typ.name());
+        quote!(<#inner as molecule::arrow::FixedLayout>::layout())
+    }
+}
+
+fn impl_fixed_layout(name: &str, layout: m4::TokenStream) -> m4::TokenStream {
+    let entity = entity_name(name);
+    quote!(
+        impl molecule::arrow::FixedLayout for #entity {
+            fn layout() -> molecule::arrow::Layout {
+                #layout
+            }
+        }
+    )
+}
+
+impl GenArrow for ast::Array {
+    fn gen_arrow(&self) -> m4::TokenStream {
+        let item_count = usize_lit(self.item_count);
+        let layout = if self.typ.is_atom() {
+            quote!(molecule::arrow::Layout::Bytes(#item_count))
+        } else {
+            let inner = layout_for_type(&self.typ);
+            quote!(molecule::arrow::Layout::array(#inner, #item_count))
+        };
+        impl_fixed_layout(self.name(), layout)
+    }
+}
+
+impl GenArrow for ast::Struct {
+    fn gen_arrow(&self) -> m4::TokenStream {
+        let fields = self.inner.iter().map(|f| {
+            let name = &f.name;
+            let layout = layout_for_type(&f.typ);
+            quote!((#name, #layout))
+        });
+        let layout = quote!(molecule::arrow::Layout::Struct(vec![ #( #fields, )* ]));
+        impl_fixed_layout(self.name(), layout)
+    }
+}
+
+impl GenArrow for ast::FixVec {
+    fn gen_arrow(&self) -> m4::TokenStream {
+        if self.typ.is_atom() {
+            return quote!();
+        }
+        let entity = entity_name(self.name());
+        let reader = reader_name(self.name());
+        let inner_layout = layout_for_type(&self.typ);
+        quote!(
+            impl<'r> #reader<'r> {
+                pub fn to_arrow(
+                    &self,
+                ) -> Result<molecule::arrow::ArrayRef, molecule::arrow::ArrowError> {
+                    let layout = #inner_layout;
+                    molecule::arrow::to_arrow(&layout, &self.as_slice()[Self::COUNT_SIZE..])
+                }
+            }
+
+            impl #entity {
+                pub fn to_arrow(
+                    &self,
+                ) -> Result<molecule::arrow::ArrayRef, molecule::arrow::ArrowError> {
+                    self.as_reader().to_arrow()
+                }
+
+                pub fn from_arrow(
+                    array: &dyn molecule::arrow::Array,
+                ) -> Result<Self, molecule::arrow::ArrowError> {
+                    let layout = #inner_layout;
+                    let raw = molecule::arrow::from_arrow(&layout, array)?;
+                    let item_count = molecule::pack_count(array.len(), Self::COUNT_SIZE).ok_or_else(|| {
+                        molecule::arrow::ArrowError::InvalidArgumentError(
+                            Self::NAME.to_owned() + ": too many items",
+                        )
+                    })?;
+                    let mut data = Vec::with_capacity(Self::COUNT_SIZE + raw.len());
+                    data.extend_from_slice(&item_count[..Self::COUNT_SIZE]);
+                    data.extend_from_slice(&raw);
+                    Ok(Self::new_unchecked(data.into()))
+                }
+            }
+        )
+    }
+}
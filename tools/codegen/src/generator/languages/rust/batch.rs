@@ -0,0 +1,27 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::entity_name;
+use crate::{
+    ast::verified::{self as ast, HasName},
+    schema_hash::type_hash,
+};
+
+pub(super) trait GenBatch {
+    fn gen_batch(&self) -> m4::TokenStream;
+}
+
+impl GenBatch for ast::Union {
+    fn gen_batch(&self) -> m4::TokenStream {
+        let entity = entity_name(self.name());
+        let hashes = self.inner.iter().map(|item| {
+            let bytes = type_hash(&item.typ);
+            quote!([#( #bytes, )*])
+        });
+        quote!(
+            impl molecule::batch::BatchItem for #entity {
+                const ITEM_TYPE_HASHES: &'static [molecule::batch::TypeHash] = &[#( #hashes, )*];
+            }
+        )
+    }
+}
@@ -4,36 +4,39 @@ use quote::quote;
 use super::super::utilities::{
     builder_name, entity_name, entity_union_name, field_name, usize_lit,
 };
-use crate::ast::verified::{self as ast, HasName};
+use crate::{
+    ast::verified::{self as ast, HasName},
+    generator::Options,
+};
 
 pub(in super::super) trait DefBuilder {
-    fn def_builder(&self) -> m4::TokenStream;
+    fn def_builder(&self, vis: &m4::TokenStream, options: &Options) -> m4::TokenStream;
 }
 
 impl DefBuilder for ast::Option_ {
-    fn def_builder(&self) -> m4::TokenStream {
+    fn def_builder(&self, vis: &m4::TokenStream, _options: &Options) -> m4::TokenStream {
         let builder = builder_name(self.name());
         let inner = entity_name(self.typ.name());
         quote!(
             #[derive(Debug, Default)]
-            pub struct #builder (pub(crate) Option<#inner>);
+            #vis struct #builder (pub(crate) Option<#inner>);
         )
     }
 }
 
 impl DefBuilder for ast::Union {
-    fn def_builder(&self) -> m4::TokenStream {
+    fn def_builder(&self, vis: &m4::TokenStream, _options: &Options) -> m4::TokenStream {
         let builder = builder_name(self.name());
         let entity_union = entity_union_name(self.name());
         quote!(
             #[derive(Debug, Default)]
-            pub struct #builder (pub(crate) #entity_union);
+            #vis struct #builder (pub(crate) #entity_union);
         )
     }
 }
 
 impl DefBuilder for ast::Array {
-    fn def_builder(&self) -> m4::TokenStream {
+    fn def_builder(&self, vis: &m4::TokenStream, _options: &Options) -> m4::TokenStream {
         let builder = builder_name(self.name());
         let inner = entity_name(self.typ.name());
         let item_count = usize_lit(self.item_count);
@@ -41,7 +44,7 @@ impl DefBuilder for ast::Array {
             .map(|_| inner.clone())
             .collect::<Vec<_>>();
         quote!(
-            pub struct #builder (pub(crate) [#inner; #item_count]);
+            #vis struct #builder (pub(crate) [#inner; #item_count]);
 
             impl ::core::fmt::Debug for #builder {
                 fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
@@ -59,30 +62,34 @@ impl DefBuilder for ast::Array {
 }
 
 impl DefBuilder for ast::Struct {
-    fn def_builder(&self) -> m4::TokenStream {
-        def_builder_for_struct_or_table(self.name(), &self.inner[..])
+    fn def_builder(&self, vis: &m4::TokenStream, _options: &Options) -> m4::TokenStream {
+        def_builder_for_struct_or_table(self.name(), &self.inner[..], vis)
     }
 }
 
 impl DefBuilder for ast::FixVec {
-    fn def_builder(&self) -> m4::TokenStream {
-        def_builder_for_vector(self.name(), self.typ.name())
+    fn def_builder(&self, vis: &m4::TokenStream, options: &Options) -> m4::TokenStream {
+        def_builder_for_vector(self.name(), self.typ.name(), vis, options)
     }
 }
 
 impl DefBuilder for ast::DynVec {
-    fn def_builder(&self) -> m4::TokenStream {
-        def_builder_for_vector(self.name(), self.typ.name())
+    fn def_builder(&self, vis: &m4::TokenStream, options: &Options) -> m4::TokenStream {
+        def_builder_for_vector(self.name(), self.typ.name(), vis, options)
     }
 }
 
 impl DefBuilder for ast::Table {
-    fn def_builder(&self) -> m4::TokenStream {
-        def_builder_for_struct_or_table(self.name(), &self.inner[..])
+    fn def_builder(&self, vis: &m4::TokenStream, _options: &Options) -> m4::TokenStream {
+        def_builder_for_struct_or_table(self.name(), &self.inner[..], vis)
     }
 }
 
-fn def_builder_for_struct_or_table(self_name: &str, inner: &[ast::FieldDecl]) -> m4::TokenStream {
+fn def_builder_for_struct_or_table(
+    self_name: &str,
+    inner: &[ast::FieldDecl],
+    vis: &m4::TokenStream,
+) -> m4::TokenStream {
     let builder = builder_name(self_name);
     let fields = inner.iter().map(|f| {
         let field_name = field_name(&f.name);
@@ -91,15 +98,26 @@ fn def_builder_for_struct_or_table(self_name: &str, inner: &[ast::FieldDecl]) ->
     });
     quote!(
         #[derive(Debug, Default)]
-        pub struct #builder { #( pub(crate) #fields )* }
+        #vis struct #builder { #( pub(crate) #fields )* }
     )
 }
 
-fn def_builder_for_vector(self_name: &str, inner_name: &str) -> m4::TokenStream {
+fn def_builder_for_vector(
+    self_name: &str,
+    inner_name: &str,
+    vis: &m4::TokenStream,
+    options: &Options,
+) -> m4::TokenStream {
     let builder = builder_name(self_name);
-    let inner = entity_name(&inner_name);
+    let inner = entity_name(inner_name);
+    let items = if let Some(inline_capacity) = options.small_vectors {
+        let inline_capacity = usize_lit(inline_capacity);
+        quote!(molecule::smallvec::SmallVec<[#inner; #inline_capacity]>)
+    } else {
+        quote!(Vec<#inner>)
+    };
     quote!(
         #[derive(Debug, Default)]
-        pub struct #builder (pub(crate) Vec<#inner>);
+        #vis struct #builder (pub(crate) #items);
     )
 }
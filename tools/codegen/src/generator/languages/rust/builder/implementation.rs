@@ -48,7 +48,7 @@ impl ImplBuilder for ast::Union {
                 molecule::NUMBER_SIZE + self.0.as_slice().len()
             }
             fn write<W: ::molecule::io::Write>(&self, writer: &mut W) -> ::molecule::io::Result<()> {
-                writer.write_all(&molecule::pack_number(self.0.item_id()))?;
+                writer.write_all(&molecule::number::Number::new(self.0.item_id()).to_le_bytes())?;
                 writer.write_all(self.0.as_slice())
             }
         )
@@ -104,10 +104,12 @@ impl ImplBuilder for ast::FixVec {
         });
         quote!(
             fn expected_length(&self) -> usize {
-                molecule::NUMBER_SIZE + Self::ITEM_SIZE * self.0.len()
+                Self::COUNT_SIZE + Self::ITEM_SIZE * self.0.len()
             }
             fn write<W: ::molecule::io::Write>(&self, writer: &mut W) -> ::molecule::io::Result<()> {
-                writer.write_all(&molecule::pack_number(self.0.len() as molecule::Number))?;
+                let item_count = molecule::pack_count(self.0.len(), Self::COUNT_SIZE)
+                    .unwrap_or_else(|| panic!("{}: too many items", Self::NAME));
+                writer.write_all(&item_count[..Self::COUNT_SIZE])?;
                 #write_inners
                 Ok(())
             }
@@ -125,21 +127,20 @@ impl ImplBuilder for ast::DynVec {
             fn write<W: ::molecule::io::Write>(&self, writer: &mut W) -> ::molecule::io::Result<()> {
                 let item_count = self.0.len();
                 if item_count == 0 {
-                    writer.write_all(&molecule::pack_number(molecule::NUMBER_SIZE as molecule::Number))?;
+                    writer.write_all(&molecule::number::Number::SIZE.to_le_bytes())?;
                 } else {
-                    let (total_size, offsets) = self.0.iter().fold(
-                        (
-                            molecule::NUMBER_SIZE * (item_count + 1),
-                            Vec::with_capacity(item_count),
-                        ),
-                        |(start, mut offsets), inner| {
-                            offsets.push(start);
-                            (start + inner.as_slice().len(), offsets)
-                        },
-                    );
-                    writer.write_all(&molecule::pack_number(total_size as molecule::Number))?;
-                    for offset in offsets.into_iter() {
-                        writer.write_all(&molecule::pack_number(offset as molecule::Number))?;
+                    // The offsets are computed while they're written, so no buffer is allocated.
+                    let mut offset = molecule::NUMBER_SIZE * (item_count + 1);
+                    let total_size = offset
+                        + self.0.iter().map(|inner| inner.as_slice().len()).sum::<usize>();
+                    let number = |size: usize| {
+                        molecule::number::Number::from_usize(size)
+                            .unwrap_or_else(|| panic!("{}: too large", Self::NAME))
+                    };
+                    writer.write_all(&number(total_size).to_le_bytes())?;
+                    for inner in self.0.iter() {
+                        writer.write_all(&number(offset).to_le_bytes())?;
+                        offset += inner.as_slice().len();
                     }
                     for inner in self.0.iter() {
                         writer.write_all(inner.as_slice())?;
@@ -159,7 +160,7 @@ impl ImplBuilder for ast::Table {
                     molecule::NUMBER_SIZE
                 }
                 fn write<W: ::molecule::io::Write>(&self, writer: &mut W) -> ::molecule::io::Result<()> {
-                    writer.write_all(&molecule::pack_number(molecule::NUMBER_SIZE as molecule::Number))?;
+                    writer.write_all(&molecule::number::Number::SIZE.to_le_bytes())?;
                     Ok(())
                 }
             )
@@ -181,9 +182,13 @@ impl ImplBuilder for ast::Table {
                         offsets.push(total_size);
                         total_size += self.#field.as_slice().len();
                     )*
-                    writer.write_all(&molecule::pack_number(total_size as molecule::Number))?;
+                    let number = |size: usize| {
+                        molecule::number::Number::from_usize(size)
+                            .unwrap_or_else(|| panic!("{}: too large", Self::NAME))
+                    };
+                    writer.write_all(&number(total_size).to_le_bytes())?;
                     for offset in offsets.into_iter() {
-                        writer.write_all(&molecule::pack_number(offset as molecule::Number))?;
+                        writer.write_all(&number(offset).to_le_bytes())?;
                     }
                     #(
                         writer.write_all(self.#field.as_slice())?;
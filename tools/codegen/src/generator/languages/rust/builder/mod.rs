@@ -2,14 +2,16 @@ use proc_macro2 as m4;
 use quote::quote;
 
 use super::utilities::builder_name;
-use crate::ast::verified::HasName;
+use crate::{ast::verified::HasName, generator::Options};
 
 mod definition;
 mod implementation;
 mod setters;
+mod strict;
+mod validation;
 
 pub(super) trait GenBuilder {
-    fn gen_builder(&self) -> m4::TokenStream;
+    fn gen_builder(&self, vis: &m4::TokenStream, options: &Options) -> m4::TokenStream;
 }
 
 impl<T> GenBuilder for T
@@ -18,20 +20,30 @@ where
         + definition::DefBuilder
         + super::constants::DefConstants
         + setters::ImplSetters
-        + implementation::ImplBuilder,
+        + implementation::ImplBuilder
+        + strict::ImplStrict
+        + validation::ImplValidate,
 {
-    fn gen_builder(&self) -> m4::TokenStream {
+    fn gen_builder(&self, vis: &m4::TokenStream, options: &Options) -> m4::TokenStream {
         let builder = builder_name(self.name());
-        let definition = self.def_builder();
+        let definition = self.def_builder(vis, options);
         let constants = self.def_constants();
-        let setters = self.impl_setters();
+        let setters = self.impl_setters(options);
         let implementation = self.impl_builder();
+        let strict = self.impl_strict();
+        let validate = if options.builder_validation {
+            self.impl_validate()
+        } else {
+            quote!()
+        };
         quote!(
             #definition
 
             impl #builder {
                 #( #constants )*
                 #( #setters )*
+                #strict
+                #validate
             }
 
             #implementation
@@ -1,15 +1,18 @@
 use proc_macro2 as m4;
 use quote::quote;
 
-use super::super::utilities::{entity_name, entity_union_name, field_name, func_name, usize_lit};
-use crate::ast::verified::{self as ast, HasName};
+use super::super::utilities::{entity_name, entity_union_name, field_name, usize_lit};
+use crate::{
+    ast::verified::{self as ast, HasName},
+    generator::Options,
+};
 
 pub(in super::super) trait ImplSetters {
-    fn impl_setters(&self) -> m4::TokenStream;
+    fn impl_setters(&self, options: &Options) -> m4::TokenStream;
 }
 
 impl ImplSetters for ast::Option_ {
-    fn impl_setters(&self) -> m4::TokenStream {
+    fn impl_setters(&self, _options: &Options) -> m4::TokenStream {
         let inner = entity_name(self.typ.name());
         quote!(
             pub fn set(mut self, v: Option<#inner>) -> Self {
@@ -21,7 +24,7 @@ impl ImplSetters for ast::Option_ {
 }
 
 impl ImplSetters for ast::Union {
-    fn impl_setters(&self) -> m4::TokenStream {
+    fn impl_setters(&self, _options: &Options) -> m4::TokenStream {
         let entity_union = entity_union_name(self.name());
         quote!(
             pub fn set<I>(mut self, v: I) -> Self
@@ -36,54 +39,42 @@ impl ImplSetters for ast::Union {
 }
 
 impl ImplSetters for ast::Array {
-    fn impl_setters(&self) -> m4::TokenStream {
+    fn impl_setters(&self, _options: &Options) -> m4::TokenStream {
         let inner = entity_name(self.typ.name());
         let item_count = usize_lit(self.item_count);
-        let entire_setter = quote!(
+        quote!(
             pub fn set(mut self, v: [#inner; #item_count]) -> Self {
                 self.0 = v;
                 self
             }
-        );
-        let each_setter = (0..self.item_count)
-            .map(|idx| {
-                let index = usize_lit(idx);
-                let func = func_name(&format!("nth{}", idx));
-                quote!(
-                    pub fn #func(mut self, v: #inner) -> Self {
-                        self.0[#index] = v;
-                        self
-                    }
-                )
-            })
-            .collect::<Vec<_>>();
-        quote!(
-            #entire_setter
-            #( #each_setter )*
+            pub fn set_nth(mut self, idx: usize, v: #inner) -> Self {
+                self.0[idx] = v;
+                self
+            }
         )
     }
 }
 
 impl ImplSetters for ast::Struct {
-    fn impl_setters(&self) -> m4::TokenStream {
+    fn impl_setters(&self, _options: &Options) -> m4::TokenStream {
         impl_setters_for_struct_or_table(&self.inner[..])
     }
 }
 
 impl ImplSetters for ast::FixVec {
-    fn impl_setters(&self) -> m4::TokenStream {
-        impl_setters_for_vector(self.typ.name())
+    fn impl_setters(&self, options: &Options) -> m4::TokenStream {
+        impl_setters_for_vector(self.typ.name(), options)
     }
 }
 
 impl ImplSetters for ast::DynVec {
-    fn impl_setters(&self) -> m4::TokenStream {
-        impl_setters_for_vector(self.typ.name())
+    fn impl_setters(&self, options: &Options) -> m4::TokenStream {
+        impl_setters_for_vector(self.typ.name(), options)
     }
 }
 
 impl ImplSetters for ast::Table {
-    fn impl_setters(&self) -> m4::TokenStream {
+    fn impl_setters(&self, _options: &Options) -> m4::TokenStream {
         impl_setters_for_struct_or_table(&self.inner[..])
     }
 }
@@ -107,11 +98,16 @@ fn impl_setters_for_struct_or_table(inner: &[ast::FieldDecl]) -> m4::TokenStream
     )
 }
 
-fn impl_setters_for_vector(inner_name: &str) -> m4::TokenStream {
-    let inner = entity_name(&inner_name);
+fn impl_setters_for_vector(inner_name: &str, options: &Options) -> m4::TokenStream {
+    let inner = entity_name(inner_name);
+    let set_stmt = if options.small_vectors.is_some() {
+        quote!(self.0 = v.into();)
+    } else {
+        quote!(self.0 = v;)
+    };
     quote!(
         pub fn set(mut self, v: Vec<#inner>) -> Self {
-            self.0 = v;
+            #set_stmt
             self
         }
         pub fn push(mut self, v: #inner) -> Self {
@@ -0,0 +1,62 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::super::utilities::{entity_name, field_name};
+use crate::ast::verified::{self as ast, HasName};
+
+/// The strict constructor of the builder, which takes the values of the required options, so
+/// they can't be forgotten.
+pub(in super::super) trait ImplStrict {
+    fn impl_strict(&self) -> m4::TokenStream {
+        quote!()
+    }
+}
+
+impl ImplStrict for ast::Option_ {}
+
+impl ImplStrict for ast::Union {}
+
+impl ImplStrict for ast::Array {}
+
+impl ImplStrict for ast::Struct {}
+
+impl ImplStrict for ast::FixVec {}
+
+impl ImplStrict for ast::DynVec {}
+
+impl ImplStrict for ast::Table {
+    fn impl_strict(&self) -> m4::TokenStream {
+        let required = self
+            .inner
+            .iter()
+            .filter(|f| f.required)
+            .map(|f| match f.typ.as_ref() {
+                ast::TopDecl::Option_(ref typ) => (f, typ),
+                _ => unreachable!("the required field ({}) should be an option", f.name),
+            })
+            .collect::<Vec<_>>();
+        if required.is_empty() {
+            return quote!();
+        }
+        let params = required.iter().map(|(f, typ)| {
+            let field_name = field_name(&f.name);
+            let inner = entity_name(typ.typ.name());
+            quote!(#field_name: #inner)
+        });
+        let stmts = required.iter().map(|(f, typ)| {
+            let field_name = field_name(&f.name);
+            let option = entity_name(typ.name());
+            quote!(
+                builder.#field_name = #option::new_builder().set(Some(#field_name)).build();
+            )
+        });
+        quote!(
+            /// Create a builder whose required options are set, the others are default.
+            pub fn new_strict(#( #params ),*) -> Self {
+                let mut builder = Self::default();
+                #( #stmts )*
+                builder
+            }
+        )
+    }
+}
@@ -0,0 +1,121 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::super::{reader::constraint_holds, utilities::field_name};
+use crate::ast::verified::{self as ast, HasInvariant};
+
+/// The checks of `validate`, which push the violations of the schema by the fields of the
+/// builder into `violations`.
+pub(in super::super) trait ImplValidate {
+    fn impl_validate_checks(&self) -> m4::TokenStream {
+        quote!()
+    }
+
+    fn impl_validate(&self) -> m4::TokenStream {
+        let checks = self.impl_validate_checks();
+        quote!(
+            /// Check the fields against the schema, and return all violations, which would make
+            /// `build` panic or build a value which fails the verification.
+            pub fn validate(&self) -> Result<(), Vec<molecule::error::Violation>> {
+                #[allow(unused_mut)]
+                let mut violations: Vec<molecule::error::Violation> = Vec::new();
+                #checks
+                if violations.is_empty() {
+                    Ok(())
+                } else {
+                    Err(violations)
+                }
+            }
+        )
+    }
+}
+
+fn size_check() -> m4::TokenStream {
+    quote!(
+        let size = molecule::prelude::Builder::expected_length(self);
+        if molecule::number::Number::from_usize(size).is_none() {
+            violations.push(molecule::error::Violation::TooLarge(
+                <Self as molecule::prelude::Builder>::Entity::NAME.to_owned(),
+                size,
+            ));
+        }
+    )
+}
+
+fn constraint_checks(constraints: &[ast::Constraint]) -> m4::TokenStream {
+    let checks = constraints.iter().map(|constraint| {
+        let holds = constraint_holds(constraint, |field| {
+            let field = field_name(field);
+            quote!(self.#field)
+        });
+        let text = constraint.to_string();
+        quote!(
+            if !(#holds) {
+                violations.push(molecule::error::Violation::ConstraintNotHeld(
+                    <Self as molecule::prelude::Builder>::Entity::NAME.to_owned(),
+                    #text.to_owned(),
+                ));
+            }
+        )
+    });
+    quote!(#( #checks )*)
+}
+
+impl ImplValidate for ast::Option_ {}
+
+impl ImplValidate for ast::Union {}
+
+impl ImplValidate for ast::Array {}
+
+impl ImplValidate for ast::Struct {
+    fn impl_validate_checks(&self) -> m4::TokenStream {
+        constraint_checks(self.constraints())
+    }
+}
+
+impl ImplValidate for ast::FixVec {
+    fn impl_validate_checks(&self) -> m4::TokenStream {
+        let max_count = m4::Literal::u64_suffixed(self.max_item_count());
+        let size_check = size_check();
+        quote!(
+            if molecule::pack_count(self.0.len(), Self::COUNT_SIZE).is_none() {
+                violations.push(molecule::error::Violation::TooManyItems(
+                    <Self as molecule::prelude::Builder>::Entity::NAME.to_owned(),
+                    self.0.len(),
+                    #max_count,
+                ));
+            }
+            #size_check
+        )
+    }
+}
+
+impl ImplValidate for ast::DynVec {
+    fn impl_validate_checks(&self) -> m4::TokenStream {
+        size_check()
+    }
+}
+
+impl ImplValidate for ast::Table {
+    fn impl_validate_checks(&self) -> m4::TokenStream {
+        let size_check = size_check();
+        let required_checks = self.inner.iter().filter(|f| f.required).map(|f| {
+            let field_name = field_name(&f.name);
+            let name = &f.name;
+            quote!(
+                if self.#field_name.is_none() {
+                    violations.push(molecule::error::Violation::MissingRequired(
+                        <Self as molecule::prelude::Builder>::Entity::NAME.to_owned(),
+                        #name.to_owned(),
+                    ));
+                }
+            )
+        });
+        let constraint_checks = constraint_checks(self.constraints());
+        quote!(
+            #size_check
+            #( #required_checks )*
+            #constraint_checks
+        )
+    }
+}
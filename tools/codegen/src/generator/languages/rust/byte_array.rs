@@ -0,0 +1,48 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{entity_name, reader_name, usize_lit};
+use crate::ast::verified::{self as ast, HasName};
+
+pub(in super::super) trait GenByteArray {
+    fn gen_byte_array(&self) -> m4::TokenStream;
+}
+
+impl GenByteArray for ast::Array {
+    fn gen_byte_array(&self) -> m4::TokenStream {
+        if !self.typ.is_atom() {
+            return quote!();
+        }
+        let entity = entity_name(self.name());
+        let reader = reader_name(self.name());
+        let size = usize_lit(self.item_count);
+        quote!(
+            impl #entity {
+                pub fn as_array(&self) -> &[u8; #size] {
+                    molecule::as_array(self.as_slice())
+                }
+                pub fn to_array(&self) -> [u8; #size] {
+                    *self.as_array()
+                }
+            }
+
+            impl<'r> #reader<'r> {
+                pub fn as_array(&self) -> &'r [u8; #size] {
+                    molecule::as_array(self.as_slice())
+                }
+            }
+
+            impl ::core::convert::From<[u8; #size]> for #entity {
+                fn from(value: [u8; #size]) -> Self {
+                    Self::new_unchecked(value.to_vec().into())
+                }
+            }
+
+            impl ::core::convert::From<#entity> for [u8; #size] {
+                fn from(value: #entity) -> Self {
+                    value.to_array()
+                }
+            }
+        )
+    }
+}
@@ -0,0 +1,142 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{entity_name, func_name, reader_name, usize_lit};
+use crate::ast::verified::{self as ast, HasName};
+
+pub(super) trait GenCanonical {
+    fn gen_canonical(&self) -> m4::TokenStream;
+}
+
+impl GenCanonical for ast::TopDecl {
+    fn gen_canonical(&self) -> m4::TokenStream {
+        let entity = entity_name(self.name());
+        let reader = reader_name(self.name());
+        let (size_stmts, write_stmts) = match self {
+            ast::TopDecl::Option_(_) => option_stmts(),
+            ast::TopDecl::Union(ref typ) => union_stmts(typ),
+            // The values of the fixed sizes never have extra fields.
+            ast::TopDecl::Array(_) | ast::TopDecl::Struct(_) | ast::TopDecl::FixVec(_) => (
+                quote!(self.as_slice().len()),
+                quote!(out.extend_from_slice(self.as_slice())),
+            ),
+            ast::TopDecl::DynVec(_) => dynvec_stmts(),
+            ast::TopDecl::Table(ref typ) => table_stmts(typ),
+            ast::TopDecl::Atom(_) => unreachable!(),
+        };
+        quote!(
+            impl<'r> molecule::canonical::Canonical for #reader<'r> {
+                fn canonical_size(&self) -> usize {
+                    #size_stmts
+                }
+
+                fn write_canonical(&self, out: &mut Vec<u8>) {
+                    #write_stmts
+                }
+            }
+
+            impl molecule::canonical::Canonical for #entity {
+                fn canonical_size(&self) -> usize {
+                    molecule::canonical::Canonical::canonical_size(&self.as_reader())
+                }
+
+                fn write_canonical(&self, out: &mut Vec<u8>) {
+                    molecule::canonical::Canonical::write_canonical(&self.as_reader(), out)
+                }
+            }
+        )
+    }
+}
+
+fn option_stmts() -> (m4::TokenStream, m4::TokenStream) {
+    (
+        quote!(self
+            .to_opt()
+            .map_or(0, |inner| molecule::canonical::Canonical::canonical_size(
+                &inner
+            ))),
+        quote!(if let Some(inner) = self.to_opt() {
+            molecule::canonical::Canonical::write_canonical(&inner, out);
+        }),
+    )
+}
+
+fn union_stmts(info: &ast::Union) -> (m4::TokenStream, m4::TokenStream) {
+    let item_ids = (0..info.inner.len()).map(usize_lit).collect::<Vec<_>>();
+    let inners = info
+        .inner
+        .iter()
+        .map(|item| reader_name(item.typ.name()))
+        .collect::<Vec<_>>();
+    let size_inners = inners.clone();
+    let size_stmts = quote!(
+        let inner = &self.as_slice()[molecule::NUMBER_SIZE..];
+        molecule::NUMBER_SIZE + match self.item_id() {
+            #( #item_ids => molecule::canonical::Canonical::canonical_size(
+                &#size_inners::new_unchecked(inner)
+            ), )*
+            _ => inner.len(),
+        }
+    );
+    let item_ids = (0..info.inner.len()).map(usize_lit);
+    let write_stmts = quote!(
+        let (item_id, inner) = self.as_slice().split_at(molecule::NUMBER_SIZE);
+        out.extend_from_slice(item_id);
+        // The item ID is verified, an unknown item is only possible in an unchecked reader.
+        match self.item_id() {
+            #( #item_ids => molecule::canonical::Canonical::write_canonical(
+                &#inners::new_unchecked(inner),
+                out,
+            ), )*
+            _ => out.extend_from_slice(inner),
+        }
+    );
+    (size_stmts, write_stmts)
+}
+
+fn dynvec_stmts() -> (m4::TokenStream, m4::TokenStream) {
+    (
+        quote!(
+            let mut size = molecule::NUMBER_SIZE * (self.len() + 1);
+            for idx in 0..self.len() {
+                size += molecule::canonical::Canonical::canonical_size(&self.get_unchecked(idx));
+            }
+            size
+        ),
+        quote!(
+            let sizes = (0..self.len())
+                .map(|idx| molecule::canonical::Canonical::canonical_size(&self.get_unchecked(idx)))
+                .collect::<Vec<_>>();
+            molecule::canonical::write_header(out, &sizes);
+            for idx in 0..self.len() {
+                molecule::canonical::Canonical::write_canonical(&self.get_unchecked(idx), out);
+            }
+        ),
+    )
+}
+
+/// The extra fields in the compatible mode are dropped.
+fn table_stmts(info: &ast::Table) -> (m4::TokenStream, m4::TokenStream) {
+    let funcs = info
+        .inner
+        .iter()
+        .map(|field| func_name(&field.name))
+        .collect::<Vec<_>>();
+    let count = usize_lit(info.inner.len() + 1);
+    let size_funcs = funcs.clone();
+    let size_stmts = quote!(
+        molecule::NUMBER_SIZE * #count
+            #( + molecule::canonical::Canonical::canonical_size(&self.#size_funcs()) )*
+    );
+    let write_stmts = {
+        let size_funcs = funcs.clone();
+        quote!(
+            let sizes: &[usize] = &[
+                #( molecule::canonical::Canonical::canonical_size(&self.#size_funcs()), )*
+            ];
+            molecule::canonical::write_header(out, sizes);
+            #( molecule::canonical::Canonical::write_canonical(&self.#funcs(), out); )*
+        )
+    };
+    (size_stmts, write_stmts)
+}
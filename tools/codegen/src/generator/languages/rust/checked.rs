@@ -0,0 +1,64 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{entity_name, func_name, reader_name, usize_lit};
+use crate::ast::verified::{self as ast, HasName as _};
+
+pub(super) trait GenChecked {
+    fn gen_checked(&self) -> m4::TokenStream;
+}
+
+impl GenChecked for ast::TopDecl {
+    fn gen_checked(&self) -> m4::TokenStream {
+        let entity = entity_name(self.name());
+        let reader = reader_name(self.name());
+        let fields = match self {
+            ast::TopDecl::Struct(ref typ) => &typ.inner,
+            ast::TopDecl::Table(ref typ) => &typ.inner,
+            _ => return quote!(),
+        };
+        let getters = |is_entity: bool| {
+            fields
+                .iter()
+                .enumerate()
+                .map(|(i, f)| {
+                    let func = func_name(&f.name);
+                    let try_func = func_name(&format!("try_{}", f.name));
+                    let getter_ret = if is_entity {
+                        let inner = entity_name(f.typ.name());
+                        quote!(#inner)
+                    } else {
+                        let inner = reader_name(f.typ.name());
+                        quote!(#inner<'r>)
+                    };
+                    let verify = if let ast::TopDecl::Table(_) = self {
+                        // Verify the header and the field only, in the compatible mode, since
+                        // the unchecked data may have extra fields.
+                        let start = usize_lit(i);
+                        let end = usize_lit(i + 1);
+                        quote!(#reader::verify_fields(self.as_slice(), #start..#end, true)?;)
+                    } else {
+                        quote!(#reader::verify(self.as_slice(), false)?;)
+                    };
+                    quote!(
+                        pub fn #try_func(&self) -> molecule::error::VerificationResult<#getter_ret> {
+                            #verify
+                            Ok(self.#func())
+                        }
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let entity_getters = getters(true);
+        let reader_getters = getters(false);
+        quote!(
+            impl #entity {
+                #( #entity_getters )*
+            }
+
+            impl<'r> #reader<'r> {
+                #( #reader_getters )*
+            }
+        )
+    }
+}
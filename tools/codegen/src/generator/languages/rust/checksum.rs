@@ -0,0 +1,45 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{builder_name, entity_name};
+use crate::ast::verified::{self as ast, HasName};
+
+fn algorithm(checksum: ast::Checksum) -> m4::TokenStream {
+    match checksum {
+        ast::Checksum::Crc32 => quote!(molecule::checksum::Algorithm::Crc32),
+        ast::Checksum::Xxh32 => quote!(molecule::checksum::Algorithm::Xxh32),
+    }
+}
+
+pub(in super::super) trait GenChecksum {
+    fn gen_checksum(&self) -> m4::TokenStream;
+}
+
+impl GenChecksum for ast::Table {
+    fn gen_checksum(&self) -> m4::TokenStream {
+        let algorithm = if let Some(checksum) = self.checksum {
+            algorithm(checksum)
+        } else {
+            return quote!();
+        };
+        let entity = entity_name(self.name());
+        let builder = builder_name(self.name());
+        quote!(
+            impl molecule::checksum::Checksummed for #entity {
+                const ALGORITHM: molecule::checksum::Algorithm = #algorithm;
+            }
+
+            impl #entity {
+                pub fn to_bytes_with_checksum(&self) -> molecule::bytes::Bytes {
+                    molecule::checksum::seal(#algorithm, self.as_slice()).into()
+                }
+            }
+
+            impl #builder {
+                pub fn build_with_checksum(&self) -> molecule::bytes::Bytes {
+                    self.build().to_bytes_with_checksum()
+                }
+            }
+        )
+    }
+}
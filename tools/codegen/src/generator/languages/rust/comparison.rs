@@ -0,0 +1,48 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{entity_name, reader_name, usize_lit};
+use crate::ast::verified::{self as ast, DefaultContent as _, HasName as _};
+
+pub(super) trait GenComparison {
+    fn gen_comparison(&self) -> m4::TokenStream;
+}
+
+impl GenComparison for ast::TopDecl {
+    fn gen_comparison(&self) -> m4::TokenStream {
+        let entity = entity_name(self.name());
+        let reader = reader_name(self.name());
+        let default_content = self
+            .default_content()
+            .into_iter()
+            .map(|b| usize_lit(b as usize));
+        quote!(
+            impl #entity {
+                pub fn eq_bytes(&self, other: &#reader<'_>) -> bool {
+                    self.as_slice() == other.as_slice()
+                }
+                pub fn is_default(&self) -> bool {
+                    self.as_reader().is_default()
+                }
+                pub fn default_ref() -> &'static Self {
+                    static DEFAULT: molecule::lazy::Lazy<#entity> =
+                        molecule::lazy::Lazy::new(<#entity as ::core::default::Default>::default);
+                    DEFAULT.get()
+                }
+            }
+
+            impl<'r> #reader<'r> {
+                pub const DEFAULT_ENCODING: &'static [u8] = &[#( #default_content, )*];
+                pub fn eq_bytes(&self, other: &#reader<'_>) -> bool {
+                    self.as_slice() == other.as_slice()
+                }
+                pub fn eq_slice(&self, slice: &[u8]) -> bool {
+                    self.as_slice() == slice
+                }
+                pub fn is_default(&self) -> bool {
+                    self.as_slice() == Self::DEFAULT_ENCODING
+                }
+            }
+        )
+    }
+}
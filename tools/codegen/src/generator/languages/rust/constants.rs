@@ -52,8 +52,10 @@ impl DefConstants for ast::Struct {
 impl DefConstants for ast::FixVec {
     fn def_constants(&self) -> m4::TokenStream {
         let item_size = usize_lit(self.item_size);
+        let count_size = usize_lit(self.count_size);
         quote!(
             pub const ITEM_SIZE: usize = #item_size;
+            pub const COUNT_SIZE: usize = #count_size;
         )
     }
 }
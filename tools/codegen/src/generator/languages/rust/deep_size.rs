@@ -0,0 +1,139 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{entity_name, func_name, reader_name, usize_lit};
+use crate::ast::verified::{self as ast, HasName};
+
+pub(super) trait GenDeepSize {
+    fn gen_deep_size(&self) -> m4::TokenStream;
+}
+
+impl GenDeepSize for ast::TopDecl {
+    fn gen_deep_size(&self) -> m4::TokenStream {
+        let entity = entity_name(self.name());
+        let reader = reader_name(self.name());
+        let stmts = match self {
+            ast::TopDecl::Option_(ref typ) => option_stmts(typ),
+            ast::TopDecl::Union(ref typ) => union_stmts(typ),
+            ast::TopDecl::Array(_) => {
+                quote!(molecule::deep_size::SizeBreakdown::payload(
+                    Self::TOTAL_SIZE
+                ))
+            }
+            ast::TopDecl::Struct(ref typ) => struct_stmts(typ),
+            ast::TopDecl::FixVec(ref typ) => {
+                let count_size = usize_lit(typ.count_size);
+                quote!(molecule::deep_size::SizeBreakdown {
+                    header: #count_size,
+                    payload: self.as_slice().len() - #count_size,
+                    fields: Vec::new(),
+                })
+            }
+            ast::TopDecl::DynVec(_) => dynvec_stmts(),
+            ast::TopDecl::Table(ref typ) => table_stmts(typ),
+            ast::TopDecl::Atom(_) => unreachable!(),
+        };
+        quote!(
+            impl<'r> molecule::deep_size::DeepSize for #reader<'r> {
+                fn deep_size_breakdown(&self) -> molecule::deep_size::SizeBreakdown {
+                    #stmts
+                }
+            }
+
+            impl molecule::deep_size::DeepSize for #entity {
+                fn deep_size_breakdown(&self) -> molecule::deep_size::SizeBreakdown {
+                    molecule::deep_size::DeepSize::deep_size_breakdown(&self.as_reader())
+                }
+            }
+        )
+    }
+}
+
+fn option_stmts(_: &ast::Option_) -> m4::TokenStream {
+    quote!(if let Some(inner) = self.to_opt() {
+        molecule::deep_size::DeepSize::deep_size_breakdown(&inner)
+    } else {
+        molecule::deep_size::SizeBreakdown::default()
+    })
+}
+
+fn union_stmts(info: &ast::Union) -> m4::TokenStream {
+    let items = info.inner.iter().enumerate().map(|(index, item)| {
+        let item_id = usize_lit(index);
+        let name = item.typ.name();
+        let inner = reader_name(name);
+        quote!(#item_id => (
+            #name,
+            molecule::deep_size::DeepSize::deep_size_breakdown(&#inner::new_unchecked(inner)),
+        ),)
+    });
+    quote!(
+        let inner = &self.as_slice()[molecule::NUMBER_SIZE..];
+        // An unknown item in the compatible mode is only a payload.
+        let (name, item) = match self.item_id() {
+            #( #items )*
+            _ => return molecule::deep_size::SizeBreakdown {
+                header: molecule::NUMBER_SIZE,
+                payload: inner.len(),
+                fields: Vec::new(),
+            },
+        };
+        molecule::deep_size::SizeBreakdown {
+            header: molecule::NUMBER_SIZE + item.header,
+            payload: item.payload,
+            fields: vec![(name.to_owned(), item)],
+        }
+    )
+}
+
+fn fields_stmts(fields: &[ast::FieldDecl]) -> m4::TokenStream {
+    let fields = fields.iter().map(|field| {
+        let name = &field.name;
+        let func = func_name(name);
+        quote!((
+            #name.to_owned(),
+            molecule::deep_size::DeepSize::deep_size_breakdown(&self.#func()),
+        ),)
+    });
+    quote!(vec![#( #fields )*])
+}
+
+fn struct_stmts(info: &ast::Struct) -> m4::TokenStream {
+    let fields = fields_stmts(&info.inner);
+    quote!(molecule::deep_size::SizeBreakdown {
+        header: 0,
+        payload: Self::TOTAL_SIZE,
+        fields: #fields,
+    })
+}
+
+fn dynvec_stmts() -> m4::TokenStream {
+    quote!(
+        let mut header = molecule::NUMBER_SIZE * (self.len() + 1);
+        for idx in 0..self.len() {
+            header += molecule::deep_size::DeepSize::deep_size_breakdown(&self.get_unchecked(idx))
+                .header;
+        }
+        molecule::deep_size::SizeBreakdown {
+            header,
+            payload: self.as_slice().len() - header,
+            fields: Vec::new(),
+        }
+    )
+}
+
+/// The extra fields in the compatible mode are counted in the header and the payload, but not in
+/// the fields.
+fn table_stmts(info: &ast::Table) -> m4::TokenStream {
+    let fields = fields_stmts(&info.inner);
+    quote!(
+        let fields: Vec<(_, molecule::deep_size::SizeBreakdown)> = #fields;
+        let header = molecule::NUMBER_SIZE * (self.field_count() + 1)
+            + fields.iter().map(|(_, field)| field.header).sum::<usize>();
+        molecule::deep_size::SizeBreakdown {
+            header,
+            payload: self.as_slice().len() - header,
+            fields,
+        }
+    )
+}
@@ -1,11 +1,37 @@
 use proc_macro2 as m4;
 use quote::quote;
 
-use super::utilities::func_name;
+use super::utilities::{func_name, usize_lit};
 use crate::ast::verified as ast;
 
+/// The fields are displayed by their formatters which are set by `@display`, if any.
+fn display_fields(fields: &[ast::FieldDecl]) -> impl Iterator<Item = m4::TokenStream> + '_ {
+    fields.iter().enumerate().map(|(i, f)| {
+        let field = f.name.clone();
+        let func = func_name(&f.name);
+        let value = if let Some(ref formatter) = f.display {
+            let offset = usize_lit(f.typ.raw_bytes_offset().unwrap());
+            quote!(molecule::display::Formatted {
+                formatter: #formatter,
+                data: &self.#func().as_slice()[#offset..],
+            })
+        } else {
+            quote!(self.#func())
+        };
+        if i == 0 {
+            quote!(write!(f, "{}: {}", #field, #value)?;)
+        } else {
+            quote!(write!(f, ", {}: {}", #field, #value)?;)
+        }
+    })
+}
+
 pub(super) trait ImplDisplay {
     fn impl_display(&self) -> m4::TokenStream;
+
+    fn impl_debug(&self) -> m4::TokenStream {
+        quote!(write!(f, "{}({:#x})", Self::NAME, self))
+    }
 }
 
 impl ImplDisplay for ast::Option_ {
@@ -30,41 +56,46 @@ impl ImplDisplay for ast::Union {
 
 impl ImplDisplay for ast::Array {
     fn impl_display(&self) -> m4::TokenStream {
-        if self.typ.is_atom() {
+        if self.logical == Some(ast::Logical::Uuid) {
+            quote!(write!(
+                f,
+                "{}({})",
+                Self::NAME,
+                molecule::logical::UuidDisplay(self.as_slice())
+            ))
+        } else if self.typ.is_atom() {
             quote!(
                 use molecule::hex_string;
                 let raw_data = hex_string(&self.raw_data());
                 write!(f, "{}(0x{})", Self::NAME, raw_data)
             )
         } else {
-            let display_items = (0..self.item_count).map(|idx| {
-                let func = func_name(&format!("nth{}", idx));
-                if idx == 0 {
-                    quote!(write!(f, "{}", self.#func())?;)
-                } else {
-                    quote!(write!(f, ", {}", self.#func())?;)
-                }
-            });
             quote!(
                 write!(f, "{} [", Self::NAME)?;
-                #( #display_items )*
+                for idx in 0..Self::ITEM_COUNT {
+                    if idx > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", self.get_unchecked(idx))?;
+                }
                 write!(f, "]")
             )
         }
     }
+
+    /// The UUIDs are canonical strings in the debug output as well.
+    fn impl_debug(&self) -> m4::TokenStream {
+        if self.logical == Some(ast::Logical::Uuid) {
+            self.impl_display()
+        } else {
+            quote!(write!(f, "{}({:#x})", Self::NAME, self))
+        }
+    }
 }
 
 impl ImplDisplay for ast::Struct {
     fn impl_display(&self) -> m4::TokenStream {
-        let display_fields = self.inner.iter().enumerate().map(|(i, f)| {
-            let field = f.name.clone();
-            let func = func_name(&f.name);
-            if i == 0 {
-                quote!(write!(f, "{}: {}", #field, self.#func())?;)
-            } else {
-                quote!(write!(f, ", {}: {}", #field, self.#func())?;)
-            }
-        });
+        let display_fields = display_fields(&self.inner);
         quote!(
             write!(f, "{} {{ ", Self::NAME)?;
             #( #display_fields )*
@@ -115,15 +146,7 @@ impl ImplDisplay for ast::DynVec {
 
 impl ImplDisplay for ast::Table {
     fn impl_display(&self) -> m4::TokenStream {
-        let display_fields = self.inner.iter().enumerate().map(|(i, f)| {
-            let field = f.name.clone();
-            let func = func_name(&f.name);
-            if i == 0 {
-                quote!(write!(f, "{}: {}", #field, self.#func())?;)
-            } else {
-                quote!(write!(f, ", {}: {}", #field, self.#func())?;)
-            }
-        });
+        let display_fields = display_fields(&self.inner);
         let display_unresolved = if self.inner.is_empty() {
             quote!(write!(f, ".. ({} fields)", extra_count)?;)
         } else {
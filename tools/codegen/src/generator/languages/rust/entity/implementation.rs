@@ -1,7 +1,7 @@
 use proc_macro2 as m4;
 use quote::quote;
 
-use super::super::utilities::{builder_name, entity_name, field_name, func_name, reader_name};
+use super::super::utilities::{builder_name, entity_name, field_name, reader_name, usize_lit};
 use crate::ast::verified::{self as ast, HasName};
 
 pub(in super::super) trait ImplEntity: HasName {
@@ -63,9 +63,13 @@ impl ImplEntity for ast::Union {
 
 impl ImplEntity for ast::Array {
     fn impl_entity_internal(&self) -> m4::TokenStream {
-        let items = (0..self.item_count)
-            .map(|idx| func_name(&format!("nth{}", idx)))
-            .map(|func| quote!(self.#func()));
+        let items = (0..self.item_count).map(usize_lit).map(|idx| {
+            if self.typ.is_atom() {
+                quote!(Byte::new(self.as_slice()[#idx]))
+            } else {
+                quote!(self.get_unchecked(#idx))
+            }
+        });
         quote!(
             fn as_builder(self) -> Self::Builder {
                 Self::new_builder().set([ #( #items, )* ])
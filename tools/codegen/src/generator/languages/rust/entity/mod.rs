@@ -7,7 +7,7 @@ use crate::ast::verified::{DefaultContent, HasName};
 mod implementation;
 
 pub(super) trait GenEntity {
-    fn gen_entity(&self) -> m4::TokenStream;
+    fn gen_entity(&self, vis: &m4::TokenStream) -> m4::TokenStream;
 }
 
 impl<T> GenEntity for T
@@ -20,7 +20,7 @@ where
         + super::getters::ImplGetters
         + implementation::ImplEntity,
 {
-    fn gen_entity(&self) -> m4::TokenStream {
+    fn gen_entity(&self, vis: &m4::TokenStream) -> m4::TokenStream {
         let entity = entity_name(self.name());
         let reader = reader_name(self.name());
         let default_content = self
@@ -28,13 +28,23 @@ where
             .into_iter()
             .map(|b| usize_lit(b as usize));
         let display_stmts = self.impl_display();
+        let debug_stmts = self.impl_debug();
         let constants = self.def_constants();
         let properties = self.def_properties();
         let getters = self.impl_getters_for_entity();
         let implementation = self.impl_entity();
         quote!(
             #[derive(Clone)]
-            pub struct #entity(molecule::bytes::Bytes);
+            #vis struct #entity(molecule::bytes::Bytes);
+
+            // The entities and the readers can be shared between threads, and cloned without
+            // copying the bytes.
+            #[cfg(target_has_atomic = "ptr")]
+            const _: fn() = || {
+                fn assert_shared<T: Send + Sync + Clone>() {}
+                assert_shared::<#entity>();
+                assert_shared::<#reader<'static>>();
+            };
 
             impl ::core::fmt::LowerHex for #entity {
                 fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
@@ -48,7 +58,7 @@ where
 
             impl ::core::fmt::Debug for #entity {
                 fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
-                    write!(f, "{}({:#x})", Self::NAME, self)
+                    #debug_stmts
                 }
             }
 
@@ -74,6 +84,12 @@ where
                 }
             }
 
+            impl molecule::content_hash::ContentHash for #entity {
+                fn canonical_bytes(&self) -> &[u8] {
+                    self.as_slice()
+                }
+            }
+
             #implementation
         )
     }
@@ -7,11 +7,11 @@ use super::utilities::{
 use crate::ast::verified::{self as ast, HasName};
 
 pub(in super::super) trait GenEnumerator {
-    fn gen_enumerator(&self) -> m4::TokenStream;
+    fn gen_enumerator(&self, vis: &m4::TokenStream) -> m4::TokenStream;
 }
 
 impl GenEnumerator for ast::Union {
-    fn gen_enumerator(&self) -> m4::TokenStream {
+    fn gen_enumerator(&self, vis: &m4::TokenStream) -> m4::TokenStream {
         let entity_union = entity_union_name(self.name());
         let reader_union = reader_union_name(self.name());
         let entity_union_string = entity_union.to_string();
@@ -78,12 +78,14 @@ impl GenEnumerator for ast::Union {
         };
         let code_union_definitions_and_impl_traits = quote!(
             #[derive(Debug, Clone)]
-            pub enum #entity_union {
-                #( #union_items(#entity_inners), )*
+            #[repr(u32)]
+            #vis enum #entity_union {
+                #( #union_items(#entity_inners) = #union_ids, )*
             }
             #[derive(Debug, Clone, Copy)]
-            pub enum #reader_union<'r> {
-                #( #union_items(#reader_inners<'r>), )*
+            #[repr(u32)]
+            #vis enum #reader_union<'r> {
+                #( #union_items(#reader_inners<'r>) = #union_ids, )*
             }
 
             impl ::core::default::Default for #entity_union {
@@ -156,6 +158,33 @@ impl GenEnumerator for ast::Union {
                 )
             })
             .collect::<Vec<_>>();
+        // The aliases look like the variants when the items are constructed.
+        let (entity_aliases, reader_aliases): (Vec<_>, Vec<_>) = self
+            .inner
+            .iter()
+            .filter_map(|inner| {
+                let alias = union_item_name(inner.alias.as_ref()?);
+                let item_name = union_item_name(inner.typ.name());
+                let entity_name = entity_name(inner.typ.name());
+                let reader_name = reader_name(inner.typ.name());
+                let note = format!("use `{}` instead", item_name);
+                let entity_alias = quote!(
+                    #[deprecated(note = #note)]
+                    #[allow(non_snake_case)]
+                    #vis fn #alias(item: #entity_name) -> Self {
+                        #entity_union::#item_name(item)
+                    }
+                );
+                let reader_alias = quote!(
+                    #[deprecated(note = #note)]
+                    #[allow(non_snake_case)]
+                    #vis fn #alias(item: #reader_name<'r>) -> Self {
+                        #reader_union::#item_name(item)
+                    }
+                );
+                Some((entity_alias, reader_alias))
+            })
+            .unzip();
         let code_impl_entity_union = {
             quote!(
                 impl #entity_union {
@@ -170,7 +199,7 @@ impl GenEnumerator for ast::Union {
                             #( #entity_union_item_paths(item) => item.as_slice(), )*
                         }
                     }
-                    pub fn item_id(&self) -> molecule::Number {
+                    pub const fn item_id(&self) -> molecule::Number {
                         match self {
                             #( #entity_union_item_paths(_) => #union_ids, )*
                         }
@@ -185,6 +214,7 @@ impl GenEnumerator for ast::Union {
                             #( #entity_union_item_paths(item) => item.as_reader().into(), )*
                         }
                     }
+                    #( #entity_aliases )*
                 }
             )
         };
@@ -197,7 +227,7 @@ impl GenEnumerator for ast::Union {
                             #( #reader_union_item_paths(item) => item.as_slice(), )*
                         }
                     }
-                    pub fn item_id(&self) -> molecule::Number {
+                    pub const fn item_id(&self) -> molecule::Number {
                         match self {
                             #( #reader_union_item_paths(_) => #union_ids, )*
                         }
@@ -207,6 +237,7 @@ impl GenEnumerator for ast::Union {
                             #( #reader_union_item_paths(_) => #union_items_string, )*
                         }
                     }
+                    #( #reader_aliases )*
                 }
             )
         };
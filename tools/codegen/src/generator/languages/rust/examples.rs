@@ -0,0 +1,204 @@
+use case::CaseExt;
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{entity_name, field_name, func_name, path_new, reader_name};
+use crate::{
+    ast::verified::{self as ast, HasName},
+    examples::{file_stem, is_uint, Sample, Value},
+    generator::Options,
+    VERSION,
+};
+
+pub(crate) fn generate(samples: &[Sample], module: Option<&str>) -> Vec<(String, String)> {
+    let options = Options {
+        format: true,
+        ..Default::default()
+    };
+    let module = path_new(module.unwrap_or("crate"));
+    let mut files = samples
+        .iter()
+        .map(|sample| {
+            let code = gen_example(sample, &module).to_string();
+            let code = super::style::restyle(&code, &options).unwrap_or(code);
+            let content = format!(
+                "// Generated by Molecule {}\n// An example of building and parsing `{}`.\n\n{}",
+                VERSION,
+                sample.decl.name(),
+                code
+            );
+            (format!("{}.rs", file_stem(&sample.decl)), content)
+        })
+        .collect::<Vec<_>>();
+    let mut content = format!("// Generated by Molecule {}\n\n", VERSION);
+    for sample in samples {
+        content.push_str(&format!("pub mod {};\n", file_stem(&sample.decl)));
+    }
+    files.push(("mod.rs".to_owned(), content));
+    files
+}
+
+fn gen_example(sample: &Sample, module: &m4::TokenStream) -> m4::TokenStream {
+    let entity = entity_name(sample.decl.name());
+    let reader = reader_name(sample.decl.name());
+    let mut lets = Vec::new();
+    let value = gen_value(sample, "", &mut lets);
+    let parts = gen_parts(&sample.decl);
+    quote!(
+        use molecule::prelude::*;
+        use #module::*;
+
+        pub fn build() -> #entity {
+            #( #lets )*
+            #value
+        }
+
+        pub fn parse(slice: &[u8]) -> molecule::error::VerificationResult<()> {
+            let reader = #reader::from_slice(slice)?;
+            #parts
+            Ok(())
+        }
+
+        pub fn run() {
+            let value = build();
+            println!("{}", value);
+            parse(value.as_slice()).unwrap();
+        }
+    )
+}
+
+/// The expression of a value; the nested values which are not primitives are bound to variables
+/// before it, which are named by the paths of them.
+fn gen_value(sample: &Sample, path: &str, lets: &mut Vec<m4::TokenStream>) -> m4::TokenStream {
+    let entity = entity_name(sample.decl.name());
+    let mut nested = |sample: &Sample, name: &str| {
+        let path = if path.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{}_{}", path, name)
+        };
+        let value = gen_value(sample, &path, lets);
+        match sample.value {
+            Value::Byte(_) | Value::Uint(_) | Value::Default => value,
+            _ => {
+                let var = func_name(&path);
+                lets.push(quote!(let #var = #value;));
+                quote!(#var)
+            }
+        }
+    };
+    match sample.value {
+        Value::Byte(byte) => {
+            let byte = m4::Literal::u8_suffixed(byte);
+            quote!(Byte::new(#byte))
+        }
+        Value::Bytes(ref data) => match sample.decl.as_ref() {
+            ast::TopDecl::Array(_) => {
+                let bytes = data.iter().map(|byte| m4::Literal::u8_suffixed(*byte));
+                quote!(#entity::new_builder().set([ #( #bytes ),* ].map(Byte::new)).build())
+            }
+            _ => {
+                let bytes = if data
+                    .iter()
+                    .all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+                {
+                    let literal = m4::Literal::byte_string(data);
+                    quote!(#literal)
+                } else {
+                    let bytes = data.iter().map(|byte| m4::Literal::u8_suffixed(*byte));
+                    quote!([ #( #bytes ),* ])
+                };
+                quote!(#entity::new_builder().extend(#bytes.iter().copied().map(Byte::new)).build())
+            }
+        },
+        Value::Uint(number) => {
+            let literal = match sample.decl.as_ref() {
+                ast::TopDecl::Array(ref typ) => match typ.item_count {
+                    2 => m4::Literal::u16_suffixed(number as u16),
+                    4 => m4::Literal::u32_suffixed(number as u32),
+                    8 => m4::Literal::u64_suffixed(number as u64),
+                    _ => m4::Literal::u128_suffixed(number),
+                },
+                _ => unreachable!(),
+            };
+            quote!(#entity::from(#literal))
+        }
+        Value::Fields(ref fields) => {
+            let setters = fields
+                .iter()
+                .map(|(name, field)| {
+                    let setter = field_name(name);
+                    let value = nested(field, &name.to_snake());
+                    quote!(.#setter(#value))
+                })
+                .collect::<Vec<_>>();
+            quote!(#entity::new_builder() #( #setters )* .build())
+        }
+        Value::Items(ref items) => {
+            let items = items
+                .iter()
+                .enumerate()
+                .map(|(idx, item)| nested(item, &format!("item{}", idx)))
+                .collect::<Vec<_>>();
+            if let ast::TopDecl::Array(_) = sample.decl.as_ref() {
+                quote!(#entity::new_builder().set([ #( #items ),* ]).build())
+            } else {
+                quote!(#entity::new_builder() #( .push(#items) )* .build())
+            }
+        }
+        Value::Some(ref inner) => {
+            let inner = nested(inner, "inner");
+            quote!(#entity::new_builder().set(Some(#inner)).build())
+        }
+        Value::Item(ref item) => {
+            let item = nested(item, "item");
+            quote!(#entity::new_builder().set(#item).build())
+        }
+        Value::Default => quote!(Default::default()),
+    }
+}
+
+/// Print the parts of a reader.
+fn gen_parts(decl: &ast::TopDecl) -> m4::TokenStream {
+    match decl {
+        ast::TopDecl::Atom(_) => quote!(),
+        ast::TopDecl::Option_(_) => quote!(if let Some(inner) = reader.to_opt() {
+            println!("Some: {}", inner);
+        } else {
+            println!("None");
+        }),
+        ast::TopDecl::Union(_) => quote!(
+            println!("item id: {}", reader.item_id());
+            println!("item: {}", reader.to_enum());
+        ),
+        ast::TopDecl::Array(ref typ) if is_uint(typ) => {
+            let to_uint = func_name(&format!("to_u{}", typ.item_count * 8));
+            quote!(println!("value: {}", reader.#to_uint());)
+        }
+        ast::TopDecl::Array(ref typ) if typ.typ.is_atom() => {
+            quote!(println!("raw data: {:?}", reader.raw_data());)
+        }
+        ast::TopDecl::Struct(ref typ) => gen_fields(&typ.inner),
+        ast::TopDecl::Table(ref typ) => gen_fields(&typ.inner),
+        ast::TopDecl::FixVec(ref typ) if typ.typ.is_atom() => {
+            quote!(println!("raw data: {:?}", reader.raw_data());)
+        }
+        ast::TopDecl::Array(_) | ast::TopDecl::FixVec(_) | ast::TopDecl::DynVec(_) => {
+            quote!(for (idx, item) in reader.iter().enumerate() {
+                println!("item {}: {}", idx, item);
+            })
+        }
+    }
+}
+
+fn gen_fields(inner: &[ast::FieldDecl]) -> m4::TokenStream {
+    if inner.is_empty() {
+        return quote!(println!("{}", reader););
+    }
+    let fields = inner.iter().map(|field| {
+        let getter = field_name(&field.name);
+        let format = m4::Literal::string(&format!("{}: {{}}", field.name));
+        quote!(println!(#format, reader.#getter());)
+    });
+    quote!( #( #fields )* )
+}
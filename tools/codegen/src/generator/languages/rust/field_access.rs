@@ -0,0 +1,135 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::reader::verify_table_header;
+use super::utilities::{entity_name, reader_name, usize_lit};
+use crate::ast::verified::{self as ast, HasName as _};
+
+pub(super) trait GenFieldAccess {
+    fn gen_field_access(&self) -> m4::TokenStream;
+
+    /// `verify_fields` of the readers of tables, which is also used by the checked getters.
+    fn gen_verify_fields(&self) -> m4::TokenStream;
+}
+
+impl GenFieldAccess for ast::TopDecl {
+    fn gen_field_access(&self) -> m4::TokenStream {
+        let entity = entity_name(self.name());
+        let reader = reader_name(self.name());
+        let (methods, reader_methods) = match self {
+            ast::TopDecl::FixVec(_) => (fixvec_methods(), quote!()),
+            ast::TopDecl::DynVec(_) => (dynvec_methods(), quote!()),
+            ast::TopDecl::Table(_) => (table_methods(), table_reader_methods()),
+            _ => return quote!(),
+        };
+        quote!(
+            impl #entity {
+                #methods
+            }
+
+            impl<'r> #reader<'r> {
+                #methods
+                #reader_methods
+            }
+        )
+    }
+
+    fn gen_verify_fields(&self) -> m4::TokenStream {
+        let typ = match self {
+            ast::TopDecl::Table(ref typ) => typ,
+            _ => return quote!(),
+        };
+        let reader = reader_name(self.name());
+        let header = verify_table_header(typ);
+        let verify_fields = typ.inner.iter().enumerate().map(|(i, f)| {
+            let field = reader_name(f.typ.name());
+            let start = usize_lit(i);
+            let end = usize_lit(i + 1);
+            quote!(
+                #start => #field::verify(&slice[offset_at(#start)..offset_at(#end)], compatible)?,
+            )
+        });
+        quote!(
+            impl<'r> #reader<'r> {
+                pub fn verify_fields(
+                    slice: &[u8],
+                    fields: ::core::ops::Range<usize>,
+                    compatible: bool,
+                ) -> molecule::error::VerificationResult<()> {
+                    #header
+                    for idx in fields {
+                        match idx {
+                            #( #verify_fields )*
+                            _ => {}
+                        }
+                    }
+                    Ok(())
+                }
+            }
+        )
+    }
+}
+
+fn fixvec_methods() -> m4::TokenStream {
+    quote!(
+        pub fn item_range(&self, idx: usize) -> ::core::ops::Range<usize> {
+            if idx >= self.item_count() {
+                panic!("{}: index out of bounds", Self::NAME);
+            }
+            let start = Self::COUNT_SIZE + Self::ITEM_SIZE * idx;
+            start..start + Self::ITEM_SIZE
+        }
+    )
+}
+
+fn dynvec_methods() -> m4::TokenStream {
+    quote!(
+        pub fn item_range(&self, idx: usize) -> ::core::ops::Range<usize> {
+            if idx >= self.item_count() {
+                panic!("{}: index out of bounds", Self::NAME);
+            }
+            let offsets = self.item_offsets();
+            let start = molecule::number::Number::from_le_slice(&offsets[idx][..]).to_usize();
+            let end = if idx + 1 == self.item_count() {
+                self.total_size()
+            } else {
+                molecule::number::Number::from_le_slice(&offsets[idx + 1][..]).to_usize()
+            };
+            start..end
+        }
+    )
+}
+
+fn table_reader_methods() -> m4::TokenStream {
+    quote!(
+        pub fn split_at_field(&self, idx: usize) -> (&'r [u8], &'r [u8]) {
+            molecule::split_at_field(self.as_slice(), idx)
+        }
+    )
+}
+
+fn table_methods() -> m4::TokenStream {
+    quote!(
+        pub fn field_range(&self, idx: usize) -> ::core::ops::Range<usize> {
+            if idx >= self.field_count() {
+                panic!("{}: index out of bounds", Self::NAME);
+            }
+            let offsets = self.field_offsets();
+            let start = molecule::number::Number::from_le_slice(&offsets[idx][..]).to_usize();
+            let end = if idx + 1 == self.field_count() {
+                self.total_size()
+            } else {
+                molecule::number::Number::from_le_slice(&offsets[idx + 1][..]).to_usize()
+            };
+            start..end
+        }
+        pub fn encode_with_zeroed_field(&self, idx: usize) -> Vec<u8> {
+            let range = self.field_range(idx);
+            let mut data = self.as_slice().to_vec();
+            for byte in &mut data[range] {
+                *byte = 0;
+            }
+            data
+        }
+    )
+}
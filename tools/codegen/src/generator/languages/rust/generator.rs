@@ -1,77 +1,131 @@
 use std::io;
 
+use proc_macro2 as m4;
+
 use super::{
-    builder::GenBuilder, entity::GenEntity, enumerator::GenEnumerator, iterator::GenIterator,
-    reader::GenReader,
+    builder::GenBuilder, byte_array::GenByteArray, checksum::GenChecksum, entity::GenEntity,
+    enumerator::GenEnumerator, getters::GenNthAccessors, iterator::GenIterator,
+    logical::GenLogical, reader::GenReader, uint::GenUint,
+};
+use crate::{
+    ast::verified::{self as ast},
+    generator::Options,
 };
-use crate::ast::verified::{self as ast};
 
 pub(super) trait Generator {
-    fn generate<W: io::Write>(&self, writer: &mut W) -> io::Result<()>;
+    fn generate<W: io::Write>(
+        &self,
+        writer: &mut W,
+        vis: &m4::TokenStream,
+        options: &Options,
+    ) -> io::Result<()>;
 }
 
 impl Generator for ast::Option_ {
-    fn generate<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
-        writeln!(writer, "{}", self.gen_entity())?;
-        writeln!(writer, "{}", self.gen_reader())?;
-        writeln!(writer, "{}", self.gen_builder())?;
+    fn generate<W: io::Write>(
+        &self,
+        writer: &mut W,
+        vis: &m4::TokenStream,
+        options: &Options,
+    ) -> io::Result<()> {
+        writeln!(writer, "{}", self.gen_entity(vis))?;
+        writeln!(writer, "{}", self.gen_reader(vis))?;
+        writeln!(writer, "{}", self.gen_builder(vis, options))?;
         Ok(())
     }
 }
 
 impl Generator for ast::Union {
-    fn generate<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
-        writeln!(writer, "{}", self.gen_entity())?;
-        writeln!(writer, "{}", self.gen_reader())?;
-        writeln!(writer, "{}", self.gen_builder())?;
-        writeln!(writer, "{}", self.gen_enumerator())?;
+    fn generate<W: io::Write>(
+        &self,
+        writer: &mut W,
+        vis: &m4::TokenStream,
+        options: &Options,
+    ) -> io::Result<()> {
+        writeln!(writer, "{}", self.gen_entity(vis))?;
+        writeln!(writer, "{}", self.gen_reader(vis))?;
+        writeln!(writer, "{}", self.gen_builder(vis, options))?;
+        writeln!(writer, "{}", self.gen_enumerator(vis))?;
         Ok(())
     }
 }
 
 impl Generator for ast::Array {
-    fn generate<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
-        writeln!(writer, "{}", self.gen_entity())?;
-        writeln!(writer, "{}", self.gen_reader())?;
-        writeln!(writer, "{}", self.gen_builder())?;
+    fn generate<W: io::Write>(
+        &self,
+        writer: &mut W,
+        vis: &m4::TokenStream,
+        options: &Options,
+    ) -> io::Result<()> {
+        writeln!(writer, "{}", self.gen_entity(vis))?;
+        writeln!(writer, "{}", self.gen_reader(vis))?;
+        writeln!(writer, "{}", self.gen_builder(vis, options))?;
+        writeln!(writer, "{}", self.gen_uint())?;
+        writeln!(writer, "{}", self.gen_byte_array())?;
+        writeln!(writer, "{}", self.gen_logical())?;
+        writeln!(writer, "{}", self.gen_iterator(vis))?;
+        if !options.compact_arrays {
+            writeln!(writer, "{}", self.gen_nth_accessors())?;
+        }
         Ok(())
     }
 }
 
 impl Generator for ast::Struct {
-    fn generate<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
-        writeln!(writer, "{}", self.gen_entity())?;
-        writeln!(writer, "{}", self.gen_reader())?;
-        writeln!(writer, "{}", self.gen_builder())?;
+    fn generate<W: io::Write>(
+        &self,
+        writer: &mut W,
+        vis: &m4::TokenStream,
+        options: &Options,
+    ) -> io::Result<()> {
+        writeln!(writer, "{}", self.gen_entity(vis))?;
+        writeln!(writer, "{}", self.gen_reader(vis))?;
+        writeln!(writer, "{}", self.gen_builder(vis, options))?;
         Ok(())
     }
 }
 
 impl Generator for ast::FixVec {
-    fn generate<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
-        writeln!(writer, "{}", self.gen_entity())?;
-        writeln!(writer, "{}", self.gen_reader())?;
-        writeln!(writer, "{}", self.gen_builder())?;
-        writeln!(writer, "{}", self.gen_iterator())?;
+    fn generate<W: io::Write>(
+        &self,
+        writer: &mut W,
+        vis: &m4::TokenStream,
+        options: &Options,
+    ) -> io::Result<()> {
+        writeln!(writer, "{}", self.gen_entity(vis))?;
+        writeln!(writer, "{}", self.gen_reader(vis))?;
+        writeln!(writer, "{}", self.gen_builder(vis, options))?;
+        writeln!(writer, "{}", self.gen_iterator(vis))?;
         Ok(())
     }
 }
 
 impl Generator for ast::DynVec {
-    fn generate<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
-        writeln!(writer, "{}", self.gen_entity())?;
-        writeln!(writer, "{}", self.gen_reader())?;
-        writeln!(writer, "{}", self.gen_builder())?;
-        writeln!(writer, "{}", self.gen_iterator())?;
+    fn generate<W: io::Write>(
+        &self,
+        writer: &mut W,
+        vis: &m4::TokenStream,
+        options: &Options,
+    ) -> io::Result<()> {
+        writeln!(writer, "{}", self.gen_entity(vis))?;
+        writeln!(writer, "{}", self.gen_reader(vis))?;
+        writeln!(writer, "{}", self.gen_builder(vis, options))?;
+        writeln!(writer, "{}", self.gen_iterator(vis))?;
         Ok(())
     }
 }
 
 impl Generator for ast::Table {
-    fn generate<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
-        writeln!(writer, "{}", self.gen_entity())?;
-        writeln!(writer, "{}", self.gen_reader())?;
-        writeln!(writer, "{}", self.gen_builder())?;
+    fn generate<W: io::Write>(
+        &self,
+        writer: &mut W,
+        vis: &m4::TokenStream,
+        options: &Options,
+    ) -> io::Result<()> {
+        writeln!(writer, "{}", self.gen_entity(vis))?;
+        writeln!(writer, "{}", self.gen_reader(vis))?;
+        writeln!(writer, "{}", self.gen_builder(vis, options))?;
+        writeln!(writer, "{}", self.gen_checksum())?;
         Ok(())
     }
 }
@@ -1,8 +1,10 @@
 use proc_macro2 as m4;
 use quote::quote;
 
+use super::super::model::struct_fields;
 use super::utilities::{
-    entity_name, entity_union_name, func_name, reader_name, reader_union_name, usize_lit,
+    builder_name, entity_name, entity_union_name, func_name, reader_name, reader_union_name,
+    usize_lit,
 };
 use crate::ast::verified::{self as ast, HasName};
 
@@ -99,44 +101,94 @@ impl ImplGetters for ast::Array {
             let getter_stmt_atom = quote!(self.as_slice());
             (inner, getter_ret, getter_ret_atom, getter_stmt_atom)
         };
-        let each_getter = (0..self.item_count)
-            .map(|i| {
-                let func = func_name(&format!("nth{}", i));
-                let start = usize_lit(self.item_size * i);
-                let end = usize_lit(self.item_size * (i + 1));
-                let getter_stmt = if is_entity {
-                    quote!(self.0.slice(#start, #end))
-                } else {
-                    quote!(&self.as_slice()[#start..#end])
-                };
-                quote!(
-                    pub fn #func(&self) -> #getter_ret {
-                        #inner::new_unchecked(#getter_stmt)
-                    }
-                )
-            })
-            .collect::<Vec<_>>();
         if self.typ.is_atom() {
             quote!(
-                #( #each_getter )*
                 pub fn raw_data(&self) -> #getter_ret_atom {
                     #getter_stmt_atom
                 }
             )
         } else {
+            let getter_stmt = if is_entity {
+                quote!(self.0.slice(start, start + Self::ITEM_SIZE))
+            } else {
+                quote!(&self.as_slice()[start..start + Self::ITEM_SIZE])
+            };
             quote!(
-                #( #each_getter )*
+                pub fn get(&self, idx: usize) -> Option<#getter_ret> {
+                    if idx >= Self::ITEM_COUNT {
+                        None
+                    } else {
+                        Some(self.get_unchecked(idx))
+                    }
+                }
+                pub fn get_unchecked(&self, idx: usize) -> #getter_ret {
+                    let start = Self::ITEM_SIZE * idx;
+                    #inner::new_unchecked(#getter_stmt)
+                }
             )
         }
     }
 }
 
+pub(super) trait GenNthAccessors {
+    fn gen_nth_accessors(&self) -> m4::TokenStream;
+}
+
+/// The getters and the setters of each item of an array, such as `nth0`, which aren't generated
+/// for compact arrays.
+impl GenNthAccessors for ast::Array {
+    fn gen_nth_accessors(&self) -> m4::TokenStream {
+        let entity = entity_name(self.name());
+        let reader = reader_name(self.name());
+        let builder = builder_name(self.name());
+        let entity_inner = entity_name(self.typ.name());
+        let reader_inner = reader_name(self.typ.name());
+        let (entity_getters, (reader_getters, setters)): (Vec<_>, (Vec<_>, Vec<_>)) = (0..self
+            .item_count)
+            .map(|idx| {
+                let func = func_name(&format!("nth{}", idx));
+                let index = usize_lit(idx);
+                let start = usize_lit(self.item_size * idx);
+                let end = usize_lit(self.item_size * (idx + 1));
+                let entity_getter = quote!(
+                    pub fn #func(&self) -> #entity_inner {
+                        #entity_inner::new_unchecked(self.0.slice(#start, #end))
+                    }
+                );
+                let reader_getter = quote!(
+                    pub fn #func(&self) -> #reader_inner<'r> {
+                        #reader_inner::new_unchecked(&self.as_slice()[#start..#end])
+                    }
+                );
+                let setter = quote!(
+                    pub fn #func(mut self, v: #entity_inner) -> Self {
+                        self.0[#index] = v;
+                        self
+                    }
+                );
+                (entity_getter, (reader_getter, setter))
+            })
+            .unzip();
+        quote!(
+            impl #entity {
+                #( #entity_getters )*
+            }
+            impl<'r> #reader<'r> {
+                #( #reader_getters )*
+            }
+            impl #builder {
+                #( #setters )*
+            }
+        )
+    }
+}
+
 impl ImplGetters for ast::Struct {
     fn impl_getters_internal(&self, is_entity: bool) -> m4::TokenStream {
-        let (_, each_getter) = self.inner.iter().zip(self.field_size.iter()).fold(
-            (0, Vec::with_capacity(self.inner.len())),
-            |(mut offset, mut getters), (f, s)| {
-                let func = func_name(&f.name);
+        let each_getter = struct_fields(self)
+            .into_iter()
+            .map(|f| {
+                let func = func_name(f.name);
                 let (inner, getter_ret) = if is_entity {
                     let inner = entity_name(f.typ.name());
                     let getter_ret = quote!(#inner);
@@ -146,25 +198,24 @@ impl ImplGetters for ast::Struct {
                     let getter_ret = quote!(#inner<'r>);
                     (inner, getter_ret)
                 };
-                let start = usize_lit(offset);
-                offset += s;
-                let end = usize_lit(offset);
+                let start = usize_lit(f.offset());
+                let end = usize_lit(f.end());
                 let getter_stmt = if is_entity {
                     quote!(self.0.slice(#start, #end))
                 } else {
                     quote!(&self.as_slice()[#start..#end])
                 };
-                let getter = quote!(
+                quote!(
                     pub fn #func(&self) -> #getter_ret {
                         #inner::new_unchecked(#getter_stmt)
                     }
-                );
-                getters.push(getter);
-                (offset, getters)
-            },
-        );
+                )
+            })
+            .collect::<Vec<_>>();
+        let flattened_getters = flattened_getters(&self.inner, is_entity);
         quote!(
             #( #each_getter )*
+            #( #flattened_getters )*
         )
     }
 }
@@ -176,7 +227,7 @@ impl ImplGetters for ast::FixVec {
             let getter_ret = quote!(#inner);
             let getter_stmt = quote!(self.0.slice(start, end));
             let getter_ret_atom = quote!(molecule::bytes::Bytes);
-            let getter_stmt_atom = quote!(self.0.slice_from(molecule::NUMBER_SIZE));
+            let getter_stmt_atom = quote!(self.0.slice_from(Self::COUNT_SIZE));
             (
                 inner,
                 getter_ret,
@@ -189,7 +240,7 @@ impl ImplGetters for ast::FixVec {
             let getter_ret = quote!(#inner<'r>);
             let getter_stmt = quote!(&self.as_slice()[start..end]);
             let getter_ret_atom = quote!(&'r [u8]);
-            let getter_stmt_atom = quote!(&self.as_slice()[molecule::NUMBER_SIZE..]);
+            let getter_stmt_atom = quote!(&self.as_slice()[Self::COUNT_SIZE..]);
             (
                 inner,
                 getter_ret,
@@ -207,7 +258,7 @@ impl ImplGetters for ast::FixVec {
                 }
             }
             pub fn get_unchecked(&self, idx: usize) -> #getter_ret {
-                let start = molecule::NUMBER_SIZE + Self::ITEM_SIZE * idx;
+                let start = Self::COUNT_SIZE + Self::ITEM_SIZE * idx;
                 let end = start + Self::ITEM_SIZE;
                 #inner::new_unchecked(#getter_stmt)
             }
@@ -250,11 +301,11 @@ impl ImplGetters for ast::DynVec {
             }
             pub fn get_unchecked(&self, idx: usize) -> #getter_ret {
                 let offsets = self.item_offsets();
-                let start = molecule::unpack_number(&offsets[idx][..]) as usize;
+                let start = molecule::number::Number::from_le_slice(&offsets[idx][..]).to_usize();
                 if idx == self.len() - 1 {
                     #inner::new_unchecked(#getter_stmt_last)
                 } else {
-                    let end = molecule::unpack_number(&offsets[idx+1][..]) as usize;
+                    let end = molecule::number::Number::from_le_slice(&offsets[idx+1][..]).to_usize();
                     #inner::new_unchecked(#getter_stmt)
                 }
             }
@@ -294,9 +345,9 @@ impl ImplGetters for ast::Table {
                     quote!(
                         pub fn #func(&self) -> #getter_ret {
                             let offsets = self.field_offsets();
-                            let start = molecule::unpack_number(&offsets[#start][..]) as usize;
+                            let start = molecule::number::Number::from_le_slice(&offsets[#start][..]).to_usize();
                             if self.has_extra_fields() {
-                                let end = molecule::unpack_number(&offsets[#end][..]) as usize;
+                                let end = molecule::number::Number::from_le_slice(&offsets[#end][..]).to_usize();
                                 #inner::new_unchecked(#getter_stmt)
                             } else {
                                 #inner::new_unchecked(#getter_stmt_last)
@@ -307,16 +358,50 @@ impl ImplGetters for ast::Table {
                     quote!(
                         pub fn #func(&self) -> #getter_ret {
                             let offsets = self.field_offsets();
-                            let start = molecule::unpack_number(&offsets[#start][..]) as usize;
-                            let end = molecule::unpack_number(&offsets[#end][..]) as usize;
+                            let start = molecule::number::Number::from_le_slice(&offsets[#start][..]).to_usize();
+                            let end = molecule::number::Number::from_le_slice(&offsets[#end][..]).to_usize();
                             #inner::new_unchecked(#getter_stmt)
                         }
                     )
                 }
             })
             .collect::<Vec<_>>();
+        let flattened_getters = flattened_getters(&self.inner, is_entity);
         quote!(
             #( #each_getter )*
+            #( #flattened_getters )*
         )
     }
 }
+
+/// The getters of the fields of the flattened structs, such as `position_x()` for
+/// `position().x()`.
+fn flattened_getters(inner: &[ast::FieldDecl], is_entity: bool) -> Vec<m4::TokenStream> {
+    inner
+        .iter()
+        .filter(|f| f.flatten)
+        .flat_map(|f| {
+            let outer_func = func_name(&f.name);
+            let fields = match f.typ.as_ref() {
+                ast::TopDecl::Struct(ref typ) => &typ.inner[..],
+                _ => unreachable!(),
+            };
+            fields.iter().map(move |inner_field| {
+                let func = func_name(&format!("{}_{}", f.name, inner_field.name));
+                let inner_func = func_name(&inner_field.name);
+                let getter_ret = if is_entity {
+                    let inner = entity_name(inner_field.typ.name());
+                    quote!(#inner)
+                } else {
+                    let inner = reader_name(inner_field.typ.name());
+                    quote!(#inner<'r>)
+                };
+                quote!(
+                    pub fn #func(&self) -> #getter_ret {
+                        self.#outer_func().#inner_func()
+                    }
+                )
+            })
+        })
+        .collect()
+}
@@ -1,21 +1,25 @@
 use proc_macro2 as m4;
 use quote::quote;
 
-use super::utilities::ident_new;
-use crate::ast::verified as ast;
+use super::utilities::{ident_new, path_new};
+use crate::{ast::verified as ast, generator::Options};
 
 pub(super) trait GenImport {
-    fn import_crate(&self) -> m4::TokenStream;
+    fn import_crate(&self, options: &Options) -> m4::TokenStream;
 }
 
 impl GenImport for ast::ImportStmt {
-    fn import_crate(&self) -> m4::TokenStream {
+    fn import_crate(&self, options: &Options) -> m4::TokenStream {
+        if let Some(path) = options.extern_schemas.get(&self.name) {
+            let path = path_new(path);
+            return quote!(use #path::*;);
+        }
         let mut stmt = quote!(use super::);
         for _ in 0..self.depth {
             stmt = quote!(#stmt super::);
         }
         for part in &self.path[..] {
-            let part = ident_new(&part);
+            let part = ident_new(part);
             stmt = quote!(#stmt #part::);
         }
         let name = ident_new(&self.name);
@@ -1,26 +1,120 @@
 use proc_macro2 as m4;
 use quote::quote;
 
-use super::utilities::{entity_iterator_name, entity_name, reader_iterator_name, reader_name};
+use super::super::model::struct_fields;
+use super::utilities::{
+    entity_iterator_name, entity_name, func_name, reader_iterator_name, reader_name, usize_lit,
+};
 use crate::ast::verified::{self as ast, HasName};
 
 pub(super) trait GenIterator {
-    fn gen_iterator(&self) -> m4::TokenStream;
+    fn gen_iterator(&self, vis: &m4::TokenStream) -> m4::TokenStream;
+}
+
+impl GenIterator for ast::Array {
+    fn gen_iterator(&self, vis: &m4::TokenStream) -> m4::TokenStream {
+        // The bytes of an array are converted to a Rust array instead.
+        if self.typ.is_atom() {
+            return quote!();
+        }
+        gen_iterator_for_vector(
+            self.name(),
+            self.typ.name(),
+            false,
+            quote!(Self::ITEM_COUNT),
+            vis,
+        )
+    }
 }
 
 impl GenIterator for ast::FixVec {
-    fn gen_iterator(&self) -> m4::TokenStream {
-        gen_iterator_for_vector(self.name(), self.typ.name(), self.typ.is_atom())
+    fn gen_iterator(&self, vis: &m4::TokenStream) -> m4::TokenStream {
+        let iterator = gen_iterator_for_vector(
+            self.name(),
+            self.typ.name(),
+            self.typ.is_atom(),
+            quote!(self.len()),
+            vis,
+        );
+        let columns = gen_columns(self);
+        quote!(
+            #iterator
+            #columns
+        )
     }
 }
 
+/// An iterator over each field of the items, if the items are structs.
+fn gen_columns(vector: &ast::FixVec) -> m4::TokenStream {
+    let inner = if let ast::TopDecl::Struct(ref inner) = vector.typ.as_ref() {
+        inner
+    } else {
+        return quote!();
+    };
+    let entity = entity_name(vector.name());
+    let reader = reader_name(vector.name());
+    let (entity_columns, reader_columns): (Vec<_>, Vec<_>) = struct_fields(inner)
+        .into_iter()
+        .map(|field| {
+            let func = func_name(&format!("iter_{}", field.name));
+            let field_reader = reader_name(field.typ.name());
+            let start = usize_lit(field.offset());
+            let end = usize_lit(field.end());
+            let entity_column = quote!(
+                pub fn #func(&self) -> molecule::column::Column<'_, #field_reader<'_>> {
+                    molecule::column::Column::new(
+                        &self.as_slice()[#reader::COUNT_SIZE..],
+                        #reader::ITEM_SIZE,
+                        #start,
+                        #end,
+                        #field_reader::new_unchecked,
+                    )
+                }
+            );
+            let reader_column = quote!(
+                pub fn #func(&self) -> molecule::column::Column<'r, #field_reader<'r>> {
+                    molecule::column::Column::new(
+                        &self.as_slice()[Self::COUNT_SIZE..],
+                        Self::ITEM_SIZE,
+                        #start,
+                        #end,
+                        #field_reader::new_unchecked,
+                    )
+                }
+            );
+            (entity_column, reader_column)
+        })
+        .unzip();
+    quote!(
+        impl #entity {
+            #( #entity_columns )*
+        }
+        impl<'r> #reader<'r> {
+            #( #reader_columns )*
+        }
+    )
+}
+
 impl GenIterator for ast::DynVec {
-    fn gen_iterator(&self) -> m4::TokenStream {
-        gen_iterator_for_vector(self.name(), self.typ.name(), self.typ.is_atom())
+    fn gen_iterator(&self, vis: &m4::TokenStream) -> m4::TokenStream {
+        gen_iterator_for_vector(
+            self.name(),
+            self.typ.name(),
+            self.typ.is_atom(),
+            quote!(self.len()),
+            vis,
+        )
     }
 }
 
-fn gen_iterator_for_vector(self_name: &str, inner_name: &str, is_atom: bool) -> m4::TokenStream {
+/// The iterators over the items, where `len` is the count of the items.
+fn gen_iterator_for_vector(
+    self_name: &str,
+    inner_name: &str,
+    is_atom: bool,
+    len: m4::TokenStream,
+    vis: &m4::TokenStream,
+) -> m4::TokenStream {
     let entity_iterator = entity_iterator_name(self_name);
     let entity = entity_name(self_name);
     let entity_inner = entity_name(inner_name);
@@ -28,7 +122,7 @@ fn gen_iterator_for_vector(self_name: &str, inner_name: &str, is_atom: bool) ->
     let reader = reader_name(self_name);
     let reader_inner = reader_name(inner_name);
     let common_part = quote!(
-        pub struct #entity_iterator (#entity, usize, usize);
+        #vis struct #entity_iterator (#entity, usize, usize);
         impl ::core::iter::Iterator for #entity_iterator {
             type Item = #entity_inner;
             fn next(&mut self) -> Option<Self::Item> {
@@ -50,7 +144,7 @@ fn gen_iterator_for_vector(self_name: &str, inner_name: &str, is_atom: bool) ->
             type Item = #entity_inner;
             type IntoIter = #entity_iterator;
             fn into_iter(self) -> Self::IntoIter {
-                let len = self.len();
+                let len = #len;
                 #entity_iterator(self, 0, len)
             }
         }
@@ -63,10 +157,10 @@ fn gen_iterator_for_vector(self_name: &str, inner_name: &str, is_atom: bool) ->
 
             impl<'r> #reader<'r> {
                 pub fn iter<'t>(&'t self) -> #reader_iterator<'t, 'r> {
-                    #reader_iterator(&self, 0, self.len())
+                    #reader_iterator(&self, 0, #len)
                 }
             }
-            pub struct #reader_iterator<'t, 'r> (&'t #reader<'r>, usize, usize);
+            #vis struct #reader_iterator<'t, 'r> (&'t #reader<'r>, usize, usize);
             impl<'t: 'r, 'r> ::core::iter::Iterator for #reader_iterator<'t, 'r> {
                 type Item = #reader_inner<'t>;
                 fn next(&mut self) -> Option<Self::Item> {
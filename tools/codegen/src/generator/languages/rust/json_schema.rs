@@ -0,0 +1,194 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{entity_name, usize_lit};
+use crate::ast::verified::{self as ast, HasName};
+
+// JSON mapping for the schemas:
+// - `byte` is an integer in `[0, 255]`.
+// - An array or a vector of bytes is a hex string with a `0x` prefix.
+// - Any other array or vector is a JSON array of its items.
+// - A struct or a table is a JSON object which has all the fields.
+// - An option is its inner item, or `null`.
+// - A union is a JSON object `{ "type": "<ItemName>", "value": <item> }`.
+pub(in super::super) trait GenJsonSchema {
+    fn gen_json_schema(&self) -> m4::TokenStream;
+}
+
+fn schema_for_byte() -> m4::TokenStream {
+    quote!(schemars::json_schema!({
+        "type": "integer",
+        "minimum": 0,
+        "maximum": 255
+    }))
+}
+
+fn schema_for_bytes(count: Option<usize>) -> m4::TokenStream {
+    let pattern = if let Some(count) = count {
+        format!("^0x([0-9a-fA-F]{{2}}){{{}}}$", count)
+    } else {
+        "^0x([0-9a-fA-F]{2})*$".to_owned()
+    };
+    quote!(schemars::json_schema!({
+        "type": "string",
+        "pattern": #pattern
+    }))
+}
+
+fn schema_for_type(typ: &ast::TopDecl) -> m4::TokenStream {
+    if typ.is_atom() {
+        schema_for_byte()
+    } else {
+        let inner = entity_name(typ.name());
+        quote!(generator.subschema_for::<#inner>())
+    }
+}
+
+fn impl_json_schema(name: &str, schema: m4::TokenStream) -> m4::TokenStream {
+    let entity = entity_name(name);
+    quote!(
+        impl schemars::JsonSchema for #entity {
+            fn schema_name() -> molecule::prelude::Cow<'static, str> {
+                molecule::prelude::Cow::Borrowed(#name)
+            }
+            #[allow(unused_variables)]
+            fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+                #schema
+            }
+        }
+    )
+}
+
+// Build large objects and lists step by step, since `json_schema!` is a recursive macro.
+fn schema_for_fields(fields: &[ast::FieldDecl]) -> m4::TokenStream {
+    let names = fields.iter().map(|f| &f.name);
+    let required = names.clone();
+    let schemas = fields.iter().map(|f| schema_for_type(&f.typ));
+    let properties = if fields.is_empty() {
+        quote!(let properties = schemars::json_schema!({});)
+    } else {
+        quote!(
+            let mut properties = schemars::json_schema!({});
+            #( properties.insert(#names.to_owned(), #schemas.into()); )*
+        )
+    };
+    quote!(
+        #properties
+        let required: &[&str] = &[ #( #required, )* ];
+        schemars::json_schema!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false
+        })
+    )
+}
+
+impl GenJsonSchema for ast::Option_ {
+    fn gen_json_schema(&self) -> m4::TokenStream {
+        let inner = schema_for_type(&self.typ);
+        let schema = quote!(schemars::json_schema!({
+            "anyOf": [
+                #inner,
+                { "type": "null" }
+            ]
+        }));
+        impl_json_schema(self.name(), schema)
+    }
+}
+
+impl GenJsonSchema for ast::Union {
+    fn gen_json_schema(&self) -> m4::TokenStream {
+        let items = self.inner.iter().map(|inner| {
+            let item_name = inner.typ.name();
+            let item_schema = schema_for_type(&inner.typ);
+            quote!({
+                "type": "object",
+                "properties": {
+                    "type": { "const": #item_name },
+                    "value": #item_schema
+                },
+                "required": ["type", "value"],
+                "additionalProperties": false
+            })
+        });
+        let schema = quote!(
+            let items = [ #( schemars::json_schema!(#items), )* ];
+            schemars::json_schema!({ "oneOf": items })
+        );
+        impl_json_schema(self.name(), schema)
+    }
+}
+
+impl GenJsonSchema for ast::Array {
+    fn gen_json_schema(&self) -> m4::TokenStream {
+        let schema = if self.typ.is_atom() {
+            schema_for_bytes(Some(self.item_count))
+        } else {
+            let item_count = usize_lit(self.item_count);
+            let inner = schema_for_type(&self.typ);
+            quote!(schemars::json_schema!({
+                "type": "array",
+                "items": #inner,
+                "minItems": #item_count,
+                "maxItems": #item_count
+            }))
+        };
+        impl_json_schema(self.name(), schema)
+    }
+}
+
+impl GenJsonSchema for ast::Struct {
+    fn gen_json_schema(&self) -> m4::TokenStream {
+        impl_json_schema(self.name(), schema_for_fields(&self.inner))
+    }
+}
+
+impl GenJsonSchema for ast::FixVec {
+    fn gen_json_schema(&self) -> m4::TokenStream {
+        let schema = if self.is_standard() {
+            if self.typ.is_atom() {
+                schema_for_bytes(None)
+            } else {
+                let inner = schema_for_type(&self.typ);
+                quote!(schemars::json_schema!({
+                    "type": "array",
+                    "items": #inner
+                }))
+            }
+        } else if self.typ.is_atom() {
+            let max_length = usize_lit(2 + 2 * self.max_item_count() as usize);
+            quote!(schemars::json_schema!({
+                "type": "string",
+                "pattern": "^0x([0-9a-fA-F]{2})*$",
+                "maxLength": #max_length
+            }))
+        } else {
+            let max_item_count = usize_lit(self.max_item_count() as usize);
+            let inner = schema_for_type(&self.typ);
+            quote!(schemars::json_schema!({
+                "type": "array",
+                "items": #inner,
+                "maxItems": #max_item_count
+            }))
+        };
+        impl_json_schema(self.name(), schema)
+    }
+}
+
+impl GenJsonSchema for ast::DynVec {
+    fn gen_json_schema(&self) -> m4::TokenStream {
+        let inner = schema_for_type(&self.typ);
+        let schema = quote!(schemars::json_schema!({
+            "type": "array",
+            "items": #inner
+        }));
+        impl_json_schema(self.name(), schema)
+    }
+}
+
+impl GenJsonSchema for ast::Table {
+    fn gen_json_schema(&self) -> m4::TokenStream {
+        impl_json_schema(self.name(), schema_for_fields(&self.inner))
+    }
+}
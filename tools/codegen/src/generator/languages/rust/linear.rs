@@ -0,0 +1,268 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::reader::{limit_item_size, semantic_checks};
+use super::utilities::{entity_name, ident_new, reader_name, usize_lit};
+use crate::ast::verified::{self as ast, HasName};
+
+pub(super) trait GenLinear {
+    fn gen_linear(&self) -> m4::TokenStream;
+}
+
+/// Only the types which have offsets or nested dynamic-size values are verified in another order;
+/// the fixed-size types only check their sizes.
+fn is_dynamic(typ: &ast::TopDecl) -> bool {
+    matches!(
+        typ,
+        ast::TopDecl::Option_(_)
+            | ast::TopDecl::Union(_)
+            | ast::TopDecl::DynVec(_)
+            | ast::TopDecl::Table(_)
+    )
+}
+
+fn verify_inner(typ: &ast::TopDecl, slice: m4::TokenStream) -> m4::TokenStream {
+    let inner = reader_name(typ.name());
+    if is_dynamic(typ) {
+        quote!(#inner::verify_linear(#slice, compatible))
+    } else {
+        quote!(#inner::verify(#slice, compatible))
+    }
+}
+
+impl GenLinear for ast::TopDecl {
+    fn gen_linear(&self) -> m4::TokenStream {
+        let reader = reader_name(self.name());
+        let entity = entity_name(self.name());
+        // The semantic checks follow the structure, as in `verify`.
+        let checks = semantic_checks(self).filter(|_| is_dynamic(self));
+        let structure = if checks.is_some() {
+            ident_new("verify_linear_structure")
+        } else {
+            ident_new("verify_linear")
+        };
+        let verify = match self {
+            ast::TopDecl::Atom(_) => unreachable!(),
+            ast::TopDecl::Option_(ref typ) => gen_option(typ, &structure),
+            ast::TopDecl::Union(ref typ) => gen_union(typ, &structure),
+            ast::TopDecl::DynVec(ref typ) => gen_dynvec(typ, &structure),
+            ast::TopDecl::Table(ref typ) => gen_table(typ, &structure),
+            ast::TopDecl::Array(_) | ast::TopDecl::Struct(_) | ast::TopDecl::FixVec(_) => quote!(
+                pub fn verify_linear(
+                    slice: &[u8],
+                    compatible: bool,
+                ) -> molecule::error::VerificationResult<()> {
+                    <Self as molecule::prelude::Reader>::verify(slice, compatible)
+                }
+            ),
+        };
+        let verify = match checks {
+            Some(checks) => quote!(
+                #verify
+                pub fn verify_linear(
+                    slice: &[u8],
+                    compatible: bool,
+                ) -> molecule::error::VerificationResult<()> {
+                    Self::verify_linear_structure(slice, compatible)?;
+                    #checks
+                }
+            ),
+            None => verify,
+        };
+        quote!(
+            impl<'r> #reader<'r> {
+                #verify
+                pub fn from_slice_linear(slice: &'r [u8]) -> molecule::error::VerificationResult<Self> {
+                    Self::verify_linear(slice, false)
+                        .map(|_| <Self as molecule::prelude::Reader>::new_unchecked(slice))
+                        .map_err(|err| {
+                            molecule::error::traced(<#entity as Entity>::NAME, slice.len(), false, err)
+                        })
+                }
+                pub fn from_compatible_slice_linear(
+                    slice: &'r [u8],
+                ) -> molecule::error::VerificationResult<Self> {
+                    Self::verify_linear(slice, true)
+                        .map(|_| <Self as molecule::prelude::Reader>::new_unchecked(slice))
+                        .map_err(|err| {
+                            molecule::error::traced(<#entity as Entity>::NAME, slice.len(), true, err)
+                        })
+                }
+            }
+
+            impl #entity {
+                pub fn from_slice_linear(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+                    #reader::from_slice_linear(slice).map(|reader| reader.to_entity())
+                }
+                pub fn from_compatible_slice_linear(
+                    slice: &[u8],
+                ) -> molecule::error::VerificationResult<Self> {
+                    #reader::from_compatible_slice_linear(slice).map(|reader| reader.to_entity())
+                }
+            }
+        )
+    }
+}
+
+fn gen_option(typ: &ast::Option_, verify: &m4::Ident) -> m4::TokenStream {
+    let verify_inner = verify_inner(&typ.typ, quote!(slice));
+    quote!(
+        pub fn #verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
+            if !slice.is_empty() {
+                #verify_inner?;
+            }
+            Ok(())
+        }
+    )
+}
+
+fn gen_union(typ: &ast::Union, verify: &m4::Ident) -> m4::TokenStream {
+    let type_name = typ.name();
+    let verify_inners = typ.inner.iter().enumerate().map(|(index, inner)| {
+        let item_id = usize_lit(index);
+        let verify_inner = verify_inner(&inner.typ, quote!(inner_slice));
+        let verify_inner = limit_item_size(inner.max_size, verify_inner);
+        quote!(
+            #item_id => #verify_inner,
+        )
+    });
+    quote!(
+        pub fn #verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
+            use molecule::verification_error as ve;
+            let slice_len = slice.len();
+            if slice_len < molecule::NUMBER_SIZE {
+                return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
+            }
+            let item_id = molecule::number::Number::from_le_slice(slice).get();
+            let inner_slice = &slice[molecule::NUMBER_SIZE..];
+            match item_id {
+                #( #verify_inners )*
+                _ => {
+                    if compatible {
+                        molecule::compatible::report(
+                            molecule::compatible::CompatibleEvent::UnknownItem {
+                                type_name: #type_name,
+                                item_count: Self::ITEM_COUNT,
+                                item_id,
+                            },
+                        );
+                    }
+                    ve!(Self, UnknownItem, Self::ITEM_COUNT, item_id)
+                }
+            }
+        }
+    )
+}
+
+/// The header is read once, and each item is verified as soon as its end is read, so the items
+/// are visited in the order of their bytes.
+fn gen_offsets_loop(verify_item: m4::TokenStream) -> m4::TokenStream {
+    quote!(
+        let mut start = offset_first;
+        for idx in 0..count {
+            let end = if idx + 1 == count {
+                total_size
+            } else {
+                molecule::number::Number::from_le_slice(&slice[molecule::NUMBER_SIZE * (idx + 2)..]).to_usize()
+            };
+            if start > end || end > total_size {
+                return ve!(Self, OffsetsNotMatch);
+            }
+            #verify_item
+            start = end;
+        }
+        Ok(())
+    )
+}
+
+fn gen_dynvec(typ: &ast::DynVec, verify: &m4::Ident) -> m4::TokenStream {
+    let verify_item = verify_inner(&typ.typ, quote!(&slice[start..end]));
+    let offsets_loop = gen_offsets_loop(quote!(#verify_item?;));
+    quote!(
+        pub fn #verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
+            use molecule::verification_error as ve;
+            let slice_len = slice.len();
+            if slice_len < molecule::NUMBER_SIZE {
+                return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
+            }
+            let total_size = molecule::number::Number::from_le_slice(slice).to_usize();
+            if slice_len != total_size {
+                return ve!(Self, TotalSizeNotMatch, total_size, slice_len);
+            }
+            if slice_len == molecule::NUMBER_SIZE {
+                return Ok(());
+            }
+            if slice_len < molecule::NUMBER_SIZE * 2 {
+                return ve!(Self, TotalSizeNotMatch, molecule::NUMBER_SIZE * 2, slice_len);
+            }
+            let offset_first = molecule::number::Number::from_le_slice(&slice[molecule::NUMBER_SIZE..]).to_usize();
+            if offset_first % 4 != 0 || offset_first < molecule::NUMBER_SIZE * 2 {
+                return ve!(Self, OffsetsNotMatch);
+            }
+            if slice_len < offset_first {
+                return ve!(Self, HeaderIsBroken, offset_first, slice_len);
+            }
+            let count = offset_first / 4 - 1;
+            #offsets_loop
+        }
+    )
+}
+
+fn gen_table(typ: &ast::Table, verify: &m4::Ident) -> m4::TokenStream {
+    let type_name = typ.name();
+    let verify_fields = typ.inner.iter().enumerate().map(|(i, f)| {
+        let idx = usize_lit(i);
+        let verify_field = verify_inner(&f.typ, quote!(&slice[start..end]));
+        quote!(
+            #idx => #verify_field?,
+        )
+    });
+    let offsets_loop = gen_offsets_loop(quote!(
+        match idx {
+            #( #verify_fields )*
+            _ => {}
+        }
+    ));
+    quote!(
+        pub fn #verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
+            use molecule::verification_error as ve;
+            let slice_len = slice.len();
+            if slice_len < molecule::NUMBER_SIZE {
+                return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
+            }
+            let total_size = molecule::number::Number::from_le_slice(slice).to_usize();
+            if slice_len != total_size {
+                return ve!(Self, TotalSizeNotMatch, total_size, slice_len);
+            }
+            if slice_len == molecule::NUMBER_SIZE && Self::FIELD_COUNT == 0 {
+                return Ok(());
+            }
+            if slice_len < molecule::NUMBER_SIZE * 2 {
+                return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE * 2, slice_len);
+            }
+            let offset_first = molecule::number::Number::from_le_slice(&slice[molecule::NUMBER_SIZE..]).to_usize();
+            if offset_first % 4 != 0 || offset_first < molecule::NUMBER_SIZE * 2 {
+                return ve!(Self, OffsetsNotMatch);
+            }
+            let count = offset_first / 4 - 1;
+            if count < Self::FIELD_COUNT {
+                return ve!(Self, FieldCountNotMatch, Self::FIELD_COUNT, count);
+            } else if count > Self::FIELD_COUNT {
+                if !compatible {
+                    return ve!(Self, FieldCountNotMatch, Self::FIELD_COUNT, count);
+                }
+                molecule::compatible::report(
+                    molecule::compatible::CompatibleEvent::ExtraFields {
+                        type_name: #type_name,
+                        expected: Self::FIELD_COUNT,
+                        actual: count,
+                    },
+                );
+            };
+            if slice_len < offset_first {
+                return ve!(Self, HeaderIsBroken, offset_first, slice_len);
+            }
+            #offsets_loop
+        }
+    )
+}
@@ -0,0 +1,101 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{entity_name, reader_name};
+use crate::ast::verified::{self as ast, HasName};
+
+pub(in super::super) trait GenLogical {
+    fn gen_logical(&self) -> m4::TokenStream;
+}
+
+fn time_unit(unit: ast::TimeUnit) -> m4::TokenStream {
+    match unit {
+        ast::TimeUnit::Seconds => quote!(molecule::logical::TimeUnit::Seconds),
+        ast::TimeUnit::Millis => quote!(molecule::logical::TimeUnit::Millis),
+        ast::TimeUnit::Micros => quote!(molecule::logical::TimeUnit::Micros),
+        ast::TimeUnit::Nanos => quote!(molecule::logical::TimeUnit::Nanos),
+    }
+}
+
+impl GenLogical for ast::Array {
+    fn gen_logical(&self) -> m4::TokenStream {
+        let entity = entity_name(self.name());
+        let reader = reader_name(self.name());
+        match self.logical {
+            Some(ast::Logical::UnixTime(unit)) => {
+                let unit = time_unit(unit);
+                quote!(
+                    impl molecule::logical::UnixTime for #entity {
+                        fn since_unix_epoch(&self) -> ::core::time::Duration {
+                            molecule::logical::UnixTime::since_unix_epoch(&self.as_reader())
+                        }
+                    }
+
+                    impl<'r> molecule::logical::UnixTime for #reader<'r> {
+                        fn since_unix_epoch(&self) -> ::core::time::Duration {
+                            let ticks: u64 = molecule::uint::Uint::from_le_slice(self.as_slice());
+                            #unit.to_duration(ticks)
+                        }
+                    }
+
+                    impl molecule::logical::FromUnixTime for #entity {
+                        fn from_since_unix_epoch(since: ::core::time::Duration) -> Option<Self> {
+                            #unit.to_ticks(since).map(|ticks| {
+                                Self::new_unchecked(molecule::uint::Uint::to_le_vec(ticks).into())
+                            })
+                        }
+                    }
+                )
+            }
+            Some(ast::Logical::Duration(unit)) => {
+                let unit = time_unit(unit);
+                quote!(
+                    impl #entity {
+                        /// `None` if the ticks of the duration overflow.
+                        pub fn from_duration(duration: ::core::time::Duration) -> Option<Self> {
+                            #unit.to_ticks(duration).map(|ticks| {
+                                Self::new_unchecked(molecule::uint::Uint::to_le_vec(ticks).into())
+                            })
+                        }
+                        pub fn to_duration(&self) -> ::core::time::Duration {
+                            self.as_reader().to_duration()
+                        }
+                    }
+
+                    impl<'r> #reader<'r> {
+                        pub fn to_duration(&self) -> ::core::time::Duration {
+                            let ticks: u64 = molecule::uint::Uint::from_le_slice(self.as_slice());
+                            #unit.to_duration(ticks)
+                        }
+                    }
+
+                    impl ::core::convert::From<#entity> for ::core::time::Duration {
+                        fn from(value: #entity) -> Self {
+                            value.to_duration()
+                        }
+                    }
+                )
+            }
+            Some(ast::Logical::Uuid) => quote!(
+                impl molecule::logical::AsUuid for #entity {
+                    fn uuid_bytes(&self) -> [u8; 16] {
+                        molecule::logical::AsUuid::uuid_bytes(&self.as_reader())
+                    }
+                }
+
+                impl<'r> molecule::logical::AsUuid for #reader<'r> {
+                    fn uuid_bytes(&self) -> [u8; 16] {
+                        *molecule::as_array(self.as_slice())
+                    }
+                }
+
+                impl molecule::logical::FromUuid for #entity {
+                    fn from_uuid_bytes(bytes: [u8; 16]) -> Self {
+                        Self::new_unchecked(bytes.to_vec().into())
+                    }
+                }
+            ),
+            None => quote!(),
+        }
+    }
+}
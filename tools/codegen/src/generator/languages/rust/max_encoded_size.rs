@@ -0,0 +1,30 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{entity_name, reader_name, usize_lit};
+use crate::ast::verified::{self as ast, HasName as _};
+
+pub(super) trait GenMaxEncodedSize {
+    fn gen_max_encoded_size(&self) -> m4::TokenStream;
+}
+
+impl GenMaxEncodedSize for ast::TopDecl {
+    fn gen_max_encoded_size(&self) -> m4::TokenStream {
+        // Only the bounded types have the maximum size.
+        let max_size = match self.max_size() {
+            Some(max_size) => usize_lit(max_size),
+            None => return quote!(),
+        };
+        let entity = entity_name(self.name());
+        let reader = reader_name(self.name());
+        quote!(
+            impl #entity {
+                pub const MAX_ENCODED_SIZE: usize = #max_size;
+            }
+
+            impl<'r> #reader<'r> {
+                pub const MAX_ENCODED_SIZE: usize = #max_size;
+            }
+        )
+    }
+}
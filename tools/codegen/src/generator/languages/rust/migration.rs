@@ -0,0 +1,201 @@
+use std::io;
+
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{
+    entity_name, entity_union_name, func_name, ident_new, path_new, reader_name, reader_union_name,
+    union_item_name, usize_lit,
+};
+use crate::{
+    ast::verified::{self as ast, HasName},
+    migration::{Body, Conversion, FieldValue, Plan, Shim},
+    VERSION,
+};
+
+pub(crate) fn generate<W: io::Write>(
+    writer: &mut W,
+    plan: &Plan,
+    old_module: &str,
+    new_module: &str,
+) -> io::Result<()> {
+    writeln!(writer, "// Generated by Molecule {}", VERSION)?;
+    writeln!(writer)?;
+    let upgrade = gen_direction("upgrade", &plan.upgrade, old_module, new_module);
+    let downgrade = gen_direction("downgrade", &plan.downgrade, new_module, old_module);
+    let code = quote!(#upgrade #downgrade).to_string();
+    let code = super::style::restyle(&code, &Default::default()).unwrap_or(code);
+    writeln!(writer, "{}", code)
+}
+
+/// The paths are relative to the parent of the generated modules.
+fn nested_path(path: &str) -> m4::TokenStream {
+    let path = if path.starts_with("self::") || path.starts_with("super::") {
+        format!("super::{}", path.trim_start_matches("self::"))
+    } else {
+        path.to_owned()
+    };
+    path_new(&path)
+}
+
+fn gen_direction(
+    name: &str,
+    shims: &[Shim],
+    source_module: &str,
+    target_module: &str,
+) -> m4::TokenStream {
+    let module = ident_new(name);
+    let source_module = nested_path(source_module);
+    let target_module = nested_path(target_module);
+    let shims = shims.iter().map(gen_shim).collect::<Vec<_>>();
+    quote!(
+        pub mod #module {
+            use molecule::{migration::MigrationError, prelude::*};
+            use #source_module as source;
+            use #target_module as target;
+
+            #( #shims )*
+        }
+    )
+}
+
+fn gen_shim(shim: &Shim) -> m4::TokenStream {
+    let source_name = shim.source.name();
+    let func = func_name(source_name);
+    let func_reader = reader_func_name(source_name);
+    let source_reader = reader_name(source_name);
+    let target_entity = entity_name(shim.target.name());
+    let body = match shim.body {
+        Body::Copy => {
+            quote!(Ok(target::#target_entity::new_unchecked(reader.as_slice().to_vec().into())))
+        }
+        Body::Fields(ref fields) => {
+            let target_fields = match shim.target.as_ref() {
+                ast::TopDecl::Struct(ref t) => &t.inner,
+                ast::TopDecl::Table(ref t) => &t.inner,
+                _ => unreachable!(),
+            };
+            let setters = fields
+                .iter()
+                .zip(target_fields)
+                .map(|(field, target_field)| {
+                    let setter = func_name(&field.name);
+                    let value = match field.value {
+                        FieldValue::Field(ref source_field, ref conversion) => {
+                            let getter = func_name(source_field);
+                            gen_conversion(conversion, quote!(reader.#getter()))
+                        }
+                        FieldValue::Default(Some(ref data)) => gen_data(&target_field.typ, data),
+                        FieldValue::Default(None) => quote!(Default::default()),
+                    };
+                    quote!(.#setter(#value))
+                });
+            quote!(Ok(target::#target_entity::new_builder() #( #setters )* .build()))
+        }
+        Body::Items(ref conversion) => match shim.target.as_ref() {
+            ast::TopDecl::Array(ref t) => {
+                let items = (0..t.item_count).map(usize_lit).map(|idx| {
+                    if t.typ.is_atom() {
+                        quote!(Byte::new(reader.as_slice()[#idx]))
+                    } else {
+                        gen_conversion(conversion, quote!(reader.get_unchecked(#idx)))
+                    }
+                });
+                quote!(Ok(target::#target_entity::new_builder().set([ #( #items, )* ]).build()))
+            }
+            ast::TopDecl::Option_(_) => {
+                let item = gen_conversion(conversion, quote!(inner));
+                quote!(
+                    let inner = match reader.to_opt() {
+                        Some(inner) => Some(#item),
+                        None => None,
+                    };
+                    Ok(target::#target_entity::new_builder().set(inner).build())
+                )
+            }
+            _ => {
+                let item = gen_conversion(conversion, quote!(reader.get_unchecked(idx)));
+                quote!(
+                    let mut items = Vec::with_capacity(reader.len());
+                    for idx in 0..reader.len() {
+                        items.push(#item);
+                    }
+                    Ok(target::#target_entity::new_builder().set(items).build())
+                )
+            }
+        },
+        Body::Union(ref items) => {
+            let (source_items, target_items) = match (shim.source.as_ref(), shim.target.as_ref()) {
+                (ast::TopDecl::Union(ref s), ast::TopDecl::Union(ref t)) => (&s.inner, &t.inner),
+                _ => unreachable!(),
+            };
+            let source_union = reader_union_name(source_name);
+            let target_union = entity_union_name(shim.target.name());
+            let arms = items.iter().zip(source_items).map(|(item, source_item)| {
+                let source_variant = union_item_name(source_item.typ.name());
+                match item {
+                    Some((index, conversion)) => {
+                        let target_variant = union_item_name(target_items[*index].typ.name());
+                        let value = gen_conversion(conversion, quote!(item));
+                        quote!(
+                            source::#source_union::#source_variant(item) => {
+                                let item = target::#target_union::#target_variant(#value);
+                                Ok(target::#target_entity::new_builder().set(item).build())
+                            }
+                        )
+                    }
+                    None => quote!(
+                        source::#source_union::#source_variant(_) => Err(
+                            MigrationError::UnmappedItem(#source_name.to_owned(), reader.item_id())
+                        ),
+                    ),
+                }
+            });
+            quote!(
+                match reader.to_enum() {
+                    #( #arms )*
+                }
+            )
+        }
+    };
+    quote!(
+        pub fn #func(slice: &[u8]) -> Result<target::#target_entity, MigrationError> {
+            #func_reader(source::#source_reader::from_slice(slice)?)
+        }
+
+        pub fn #func_reader(
+            reader: source::#source_reader<'_>,
+        ) -> Result<target::#target_entity, MigrationError> {
+            #body
+        }
+    )
+}
+
+fn reader_func_name(name: &str) -> m4::Ident {
+    let func = func_name(name);
+    ident_new(&format!("{}_reader", func))
+}
+
+fn gen_conversion(conversion: &Conversion, reader: m4::TokenStream) -> m4::TokenStream {
+    match conversion {
+        Conversion::Atom => quote!(#reader.to_entity()),
+        Conversion::Shim(ref name) => {
+            let func_reader = reader_func_name(name);
+            quote!(#func_reader(#reader)?)
+        }
+        Conversion::Copy => quote!(molecule::prelude::Entity::new_unchecked(
+            #reader.as_slice().to_vec().into()
+        )),
+    }
+}
+
+fn gen_data(typ: &ast::TopDecl, data: &[u8]) -> m4::TokenStream {
+    if typ.is_atom() {
+        let byte = m4::Literal::u8_suffixed(data[0]);
+        quote!(Byte::new(#byte))
+    } else {
+        let entity = entity_name(typ.name());
+        let bytes = data.iter().map(|byte| m4::Literal::u8_suffixed(*byte));
+        quote!(target::#entity::new_unchecked(vec![ #( #bytes, )* ].into()))
+    }
+}
@@ -2,31 +2,116 @@ use std::io;
 
 use quote::quote;
 
-use crate::{ast::verified as ast, VERSION};
+use crate::{
+    ast::verified::{self as ast, HasName as _},
+    generator::{metadata::Metadata, Options},
+    VERSION,
+};
 
-pub(self) mod utilities;
+mod utilities;
+use utilities::visibility_new;
 
-pub(self) mod builder;
-pub(self) mod entity;
-pub(self) mod reader;
+mod builder;
+mod entity;
+mod reader;
 
 /// Constants for `{ Entity, Reader }`
-pub(self) mod display;
+mod display;
 
 /// Constants for `{ Entity, Reader, Builder }`
-pub(self) mod constants;
+mod constants;
 
 /// Constants for `{ Entity, Reader }`
-pub(self) mod properties;
+mod properties;
 
 /// Constants for `{ Entity, Reader }`
-pub(self) mod getters;
+mod getters;
 
-/// Iterator for `{ Union } x { Entity, Reader }`
-pub(self) mod iterator;
+/// Iterator for `{ Array, Vector } x { Entity, Reader }`
+mod iterator;
 
 /// Enumerator for `{ Vector } x { Entity, Reader }`
-pub(self) mod enumerator;
+mod enumerator;
+
+/// Integer conversions for `{ Array } x { Entity, Reader }` which have well-known names
+mod uint;
+
+/// Conversions between `{ Array }` of bytes and Rust arrays
+mod byte_array;
+
+/// Typed conversions for `{ Array }` of bytes which have `@logical`
+mod logical;
+
+/// Checksum trailers for `{ Table }` which have `@checksum`
+mod checksum;
+
+/// Byte accounting for `{ Entity, Reader }`
+mod deep_size;
+use deep_size::GenDeepSize as _;
+
+/// Batches for `{ Union } x { Entity }`
+mod batch;
+use batch::GenBatch as _;
+
+/// Canonical re-encoding for `{ Entity, Reader }`
+mod canonical;
+use canonical::GenCanonical as _;
+
+/// Constants module for `{ Union, Struct, Table }`
+mod schema_constants;
+use schema_constants::GenSchemaConstants as _;
+
+/// Implement `schemars::JsonSchema` for `{ Entity }`
+mod json_schema;
+use json_schema::GenJsonSchema as _;
+
+/// Conversions between `{ FixVec }` and Apache Arrow arrays
+mod arrow;
+use arrow::GenArrow as _;
+
+/// Implement `rayon::iter::IntoParallelIterator` for `{ FixVec, DynVec } Reader`
+mod parallel;
+use parallel::GenParallel as _;
+
+/// `#[repr(transparent)]` readers over `[u8]`
+mod transparent;
+use transparent::GenTransparent as _;
+
+/// `{ Entity }SourceReader` over `molecule::source::Source`
+mod source;
+use source::GenSource as _;
+
+/// `from_slice_linear` for `{ Entity }Reader`
+mod linear;
+use linear::GenLinear as _;
+
+/// Byte comparisons and shared defaults for `{ Entity, Reader }`
+mod comparison;
+use comparison::GenComparison as _;
+
+/// Checked getters for `{ Struct, Table } x { Entity, Reader }`
+mod checked;
+use checked::GenChecked as _;
+
+/// Field access for `{ Table, FixVec, DynVec } x { Entity, Reader }`
+mod field_access;
+use field_access::GenFieldAccess as _;
+
+/// The maximum sizes of the bounded `{ Entity, Reader }`
+mod max_encoded_size;
+use max_encoded_size::GenMaxEncodedSize as _;
+
+/// `{ Table }TypedBuilder`, which only builds after all fields are set
+mod typestate;
+use typestate::GenTypestate as _;
+
+/// PyO3 classes which wrap `{ Entity }`
+mod python;
+use python::GenPython as _;
+
+/// Type aliases for `{ Entity, Reader, Builder }`
+mod alias;
+use alias::GenAlias as _;
 
 mod import;
 use import::GenImport as _;
@@ -34,37 +119,187 @@ use import::GenImport as _;
 mod generator;
 use generator::Generator as _;
 
+/// Post-process for `{ edition, allows, format }`
+mod style;
+
+/// Shims between two versions of a schema
+mod migration;
+pub(crate) use migration::generate as generate_migration;
+
+/// Example snippets for `{ Entity, Reader, Builder }`
+mod examples;
+pub(crate) use examples::generate as generate_examples;
+
 pub(crate) struct Generator;
 
 impl super::LanguageGenerator for Generator {
-    fn generate<W: io::Write>(writer: &mut W, ast: &ast::Ast) -> io::Result<()> {
+    fn generate<W: io::Write>(writer: &mut W, ast: &ast::Ast, options: &Options) -> io::Result<()> {
+        let metadata = Metadata::new(ast);
         writeln!(writer, "// Generated by Molecule {}", VERSION)?;
+        writeln!(writer, "// {}", metadata.comment())?;
         writeln!(writer)?;
-        let code = quote!(
-            use molecule::prelude::*;
-        );
-        write!(writer, "{}", code)?;
-        let major_imports = ast.major_imports();
-        if !major_imports.is_empty() {
-            writeln!(writer)?;
-            for import in major_imports {
-                let code = import.import_crate();
-                write!(writer, "{}", code)?;
-            }
+        let mut buffer = Vec::new();
+        generate_items(&mut buffer, ast, &metadata, options)?;
+        let code = String::from_utf8(buffer).unwrap();
+        if let Some(code) = style::restyle(&code, options) {
+            write!(writer, "{}", code)
+        } else {
+            write!(writer, "{}", code)
         }
+    }
+}
+
+fn generate_items<W: io::Write>(
+    writer: &mut W,
+    ast: &ast::Ast,
+    metadata: &Metadata,
+    options: &Options,
+) -> io::Result<()> {
+    let code = quote!(
+        use molecule::prelude::*;
+    );
+    write!(writer, "{}", code)?;
+    let major_imports = ast.major_imports();
+    if !major_imports.is_empty() {
         writeln!(writer)?;
+        for import in major_imports {
+            let code = import.import_crate(options);
+            write!(writer, "{}", code)?;
+        }
+    }
+    writeln!(writer)?;
+    options.hooks.write_header(writer)?;
+    let vis = visibility_new(&options.visibility);
+    for decl in ast.major_decls() {
+        match decl.as_ref() {
+            ast::TopDecl::Option_(ref i) => i.generate(writer, &vis, options)?,
+            ast::TopDecl::Union(ref i) => {
+                i.generate(writer, &vis, options)?;
+                writeln!(writer, "{}", i.gen_batch())?;
+            }
+            ast::TopDecl::Array(ref i) => i.generate(writer, &vis, options)?,
+            ast::TopDecl::Struct(ref i) => i.generate(writer, &vis, options)?,
+            ast::TopDecl::FixVec(ref i) => i.generate(writer, &vis, options)?,
+            ast::TopDecl::DynVec(ref i) => i.generate(writer, &vis, options)?,
+            ast::TopDecl::Table(ref i) => i.generate(writer, &vis, options)?,
+            ast::TopDecl::Atom(_) => unreachable!(),
+        };
+        writeln!(writer, "{}", decl.gen_deep_size())?;
+        writeln!(writer, "{}", decl.gen_canonical())?;
+        options.hooks.write_type(writer, decl.name())?;
+    }
+    for alias in ast.major_aliases() {
+        writeln!(writer, "{}", alias.gen_alias(&vis))?;
+    }
+    let constants = ast
+        .major_decls()
+        .iter()
+        .map(|decl| decl.gen_schema_constants())
+        .collect::<Vec<_>>();
+    let generator_version = metadata.generator_version;
+    let schema_version = &metadata.schema_version;
+    let code = quote!(
+        #vis mod constants {
+            pub const GENERATOR_VERSION: &str = #generator_version;
+            pub const SCHEMA_VERSION: &str = #schema_version;
+            #( #constants )*
+        }
+    );
+    writeln!(writer, "{}", code)?;
+    if options.json_schema {
         for decl in ast.major_decls() {
-            match decl.as_ref() {
-                ast::TopDecl::Option_(ref i) => i.generate(writer)?,
-                ast::TopDecl::Union(ref i) => i.generate(writer)?,
-                ast::TopDecl::Array(ref i) => i.generate(writer)?,
-                ast::TopDecl::Struct(ref i) => i.generate(writer)?,
-                ast::TopDecl::FixVec(ref i) => i.generate(writer)?,
-                ast::TopDecl::DynVec(ref i) => i.generate(writer)?,
-                ast::TopDecl::Table(ref i) => i.generate(writer)?,
+            let code = match decl.as_ref() {
+                ast::TopDecl::Option_(ref i) => i.gen_json_schema(),
+                ast::TopDecl::Union(ref i) => i.gen_json_schema(),
+                ast::TopDecl::Array(ref i) => i.gen_json_schema(),
+                ast::TopDecl::Struct(ref i) => i.gen_json_schema(),
+                ast::TopDecl::FixVec(ref i) => i.gen_json_schema(),
+                ast::TopDecl::DynVec(ref i) => i.gen_json_schema(),
+                ast::TopDecl::Table(ref i) => i.gen_json_schema(),
                 ast::TopDecl::Atom(_) => unreachable!(),
             };
+            writeln!(writer, "{}", code)?;
+        }
+    }
+    if options.transparent {
+        for decl in ast.major_decls() {
+            writeln!(writer, "{}", decl.gen_transparent(&vis))?;
+        }
+    }
+    if options.source_readers {
+        for decl in ast.major_decls() {
+            writeln!(writer, "{}", decl.gen_source(&vis))?;
+        }
+    }
+    if options.linear_verification {
+        for decl in ast.major_decls() {
+            writeln!(writer, "{}", decl.gen_linear())?;
+        }
+    }
+    if options.byte_comparisons {
+        for decl in ast.major_decls() {
+            writeln!(writer, "{}", decl.gen_comparison())?;
+        }
+    }
+    if options.checked_getters {
+        for decl in ast.major_decls() {
+            writeln!(writer, "{}", decl.gen_checked())?;
+        }
+    }
+    if options.field_access {
+        for decl in ast.major_decls() {
+            writeln!(writer, "{}", decl.gen_field_access())?;
+        }
+    }
+    if options.field_access || options.checked_getters {
+        for decl in ast.major_decls() {
+            writeln!(writer, "{}", decl.gen_verify_fields())?;
+        }
+    }
+    if options.max_encoded_size {
+        for decl in ast.major_decls() {
+            writeln!(writer, "{}", decl.gen_max_encoded_size())?;
+        }
+    }
+    if options.typestate_builders {
+        for decl in ast.major_decls() {
+            if let ast::TopDecl::Table(ref i) = decl.as_ref() {
+                writeln!(writer, "{}", i.gen_typestate(&vis))?;
+            }
+        }
+    }
+    if options.arrow {
+        for decl in ast.major_decls() {
+            let code = match decl.as_ref() {
+                ast::TopDecl::Array(ref i) => i.gen_arrow(),
+                ast::TopDecl::Struct(ref i) => i.gen_arrow(),
+                ast::TopDecl::FixVec(ref i) => i.gen_arrow(),
+                _ => continue,
+            };
+            writeln!(writer, "{}", code)?;
+        }
+    }
+    if options.rayon {
+        for decl in ast.major_decls() {
+            let code = match decl.as_ref() {
+                ast::TopDecl::FixVec(ref i) => i.gen_parallel(),
+                ast::TopDecl::DynVec(ref i) => i.gen_parallel(),
+                _ => continue,
+            };
+            writeln!(writer, "{}", code)?;
+        }
+    }
+    if options.pyo3 {
+        let decls = ast.major_decls();
+        for decl in &decls {
+            writeln!(writer, "{}", decl.gen_python())?;
         }
-        Ok(())
+        let decls = decls.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+        writeln!(
+            writer,
+            "{}",
+            python::gen_python_module(&ast.namespace, &decls)
+        )?;
     }
+    Ok(())
 }
@@ -0,0 +1,47 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::reader_name;
+use crate::ast::verified::{self as ast, HasName};
+
+pub(in super::super) trait GenParallel {
+    fn gen_parallel(&self) -> m4::TokenStream;
+}
+
+impl GenParallel for ast::FixVec {
+    fn gen_parallel(&self) -> m4::TokenStream {
+        gen_parallel_for_vector(self.name(), &self.typ)
+    }
+}
+
+impl GenParallel for ast::DynVec {
+    fn gen_parallel(&self) -> m4::TokenStream {
+        gen_parallel_for_vector(self.name(), &self.typ)
+    }
+}
+
+/// The bytes are iterated by `raw_data`, so only the vectors of other types are parallel.
+fn gen_parallel_for_vector(self_name: &str, inner: &ast::TopDecl) -> m4::TokenStream {
+    if inner.is_atom() {
+        return quote!();
+    }
+    let reader = reader_name(self_name);
+    let reader_inner = reader_name(inner.name());
+    quote!(
+        impl<'r> molecule::parallel::rayon::iter::IntoParallelIterator for #reader<'r> {
+            type Item = #reader_inner<'r>;
+            type Iter = molecule::parallel::ParIter<Self, #reader_inner<'r>>;
+            fn into_par_iter(self) -> Self::Iter {
+                molecule::parallel::ParIter::new(self, self.len(), Self::get_unchecked)
+            }
+        }
+
+        impl<'t, 'r> molecule::parallel::rayon::iter::IntoParallelIterator for &'t #reader<'r> {
+            type Item = #reader_inner<'r>;
+            type Iter = molecule::parallel::ParIter<#reader<'r>, #reader_inner<'r>>;
+            fn into_par_iter(self) -> Self::Iter {
+                (*self).into_par_iter()
+            }
+        }
+    )
+}
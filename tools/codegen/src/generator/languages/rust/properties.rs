@@ -25,7 +25,7 @@ impl DefProperties for ast::Union {
     fn def_properties(&self) -> m4::TokenStream {
         quote!(
             pub fn item_id(&self) -> molecule::Number {
-                molecule::unpack_number(self.as_slice())
+                molecule::number::Number::from_le_slice(self.as_slice()).get()
             }
         )
     }
@@ -47,10 +47,10 @@ impl DefProperties for ast::FixVec {
     fn def_properties(&self) -> m4::TokenStream {
         quote!(
             pub fn total_size(&self) -> usize {
-                molecule::NUMBER_SIZE * (self.item_count() + 1)
+                Self::COUNT_SIZE + Self::ITEM_SIZE * self.item_count()
             }
             pub fn item_count(&self) -> usize {
-                molecule::unpack_number(self.as_slice()) as usize
+                molecule::unpack_count(self.as_slice(), Self::COUNT_SIZE)
             }
 
             pub fn len(&self) -> usize {
@@ -67,13 +67,13 @@ impl DefProperties for ast::DynVec {
     fn def_properties(&self) -> m4::TokenStream {
         quote!(
             pub fn total_size(&self) -> usize {
-                molecule::unpack_number(self.as_slice()) as usize
+                molecule::number::Number::from_le_slice(self.as_slice()).to_usize()
             }
             pub fn item_count(&self) -> usize {
                 if self.total_size() == molecule::NUMBER_SIZE {
                     0
                 } else {
-                    (molecule::unpack_number(&self.as_slice()[molecule::NUMBER_SIZE..]) as usize / 4) - 1
+                    (molecule::number::Number::from_le_slice(&self.as_slice()[molecule::NUMBER_SIZE..]).to_usize() / 4) - 1
                 }
             }
             pub fn item_offsets(&self) ->  &[[u8; 4]] {
@@ -94,13 +94,13 @@ impl DefProperties for ast::Table {
     fn def_properties(&self) -> m4::TokenStream {
         quote!(
             pub fn total_size(&self) -> usize {
-                molecule::unpack_number(self.as_slice()) as usize
+                molecule::number::Number::from_le_slice(self.as_slice()).to_usize()
             }
             pub fn field_count(&self) -> usize {
                 if self.total_size() == molecule::NUMBER_SIZE {
                     0
                 } else {
-                    (molecule::unpack_number(&self.as_slice()[molecule::NUMBER_SIZE..]) as usize / 4) - 1
+                    (molecule::number::Number::from_le_slice(&self.as_slice()[molecule::NUMBER_SIZE..]).to_usize() / 4) - 1
                 }
             }
             pub fn field_offsets(&self) ->  &[[u8; 4]] {
@@ -0,0 +1,297 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{
+    entity_name, entity_union_name, field_name, ident_name, union_item_name, usize_lit,
+};
+use crate::ast::verified::{self as ast, HasName};
+
+// Python mapping for the schemas:
+// - `byte` is an `int`.
+// - Any other type is a class, which wraps the entity and has the same name.
+pub(super) trait GenPython {
+    fn gen_python(&self) -> m4::TokenStream;
+}
+
+pub(super) fn python_name(name: &str) -> m4::Ident {
+    ident_name(name, "Py")
+}
+
+fn python_type(typ: &ast::TopDecl) -> m4::TokenStream {
+    if typ.is_atom() {
+        quote!(u8)
+    } else {
+        let inner = python_name(typ.name());
+        quote!(#inner)
+    }
+}
+
+// The entity of a value which is extracted from Python.
+fn from_python(typ: &ast::TopDecl, value: m4::TokenStream) -> m4::TokenStream {
+    if typ.is_atom() {
+        quote!(Byte::new(#value))
+    } else {
+        quote!(#value.0)
+    }
+}
+
+// The Python value of an entity.
+fn to_python(typ: &ast::TopDecl, value: m4::TokenStream) -> m4::TokenStream {
+    if typ.is_atom() {
+        quote!(u8::from(#value))
+    } else {
+        let inner = python_name(typ.name());
+        quote!(#inner(#value))
+    }
+}
+
+fn index_error(len: m4::TokenStream) -> m4::TokenStream {
+    quote!(pyo3::exceptions::PyIndexError::new_err(::std::format!(
+        "{}: index out of range, the length is {}",
+        <Self as pyo3::PyTypeInfo>::NAME,
+        #len
+    )))
+}
+
+impl GenPython for ast::TopDecl {
+    fn gen_python(&self) -> m4::TokenStream {
+        let methods = match self {
+            ast::TopDecl::Atom(_) => unreachable!(),
+            ast::TopDecl::Option_(ref typ) => gen_option(typ),
+            ast::TopDecl::Union(ref typ) => gen_union(typ),
+            ast::TopDecl::Array(ref typ) => gen_array(typ),
+            ast::TopDecl::Struct(ref typ) => gen_fields(self.name(), &typ.inner),
+            ast::TopDecl::FixVec(ref typ) => gen_vector(self.name(), &typ.typ),
+            ast::TopDecl::DynVec(ref typ) => gen_vector(self.name(), &typ.typ),
+            ast::TopDecl::Table(ref typ) => gen_fields(self.name(), &typ.inner),
+        };
+        let name = self.name();
+        let entity = entity_name(name);
+        let python = python_name(name);
+        quote!(
+            #[pyo3::pyclass(name = #name, frozen)]
+            #[derive(Clone)]
+            pub struct #python(pub #entity);
+
+            #[pyo3::pymethods]
+            impl #python {
+                #[new]
+                #[pyo3(signature = (data = None))]
+                fn new(data: Option<&pyo3::Bound<'_, pyo3::types::PyBytes>>) -> pyo3::PyResult<Self> {
+                    use pyo3::types::PyBytesMethods as _;
+                    match data {
+                        Some(data) => #entity::from_slice(data.as_bytes())
+                            .map(Self)
+                            .map_err(|err| pyo3::exceptions::PyValueError::new_err(::std::format!("{}", err))),
+                        None => Ok(Self(::core::default::Default::default())),
+                    }
+                }
+                #[staticmethod]
+                fn from_compatible_bytes(data: &pyo3::Bound<'_, pyo3::types::PyBytes>) -> pyo3::PyResult<Self> {
+                    use pyo3::types::PyBytesMethods as _;
+                    #entity::from_compatible_slice(data.as_bytes())
+                        .map(Self)
+                        .map_err(|err| pyo3::exceptions::PyValueError::new_err(::std::format!("{}", err)))
+                }
+                fn __bytes__<'py>(&self, py: pyo3::Python<'py>) -> pyo3::Bound<'py, pyo3::types::PyBytes> {
+                    pyo3::types::PyBytes::new_bound(py, self.0.as_slice())
+                }
+                fn __repr__(&self) -> ::std::string::String {
+                    ::std::format!("{}", self.0)
+                }
+                fn __eq__(&self, other: &Self) -> bool {
+                    self.0.as_slice() == other.0.as_slice()
+                }
+                fn __hash__(&self) -> u64 {
+                    use ::std::hash::{Hash as _, Hasher as _};
+                    let mut hasher = ::std::collections::hash_map::DefaultHasher::new();
+                    self.0.as_slice().hash(&mut hasher);
+                    hasher.finish()
+                }
+                fn is_default(&self) -> bool {
+                    self.0.as_slice() == <#entity as ::core::default::Default>::default().as_slice()
+                }
+                #methods
+            }
+        )
+    }
+}
+
+fn gen_option(typ: &ast::Option_) -> m4::TokenStream {
+    let entity = entity_name(typ.name());
+    let inner = python_type(&typ.typ);
+    let value = to_python(&typ.typ, quote!(v));
+    let set = from_python(&typ.typ, quote!(v));
+    quote!(
+        #[staticmethod]
+        #[pyo3(signature = (value = None))]
+        fn build(value: Option<#inner>) -> Self {
+            Self(#entity::new_builder().set(value.map(|v| #set)).build())
+        }
+        fn get(&self) -> Option<#inner> {
+            self.0.to_opt().map(|v| #value)
+        }
+        fn is_none(&self) -> bool {
+            self.0.is_none()
+        }
+    )
+}
+
+fn gen_union(typ: &ast::Union) -> m4::TokenStream {
+    let entity = entity_name(typ.name());
+    let entity_union = entity_union_name(typ.name());
+    let extracts = typ.inner.iter().map(|item| {
+        let inner = python_type(&item.typ);
+        let set = from_python(&item.typ, quote!(v));
+        quote!(
+            if let Ok(v) = item.extract::<#inner>() {
+                return Ok(Self(#entity::new_builder().set(#set).build()));
+            }
+        )
+    });
+    let items = typ.inner.iter().map(|item| {
+        let item_name = union_item_name(item.typ.name());
+        let value = to_python(&item.typ, quote!(v));
+        quote!(#entity_union::#item_name(v) => #value.into_py(py))
+    });
+    quote!(
+        #[staticmethod]
+        fn build(item: &pyo3::Bound<'_, pyo3::PyAny>) -> pyo3::PyResult<Self> {
+            use pyo3::types::PyAnyMethods as _;
+            #( #extracts )*
+            Err(pyo3::exceptions::PyTypeError::new_err(::std::format!(
+                "{}: the item is not in the union",
+                <Self as pyo3::PyTypeInfo>::NAME,
+            )))
+        }
+        fn item_id(&self) -> u32 {
+            self.0.item_id()
+        }
+        fn item(&self, py: pyo3::Python<'_>) -> pyo3::PyObject {
+            use pyo3::IntoPy as _;
+            match self.0.to_enum() {
+                #( #items, )*
+            }
+        }
+    )
+}
+
+fn gen_array(typ: &ast::Array) -> m4::TokenStream {
+    let entity = entity_name(typ.name());
+    let inner = python_type(&typ.typ);
+    let inner_entity = entity_name(typ.typ.name());
+    let set = from_python(&typ.typ, quote!(v));
+    let value = to_python(
+        &typ.typ,
+        quote!(#inner_entity::new_unchecked(self.0.as_bytes().slice(start, start + #entity::ITEM_SIZE))),
+    );
+    let index_error = index_error(quote!(#entity::ITEM_COUNT));
+    let item_count = usize_lit(typ.item_count);
+    quote!(
+        #[staticmethod]
+        fn build(items: Vec<#inner>) -> pyo3::PyResult<Self> {
+            let len = items.len();
+            let items = items.into_iter().map(|v| #set).collect::<Vec<_>>();
+            let items: [#inner_entity; #item_count] = ::core::convert::TryFrom::try_from(items).map_err(|_| {
+                pyo3::exceptions::PyValueError::new_err(::std::format!(
+                    "{}: expect {} items, actual {}",
+                    <Self as pyo3::PyTypeInfo>::NAME,
+                    #entity::ITEM_COUNT,
+                    len
+                ))
+            })?;
+            Ok(Self(#entity::new_builder().set(items).build()))
+        }
+        fn __len__(&self) -> usize {
+            #entity::ITEM_COUNT
+        }
+        fn __getitem__(&self, idx: usize) -> pyo3::PyResult<#inner> {
+            if idx >= #entity::ITEM_COUNT {
+                return Err(#index_error);
+            }
+            let start = #entity::ITEM_SIZE * idx;
+            Ok(#value)
+        }
+    )
+}
+
+fn gen_vector(name: &str, typ: &ast::TopDecl) -> m4::TokenStream {
+    let entity = entity_name(name);
+    let inner = python_type(typ);
+    let set = from_python(typ, quote!(v));
+    let value = to_python(typ, quote!(v));
+    let index_error = index_error(quote!(self.0.len()));
+    quote!(
+        #[staticmethod]
+        fn build(items: Vec<#inner>) -> Self {
+            Self(#entity::new_builder().extend(items.into_iter().map(|v| #set)).build())
+        }
+        fn __len__(&self) -> usize {
+            self.0.len()
+        }
+        fn __getitem__(&self, idx: usize) -> pyo3::PyResult<#inner> {
+            match self.0.get(idx) {
+                Some(v) => Ok(#value),
+                None => Err(#index_error),
+            }
+        }
+    )
+}
+
+fn gen_fields(name: &str, fields: &[ast::FieldDecl]) -> m4::TokenStream {
+    let entity = entity_name(name);
+    let names = &fields
+        .iter()
+        .map(|f| field_name(&f.name))
+        .collect::<Vec<_>>();
+    let types = fields.iter().map(|f| python_type(&f.typ));
+    let sets = fields.iter().map(|f| {
+        let field = field_name(&f.name);
+        let set = from_python(&f.typ, quote!(v));
+        quote!(
+            if let Some(v) = #field {
+                builder = builder.#field(#set);
+            }
+        )
+    });
+    let getters = fields.iter().map(|f| {
+        let field = field_name(&f.name);
+        let inner = python_type(&f.typ);
+        let value = to_python(&f.typ, quote!(self.0.#field()));
+        quote!(
+            #[getter]
+            fn #field(&self) -> #inner {
+                #value
+            }
+        )
+    });
+    quote!(
+        #[staticmethod]
+        #[pyo3(signature = (#( #names = None ),*))]
+        fn build(#( #names: Option<#types> ),*) -> Self {
+            #[allow(unused_mut)]
+            let mut builder = #entity::new_builder();
+            #( #sets )*
+            Self(builder.build())
+        }
+        #( #getters )*
+    )
+}
+
+/// The module of all classes, which is named after the schema in Python.
+///
+/// The Rust function has a fixed name, since the schema name is usually taken by the Rust module
+/// which includes the generated code.
+pub(super) fn gen_python_module(namespace: &str, decls: &[&ast::TopDecl]) -> m4::TokenStream {
+    let module = field_name(namespace).to_string();
+    let classes = decls.iter().map(|decl| python_name(decl.name()));
+    quote!(
+        #[pyo3::pymodule]
+        #[pyo3(name = #module)]
+        pub fn python_module(module: &pyo3::Bound<'_, pyo3::types::PyModule>) -> pyo3::PyResult<()> {
+            use pyo3::types::PyModuleMethods as _;
+            #( module.add_class::<#classes>()?; )*
+            Ok(())
+        }
+    )
+}
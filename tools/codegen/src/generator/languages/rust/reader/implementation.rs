@@ -1,21 +1,56 @@
 use proc_macro2 as m4;
 use quote::quote;
 
-use super::super::utilities::{entity_name, reader_name, usize_lit};
-use crate::ast::verified::{self as ast, HasName};
+use super::super::utilities::{entity_name, func_name, ident_new, reader_name, usize_lit};
+use crate::ast::verified::{self as ast, HasInvariant, HasName};
 
-pub(in super::super) trait ImplReader: HasName {
-    fn impl_reader_internal(&self) -> m4::TokenStream;
+pub(in super::super) trait ImplReader: HasName + HasInvariant {
+    /// The verification of the structure, which is named `verify` unless the type has semantic
+    /// checks.
+    fn impl_reader_internal(&self, verify: &m4::Ident) -> m4::TokenStream;
+
+    fn impl_reader_peek_size(&self) -> m4::TokenStream;
+
+    /// Whether the bytes start with a header of offsets.
+    fn has_offsets(&self) -> bool {
+        false
+    }
 
     fn impl_reader(&self) -> m4::TokenStream {
         let entity = entity_name(self.name());
         let reader = reader_name(self.name());
         let reader_string = reader.to_string();
-        let internal = self.impl_reader_internal();
+        let peek_size = self.impl_reader_peek_size();
+        let has_offsets = if self.has_offsets() {
+            quote!(
+                const HAS_OFFSETS: bool = true;
+            )
+        } else {
+            quote!()
+        };
+        // The semantic checks follow the structure, which is verified in `verify_structure`.
+        let (internal, inherent) = if let Some(checks) = semantic_checks(self) {
+            let structure = self.impl_reader_internal(&ident_new("verify_structure"));
+            let internal = quote!(
+                fn verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
+                    Self::verify_structure(slice, compatible)?;
+                    #checks
+                }
+            );
+            let inherent = quote!(
+                impl<'r> #reader<'r> {
+                    #structure
+                }
+            );
+            (internal, inherent)
+        } else {
+            (self.impl_reader_internal(&ident_new("verify")), quote!())
+        };
         quote!(
             impl<'r> molecule::prelude::Reader<'r> for #reader<'r> {
                 type Entity = #entity;
                 const NAME: &'static str = #reader_string;
+                #has_offsets
                 fn to_entity(&self) -> Self::Entity {
                     Self::Entity::new_unchecked(self.as_slice().into())
                 }
@@ -26,16 +61,125 @@ pub(in super::super) trait ImplReader: HasName {
                     self.0
                 }
                 #internal
+                #peek_size
             }
+
+            #inherent
         )
     }
 }
 
+/// Reject an item of a union which is larger than its `@max_size` before verifying it, so the
+/// bytes of it are never visited.
+pub(in super::super) fn limit_item_size(
+    max_size: Option<usize>,
+    verify_inner: m4::TokenStream,
+) -> m4::TokenStream {
+    if let Some(max_size) = max_size {
+        let max_size = usize_lit(max_size);
+        quote!(if inner_slice.len() > #max_size {
+            ve!(Self, ItemTooLarge, #max_size, inner_slice.len())
+        } else {
+            #verify_inner
+        })
+    } else {
+        verify_inner
+    }
+}
+
+/// Check the constraints between the fields, then validate the invariant, on the `slice` which
+/// has passed the structural verification; or `None` if the type has neither.
+pub(in super::super) fn semantic_checks<T: HasName + HasInvariant + ?Sized>(
+    decl: &T,
+) -> Option<m4::TokenStream> {
+    let constraints = decl.constraints();
+    let invariant = decl.invariant();
+    if constraints.is_empty() && invariant.is_none() {
+        return None;
+    }
+    let reader = reader_name(decl.name());
+    let checks = constraints.iter().map(|constraint| {
+        let holds = constraint_holds(constraint, |field| {
+            let func = func_name(field);
+            quote!(reader.#func())
+        });
+        let text = constraint.to_string();
+        quote!(
+            if !(#holds) {
+                return Err(molecule::error::VerificationError::ConstraintNotHeld(
+                    #reader::NAME.to_owned(),
+                    #text.to_owned(),
+                ));
+            }
+        )
+    });
+    let reader_stmt = if constraints.is_empty() {
+        quote!()
+    } else {
+        quote!(let reader = #reader::new_unchecked(slice);)
+    };
+    let validation = if let Some(invariant) = invariant {
+        quote!(molecule::validation::validate(#reader::NAME, #invariant, slice))
+    } else {
+        quote!(Ok(()))
+    };
+    Some(quote!(
+        #reader_stmt
+        #( #checks )*
+        #validation
+    ))
+}
+
+/// Whether the constraint holds, where the fields are read by `field`.
+pub(in super::super) fn constraint_holds<F>(
+    constraint: &ast::Constraint,
+    field: F,
+) -> m4::TokenStream
+where
+    F: Fn(&str) -> m4::TokenStream,
+{
+    let lhs = operand(&constraint.lhs, &field);
+    let rhs = operand(&constraint.rhs, &field);
+    let comparison = match constraint.comparison {
+        ast::Comparison::Eq => quote!(==),
+        ast::Comparison::Ne => quote!(!=),
+        ast::Comparison::Lt => quote!(<),
+        ast::Comparison::Le => quote!(<=),
+        ast::Comparison::Gt => quote!(>),
+        ast::Comparison::Ge => quote!(>=),
+    };
+    quote!(#lhs #comparison #rhs)
+}
+
+fn operand<F>(operand: &ast::Operand, field: F) -> m4::TokenStream
+where
+    F: Fn(&str) -> m4::TokenStream,
+{
+    match operand {
+        ast::Operand::Len(ref name) => {
+            let value = field(name);
+            quote!((#value.len() as u64))
+        }
+        ast::Operand::ItemId(ref name) => {
+            let value = field(name);
+            quote!(u64::from(#value.item_id()))
+        }
+        ast::Operand::Value(ref name) => {
+            let value = field(name);
+            quote!(molecule::unpack_uint(#value.as_slice()))
+        }
+        ast::Operand::Integer(value) => {
+            let value = m4::Literal::u64_suffixed(*value);
+            quote!(#value)
+        }
+    }
+}
+
 impl ImplReader for ast::Option_ {
-    fn impl_reader_internal(&self) -> m4::TokenStream {
+    fn impl_reader_internal(&self, verify: &m4::Ident) -> m4::TokenStream {
         let inner = reader_name(self.typ.name());
         quote!(
-            fn verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
+            fn #verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
                 if !slice.is_empty() {
                     #inner::verify(&slice[..], compatible)?;
                 }
@@ -43,40 +187,96 @@ impl ImplReader for ast::Option_ {
             }
         )
     }
+
+    fn impl_reader_peek_size(&self) -> m4::TokenStream {
+        let inner = reader_name(self.typ.name());
+        quote!(
+            fn peek_size(slice: &[u8]) -> molecule::error::VerificationResult<usize> {
+                // An option is `None` only if there's nothing left.
+                if slice.is_empty() {
+                    Ok(0)
+                } else {
+                    #inner::peek_size(slice)
+                }
+            }
+        )
+    }
 }
 
 impl ImplReader for ast::Union {
-    fn impl_reader_internal(&self) -> m4::TokenStream {
+    fn impl_reader_internal(&self, verify: &m4::Ident) -> m4::TokenStream {
+        let type_name = self.name();
         let verify_inners = self.inner.iter().enumerate().map(|(index, inner)| {
             let item_id = usize_lit(index);
-            let inner = reader_name(inner.typ.name());
+            let verify_inner = {
+                let inner = reader_name(inner.typ.name());
+                quote!(#inner::verify(inner_slice, compatible))
+            };
+            let verify_inner = limit_item_size(inner.max_size, verify_inner);
             quote!(
-                #item_id => #inner::verify(inner_slice, compatible),
+                #item_id => #verify_inner,
             )
         });
         quote!(
-            fn verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
+            fn #verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
                 use molecule::verification_error as ve;
                 let slice_len = slice.len();
                 if slice_len < molecule::NUMBER_SIZE {
                     return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
                 }
-                let item_id = molecule::unpack_number(slice);
+                let item_id = molecule::number::Number::from_le_slice(slice).get();
                 let inner_slice = &slice[molecule::NUMBER_SIZE..];
                 match item_id {
                     #( #verify_inners )*
-                    _ => ve!(Self, UnknownItem, Self::ITEM_COUNT, item_id),
+                    _ => {
+                        if compatible {
+                            molecule::compatible::report(
+                                molecule::compatible::CompatibleEvent::UnknownItem {
+                                    type_name: #type_name,
+                                    item_count: Self::ITEM_COUNT,
+                                    item_id,
+                                },
+                            );
+                        }
+                        ve!(Self, UnknownItem, Self::ITEM_COUNT, item_id)
+                    }
                 }?;
                 Ok(())
             }
         )
     }
+
+    fn impl_reader_peek_size(&self) -> m4::TokenStream {
+        let peek_inners = self.inner.iter().enumerate().map(|(index, inner)| {
+            let item_id = usize_lit(index);
+            let inner = reader_name(inner.typ.name());
+            quote!(
+                #item_id => #inner::peek_size(inner_slice)?,
+            )
+        });
+        quote!(
+            fn peek_size(slice: &[u8]) -> molecule::error::VerificationResult<usize> {
+                use molecule::verification_error as ve;
+                let slice_len = slice.len();
+                if slice_len < molecule::NUMBER_SIZE {
+                    return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
+                }
+                let item_id = molecule::number::Number::from_le_slice(slice).get();
+                let inner_slice = &slice[molecule::NUMBER_SIZE..];
+                let inner_size = match item_id {
+                    #( #peek_inners )*
+                    _ => return ve!(Self, UnknownItem, Self::ITEM_COUNT, item_id),
+                };
+                Ok(molecule::NUMBER_SIZE + inner_size)
+            }
+        )
+    }
 }
 
 impl ImplReader for ast::Array {
-    fn impl_reader_internal(&self) -> m4::TokenStream {
+    fn impl_reader_internal(&self, verify: &m4::Ident) -> m4::TokenStream {
         quote!(
-            fn verify(slice: &[u8], _compatible: bool) -> molecule::error::VerificationResult<()> {
+            fn #verify(slice: &[u8], _compatible: bool) -> molecule::error::VerificationResult<()> {
                 use molecule::verification_error as ve;
                 let slice_len = slice.len();
                 if slice_len != Self::TOTAL_SIZE {
@@ -86,12 +286,25 @@ impl ImplReader for ast::Array {
             }
         )
     }
+
+    fn impl_reader_peek_size(&self) -> m4::TokenStream {
+        quote!(
+            fn peek_size(slice: &[u8]) -> molecule::error::VerificationResult<usize> {
+                use molecule::verification_error as ve;
+                let slice_len = slice.len();
+                if slice_len < Self::TOTAL_SIZE {
+                    return ve!(Self, TotalSizeNotMatch, Self::TOTAL_SIZE, slice_len);
+                }
+                Ok(Self::TOTAL_SIZE)
+            }
+        )
+    }
 }
 
 impl ImplReader for ast::Struct {
-    fn impl_reader_internal(&self) -> m4::TokenStream {
+    fn impl_reader_internal(&self, verify: &m4::Ident) -> m4::TokenStream {
         quote!(
-            fn verify(slice: &[u8], _compatible: bool) -> molecule::error::VerificationResult<()> {
+            fn #verify(slice: &[u8], _compatible: bool) -> molecule::error::VerificationResult<()> {
                 use molecule::verification_error as ve;
                 let slice_len = slice.len();
                 if slice_len != Self::TOTAL_SIZE {
@@ -101,25 +314,45 @@ impl ImplReader for ast::Struct {
             }
         )
     }
+
+    fn impl_reader_peek_size(&self) -> m4::TokenStream {
+        quote!(
+            fn peek_size(slice: &[u8]) -> molecule::error::VerificationResult<usize> {
+                use molecule::verification_error as ve;
+                let slice_len = slice.len();
+                if slice_len < Self::TOTAL_SIZE {
+                    return ve!(Self, TotalSizeNotMatch, Self::TOTAL_SIZE, slice_len);
+                }
+                Ok(Self::TOTAL_SIZE)
+            }
+        )
+    }
 }
 
 impl ImplReader for ast::FixVec {
-    fn impl_reader_internal(&self) -> m4::TokenStream {
+    fn impl_reader_internal(&self, verify: &m4::Ident) -> m4::TokenStream {
         quote!(
-            fn verify(slice: &[u8], _compatible: bool) -> molecule::error::VerificationResult<()> {
+            fn #verify(slice: &[u8], _compatible: bool) -> molecule::error::VerificationResult<()> {
                 use molecule::verification_error as ve;
                 let slice_len = slice.len();
-                if slice_len < molecule::NUMBER_SIZE {
-                    return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
+                if slice_len < Self::COUNT_SIZE {
+                    return ve!(Self, HeaderIsBroken, Self::COUNT_SIZE, slice_len);
                 }
-                let item_count = molecule::unpack_number(slice) as usize;
+                let item_count = molecule::unpack_count(slice, Self::COUNT_SIZE);
                 if item_count == 0 {
-                    if slice_len != molecule::NUMBER_SIZE {
-                        return ve!(Self, TotalSizeNotMatch, molecule::NUMBER_SIZE, slice_len);
+                    if slice_len != Self::COUNT_SIZE {
+                        return ve!(Self, TotalSizeNotMatch, Self::COUNT_SIZE, slice_len);
                     }
                     return Ok(());
                 }
-                let total_size = molecule::NUMBER_SIZE + Self::ITEM_SIZE * item_count;
+                let total_size = match molecule::fixvec_total_size(
+                    Self::COUNT_SIZE,
+                    Self::ITEM_SIZE,
+                    item_count,
+                ) {
+                    Some(total_size) => total_size,
+                    None => return ve!(Self, SizeOverflow),
+                };
                 if slice_len != total_size {
                     return ve!(Self, TotalSizeNotMatch, total_size, slice_len);
                 }
@@ -127,19 +360,48 @@ impl ImplReader for ast::FixVec {
             }
         )
     }
+
+    fn impl_reader_peek_size(&self) -> m4::TokenStream {
+        quote!(
+            fn peek_size(slice: &[u8]) -> molecule::error::VerificationResult<usize> {
+                use molecule::verification_error as ve;
+                let slice_len = slice.len();
+                if slice_len < Self::COUNT_SIZE {
+                    return ve!(Self, HeaderIsBroken, Self::COUNT_SIZE, slice_len);
+                }
+                let item_count = molecule::unpack_count(slice, Self::COUNT_SIZE);
+                let total_size = match molecule::fixvec_total_size(
+                    Self::COUNT_SIZE,
+                    Self::ITEM_SIZE,
+                    item_count,
+                ) {
+                    Some(total_size) => total_size,
+                    None => return ve!(Self, SizeOverflow),
+                };
+                if slice_len < total_size {
+                    return ve!(Self, TotalSizeNotMatch, total_size, slice_len);
+                }
+                Ok(total_size)
+            }
+        )
+    }
 }
 
 impl ImplReader for ast::DynVec {
-    fn impl_reader_internal(&self) -> m4::TokenStream {
+    fn has_offsets(&self) -> bool {
+        true
+    }
+
+    fn impl_reader_internal(&self, verify: &m4::Ident) -> m4::TokenStream {
         let inner = reader_name(self.typ.name());
         quote!(
-            fn verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
+            fn #verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
                 use molecule::verification_error as ve;
                 let slice_len = slice.len();
                 if slice_len < molecule::NUMBER_SIZE {
                     return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
                 }
-                let total_size = molecule::unpack_number(slice) as usize;
+                let total_size = molecule::number::Number::from_le_slice(slice).to_usize();
                 if slice_len != total_size {
                     return ve!(Self, TotalSizeNotMatch, total_size, slice_len);
                 }
@@ -149,111 +411,153 @@ impl ImplReader for ast::DynVec {
                 if slice_len < molecule::NUMBER_SIZE * 2 {
                     return ve!(Self, TotalSizeNotMatch, molecule::NUMBER_SIZE * 2, slice_len);
                 }
-                let offset_first = molecule::unpack_number(&slice[molecule::NUMBER_SIZE..]) as usize;
+                let offset_first = molecule::number::Number::from_le_slice(&slice[molecule::NUMBER_SIZE..]).to_usize();
                 if offset_first % 4 != 0 || offset_first < molecule::NUMBER_SIZE * 2 {
                     return ve!(Self, OffsetsNotMatch);
                 }
                 let item_count = offset_first / 4 - 1;
-                let header_size = molecule::NUMBER_SIZE * (item_count + 1);
+                let header_size = match molecule::header_size(item_count) {
+                    Some(header_size) => header_size,
+                    None => return ve!(Self, SizeOverflow),
+                };
                 if slice_len < header_size {
                     return ve!(Self, HeaderIsBroken, header_size, slice_len);
                 }
-                let ptr = molecule::unpack_number_vec(&slice[molecule::NUMBER_SIZE..]);
-                let mut offsets: Vec<usize> = ptr[..item_count]
-                    .iter()
-                    .map(|x| molecule::unpack_number(&x[..]) as usize)
-                    .collect();
-                offsets.push(total_size);
-                if offsets.windows(2).any(|i| i[0] > i[1]) {
+                let offsets = molecule::unpack_number_vec(&slice[molecule::NUMBER_SIZE..header_size]);
+                let offset_at = |idx: usize| {
+                    if idx == item_count {
+                        total_size
+                    } else {
+                        molecule::number::Number::from_le_slice(&offsets[idx][..]).to_usize()
+                    }
+                };
+                if (0..item_count).any(|idx| offset_at(idx) > offset_at(idx + 1)) {
                     return ve!(Self, OffsetsNotMatch);
                 }
-                for pair in offsets.windows(2) {
-                    let start = pair[0];
-                    let end =  pair[1];
-                    #inner::verify(&slice[start..end], compatible)?;
+                for idx in 0..item_count {
+                    #inner::verify(&slice[offset_at(idx)..offset_at(idx + 1)], compatible)?;
                 }
                 Ok(())
             }
         )
     }
+
+    fn impl_reader_peek_size(&self) -> m4::TokenStream {
+        quote!(
+            fn peek_size(slice: &[u8]) -> molecule::error::VerificationResult<usize> {
+                use molecule::verification_error as ve;
+                let slice_len = slice.len();
+                if slice_len < molecule::NUMBER_SIZE {
+                    return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
+                }
+                let total_size = molecule::number::Number::from_le_slice(slice).to_usize();
+                if slice_len < total_size {
+                    return ve!(Self, TotalSizeNotMatch, total_size, slice_len);
+                }
+                Ok(total_size)
+            }
+        )
+    }
 }
 
 impl ImplReader for ast::Table {
-    fn impl_reader_internal(&self) -> m4::TokenStream {
-        if self.inner.is_empty() {
+    fn has_offsets(&self) -> bool {
+        true
+    }
+
+    fn impl_reader_internal(&self, verify: &m4::Ident) -> m4::TokenStream {
+        let header = verify_table_header(self);
+        let verify_fields = self.inner.iter().enumerate().map(|(i, f)| {
+            let field = reader_name(f.typ.name());
+            let start = usize_lit(i);
+            let end = usize_lit(i + 1);
             quote!(
-                fn verify(
-                    slice: &[u8],
-                    compatible: bool,
-                ) -> molecule::error::VerificationResult<()> {
-                    use molecule::verification_error as ve;
-                    let slice_len = slice.len();
-                    if slice_len < molecule::NUMBER_SIZE {
-                        return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
-                    }
-                    let total_size = molecule::unpack_number(slice) as usize;
-                    if slice_len != total_size {
-                        return ve!(Self, TotalSizeNotMatch, total_size, slice_len);
-                    }
-                    if slice_len > molecule::NUMBER_SIZE && !compatible {
-                        return ve!(Self, FieldCountNotMatch, Self::FIELD_COUNT, !0);
-                    }
-                    Ok(())
-                }
+                #field::verify(&slice[offset_at(#start)..offset_at(#end)], compatible)?;
             )
-        } else {
-            let verify_fields = self.inner.iter().enumerate().map(|(i, f)| {
-                let field = reader_name(f.typ.name());
-                let start = usize_lit(i);
-                let end = usize_lit(i + 1);
-                quote!(
-                    #field::verify(&slice[offsets[#start]..offsets[#end]], compatible)?;
-                )
-            });
-            quote!(
-                fn verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
-                    use molecule::verification_error as ve;
-                    let slice_len = slice.len();
-                    if slice_len < molecule::NUMBER_SIZE {
-                        return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
-                    }
-                    let total_size = molecule::unpack_number(slice) as usize;
-                    if slice_len != total_size {
-                        return ve!(Self, TotalSizeNotMatch, total_size, slice_len);
-                    }
-                    if slice_len == molecule::NUMBER_SIZE && Self::FIELD_COUNT == 0 {
-                        return Ok(());
-                    }
-                    if slice_len < molecule::NUMBER_SIZE * 2 {
-                        return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE * 2, slice_len);
-                    }
-                    let offset_first = molecule::unpack_number(&slice[molecule::NUMBER_SIZE..]) as usize;
-                    if offset_first % 4 != 0 || offset_first < molecule::NUMBER_SIZE * 2 {
-                        return ve!(Self, OffsetsNotMatch);
-                    }
-                    let field_count = offset_first / 4 - 1;
-                    if field_count < Self::FIELD_COUNT {
-                        return ve!(Self, FieldCountNotMatch, Self::FIELD_COUNT, field_count);
-                    } else if !compatible && field_count > Self::FIELD_COUNT {
-                        return ve!(Self, FieldCountNotMatch, Self::FIELD_COUNT, field_count);
-                    };
-                    let header_size = molecule::NUMBER_SIZE * (field_count + 1);
-                    if slice_len < header_size {
-                        return ve!(Self, HeaderIsBroken, header_size, slice_len);
-                    }
-                    let ptr = molecule::unpack_number_vec(&slice[molecule::NUMBER_SIZE..]);
-                    let mut offsets: Vec<usize> = ptr[..field_count]
-                        .iter()
-                        .map(|x| molecule::unpack_number(&x[..]) as usize)
-                        .collect();
-                    offsets.push(total_size);
-                    if offsets.windows(2).any(|i| i[0] > i[1]) {
-                        return ve!(Self, OffsetsNotMatch);
-                    }
-                    #( #verify_fields )*
-                    Ok(())
+        });
+        quote!(
+            fn #verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
+                #header
+                #( #verify_fields )*
+                Ok(())
+            }
+        )
+    }
+
+    fn impl_reader_peek_size(&self) -> m4::TokenStream {
+        quote!(
+            fn peek_size(slice: &[u8]) -> molecule::error::VerificationResult<usize> {
+                use molecule::verification_error as ve;
+                let slice_len = slice.len();
+                if slice_len < molecule::NUMBER_SIZE {
+                    return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
                 }
-            )
-        }
+                let total_size = molecule::number::Number::from_le_slice(slice).to_usize();
+                if slice_len < total_size {
+                    return ve!(Self, TotalSizeNotMatch, total_size, slice_len);
+                }
+                Ok(total_size)
+            }
+        )
     }
 }
+
+/// Verify the header of a table, then define `offset_at(idx)`, which is the offset of the field
+/// at the index, or the total size after the last field.
+pub(in super::super) fn verify_table_header(typ: &ast::Table) -> m4::TokenStream {
+    let type_name = typ.name();
+    quote!(
+        use molecule::verification_error as ve;
+        let slice_len = slice.len();
+        if slice_len < molecule::NUMBER_SIZE {
+            return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
+        }
+        let total_size = molecule::number::Number::from_le_slice(slice).to_usize();
+        if slice_len != total_size {
+            return ve!(Self, TotalSizeNotMatch, total_size, slice_len);
+        }
+        if slice_len == molecule::NUMBER_SIZE && Self::FIELD_COUNT == 0 {
+            return Ok(());
+        }
+        if slice_len < molecule::NUMBER_SIZE * 2 {
+            return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE * 2, slice_len);
+        }
+        let offset_first = molecule::number::Number::from_le_slice(&slice[molecule::NUMBER_SIZE..]).to_usize();
+        if offset_first % 4 != 0 || offset_first < molecule::NUMBER_SIZE * 2 {
+            return ve!(Self, OffsetsNotMatch);
+        }
+        let field_count = offset_first / 4 - 1;
+        if field_count < Self::FIELD_COUNT {
+            return ve!(Self, FieldCountNotMatch, Self::FIELD_COUNT, field_count);
+        } else if field_count > Self::FIELD_COUNT {
+            if !compatible {
+                return ve!(Self, FieldCountNotMatch, Self::FIELD_COUNT, field_count);
+            }
+            molecule::compatible::report(
+                molecule::compatible::CompatibleEvent::ExtraFields {
+                    type_name: #type_name,
+                    expected: Self::FIELD_COUNT,
+                    actual: field_count,
+                },
+            );
+        };
+        let header_size = match molecule::header_size(field_count) {
+            Some(header_size) => header_size,
+            None => return ve!(Self, SizeOverflow),
+        };
+        if slice_len < header_size {
+            return ve!(Self, HeaderIsBroken, header_size, slice_len);
+        }
+        let offsets = molecule::unpack_number_vec(&slice[molecule::NUMBER_SIZE..header_size]);
+        let offset_at = |idx: usize| {
+            if idx == field_count {
+                total_size
+            } else {
+                molecule::number::Number::from_le_slice(&offsets[idx][..]).to_usize()
+            }
+        };
+        if (0..field_count).any(|idx| offset_at(idx) > offset_at(idx + 1)) {
+            return ve!(Self, OffsetsNotMatch);
+        }
+    )
+}
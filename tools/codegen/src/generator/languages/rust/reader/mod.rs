@@ -6,8 +6,12 @@ use crate::ast::verified::HasName;
 
 mod implementation;
 
+pub(super) use implementation::{
+    constraint_holds, limit_item_size, semantic_checks, verify_table_header,
+};
+
 pub(super) trait GenReader {
-    fn gen_reader(&self) -> m4::TokenStream;
+    fn gen_reader(&self, vis: &m4::TokenStream) -> m4::TokenStream;
 }
 
 impl<T> GenReader for T
@@ -19,16 +23,17 @@ where
         + super::getters::ImplGetters
         + implementation::ImplReader,
 {
-    fn gen_reader(&self) -> m4::TokenStream {
+    fn gen_reader(&self, vis: &m4::TokenStream) -> m4::TokenStream {
         let reader = reader_name(self.name());
         let display_stmts = self.impl_display();
+        let debug_stmts = self.impl_debug();
         let constants = self.def_constants();
         let properties = self.def_properties();
         let getters = self.impl_getters_for_reader();
         let implementation = self.impl_reader();
         quote!(
             #[derive(Clone, Copy)]
-            pub struct #reader<'r>(&'r [u8]);
+            #vis struct #reader<'r>(&'r [u8]);
 
             impl<'r> ::core::fmt::LowerHex for #reader<'r> {
                 fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
@@ -42,7 +47,7 @@ where
 
             impl<'r> ::core::fmt::Debug for #reader<'r> {
                 fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
-                    write!(f, "{}({:#x})", Self::NAME, self)
+                    #debug_stmts
                 }
             }
 
@@ -58,6 +63,12 @@ where
                 #getters
             }
 
+            impl<'r> molecule::content_hash::ContentHash for #reader<'r> {
+                fn canonical_bytes(&self) -> &[u8] {
+                    self.as_slice()
+                }
+            }
+
             #implementation
         )
     }
@@ -0,0 +1,69 @@
+use case::CaseExt;
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{func_name, ident_new, usize_lit};
+use crate::ast::verified::{self as ast, HasName};
+
+pub(in super::super) trait GenSchemaConstants {
+    fn gen_schema_constants(&self) -> m4::TokenStream;
+}
+
+fn constant_name(prefix: &str, name: &str) -> m4::Ident {
+    ident_new(&format!(
+        "{}_{}",
+        prefix,
+        name.to_snake().to_ascii_uppercase()
+    ))
+}
+
+impl GenSchemaConstants for ast::TopDecl {
+    fn gen_schema_constants(&self) -> m4::TokenStream {
+        let module = func_name(self.name());
+        let name = self.name();
+        let constants = match self {
+            ast::TopDecl::Union(ref i) => i
+                .inner
+                .iter()
+                .enumerate()
+                .map(|(item_id, item)| {
+                    let constant = constant_name("ITEM", item.typ.name());
+                    let item_id = usize_lit(item_id);
+                    let alias = item.alias.as_ref().map(|alias| {
+                        let alias = constant_name("ITEM", alias);
+                        let note = format!("use `{}` instead", constant);
+                        quote!(
+                            #[deprecated(note = #note)]
+                            pub const #alias: molecule::Number = #item_id;
+                        )
+                    });
+                    quote!(
+                        pub const #constant: molecule::Number = #item_id;
+                        #alias
+                    )
+                })
+                .collect(),
+            ast::TopDecl::Struct(ref i) => gen_field_indexes(&i.inner),
+            ast::TopDecl::Table(ref i) => gen_field_indexes(&i.inner),
+            _ => Vec::new(),
+        };
+        quote!(
+            pub mod #module {
+                pub const NAME: &str = #name;
+                #( #constants )*
+            }
+        )
+    }
+}
+
+fn gen_field_indexes(fields: &[ast::FieldDecl]) -> Vec<m4::TokenStream> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let constant = constant_name("FIELD", &field.name);
+            let index = usize_lit(index);
+            quote!(pub const #constant: usize = #index;)
+        })
+        .collect()
+}
@@ -0,0 +1,227 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::super::model::struct_fields;
+use super::utilities::{entity_name, func_name, ident_name, reader_name, usize_lit};
+use crate::ast::verified::{self as ast, HasName};
+
+pub(super) trait GenSource {
+    fn gen_source(&self, vis: &m4::TokenStream) -> m4::TokenStream;
+}
+
+fn source_reader_name(name: &str) -> m4::Ident {
+    ident_name(name, "SourceReader")
+}
+
+/// The return type of a part, and the expression which converts the view of it.
+///
+/// A byte is read at once, other types are wrapped by their source readers.
+fn gen_part(typ: &ast::TopDecl) -> (m4::TokenStream, m4::TokenStream) {
+    if typ.is_atom() {
+        (
+            quote!(Byte),
+            quote!(
+                view.with_slice(
+                    |slice| ByteReader::from_slice(slice).map(|reader| reader.to_entity())
+                )
+            ),
+        )
+    } else {
+        let source_reader = source_reader_name(typ.name());
+        (
+            quote!(#source_reader<'s, S>),
+            quote!(Ok(#source_reader::from_view(view))),
+        )
+    }
+}
+
+impl GenSource for ast::TopDecl {
+    fn gen_source(&self, vis: &m4::TokenStream) -> m4::TokenStream {
+        let source_reader = source_reader_name(self.name());
+        let reader = reader_name(self.name());
+        let entity = entity_name(self.name());
+        let name = quote!(<#entity as Entity>::NAME);
+        let accessors = gen_accessors(self, &name);
+        quote!(
+            #[derive(Debug)]
+            #vis struct #source_reader<'s, S: ?Sized>(molecule::source::SourceView<'s, S>);
+
+            impl<'s, S: ?Sized> Clone for #source_reader<'s, S> {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl<'s, S: ?Sized> Copy for #source_reader<'s, S> {}
+
+            impl<'s, S: molecule::source::Source + ?Sized> #source_reader<'s, S> {
+                /// The value is the whole source.
+                pub fn new(source: &'s S) -> Self {
+                    Self(molecule::source::SourceView::new(source))
+                }
+                pub fn from_view(view: molecule::source::SourceView<'s, S>) -> Self {
+                    Self(view)
+                }
+                pub fn view(&self) -> molecule::source::SourceView<'s, S> {
+                    self.0
+                }
+                /// Read and verify the whole value, then pass the reader of it to the function.
+                pub fn with_reader<T, F>(&self, f: F) -> molecule::source::SourceResult<T>
+                where
+                    F: FnOnce(#reader<'_>) -> T,
+                {
+                    self.0.with_slice(|slice| #reader::from_slice(slice).map(f))
+                }
+                pub fn to_entity(&self) -> molecule::source::SourceResult<#entity> {
+                    self.with_reader(|reader| reader.to_entity())
+                }
+                #accessors
+            }
+        )
+    }
+}
+
+fn gen_accessors(decl: &ast::TopDecl, name: &m4::TokenStream) -> m4::TokenStream {
+    match decl {
+        ast::TopDecl::Atom(_) => unreachable!(),
+        ast::TopDecl::Option_(ref typ) => {
+            let (ret, stmt) = gen_part(&typ.typ);
+            quote!(
+                pub fn to_opt(&self) -> molecule::source::SourceResult<Option<#ret>> {
+                    if self.0.is_empty() {
+                        return Ok(None);
+                    }
+                    let view = self.0;
+                    #stmt.map(Some)
+                }
+            )
+        }
+        ast::TopDecl::Union(_) => quote!(
+            pub fn item_id(&self) -> molecule::source::SourceResult<molecule::Number> {
+                self.0.number_at(#name, 0)
+            }
+            /// The view of the item, which could be converted by the source reader of the type of
+            /// the item id.
+            pub fn item(&self) -> molecule::source::SourceResult<molecule::source::SourceView<'s, S>> {
+                self.0.union_item(#name).map(|(_, item)| item)
+            }
+        ),
+        ast::TopDecl::Array(ref typ) => {
+            let raw_data = if typ.typ.is_atom() {
+                quote!(
+                    pub fn raw_data(&self) -> molecule::source::SourceResult<Cow<'s, [u8]>> {
+                        self.0.read()
+                    }
+                )
+            } else {
+                quote!()
+            };
+            let (ret, stmt) = gen_part(&typ.typ);
+            let getters = (0..typ.item_count).map(|idx| {
+                let func = func_name(&format!("nth{}", idx));
+                let start = usize_lit(typ.item_size * idx);
+                let end = usize_lit(typ.item_size * (idx + 1));
+                quote!(
+                    pub fn #func(&self) -> molecule::source::SourceResult<#ret> {
+                        let view = self.0.slice(#start, #end)?;
+                        #stmt
+                    }
+                )
+            });
+            quote!(
+                #raw_data
+                #( #getters )*
+            )
+        }
+        ast::TopDecl::Struct(ref typ) => {
+            let getters = struct_fields(typ)
+                .into_iter()
+                .map(|field| {
+                    let func = func_name(field.name);
+                    let (ret, stmt) = gen_part(field.typ);
+                    let start = usize_lit(field.offset());
+                    let end = usize_lit(field.end());
+                    quote!(
+                        pub fn #func(&self) -> molecule::source::SourceResult<#ret> {
+                            let view = self.0.slice(#start, #end)?;
+                            #stmt
+                        }
+                    )
+                })
+                .collect::<Vec<_>>();
+            quote!( #( #getters )* )
+        }
+        ast::TopDecl::FixVec(ref typ) => {
+            let count_size = usize_lit(typ.count_size);
+            let item_size = usize_lit(typ.item_size);
+            let raw_data = if typ.typ.is_atom() {
+                quote!(
+                    pub fn raw_data(&self) -> molecule::source::SourceResult<Cow<'s, [u8]>> {
+                        self.len()?;
+                        self.0.slice(#count_size, self.0.len())?.read()
+                    }
+                )
+            } else {
+                quote!()
+            };
+            let (ret, stmt) = gen_part(&typ.typ);
+            quote!(
+                pub fn len(&self) -> molecule::source::SourceResult<usize> {
+                    self.0.fixed_count(#name, #count_size, #item_size)
+                }
+                pub fn is_empty(&self) -> molecule::source::SourceResult<bool> {
+                    self.len().map(|len| len == 0)
+                }
+                pub fn get(&self, idx: usize) -> molecule::source::SourceResult<Option<#ret>> {
+                    match self.0.fixed_item(#name, #count_size, #item_size, idx)? {
+                        Some(view) => #stmt.map(Some),
+                        None => Ok(None),
+                    }
+                }
+                #raw_data
+            )
+        }
+        ast::TopDecl::DynVec(ref typ) => {
+            let (ret, stmt) = gen_part(&typ.typ);
+            quote!(
+                pub fn len(&self) -> molecule::source::SourceResult<usize> {
+                    self.0.offset_count(#name)
+                }
+                pub fn is_empty(&self) -> molecule::source::SourceResult<bool> {
+                    self.len().map(|len| len == 0)
+                }
+                pub fn get(&self, idx: usize) -> molecule::source::SourceResult<Option<#ret>> {
+                    match self.0.offset_item(#name, idx)? {
+                        Some(view) => #stmt.map(Some),
+                        None => Ok(None),
+                    }
+                }
+            )
+        }
+        ast::TopDecl::Table(ref typ) => {
+            let field_count = usize_lit(typ.inner.len());
+            let getters = typ
+                .inner
+                .iter()
+                .enumerate()
+                .map(|(idx, field)| {
+                    let func = func_name(&field.name);
+                    let (ret, stmt) = gen_part(&field.typ);
+                    let idx = usize_lit(idx);
+                    quote!(
+                        pub fn #func(&self) -> molecule::source::SourceResult<#ret> {
+                            let view = self.0.field(#name, #field_count, #idx)?;
+                            #stmt
+                        }
+                    )
+                })
+                .collect::<Vec<_>>();
+            quote!(
+                pub fn field_count(&self) -> molecule::source::SourceResult<usize> {
+                    self.0.offset_count(#name)
+                }
+                #( #getters )*
+            )
+        }
+    }
+}
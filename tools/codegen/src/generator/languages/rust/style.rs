@@ -0,0 +1,463 @@
+//! Post-process the generated code for the edition, the allowed lints and the format.
+//!
+//! The code is generated for the edition 2018 without any attributes. It's re-parsed into tokens,
+//! and the tokens are rewritten and printed again, so the semantics never change.
+
+use std::{iter::FromIterator as _, str::FromStr as _};
+
+use proc_macro2 as m4;
+
+use crate::{generator::Options, Edition};
+
+/// The crates whose paths should start with `::` in the edition 2015.
+const CRATES: &[&str] = &["molecule", "schemars"];
+
+/// The operators which are printed as a whole.
+const OPERATORS: &[&str] = &[
+    "<<=", ">>=", "..=", "...", "::", "->", "=>", "==", "!=", "<=", ">=", "&&", "||", "+=", "-=",
+    "*=", "/=", "%=", "^=", "&=", "|=", "<<", ">>", "..",
+];
+
+const KEYWORDS: &[&str] = &[
+    "as", "break", "const", "continue", "dyn", "else", "enum", "extern", "fn", "for", "if", "impl",
+    "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "static", "struct",
+    "trait", "type", "unsafe", "use", "where", "while",
+];
+
+/// The max width of a block which is printed in one line, such as `Self { inner }`.
+const INLINE_BLOCK_WIDTH: usize = 60;
+
+const INDENT: &str = "    ";
+
+/// Whether an identifier is reserved in the edition.
+///
+/// The generator never uses them as keywords, except `dyn`, which is only a keyword if a type
+/// follows it.
+fn is_reserved(ident: &str, edition: Edition) -> bool {
+    match ident {
+        "async" | "await" | "dyn" | "try" => edition >= Edition::Edition2018,
+        "gen" => edition >= Edition::Edition2024,
+        _ => false,
+    }
+}
+
+/// Return `None` if the code doesn't have to be changed.
+pub(super) fn restyle(code: &str, options: &Options) -> Option<String> {
+    let stream = m4::TokenStream::from_str(code)
+        .unwrap_or_else(|_| panic!("the generated Rust code could not be parsed"));
+    let mut changed = false;
+    let tokens = rewrite(stream, options.edition, &mut changed);
+    if !changed && options.allows.is_empty() && !options.format {
+        return None;
+    }
+    let allow = if options.allows.is_empty() {
+        Vec::new()
+    } else {
+        for lint in &options.allows {
+            let is_path = lint.split("::").all(|part| {
+                !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_')
+            });
+            if !is_path {
+                panic!("the lint [{}] is invalid", lint);
+            }
+        }
+        let attr = format!("#[allow({})]", options.allows.join(", "));
+        m4::TokenStream::from_str(&attr)
+            .unwrap()
+            .into_iter()
+            .collect()
+    };
+    let mut output = String::new();
+    let mut prev_is_use = false;
+    for item in split_items(tokens) {
+        let is_use =
+            matches!(item.first(), Some(m4::TokenTree::Ident(ref ident)) if ident == "use");
+        let mut tokens = allow.clone();
+        tokens.extend(item);
+        if options.format {
+            // The `use` declarations are grouped together.
+            let is_grouped = is_use && prev_is_use;
+            if !output.is_empty() && !is_grouped {
+                output.push('\n');
+            }
+            for line in split_lines(&atoms(&tokens), false) {
+                output.push_str(&format_seq(&line, 0));
+                output.push('\n');
+            }
+        } else {
+            output.push_str(&m4::TokenStream::from_iter(tokens).to_string());
+            output.push('\n');
+        }
+        prev_is_use = is_use;
+    }
+    Some(output)
+}
+
+/// Escape the reserved identifiers, and add `::` before the crates in the edition 2015.
+fn rewrite(stream: m4::TokenStream, edition: Edition, changed: &mut bool) -> Vec<m4::TokenTree> {
+    let tokens: Vec<_> = stream.into_iter().collect();
+    let mut output: Vec<m4::TokenTree> = Vec::with_capacity(tokens.len());
+    for (idx, token) in tokens.iter().enumerate() {
+        let next = tokens.get(idx + 1);
+        match token {
+            m4::TokenTree::Ident(ident) => {
+                let name = ident.to_string();
+                let is_keyword = name == "dyn"
+                    && match next {
+                        Some(m4::TokenTree::Ident(_)) => true,
+                        Some(m4::TokenTree::Punct(p)) => {
+                            p.as_char() == '\''
+                                || (p.as_char() == ':' && p.spacing() == m4::Spacing::Joint)
+                        }
+                        _ => false,
+                    };
+                if is_reserved(&name, edition) && !is_keyword {
+                    let raw = m4::TokenStream::from_str(&format!("r#{}", name)).unwrap();
+                    output.extend(raw);
+                    *changed = true;
+                    continue;
+                }
+                let is_path_root = !is_path_separator(&output[output.len().saturating_sub(2)..])
+                    && matches!(next, Some(m4::TokenTree::Punct(p)) if p.as_char() == ':' && p.spacing() == m4::Spacing::Joint);
+                if edition == Edition::Edition2015
+                    && CRATES.contains(&name.as_str())
+                    && is_path_root
+                {
+                    output.push(m4::Punct::new(':', m4::Spacing::Joint).into());
+                    output.push(m4::Punct::new(':', m4::Spacing::Alone).into());
+                    *changed = true;
+                }
+                output.push(token.clone());
+            }
+            m4::TokenTree::Group(group) => {
+                let inner = rewrite(group.stream(), edition, changed);
+                let mut new_group =
+                    m4::Group::new(group.delimiter(), m4::TokenStream::from_iter(inner));
+                new_group.set_span(group.span());
+                output.push(new_group.into());
+            }
+            _ => output.push(token.clone()),
+        }
+    }
+    output
+}
+
+fn is_path_separator(tokens: &[m4::TokenTree]) -> bool {
+    match tokens {
+        [m4::TokenTree::Punct(first), m4::TokenTree::Punct(second)] => {
+            first.as_char() == ':'
+                && first.spacing() == m4::Spacing::Joint
+                && second.as_char() == ':'
+        }
+        _ => false,
+    }
+}
+
+/// Split the tokens of a module into items, which end with `;` or `{ ... }`.
+fn split_items(tokens: Vec<m4::TokenTree>) -> Vec<Vec<m4::TokenTree>> {
+    let mut items = Vec::new();
+    let mut item = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        let is_end = match token {
+            m4::TokenTree::Punct(ref p) => p.as_char() == ';',
+            m4::TokenTree::Group(ref g) => {
+                g.delimiter() == m4::Delimiter::Brace
+                    && !matches!(tokens.peek(), Some(m4::TokenTree::Punct(p)) if p.as_char() == ';')
+            }
+            _ => false,
+        };
+        item.push(token);
+        if is_end {
+            items.push(item);
+            item = Vec::new();
+        }
+    }
+    if !item.is_empty() {
+        items.push(item);
+    }
+    items
+}
+
+#[derive(Clone)]
+enum Atom {
+    Word(String),
+    Lit(String),
+    Op(String),
+    Group(m4::Delimiter, Vec<m4::TokenTree>),
+}
+
+impl Atom {
+    fn is_op(&self, op: &str) -> bool {
+        matches!(self, Atom::Op(ref x) if x == op)
+    }
+
+    fn is_keyword(&self) -> bool {
+        matches!(self, Atom::Word(ref x) if KEYWORDS.contains(&x.as_str()))
+    }
+
+    fn is_name(&self) -> bool {
+        matches!(self, Atom::Word(_)) && !self.is_keyword()
+    }
+
+    fn is_group(&self, delimiter: m4::Delimiter) -> bool {
+        matches!(self, Atom::Group(d, _) if *d == delimiter)
+    }
+}
+
+/// Merge the punctuations into operators.
+fn atoms(tokens: &[m4::TokenTree]) -> Vec<Atom> {
+    let mut atoms = Vec::new();
+    let mut idx = 0;
+    while idx < tokens.len() {
+        let atom = match tokens[idx] {
+            m4::TokenTree::Ident(ref ident) => Atom::Word(ident.to_string()),
+            m4::TokenTree::Literal(ref lit) => Atom::Lit(lit.to_string()),
+            m4::TokenTree::Group(ref group) => {
+                Atom::Group(group.delimiter(), group.stream().into_iter().collect())
+            }
+            m4::TokenTree::Punct(_) => {
+                // The joint punctuations, such as `::`.
+                let mut joint = String::new();
+                for token in &tokens[idx..] {
+                    match token {
+                        m4::TokenTree::Punct(p) => {
+                            joint.push(p.as_char());
+                            if p.spacing() == m4::Spacing::Alone {
+                                break;
+                            }
+                        }
+                        _ => break,
+                    }
+                }
+                let op = OPERATORS
+                    .iter()
+                    .find(|op| joint.starts_with(*op))
+                    .map(|op| op.to_string())
+                    .unwrap_or_else(|| joint[..1].to_owned());
+                idx += op.len() - 1;
+                Atom::Op(op)
+            }
+        };
+        atoms.push(atom);
+        idx += 1;
+    }
+    atoms
+}
+
+/// Split the atoms of a block into lines, which end with `;`, `{ ... }` or an attribute.
+fn split_lines(atoms: &[Atom], split_commas: bool) -> Vec<Vec<Atom>> {
+    let mut lines = Vec::new();
+    let mut line = Vec::new();
+    for (idx, atom) in atoms.iter().enumerate() {
+        let next = atoms.get(idx + 1);
+        let is_end = match atom {
+            Atom::Op(op) => op == ";" || (split_commas && op == ","),
+            Atom::Group(m4::Delimiter::Brace, _) => match next {
+                Some(Atom::Op(op)) => ![";", ",", ".", "?"].contains(&op.as_str()),
+                Some(Atom::Word(word)) => word != "else" && word != "as",
+                _ => true,
+            },
+            Atom::Group(m4::Delimiter::Bracket, _) => idx > 0 && atoms[idx - 1].is_op("#"),
+            _ => false,
+        };
+        line.push(atom.clone());
+        if is_end {
+            lines.push(line);
+            line = Vec::new();
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Plain,
+    Unary,
+    GenericOpen,
+    GenericClose,
+    /// The end of the generics of an `impl`, which is followed by a type.
+    ImplGenericClose,
+    ClosureOpen,
+    ClosureClose,
+    /// The name of a lifetime, such as `'r`.
+    Lifetime,
+    /// The restriction of `pub`, such as `(crate)`.
+    Visibility,
+}
+
+fn is_value(atom: &Atom, role: Role) -> bool {
+    match atom {
+        Atom::Word(word) => !KEYWORDS.contains(&word.as_str()) || word == "self",
+        Atom::Lit(_) | Atom::Group(..) => true,
+        Atom::Op(op) => {
+            op == "?"
+                || role == Role::GenericClose
+                || role == Role::ImplGenericClose
+                || role == Role::ClosureClose
+        }
+    }
+}
+
+fn is_generic_open(prev: Option<(&Atom, Role)>, prev_prev: Option<&Atom>) -> bool {
+    match prev {
+        None => true,
+        Some((atom, role)) if !is_value(atom, role) => true,
+        Some((Atom::Word(word), _)) => {
+            let is_type = word.starts_with(|c: char| c.is_ascii_uppercase())
+                && word.chars().any(|c| c.is_ascii_lowercase());
+            let is_declared = matches!(
+                prev_prev,
+                Some(Atom::Word(ref x)) if ["fn", "struct", "enum", "trait", "type"].contains(&x.as_str())
+            );
+            word == "impl" || is_type || is_declared
+        }
+        _ => false,
+    }
+}
+
+fn needs_space(prev: &Atom, prev_role: Role, cur: &Atom, cur_role: Role) -> bool {
+    if prev.is_op("'")
+        || prev_role == Role::Unary
+        || prev_role == Role::GenericOpen
+        || prev_role == Role::ClosureOpen
+        || ["#", ".", "::", "!", "..", "..="]
+            .iter()
+            .any(|op| prev.is_op(op))
+    {
+        return false;
+    }
+    if prev_role == Role::ImplGenericClose || prev_role == Role::Visibility {
+        return true;
+    }
+    // Calls, indexes and paths follow names, but not lifetimes.
+    let is_callee = (prev.is_name() && prev_role != Role::Lifetime)
+        || matches!(prev, Atom::Group(..) | Atom::Lit(_))
+        || prev_role == Role::GenericClose;
+    match cur {
+        Atom::Op(op) => {
+            if [",", ";", ".", "?", ":", "..", "..="].contains(&op.as_str())
+                || cur_role == Role::GenericOpen
+                || cur_role == Role::GenericClose
+                || cur_role == Role::ImplGenericClose
+                || cur_role == Role::ClosureClose
+            {
+                false
+            } else if op == "::" || op == "!" {
+                !is_callee
+            } else {
+                true
+            }
+        }
+        Atom::Group(m4::Delimiter::Parenthesis, _) => !is_callee && cur_role != Role::Visibility,
+        Atom::Group(m4::Delimiter::Bracket, _) => !is_callee,
+        _ => true,
+    }
+}
+
+/// Print the atoms in one line, except the blocks in them.
+fn format_seq(seq: &[Atom], indent: usize) -> String {
+    let mut output = String::new();
+    let mut angles = 0usize;
+    // The depth of the angles when the generics of an `impl` end.
+    let mut impl_angles = None;
+    let mut closure = false;
+    let mut prev: Option<(&Atom, Role)> = None;
+    let mut prev_prev: Option<&Atom> = None;
+    for atom in seq {
+        let role = match atom {
+            Atom::Op(op) if op == "<" && is_generic_open(prev, prev_prev) => {
+                if prev.is_some_and(|(a, _)| matches!(a, Atom::Word(ref x) if x == "impl")) {
+                    impl_angles = Some(angles);
+                }
+                angles += 1;
+                Role::GenericOpen
+            }
+            Atom::Op(op) if (op == ">" || op == ">>") && angles > 0 => {
+                angles = angles.saturating_sub(op.len());
+                if impl_angles == Some(angles) {
+                    impl_angles = None;
+                    Role::ImplGenericClose
+                } else {
+                    Role::GenericClose
+                }
+            }
+            Atom::Word(_) if prev.is_some_and(|(a, _)| a.is_op("'")) => Role::Lifetime,
+            Atom::Group(m4::Delimiter::Parenthesis, _)
+                if prev.is_some_and(|(a, _)| matches!(a, Atom::Word(ref x) if x == "pub")) =>
+            {
+                Role::Visibility
+            }
+            Atom::Op(op) if op == "|" && closure => {
+                closure = false;
+                Role::ClosureClose
+            }
+            Atom::Op(op) if op == "|" && !prev.is_some_and(|(a, r)| is_value(a, r)) => {
+                closure = true;
+                Role::ClosureOpen
+            }
+            Atom::Op(op)
+                if ["&", "*", "-", "!", "&&"].contains(&op.as_str())
+                    && !prev.is_some_and(|(a, r)| is_value(a, r))
+                    && !prev.is_some_and(|(a, _)| a.is_name() || a.is_op("::")) =>
+            {
+                Role::Unary
+            }
+            _ => Role::Plain,
+        };
+        if let Some((prev_atom, prev_role)) = prev {
+            if needs_space(prev_atom, prev_role, atom, role) {
+                output.push(' ');
+            }
+        }
+        match atom {
+            Atom::Word(x) | Atom::Lit(x) | Atom::Op(x) => output.push_str(x),
+            Atom::Group(m4::Delimiter::Brace, tokens) => {
+                output.push_str(&format_block(tokens, indent));
+            }
+            Atom::Group(delimiter, tokens) => {
+                let (open, close) = match delimiter {
+                    m4::Delimiter::Parenthesis => ("(", ")"),
+                    m4::Delimiter::Bracket => ("[", "]"),
+                    _ => ("", ""),
+                };
+                output.push_str(open);
+                output.push_str(&format_seq(&atoms(tokens), indent));
+                output.push_str(close);
+            }
+        }
+        prev_prev = prev.map(|(atom, _)| atom);
+        prev = Some((atom, role));
+    }
+    output
+}
+
+fn format_block(tokens: &[m4::TokenTree], indent: usize) -> String {
+    let atoms = atoms(tokens);
+    if atoms.is_empty() {
+        return "{}".to_owned();
+    }
+    let is_statements = atoms.iter().any(|atom| atom.is_op(";"));
+    let is_simple = !is_statements
+        && !atoms
+            .iter()
+            .any(|atom| atom.is_group(m4::Delimiter::Brace) || atom.is_op("#"));
+    if is_simple {
+        let line = format_seq(&atoms, indent);
+        if line.len() <= INLINE_BLOCK_WIDTH {
+            return format!("{{ {} }}", line);
+        }
+    }
+    let mut output = String::from("{\n");
+    for line in split_lines(&atoms, !is_statements) {
+        output.push_str(&INDENT.repeat(indent + 1));
+        output.push_str(&format_seq(&line, indent + 1));
+        output.push('\n');
+    }
+    output.push_str(&INDENT.repeat(indent));
+    output.push('}');
+    output
+}
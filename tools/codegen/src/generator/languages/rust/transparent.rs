@@ -0,0 +1,81 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{entity_name, reader_name, slice_name};
+use crate::ast::verified::HasName;
+
+pub(super) trait GenTransparent {
+    fn gen_transparent(&self, vis: &m4::TokenStream) -> m4::TokenStream;
+}
+
+impl<T> GenTransparent for T
+where
+    T: HasName,
+{
+    fn gen_transparent(&self, vis: &m4::TokenStream) -> m4::TokenStream {
+        let slice = slice_name(self.name());
+        let reader = reader_name(self.name());
+        let entity = entity_name(self.name());
+        quote!(
+            #[repr(transparent)]
+            #vis struct #slice([u8]);
+
+            impl ::core::fmt::Debug for #slice {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                    ::core::fmt::Debug::fmt(&self.as_reader(), f)
+                }
+            }
+
+            impl ::core::fmt::Display for #slice {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                    ::core::fmt::Display::fmt(&self.as_reader(), f)
+                }
+            }
+
+            impl #slice {
+                pub fn from_slice(slice: &[u8]) -> molecule::error::VerificationResult<&Self> {
+                    #reader::from_slice(slice).map(::core::convert::Into::into)
+                }
+                pub fn from_compatible_slice(
+                    slice: &[u8],
+                ) -> molecule::error::VerificationResult<&Self> {
+                    #reader::from_compatible_slice(slice).map(::core::convert::Into::into)
+                }
+                /// # Safety
+                ///
+                /// The slice should be verified as the type.
+                pub unsafe fn from_slice_unchecked(slice: &[u8]) -> &Self {
+                    &*(slice as *const [u8] as *const Self)
+                }
+                pub fn as_slice(&self) -> &[u8] {
+                    &self.0
+                }
+                pub fn as_reader(&self) -> #reader<'_> {
+                    #reader::new_unchecked(&self.0)
+                }
+                pub fn to_entity(&self) -> #entity {
+                    self.as_reader().to_entity()
+                }
+            }
+
+            impl<'r> ::core::convert::From<#reader<'r>> for &'r #slice {
+                fn from(reader: #reader<'r>) -> Self {
+                    unsafe { #slice::from_slice_unchecked(reader.as_slice()) }
+                }
+            }
+
+            impl<'r> ::core::convert::TryFrom<&'r [u8]> for &'r #slice {
+                type Error = molecule::error::VerificationError;
+                fn try_from(slice: &'r [u8]) -> Result<Self, Self::Error> {
+                    #slice::from_slice(slice)
+                }
+            }
+
+            impl ::core::convert::AsRef<[u8]> for #slice {
+                fn as_ref(&self) -> &[u8] {
+                    &self.0
+                }
+            }
+        )
+    }
+}
@@ -0,0 +1,96 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{builder_name, entity_name, field_name, ident_name, typed_builder_name};
+use crate::ast::verified::{self as ast, HasName};
+
+pub(super) trait GenTypestate {
+    fn gen_typestate(&self, vis: &m4::TokenStream) -> m4::TokenStream;
+}
+
+impl GenTypestate for ast::Table {
+    fn gen_typestate(&self, vis: &m4::TokenStream) -> m4::TokenStream {
+        let entity = entity_name(self.name());
+        let builder = builder_name(self.name());
+        let typed_builder = typed_builder_name(self.name());
+        // The markers are numbered, since the names of the fields could shadow the types.
+        let markers = (0..self.inner.len())
+            .map(|index| ident_name("F", &index.to_string()))
+            .collect::<Vec<_>>();
+        let unset = markers
+            .iter()
+            .map(|_| quote!(molecule::typestate::Unset))
+            .collect::<Vec<_>>();
+        let set = markers
+            .iter()
+            .map(|_| quote!(molecule::typestate::Set))
+            .collect::<Vec<_>>();
+        let setters = self
+            .inner
+            .iter()
+            .enumerate()
+            .map(|(index, f)| {
+                let field_name = field_name(&f.name);
+                let field_type = entity_name(f.typ.name());
+                let others = markers
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != index)
+                    .map(|(_, marker)| marker);
+                let from = markers.iter().enumerate().map(|(i, marker)| {
+                    if i == index {
+                        quote!(molecule::typestate::Unset)
+                    } else {
+                        quote!(#marker)
+                    }
+                });
+                let to = markers.iter().enumerate().map(|(i, marker)| {
+                    if i == index {
+                        quote!(molecule::typestate::Set)
+                    } else {
+                        quote!(#marker)
+                    }
+                });
+                quote!(
+                    impl<#( #others ),*> #typed_builder<#( #from ),*> {
+                        pub fn #field_name(self, v: #field_type) -> #typed_builder<#( #to ),*> {
+                            #typed_builder {
+                                inner: self.inner.#field_name(v),
+                                marker: ::core::marker::PhantomData,
+                            }
+                        }
+                    }
+                )
+            })
+            .collect::<Vec<_>>();
+        let phantom = markers.clone();
+        quote!(
+            /// The builder of which `build` is only available after all fields are set.
+            #[derive(Debug)]
+            #vis struct #typed_builder<#( #markers ),*> {
+                inner: #builder,
+                marker: ::core::marker::PhantomData<(#( #phantom, )*)>,
+            }
+
+            impl #entity {
+                pub fn new_typed_builder() -> #typed_builder<#( #unset ),*> {
+                    #typed_builder {
+                        inner: ::core::default::Default::default(),
+                        marker: ::core::marker::PhantomData,
+                    }
+                }
+            }
+
+            #( #setters )*
+
+            impl #typed_builder<#( #set ),*> {
+                pub fn build(self) -> #entity {
+                    molecule::prelude::Builder::build(&self.inner)
+                }
+                pub fn into_builder(self) -> #builder {
+                    self.inner
+                }
+            }
+        )
+    }
+}
@@ -0,0 +1,73 @@
+use proc_macro2 as m4;
+use quote::quote;
+
+use super::utilities::{entity_name, func_name, ident_new, reader_name};
+use crate::ast::verified::{self as ast, HasName};
+
+/// The well-known names of the arrays of bytes which are unsigned integers.
+const UINT_NAMES: &[(&str, usize, &str)] = &[
+    ("Uint16", 2, "u16"),
+    ("Uint32", 4, "u32"),
+    ("Uint64", 8, "u64"),
+    ("Uint128", 16, "u128"),
+];
+
+pub(in super::super) trait GenUint {
+    fn gen_uint(&self) -> m4::TokenStream;
+}
+
+impl GenUint for ast::Array {
+    fn gen_uint(&self) -> m4::TokenStream {
+        let uint = UINT_NAMES.iter().find(|(name, size, _)| {
+            *name == self.name() && self.typ.is_atom() && *size == self.item_count
+        });
+        let primitive = if let Some((_, _, primitive)) = uint {
+            ident_new(primitive)
+        } else {
+            return quote!();
+        };
+        let entity = entity_name(self.name());
+        let reader = reader_name(self.name());
+        let from_le = func_name(&format!("from_{}", primitive));
+        let from_be = func_name(&format!("from_{}_be", primitive));
+        let to_le = func_name(&format!("to_{}", primitive));
+        let to_be = func_name(&format!("to_{}_be", primitive));
+        quote!(
+            impl #entity {
+                pub fn #from_le(value: #primitive) -> Self {
+                    Self::new_unchecked(molecule::uint::Uint::to_le_vec(value).into())
+                }
+                pub fn #from_be(value: #primitive) -> Self {
+                    Self::new_unchecked(molecule::uint::Uint::to_be_vec(value).into())
+                }
+                pub fn #to_le(&self) -> #primitive {
+                    self.as_reader().#to_le()
+                }
+                pub fn #to_be(&self) -> #primitive {
+                    self.as_reader().#to_be()
+                }
+            }
+
+            impl<'r> #reader<'r> {
+                pub fn #to_le(&self) -> #primitive {
+                    molecule::uint::Uint::from_le_slice(self.as_slice())
+                }
+                pub fn #to_be(&self) -> #primitive {
+                    molecule::uint::Uint::from_be_slice(self.as_slice())
+                }
+            }
+
+            impl ::core::convert::From<#primitive> for #entity {
+                fn from(value: #primitive) -> Self {
+                    Self::#from_le(value)
+                }
+            }
+
+            impl ::core::convert::From<#entity> for #primitive {
+                fn from(value: #entity) -> Self {
+                    value.#to_le()
+                }
+            }
+        )
+    }
+}
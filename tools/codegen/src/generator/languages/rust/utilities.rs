@@ -1,5 +1,8 @@
 use case::CaseExt;
 use proc_macro2 as m4;
+use quote::quote;
+
+use crate::Visibility;
 
 pub(super) fn usize_lit(num: usize) -> m4::Literal {
     m4::Literal::usize_unsuffixed(num)
@@ -9,6 +12,38 @@ pub(super) fn ident_new(ident: &str) -> m4::Ident {
     m4::Ident::new(ident, m4::Span::call_site())
 }
 
+/// Build a path, such as `::crate_name::module`, or panic if it's invalid.
+pub(super) fn path_new(path: &str) -> m4::TokenStream {
+    let (mut stream, relative) = if let Some(relative) = path.strip_prefix("::") {
+        (quote!(::), relative)
+    } else {
+        (quote!(), path)
+    };
+    for (index, part) in relative.split("::").enumerate() {
+        if part.is_empty() {
+            panic!("the path [{}] is invalid", path);
+        }
+        let part = ident_new(part);
+        if index == 0 {
+            stream = quote!(#stream #part);
+        } else {
+            stream = quote!(#stream :: #part);
+        }
+    }
+    stream
+}
+
+pub(super) fn visibility_new(visibility: &Visibility) -> m4::TokenStream {
+    match visibility {
+        Visibility::Public => quote!(pub),
+        Visibility::Crate => quote!(pub(crate)),
+        Visibility::Restricted(path) => {
+            let path = path_new(path);
+            quote!(pub(in #path))
+        }
+    }
+}
+
 pub(super) fn ident_name(name: &str, suffix: &str) -> m4::Ident {
     let span = m4::Span::call_site();
     m4::Ident::new(&format!("{}{}", name, suffix).to_camel(), span)
@@ -22,6 +57,10 @@ pub(super) fn reader_name(name: &str) -> m4::Ident {
     ident_name(name, "Reader")
 }
 
+pub(super) fn slice_name(name: &str) -> m4::Ident {
+    ident_name(name, "Slice")
+}
+
 pub(super) fn entity_union_name(name: &str) -> m4::Ident {
     ident_name(name, "Union")
 }
@@ -38,6 +77,10 @@ pub(super) fn builder_name(name: &str) -> m4::Ident {
     ident_name(name, "Builder")
 }
 
+pub(super) fn typed_builder_name(name: &str) -> m4::Ident {
+    ident_name(name, "TypedBuilder")
+}
+
 pub(super) fn field_name(name: &str) -> m4::Ident {
     let span = m4::Span::call_site();
     m4::Ident::new(&name.to_snake(), span)
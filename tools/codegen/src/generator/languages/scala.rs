@@ -0,0 +1,578 @@
+//! A Scala object of the types, which verify, read and build the bytes on the JVM, for Spark jobs
+//! as well, with Scala 2.12 or later.
+//!
+//! There is no Java backend to share, so the object has its own runtime `Mol` over
+//! `Array[Byte]`, and the types are views of `Mol.Slice` which don't copy the bytes. The
+//! companion object of each type has `verify`, `fromBytes`, where the strict mode is the default,
+//! and `apply` to build it. An option is an alias of `Option[T]`, and a union is a sealed trait,
+//! whose case classes are named after the item types. The imported types are generated into the
+//! same object, so the file doesn't depend on the files of the imported schemas.
+
+use std::io;
+
+use case::CaseExt;
+
+use super::model::{Decl, Model};
+use crate::{
+    ast::verified::{self as ast, HasName},
+    generator::{metadata::Metadata, Options},
+    VERSION,
+};
+
+pub(crate) struct Generator;
+
+// The helpers which are shared by the types.
+const RUNTIME: &str = r#"  object Mol {
+    sealed trait VerifyError
+    object VerifyError {
+      case object TotalSize extends VerifyError
+      case object Header extends VerifyError
+      case object Offset extends VerifyError
+      case object FieldCount extends VerifyError
+      case object UnknownItem extends VerifyError
+    }
+
+    /** A view of bytes, which doesn't copy them. */
+    final class Slice(val buf: Array[Byte], val pos: Int, val len: Int) {
+      def apply(i: Int): Byte = buf(pos + i)
+      def sub(start: Int, size: Int): Slice = new Slice(buf, pos + start, size)
+      def toArray: Array[Byte] = java.util.Arrays.copyOfRange(buf, pos, pos + len)
+    }
+
+    object Slice {
+      def apply(bytes: Array[Byte]): Slice = new Slice(bytes, 0, bytes.length)
+    }
+
+    def check(ok: Boolean, err: VerifyError): Either[VerifyError, Unit] =
+      if (ok) Right(()) else Left(err)
+
+    def readNumber(s: Slice, start: Int, size: Int): Long =
+      (size - 1 to 0 by -1).foldLeft(0L)((acc, i) => (acc << 8) | (s(start + i) & 0xffL))
+
+    def packNumber(size: Int, value: Long): Array[Byte] =
+      Array.tabulate(size)(i => (value >>> (8 * i)).toByte)
+
+    def verifyByte(s: Slice): Either[VerifyError, Byte] =
+      if (s.len == 1) Right(s(0)) else Left(VerifyError.TotalSize)
+
+    def verifyAll(count: Int)(f: Int => Either[VerifyError, Any]): Either[VerifyError, Unit] =
+      (0 until count).iterator.map(f).collectFirst { case Left(err) => err }.toLeft(())
+
+    /** Verifies the header of a dynvec or a table, and returns the count of the items. */
+    def verifyDynamic(s: Slice): Either[VerifyError, Int] =
+      if (s.len < 4) Left(VerifyError.Header)
+      else {
+        val total = readNumber(s, 0, 4)
+        if (total != s.len) Left(VerifyError.TotalSize)
+        else if (total == 4) Right(0)
+        else if (total < 8) Left(VerifyError.Header)
+        else {
+          val first = readNumber(s, 4, 4)
+          if (first % 4 != 0 || first < 8 || first > total) Left(VerifyError.Offset)
+          else {
+            val count = (first / 4 - 1).toInt
+            val offsets = (0 until count).map(i => readNumber(s, 4 + 4 * i, 4)) :+ total
+            if (offsets.zip(offsets.tail).forall { case (a, b) => a <= b }) Right(count)
+            else Left(VerifyError.Offset)
+          }
+        }
+      }
+
+    def dynamicLength(s: Slice): Int =
+      if (s.len == 4) 0 else (readNumber(s, 4, 4) / 4 - 1).toInt
+
+    def dynamicAt(s: Slice, i: Int): Slice = {
+      val start = readNumber(s, 4 + 4 * i, 4).toInt
+      val end = if (i + 1 == dynamicLength(s)) s.len else readNumber(s, 8 + 4 * i, 4).toInt
+      s.sub(start, end - start)
+    }
+
+    def packDynamic(items: Seq[Array[Byte]]): Array[Byte] = {
+      val starts = items.scanLeft(4 * (items.length + 1))(_ + _.length)
+      val offsets = starts.init.map(start => packNumber(4, start.toLong))
+      Array.concat(Seq(packNumber(4, starts.last.toLong)) ++ offsets ++ items: _*)
+    }
+  }
+"#;
+
+const KEYWORDS: &[&str] = &[
+    "abstract",
+    "case",
+    "catch",
+    "class",
+    "def",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "final",
+    "finally",
+    "for",
+    "forSome",
+    "given",
+    "if",
+    "implicit",
+    "import",
+    "lazy",
+    "macro",
+    "match",
+    "new",
+    "null",
+    "object",
+    "override",
+    "package",
+    "private",
+    "protected",
+    "return",
+    "sealed",
+    "super",
+    "then",
+    "this",
+    "throw",
+    "trait",
+    "try",
+    "true",
+    "type",
+    "val",
+    "var",
+    "while",
+    "with",
+    "yield",
+];
+
+// The members of the views and of `AnyRef`, which the getters can't be named after.
+const MEMBERS: &[&str] = &[
+    "clone",
+    "eq",
+    "equals",
+    "finalize",
+    "getClass",
+    "hashCode",
+    "ne",
+    "notify",
+    "notifyAll",
+    "slice",
+    "synchronized",
+    "toBytes",
+    "toString",
+    "wait",
+];
+
+fn ident(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("`{}`", name)
+    } else if MEMBERS.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_owned()
+    }
+}
+
+/// The name of the type; the case classes of a union shadow the types in its companion object, so
+/// the types are qualified there.
+fn type_name(typ: &ast::TopDecl, namespace: Option<&str>) -> String {
+    match (typ.is_atom(), namespace) {
+        (true, Some(_)) => "scala.Byte".to_owned(),
+        (true, None) => "Byte".to_owned(),
+        (false, Some(namespace)) => format!("{}.{}", namespace, typ.name().to_camel()),
+        (false, None) => typ.name().to_camel(),
+    }
+}
+
+fn verify_expr(typ: &ast::TopDecl, slice: &str, namespace: Option<&str>) -> String {
+    if typ.is_atom() {
+        format!("Mol.verifyByte({})", slice)
+    } else {
+        format!(
+            "{}.verify({}, compatible)",
+            type_name(typ, namespace),
+            slice
+        )
+    }
+}
+
+/// The value of the type in a slice, which has been verified.
+fn read_expr(typ: &ast::TopDecl, slice: &str, namespace: Option<&str>) -> String {
+    match typ {
+        _ if typ.is_atom() => format!("{}(0)", slice),
+        ast::TopDecl::Option_(_) | ast::TopDecl::Union(_) => {
+            format!("{}.read({})", type_name(typ, namespace), slice)
+        }
+        _ => format!("new {}({})", type_name(typ, namespace), slice),
+    }
+}
+
+fn bytes_expr(typ: &ast::TopDecl, value: &str, namespace: Option<&str>) -> String {
+    match typ {
+        _ if typ.is_atom() => format!("Array({})", value),
+        ast::TopDecl::Option_(_) => format!("{}.toBytes({})", type_name(typ, namespace), value),
+        _ => format!("{}.toBytes", value),
+    }
+}
+
+impl Generator {
+    fn write_class<W: io::Write>(
+        writer: &mut W,
+        namespace: &str,
+        kind: &str,
+        decl: &ast::TopDecl,
+        members: &[String],
+    ) -> io::Result<()> {
+        let name = type_name(decl, None);
+        writeln!(writer)?;
+        writeln!(writer, "  /** The {} `{}`. */", kind, decl.name())?;
+        writeln!(
+            writer,
+            "  final class {} private[{}] (val slice: Mol.Slice) {{",
+            name, namespace
+        )?;
+        for member in members {
+            writeln!(writer, "    {}", member)?;
+        }
+        writeln!(writer, "    def toBytes: Array[Byte] = slice.toArray\n  }}")
+    }
+
+    /// The companion object, with `verify` in the given lines and `fromBytes`; `Byte` is shadowed
+    /// in the companion object of a union, so the type of bytes is given.
+    fn write_object<W: io::Write>(
+        writer: &mut W,
+        name: &str,
+        byte: &str,
+        verify: &[String],
+        members: &[String],
+    ) -> io::Result<()> {
+        writeln!(writer)?;
+        writeln!(writer, "  object {} {{", name)?;
+        writeln!(
+            writer,
+            "    def verify(s: Mol.Slice, compatible: Boolean): Either[Mol.VerifyError, {}] =",
+            name
+        )?;
+        for line in verify {
+            writeln!(writer, "      {}", line)?;
+        }
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "    def fromBytes(bytes: Array[{}], compatible: Boolean = false): Either[Mol.VerifyError, {}] =",
+            byte, name
+        )?;
+        writeln!(writer, "      verify(Mol.Slice(bytes), compatible)")?;
+        for member in members {
+            writeln!(writer)?;
+            writeln!(writer, "    {}", member)?;
+        }
+        writeln!(writer, "  }}")
+    }
+
+    /// The verification of a type which is built in a for comprehension.
+    fn for_verify(steps: &[String], value: &str) -> Vec<String> {
+        let mut lines = vec!["for {".to_owned()];
+        lines.extend(steps.iter().map(|step| format!("  {}", step)));
+        lines.push(format!("}} yield {}", value));
+        lines
+    }
+
+    /// The members of the view of a vector.
+    fn vector_members(item: &ast::TopDecl, length: &str, at: &str) -> Vec<String> {
+        let item_name = type_name(item, None);
+        vec![
+            format!("def length: Int = {}", length),
+            format!(
+                "def apply(i: Int): {} = {}",
+                item_name,
+                read_expr(item, at, None)
+            ),
+            format!(
+                "def items: IndexedSeq[{}] = (0 until length).map(apply)",
+                item_name
+            ),
+        ]
+    }
+
+    /// The getters and the builder of a struct or a table.
+    fn write_fields<W: io::Write>(
+        writer: &mut W,
+        namespace: &str,
+        decl: &ast::TopDecl,
+        fields: &[ast::FieldDecl],
+        slices: &[String],
+        verify: &[String],
+    ) -> io::Result<()> {
+        let name = type_name(decl, None);
+        let (kind, pack) = if let ast::TopDecl::Struct(_) = decl {
+            ("struct", "Array.concat({})")
+        } else {
+            ("table", "Mol.packDynamic(Seq({}))")
+        };
+        let getters = fields
+            .iter()
+            .zip(slices)
+            .map(|(f, slice)| {
+                format!(
+                    "def {}: {} = {}",
+                    ident(&f.name),
+                    type_name(&f.typ, None),
+                    read_expr(&f.typ, slice, None)
+                )
+            })
+            .collect::<Vec<_>>();
+        Self::write_class(writer, namespace, kind, decl, &getters)?;
+        let params = fields
+            .iter()
+            .map(|f| format!("{}: {}", ident(&f.name), type_name(&f.typ, None)))
+            .collect::<Vec<_>>();
+        let bytes = fields
+            .iter()
+            .map(|f| bytes_expr(&f.typ, &ident(&f.name), None))
+            .collect::<Vec<_>>();
+        let apply = format!(
+            "def apply({}): {} =\n      new {}(Mol.Slice({}))",
+            params.join(", "),
+            name,
+            name,
+            pack.replace("{}", &bytes.join(", "))
+        );
+        Self::write_object(writer, &name, "Byte", verify, &[apply])
+    }
+
+    fn generate_decl<W: io::Write>(writer: &mut W, namespace: &str, decl: &Decl) -> io::Result<()> {
+        let (decl, fields) = (decl.decl, &decl.fields);
+        let name = type_name(decl, None);
+        let value = format!("new {}(s)", name);
+        match decl {
+            ast::TopDecl::Atom(_) => unreachable!(),
+            ast::TopDecl::Option_(ref i) => {
+                let inner = type_name(&i.typ, None);
+                writeln!(writer)?;
+                writeln!(writer, "  /** The option `{}`. */", i.name)?;
+                writeln!(writer, "  type {} = Option[{}]", name, inner)?;
+                let verify = vec![
+                    "if (s.len == 0) Right(None)".to_owned(),
+                    format!("else {}.map(Some(_))", verify_expr(&i.typ, "s", None)),
+                ];
+                let members = vec![
+                    format!(
+                        "def read(s: Mol.Slice): {} =\n      if (s.len == 0) None else Some({})",
+                        name,
+                        read_expr(&i.typ, "s", None)
+                    ),
+                    format!(
+                        "def toBytes(value: {}): Array[Byte] =\n      value.fold(Array.emptyByteArray)(v => {})",
+                        name,
+                        bytes_expr(&i.typ, "v", None)
+                    ),
+                ];
+                Self::write_object(writer, &name, "Byte", &verify, &members)?;
+            }
+            ast::TopDecl::Union(ref i) => {
+                let ns = Some(namespace);
+                let cases = i
+                    .inner
+                    .iter()
+                    .map(|item| item.typ.name().to_camel())
+                    .collect::<Vec<_>>();
+                writeln!(writer)?;
+                writeln!(writer, "  /** The union `{}`. */", i.name)?;
+                writeln!(writer, "  sealed trait {} {{", name)?;
+                writeln!(writer, "    def itemId: Long")?;
+                writeln!(writer, "    protected def itemBytes: Array[Byte]")?;
+                writeln!(
+                    writer,
+                    "    def toBytes: Array[Byte] = Array.concat(Mol.packNumber(4, itemId), itemBytes)"
+                )?;
+                writeln!(writer, "  }}")?;
+                let mut verify = vec![
+                    "if (s.len < 4) Left(Mol.VerifyError.Header)".to_owned(),
+                    "else {".to_owned(),
+                    "  val body = s.sub(4, s.len - 4)".to_owned(),
+                    "  Mol.readNumber(s, 0, 4) match {".to_owned(),
+                ];
+                let mut read = format!(
+                    "def read(s: Mol.Slice): {} = {{\n      val body = s.sub(4, s.len - 4)\n      Mol.readNumber(s, 0, 4) match {{\n",
+                    name
+                );
+                let mut members = Vec::new();
+                for (idx, (case, item)) in cases.iter().zip(&i.inner).enumerate() {
+                    verify.push(format!(
+                        "    case {}L => {}.map({}(_))",
+                        idx,
+                        verify_expr(&item.typ, "body", ns),
+                        case
+                    ));
+                    read.push_str(&format!(
+                        "        case {}L => {}({})\n",
+                        idx,
+                        case,
+                        read_expr(&item.typ, "body", ns)
+                    ));
+                    members.push(format!(
+                        "final case class {}(value: {}) extends {} {{\n      def itemId: Long = {}L\n      protected def itemBytes: Array[scala.Byte] = {}\n    }}",
+                        case,
+                        type_name(&item.typ, ns),
+                        name,
+                        idx,
+                        bytes_expr(&item.typ, "value", ns)
+                    ));
+                }
+                verify.push("    case _ => Left(Mol.VerifyError.UnknownItem)".to_owned());
+                verify.push("  }".to_owned());
+                verify.push("}".to_owned());
+                read.push_str(&format!(
+                    "        case id => throw new IllegalStateException(s\"unknown item $id of {}\")\n      }}\n    }}",
+                    name
+                ));
+                members.push(read);
+                Self::write_object(writer, &name, "scala.Byte", &verify, &members)?;
+            }
+            ast::TopDecl::Array(ref i) => {
+                let mut members = Self::vector_members(
+                    &i.typ,
+                    &i.item_count.to_string(),
+                    &format!("slice.sub(i * {0}, {0})", i.item_size),
+                );
+                members[0] = format!("def length: Int = {}.Length", name);
+                Self::write_class(writer, namespace, "array", decl, &members)?;
+                let verify = vec![format!(
+                    "if (s.len == Size) Right({}) else Left(Mol.VerifyError.TotalSize)",
+                    value
+                )];
+                let item = type_name(&i.typ, None);
+                let members = vec![
+                    format!("val Size = {}", i.total_size()),
+                    format!("val Length = {}", i.item_count),
+                    format!(
+                        "def apply(items: Seq[{}]): {} = {{\n      require(items.length == Length, s\"{} has $Length items\")\n      new {}(Mol.Slice(Array.concat(items.map(v => {}): _*)))\n    }}",
+                        item,
+                        name,
+                        name,
+                        name,
+                        bytes_expr(&i.typ, "v", None)
+                    ),
+                ];
+                Self::write_object(writer, &name, "Byte", &verify, &members)?;
+            }
+            ast::TopDecl::Struct(ref i) => {
+                let slices = fields
+                    .iter()
+                    .map(|f| format!("slice.sub({}, {})", f.offset(), f.size()))
+                    .collect::<Vec<_>>();
+                let verify = vec![format!(
+                    "if (s.len == {}) Right({}) else Left(Mol.VerifyError.TotalSize)",
+                    i.total_size(),
+                    value
+                )];
+                Self::write_fields(writer, namespace, decl, &i.inner, &slices, &verify)?;
+            }
+            ast::TopDecl::FixVec(ref i) => {
+                let members = Self::vector_members(
+                    &i.typ,
+                    &format!("Mol.readNumber(slice, 0, {}).toInt", i.count_size),
+                    &format!(
+                        "slice.sub({} + i * {}, {})",
+                        i.count_size, i.item_size, i.item_size
+                    ),
+                );
+                Self::write_class(writer, namespace, "fixvec", decl, &members)?;
+                let verify = Self::for_verify(
+                    &[
+                        format!(
+                            "_ <- Mol.check(s.len >= {}, Mol.VerifyError.Header)",
+                            i.count_size
+                        ),
+                        format!(
+                            "_ <- Mol.check(s.len == {} + Mol.readNumber(s, 0, {}) * {}, Mol.VerifyError.TotalSize)",
+                            i.count_size, i.count_size, i.item_size
+                        ),
+                    ],
+                    &value,
+                );
+                let apply = format!(
+                    "def apply(items: Seq[{}]): {} =\n      new {}(Mol.Slice(Array.concat(Mol.packNumber({}, items.length.toLong) +: items.map(v => {}): _*)))",
+                    type_name(&i.typ, None),
+                    name,
+                    name,
+                    i.count_size,
+                    bytes_expr(&i.typ, "v", None)
+                );
+                Self::write_object(writer, &name, "Byte", &verify, &[apply])?;
+            }
+            ast::TopDecl::DynVec(ref i) => {
+                let members = Self::vector_members(
+                    &i.typ,
+                    "Mol.dynamicLength(slice)",
+                    "Mol.dynamicAt(slice, i)",
+                );
+                Self::write_class(writer, namespace, "dynvec", decl, &members)?;
+                let verify = Self::for_verify(
+                    &[
+                        "count <- Mol.verifyDynamic(s)".to_owned(),
+                        format!(
+                            "_ <- Mol.verifyAll(count)(i => {})",
+                            verify_expr(&i.typ, "Mol.dynamicAt(s, i)", None)
+                        ),
+                    ],
+                    &value,
+                );
+                let apply = format!(
+                    "def apply(items: Seq[{}]): {} =\n      new {}(Mol.Slice(Mol.packDynamic(items.map(v => {}))))",
+                    type_name(&i.typ, None),
+                    name,
+                    name,
+                    bytes_expr(&i.typ, "v", None)
+                );
+                Self::write_object(writer, &name, "Byte", &verify, &[apply])?;
+            }
+            ast::TopDecl::Table(ref i) => {
+                let count = i.inner.len();
+                let check = if count == 0 {
+                    "compatible || count == 0".to_owned()
+                } else {
+                    format!("count >= {0} && (compatible || count == {0})", count)
+                };
+                let mut steps = vec![
+                    "count <- Mol.verifyDynamic(s)".to_owned(),
+                    format!("_ <- Mol.check({}, Mol.VerifyError.FieldCount)", check),
+                ];
+                for (idx, f) in i.inner.iter().enumerate() {
+                    steps.push(format!(
+                        "_ <- {}",
+                        verify_expr(&f.typ, &format!("Mol.dynamicAt(s, {})", idx), None)
+                    ));
+                }
+                let verify = Self::for_verify(&steps, &value);
+                let slices = (0..count)
+                    .map(|idx| format!("Mol.dynamicAt(slice, {})", idx))
+                    .collect::<Vec<_>>();
+                Self::write_fields(writer, namespace, decl, &i.inner, &slices, &verify)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl super::LanguageGenerator for Generator {
+    fn generate<W: io::Write>(
+        writer: &mut W,
+        ast: &ast::Ast,
+        _options: &Options,
+    ) -> io::Result<()> {
+        let namespace = ast.namespace.to_camel();
+        writeln!(writer, "// Generated by Molecule {}", VERSION)?;
+        writeln!(writer, "// {}", Metadata::new(ast).comment())?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "/** The types of the molecule schema `{}`. */",
+            ast.namespace
+        )?;
+        writeln!(writer, "object {} {{", namespace)?;
+        write!(writer, "{}", RUNTIME)?;
+        for decl in &Model::new(ast).decls {
+            Self::generate_decl(writer, &namespace, decl)?;
+        }
+        writeln!(writer, "}}")
+    }
+}
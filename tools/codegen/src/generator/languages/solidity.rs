@@ -0,0 +1,327 @@
+//! A Solidity library which verifies and reads the types from `bytes calldata`.
+//!
+//! Each type has a `verify<Type>` function, which checks the bytes in the strict mode. The
+//! getters, such as `<type><Field>`, `<type>Length` and `<type>At`, slice the calldata without
+//! copying it, and they don't check the bytes again, so they should only be called on verified
+//! bytes; an out-of-bounds slice reverts.
+//!
+//! The imported types are generated into the same library, so the file doesn't depend on the
+//! files of the imported schemas.
+
+use std::io;
+
+use case::CaseExt;
+
+use super::model::{Decl, Model};
+use crate::{
+    ast::verified::{self as ast, HasName},
+    generator::{metadata::Metadata, Options},
+    VERSION,
+};
+
+pub(crate) struct Generator;
+
+const PRAGMA: &str = "pragma solidity ^0.8.0;";
+
+// The helpers which are shared by the functions of the types.
+const RUNTIME: &str = r#"    function readNumber(bytes calldata data, uint256 offset, uint256 size)
+        internal
+        pure
+        returns (uint256 value)
+    {
+        for (uint256 i = 0; i < size; i++) {
+            value |= uint256(uint8(data[offset + i])) << (8 * i);
+        }
+    }
+
+    function verifyByte(bytes calldata data) internal pure returns (bool) {
+        return data.length == 1;
+    }
+
+    function verifyDynamic(bytes calldata data) internal pure returns (bool, uint256) {
+        if (data.length < 4) return (false, 0);
+        uint256 total = readNumber(data, 0, 4);
+        if (total != data.length) return (false, 0);
+        if (total == 4) return (true, 0);
+        if (total < 8) return (false, 0);
+        uint256 first = readNumber(data, 4, 4);
+        if (first % 4 != 0 || first < 8 || first > total) return (false, 0);
+        uint256 count = first / 4 - 1;
+        uint256 previous = first;
+        for (uint256 i = 1; i < count; i++) {
+            uint256 offset = readNumber(data, 4 + 4 * i, 4);
+            if (offset < previous || offset > total) return (false, 0);
+            previous = offset;
+        }
+        return (true, count);
+    }
+
+    function dynamicLength(bytes calldata data) internal pure returns (uint256) {
+        return data.length == 4 ? 0 : readNumber(data, 4, 4) / 4 - 1;
+    }
+
+    function dynamicAt(bytes calldata data, uint256 index) internal pure returns (bytes calldata) {
+        uint256 count = dynamicLength(data);
+        require(index < count, "molecule: index out of bounds");
+        uint256 start = readNumber(data, 4 + 4 * index, 4);
+        uint256 end = index + 1 == count ? data.length : readNumber(data, 8 + 4 * index, 4);
+        return data[start:end];
+    }
+"#;
+
+fn function_prefix(typ: &ast::TopDecl) -> String {
+    let name = typ.name().to_camel();
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+fn verify_name(typ: &ast::TopDecl) -> String {
+    if typ.is_atom() {
+        "verifyByte".to_owned()
+    } else {
+        format!("verify{}", typ.name().to_camel())
+    }
+}
+
+fn verify_call(typ: &ast::TopDecl, arg: &str) -> String {
+    format!("{}({})", verify_name(typ), arg)
+}
+
+/// The return type and the expression of a getter which reads a slice of the bytes.
+///
+/// A byte is read at the start of the slice, if the start is known.
+fn getter(typ: &ast::TopDecl, slice: &str, start: Option<&str>) -> (&'static str, String) {
+    match (typ.is_atom(), start) {
+        (true, Some(start)) => ("uint8", format!("uint8(data[{}])", start)),
+        (true, None) => ("uint8", format!("uint8({}[0])", slice)),
+        (false, _) => ("bytes calldata", slice.to_owned()),
+    }
+}
+
+impl Generator {
+    fn write_getter<W: io::Write>(
+        writer: &mut W,
+        name: &str,
+        params: &str,
+        typ: &ast::TopDecl,
+        slice: &str,
+        start: Option<&str>,
+    ) -> io::Result<()> {
+        let (returns, expr) = getter(typ, slice, start);
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "    function {}(bytes calldata data{}) internal pure returns ({}) {{",
+            name, params, returns
+        )?;
+        writeln!(writer, "        return {};", expr)?;
+        writeln!(writer, "    }}")
+    }
+
+    fn write_verify<W: io::Write>(
+        writer: &mut W,
+        decl: &ast::TopDecl,
+        body: &[String],
+    ) -> io::Result<()> {
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "    function {}(bytes calldata data) internal pure returns (bool) {{",
+            verify_name(decl)
+        )?;
+        for line in body {
+            writeln!(writer, "        {}", line)?;
+        }
+        writeln!(writer, "    }}")
+    }
+
+    fn generate_decl<W: io::Write>(writer: &mut W, decl: &Decl) -> io::Result<()> {
+        let (decl, fields) = (decl.decl, &decl.fields);
+        let prefix = function_prefix(decl);
+        match decl {
+            ast::TopDecl::Atom(_) => unreachable!(),
+            ast::TopDecl::Option_(ref i) => {
+                let body = vec![format!(
+                    "return data.length == 0 || {};",
+                    verify_call(&i.typ, "data")
+                )];
+                Self::write_verify(writer, decl, &body)?;
+                writeln!(writer)?;
+                writeln!(
+                    writer,
+                    "    function {}IsSome(bytes calldata data) internal pure returns (bool) {{",
+                    prefix
+                )?;
+                writeln!(writer, "        return data.length != 0;")?;
+                writeln!(writer, "    }}")?;
+            }
+            ast::TopDecl::Union(ref i) => {
+                let mut body = vec![
+                    "if (data.length < 4) return false;".to_owned(),
+                    "uint256 itemId = readNumber(data, 0, 4);".to_owned(),
+                ];
+                for (idx, item) in i.inner.iter().enumerate() {
+                    body.push(format!(
+                        "if (itemId == {}) return {};",
+                        idx,
+                        verify_call(&item.typ, "data[4:]")
+                    ));
+                }
+                body.push("return false;".to_owned());
+                Self::write_verify(writer, decl, &body)?;
+                writeln!(writer)?;
+                writeln!(
+                    writer,
+                    "    function {}ItemId(bytes calldata data) internal pure returns (uint256) {{",
+                    prefix
+                )?;
+                writeln!(writer, "        return readNumber(data, 0, 4);")?;
+                writeln!(writer, "    }}")?;
+                writeln!(writer)?;
+                writeln!(
+                    writer,
+                    "    function {}Item(bytes calldata data) internal pure returns (bytes calldata) {{",
+                    prefix
+                )?;
+                writeln!(writer, "        return data[4:];")?;
+                writeln!(writer, "    }}")?;
+            }
+            ast::TopDecl::Array(ref i) => {
+                let body = vec![format!("return data.length == {};", i.total_size())];
+                Self::write_verify(writer, decl, &body)?;
+                let start = format!("index * {}", i.item_size);
+                let slice = format!("data[{}:(index + 1) * {}]", start, i.item_size);
+                Self::write_getter(
+                    writer,
+                    &format!("{}At", prefix),
+                    ", uint256 index",
+                    &i.typ,
+                    &slice,
+                    Some(&start),
+                )?;
+            }
+            ast::TopDecl::Struct(ref i) => {
+                let body = vec![format!("return data.length == {};", i.total_size())];
+                Self::write_verify(writer, decl, &body)?;
+                for f in fields {
+                    let (offset, size) = (f.offset(), f.size());
+                    let start = offset.to_string();
+                    let slice = format!("data[{}:{}]", start, offset + size);
+                    let name = format!("{}{}", prefix, f.name.to_camel());
+                    Self::write_getter(writer, &name, "", f.typ, &slice, Some(&start))?;
+                }
+            }
+            ast::TopDecl::FixVec(ref i) => {
+                let count = format!("readNumber(data, 0, {})", i.count_size);
+                let body = vec![
+                    format!("if (data.length < {}) return false;", i.count_size),
+                    format!(
+                        "return data.length == {} + {} * {};",
+                        i.count_size, count, i.item_size
+                    ),
+                ];
+                Self::write_verify(writer, decl, &body)?;
+                writeln!(writer)?;
+                writeln!(
+                    writer,
+                    "    function {}Length(bytes calldata data) internal pure returns (uint256) {{",
+                    prefix
+                )?;
+                writeln!(writer, "        return {};", count)?;
+                writeln!(writer, "    }}")?;
+                let start = format!("{} + index * {}", i.count_size, i.item_size);
+                let slice = format!(
+                    "data[{}:{} + (index + 1) * {}]",
+                    start, i.count_size, i.item_size
+                );
+                Self::write_getter(
+                    writer,
+                    &format!("{}At", prefix),
+                    ", uint256 index",
+                    &i.typ,
+                    &slice,
+                    Some(&start),
+                )?;
+            }
+            ast::TopDecl::DynVec(ref i) => {
+                let body = vec![
+                    "(bool ok, uint256 count) = verifyDynamic(data);".to_owned(),
+                    "if (!ok) return false;".to_owned(),
+                    "for (uint256 i = 0; i < count; i++) {".to_owned(),
+                    format!(
+                        "    if (!{}) return false;",
+                        verify_call(&i.typ, "dynamicAt(data, i)")
+                    ),
+                    "}".to_owned(),
+                    "return true;".to_owned(),
+                ];
+                Self::write_verify(writer, decl, &body)?;
+                writeln!(writer)?;
+                writeln!(
+                    writer,
+                    "    function {}Length(bytes calldata data) internal pure returns (uint256) {{",
+                    prefix
+                )?;
+                writeln!(writer, "        return dynamicLength(data);")?;
+                writeln!(writer, "    }}")?;
+                Self::write_getter(
+                    writer,
+                    &format!("{}At", prefix),
+                    ", uint256 index",
+                    &i.typ,
+                    "dynamicAt(data, index)",
+                    None,
+                )?;
+            }
+            ast::TopDecl::Table(ref i) => {
+                let mut body = vec![
+                    "(bool ok, uint256 count) = verifyDynamic(data);".to_owned(),
+                    format!("if (!ok || count != {}) return false;", i.inner.len()),
+                ];
+                for (idx, f) in i.inner.iter().enumerate() {
+                    let slice = format!("dynamicAt(data, {})", idx);
+                    body.push(format!(
+                        "if (!{}) return false;",
+                        verify_call(&f.typ, &slice)
+                    ));
+                }
+                body.push("return true;".to_owned());
+                Self::write_verify(writer, decl, &body)?;
+                for (idx, f) in i.inner.iter().enumerate() {
+                    let slice = format!("dynamicAt(data, {})", idx);
+                    let name = format!("{}{}", prefix, f.name.to_camel());
+                    Self::write_getter(writer, &name, "", &f.typ, &slice, None)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl super::LanguageGenerator for Generator {
+    fn generate<W: io::Write>(
+        writer: &mut W,
+        ast: &ast::Ast,
+        _options: &Options,
+    ) -> io::Result<()> {
+        writeln!(writer, "// Generated by Molecule {}", VERSION)?;
+        writeln!(writer, "// {}", Metadata::new(ast).comment())?;
+        writeln!(writer)?;
+        writeln!(writer, "{}", PRAGMA)?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "/// @notice The types of the molecule schema `{}`.",
+            ast.namespace
+        )?;
+        writeln!(writer, "library {} {{", ast.namespace.to_camel())?;
+        write!(writer, "{}", RUNTIME)?;
+        for decl in &Model::new(ast).decls {
+            Self::generate_decl(writer, decl)?;
+        }
+        writeln!(writer, "}}")
+    }
+}
@@ -0,0 +1,140 @@
+use std::{io, rc::Rc};
+
+use case::CaseExt;
+
+use crate::{
+    ast::verified::{self as ast, HasName},
+    generator::{metadata::Metadata, Flatten, Options},
+    VERSION,
+};
+
+pub(crate) struct Generator;
+
+struct Column {
+    name: String,
+    typ: &'static str,
+    nullable: bool,
+}
+
+fn is_bytes(typ: &ast::TopDecl) -> bool {
+    match typ {
+        ast::TopDecl::Array(ref i) => i.typ.is_atom(),
+        ast::TopDecl::FixVec(ref i) => i.typ.is_atom(),
+        _ => false,
+    }
+}
+
+fn nested_type(flatten: Flatten) -> &'static str {
+    match flatten {
+        Flatten::Columns | Flatten::Json => "JSON",
+        Flatten::Binary => "BLOB",
+    }
+}
+
+fn push_columns(
+    columns: &mut Vec<Column>,
+    prefix: &str,
+    typ: &ast::TopDecl,
+    nullable: bool,
+    flatten: Flatten,
+) {
+    let column = |typ| Column {
+        name: prefix.to_owned(),
+        typ,
+        nullable,
+    };
+    match typ {
+        ast::TopDecl::Atom(_) => columns.push(column("SMALLINT")),
+        _ if is_bytes(typ) => columns.push(column("BLOB")),
+        ast::TopDecl::Option_(ref i) => push_columns(columns, prefix, &i.typ, true, flatten),
+        ast::TopDecl::Struct(ref i) if flatten == Flatten::Columns => {
+            for f in &i.inner {
+                let name = format!("{}_{}", prefix, f.name.to_snake());
+                push_columns(columns, &name, &f.typ, nullable, flatten);
+            }
+        }
+        ast::TopDecl::Table(ref i) if flatten == Flatten::Columns => {
+            for f in &i.inner {
+                let name = format!("{}_{}", prefix, f.name.to_snake());
+                push_columns(columns, &name, &f.typ, nullable, flatten);
+            }
+        }
+        _ => columns.push(column(nested_type(flatten))),
+    }
+}
+
+impl Generator {
+    /// Find the exported types, which should be structs or tables.
+    ///
+    /// If no types are exported, all types which are defined in the schema file are exported,
+    /// and the others than the structs and the tables are skipped.
+    pub(super) fn exported_decls(
+        ast: &ast::Ast,
+        types: &[String],
+    ) -> Result<Vec<Rc<ast::TopDecl>>, String> {
+        if types.is_empty() {
+            return Ok(ast.major_decls());
+        }
+        types
+            .iter()
+            .map(|name| {
+                let decl = ast
+                    .get_decl(name)
+                    .ok_or_else(|| format!("the type `{}` is not defined", name))?;
+                match decl.as_ref() {
+                    ast::TopDecl::Struct(_) | ast::TopDecl::Table(_) => Ok(decl),
+                    _ => Err(format!(
+                        "the type `{}` is not a struct or a table, so it can't be exported as a SQL table",
+                        name
+                    )),
+                }
+            })
+            .collect()
+    }
+
+    fn create_table<W: io::Write>(
+        writer: &mut W,
+        name: &str,
+        fields: &[ast::FieldDecl],
+        flatten: Flatten,
+    ) -> io::Result<()> {
+        let mut columns = Vec::new();
+        for f in fields {
+            push_columns(&mut columns, &f.name.to_snake(), &f.typ, false, flatten);
+        }
+        if columns.is_empty() {
+            writeln!(writer, "-- {} has no columns.", name)?;
+            return writeln!(writer);
+        }
+        writeln!(writer, "CREATE TABLE {} (", name.to_snake())?;
+        for (idx, column) in columns.iter().enumerate() {
+            let null = if column.nullable { "NULL" } else { "NOT NULL" };
+            let sep = if idx + 1 == columns.len() { "" } else { "," };
+            writeln!(writer, "    {} {} {}{}", column.name, column.typ, null, sep)?;
+        }
+        writeln!(writer, ");")?;
+        writeln!(writer)
+    }
+}
+
+impl super::LanguageGenerator for Generator {
+    fn generate<W: io::Write>(writer: &mut W, ast: &ast::Ast, options: &Options) -> io::Result<()> {
+        writeln!(writer, "-- Generated by Molecule {}", VERSION)?;
+        writeln!(writer, "-- {}", Metadata::new(ast).comment())?;
+        writeln!(writer)?;
+        let decls = Self::exported_decls(ast, &options.types)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        for decl in decls {
+            match decl.as_ref() {
+                ast::TopDecl::Struct(ref i) => {
+                    Self::create_table(writer, i.name(), &i.inner, options.flatten)?
+                }
+                ast::TopDecl::Table(ref i) => {
+                    Self::create_table(writer, i.name(), &i.inner, options.flatten)?
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+//! A TLA+ module of the encoding predicates, to prove the readers against the schema rather than
+//! a transcription of it, with TLAPS or TLC.
+//!
+//! The bytes are a sequence of naturals in `0..255`, and the offsets start from 0. Each type has
+//! the predicate `Is<Type>(b, compatible)`, which holds if `b` is a valid encoding of the type, in
+//! the compatible mode if `compatible` is `TRUE`, and the accessors of its parts, such as
+//! `<Type>_<field>(b)` and `<Type>_at(b, i)`, which are the bytes where the readers look. An
+//! operator is defined after the operators which it uses, so the types are sorted by their
+//! dependencies.
+
+use std::io;
+
+use super::model::{Decl, Model};
+use crate::{
+    ast::verified::{self as ast, HasName},
+    generator::{metadata::Metadata, Options},
+    VERSION,
+};
+
+pub(crate) struct Generator;
+
+// The operators which are shared by the types.
+const RUNTIME: &str = r#"EXTENDS Integers, Sequences
+
+MolBytes == Seq(0..255)
+
+MolSub(b, start, size) == SubSeq(b, start + 1, start + size)
+
+\* The little-endian number of `size` bytes at `pos`.
+MolNum(b, pos, size) ==
+  LET F[i \in 0..size] == IF i = 0 THEN 0 ELSE F[i - 1] * 256 + b[pos + size - i + 1]
+  IN F[size]
+
+MolDynCount(b) == IF Len(b) = 4 THEN 0 ELSE MolNum(b, 4, 4) \div 4 - 1
+
+MolDynStart(b, i) == MolNum(b, 4 + 4 * i, 4)
+
+MolDynEnd(b, i) == IF i + 1 = MolDynCount(b) THEN Len(b) ELSE MolDynStart(b, i + 1)
+
+MolDynAt(b, i) == MolSub(b, MolDynStart(b, i), MolDynEnd(b, i) - MolDynStart(b, i))
+
+\* The header of a dynvec or a table: the total size, and the offsets in order.
+MolIsDynamic(b) ==
+  /\ Len(b) >= 4
+  /\ MolNum(b, 0, 4) = Len(b)
+  /\ \/ Len(b) = 4
+     \/ /\ Len(b) >= 8
+        /\ MolNum(b, 4, 4) % 4 = 0
+        /\ MolNum(b, 4, 4) >= 8
+        /\ MolNum(b, 4, 4) <= Len(b)
+        /\ \A i \in 0..(MolDynCount(b) - 1) : MolDynStart(b, i) <= MolDynEnd(b, i)
+
+IsByte(b, compatible) == Len(b) = 1
+"#;
+
+fn predicate(typ: &ast::TopDecl, bytes: &str) -> String {
+    let name = if typ.is_atom() { "Byte" } else { typ.name() };
+    format!("Is{}({}, compatible)", name, bytes)
+}
+
+impl Generator {
+    fn write_accessor<W: io::Write>(
+        writer: &mut W,
+        name: &str,
+        params: &str,
+        body: &str,
+    ) -> io::Result<()> {
+        writeln!(writer, "{}({}) == {}", name, params, body)
+    }
+
+    /// The predicate, which is the conjunction of the given conditions.
+    fn write_predicate<W: io::Write>(
+        writer: &mut W,
+        decl: &ast::TopDecl,
+        conditions: &[String],
+    ) -> io::Result<()> {
+        write!(writer, "Is{}(b, compatible) ==", decl.name())?;
+        if let [condition] = conditions {
+            writeln!(writer, " {}", condition)
+        } else {
+            writeln!(writer)?;
+            for condition in conditions {
+                writeln!(writer, "  /\\ {}", condition)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn generate_decl<W: io::Write>(writer: &mut W, decl: &Decl) -> io::Result<()> {
+        let (decl, fields) = (decl.decl, &decl.fields);
+        let name = decl.name();
+        writeln!(writer)?;
+        match decl {
+            ast::TopDecl::Atom(_) => unreachable!(),
+            ast::TopDecl::Option_(ref i) => {
+                writeln!(writer, "\\* The option {}.", name)?;
+                Self::write_predicate(
+                    writer,
+                    decl,
+                    &[format!("Len(b) = 0 \\/ {}", predicate(&i.typ, "b"))],
+                )?;
+            }
+            ast::TopDecl::Union(ref i) => {
+                writeln!(
+                    writer,
+                    "\\* The union {}, where the item ID is the index.",
+                    name
+                )?;
+                Self::write_accessor(writer, &format!("{}_id", name), "b", "MolNum(b, 0, 4)")?;
+                Self::write_accessor(
+                    writer,
+                    &format!("{}_item", name),
+                    "b",
+                    "MolSub(b, 4, Len(b) - 4)",
+                )?;
+                let item = format!("{}_item(b)", name);
+                let items = if i.inner.is_empty() {
+                    "FALSE".to_owned()
+                } else {
+                    let arms = i
+                        .inner
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, inner)| {
+                            format!(
+                                "{}_id(b) = {} -> {}",
+                                name,
+                                idx,
+                                predicate(&inner.typ, &item)
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    format!(
+                        "CASE {}\n       [] OTHER -> FALSE",
+                        arms.join("\n       [] ")
+                    )
+                };
+                Self::write_predicate(writer, decl, &["Len(b) >= 4".to_owned(), items])?;
+            }
+            ast::TopDecl::Array(ref i) => {
+                writeln!(
+                    writer,
+                    "\\* The array {}, of {} items in {} bytes.",
+                    name,
+                    i.item_count,
+                    i.total_size()
+                )?;
+                Self::write_accessor(
+                    writer,
+                    &format!("{}_at", name),
+                    "b, i",
+                    &format!("MolSub(b, i * {0}, {0})", i.item_size),
+                )?;
+                Self::write_predicate(writer, decl, &[format!("Len(b) = {}", i.total_size())])?;
+            }
+            ast::TopDecl::Struct(ref i) => {
+                writeln!(
+                    writer,
+                    "\\* The struct {}, in {} bytes.",
+                    name,
+                    i.total_size()
+                )?;
+                for f in fields {
+                    let (offset, size) = (f.offset(), f.size());
+                    Self::write_accessor(
+                        writer,
+                        &format!("{}_{}", name, f.name),
+                        "b",
+                        &format!("MolSub(b, {}, {})", offset, size),
+                    )?;
+                }
+                Self::write_predicate(writer, decl, &[format!("Len(b) = {}", i.total_size())])?;
+            }
+            ast::TopDecl::FixVec(ref i) => {
+                writeln!(
+                    writer,
+                    "\\* The fixvec {}, of {}-byte items.",
+                    name, i.item_size
+                )?;
+                Self::write_accessor(
+                    writer,
+                    &format!("{}_length", name),
+                    "b",
+                    &format!("MolNum(b, 0, {})", i.count_size),
+                )?;
+                Self::write_accessor(
+                    writer,
+                    &format!("{}_at", name),
+                    "b, i",
+                    &format!(
+                        "MolSub(b, {} + i * {}, {})",
+                        i.count_size, i.item_size, i.item_size
+                    ),
+                )?;
+                Self::write_predicate(
+                    writer,
+                    decl,
+                    &[
+                        format!("Len(b) >= {}", i.count_size),
+                        format!(
+                            "Len(b) = {} + {}_length(b) * {}",
+                            i.count_size, name, i.item_size
+                        ),
+                    ],
+                )?;
+            }
+            ast::TopDecl::DynVec(ref i) => {
+                writeln!(writer, "\\* The dynvec {}.", name)?;
+                Self::write_accessor(writer, &format!("{}_length", name), "b", "MolDynCount(b)")?;
+                Self::write_accessor(writer, &format!("{}_at", name), "b, i", "MolDynAt(b, i)")?;
+                Self::write_predicate(
+                    writer,
+                    decl,
+                    &[
+                        "MolIsDynamic(b)".to_owned(),
+                        format!(
+                            "\\A i \\in 0..({}_length(b) - 1) : {}",
+                            name,
+                            predicate(&i.typ, &format!("{}_at(b, i)", name))
+                        ),
+                    ],
+                )?;
+            }
+            ast::TopDecl::Table(ref i) => {
+                let count = i.inner.len();
+                writeln!(writer, "\\* The table {}.", name)?;
+                for (idx, f) in i.inner.iter().enumerate() {
+                    Self::write_accessor(
+                        writer,
+                        &format!("{}_{}", name, f.name),
+                        "b",
+                        &format!("MolDynAt(b, {})", idx),
+                    )?;
+                }
+                let mut conditions = vec!["MolIsDynamic(b)".to_owned()];
+                if count > 0 {
+                    conditions.push(format!("MolDynCount(b) >= {}", count));
+                }
+                conditions.push(format!("compatible \\/ MolDynCount(b) = {}", count));
+                for f in &i.inner {
+                    conditions.push(predicate(&f.typ, &format!("{}_{}(b)", name, f.name)));
+                }
+                Self::write_predicate(writer, decl, &conditions)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl super::LanguageGenerator for Generator {
+    fn generate<W: io::Write>(
+        writer: &mut W,
+        ast: &ast::Ast,
+        _options: &Options,
+    ) -> io::Result<()> {
+        writeln!(writer, "\\* Generated by Molecule {}", VERSION)?;
+        writeln!(writer, "\\* {}", Metadata::new(ast).comment())?;
+        writeln!(writer)?;
+        writeln!(writer, "---- MODULE {} ----", ast.namespace)?;
+        write!(writer, "{}", RUNTIME)?;
+        for decl in &Model::sorted(ast).decls {
+            Self::generate_decl(writer, decl)?;
+        }
+        writeln!(writer)?;
+        writeln!(writer, "====")
+    }
+}
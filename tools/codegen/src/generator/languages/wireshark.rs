@@ -0,0 +1,484 @@
+//! A Lua dissector for Wireshark 3.0 or later, to inspect molecule messages over TCP live.
+//!
+//! Each type has a dissector, which adds the fields, the items and the headers to the tree, with
+//! filters such as `ci_tests.struct_a.f1`, and reports the malformed parts as expert infos. A
+//! union is dispatched by its item ID. The messages on a TCP stream are the values of one type,
+//! which is chosen in the preferences of the protocol and is the last type of the schema by
+//! default; they are reassembled by the sizes in their headers, so the type should be a struct,
+//! an array, a vector or a table.
+//!
+//! The dissector is registered on the TCP port in the preferences, which is set by
+//! `--dissector-port`, and as a heuristic dissector of TCP with `--dissector-heuristic`, which
+//! accepts a segment of exactly one valid message. Install the file into the Wireshark plugin
+//! directory to load it.
+
+use std::io;
+
+use case::CaseExt;
+
+use super::model::{Decl, Model};
+use crate::{
+    ast::verified::{self as ast, HasName},
+    generator::{metadata::Metadata, Options},
+    VERSION,
+};
+
+pub(crate) struct Generator;
+
+// The helpers which are shared by the types; `proto`, `f`, `dissect` and `framing` are declared
+// before them.
+const RUNTIME: &str = r#"local e_malformed = ProtoExpert.new(
+  proto.name .. ".malformed", "Malformed molecule data", expert.group.MALFORMED, expert.severity.ERROR)
+proto.experts = { e_malformed }
+
+f.total_size = ProtoField.uint32(proto.name .. ".total_size", "total_size", base.DEC)
+f.count = ProtoField.uint32(proto.name .. ".count", "count", base.DEC)
+
+proto.prefs.compatible = Pref.bool("Compatible mode", false, "Allow the unknown fields of tables")
+
+-- The item is nil if the bytes are only verified.
+local function add(item, field, range)
+  if item then return item:add_le(field, range) end
+end
+
+local function malformed(item, message)
+  if item then item:add_proto_expert_info(e_malformed, message) end
+  return false
+end
+
+local function dissect_byte(range, item, field)
+  if range:len() ~= 1 then return malformed(item, "total size") end
+  add(item, field, range)
+  return true
+end
+
+-- The ranges of the items of a dynvec or the fields of a table, or nil and the error.
+local function dynamic_items(range)
+  local len = range:len()
+  if len < 4 then return nil, "header" end
+  local total = range(0, 4):le_uint()
+  if total ~= len then return nil, "total size" end
+  if total == 4 then return {} end
+  if total < 8 then return nil, "header" end
+  local first = range(4, 4):le_uint()
+  if first % 4 ~= 0 or first < 8 or first > total then return nil, "offset" end
+  local count = first / 4 - 1
+  local items = {}
+  for i = 0, count - 1 do
+    local start = range(4 + 4 * i, 4):le_uint()
+    local stop = total
+    if i + 1 < count then stop = range(8 + 4 * i, 4):le_uint() end
+    if start > stop or stop > total then return nil, "offset" end
+    items[i + 1] = range(start, stop - start)
+  end
+  return items
+end
+"#;
+
+// The framing of the messages over TCP and the registration of the dissector.
+const DISSECTOR: &str = r#"
+-- The size of the message at the offset, or nil if more bytes are needed to know it.
+local function message_size(frame, tvb, offset)
+  local available = tvb:len() - offset
+  if frame.size then return frame.size end
+  if frame.count_size then
+    if available < frame.count_size then return nil end
+    return frame.count_size + tvb(offset, frame.count_size):le_uint() * frame.item_size
+  end
+  if frame.dynamic then
+    if available < 4 then return nil end
+    return tvb(offset, 4):le_uint()
+  end
+  return available
+end
+
+function proto.dissector(tvb, pinfo, tree)
+  local name = type_names[proto.prefs.type]
+  if not name then return 0 end
+  local frame = framing[name]
+  local offset = 0
+  while offset < tvb:len() do
+    local size = message_size(frame, tvb, offset)
+    if size == nil or offset + size > tvb:len() then
+      pinfo.desegment_offset = offset
+      pinfo.desegment_len = size and offset + size - tvb:len() or DESEGMENT_ONE_MORE_SEGMENT
+      return tvb:len()
+    end
+    if size == 0 then break end
+    pinfo.cols.protocol = proto.name
+    local range = tvb(offset, size)
+    dissect[name](range, tree:add(proto, range):append_text(": " .. name))
+    offset = offset + size
+  end
+  return offset
+end
+
+local registered_port = 0
+
+local function register_port()
+  local tcp_port = DissectorTable.get("tcp.port")
+  if registered_port ~= 0 then tcp_port:remove(registered_port, proto) end
+  registered_port = proto.prefs.port
+  if registered_port ~= 0 then tcp_port:add(registered_port, proto) end
+end
+
+proto.prefs_changed = register_port
+register_port()
+"#;
+
+// Accepts a segment if it's exactly one valid message of the type in the preferences.
+const HEURISTIC: &str = r#"
+local function heuristic(tvb, pinfo, tree)
+  local name = type_names[proto.prefs.type]
+  if not name then return false end
+  local size = message_size(framing[name], tvb, 0)
+  if size ~= tvb:len() or size == 0 or not dissect[name](tvb(0, size), nil) then
+    return false
+  end
+  proto.dissector(tvb, pinfo, tree)
+  return true
+end
+
+proto:register_heuristic("tcp", heuristic)
+"#;
+
+/// The key of a field in `f`, which is its filter after the protocol name.
+fn field_key(decl: &ast::TopDecl, field: &str) -> String {
+    format!("{}.{}", decl.name().to_snake(), field.to_snake())
+}
+
+/// Dissects the range as the type, into a new item of the field.
+fn dissect_call(typ: &ast::TopDecl, range: &str, key: &str) -> String {
+    if typ.is_atom() {
+        format!("dissect_byte({}, item, f[\"{}\"])", range, key)
+    } else {
+        format!(
+            "dissect.{}({1}, add(item, f[\"{2}\"], {1}))",
+            typ.name(),
+            range,
+            key
+        )
+    }
+}
+
+impl Generator {
+    fn write_field<W: io::Write>(
+        writer: &mut W,
+        decl: &ast::TopDecl,
+        field: &str,
+        typ: &ast::TopDecl,
+    ) -> io::Result<()> {
+        let key = field_key(decl, field);
+        let constructor = if typ.is_atom() { "uint8" } else { "bytes" };
+        write!(
+            writer,
+            "f[\"{0}\"] = ProtoField.{1}(proto.name .. \".{0}\", \"{2}\"",
+            key, constructor, field
+        )?;
+        if typ.is_atom() {
+            write!(writer, ", base.HEX")?;
+        }
+        writeln!(writer, ")")
+    }
+
+    /// The items of an array or a fixvec, which aren't added to the tree if they are bytes,
+    /// since the bytes are shown by the item of the value.
+    fn write_items<W: io::Write>(
+        writer: &mut W,
+        decl: &ast::TopDecl,
+        typ: &ast::TopDecl,
+        start: usize,
+        item_size: usize,
+        count: &str,
+    ) -> io::Result<()> {
+        if typ.is_atom() {
+            return Ok(());
+        }
+        let offset = if start == 0 {
+            format!("i * {}", item_size)
+        } else {
+            format!("{} + i * {}", start, item_size)
+        };
+        let range = format!("range({}, {})", offset, item_size);
+        writeln!(writer, "  if item then")?;
+        writeln!(writer, "    for i = 0, {} - 1 do", count)?;
+        writeln!(
+            writer,
+            "      {}",
+            dissect_call(typ, &range, &field_key(decl, "item"))
+        )?;
+        writeln!(writer, "    end")?;
+        writeln!(writer, "  end")
+    }
+
+    fn generate_decl<W: io::Write>(writer: &mut W, decl: &Decl) -> io::Result<()> {
+        let (decl, fields) = (decl.decl, &decl.fields);
+        let name = decl.name();
+        writeln!(writer)?;
+        match decl {
+            ast::TopDecl::Atom(_) => unreachable!(),
+            ast::TopDecl::Option_(ref i) => {
+                writeln!(writer, "-- The option {}, which is empty for None.", name)?;
+                Self::write_field(writer, decl, "value", &i.typ)?;
+                writeln!(writer, "framing.{} = {{}}", name)?;
+                writeln!(writer, "function dissect.{}(range, item)", name)?;
+                writeln!(writer, "  if range:len() == 0 then")?;
+                writeln!(writer, "    if item then item:append_text(\" (None)\") end")?;
+                writeln!(writer, "    return true")?;
+                writeln!(writer, "  end")?;
+                writeln!(
+                    writer,
+                    "  return {}",
+                    dissect_call(&i.typ, "range", &field_key(decl, "value"))
+                )?;
+                writeln!(writer, "end")?;
+            }
+            ast::TopDecl::Union(ref i) => {
+                let key = field_key(decl, "item_id");
+                writeln!(
+                    writer,
+                    "-- The union {}, where the item ID is the index.",
+                    name
+                )?;
+                let names = i
+                    .inner
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, inner)| format!("[{}] = \"{}\"", idx, inner.typ.name()))
+                    .collect::<Vec<_>>();
+                writeln!(
+                    writer,
+                    "f[\"{0}\"] = ProtoField.uint32(proto.name .. \".{0}\", \"item_id\", base.DEC, {{ {1} }})",
+                    key,
+                    names.join(", ")
+                )?;
+                writeln!(
+                    writer,
+                    "f[\"{0}\"] = ProtoField.bytes(proto.name .. \".{0}\", \"item\")",
+                    field_key(decl, "item")
+                )?;
+                writeln!(writer, "framing.{} = {{}}", name)?;
+                writeln!(writer, "function dissect.{}(range, item)", name)?;
+                writeln!(
+                    writer,
+                    "  if range:len() < 4 then return malformed(item, \"header\") end"
+                )?;
+                writeln!(writer, "  local id = range(0, 4):le_uint()")?;
+                writeln!(writer, "  add(item, f[\"{}\"], range(0, 4))", key)?;
+                writeln!(writer, "  local body = range(4, range:len() - 4)")?;
+                for (idx, inner) in i.inner.iter().enumerate() {
+                    writeln!(
+                        writer,
+                        "  if id == {} then return {} end",
+                        idx,
+                        dissect_call(&inner.typ, "body", &field_key(decl, "item"))
+                    )?;
+                }
+                writeln!(writer, "  return malformed(item, \"item id\")")?;
+                writeln!(writer, "end")?;
+            }
+            ast::TopDecl::Array(ref i) => {
+                writeln!(
+                    writer,
+                    "-- The array {}, of {} items in {} bytes.",
+                    name,
+                    i.item_count,
+                    i.total_size()
+                )?;
+                if !i.typ.is_atom() {
+                    Self::write_field(writer, decl, "item", &i.typ)?;
+                }
+                writeln!(writer, "framing.{} = {{ size = {} }}", name, i.total_size())?;
+                writeln!(writer, "function dissect.{}(range, item)", name)?;
+                writeln!(
+                    writer,
+                    "  if range:len() ~= {} then return malformed(item, \"total size\") end",
+                    i.total_size()
+                )?;
+                Self::write_items(
+                    writer,
+                    decl,
+                    &i.typ,
+                    0,
+                    i.item_size,
+                    &i.item_count.to_string(),
+                )?;
+                writeln!(writer, "  return true")?;
+                writeln!(writer, "end")?;
+            }
+            ast::TopDecl::Struct(ref i) => {
+                writeln!(
+                    writer,
+                    "-- The struct {}, in {} bytes.",
+                    name,
+                    i.total_size()
+                )?;
+                for f in &i.inner {
+                    Self::write_field(writer, decl, &f.name, &f.typ)?;
+                }
+                writeln!(writer, "framing.{} = {{ size = {} }}", name, i.total_size())?;
+                writeln!(writer, "function dissect.{}(range, item)", name)?;
+                writeln!(
+                    writer,
+                    "  if range:len() ~= {} then return malformed(item, \"total size\") end",
+                    i.total_size()
+                )?;
+                writeln!(writer, "  if item then")?;
+                for f in fields {
+                    let (offset, size) = (f.offset(), f.size());
+                    let range = format!("range({}, {})", offset, size);
+                    writeln!(
+                        writer,
+                        "    {}",
+                        dissect_call(f.typ, &range, &field_key(decl, f.name))
+                    )?;
+                }
+                writeln!(writer, "  end")?;
+                writeln!(writer, "  return true")?;
+                writeln!(writer, "end")?;
+            }
+            ast::TopDecl::FixVec(ref i) => {
+                writeln!(
+                    writer,
+                    "-- The fixvec {}, of {}-byte items.",
+                    name, i.item_size
+                )?;
+                if !i.typ.is_atom() {
+                    Self::write_field(writer, decl, "item", &i.typ)?;
+                }
+                writeln!(
+                    writer,
+                    "framing.{} = {{ count_size = {}, item_size = {} }}",
+                    name, i.count_size, i.item_size
+                )?;
+                writeln!(writer, "function dissect.{}(range, item)", name)?;
+                writeln!(
+                    writer,
+                    "  if range:len() < {} then return malformed(item, \"header\") end",
+                    i.count_size
+                )?;
+                writeln!(
+                    writer,
+                    "  local count = range(0, {}):le_uint()",
+                    i.count_size
+                )?;
+                writeln!(
+                    writer,
+                    "  if range:len() ~= {} + count * {} then return malformed(item, \"total size\") end",
+                    i.count_size, i.item_size
+                )?;
+                writeln!(writer, "  add(item, f.count, range(0, {}))", i.count_size)?;
+                Self::write_items(writer, decl, &i.typ, i.count_size, i.item_size, "count")?;
+                writeln!(writer, "  return true")?;
+                writeln!(writer, "end")?;
+            }
+            ast::TopDecl::DynVec(ref i) => {
+                writeln!(writer, "-- The dynvec {}.", name)?;
+                Self::write_field(writer, decl, "item", &i.typ)?;
+                writeln!(writer, "framing.{} = {{ dynamic = true }}", name)?;
+                writeln!(writer, "function dissect.{}(range, item)", name)?;
+                writeln!(writer, "  local items, err = dynamic_items(range)")?;
+                writeln!(
+                    writer,
+                    "  if not items then return malformed(item, err) end"
+                )?;
+                writeln!(writer, "  add(item, f.total_size, range(0, 4))")?;
+                writeln!(writer, "  local ok = true")?;
+                writeln!(writer, "  for _, r in ipairs(items) do")?;
+                writeln!(
+                    writer,
+                    "    ok = {} and ok",
+                    dissect_call(&i.typ, "r", &field_key(decl, "item"))
+                )?;
+                writeln!(writer, "  end")?;
+                writeln!(writer, "  return ok")?;
+                writeln!(writer, "end")?;
+            }
+            ast::TopDecl::Table(ref i) => {
+                let count = i.inner.len();
+                writeln!(writer, "-- The table {}.", name)?;
+                for f in &i.inner {
+                    Self::write_field(writer, decl, &f.name, &f.typ)?;
+                }
+                writeln!(writer, "framing.{} = {{ dynamic = true }}", name)?;
+                writeln!(writer, "function dissect.{}(range, item)", name)?;
+                writeln!(writer, "  local fields, err = dynamic_items(range)")?;
+                writeln!(
+                    writer,
+                    "  if not fields then return malformed(item, err) end"
+                )?;
+                writeln!(
+                    writer,
+                    "  if #fields < {0} or (#fields > {0} and not proto.prefs.compatible) then",
+                    count
+                )?;
+                writeln!(writer, "    return malformed(item, \"field count\")")?;
+                writeln!(writer, "  end")?;
+                writeln!(writer, "  add(item, f.total_size, range(0, 4))")?;
+                writeln!(writer, "  local ok = true")?;
+                for (idx, f) in i.inner.iter().enumerate() {
+                    let range = format!("fields[{}]", idx + 1);
+                    writeln!(
+                        writer,
+                        "  ok = {} and ok",
+                        dissect_call(&f.typ, &range, &field_key(decl, &f.name))
+                    )?;
+                }
+                writeln!(writer, "  return ok")?;
+                writeln!(writer, "end")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl super::LanguageGenerator for Generator {
+    fn generate<W: io::Write>(writer: &mut W, ast: &ast::Ast, options: &Options) -> io::Result<()> {
+        let namespace = ast.namespace.to_snake();
+        writeln!(writer, "-- Generated by Molecule {}", VERSION)?;
+        writeln!(writer, "-- {}", Metadata::new(ast).comment())?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "local proto = Proto(\"{}\", \"Molecule {}\")",
+            namespace, ast.namespace
+        )?;
+        writeln!(writer, "local f = {{}}")?;
+        writeln!(writer, "local dissect = {{}}")?;
+        writeln!(writer, "local framing = {{}}")?;
+        writeln!(writer)?;
+        write!(writer, "{}", RUNTIME)?;
+        let decls = Model::new(ast).decls;
+        for decl in &decls {
+            Self::generate_decl(writer, decl)?;
+        }
+        writeln!(writer)?;
+        writeln!(writer, "proto.fields = f")?;
+        writeln!(writer)?;
+        writeln!(writer, "local type_names = {{")?;
+        for decl in &decls {
+            writeln!(writer, "  \"{}\",", decl.name())?;
+        }
+        writeln!(writer, "}}")?;
+        writeln!(writer, "local type_enum = {{}}")?;
+        writeln!(
+            writer,
+            "for i, name in ipairs(type_names) do type_enum[i] = {{ i, name, i }} end"
+        )?;
+        writeln!(
+            writer,
+            "proto.prefs.type = Pref.enum(\"Message type\", {}, \"The type of the messages\", type_enum, false)",
+            decls.len()
+        )?;
+        writeln!(
+            writer,
+            "proto.prefs.port = Pref.uint(\"TCP port\", {}, \"The TCP port of the messages, or 0\")",
+            options.dissector_port.unwrap_or(0)
+        )?;
+        write!(writer, "{}", DISSECTOR)?;
+        if options.dissector_heuristic {
+            write!(writer, "{}", HEURISTIC)?;
+        }
+        Ok(())
+    }
+}
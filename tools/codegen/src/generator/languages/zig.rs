@@ -0,0 +1,601 @@
+//! Zig structs which verify, read and build the types, for Zig 0.12 or later.
+//!
+//! A fixed-size type, which is an array or a struct, holds its bytes in `raw: [size]u8`, where
+//! `size` is known at comptime, and it's built by `init` without an allocator. Other types are
+//! views of `raw: []const u8`, and their `build` functions take an explicit allocator and return
+//! the bytes which are owned by the caller.
+//!
+//! `fromSlice` verifies the bytes in the strict mode; the getters, such as `get<Field>`, `len`
+//! and `at`, don't check the bytes again. The imported types are generated into the same file, so
+//! the file doesn't depend on the files of the imported schemas.
+
+use std::io;
+
+use case::CaseExt;
+
+use super::model::{Decl, Model};
+use crate::{
+    ast::verified::{self as ast, HasName},
+    generator::{metadata::Metadata, Options},
+    VERSION,
+};
+
+pub(crate) struct Generator;
+
+const ALLOCATOR: &str = "allocator: std.mem.Allocator";
+const ALLOCATOR_ERROR: &str = "std.mem.Allocator.Error![]u8";
+
+// The helpers which are shared by the types.
+const RUNTIME: &str = r#"const std = @import("std");
+
+pub const Error = error{ TotalSize, Header, Offset, FieldCount, UnknownItem };
+
+fn readNumber(bytes: []const u8) usize {
+    var value: usize = 0;
+    for (bytes, 0..) |byte, i| value |= @as(usize, byte) << @intCast(8 * i);
+    return value;
+}
+
+fn writeNumber(bytes: []u8, value: usize) void {
+    for (bytes, 0..) |*byte, i| byte.* = @truncate(value >> @intCast(8 * i));
+}
+
+fn verifyByte(slice: []const u8) Error!void {
+    if (slice.len != 1) return error.TotalSize;
+}
+
+/// Verifies the header of a dynvec or a table, and returns the count of the items.
+fn verifyDynamic(slice: []const u8) Error!usize {
+    if (slice.len < 4) return error.Header;
+    const total = readNumber(slice[0..4]);
+    if (total != slice.len) return error.TotalSize;
+    if (total == 4) return 0;
+    if (total < 8) return error.Header;
+    const first = readNumber(slice[4..8]);
+    if (first % 4 != 0 or first < 8 or first > total) return error.Offset;
+    const count = first / 4 - 1;
+    var previous = first;
+    for (1..count) |i| {
+        const offset = readNumber(slice[4 + 4 * i ..][0..4]);
+        if (offset < previous or offset > total) return error.Offset;
+        previous = offset;
+    }
+    return count;
+}
+
+fn dynamicLength(slice: []const u8) usize {
+    return if (slice.len == 4) 0 else readNumber(slice[4..8]) / 4 - 1;
+}
+
+fn dynamicAt(slice: []const u8, index: usize) []const u8 {
+    const count = dynamicLength(slice);
+    std.debug.assert(index < count);
+    const start = readNumber(slice[4 + 4 * index ..][0..4]);
+    const end = if (index + 1 == count) slice.len else readNumber(slice[8 + 4 * index ..][0..4]);
+    return slice[start..end];
+}
+
+fn packDynamic(allocator: std.mem.Allocator, items: []const []const u8) std.mem.Allocator.Error![]u8 {
+    const header = 4 * (items.len + 1);
+    var total = header;
+    for (items) |item| total += item.len;
+    const raw = try allocator.alloc(u8, total);
+    writeNumber(raw[0..4], total);
+    var offset = header;
+    for (items, 0..) |item, i| {
+        writeNumber(raw[4 + 4 * i ..][0..4], offset);
+        @memcpy(raw[offset..][0..item.len], item);
+        offset += item.len;
+    }
+    return raw;
+}
+
+fn packUnion(allocator: std.mem.Allocator, id: usize, item: []const u8) std.mem.Allocator.Error![]u8 {
+    const raw = try allocator.alloc(u8, 4 + item.len);
+    writeNumber(raw[0..4], id);
+    @memcpy(raw[4..], item);
+    return raw;
+}
+"#;
+
+const KEYWORDS: &[&str] = &[
+    "addrspace",
+    "align",
+    "allowzero",
+    "and",
+    "anyframe",
+    "anytype",
+    "asm",
+    "async",
+    "await",
+    "break",
+    "callconv",
+    "catch",
+    "comptime",
+    "const",
+    "continue",
+    "defer",
+    "else",
+    "enum",
+    "errdefer",
+    "error",
+    "export",
+    "extern",
+    "false",
+    "fn",
+    "for",
+    "if",
+    "inline",
+    "linksection",
+    "noalias",
+    "noinline",
+    "nosuspend",
+    "null",
+    "opaque",
+    "or",
+    "orelse",
+    "packed",
+    "pub",
+    "resume",
+    "return",
+    "struct",
+    "suspend",
+    "switch",
+    "test",
+    "threadlocal",
+    "true",
+    "try",
+    "type",
+    "undefined",
+    "union",
+    "unreachable",
+    "usingnamespace",
+    "var",
+    "void",
+    "volatile",
+    "while",
+];
+
+/// Quotes a name which is a keyword or a primitive type of Zig.
+fn ident(name: &str) -> String {
+    let is_int = name.len() > 1
+        && (name.starts_with('u') || name.starts_with('i'))
+        && name[1..].bytes().all(|b| b.is_ascii_digit());
+    if is_int || KEYWORDS.contains(&name) {
+        format!("@\"{}\"", name)
+    } else {
+        name.to_owned()
+    }
+}
+
+fn type_name(typ: &ast::TopDecl) -> String {
+    if typ.is_atom() {
+        "u8".to_owned()
+    } else {
+        typ.name().to_camel()
+    }
+}
+
+fn getter_name(field: &str) -> String {
+    format!("get{}", field.to_camel())
+}
+
+fn verify_expr(typ: &ast::TopDecl, slice: &str) -> String {
+    if typ.is_atom() {
+        format!("try verifyByte({})", slice)
+    } else {
+        format!("_ = try {}.fromSlice({})", type_name(typ), slice)
+    }
+}
+
+/// The value of the type in a slice, which has been verified.
+fn read_expr(typ: &ast::TopDecl, slice: &str) -> String {
+    if typ.is_atom() {
+        format!("{}[0]", slice)
+    } else if typ.total_size().is_some() {
+        format!(".{{ .raw = {}[0..{}.size].* }}", slice, type_name(typ))
+    } else {
+        format!(".{{ .raw = {} }}", slice)
+    }
+}
+
+/// The bytes of a value of the type.
+fn bytes_expr(typ: &ast::TopDecl, value: &str) -> String {
+    if typ.is_atom() {
+        format!("std.mem.asBytes(&{})", value)
+    } else if typ.total_size().is_some() {
+        format!("&{}.raw", value)
+    } else {
+        format!("{}.raw", value)
+    }
+}
+
+impl Generator {
+    fn write_body<W: io::Write>(
+        writer: &mut W,
+        signature: &str,
+        body: &[String],
+    ) -> io::Result<()> {
+        writeln!(writer, "    pub fn {} {{", signature)?;
+        for line in body {
+            writeln!(writer, "        {}", line)?;
+        }
+        writeln!(writer, "    }}")
+    }
+
+    fn write_fn<W: io::Write>(writer: &mut W, signature: &str, body: &[String]) -> io::Result<()> {
+        writeln!(writer)?;
+        Self::write_body(writer, signature, body)
+    }
+
+    fn write_from_slice<W: io::Write>(
+        writer: &mut W,
+        name: &str,
+        body: &[String],
+    ) -> io::Result<()> {
+        writeln!(writer)?;
+        writeln!(writer, "    /// Verifies the bytes in the strict mode.")?;
+        let signature = format!("fromSlice(slice: []const u8) Error!{}", name);
+        Self::write_body(writer, &signature, body)
+    }
+
+    fn write_fields<W: io::Write>(writer: &mut W, fields: &[ast::FieldDecl]) -> io::Result<()> {
+        writeln!(writer)?;
+        if fields.is_empty() {
+            return writeln!(writer, "    pub const Fields = struct {{}};");
+        }
+        writeln!(writer, "    pub const Fields = struct {{")?;
+        for f in fields {
+            writeln!(writer, "        {}: {},", ident(&f.name), type_name(&f.typ))?;
+        }
+        writeln!(writer, "    }};")
+    }
+
+    fn write_build<W: io::Write>(writer: &mut W, params: &str, body: &[String]) -> io::Result<()> {
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "    /// Packs the bytes with the allocator; the caller owns the returned bytes."
+        )?;
+        let signature = format!("build({}, {}) {}", ALLOCATOR, params, ALLOCATOR_ERROR);
+        Self::write_body(writer, &signature, body)
+    }
+
+    fn generate_decl<W: io::Write>(writer: &mut W, decl: &Decl) -> io::Result<()> {
+        let (decl, fields) = (decl.decl, &decl.fields);
+        let name = type_name(decl);
+        writeln!(writer)?;
+        match decl {
+            ast::TopDecl::Atom(_) => unreachable!(),
+            ast::TopDecl::Option_(ref i) => {
+                let inner = type_name(&i.typ);
+                writeln!(writer, "/// The option `{}`.", i.name)?;
+                writeln!(writer, "pub const {} = struct {{", name)?;
+                writeln!(writer, "    raw: []const u8,")?;
+                let body = vec![
+                    "if (slice.len != 0) {".to_owned(),
+                    format!("    {};", verify_expr(&i.typ, "slice")),
+                    "}".to_owned(),
+                    "return .{ .raw = slice };".to_owned(),
+                ];
+                Self::write_from_slice(writer, &name, &body)?;
+                let body = vec![
+                    "if (self.raw.len == 0) return null;".to_owned(),
+                    format!("return {};", read_expr(&i.typ, "self.raw")),
+                ];
+                Self::write_fn(writer, &format!("get(self: {}) ?{}", name, inner), &body)?;
+                let body = vec![
+                    "const v = value orelse return allocator.alloc(u8, 0);".to_owned(),
+                    format!("return allocator.dupe(u8, {});", bytes_expr(&i.typ, "v")),
+                ];
+                Self::write_build(writer, &format!("value: ?{}", inner), &body)?;
+            }
+            ast::TopDecl::Union(ref i) => {
+                writeln!(writer, "/// The union `{}`.", i.name)?;
+                writeln!(writer, "pub const {} = struct {{", name)?;
+                writeln!(writer, "    raw: []const u8,")?;
+                writeln!(writer)?;
+                writeln!(writer, "    pub const Item = union(enum) {{")?;
+                for item in &i.inner {
+                    writeln!(
+                        writer,
+                        "        {}: {},",
+                        ident(&item.typ.name().to_snake()),
+                        type_name(&item.typ)
+                    )?;
+                }
+                writeln!(writer, "    }};")?;
+                let mut body = vec![
+                    "if (slice.len < 4) return error.Header;".to_owned(),
+                    "const body = slice[4..];".to_owned(),
+                    "switch (readNumber(slice[0..4])) {".to_owned(),
+                ];
+                for (idx, item) in i.inner.iter().enumerate() {
+                    body.push(format!(
+                        "    {} => {},",
+                        idx,
+                        verify_expr(&item.typ, "body")
+                    ));
+                }
+                body.push("    else => return error.UnknownItem,".to_owned());
+                body.push("}".to_owned());
+                body.push("return .{ .raw = slice };".to_owned());
+                Self::write_from_slice(writer, &name, &body)?;
+                let body = vec!["return readNumber(self.raw[0..4]);".to_owned()];
+                Self::write_fn(writer, &format!("itemId(self: {}) usize", name), &body)?;
+                let mut body = vec![
+                    "const body = self.raw[4..];".to_owned(),
+                    "return switch (self.itemId()) {".to_owned(),
+                ];
+                for (idx, item) in i.inner.iter().enumerate() {
+                    body.push(format!(
+                        "    {} => .{{ .{} = {} }},",
+                        idx,
+                        ident(&item.typ.name().to_snake()),
+                        read_expr(&item.typ, "body")
+                    ));
+                }
+                body.push("    else => unreachable,".to_owned());
+                body.push("};".to_owned());
+                Self::write_fn(writer, &format!("item(self: {}) Item", name), &body)?;
+                let mut body = vec!["return switch (value) {".to_owned()];
+                for (idx, item) in i.inner.iter().enumerate() {
+                    body.push(format!(
+                        "    .{} => |v| packUnion(allocator, {}, {}),",
+                        ident(&item.typ.name().to_snake()),
+                        idx,
+                        bytes_expr(&item.typ, "v")
+                    ));
+                }
+                body.push("};".to_owned());
+                Self::write_build(writer, "value: Item", &body)?;
+            }
+            ast::TopDecl::Array(ref i) => {
+                let item = type_name(&i.typ);
+                writeln!(writer, "/// The array `{}`.", i.name)?;
+                writeln!(writer, "pub const {} = struct {{", name)?;
+                writeln!(writer, "    raw: [size]u8,")?;
+                writeln!(writer)?;
+                writeln!(writer, "    pub const size = {};", i.total_size())?;
+                writeln!(writer, "    pub const len = {};", i.item_count)?;
+                let body = vec![
+                    "if (slice.len != size) return error.TotalSize;".to_owned(),
+                    "return .{ .raw = slice[0..size].* };".to_owned(),
+                ];
+                Self::write_from_slice(writer, &name, &body)?;
+                let body = if i.typ.is_atom() {
+                    vec!["return self.raw[index];".to_owned()]
+                } else {
+                    let slice = format!("self.raw[index * {} ..]", i.item_size);
+                    vec![format!("return {};", read_expr(&i.typ, &slice))]
+                };
+                Self::write_fn(
+                    writer,
+                    &format!("at(self: {}, index: usize) {}", name, item),
+                    &body,
+                )?;
+                let body = if i.typ.is_atom() {
+                    vec!["return .{ .raw = items };".to_owned()]
+                } else {
+                    vec![
+                        "var raw: [size]u8 = undefined;".to_owned(),
+                        format!(
+                            "for (items, 0..) |item, i| @memcpy(raw[i * {} ..][0..{}], {});",
+                            i.item_size,
+                            i.item_size,
+                            bytes_expr(&i.typ, "item")
+                        ),
+                        "return .{ .raw = raw };".to_owned(),
+                    ]
+                };
+                Self::write_fn(
+                    writer,
+                    &format!("init(items: [len]{}) {}", item, name),
+                    &body,
+                )?;
+            }
+            ast::TopDecl::Struct(ref i) => {
+                writeln!(writer, "/// The struct `{}`.", i.name)?;
+                writeln!(writer, "pub const {} = struct {{", name)?;
+                writeln!(writer, "    raw: [size]u8,")?;
+                writeln!(writer)?;
+                writeln!(writer, "    pub const size = {};", i.total_size())?;
+                Self::write_fields(writer, &i.inner)?;
+                let body = vec![
+                    "if (slice.len != size) return error.TotalSize;".to_owned(),
+                    "return .{ .raw = slice[0..size].* };".to_owned(),
+                ];
+                Self::write_from_slice(writer, &name, &body)?;
+                let mut init = vec!["var raw: [size]u8 = undefined;".to_owned()];
+                for f in fields {
+                    let (offset, size) = (f.offset(), f.size());
+                    let expr = if f.typ.is_atom() {
+                        format!("self.raw[{}]", offset)
+                    } else {
+                        format!(".{{ .raw = self.raw[{}..{}].* }}", offset, offset + size)
+                    };
+                    let body = vec![format!("return {};", expr)];
+                    Self::write_fn(
+                        writer,
+                        &format!(
+                            "{}(self: {}) {}",
+                            getter_name(f.name),
+                            name,
+                            type_name(f.typ)
+                        ),
+                        &body,
+                    )?;
+                    let value = format!("fields.{}", ident(f.name));
+                    if f.typ.is_atom() {
+                        init.push(format!("raw[{}] = {};", offset, value));
+                    } else {
+                        init.push(format!(
+                            "@memcpy(raw[{}..{}], {});",
+                            offset,
+                            offset + size,
+                            bytes_expr(f.typ, &value)
+                        ));
+                    }
+                }
+                init.push("return .{ .raw = raw };".to_owned());
+                Self::write_fn(writer, &format!("init(fields: Fields) {}", name), &init)?;
+            }
+            ast::TopDecl::FixVec(ref i) => {
+                let item = type_name(&i.typ);
+                let header = format!("slice[0..{}]", i.count_size);
+                writeln!(writer, "/// The fixvec `{}`.", i.name)?;
+                writeln!(writer, "pub const {} = struct {{", name)?;
+                writeln!(writer, "    raw: []const u8,")?;
+                let body = vec![
+                    format!("if (slice.len < {}) return error.Header;", i.count_size),
+                    format!(
+                        "if (slice.len != {} + readNumber({}) * {}) return error.TotalSize;",
+                        i.count_size, header, i.item_size
+                    ),
+                    "return .{ .raw = slice };".to_owned(),
+                ];
+                Self::write_from_slice(writer, &name, &body)?;
+                let body = vec![format!("return readNumber(self.raw[0..{}]);", i.count_size)];
+                Self::write_fn(writer, &format!("len(self: {}) usize", name), &body)?;
+                let body = if i.typ.is_atom() {
+                    vec![format!("return self.raw[{} + index];", i.count_size)]
+                } else {
+                    let slice = format!("self.raw[{} + index * {} ..]", i.count_size, i.item_size);
+                    vec![format!("return {};", read_expr(&i.typ, &slice))]
+                };
+                Self::write_fn(
+                    writer,
+                    &format!("at(self: {}, index: usize) {}", name, item),
+                    &body,
+                )?;
+                let mut body = vec![
+                    format!(
+                        "const raw = try allocator.alloc(u8, {} + items.len * {});",
+                        i.count_size, i.item_size
+                    ),
+                    format!("writeNumber(raw[0..{}], items.len);", i.count_size),
+                ];
+                if i.typ.is_atom() {
+                    body.push(format!("@memcpy(raw[{}..], items);", i.count_size));
+                } else {
+                    body.push(format!(
+                        "for (items, 0..) |item, i| @memcpy(raw[{} + i * {} ..][0..{}], {});",
+                        i.count_size,
+                        i.item_size,
+                        i.item_size,
+                        bytes_expr(&i.typ, "item")
+                    ));
+                }
+                body.push("return raw;".to_owned());
+                Self::write_build(writer, &format!("items: []const {}", item), &body)?;
+            }
+            ast::TopDecl::DynVec(ref i) => {
+                let item = type_name(&i.typ);
+                writeln!(writer, "/// The dynvec `{}`.", i.name)?;
+                writeln!(writer, "pub const {} = struct {{", name)?;
+                writeln!(writer, "    raw: []const u8,")?;
+                let body = vec![
+                    "const count = try verifyDynamic(slice);".to_owned(),
+                    "for (0..count) |i| {".to_owned(),
+                    format!("    {};", verify_expr(&i.typ, "dynamicAt(slice, i)")),
+                    "}".to_owned(),
+                    "return .{ .raw = slice };".to_owned(),
+                ];
+                Self::write_from_slice(writer, &name, &body)?;
+                let body = vec!["return dynamicLength(self.raw);".to_owned()];
+                Self::write_fn(writer, &format!("len(self: {}) usize", name), &body)?;
+                let body = vec![format!(
+                    "return {};",
+                    read_expr(&i.typ, "dynamicAt(self.raw, index)")
+                )];
+                Self::write_fn(
+                    writer,
+                    &format!("at(self: {}, index: usize) {}", name, item),
+                    &body,
+                )?;
+                let body = vec![
+                    "const slices = try allocator.alloc([]const u8, items.len);".to_owned(),
+                    "defer allocator.free(slices);".to_owned(),
+                    format!(
+                        "for (items, slices) |item, *slice| slice.* = {};",
+                        bytes_expr(&i.typ, "item")
+                    ),
+                    "return packDynamic(allocator, slices);".to_owned(),
+                ];
+                Self::write_build(writer, &format!("items: []const {}", item), &body)?;
+            }
+            ast::TopDecl::Table(ref i) => {
+                writeln!(writer, "/// The table `{}`.", i.name)?;
+                writeln!(writer, "pub const {} = struct {{", name)?;
+                writeln!(writer, "    raw: []const u8,")?;
+                Self::write_fields(writer, &i.inner)?;
+                let mut body = vec![
+                    "const count = try verifyDynamic(slice);".to_owned(),
+                    format!("if (count != {}) return error.FieldCount;", i.inner.len()),
+                ];
+                for (idx, f) in i.inner.iter().enumerate() {
+                    let slice = format!("dynamicAt(slice, {})", idx);
+                    body.push(format!("{};", verify_expr(&f.typ, &slice)));
+                }
+                body.push("return .{ .raw = slice };".to_owned());
+                Self::write_from_slice(writer, &name, &body)?;
+                for (idx, f) in i.inner.iter().enumerate() {
+                    let slice = format!("dynamicAt(self.raw, {})", idx);
+                    let body = vec![format!("return {};", read_expr(&f.typ, &slice))];
+                    Self::write_fn(
+                        writer,
+                        &format!(
+                            "{}(self: {}) {}",
+                            getter_name(&f.name),
+                            name,
+                            type_name(&f.typ)
+                        ),
+                        &body,
+                    )?;
+                }
+                let items = i
+                    .inner
+                    .iter()
+                    .map(|f| bytes_expr(&f.typ, &format!("fields.{}", ident(&f.name))))
+                    .collect::<Vec<_>>();
+                // An unused parameter is a compile error in Zig.
+                let params = if i.inner.is_empty() {
+                    "_: Fields"
+                } else {
+                    "fields: Fields"
+                };
+                let items = if items.is_empty() {
+                    "&.{}".to_owned()
+                } else {
+                    format!("&.{{ {} }}", items.join(", "))
+                };
+                let body = vec![format!("return packDynamic(allocator, {});", items)];
+                Self::write_build(writer, params, &body)?;
+            }
+        }
+        writeln!(writer, "}};")
+    }
+}
+
+impl super::LanguageGenerator for Generator {
+    fn generate<W: io::Write>(
+        writer: &mut W,
+        ast: &ast::Ast,
+        _options: &Options,
+    ) -> io::Result<()> {
+        writeln!(writer, "// Generated by Molecule {}", VERSION)?;
+        writeln!(writer, "// {}", Metadata::new(ast).comment())?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "//! The types of the molecule schema `{}`.",
+            ast.namespace
+        )?;
+        writeln!(writer)?;
+        write!(writer, "{}", RUNTIME)?;
+        for decl in &Model::new(ast).decls {
+            Self::generate_decl(writer, decl)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+//! The versions of the generator and the schema, which are embedded into the generated files.
+//!
+//! Tooling could find the metadata in any generated file by the marker, and compare it with the
+//! other generated files which are combined in one binary.
+
+use serde_json::{json, Value};
+
+use crate::{ast::verified as ast, schema_hash::hash_of, VERSION};
+
+/// The marker of the metadata comment, which is followed by a JSON object in one line.
+pub(crate) const MARKER: &str = "molecule-metadata:";
+
+pub(crate) struct Metadata {
+    pub(crate) namespace: String,
+    pub(crate) generator_version: &'static str,
+    /// The hash of the canonical schema, including the imported types.
+    pub(crate) schema_version: String,
+}
+
+impl Metadata {
+    pub(crate) fn new(ast: &ast::Ast) -> Self {
+        let schema_version = hash_of(ast)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        Self {
+            namespace: ast.namespace.clone(),
+            generator_version: VERSION,
+            schema_version,
+        }
+    }
+
+    pub(crate) fn json(&self) -> Value {
+        json!({
+            "namespace": self.namespace,
+            "generator_version": self.generator_version,
+            "schema_version": self.schema_version,
+        })
+    }
+
+    /// The text of the metadata comment, without the prefix of the comment.
+    pub(crate) fn comment(&self) -> String {
+        format!("{} {}", MARKER, self.json())
+    }
+}
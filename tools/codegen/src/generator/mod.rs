@@ -1,10 +1,188 @@
-use std::{io, path::Path};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    io,
+    path::{Path, PathBuf},
+};
 
 use crate::{Ast, Parser};
 
+pub(crate) mod header;
+pub(crate) mod hooks;
 mod languages;
+pub(crate) mod metadata;
+pub(crate) mod template;
 
 pub use languages::Language;
+pub(crate) use languages::{generate_examples, generate_migration};
+
+/// How to store the nested structures when exporting a table as SQL columns.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Flatten {
+    /// Expand the fields of nested structs and tables into columns, and store the others as JSON.
+    #[default]
+    Columns,
+    /// Store the nested structures as JSON.
+    Json,
+    /// Store the nested structures as the raw molecule bytes.
+    Binary,
+}
+
+impl TryFrom<&str> for Flatten {
+    type Error = String;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "columns" => Ok(Flatten::Columns),
+            "json" => Ok(Flatten::Json),
+            "binary" => Ok(Flatten::Binary),
+            strategy => Err(format!("unsupport flatten strategy: [{}]", strategy)),
+        }
+    }
+}
+
+/// The visibility of the types, the builders and the constants in the generated Rust code.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum Visibility {
+    /// `pub`
+    #[default]
+    Public,
+    /// `pub(crate)`
+    Crate,
+    /// `pub(in path)`, the path should be an ancestor module of the generated code.
+    Restricted(String),
+}
+
+impl TryFrom<&str> for Visibility {
+    type Error = String;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let restricted = value
+            .strip_prefix("pub(in ")
+            .and_then(|path| path.strip_suffix(')'))
+            .map(str::trim)
+            .filter(|path| !path.is_empty());
+        match value {
+            "pub" => Ok(Visibility::Public),
+            "pub(crate)" => Ok(Visibility::Crate),
+            _ => restricted
+                .map(|path| Visibility::Restricted(path.to_owned()))
+                .ok_or_else(|| format!("unsupport visibility: [{}]", value)),
+        }
+    }
+}
+
+/// The Rust edition of the crate which includes the generated code.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Edition {
+    /// The paths of the dependencies start with `::`.
+    Edition2015,
+    #[default]
+    Edition2018,
+    Edition2021,
+    /// `gen` is reserved.
+    Edition2024,
+}
+
+impl TryFrom<&str> for Edition {
+    type Error = String;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "2015" => Ok(Edition::Edition2015),
+            "2018" => Ok(Edition::Edition2018),
+            "2021" => Ok(Edition::Edition2021),
+            "2024" => Ok(Edition::Edition2024),
+            edition => Err(format!("unsupport edition: [{}]", edition)),
+        }
+    }
+}
+
+/// The profile of the generated C code.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CProfile {
+    /// The readers and the builders.
+    #[default]
+    Default,
+    /// Only the readers, for on-chain scripts: no libc functions and no variable-length arrays,
+    /// so the stack usage is bounded, and fewer branches in the checks of the offsets.
+    Script,
+}
+
+impl TryFrom<&str> for CProfile {
+    type Error = String;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "default" => Ok(CProfile::Default),
+            "script" => Ok(CProfile::Script),
+            profile => Err(format!("unsupport C profile: [{}]", profile)),
+        }
+    }
+}
+
+/// Options which customize the generated code.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Options {
+    /// Implement `schemars::JsonSchema` for all Rust entities.
+    pub(crate) json_schema: bool,
+    /// Implement conversions between Rust vectors of fixed-size items and Apache Arrow arrays.
+    pub(crate) arrow: bool,
+    /// Implement `rayon::iter::IntoParallelIterator` for Rust readers of vectors.
+    pub(crate) rayon: bool,
+    /// The inline capacity of the items of the Rust vector builders, which are `SmallVec`s if
+    /// it's set.
+    pub(crate) small_vectors: Option<usize>,
+    /// Generate `#[repr(transparent)]` readers over `[u8]` for all Rust entities.
+    pub(crate) transparent: bool,
+    /// Generate readers over `molecule::source::Source` for all Rust entities.
+    pub(crate) source_readers: bool,
+    /// Generate `from_slice_linear` for Rust readers, which verify the bytes in order.
+    pub(crate) linear_verification: bool,
+    /// Generate PyO3 classes which wrap the Rust entities, and a Python module of them.
+    pub(crate) pyo3: bool,
+    /// Generate `get(idx)` only for Rust arrays, without the `nthN` accessors of each item.
+    pub(crate) compact_arrays: bool,
+    /// Generate a typestate builder for each Rust table, which only builds after all fields are
+    /// set.
+    pub(crate) typestate_builders: bool,
+    /// Generate `eq_bytes`, `eq_slice`, `is_default`, `default_ref` and `DEFAULT_ENCODING` for
+    /// all Rust entities and readers.
+    pub(crate) byte_comparisons: bool,
+    /// Generate `validate` for all Rust builders, which returns all violations of the schema.
+    pub(crate) builder_validation: bool,
+    /// Generate `try_{ field }` for the Rust structs and tables, which verify the field first.
+    pub(crate) checked_getters: bool,
+    /// Generate `field_range`, `encode_with_zeroed_field`, `split_at_field` and `verify_fields`
+    /// for the Rust tables, and `item_range` for the Rust vectors.
+    pub(crate) field_access: bool,
+    /// Generate `MAX_ENCODED_SIZE` for the Rust entities and readers of the bounded types.
+    pub(crate) max_encoded_size: bool,
+    /// Only export these types and the types they depend on.
+    ///
+    /// It's only used by the exporters, such as OpenAPI; if it's empty, all types are exported.
+    pub(crate) types: Vec<String>,
+    /// The flatten strategy for the SQL exporter.
+    pub(crate) flatten: Flatten,
+    /// The profile of the generated C code.
+    pub(crate) c_profile: CProfile,
+    /// The default TCP port of the Wireshark dissector.
+    pub(crate) dissector_port: Option<u16>,
+    /// Register the Wireshark dissector as a heuristic dissector of TCP.
+    pub(crate) dissector_heuristic: bool,
+    /// The imported schemas which are generated into other crates, and the Rust paths of them.
+    pub(crate) extern_schemas: HashMap<String, String>,
+    /// The visibility of the generated Rust items.
+    pub(crate) visibility: Visibility,
+    /// The edition of the crate which includes the generated Rust code.
+    pub(crate) edition: Edition,
+    /// The lints which are allowed on each generated Rust item.
+    pub(crate) allows: Vec<String>,
+    /// Format the generated Rust code.
+    pub(crate) format: bool,
+    /// The banner at the top of the generated files, which is rendered from the template.
+    pub(crate) header: Option<String>,
+    /// The code which is injected at the anchors of the generated files.
+    pub(crate) hooks: hooks::Hooks,
+    /// The directory of the templates which override the built-in ones.
+    pub(crate) templates_dir: Option<PathBuf>,
+}
 
 #[derive(Debug)]
 pub(crate) struct Generator {
@@ -17,7 +195,16 @@ impl Generator {
         Self { ast }
     }
 
-    pub(crate) fn generate<W: io::Write>(&self, lang: Language, writer: &mut W) -> io::Result<()> {
-        lang.generate(writer, &self.ast)
+    pub(crate) fn check_types(&self, lang: Language, types: &[String]) -> Result<(), String> {
+        lang.check_types(&self.ast, types)
+    }
+
+    pub(crate) fn generate<W: io::Write>(
+        &self,
+        lang: Language,
+        writer: &mut W,
+        options: &Options,
+    ) -> io::Result<()> {
+        lang.generate(writer, &self.ast, options)
     }
 }
@@ -0,0 +1,393 @@
+//! Render the generated files from the data models through templates.
+//!
+//! The templates are a subset of Jinja: `{{ a.b }}` prints a value, `{% for x in a.b %}` loops
+//! over an array with `loop.first`, `loop.last` and `loop.index0`, `{% if [not] a.b %}` with an
+//! optional `{% else %}` tests a value, `{% include a.b %}` or `{% include "name" %}` renders
+//! another template in the same context, and `{# ... #}` is a comment. As Jinja does with
+//! `trim_blocks` and `lstrip_blocks`, the newline after a block tag and the indent before it are
+//! removed, so a line which only has a tag doesn't leave a blank line.
+//!
+//! The built-in templates are named by their paths, such as `imhex/file.hexpat`; a template in
+//! the templates directory at the same path overrides the built-in one, so the output could be
+//! changed without changing the generators.
+
+use std::{fs, io, path::Path};
+
+use serde_json::{json, Value};
+
+const BUILTIN: &[(&str, &str)] = &[
+    (
+        "imhex/file.hexpat",
+        include_str!("templates/imhex/file.hexpat"),
+    ),
+    (
+        "imhex/dynamic_header.hexpat",
+        include_str!("templates/imhex/dynamic_header.hexpat"),
+    ),
+    (
+        "imhex/option.hexpat",
+        include_str!("templates/imhex/option.hexpat"),
+    ),
+    (
+        "imhex/union.hexpat",
+        include_str!("templates/imhex/union.hexpat"),
+    ),
+    (
+        "imhex/array.hexpat",
+        include_str!("templates/imhex/array.hexpat"),
+    ),
+    (
+        "imhex/struct.hexpat",
+        include_str!("templates/imhex/struct.hexpat"),
+    ),
+    (
+        "imhex/fixvec.hexpat",
+        include_str!("templates/imhex/fixvec.hexpat"),
+    ),
+    (
+        "imhex/dynvec.hexpat",
+        include_str!("templates/imhex/dynvec.hexpat"),
+    ),
+    (
+        "imhex/table.hexpat",
+        include_str!("templates/imhex/table.hexpat"),
+    ),
+];
+
+#[derive(Debug)]
+enum Expr {
+    Literal(String),
+    Path(Vec<String>),
+}
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Print(Expr),
+    For {
+        var: String,
+        iter: Expr,
+        body: Vec<Node>,
+    },
+    If {
+        negated: bool,
+        cond: Expr,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    Include(Expr),
+}
+
+#[derive(Debug)]
+enum Token<'a> {
+    Text(&'a str),
+    Print(&'a str),
+    Tag(&'a str),
+}
+
+/// Split a template into the texts and the tags, and trim the lines of the block tags.
+fn tokenize<'a>(name: &str, source: &'a str) -> Vec<Token<'a>> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    while let Some(start) = rest.find('{') {
+        let (close, is_block) = match rest[start..].get(..2) {
+            Some("{{") => ("}}", false),
+            Some("{%") => ("%}", true),
+            Some("{#") => ("#}", true),
+            _ => {
+                tokens.push(Token::Text(&rest[..=start]));
+                rest = &rest[start + 1..];
+                continue;
+            }
+        };
+        let mut text = &rest[..start];
+        if is_block {
+            // The indent before a block tag is removed.
+            let line_start = text.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+            if text[line_start..].trim().is_empty() {
+                text = &text[..line_start];
+            }
+        }
+        tokens.push(Token::Text(text));
+        let end = rest[start + 2..]
+            .find(close)
+            .map(|idx| start + 2 + idx)
+            .unwrap_or_else(|| panic!("the template [{}] has an unclosed tag", name));
+        let content = rest[start + 2..end].trim();
+        rest = &rest[end + 2..];
+        match &close[..1] {
+            "}" => tokens.push(Token::Print(content)),
+            "%" => tokens.push(Token::Tag(content)),
+            _ => {}
+        }
+        if is_block {
+            // The newline after a block tag is removed.
+            rest = rest
+                .strip_prefix("\r\n")
+                .or_else(|| rest.strip_prefix('\n'))
+                .unwrap_or(rest);
+        }
+    }
+    tokens.push(Token::Text(rest));
+    tokens
+}
+
+fn parse_expr(name: &str, expr: &str) -> Expr {
+    if let Some(literal) = expr
+        .strip_prefix('"')
+        .and_then(|expr| expr.strip_suffix('"'))
+    {
+        return Expr::Literal(literal.to_owned());
+    }
+    let path: Vec<_> = expr.split('.').map(|part| part.trim().to_owned()).collect();
+    let is_valid = path.iter().all(|part| {
+        !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    });
+    if !is_valid {
+        panic!(
+            "the template [{}] has an invalid expression `{}`",
+            name, expr
+        );
+    }
+    Expr::Path(path)
+}
+
+/// Parse the nodes until one of the tags in `ends`, and return the nodes and the end tag.
+fn parse_nodes<'a>(
+    name: &str,
+    tokens: &mut impl Iterator<Item = Token<'a>>,
+    ends: &[&str],
+) -> (Vec<Node>, Option<&'a str>) {
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.next() {
+        let tag = match token {
+            Token::Text(text) => {
+                if !text.is_empty() {
+                    nodes.push(Node::Text(text.to_owned()));
+                }
+                continue;
+            }
+            Token::Print(expr) => {
+                nodes.push(Node::Print(parse_expr(name, expr)));
+                continue;
+            }
+            Token::Tag(tag) => tag,
+        };
+        let (keyword, args) = tag.split_once(' ').unwrap_or((tag, ""));
+        let args = args.trim();
+        match keyword {
+            _ if ends.contains(&keyword) => return (nodes, Some(keyword)),
+            "for" => {
+                let (var, iter) = args
+                    .split_once(" in ")
+                    .unwrap_or_else(|| panic!("the template [{}] has an invalid loop", name));
+                let (body, _) = parse_block(name, tokens, &["endfor"]);
+                nodes.push(Node::For {
+                    var: var.trim().to_owned(),
+                    iter: parse_expr(name, iter.trim()),
+                    body,
+                });
+            }
+            "if" => {
+                let (negated, cond) = match args.strip_prefix("not ") {
+                    Some(cond) => (true, cond.trim()),
+                    None => (false, args),
+                };
+                let (then, end) = parse_block(name, tokens, &["else", "endif"]);
+                let otherwise = if end == "else" {
+                    parse_block(name, tokens, &["endif"]).0
+                } else {
+                    Vec::new()
+                };
+                nodes.push(Node::If {
+                    negated,
+                    cond: parse_expr(name, cond),
+                    then,
+                    otherwise,
+                });
+            }
+            "include" => nodes.push(Node::Include(parse_expr(name, args))),
+            _ => panic!("the template [{}] has an unknown tag `{}`", name, tag),
+        }
+    }
+    (nodes, None)
+}
+
+fn parse_block<'a>(
+    name: &str,
+    tokens: &mut impl Iterator<Item = Token<'a>>,
+    ends: &[&str],
+) -> (Vec<Node>, &'a str) {
+    let (nodes, end) = parse_nodes(name, tokens, ends);
+    let end = end.unwrap_or_else(|| {
+        panic!(
+            "the template [{}] doesn't close the block by `{}`",
+            name,
+            ends.last().unwrap()
+        )
+    });
+    (nodes, end)
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(value) => *value,
+        Value::Number(number) => number.as_f64() != Some(0.0),
+        Value::String(string) => !string.is_empty(),
+        Value::Array(array) => !array.is_empty(),
+        Value::Object(object) => !object.is_empty(),
+    }
+}
+
+/// The templates, which are read from the templates directory or the built-in ones.
+pub(crate) struct Templates<'a> {
+    dir: Option<&'a Path>,
+}
+
+/// The variables of the loops, the innermost last.
+type Locals = Vec<(String, Value)>;
+
+impl<'a> Templates<'a> {
+    pub(crate) fn new(dir: Option<&'a Path>) -> Self {
+        Self { dir }
+    }
+
+    fn source(&self, name: &str) -> String {
+        let path = self
+            .dir
+            .map(|dir| dir.join(name))
+            .filter(|path| path.is_file());
+        if let Some(path) = path {
+            return fs::read_to_string(&path).unwrap_or_else(|err| {
+                panic!("failed to read the template [{}]: {}", path.display(), err)
+            });
+        }
+        BUILTIN
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, source)| (*source).to_owned())
+            .unwrap_or_else(|| panic!("the template [{}] is not found", name))
+    }
+
+    /// Render the template with the data model.
+    pub(crate) fn render<W: io::Write>(
+        &self,
+        writer: &mut W,
+        name: &str,
+        context: &Value,
+    ) -> io::Result<()> {
+        self.render_template(writer, name, context, &mut Vec::new())
+    }
+
+    fn render_template<W: io::Write>(
+        &self,
+        writer: &mut W,
+        name: &str,
+        context: &Value,
+        locals: &mut Locals,
+    ) -> io::Result<()> {
+        let source = self.source(name);
+        let mut tokens = tokenize(name, &source).into_iter();
+        let (nodes, end) = parse_nodes(name, &mut tokens, &[]);
+        if let Some(end) = end {
+            panic!("the template [{}] has an unexpected `{}`", name, end);
+        }
+        self.render_nodes(writer, name, &nodes, context, locals)
+    }
+
+    fn render_nodes<W: io::Write>(
+        &self,
+        writer: &mut W,
+        name: &str,
+        nodes: &[Node],
+        context: &Value,
+        locals: &mut Locals,
+    ) -> io::Result<()> {
+        let eval = |expr: &Expr, locals: &Locals| eval(name, expr, context, locals);
+        for node in nodes {
+            match node {
+                Node::Text(text) => write!(writer, "{}", text)?,
+                Node::Print(expr) => match eval(expr, locals) {
+                    Value::String(string) => write!(writer, "{}", string)?,
+                    Value::Number(number) => write!(writer, "{}", number)?,
+                    Value::Bool(value) => write!(writer, "{}", value)?,
+                    Value::Null => {}
+                    _ => panic!(
+                        "the template [{}] prints a value which is not a string or a number",
+                        name
+                    ),
+                },
+                Node::For { var, iter, body } => {
+                    let items = match eval(iter, locals) {
+                        Value::Array(items) => items,
+                        _ => panic!(
+                            "the template [{}] loops over a value which is not an array",
+                            name
+                        ),
+                    };
+                    let count = items.len();
+                    for (index, item) in items.into_iter().enumerate() {
+                        let state = json!({
+                            "first": index == 0,
+                            "last": index + 1 == count,
+                            "index0": index,
+                        });
+                        locals.push(("loop".to_owned(), state));
+                        locals.push((var.to_owned(), item));
+                        self.render_nodes(writer, name, body, context, locals)?;
+                        locals.truncate(locals.len() - 2);
+                    }
+                }
+                Node::If {
+                    negated,
+                    cond,
+                    then,
+                    otherwise,
+                } => {
+                    let nodes = if is_truthy(&eval(cond, locals)) != *negated {
+                        then
+                    } else {
+                        otherwise
+                    };
+                    self.render_nodes(writer, name, nodes, context, locals)?;
+                }
+                Node::Include(expr) => {
+                    let included = match eval(expr, locals) {
+                        Value::String(included) => included,
+                        _ => panic!(
+                            "the template [{}] includes a name which is not a string",
+                            name
+                        ),
+                    };
+                    self.render_template(writer, &included, context, locals)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Look up a path in the variables of the loops, and then in the data model.
+fn eval(name: &str, expr: &Expr, context: &Value, locals: &Locals) -> Value {
+    let path = match expr {
+        Expr::Literal(literal) => return Value::String(literal.clone()),
+        Expr::Path(path) => path,
+    };
+    let mut value = locals
+        .iter()
+        .rev()
+        .find(|(local, _)| *local == path[0])
+        .map(|(_, value)| value)
+        .or_else(|| context.get(&path[0]));
+    for part in &path[1..] {
+        value = value.and_then(|value| value.get(part));
+    }
+    value.cloned().unwrap_or_else(|| {
+        panic!(
+            "the template [{}] refers to an unknown value `{}`",
+            name,
+            path.join(".")
+        )
+    })
+}
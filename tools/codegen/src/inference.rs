@@ -0,0 +1,312 @@
+use molecule::{unpack_number, NUMBER_SIZE};
+
+/// Propose a schema from sample buffers of a type, such as for the undocumented legacy data.
+///
+/// It's experimental: the shapes are guessed from the headers in the samples, and the names are
+/// made up from the positions, so the schema is only a starting point for the documentation.
+///
+/// - The samples whose headers are a total size and offsets are tables, if they all have the same
+///   count of fields; otherwise they're dynamic vectors.
+/// - The samples whose headers are an item count, which splits the rest into items of the same
+///   size, are fixed vectors.
+/// - The samples which have the same size are arrays of bytes; the fields of a struct can't be
+///   told apart without the headers.
+/// - The empty samples make an option; the samples which start with small item IDs make a union.
+pub struct Inferrer {
+    type_name: Option<String>,
+}
+
+impl Default for Inferrer {
+    fn default() -> Self {
+        Inferrer::new()
+    }
+}
+
+/// The most item IDs of an inferred union, since any bytes could start with an item ID.
+const MAX_UNION_ITEMS: usize = 16;
+
+#[derive(Debug)]
+enum Shape {
+    Array(usize),
+    FixVec(usize),
+    DynVec(Box<Shape>),
+    Table(Vec<Shape>),
+    Option_(Box<Shape>),
+    Union(Vec<Option<Shape>>),
+    /// It's written as `Bytes`, with a warning.
+    Unknown,
+}
+
+/// Read the offsets of a table or a dynamic vector, with the total size at the end.
+#[allow(clippy::manual_is_multiple_of)]
+fn offsets(sample: &[u8]) -> Option<Vec<usize>> {
+    if sample.len() < NUMBER_SIZE || unpack_number(sample) as usize != sample.len() {
+        return None;
+    }
+    if sample.len() == NUMBER_SIZE {
+        return Some(vec![NUMBER_SIZE]);
+    }
+    if sample.len() < NUMBER_SIZE * 2 {
+        return None;
+    }
+    let header = unpack_number(&sample[NUMBER_SIZE..]) as usize;
+    if header % NUMBER_SIZE != 0 || header < NUMBER_SIZE * 2 || header > sample.len() {
+        return None;
+    }
+    let mut offsets = sample[NUMBER_SIZE..header]
+        .chunks(NUMBER_SIZE)
+        .map(|chunk| unpack_number(chunk) as usize)
+        .collect::<Vec<_>>();
+    offsets.push(sample.len());
+    if offsets.windows(2).all(|pair| pair[0] <= pair[1]) {
+        Some(offsets)
+    } else {
+        None
+    }
+}
+
+/// The item size of a fixed vector, which is none if it's empty.
+#[allow(clippy::manual_is_multiple_of)]
+fn fixvec_item_size(sample: &[u8]) -> Option<Option<usize>> {
+    if sample.len() < NUMBER_SIZE {
+        return None;
+    }
+    let count = unpack_number(sample) as usize;
+    let rest = sample.len() - NUMBER_SIZE;
+    if count == 0 {
+        return if rest == 0 { Some(None) } else { None };
+    }
+    if rest == 0 || rest % count != 0 {
+        return None;
+    }
+    Some(Some(rest / count))
+}
+
+struct Inference {
+    warnings: Vec<String>,
+}
+
+impl Inference {
+    fn infer(&mut self, path: &str, samples: &[&[u8]]) -> Shape {
+        if samples.is_empty() {
+            self.warnings
+                .push(format!("{} never appears in the samples", path));
+            return Shape::Unknown;
+        }
+        let non_empty = samples
+            .iter()
+            .copied()
+            .filter(|sample| !sample.is_empty())
+            .collect::<Vec<_>>();
+        if non_empty.len() < samples.len() {
+            let inner = if non_empty.is_empty() {
+                self.warnings
+                    .push(format!("{} is always none in the samples", path));
+                Shape::Unknown
+            } else {
+                self.infer(path, &non_empty)
+            };
+            return Shape::Option_(Box::new(inner));
+        }
+        if let Some(offsets) = samples
+            .iter()
+            .map(|sample| offsets(sample))
+            .collect::<Option<Vec<_>>>()
+        {
+            let items = samples
+                .iter()
+                .zip(offsets.iter())
+                .map(|(sample, offsets)| {
+                    offsets
+                        .windows(2)
+                        .map(|pair| &sample[pair[0]..pair[1]])
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            let count = items[0].len();
+            if items.iter().all(|fields| fields.len() == count) {
+                let fields = (0..count)
+                    .map(|index| {
+                        let field = items.iter().map(|fields| fields[index]).collect::<Vec<_>>();
+                        self.infer(&format!("{}.field{}", path, index), &field)
+                    })
+                    .collect();
+                return Shape::Table(fields);
+            }
+            let all = items.into_iter().flatten().collect::<Vec<_>>();
+            let item = self.infer(&format!("{}[]", path), &all);
+            return Shape::DynVec(Box::new(item));
+        }
+        if let Some(sizes) = samples
+            .iter()
+            .map(|sample| fixvec_item_size(sample))
+            .collect::<Option<Vec<_>>>()
+        {
+            let mut sizes = sizes.into_iter().flatten();
+            match sizes.next() {
+                None => return Shape::FixVec(1),
+                Some(size) if sizes.all(|other| other == size) => return Shape::FixVec(size),
+                _ => {}
+            }
+        }
+        let size = samples[0].len();
+        if samples.iter().all(|sample| sample.len() == size) {
+            return Shape::Array(size);
+        }
+        if samples.iter().all(|sample| {
+            sample.len() >= NUMBER_SIZE && (unpack_number(sample) as usize) < MAX_UNION_ITEMS
+        }) {
+            let max_id = samples
+                .iter()
+                .map(|sample| unpack_number(sample) as usize)
+                .max()
+                .unwrap_or_default();
+            let items = (0..=max_id)
+                .map(|id| {
+                    let inner = samples
+                        .iter()
+                        .filter(|sample| unpack_number(sample) as usize == id)
+                        .map(|sample| &sample[NUMBER_SIZE..])
+                        .collect::<Vec<_>>();
+                    if inner.is_empty() {
+                        None
+                    } else {
+                        Some(self.infer(&format!("{}#{}", path, id), &inner))
+                    }
+                })
+                .collect();
+            return Shape::Union(items);
+        }
+        self.warnings
+            .push(format!("{} doesn't match any type", path));
+        Shape::Unknown
+    }
+}
+
+/// The declarations of the inferred types in order, the dependencies are before the types.
+struct Declarations {
+    decls: Vec<(String, String)>,
+}
+
+impl Declarations {
+    fn push(&mut self, name: &str, decl: String) {
+        if !self.decls.iter().any(|(n, _)| n == name) {
+            self.decls.push((name.to_owned(), decl));
+        }
+    }
+
+    /// Declare the shape and return the name of it.
+    ///
+    /// The arrays and the fixed vectors are named by their structures, so they're shared, unless
+    /// they should have the name, such as the items of a union, which should be different.
+    fn declare(&mut self, shape: &Shape, name: &str, named: bool) -> String {
+        match shape {
+            Shape::Array(1) if !named => "byte".to_owned(),
+            Shape::Array(size) => {
+                let name = if named {
+                    name.to_owned()
+                } else {
+                    format!("Byte{}", size)
+                };
+                self.push(&name, format!("array {} [byte; {}];", name, size));
+                name
+            }
+            Shape::FixVec(1) | Shape::Unknown if !named => {
+                self.push("Bytes", "vector Bytes <byte>;".to_owned());
+                "Bytes".to_owned()
+            }
+            Shape::FixVec(1) | Shape::Unknown => {
+                self.push(name, format!("vector {} <byte>;", name));
+                name.to_owned()
+            }
+            Shape::FixVec(size) => {
+                let item = self.declare(&Shape::Array(*size), "", false);
+                let name = if named {
+                    name.to_owned()
+                } else {
+                    format!("{}Vec", item)
+                };
+                self.push(&name, format!("vector {} <{}>;", name, item));
+                name
+            }
+            Shape::DynVec(item) => {
+                let item = self.declare(item, &format!("{}Item", name), false);
+                self.push(name, format!("vector {} <{}>;", name, item));
+                name.to_owned()
+            }
+            Shape::Table(fields) => {
+                let mut decl = format!("table {} {{\n", name);
+                for (index, field) in fields.iter().enumerate() {
+                    let typ = self.declare(field, &format!("{}Field{}", name, index), false);
+                    decl.push_str(&format!("    field{}: {},\n", index, typ));
+                }
+                decl.push('}');
+                self.push(name, decl);
+                name.to_owned()
+            }
+            Shape::Option_(inner) => {
+                let inner = self.declare(inner, &format!("{}Inner", name), false);
+                self.push(name, format!("option {} ({});", name, inner));
+                name.to_owned()
+            }
+            Shape::Union(items) => {
+                let mut decl = format!("union {} {{\n", name);
+                for (id, item) in items.iter().enumerate() {
+                    let item_name = format!("{}Item{}", name, id);
+                    let typ = match item {
+                        Some(item) => self.declare(item, &item_name, true),
+                        // The IDs before the last one should still have items.
+                        None => self.declare(&Shape::FixVec(1), &item_name, true),
+                    };
+                    decl.push_str(&format!("    {},\n", typ));
+                }
+                decl.push('}');
+                self.push(name, decl);
+                name.to_owned()
+            }
+        }
+    }
+}
+
+impl Inferrer {
+    pub fn new() -> Self {
+        Self { type_name: None }
+    }
+
+    /// The name of the type of the samples, which is `Root` by default.
+    pub fn type_name<S: AsRef<str>>(&mut self, name: S) -> &mut Self {
+        self.type_name.replace(name.as_ref().to_owned());
+        self
+    }
+
+    /// Return the proposed schema, which starts with the warnings as comments.
+    ///
+    /// Return an error if there are no samples.
+    pub fn infer<T: AsRef<[u8]>>(&self, samples: &[T]) -> Result<String, String> {
+        if samples.is_empty() {
+            return Err("there are no samples".to_owned());
+        }
+        let type_name = self.type_name.as_deref().unwrap_or("Root");
+        let samples = samples.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+        let mut inference = Inference {
+            warnings: Vec::new(),
+        };
+        let shape = inference.infer(type_name, &samples);
+        let mut decls = Declarations { decls: Vec::new() };
+        decls.declare(&shape, type_name, true);
+        let mut schema = format!(
+            "// Inferred from {} samples of {}; the names and the types should be reviewed.\n",
+            samples.len(),
+            type_name
+        );
+        for warning in &inference.warnings {
+            schema.push_str(&format!("// Warning: {}.\n", warning));
+        }
+        for (_, decl) in &decls.decls {
+            schema.push('\n');
+            schema.push_str(decl);
+            schema.push('\n');
+        }
+        Ok(schema)
+    }
+}
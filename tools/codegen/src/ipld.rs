@@ -0,0 +1,337 @@
+//! Map the values of a schema to the IPLD data model and back, without generating code.
+//!
+//! The structs and the tables are maps by the names of their fields, the unions are maps of one
+//! entry by the names of their item types, the options are null or their values, and the other
+//! types are lists; the bytes are integers, so the arrays and the vectors of bytes are bytes.
+
+use std::{collections::BTreeMap, path::Path, rc::Rc};
+
+use molecule::{
+    error::VerificationResult, ipld::Ipld, pack_count, pack_number, unpack_count, unpack_number,
+    Number, NUMBER_SIZE,
+};
+
+use crate::{
+    ast::verified::{self as ast, HasName},
+    verifier, Parser,
+};
+
+/// Convert the values of a type of a schema file between the molecule encoding and IPLD.
+pub struct IpldBridge {
+    decl: Rc<ast::TopDecl>,
+}
+
+impl IpldBridge {
+    pub fn new<P: AsRef<Path>, S: AsRef<str>>(path: P, type_name: S) -> Self {
+        let ast = Parser::parse(&path);
+        let type_name = type_name.as_ref();
+        let decl = ast
+            .get_decl(type_name)
+            .unwrap_or_else(|| panic!("the type `{}` is not found", type_name));
+        Self { decl }
+    }
+
+    /// Verify the data in the strict mode, then convert it to a node.
+    pub fn to_ipld(&self, data: &[u8]) -> VerificationResult<Ipld> {
+        verifier::verify(&self.decl, data, false)?;
+        Ok(to_ipld(&self.decl, data))
+    }
+
+    /// Encode the node as the type, or return why it doesn't match the type.
+    pub fn from_ipld(&self, node: &Ipld) -> Result<Vec<u8>, String> {
+        let mut data = Vec::new();
+        from_ipld(&self.decl, node, &mut data)?;
+        Ok(data)
+    }
+}
+
+fn type_name(decl: &ast::TopDecl) -> &str {
+    if decl.is_atom() {
+        ast::ATOM_NAME
+    } else {
+        decl.name()
+    }
+}
+
+/// The items of a dynvec or the fields of a table, by the offsets in the header.
+fn dynamic_items(slice: &[u8]) -> Vec<&[u8]> {
+    if slice.len() == NUMBER_SIZE {
+        return Vec::new();
+    }
+    let count = unpack_number(&slice[NUMBER_SIZE..]) as usize / NUMBER_SIZE - 1;
+    let mut offsets = slice[NUMBER_SIZE..]
+        .chunks(NUMBER_SIZE)
+        .take(count)
+        .map(|x| unpack_number(x) as usize)
+        .collect::<Vec<_>>();
+    offsets.push(slice.len());
+    offsets.windows(2).map(|i| &slice[i[0]..i[1]]).collect()
+}
+
+/// Convert the verified slice.
+fn to_ipld(decl: &ast::TopDecl, slice: &[u8]) -> Ipld {
+    let list = |typ: &ast::TopDecl, items: Vec<&[u8]>| {
+        if typ.is_atom() {
+            Ipld::Bytes(items.concat())
+        } else {
+            Ipld::List(items.into_iter().map(|item| to_ipld(typ, item)).collect())
+        }
+    };
+    let map = |fields: &[ast::FieldDecl], items: Vec<&[u8]>| {
+        let entries = fields
+            .iter()
+            .zip(items)
+            .map(|(field, item)| (field.name.clone(), to_ipld(&field.typ, item)))
+            .collect();
+        Ipld::Map(entries)
+    };
+    match decl {
+        ast::TopDecl::Atom(_) => Ipld::Integer(i64::from(slice[0])),
+        ast::TopDecl::Option_(ref typ) => {
+            if slice.is_empty() {
+                Ipld::Null
+            } else {
+                to_ipld(&typ.typ, slice)
+            }
+        }
+        ast::TopDecl::Union(ref typ) => {
+            let item = &typ.inner[unpack_number(slice) as usize];
+            let mut entries = BTreeMap::new();
+            entries.insert(
+                type_name(&item.typ).to_owned(),
+                to_ipld(&item.typ, &slice[NUMBER_SIZE..]),
+            );
+            Ipld::Map(entries)
+        }
+        ast::TopDecl::Array(ref typ) => list(&typ.typ, slice.chunks(typ.item_size).collect()),
+        ast::TopDecl::Struct(ref typ) => {
+            let mut offset = 0;
+            let items = typ
+                .field_size
+                .iter()
+                .map(|size| {
+                    offset += size;
+                    &slice[offset - size..offset]
+                })
+                .collect();
+            map(&typ.inner, items)
+        }
+        ast::TopDecl::FixVec(ref typ) => {
+            let count = unpack_count(slice, typ.count_size);
+            let items = slice[typ.count_size..]
+                .chunks(typ.item_size)
+                .take(count)
+                .collect();
+            list(&typ.typ, items)
+        }
+        ast::TopDecl::DynVec(ref typ) => list(&typ.typ, dynamic_items(slice)),
+        ast::TopDecl::Table(ref typ) => map(&typ.inner, dynamic_items(slice)),
+    }
+}
+
+fn node_kind(node: &Ipld) -> &'static str {
+    match node {
+        Ipld::Null => "null",
+        Ipld::Bool(_) => "a bool",
+        Ipld::Integer(_) => "an integer",
+        Ipld::Float(_) => "a float",
+        Ipld::String(_) => "a string",
+        Ipld::Bytes(_) => "bytes",
+        Ipld::List(_) => "a list",
+        Ipld::Map(_) => "a map",
+        Ipld::Link(_) => "a link",
+    }
+}
+
+/// Write the items of a list node, or the bytes of a bytes node if the items are bytes, and
+/// return the count of them.
+fn list_from_ipld(
+    name: &str,
+    typ: &ast::TopDecl,
+    node: &Ipld,
+    data: &mut Vec<u8>,
+) -> Result<usize, String> {
+    match node {
+        Ipld::Bytes(bytes) if typ.is_atom() => {
+            data.extend_from_slice(bytes);
+            Ok(bytes.len())
+        }
+        Ipld::List(items) if !typ.is_atom() => {
+            for (i, item) in items.iter().enumerate() {
+                from_ipld(typ, item, data).map_err(|err| format!("{}[{}]: {}", name, i, err))?;
+            }
+            Ok(items.len())
+        }
+        _ => Err(format!(
+            "{} expects {}, but it is {}",
+            name,
+            if typ.is_atom() { "bytes" } else { "a list" },
+            node_kind(node)
+        )),
+    }
+}
+
+/// The nodes of the fields in a map node, in the order of the fields.
+fn fields_from_ipld<'a>(
+    name: &str,
+    fields: &[ast::FieldDecl],
+    node: &'a Ipld,
+) -> Result<Vec<&'a Ipld>, String> {
+    let entries = match node {
+        Ipld::Map(entries) => entries,
+        _ => {
+            return Err(format!(
+                "{} expects a map, but it is {}",
+                name,
+                node_kind(node)
+            ))
+        }
+    };
+    if let Some(key) = entries
+        .keys()
+        .find(|key| fields.iter().all(|field| &field.name != *key))
+    {
+        return Err(format!("{} has no field ({})", name, key));
+    }
+    fields
+        .iter()
+        .map(|field| {
+            entries
+                .get(&field.name)
+                .ok_or_else(|| format!("{} misses the field ({})", name, field.name))
+        })
+        .collect()
+}
+
+/// Write a header of the total size and the offsets, then the items.
+fn write_dynamic(items: Vec<Vec<u8>>, data: &mut Vec<u8>) {
+    let header_size = NUMBER_SIZE * (items.len() + 1);
+    let total_size = header_size + items.iter().map(Vec::len).sum::<usize>();
+    data.extend_from_slice(&pack_number(total_size as Number));
+    let mut offset = header_size;
+    for item in &items {
+        data.extend_from_slice(&pack_number(offset as Number));
+        offset += item.len();
+    }
+    for item in items {
+        data.extend(item);
+    }
+}
+
+fn from_ipld(decl: &ast::TopDecl, node: &Ipld, data: &mut Vec<u8>) -> Result<(), String> {
+    let name = type_name(decl);
+    match decl {
+        ast::TopDecl::Atom(_) => match node {
+            Ipld::Integer(value) if (0..=255).contains(value) => data.push(*value as u8),
+            Ipld::Integer(value) => {
+                return Err(format!(
+                    "{} expects an integer in 0..=255, but it is {}",
+                    name, value
+                ))
+            }
+            _ => {
+                return Err(format!(
+                    "{} expects an integer in 0..=255, but it is {}",
+                    name,
+                    node_kind(node)
+                ))
+            }
+        },
+        ast::TopDecl::Option_(ref typ) => {
+            if *node != Ipld::Null {
+                from_ipld(&typ.typ, node, data)?;
+            }
+        }
+        ast::TopDecl::Union(ref typ) => {
+            let entry = match node {
+                Ipld::Map(entries) if entries.len() == 1 => entries.iter().next().unwrap(),
+                _ => {
+                    return Err(format!(
+                        "{} expects a map of one entry, but it is {}",
+                        name,
+                        node_kind(node)
+                    ))
+                }
+            };
+            let (item_id, item) = typ
+                .inner
+                .iter()
+                .enumerate()
+                .find(|(_, item)| type_name(&item.typ) == entry.0)
+                .ok_or_else(|| format!("{} has no item ({})", name, entry.0))?;
+            data.extend_from_slice(&pack_number(item_id as Number));
+            from_ipld(&item.typ, entry.1, data)?;
+        }
+        ast::TopDecl::Array(ref typ) => {
+            let count = list_from_ipld(name, &typ.typ, node, data)?;
+            if count != typ.item_count {
+                return Err(format!(
+                    "{} expects {} items, but it has {}",
+                    name, typ.item_count, count
+                ));
+            }
+        }
+        ast::TopDecl::Struct(ref typ) => {
+            for (field, node) in typ
+                .inner
+                .iter()
+                .zip(fields_from_ipld(name, &typ.inner, node)?)
+            {
+                from_ipld(&field.typ, node, data)
+                    .map_err(|err| format!("{}.{}: {}", name, field.name, err))?;
+            }
+        }
+        ast::TopDecl::FixVec(ref typ) => {
+            let mut items = Vec::new();
+            let count = list_from_ipld(name, &typ.typ, node, &mut items)?;
+            let header = pack_count(count, typ.count_size).ok_or_else(|| {
+                format!(
+                    "{} has at most {} items, but it has {}",
+                    name,
+                    typ.max_item_count(),
+                    count
+                )
+            })?;
+            data.extend_from_slice(&header[..typ.count_size]);
+            data.extend(items);
+        }
+        ast::TopDecl::DynVec(ref typ) => {
+            let items = match node {
+                Ipld::List(items) => items,
+                _ => {
+                    return Err(format!(
+                        "{} expects a list, but it is {}",
+                        name,
+                        node_kind(node)
+                    ))
+                }
+            };
+            let items = items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let mut data = Vec::new();
+                    from_ipld(&typ.typ, item, &mut data)
+                        .map(|_| data)
+                        .map_err(|err| format!("{}[{}]: {}", name, i, err))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            write_dynamic(items, data);
+        }
+        ast::TopDecl::Table(ref typ) => {
+            let items = typ
+                .inner
+                .iter()
+                .zip(fields_from_ipld(name, &typ.inner, node)?)
+                .map(|(field, node)| {
+                    let mut data = Vec::new();
+                    from_ipld(&field.typ, node, &mut data)
+                        .map(|_| data)
+                        .map_err(|err| format!("{}.{}: {}", name, field.name, err))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            write_dynamic(items, data);
+        }
+    }
+    Ok(())
+}
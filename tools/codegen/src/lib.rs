@@ -1,16 +1,52 @@
 #![recursion_limit = "256"]
 
 pub(crate) mod ast;
+pub(crate) mod bounds;
 pub(crate) mod compiler;
+pub(crate) mod diagnostics;
+pub(crate) mod editor;
+pub(crate) mod examples;
+pub(crate) mod explain;
+pub(crate) mod explorer;
 pub(crate) mod generator;
+pub(crate) mod inference;
+#[cfg(feature = "ipld")]
+pub(crate) mod ipld;
+pub(crate) mod lints;
+pub(crate) mod migration;
+pub(crate) mod minimizer;
+pub(crate) mod mutator;
 pub(crate) mod parser;
+pub(crate) mod salvage;
+pub(crate) mod schema_hash;
+pub(crate) mod stats;
+pub(crate) mod suggestions;
+pub(crate) mod tree_sitter;
 pub(crate) mod utils;
+pub(crate) mod verifier;
 
 pub(crate) use ast::verified::Ast;
+pub use bounds::max_encoded_sizes;
 pub use compiler::Compiler;
+pub use diagnostics::{build_finished_message, check_syntax, SyntaxError};
+pub use editor::{editor_files, syntax, Syntax};
+pub use examples::{ExampleLanguage, Examples};
+pub use explorer::Explorer;
 pub(crate) use generator::Generator;
-pub use generator::Language;
+pub use generator::{CProfile, Edition, Flatten, Language, Visibility};
+pub use inference::Inferrer;
+#[cfg(feature = "ipld")]
+pub use ipld::IpldBridge;
+pub use lints::{find_lint, lint, Diagnostic, Level, Lint, LintLevels, LINTS, WARNINGS};
+pub use migration::Migration;
+pub use minimizer::Minimizer;
+pub use mutator::{Mutation, Mutator};
 pub(crate) use parser::Parser;
+pub use salvage::{PartialContent, PartialValue};
+pub use schema_hash::schema_hash;
+pub use stats::{schema_stats, TypeStats};
+pub use suggestions::{suggest, Suggestion};
+pub use verifier::Verifier;
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
-pub const C_API_VERSION_MIN: &str = "0.4.0";
+pub const C_API_VERSION_MIN: &str = "0.4.3";
@@ -0,0 +1,246 @@
+use std::{collections::HashMap, fmt, fs, path::Path};
+
+use crate::{
+    ast::verified::{self as ast, HasName},
+    diagnostics::{compiler_message, Span},
+    editor,
+    suggestions::suggest_for,
+    Parser,
+};
+
+/// The name which sets the level of all warnings, as `--deny warnings`.
+pub const WARNINGS: &str = "warnings";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lint {
+    /// A stable code, such as `M0001`.
+    pub code: &'static str,
+    /// A stable name, such as `non-camel-case-types`.
+    pub name: &'static str,
+    pub default_level: Level,
+    pub description: &'static str,
+}
+
+/// All lints; the codes are never reused.
+///
+/// The lints which suggest refactors are allowed by default, since the refactors break the
+/// compatibility.
+pub const LINTS: &[Lint] = &[
+    Lint {
+        code: "M0001",
+        name: "non-camel-case-types",
+        default_level: Level::Warn,
+        description: "the names of the types should be in UpperCamelCase",
+    },
+    Lint {
+        code: "M0002",
+        name: "non-snake-case-fields",
+        default_level: Level::Warn,
+        description: "the names of the fields should be in snake_case",
+    },
+    Lint {
+        code: "M0003",
+        name: "union-single-item",
+        default_level: Level::Allow,
+        description: "a union has only one item",
+    },
+    Lint {
+        code: "M0004",
+        name: "dynvec-of-constant-size",
+        default_level: Level::Allow,
+        description: "a dynamic vector of tables which always have the same size",
+    },
+    Lint {
+        code: "M0005",
+        name: "table-to-struct",
+        default_level: Level::Allow,
+        description: "a table of fixed-size fields",
+    },
+    Lint {
+        code: "M0006",
+        name: "table-single-field",
+        default_level: Level::Allow,
+        description: "a table has only one field",
+    },
+];
+
+/// Find a lint by its name or its code.
+pub fn find_lint(name: &str) -> Option<&'static Lint> {
+    LINTS
+        .iter()
+        .find(|lint| lint.name == name || lint.code == name)
+}
+
+/// The levels of the lints, which override the default levels.
+#[derive(Debug, Clone, Default)]
+pub struct LintLevels {
+    deny_warnings: bool,
+    levels: HashMap<&'static str, Level>,
+}
+
+impl LintLevels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deny the lints whose levels are `Warn`, after the overrides.
+    pub fn deny_warnings(&mut self, enable: bool) -> &mut Self {
+        self.deny_warnings = enable;
+        self
+    }
+
+    /// Set the level of a lint by its name or its code.
+    pub fn set<S: AsRef<str>>(&mut self, name: S, level: Level) -> &mut Self {
+        let name = name.as_ref();
+        let lint = find_lint(name).unwrap_or_else(|| panic!("the lint `{}` is not found", name));
+        self.levels.insert(lint.name, level);
+        self
+    }
+
+    pub fn level_of(&self, lint: &Lint) -> Level {
+        let level = self
+            .levels
+            .get(lint.name)
+            .copied()
+            .unwrap_or(lint.default_level);
+        if level == Level::Warn && self.deny_warnings {
+            Level::Deny
+        } else {
+            level
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub lint: &'static Lint,
+    /// It's never `Allow`.
+    pub level: Level,
+    pub type_name: String,
+    pub message: String,
+    pub file_name: String,
+    /// The line and the column of the declaration of the type, if it's found.
+    pub position: Option<(usize, usize)>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let level = if self.level == Level::Deny {
+            "error"
+        } else {
+            "warning"
+        };
+        write!(
+            f,
+            "{}[{}]: {}: {} [{}]",
+            level, self.lint.code, self.type_name, self.message, self.lint.name
+        )
+    }
+}
+
+impl Diagnostic {
+    pub fn json_message(&self) -> String {
+        let level = if self.level == Level::Deny {
+            "error"
+        } else {
+            "warning"
+        };
+        let span = self.position.map(|(line, column)| Span {
+            file_name: &self.file_name,
+            line,
+            column,
+        });
+        compiler_message(
+            level,
+            Some(self.lint.code),
+            &self.message,
+            span,
+            &self.to_string(),
+        )
+    }
+}
+
+/// Check the types in a schema file, and return the diagnostics which are not allowed.
+///
+/// The imported types are not checked.
+pub fn lint<P: AsRef<Path>>(path: P, levels: &LintLevels) -> Vec<Diagnostic> {
+    let ast = Parser::parse(&path);
+    let content = fs::read_to_string(&path).unwrap();
+    let file_name = path.as_ref().to_string_lossy();
+    let mut found = Vec::new();
+    for decl in ast.major_decls() {
+        let name = decl.name();
+        if !is_camel_case(name) {
+            let message = format!("the type name `{}` is not in UpperCamelCase", name);
+            found.push(("non-camel-case-types", name.to_owned(), message));
+        }
+        let fields = match decl.as_ref() {
+            ast::TopDecl::Struct(ref typ) => &typ.inner[..],
+            ast::TopDecl::Table(ref typ) => &typ.inner[..],
+            _ => &[],
+        };
+        for field in fields {
+            if !is_snake_case(&field.name) {
+                let message = format!("the field name `{}` is not in snake_case", field.name);
+                found.push(("non-snake-case-fields", name.to_owned(), message));
+            }
+        }
+    }
+    for suggestion in suggest_for(&ast) {
+        found.push((suggestion.rule, suggestion.type_name, suggestion.message));
+    }
+    found
+        .into_iter()
+        .filter_map(|(name, type_name, message)| {
+            let lint = find_lint(name).unwrap();
+            let level = levels.level_of(lint);
+            if level == Level::Allow {
+                None
+            } else {
+                Some(Diagnostic {
+                    lint,
+                    level,
+                    position: declaration_position(&content, &type_name),
+                    type_name,
+                    message,
+                    file_name: file_name.clone().into_owned(),
+                })
+            }
+        })
+        .collect()
+}
+
+/// Find the line and the column of the name in a declaration, such as `table Name {`.
+fn declaration_position(content: &str, name: &str) -> Option<(usize, usize)> {
+    let keywords = editor::syntax().keywords;
+    content.lines().enumerate().find_map(|(idx, line)| {
+        let trimmed = line.trim_start();
+        let rest = keywords
+            .iter()
+            .find_map(|keyword| trimmed.strip_prefix(keyword.as_str()))?;
+        let declared = rest.trim_start();
+        let is_name = rest.starts_with(char::is_whitespace)
+            && declared.starts_with(name)
+            && !declared[name.len()..].starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_');
+        if is_name {
+            Some((idx + 1, line.len() - declared.len() + 1))
+        } else {
+            None
+        }
+    })
+}
+
+fn is_camel_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase()) && !name.contains('_')
+}
+
+fn is_snake_case(name: &str) -> bool {
+    !name.contains(|c: char| c.is_ascii_uppercase()) && !name.starts_with('_')
+}
@@ -0,0 +1,584 @@
+//! Shims which convert the values between two versions of a schema.
+//!
+//! The types of the two versions are paired by names, and so are the fields. The mapping file
+//! declares the types and the fields which are renamed or removed, and the values of the fields
+//! which only exist in one version:
+//!
+//! ```json
+//! {
+//!     "types": { "OldName": "NewName", "Removed": null },
+//!     "fields": { "OldName.old_field": "new_field" },
+//!     "defaults": { "Type.field": "0x0a000000" }
+//! }
+//! ```
+//!
+//! The fields are renamed by the names of the types and the fields in the old version. The key of a
+//! default is the type and the field in the version which has the field; without a default, the
+//! field is filled with the default value of its type.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use serde_json::Value;
+
+use crate::{
+    ast::verified::{self as ast, HasName},
+    generator, verifier, Parser,
+};
+
+/// Generate Rust shims between two versions of a schema: `upgrade::type_name(old_bytes)` returns
+/// the new entity, and `downgrade::type_name(new_bytes)` returns the old one.
+///
+/// Each type which exists in both versions gets a shim in each direction, named after the type in
+/// the source version. The values are re-encoded field by field: the new fields are filled with
+/// the defaults and the removed fields are dropped.
+pub struct Migration {
+    old_file_path: Option<PathBuf>,
+    new_file_path: Option<PathBuf>,
+    mapping_file: Option<PathBuf>,
+    old_module: String,
+    new_module: String,
+}
+
+impl Default for Migration {
+    fn default() -> Self {
+        Migration::new()
+    }
+}
+
+impl Migration {
+    pub fn new() -> Self {
+        Self {
+            old_file_path: None,
+            new_file_path: None,
+            mapping_file: None,
+            old_module: "super::old".to_owned(),
+            new_module: "super::new".to_owned(),
+        }
+    }
+
+    pub fn old_file_path<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.old_file_path.replace(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn new_file_path<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.new_file_path.replace(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Read the renamed types and fields, and the defaults from a JSON file.
+    pub fn mapping_file<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.mapping_file.replace(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// The Rust path of the module which includes the generated code of the old schema.
+    ///
+    /// It's `super::old` by default; a relative path is relative to the module which includes the
+    /// shims.
+    pub fn old_module<S: AsRef<str>>(&mut self, path: S) -> &mut Self {
+        self.old_module = path.as_ref().to_owned();
+        self
+    }
+
+    /// The Rust path of the module which includes the generated code of the new schema.
+    ///
+    /// It's `super::new` by default.
+    pub fn new_module<S: AsRef<str>>(&mut self, path: S) -> &mut Self {
+        self.new_module = path.as_ref().to_owned();
+        self
+    }
+
+    pub fn generate<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        let old = Parser::parse(self.old_file_path.as_ref().unwrap());
+        let new = Parser::parse(self.new_file_path.as_ref().unwrap());
+        let mapping = match self.mapping_file {
+            Some(ref path) => Mapping::load(path),
+            None => Mapping::default(),
+        };
+        let plan = Plan::new(&old, &new, &mapping);
+        generator::generate_migration(writer, &plan, &self.old_module, &self.new_module)
+    }
+}
+
+/// The renamed types and fields, and the defaults, which are read from the mapping file.
+#[derive(Debug, Default)]
+struct Mapping {
+    /// The new names of the old types; `None` if the type is removed.
+    types: HashMap<String, Option<String>>,
+    /// The new names of the fields, by `OldType.old_field`.
+    fields: HashMap<String, String>,
+    /// The values of the fields, by `Type.field`.
+    defaults: HashMap<String, Vec<u8>>,
+}
+
+impl Mapping {
+    fn load(path: &Path) -> Self {
+        let content = fs::read_to_string(path).unwrap_or_else(|err| {
+            panic!(
+                "failed to read the mapping file [{}]: {}",
+                path.display(),
+                err
+            )
+        });
+        let value: Value = serde_json::from_str(&content).unwrap_or_else(|err| {
+            panic!(
+                "failed to parse the mapping file [{}]: {}",
+                path.display(),
+                err
+            )
+        });
+        let mut mapping = Self::default();
+        for (key, value) in object(&value, "the mapping") {
+            match key.as_str() {
+                "types" => {
+                    for (old, new) in object(value, "`types`") {
+                        let new = match new {
+                            Value::String(new) => Some(new.to_owned()),
+                            Value::Null => None,
+                            _ => panic!("the new name of `{}` should be a string or null", old),
+                        };
+                        mapping.types.insert(old.to_owned(), new);
+                    }
+                }
+                "fields" => {
+                    for (old, new) in object(value, "`fields`") {
+                        let new = new.as_str().unwrap_or_else(|| {
+                            panic!("the new name of `{}` should be a string", old)
+                        });
+                        mapping.fields.insert(old.to_owned(), new.to_owned());
+                    }
+                }
+                "defaults" => {
+                    for (field, hex) in object(value, "`defaults`") {
+                        let data = hex
+                            .as_str()
+                            .and_then(parse_hex)
+                            .unwrap_or_else(|| panic!("the default of `{}` should be hex", field));
+                        mapping.defaults.insert(field.to_owned(), data);
+                    }
+                }
+                _ => panic!("unknown key `{}` in the mapping", key),
+            }
+        }
+        mapping
+    }
+}
+
+fn object<'a>(value: &'a Value, what: &str) -> &'a serde_json::Map<String, Value> {
+    value
+        .as_object()
+        .unwrap_or_else(|| panic!("{} should be an object", what))
+}
+
+#[allow(clippy::manual_is_multiple_of)]
+fn parse_hex(input: &str) -> Option<Vec<u8>> {
+    let input = input.strip_prefix("0x").unwrap_or(input);
+    if input.len() % 2 != 0 {
+        return None;
+    }
+    (0..input.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(input.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// The shims of both directions.
+pub(crate) struct Plan {
+    pub(crate) upgrade: Vec<Shim>,
+    pub(crate) downgrade: Vec<Shim>,
+}
+
+/// Convert a value of the source type to the target type.
+pub(crate) struct Shim {
+    pub(crate) source: Rc<ast::TopDecl>,
+    pub(crate) target: Rc<ast::TopDecl>,
+    pub(crate) body: Body,
+}
+
+pub(crate) enum Body {
+    /// The encodings are the same, so the bytes are copied.
+    Copy,
+    /// Build a struct or a table, in the order of the target fields.
+    Fields(Vec<FieldShim>),
+    /// Convert the items of an array, a vector or an option.
+    Items(Conversion),
+    /// The target item of each source item, by the item ids.
+    Union(Vec<Option<(usize, Conversion)>>),
+}
+
+pub(crate) struct FieldShim {
+    /// The name of the target field.
+    pub(crate) name: String,
+    pub(crate) value: FieldValue,
+}
+
+pub(crate) enum FieldValue {
+    /// Convert the source field.
+    Field(String, Conversion),
+    /// The field doesn't exist in the source; `None` is the default value of the type.
+    Default(Option<Vec<u8>>),
+}
+
+/// Convert a reader of the source to an entity of the target.
+pub(crate) enum Conversion {
+    /// Both are `byte`.
+    Atom,
+    /// Call the shim of the source type.
+    Shim(String),
+    /// The encodings are the same, so the bytes are copied.
+    Copy,
+}
+
+impl Plan {
+    fn new(old: &ast::Ast, new: &ast::Ast, mapping: &Mapping) -> Self {
+        let mut upgrade = HashMap::new();
+        for (old_name, new_name) in &mapping.types {
+            if old.get_decl(old_name).is_none() {
+                panic!("the type `{}` doesn't exist in the old schema", old_name);
+            }
+            if let Some(new_name) = new_name {
+                if new.get_decl(new_name).is_none() {
+                    panic!("the type `{}` doesn't exist in the new schema", new_name);
+                }
+            }
+        }
+        for decl in old.major_decls() {
+            let new_name = match mapping.types.get(decl.name()) {
+                Some(new_name) => new_name.clone(),
+                None => Some(decl.name().to_owned()),
+            };
+            if let Some(new_decl) = new_name.and_then(|name| new.get_decl(&name)) {
+                if new_decl.is_atom() {
+                    continue;
+                }
+                upgrade.insert(decl.name().to_owned(), new_decl.name().to_owned());
+            }
+        }
+        let mut downgrade = HashMap::new();
+        for (old_name, new_name) in &upgrade {
+            if let Some(other) = downgrade.insert(new_name.to_owned(), old_name.to_owned()) {
+                panic!(
+                    "both `{}` and `{}` are mapped to `{}`",
+                    old_name, other, new_name
+                );
+            }
+        }
+        let mut upgrade_fields = HashMap::new();
+        let mut downgrade_fields = HashMap::new();
+        for (key, new_field) in &mapping.fields {
+            let (old_name, old_field) = split_field(key);
+            let has_field = |decl: Option<Rc<ast::TopDecl>>, field: &str| {
+                decl.as_deref()
+                    .and_then(fields_of)
+                    .is_some_and(|fields| fields.iter().any(|f| f.name == field))
+            };
+            if !has_field(old.get_decl(old_name), old_field) {
+                panic!("the field `{}` doesn't exist in the old schema", key);
+            }
+            let new_name = upgrade.get(old_name).unwrap_or_else(|| {
+                panic!(
+                    "the type of the field `{}` is removed in the new schema",
+                    key
+                )
+            });
+            if !has_field(new.get_decl(new_name), new_field) {
+                panic!(
+                    "the field `{}.{}` doesn't exist in the new schema",
+                    new_name, new_field
+                );
+            }
+            let new_key = (new_name.to_owned(), new_field.to_owned());
+            if let Some(other) = downgrade_fields.insert(new_key, old_field.to_owned()) {
+                panic!(
+                    "both `{}.{}` and `{}` are renamed to `{}.{}`",
+                    old_name, other, key, new_name, new_field
+                );
+            }
+            let old_key = (old_name.to_owned(), old_field.to_owned());
+            upgrade_fields.insert(old_key, new_field.to_owned());
+        }
+        let used = RefCell::new(HashSet::new());
+        let plan = Self {
+            upgrade: Direction::new(old, new, &upgrade, &upgrade_fields, mapping, &used).shims(),
+            downgrade: Direction::new(new, old, &downgrade, &downgrade_fields, mapping, &used)
+                .shims(),
+        };
+        let mut unused = mapping
+            .defaults
+            .keys()
+            .filter(|key| !used.borrow().contains(*key))
+            .collect::<Vec<_>>();
+        unused.sort();
+        if let Some(key) = unused.first() {
+            panic!("the default of `{}` is not used", key);
+        }
+        plan
+    }
+}
+
+/// Pair the types from the source to the target.
+struct Direction<'a> {
+    source: &'a ast::Ast,
+    target: &'a ast::Ast,
+    /// The names of the target types, by the names of the source types.
+    counterparts: &'a HashMap<String, String>,
+    /// The names of the target fields, by the names of the source types and fields.
+    renames: &'a HashMap<(String, String), String>,
+    mapping: &'a Mapping,
+    /// The defaults which are used.
+    used: &'a RefCell<HashSet<String>>,
+}
+
+impl<'a> Direction<'a> {
+    fn new(
+        source: &'a ast::Ast,
+        target: &'a ast::Ast,
+        counterparts: &'a HashMap<String, String>,
+        renames: &'a HashMap<(String, String), String>,
+        mapping: &'a Mapping,
+        used: &'a RefCell<HashSet<String>>,
+    ) -> Self {
+        Self {
+            source,
+            target,
+            counterparts,
+            renames,
+            mapping,
+            used,
+        }
+    }
+
+    fn shims(&self) -> Vec<Shim> {
+        self.source
+            .major_decls()
+            .into_iter()
+            .filter_map(|source| {
+                let target = self
+                    .target
+                    .get_decl(self.counterparts.get(source.name())?)?;
+                let body = self.body(&source, &target);
+                Some(Shim {
+                    source,
+                    target,
+                    body,
+                })
+            })
+            .collect()
+    }
+
+    fn body(&self, source: &ast::TopDecl, target: &ast::TopDecl) -> Body {
+        if self.is_identical(source, target, &mut HashSet::new()) {
+            return Body::Copy;
+        }
+        match (source, target) {
+            (ast::TopDecl::Struct(_), ast::TopDecl::Struct(t))
+            | (ast::TopDecl::Table(_), ast::TopDecl::Struct(t)) => {
+                self.fields(source, &t.name, &t.inner)
+            }
+            (ast::TopDecl::Struct(_), ast::TopDecl::Table(t))
+            | (ast::TopDecl::Table(_), ast::TopDecl::Table(t)) => {
+                self.fields(source, &t.name, &t.inner)
+            }
+            (ast::TopDecl::Array(s), ast::TopDecl::Array(t)) if s.item_count == t.item_count => {
+                Body::Items(self.conversion(&s.typ, &t.typ, target.name()))
+            }
+            (ast::TopDecl::FixVec(_), ast::TopDecl::FixVec(_))
+            | (ast::TopDecl::FixVec(_), ast::TopDecl::DynVec(_))
+            | (ast::TopDecl::DynVec(_), ast::TopDecl::FixVec(_))
+            | (ast::TopDecl::DynVec(_), ast::TopDecl::DynVec(_))
+            | (ast::TopDecl::Option_(_), ast::TopDecl::Option_(_)) => {
+                let source_item = source.dependencies()[0];
+                let target_item = target.dependencies()[0];
+                Body::Items(self.conversion(source_item, target_item, target.name()))
+            }
+            (ast::TopDecl::Union(s), ast::TopDecl::Union(t)) => Body::Union(
+                s.inner
+                    .iter()
+                    .map(|source_item| {
+                        t.inner
+                            .iter()
+                            .position(|target_item| {
+                                self.is_counterpart(&source_item.typ, &target_item.typ)
+                            })
+                            .map(|index| {
+                                let target_item = &t.inner[index].typ;
+                                let conversion =
+                                    self.conversion(&source_item.typ, target_item, target.name());
+                                (index, conversion)
+                            })
+                    })
+                    .collect(),
+            ),
+            _ => panic!(
+                "`{}` ({}) can't be migrated to `{}` ({})",
+                source.name(),
+                kind(source),
+                target.name(),
+                kind(target)
+            ),
+        }
+    }
+
+    fn fields(
+        &self,
+        source: &ast::TopDecl,
+        target_name: &str,
+        target_fields: &[ast::FieldDecl],
+    ) -> Body {
+        let source_fields = fields_of(source).unwrap();
+        let fields = target_fields
+            .iter()
+            .map(|target_field| {
+                let value = match source_fields.iter().find(|source_field| {
+                    self.field_name(source.name(), &source_field.name) == target_field.name
+                }) {
+                    Some(source_field) => {
+                        let context = format!("{}.{}", target_name, target_field.name);
+                        let conversion =
+                            self.conversion(&source_field.typ, &target_field.typ, &context);
+                        FieldValue::Field(source_field.name.clone(), conversion)
+                    }
+                    None => FieldValue::Default(self.default(target_name, target_field)),
+                };
+                FieldShim {
+                    name: target_field.name.clone(),
+                    value,
+                }
+            })
+            .collect();
+        Body::Fields(fields)
+    }
+
+    /// The name of the source field in the target.
+    fn field_name<'b>(&'b self, type_name: &str, field: &'b str) -> &'b str {
+        self.renames
+            .get(&(type_name.to_owned(), field.to_owned()))
+            .map_or(field, String::as_str)
+    }
+
+    fn default(&self, target_name: &str, field: &ast::FieldDecl) -> Option<Vec<u8>> {
+        let key = format!("{}.{}", target_name, field.name);
+        let data = self.mapping.defaults.get(&key)?;
+        if let Err(err) = verifier::verify(&field.typ, data, false) {
+            panic!("the default of `{}` is invalid: {}", key, err);
+        }
+        self.used.borrow_mut().insert(key);
+        Some(data.clone())
+    }
+
+    fn conversion(
+        &self,
+        source: &ast::TopDecl,
+        target: &ast::TopDecl,
+        context: &str,
+    ) -> Conversion {
+        if source.is_atom() && target.is_atom() {
+            Conversion::Atom
+        } else if self.is_counterpart(source, target) {
+            Conversion::Shim(source.name().to_owned())
+        } else if self.is_identical(source, target, &mut HashSet::new()) {
+            Conversion::Copy
+        } else {
+            panic!(
+                "`{}` changes from `{}` to `{}`, which can't be migrated",
+                context,
+                source.name(),
+                target.name()
+            )
+        }
+    }
+
+    fn is_counterpart(&self, source: &ast::TopDecl, target: &ast::TopDecl) -> bool {
+        if source.is_atom() || target.is_atom() {
+            return source.is_atom() && target.is_atom();
+        }
+        self.counterparts
+            .get(source.name())
+            .is_some_and(|name| name == target.name())
+    }
+
+    /// Whether the encodings are the same, including the names of the fields after the renames.
+    ///
+    /// The pairs which are being compared are assumed to be identical, for recursive types.
+    fn is_identical(
+        &self,
+        source: &ast::TopDecl,
+        target: &ast::TopDecl,
+        visiting: &mut HashSet<(String, String)>,
+    ) -> bool {
+        if !visiting.insert((source.name().to_owned(), target.name().to_owned())) {
+            return true;
+        }
+        let fields = |s: &[ast::FieldDecl],
+                      t: &[ast::FieldDecl],
+                      visiting: &mut HashSet<(String, String)>| {
+            s.len() == t.len()
+                && s.iter().zip(t).all(|(s, t)| {
+                    self.field_name(source.name(), &s.name) == t.name
+                        && self.is_identical(&s.typ, &t.typ, visiting)
+                })
+        };
+        match (source, target) {
+            (ast::TopDecl::Atom(_), ast::TopDecl::Atom(_)) => true,
+            (ast::TopDecl::Option_(s), ast::TopDecl::Option_(t)) => {
+                self.is_identical(&s.typ, &t.typ, visiting)
+            }
+            (ast::TopDecl::Union(s), ast::TopDecl::Union(t)) => {
+                s.inner.len() == t.inner.len()
+                    && s.inner
+                        .iter()
+                        .zip(&t.inner)
+                        .all(|(s, t)| self.is_identical(&s.typ, &t.typ, visiting))
+            }
+            (ast::TopDecl::Array(s), ast::TopDecl::Array(t)) => {
+                s.item_count == t.item_count && self.is_identical(&s.typ, &t.typ, visiting)
+            }
+            (ast::TopDecl::Struct(s), ast::TopDecl::Struct(t)) => {
+                fields(&s.inner, &t.inner, visiting)
+            }
+            (ast::TopDecl::FixVec(s), ast::TopDecl::FixVec(t)) => {
+                s.count_size == t.count_size && self.is_identical(&s.typ, &t.typ, visiting)
+            }
+            (ast::TopDecl::DynVec(s), ast::TopDecl::DynVec(t)) => {
+                self.is_identical(&s.typ, &t.typ, visiting)
+            }
+            (ast::TopDecl::Table(s), ast::TopDecl::Table(t)) => {
+                fields(&s.inner, &t.inner, visiting)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn kind(decl: &ast::TopDecl) -> &'static str {
+    match decl {
+        ast::TopDecl::Atom(_) => "byte",
+        ast::TopDecl::Option_(_) => "an option",
+        ast::TopDecl::Union(_) => "a union",
+        ast::TopDecl::Array(_) => "an array",
+        ast::TopDecl::Struct(_) => "a struct",
+        ast::TopDecl::FixVec(_) | ast::TopDecl::DynVec(_) => "a vector",
+        ast::TopDecl::Table(_) => "a table",
+    }
+}
+
+fn fields_of(decl: &ast::TopDecl) -> Option<&[ast::FieldDecl]> {
+    match decl {
+        ast::TopDecl::Struct(ref s) => Some(&s.inner),
+        ast::TopDecl::Table(ref s) => Some(&s.inner),
+        _ => None,
+    }
+}
+
+/// Split `Type.field` into the type and the field.
+fn split_field(key: &str) -> (&str, &str) {
+    key.split_once('.')
+        .unwrap_or_else(|| panic!("the field `{}` should be `Type.field`", key))
+}
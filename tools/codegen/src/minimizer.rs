@@ -0,0 +1,160 @@
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use molecule::{error::VerificationError, pack_number, unpack_number, Number, NUMBER_SIZE};
+
+use crate::{ast::verified as ast, verifier, Parser};
+
+/// Shrink a buffer which fails the verification of a type, while keeping the same failure.
+///
+/// Two failures are the same if they have the same kind and are reported by the same type. The
+/// rules are the same as the generated Rust readers, so the output is a minimal reproducer for
+/// bug reports.
+pub struct Minimizer {
+    file_path: Option<PathBuf>,
+    type_name: Option<String>,
+    compatible: bool,
+}
+
+impl Default for Minimizer {
+    fn default() -> Self {
+        Minimizer::new()
+    }
+}
+
+fn classify(err: &VerificationError) -> (&'static str, String) {
+    (err.kind(), err.type_name().to_owned())
+}
+
+impl Minimizer {
+    pub fn new() -> Self {
+        Self {
+            file_path: None,
+            type_name: None,
+            compatible: false,
+        }
+    }
+
+    pub fn file_path<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.file_path.replace(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn type_name<S: AsRef<str>>(&mut self, name: S) -> &mut Self {
+        self.type_name.replace(name.as_ref().to_owned());
+        self
+    }
+
+    /// Verify the buffers as `from_compatible_slice` does.
+    pub fn compatible(&mut self, enable: bool) -> &mut Self {
+        self.compatible = enable;
+        self
+    }
+
+    /// Return the minimized buffer and the failure it keeps.
+    ///
+    /// Return an error if the input passes the verification.
+    pub fn minimize(&self, data: &[u8]) -> Result<(Vec<u8>, VerificationError), String> {
+        let ast = Parser::parse(self.file_path.as_ref().unwrap());
+        let type_name = self.type_name.as_ref().unwrap();
+        let decl = ast
+            .get_decl(type_name)
+            .unwrap_or_else(|| panic!("the type `{}` is not found", type_name));
+        let expected = match verifier::verify(&decl, data, self.compatible) {
+            Ok(()) => return Err(format!("the input is a valid `{}`", type_name)),
+            Err(err) => classify(&err),
+        };
+        let minimizer = Minimization {
+            decl,
+            compatible: self.compatible,
+            expected,
+        };
+        let mut data = minimizer.remove_chunks(data.to_owned());
+        minimizer.clear_bytes(&mut data);
+        let err = verifier::verify(&minimizer.decl, &data, self.compatible).unwrap_err();
+        Ok((data, err))
+    }
+}
+
+struct Minimization {
+    decl: Rc<ast::TopDecl>,
+    compatible: bool,
+    expected: (&'static str, String),
+}
+
+impl Minimization {
+    fn fails_same(&self, data: &[u8]) -> bool {
+        match verifier::verify(&self.decl, data, self.compatible) {
+            Ok(()) => false,
+            Err(err) => classify(&err) == self.expected,
+        }
+    }
+
+    /// Remove the bytes in the range, and return the result if it keeps the failure.
+    ///
+    /// If the data starts with its total size, the total size is updated as well, otherwise
+    /// the failures in the nested types could not be reached.
+    fn remove(&self, data: &[u8], start: usize, end: usize) -> Option<Vec<u8>> {
+        let mut candidate = data[..start].to_vec();
+        candidate.extend_from_slice(&data[end..]);
+        if self.fails_same(&candidate) {
+            return Some(candidate);
+        }
+        let has_total_size = data.len() >= NUMBER_SIZE
+            && unpack_number(data) as usize == data.len()
+            && start >= NUMBER_SIZE;
+        if has_total_size {
+            let total_size = pack_number(candidate.len() as Number);
+            candidate[..NUMBER_SIZE].copy_from_slice(&total_size);
+            if self.fails_same(&candidate) {
+                return Some(candidate);
+            }
+        }
+        None
+    }
+
+    /// Remove chunks from the data, as the delta debugging algorithm does.
+    fn remove_chunks(&self, mut data: Vec<u8>) -> Vec<u8> {
+        let mut granularity = 2;
+        while data.len() >= 2 {
+            let chunk_size = data.len().div_ceil(granularity);
+            let mut removed = false;
+            let mut start = 0;
+            while start < data.len() {
+                let end = (start + chunk_size).min(data.len());
+                if let Some(candidate) = self.remove(&data, start, end) {
+                    data = candidate;
+                    removed = true;
+                } else {
+                    start = end;
+                }
+            }
+            if removed {
+                granularity = (granularity - 1).max(2);
+            } else if granularity < data.len() {
+                granularity = (granularity * 2).min(data.len());
+            } else {
+                break;
+            }
+        }
+        if !data.is_empty() && self.fails_same(&[]) {
+            data.clear();
+        }
+        data
+    }
+
+    /// Set the bytes to zero if they don't matter.
+    fn clear_bytes(&self, data: &mut [u8]) {
+        for i in 0..data.len() {
+            let byte = data[i];
+            if byte != 0 {
+                data[i] = 0;
+                if !self.fails_same(data) {
+                    data[i] = byte;
+                }
+            }
+        }
+    }
+}
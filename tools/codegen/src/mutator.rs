@@ -0,0 +1,209 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use molecule::{error::VerificationError, unpack_count, unpack_number, Number, NUMBER_SIZE};
+
+use crate::{ast::verified as ast, verifier, Parser};
+
+/// A buffer which is mutated from a valid one and fails the verification.
+#[derive(Debug)]
+pub struct Mutation {
+    /// What is mutated, such as `offset 1 of Table3 at 8: 20 -> 21`.
+    pub label: String,
+    pub data: Vec<u8>,
+    /// The failure in the strict mode.
+    pub error: VerificationError,
+    /// Whether it passes the verification in the compatible mode.
+    pub compatible: bool,
+}
+
+/// Generate negative test cases from valid buffers of a type.
+///
+/// The numbers in the headers (total sizes, offsets, item counts and item ids) are located by
+/// the schema, and each of them is set to the values around the boundaries. The buffer is also
+/// truncated at each number and extended by a byte. Only the mutations which fail the
+/// verification are kept.
+pub struct Mutator {
+    file_path: Option<PathBuf>,
+    type_name: Option<String>,
+}
+
+impl Default for Mutator {
+    fn default() -> Self {
+        Mutator::new()
+    }
+}
+
+/// A number in the headers of a buffer.
+struct Field {
+    what: String,
+    start: usize,
+    size: usize,
+}
+
+impl Mutator {
+    pub fn new() -> Self {
+        Self {
+            file_path: None,
+            type_name: None,
+        }
+    }
+
+    pub fn file_path<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.file_path.replace(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn type_name<S: AsRef<str>>(&mut self, name: S) -> &mut Self {
+        self.type_name.replace(name.as_ref().to_owned());
+        self
+    }
+
+    /// Return the mutations in a stable order.
+    ///
+    /// Return an error if the input fails the verification.
+    pub fn mutate(&self, data: &[u8]) -> Result<Vec<Mutation>, String> {
+        let ast = Parser::parse(self.file_path.as_ref().unwrap());
+        let type_name = self.type_name.as_ref().unwrap();
+        let decl = ast
+            .get_decl(type_name)
+            .unwrap_or_else(|| panic!("the type `{}` is not found", type_name));
+        if let Err(err) = verifier::verify(&decl, data, false) {
+            return Err(format!("the input is not a valid `{}`: {}", type_name, err));
+        }
+        let mut fields = Vec::new();
+        locate(&decl, data, 0, &mut fields);
+        let mut candidates = Vec::new();
+        for field in &fields {
+            let slice = &data[field.start..field.start + field.size];
+            let value = unpack_count(slice, field.size) as u64;
+            let max = (1u64 << (field.size * 8)) - 1;
+            let len = data.len() as u64;
+            let mut values = vec![value.wrapping_sub(1) & max, value + 1, 0, max, len, len + 1];
+            let mut seen = HashSet::new();
+            values.retain(|v| *v != value && *v <= max && seen.insert(*v));
+            for new_value in values {
+                let mut mutated = data.to_vec();
+                let bytes = (new_value as Number).to_le_bytes();
+                mutated[field.start..field.start + field.size]
+                    .copy_from_slice(&bytes[..field.size]);
+                let label = format!(
+                    "{} at {}: {} -> {}",
+                    field.what, field.start, value, new_value
+                );
+                candidates.push((label, mutated));
+            }
+            for cut in &[field.start, field.start + 1] {
+                if *cut < field.start + field.size {
+                    let label = format!("truncate in {} at {}", field.what, cut);
+                    candidates.push((label, data[..*cut].to_vec()));
+                }
+            }
+        }
+        if !data.is_empty() {
+            let label = format!("truncate to {} bytes", data.len() - 1);
+            candidates.push((label, data[..data.len() - 1].to_vec()));
+        }
+        let mut extended = data.to_vec();
+        extended.push(0);
+        candidates.push(("append a byte".to_owned(), extended));
+        let mut seen = HashSet::new();
+        let mutations = candidates
+            .into_iter()
+            .filter(|(_, data)| seen.insert(data.clone()))
+            .filter_map(|(label, data)| {
+                verifier::verify(&decl, &data, false)
+                    .err()
+                    .map(|error| Mutation {
+                        compatible: verifier::verify(&decl, &data, true).is_ok(),
+                        label,
+                        data,
+                        error,
+                    })
+            })
+            .collect();
+        Ok(mutations)
+    }
+}
+
+/// Locate the numbers in the headers of a valid buffer, including the nested ones.
+fn locate(decl: &ast::TopDecl, slice: &[u8], base: usize, fields: &mut Vec<Field>) {
+    match decl {
+        ast::TopDecl::Option_(ref typ) => {
+            if !slice.is_empty() {
+                locate(&typ.typ, slice, base, fields);
+            }
+        }
+        ast::TopDecl::Union(ref typ) => {
+            fields.push(number(format!("item id of {}", typ.name), base));
+            let item_id = unpack_number(slice) as usize;
+            locate(
+                &typ.inner[item_id].typ,
+                &slice[NUMBER_SIZE..],
+                base + NUMBER_SIZE,
+                fields,
+            );
+        }
+        ast::TopDecl::FixVec(ref typ) => {
+            fields.push(Field {
+                what: format!("item count of {}", typ.name),
+                start: base,
+                size: typ.count_size,
+            });
+        }
+        ast::TopDecl::DynVec(ref typ) => {
+            let types = vec![&typ.typ; item_count(slice)];
+            locate_offsets(&typ.name, &types, slice, base, fields);
+        }
+        ast::TopDecl::Table(ref typ) => {
+            let types: Vec<_> = typ.inner.iter().map(|field| &field.typ).collect();
+            locate_offsets(&typ.name, &types, slice, base, fields);
+        }
+        ast::TopDecl::Atom(_) | ast::TopDecl::Array(_) | ast::TopDecl::Struct(_) => {}
+    }
+}
+
+fn item_count(slice: &[u8]) -> usize {
+    if slice.len() == NUMBER_SIZE {
+        0
+    } else {
+        unpack_number(&slice[NUMBER_SIZE..]) as usize / NUMBER_SIZE - 1
+    }
+}
+
+fn locate_offsets(
+    name: &str,
+    types: &[&Rc<ast::TopDecl>],
+    slice: &[u8],
+    base: usize,
+    fields: &mut Vec<Field>,
+) {
+    fields.push(number(format!("total size of {}", name), base));
+    for idx in 0..item_count(slice) {
+        let start = NUMBER_SIZE * (idx + 1);
+        fields.push(number(format!("offset {} of {}", idx, name), base + start));
+    }
+    for (idx, typ) in types.iter().enumerate() {
+        let (start, end) = (offset_at(slice, idx), offset_at(slice, idx + 1));
+        locate(typ, &slice[start..end], base + start, fields);
+    }
+}
+
+fn offset_at(slice: &[u8], idx: usize) -> usize {
+    if idx == item_count(slice) {
+        slice.len()
+    } else {
+        unpack_number(&slice[NUMBER_SIZE * (idx + 1)..]) as usize
+    }
+}
+
+fn number(what: String, start: usize) -> Field {
+    Field {
+        what,
+        start,
+        size: NUMBER_SIZE,
+    }
+}
@@ -1,9 +1,12 @@
 use std::{ffi, fs, io::Read as _, path::Path};
 
-use pest::{error::Error as PestError, Parser as _};
+use pest::{
+    error::{Error as PestError, ErrorVariant, InputLocation, LineColLocation},
+    Parser as _,
+};
 use same_file::is_same_file;
 
-use crate::{ast, utils::PairsUtils as _};
+use crate::{ast, diagnostics::SyntaxError, editor, utils::PairsUtils as _};
 
 mod inner;
 pub(crate) use inner::Rule;
@@ -12,11 +15,23 @@ pub(crate) struct Parser;
 
 impl Parser {
     pub(crate) fn parse<P: AsRef<Path>>(path: &P) -> ast::verified::Ast {
-        let ast_raw = Self::preprocess(path).unwrap();
+        let ast_raw = Self::preprocess(path).unwrap_or_else(|errors| {
+            let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+            panic!(
+                "found {} syntax errors:\n\n{}",
+                errors.len(),
+                messages.join("\n\n")
+            )
+        });
         ast::verified::Ast::new(ast_raw)
     }
 
-    fn preprocess<P: AsRef<Path>>(path: &P) -> Result<ast::raw::Ast, PestError<inner::Rule>> {
+    pub(crate) fn check<P: AsRef<Path>>(path: &P) -> Vec<SyntaxError> {
+        Self::preprocess(path).err().unwrap_or_default()
+    }
+
+    /// Parse the schema file and the imported files, and collect the syntax errors of all of them.
+    fn preprocess<P: AsRef<Path>>(path: &P) -> Result<ast::raw::Ast, Vec<SyntaxError>> {
         let mut ast = ast::raw::Ast::default();
         let mut imported_depth = 0;
         ast.namespace = path
@@ -26,7 +41,10 @@ impl Parser {
             .unwrap()
             .to_owned();
 
-        Self::preprocess_single(&mut ast, path, imported_depth)?;
+        let mut errors = Vec::new();
+        if let Err(mut errs) = Self::preprocess_single(&mut ast, path, imported_depth) {
+            errors.append(&mut errs);
+        }
 
         let mut path_bufs = Vec::new();
 
@@ -47,18 +65,22 @@ impl Parser {
                 path_buf.push(&stmt.name);
                 path_buf.set_extension("mol");
                 let path_new = path_buf.as_path();
-                if is_same_file(path, &path_new).unwrap() {
+                if is_same_file(path, path_new).unwrap() {
                     panic!("found cyclic dependencie");
                 }
 
                 if path_bufs
                     .iter()
-                    .any(|ref path_old| is_same_file(&path_old, &path_new).unwrap())
+                    .any(|path_old| is_same_file(path_old, path_new).unwrap())
                 {
                     continue;
                 } else {
                     imports.push(stmt);
-                    Self::preprocess_single(&mut ast, &path_new, imported_depth)?;
+                    if let Err(mut errs) =
+                        Self::preprocess_single(&mut ast, &path_new, imported_depth)
+                    {
+                        errors.append(&mut errs);
+                    }
                     path_bufs.push(path_buf);
                 }
             }
@@ -66,21 +88,32 @@ impl Parser {
 
         ast.imports = imports;
 
-        Ok(ast)
+        if errors.is_empty() {
+            Ok(ast)
+        } else {
+            Err(errors)
+        }
     }
 
     fn preprocess_single<P: AsRef<Path>>(
         ast: &mut ast::raw::Ast,
         path: &P,
         imported_depth: usize,
-    ) -> Result<(), PestError<inner::Rule>> {
+    ) -> Result<(), Vec<SyntaxError>> {
         let buffer = {
             let mut buffer = String::new();
-            let mut file_in = fs::OpenOptions::new().read(true).open(&path).unwrap();
+            let mut file_in = fs::OpenOptions::new().read(true).open(path).unwrap();
             file_in.read_to_string(&mut buffer).unwrap();
             buffer
         };
-        let mut file_content = inner::Parser::parse(inner::Rule::grammar, &buffer)?;
+        let mut file_content =
+            inner::Parser::parse(inner::Rule::grammar, &buffer).map_err(|err| {
+                let path = path.as_ref().to_string_lossy();
+                Self::recover(&buffer, err)
+                    .into_iter()
+                    .map(|err| Self::syntax_error(err, &path))
+                    .collect::<Vec<_>>()
+            })?;
         let grammar = file_content
             .next()
             .unwrap_or_else(|| panic!("grammar should only have one pair"));
@@ -101,9 +134,11 @@ impl Parser {
                 }
                 inner::Rule::option_decl => {
                     let mut pair = pair.into_inner();
+                    let invariant = pair.next_invariant();
                     let node = ast::raw::OptionDecl {
                         name: pair.next_string(),
                         typ: pair.next_string(),
+                        invariant,
                         imported_depth,
                     };
                     pair.next_should_be_none();
@@ -111,9 +146,11 @@ impl Parser {
                 }
                 inner::Rule::union_decl => {
                     let mut pair = pair.into_inner();
+                    let invariant = pair.next_invariant();
                     let node = ast::raw::UnionDecl {
                         name: pair.next_string(),
                         inner: pair.next_items(),
+                        invariant,
                         imported_depth,
                     };
                     pair.next_should_be_none();
@@ -121,10 +158,15 @@ impl Parser {
                 }
                 inner::Rule::array_decl => {
                     let mut pair = pair.into_inner();
+                    let attributes = pair.next_decl_attributes();
+                    attributes.no_count_size();
+                    attributes.no_checksum();
                     let node = ast::raw::ArrayDecl {
                         name: pair.next_string(),
                         typ: pair.next_string(),
                         length: pair.next_usize(),
+                        invariant: attributes.invariant,
+                        logical: attributes.logical,
                         imported_depth,
                     };
                     pair.next_should_be_none();
@@ -132,9 +174,12 @@ impl Parser {
                 }
                 inner::Rule::struct_decl => {
                     let mut pair = pair.into_inner();
+                    let invariant = pair.next_invariant();
                     let node = ast::raw::StructDecl {
                         name: pair.next_string(),
                         inner: pair.next_fields(),
+                        constraints: pair.next_constraints(),
+                        invariant,
                         imported_depth,
                     };
                     pair.next_should_be_none();
@@ -142,9 +187,14 @@ impl Parser {
                 }
                 inner::Rule::vector_decl => {
                     let mut pair = pair.into_inner();
+                    let attributes = pair.next_decl_attributes();
+                    attributes.no_checksum();
+                    attributes.no_logical();
                     let node = ast::raw::VectorDecl {
                         name: pair.next_string(),
                         typ: pair.next_string(),
+                        count_size: attributes.count_size,
+                        invariant: attributes.invariant,
                         imported_depth,
                     };
                     pair.next_should_be_none();
@@ -152,9 +202,25 @@ impl Parser {
                 }
                 inner::Rule::table_decl => {
                     let mut pair = pair.into_inner();
+                    let attributes = pair.next_decl_attributes();
+                    attributes.no_count_size();
+                    attributes.no_logical();
                     let node = ast::raw::TableDecl {
                         name: pair.next_string(),
                         inner: pair.next_fields(),
+                        constraints: pair.next_constraints(),
+                        invariant: attributes.invariant,
+                        checksum: attributes.checksum,
+                        imported_depth,
+                    };
+                    pair.next_should_be_none();
+                    ast.add_decl(node);
+                }
+                inner::Rule::alias_decl => {
+                    let mut pair = pair.into_inner();
+                    let node = ast::raw::AliasDecl {
+                        name: pair.next_string(),
+                        typ: pair.next_string(),
                         imported_depth,
                     };
                     pair.next_should_be_none();
@@ -176,4 +242,109 @@ impl Parser {
         }
         Ok(())
     }
+
+    fn syntax_error(err: PestError<inner::Rule>, path: &str) -> SyntaxError {
+        let (line, column) = match err.line_col {
+            LineColLocation::Pos(pos) => pos,
+            LineColLocation::Span(start, _) => start,
+        };
+        let message = match err.variant {
+            ErrorVariant::ParsingError {
+                ref positives,
+                ref negatives,
+            } => match (negatives.is_empty(), positives.is_empty()) {
+                (false, false) => format!(
+                    "unexpected {}; expected {}",
+                    enumerate(negatives),
+                    enumerate(positives)
+                ),
+                (false, true) => format!("unexpected {}", enumerate(negatives)),
+                (true, false) => format!("expected {}", enumerate(positives)),
+                (true, true) => "unknown parsing error".to_owned(),
+            },
+            ErrorVariant::CustomError { ref message } => message.to_owned(),
+        };
+        SyntaxError {
+            file_name: path.to_owned(),
+            line,
+            column,
+            message,
+            rendered: err.with_path(path).to_string(),
+        }
+    }
+
+    /// Parse the statements one by one, and skip to the next statement after a syntax error, so
+    /// all the broken statements are reported.
+    ///
+    /// If each statement could be parsed alone, such as an import after the declarations, the
+    /// original error is returned.
+    fn recover(buffer: &str, error: PestError<inner::Rule>) -> Vec<PestError<inner::Rule>> {
+        let mut errors = Vec::new();
+        let mut start = 0;
+        while start < buffer.len() {
+            // Blank the parsed statements, so the positions in the errors are still in the file.
+            let mut input: String = buffer[..start]
+                .chars()
+                .map(|c| {
+                    if c == '\n' {
+                        "\n".to_owned()
+                    } else {
+                        " ".repeat(c.len_utf8())
+                    }
+                })
+                .collect();
+            input.push_str(&buffer[start..]);
+            match inner::Parser::parse(inner::Rule::recovery, &input) {
+                Ok(mut pairs) => {
+                    start = pairs.next().unwrap().as_span().end();
+                }
+                Err(err) => {
+                    let pos = match err.location {
+                        InputLocation::Pos(pos) => pos,
+                        InputLocation::Span((pos, _)) => pos,
+                    };
+                    errors.push(err);
+                    if let Some(next) = Self::next_statement(buffer, pos) {
+                        start = next;
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+        if errors.is_empty() {
+            errors.push(error);
+        }
+        errors
+    }
+
+    /// Find the start of the first line after the position, which starts with a keyword or an
+    /// attribute.
+    fn next_statement(buffer: &str, pos: usize) -> Option<usize> {
+        let syntax = editor::syntax();
+        let mut line_start = pos + buffer[pos..].find('\n')? + 1;
+        loop {
+            let line = &buffer[line_start..];
+            let trimmed = line.trim_start();
+            let is_statement = trimmed.starts_with(&syntax.attribute_prefix)
+                || syntax.keywords.iter().any(|keyword| {
+                    trimmed.starts_with(keyword)
+                        && trimmed[keyword.len()..].starts_with(char::is_whitespace)
+                });
+            if is_statement {
+                return Some(line_start);
+            }
+            line_start += line.find('\n')? + 1;
+        }
+    }
+}
+
+/// List the rules as pest does, such as `a, b, or c`.
+fn enumerate(rules: &[inner::Rule]) -> String {
+    let names: Vec<_> = rules.iter().map(|rule| format!("{:?}", rule)).collect();
+    match names.len() {
+        1 => names[0].clone(),
+        2 => format!("{} or {}", names[0], names[1]),
+        len => format!("{}, or {}", names[..len - 1].join(", "), names[len - 1]),
+    }
 }
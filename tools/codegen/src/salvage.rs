@@ -0,0 +1,403 @@
+//! Decode as much of a damaged buffer as possible, such as for the forensic analysis of a broken
+//! storage.
+//!
+//! A subtree which passes the verification is decoded as `Explorer::decode` does; otherwise its
+//! fields or items are salvaged one by one, as long as the headers can still locate them.
+
+use std::fmt;
+
+use molecule::{
+    error::VerificationError, fixvec_total_size, unpack_count, unpack_number, NUMBER_SIZE,
+};
+
+use crate::{
+    ast::verified::{self as ast, HasName},
+    explorer::{display, display_field_value},
+    verifier::{self, reader_name},
+};
+
+/// A value which is decoded from a damaged buffer, with the errors of its subtrees.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialValue {
+    /// The name of the type, as in the schema.
+    pub type_name: String,
+    /// The start of the bytes of the value in the whole buffer.
+    pub offset: usize,
+    pub size: usize,
+    pub content: PartialContent,
+    /// The error of the value itself, such as a broken header; the errors in its fields or items
+    /// are in their own values.
+    pub error: Option<VerificationError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartialContent {
+    /// The value is verified, and formatted as the `Display` of the generated Rust entities.
+    Decoded(String),
+    /// The fields or the items which could be located, by their names or their indexes.
+    Salvaged(Vec<(String, PartialValue)>),
+    /// Nothing could be decoded.
+    Broken,
+}
+
+impl PartialValue {
+    /// Whether the whole value is decoded without any error.
+    pub fn is_complete(&self) -> bool {
+        matches!(self.content, PartialContent::Decoded(_))
+    }
+
+    /// All errors by the paths of the values, such as `Root.f1.0`, in the order of the bytes.
+    pub fn errors(&self) -> Vec<(String, &VerificationError)> {
+        let mut errors = Vec::new();
+        self.collect_errors(self.type_name.clone(), &mut errors);
+        errors
+    }
+
+    fn collect_errors<'a>(
+        &'a self,
+        path: String,
+        errors: &mut Vec<(String, &'a VerificationError)>,
+    ) {
+        if let Some(ref error) = self.error {
+            errors.push((path.clone(), error));
+        }
+        if let PartialContent::Salvaged(ref children) = self.content {
+            for (name, child) in children {
+                child.collect_errors(format!("{}.{}", path, name), errors);
+            }
+        }
+    }
+
+    fn fmt_tree(&self, label: Option<&str>, depth: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:width$}", "", width = depth * 2)?;
+        if let Some(label) = label {
+            write!(f, "{}: ", label)?;
+        }
+        match self.content {
+            PartialContent::Decoded(ref text) => write!(f, "{}", text)?,
+            PartialContent::Salvaged(_) => write!(f, "{}", self.type_name)?,
+            PartialContent::Broken => write!(f, "{} <broken>", self.type_name)?,
+        }
+        write!(f, " [{}..{}]", self.offset, self.offset + self.size)?;
+        if let Some(ref error) = self.error {
+            write!(f, " error: {}", error)?;
+        }
+        if let PartialContent::Salvaged(ref children) = self.content {
+            for (name, child) in children {
+                writeln!(f)?;
+                child.fmt_tree(Some(name), depth + 1, f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The tree of the values, one in a line, with the ranges of their bytes and their errors.
+impl fmt::Display for PartialValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_tree(None, 0, f)
+    }
+}
+
+macro_rules! broken {
+    ($decl:ident, $offset:ident, $slice:ident, $err:ident $(, $args:expr )*) => {
+        PartialValue {
+            type_name: $decl.name().to_owned(),
+            offset: $offset,
+            size: $slice.len(),
+            content: PartialContent::Broken,
+            error: Some(VerificationError::$err(reader_name($decl) $(, $args )*)),
+        }
+    };
+}
+
+/// Salvage the items of the fixed size which are complete.
+fn fixed_items(
+    item: &ast::TopDecl,
+    item_size: usize,
+    count: usize,
+    offset: usize,
+    data: &[u8],
+    compatible: bool,
+) -> Vec<(String, PartialValue)> {
+    data.chunks_exact(item_size)
+        .take(count)
+        .enumerate()
+        .map(|(idx, chunk)| {
+            let child = salvage(item, offset + idx * item_size, chunk, compatible, None);
+            (idx.to_string(), child)
+        })
+        .collect()
+}
+
+/// Read the offsets of a DynVec or a Table, and return the error of the header, if any.
+///
+/// The offsets are `None` if the count of the items can't be found.
+#[allow(clippy::manual_is_multiple_of)]
+fn dynamic_offsets(
+    decl: &ast::TopDecl,
+    slice: &[u8],
+) -> (Option<VerificationError>, Option<Vec<usize>>) {
+    let slice_len = slice.len();
+    if slice_len < NUMBER_SIZE {
+        let err = VerificationError::HeaderIsBroken(reader_name(decl), NUMBER_SIZE, slice_len);
+        return (Some(err), None);
+    }
+    let total_size = unpack_number(slice) as usize;
+    let mut error = if total_size != slice_len {
+        Some(VerificationError::TotalSizeNotMatch(
+            reader_name(decl),
+            total_size,
+            slice_len,
+        ))
+    } else {
+        None
+    };
+    if slice_len == NUMBER_SIZE {
+        return (error, Some(vec![slice_len]));
+    }
+    if slice_len < NUMBER_SIZE * 2 {
+        let err = VerificationError::HeaderIsBroken(reader_name(decl), NUMBER_SIZE * 2, slice_len);
+        return (error.or(Some(err)), None);
+    }
+    let offset_first = unpack_number(&slice[NUMBER_SIZE..]) as usize;
+    if offset_first % NUMBER_SIZE != 0 || offset_first < NUMBER_SIZE * 2 || offset_first > slice_len
+    {
+        let err = VerificationError::OffsetsNotMatch(reader_name(decl));
+        return (error.or(Some(err)), None);
+    }
+    let mut offsets = slice[NUMBER_SIZE..offset_first]
+        .chunks(NUMBER_SIZE)
+        .map(|x| unpack_number(x) as usize)
+        .collect::<Vec<_>>();
+    offsets.push(slice_len);
+    if error.is_none() && offsets.windows(2).any(|pair| pair[0] > pair[1]) {
+        error = Some(VerificationError::OffsetsNotMatch(reader_name(decl)));
+    }
+    (error, Some(offsets))
+}
+
+/// Salvage the items between the offsets; an item whose offsets are out of order is broken.
+fn dynamic_items<'a, I>(
+    decl: &ast::TopDecl,
+    items: I,
+    offsets: &[usize],
+    offset: usize,
+    slice: &[u8],
+    compatible: bool,
+) -> Vec<(String, PartialValue)>
+where
+    I: Iterator<Item = (String, &'a ast::TopDecl, Option<&'a ast::FieldDecl>)>,
+{
+    items
+        .zip(offsets.windows(2))
+        .map(|((name, item, field), pair)| {
+            let (start, end) = (pair[0], pair[1]);
+            let header_size = NUMBER_SIZE * offsets.len();
+            let child = if start < header_size || start > end || end > slice.len() {
+                let start = start.min(slice.len());
+                let end = end.clamp(start, slice.len());
+                PartialValue {
+                    type_name: item.name().to_owned(),
+                    offset: offset + start,
+                    size: end - start,
+                    content: PartialContent::Broken,
+                    error: Some(VerificationError::OffsetsNotMatch(reader_name(decl))),
+                }
+            } else {
+                salvage(item, offset + start, &slice[start..end], compatible, field)
+            };
+            (name, child)
+        })
+        .collect()
+}
+
+/// Salvage the value at the offset of the whole buffer; the field is used for its `@display`.
+pub(crate) fn salvage(
+    decl: &ast::TopDecl,
+    offset: usize,
+    slice: &[u8],
+    compatible: bool,
+    field: Option<&ast::FieldDecl>,
+) -> PartialValue {
+    if verifier::verify(decl, slice, compatible).is_ok() {
+        let mut text = String::new();
+        if let Some(field) = field {
+            display_field_value(field, slice, &mut text);
+        } else {
+            display(decl, slice, &mut text);
+        }
+        return PartialValue {
+            type_name: decl.name().to_owned(),
+            offset,
+            size: slice.len(),
+            content: PartialContent::Decoded(text),
+            error: None,
+        };
+    }
+    let slice_len = slice.len();
+    let (error, children) = match decl {
+        ast::TopDecl::Atom(ref typ) => {
+            return broken!(decl, offset, slice, TotalSizeNotMatch, typ.size, slice_len);
+        }
+        ast::TopDecl::Option_(ref typ) => {
+            let inner = salvage(&typ.typ, offset, slice, compatible, None);
+            (None, vec![("Some".to_owned(), inner)])
+        }
+        ast::TopDecl::Union(ref typ) => {
+            if slice_len < NUMBER_SIZE {
+                return broken!(decl, offset, slice, HeaderIsBroken, NUMBER_SIZE, slice_len);
+            }
+            let item_id = unpack_number(slice);
+            let item = if let Some(item) = typ.inner.get(item_id as usize) {
+                item
+            } else {
+                return broken!(decl, offset, slice, UnknownItem, typ.inner.len(), item_id);
+            };
+            let data = &slice[NUMBER_SIZE..];
+            let error = item
+                .max_size
+                .filter(|max_size| data.len() > *max_size)
+                .map(|max_size| {
+                    VerificationError::ItemTooLarge(reader_name(decl), max_size, data.len())
+                });
+            let inner = salvage(&item.typ, offset + NUMBER_SIZE, data, compatible, None);
+            (error, vec![(item.typ.name().to_owned(), inner)])
+        }
+        ast::TopDecl::Array(ref typ) => {
+            let expected = typ.total_size();
+            if typ.typ.is_atom() {
+                return broken!(decl, offset, slice, TotalSizeNotMatch, expected, slice_len);
+            }
+            let error =
+                VerificationError::TotalSizeNotMatch(reader_name(decl), expected, slice_len);
+            let items = fixed_items(
+                &typ.typ,
+                typ.item_size,
+                typ.item_count,
+                offset,
+                slice,
+                compatible,
+            );
+            (Some(error), items)
+        }
+        ast::TopDecl::Struct(ref typ) => {
+            let expected = typ.total_size();
+            let error =
+                VerificationError::TotalSizeNotMatch(reader_name(decl), expected, slice_len);
+            let mut start = 0;
+            let mut fields = Vec::new();
+            for (field, size) in typ.inner.iter().zip(&typ.field_size) {
+                if start + size > slice_len {
+                    break;
+                }
+                let data = &slice[start..start + size];
+                let child = salvage(&field.typ, offset + start, data, compatible, Some(field));
+                fields.push((field.name.clone(), child));
+                start += size;
+            }
+            (Some(error), fields)
+        }
+        ast::TopDecl::FixVec(ref typ) => {
+            if slice_len < typ.count_size {
+                return broken!(
+                    decl,
+                    offset,
+                    slice,
+                    HeaderIsBroken,
+                    typ.count_size,
+                    slice_len
+                );
+            }
+            let count = unpack_count(slice, typ.count_size);
+            let error = match fixvec_total_size(typ.count_size, typ.item_size, count) {
+                Some(total_size) => {
+                    VerificationError::TotalSizeNotMatch(reader_name(decl), total_size, slice_len)
+                }
+                None => VerificationError::SizeOverflow(reader_name(decl)),
+            };
+            if typ.typ.is_atom() {
+                return PartialValue {
+                    type_name: decl.name().to_owned(),
+                    offset,
+                    size: slice_len,
+                    content: PartialContent::Broken,
+                    error: Some(error),
+                };
+            }
+            let items = fixed_items(
+                &typ.typ,
+                typ.item_size,
+                count,
+                offset + typ.count_size,
+                &slice[typ.count_size..],
+                compatible,
+            );
+            (Some(error), items)
+        }
+        ast::TopDecl::DynVec(ref typ) => {
+            let (error, offsets) = dynamic_offsets(decl, slice);
+            let offsets = if let Some(offsets) = offsets {
+                offsets
+            } else {
+                return PartialValue {
+                    type_name: decl.name().to_owned(),
+                    offset,
+                    size: slice_len,
+                    content: PartialContent::Broken,
+                    error,
+                };
+            };
+            let items = (0..).map(|idx: usize| (idx.to_string(), typ.typ.as_ref(), None));
+            let items = dynamic_items(decl, items, &offsets, offset, slice, compatible);
+            (error, items)
+        }
+        ast::TopDecl::Table(ref typ) => {
+            let (mut error, offsets) = dynamic_offsets(decl, slice);
+            let offsets = match offsets {
+                Some(offsets) if offsets.len() > 1 || typ.inner.is_empty() => offsets,
+                _ => {
+                    let error = error.or_else(|| {
+                        Some(VerificationError::HeaderIsBroken(
+                            reader_name(decl),
+                            NUMBER_SIZE * 2,
+                            slice_len,
+                        ))
+                    });
+                    return PartialValue {
+                        type_name: decl.name().to_owned(),
+                        offset,
+                        size: slice_len,
+                        content: PartialContent::Broken,
+                        error,
+                    };
+                }
+            };
+            let expected = typ.inner.len();
+            let field_count = offsets.len() - 1;
+            if error.is_none()
+                && (field_count < expected || (!compatible && field_count > expected))
+            {
+                error = Some(VerificationError::FieldCountNotMatch(
+                    reader_name(decl),
+                    expected,
+                    field_count,
+                ));
+            }
+            // The extra fields in the compatible mode are not salvaged, as they're not displayed.
+            let fields = typ
+                .inner
+                .iter()
+                .map(|field| (field.name.clone(), field.typ.as_ref(), Some(field)));
+            let fields = dynamic_items(decl, fields, &offsets, offset, slice, compatible);
+            (error, fields)
+        }
+    };
+    PartialValue {
+        type_name: decl.name().to_owned(),
+        offset,
+        size: slice_len,
+        content: PartialContent::Salvaged(children),
+        error,
+    }
+}
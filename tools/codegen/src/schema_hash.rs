@@ -0,0 +1,117 @@
+use std::path::Path;
+
+use crate::{
+    ast::verified::{self as ast, HasName},
+    Parser,
+};
+
+/// Hash a schema file, so data could be checked against the schema which it was written with.
+///
+/// The hash is taken from the canonical form of the types, including the imported ones, so it
+/// doesn't change with comments or whitespace.
+pub fn schema_hash<P: AsRef<Path>>(path: P) -> [u8; 32] {
+    hash_of(&Parser::parse(&path))
+}
+
+pub(crate) fn hash_of(ast: &ast::Ast) -> [u8; 32] {
+    let hash = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(b"molecule-schema\0")
+        .hash(canonical(ast).as_bytes());
+    let mut result = [0u8; 32];
+    result.copy_from_slice(hash.as_bytes());
+    result
+}
+
+/// Hash a type with the types in it, so the items of a batch could be checked against their
+/// definitions; it doesn't change with the other types in the schema.
+pub(crate) fn type_hash(decl: &ast::TopDecl) -> [u8; 8] {
+    fn visit<'a>(decl: &'a ast::TopDecl, visited: &mut Vec<&'a str>, text: &mut String) {
+        if visited.contains(&decl.name()) {
+            return;
+        }
+        visited.push(decl.name());
+        if decl.is_atom() {
+            text.push_str(ast::ATOM_NAME);
+            text.push('\n');
+            return;
+        }
+        for dep in decl.dependencies() {
+            visit(dep, visited, text);
+        }
+        text.push_str(&canonical_decl(decl));
+        text.push('\n');
+    }
+    let mut text = String::new();
+    visit(decl, &mut Vec::new(), &mut text);
+    let hash = blake2b_simd::Params::new()
+        .hash_length(8)
+        .personal(b"molecule-type\0\0\0")
+        .hash(text.as_bytes());
+    let mut result = [0u8; 8];
+    result.copy_from_slice(hash.as_bytes());
+    result
+}
+
+fn canonical(ast: &ast::Ast) -> String {
+    let mut text = String::new();
+    for decl in ast.decls() {
+        text.push_str(&canonical_decl(decl));
+        text.push('\n');
+    }
+    text
+}
+
+/// Write a declaration in one line, without comments.
+pub(crate) fn canonical_decl(decl: &ast::TopDecl) -> String {
+    match decl {
+        ast::TopDecl::Atom(_) => unreachable!(),
+        ast::TopDecl::Option_(ref typ) => format!("option {} ({});", typ.name, typ.typ.name()),
+        ast::TopDecl::Union(ref typ) => {
+            let items: Vec<_> = typ
+                .inner
+                .iter()
+                .map(|item| {
+                    let item_decl = match item.alias {
+                        Some(ref alias) => format!("{} as {}", item.typ.name(), alias),
+                        None => item.typ.name().to_owned(),
+                    };
+                    match item.max_size {
+                        Some(max_size) => format!("@max_size({}) {}", max_size, item_decl),
+                        None => item_decl,
+                    }
+                })
+                .collect();
+            format!("union {} {{ {}, }}", typ.name, items.join(", "))
+        }
+        ast::TopDecl::Array(ref typ) => format!(
+            "array {} [{}; {}];",
+            typ.name,
+            typ.typ.name(),
+            typ.item_count
+        ),
+        ast::TopDecl::Struct(ref typ) => {
+            format!("struct {} {{ {} }}", typ.name, fields(&typ.inner))
+        }
+        ast::TopDecl::FixVec(ref typ) => {
+            let vector = format!("vector {} <{}>;", typ.name, typ.typ.name());
+            if typ.is_standard() {
+                vector
+            } else {
+                format!("@count_size({}) {}", typ.count_size, vector)
+            }
+        }
+        ast::TopDecl::DynVec(ref typ) => format!("vector {} <{}>;", typ.name, typ.typ.name()),
+        ast::TopDecl::Table(ref typ) => {
+            format!("table {} {{ {} }}", typ.name, fields(&typ.inner))
+        }
+    }
+}
+
+fn fields(inner: &[ast::FieldDecl]) -> String {
+    inner
+        .iter()
+        .map(|field| format!("{}: {},", field.name, field.typ.name()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,121 @@
+use std::path::Path;
+
+use molecule::NUMBER_SIZE;
+
+use crate::{
+    ast::verified::{self as ast, HasName},
+    Parser,
+};
+
+/// The statistics of a type, for reviewing the design of a schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeStats {
+    pub name: String,
+    /// The kind of the type, such as `Table` or `FixVec`.
+    pub kind: String,
+    /// The size if the type is fixed-size.
+    pub fixed_size: Option<usize>,
+    /// The size of the smallest value.
+    pub min_size: usize,
+    /// The bytes of the headers in the smallest value, which are not data.
+    pub min_header_size: usize,
+    /// The maximum size of the values, or `None` if it's unbounded.
+    pub max_size: Option<usize>,
+    /// The count of the levels of the nested types; a byte is zero.
+    pub depth: usize,
+    /// The count of the fields or the items, or one for an option and a vector.
+    pub fan_out: usize,
+}
+
+impl TypeStats {
+    /// The percentage of the headers in the smallest value.
+    pub fn header_overhead(&self) -> f64 {
+        if self.min_size == 0 {
+            0.0
+        } else {
+            self.min_header_size as f64 * 100.0 / self.min_size as f64
+        }
+    }
+}
+
+/// Collect the statistics of the types in a schema file, in the order of the declarations.
+///
+/// The imported types are not listed.
+pub fn schema_stats<P: AsRef<Path>>(path: P) -> Vec<TypeStats> {
+    let ast = Parser::parse(&path);
+    ast.major_decls()
+        .iter()
+        .map(|decl| type_stats(decl))
+        .collect()
+}
+
+pub(crate) fn type_stats(decl: &ast::TopDecl) -> TypeStats {
+    let (min_size, min_header_size) = min_size(decl);
+    TypeStats {
+        name: decl.name().to_owned(),
+        kind: kind(decl).to_owned(),
+        fixed_size: decl.total_size(),
+        min_size,
+        min_header_size,
+        max_size: decl.max_size(),
+        depth: depth(decl),
+        fan_out: decl.dependencies().len(),
+    }
+}
+
+/// The kind of the declaration, as it's written in the schema.
+fn kind(decl: &ast::TopDecl) -> &'static str {
+    match decl {
+        ast::TopDecl::Atom(_) => "Atom",
+        ast::TopDecl::Option_(_) => "Option",
+        ast::TopDecl::Union(_) => "Union",
+        ast::TopDecl::Array(_) => "Array",
+        ast::TopDecl::Struct(_) => "Struct",
+        ast::TopDecl::FixVec(_) => "FixVec",
+        ast::TopDecl::DynVec(_) => "DynVec",
+        ast::TopDecl::Table(_) => "Table",
+    }
+}
+
+/// The size of the smallest value, and the bytes of the headers in it.
+pub(crate) fn min_size(decl: &ast::TopDecl) -> (usize, usize) {
+    match decl {
+        ast::TopDecl::Atom(ref typ) => (typ.size, 0),
+        ast::TopDecl::Option_(_) => (0, 0),
+        ast::TopDecl::Union(ref typ) => {
+            let (size, header_size) = typ
+                .inner
+                .iter()
+                .map(|item| min_size(&item.typ))
+                .min()
+                .unwrap_or((0, 0));
+            (NUMBER_SIZE + size, NUMBER_SIZE + header_size)
+        }
+        ast::TopDecl::Array(ref typ) => (typ.total_size(), 0),
+        ast::TopDecl::Struct(ref typ) => (typ.total_size(), 0),
+        ast::TopDecl::FixVec(ref typ) => (typ.count_size, typ.count_size),
+        ast::TopDecl::DynVec(_) => (NUMBER_SIZE, NUMBER_SIZE),
+        ast::TopDecl::Table(ref typ) => {
+            let header_size = NUMBER_SIZE * (typ.inner.len() + 1);
+            typ.inner
+                .iter()
+                .map(|field| min_size(&field.typ))
+                .fold((header_size, header_size), |(size, header_size), field| {
+                    (size + field.0, header_size + field.1)
+                })
+        }
+    }
+}
+
+fn depth(decl: &ast::TopDecl) -> usize {
+    if decl.is_atom() {
+        0
+    } else {
+        decl.dependencies()
+            .into_iter()
+            .map(|typ| depth(typ))
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
+}
@@ -0,0 +1,87 @@
+use std::path::Path;
+
+use molecule::NUMBER_SIZE;
+
+use crate::{
+    ast::verified::{self as ast, HasName},
+    stats, Parser,
+};
+
+/// A suggestion to refactor a type in a schema, to reduce the size of the encoded data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub type_name: String,
+    /// A stable name of the rule, such as `table-to-struct`.
+    pub rule: &'static str,
+    pub message: String,
+    /// The bytes which are saved for each value, or for each item of a vector.
+    pub saving: usize,
+}
+
+/// Check the types in a schema file, and suggest refactors which reduce the headers.
+///
+/// The imported types are not checked. The suggestions may break the compatibility, since the
+/// encodings are changed.
+pub fn suggest<P: AsRef<Path>>(path: P) -> Vec<Suggestion> {
+    suggest_for(&Parser::parse(&path))
+}
+
+pub(crate) fn suggest_for(ast: &ast::Ast) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    for decl in ast.major_decls() {
+        let mut add = |rule, saving, message| {
+            suggestions.push(Suggestion {
+                type_name: decl.name().to_owned(),
+                rule,
+                message,
+                saving,
+            })
+        };
+        match decl.as_ref() {
+            ast::TopDecl::Union(ref typ) if typ.inner.len() == 1 => {
+                let message = format!(
+                    "the union has only one item, use `{}` instead, saving {} bytes per value",
+                    typ.inner[0].typ.name(),
+                    NUMBER_SIZE
+                );
+                add("union-single-item", NUMBER_SIZE, message);
+            }
+            // Only a table could be fixed-size, as a struct.
+            ast::TopDecl::DynVec(ref typ) if matches!(typ.typ.as_ref(), ast::TopDecl::Table(_)) => {
+                let (min_size, min_header_size) = stats::min_size(&typ.typ);
+                if typ.typ.max_size() == Some(min_size) {
+                    let saving = NUMBER_SIZE + min_header_size;
+                    let message = format!(
+                        "the items always have {} bytes, make `{}` fixed-size so the vector is \
+                         a FixVec, saving {} bytes per item",
+                        min_size,
+                        typ.typ.name(),
+                        saving
+                    );
+                    add("dynvec-of-constant-size", saving, message);
+                }
+            }
+            ast::TopDecl::Table(ref typ) if !typ.inner.is_empty() => {
+                let saving = NUMBER_SIZE * (typ.inner.len() + 1);
+                if typ.inner.iter().all(|f| f.typ.total_size().is_some()) {
+                    let message = format!(
+                        "all fields are fixed-size, the table could be a struct, saving {} \
+                         bytes per value",
+                        saving
+                    );
+                    add("table-to-struct", saving, message);
+                } else if typ.inner.len() == 1 {
+                    let message = format!(
+                        "the table has only one field, use `{}` instead if no fields will be \
+                         added, saving {} bytes per value",
+                        typ.inner[0].typ.name(),
+                        saving
+                    );
+                    add("table-single-field", saving, message);
+                }
+            }
+            _ => {}
+        }
+    }
+    suggestions
+}
@@ -0,0 +1,346 @@
+//! A tree-sitter grammar which is translated from the rules of the parser.
+//!
+//! Tree-sitter re-parses a file incrementally, so editors could highlight and navigate large
+//! schema files on every keystroke. The breaks between the tokens become the `extras`, so the
+//! tree-sitter grammar also accepts comments in the few places where the parser doesn't, such as
+//! inside the paths of imports.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    editor::{rules, Syntax},
+    VERSION,
+};
+
+const ROOT_RULE: &str = "grammar";
+/// The rule of the whitespaces and the comments.
+const BREAK_RULE: &str = "brk";
+
+#[derive(Debug)]
+enum Expr {
+    Str(String),
+    Range(char, char),
+    Ref(String),
+    Seq(Vec<Expr>),
+    Choice(Vec<Expr>),
+    Repeat(Box<Expr>),
+    Repeat1(Box<Expr>),
+    Optional(Box<Expr>),
+    Not,
+    /// A positive predicate, which only improves the errors of pest.
+    Peek,
+}
+
+struct Rule {
+    silent: bool,
+    atomic: bool,
+    expr: Expr,
+}
+
+/// A recursive descent parser for the expressions of pest.
+struct ExprParser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    rule: &'a str,
+}
+
+impl<'a> ExprParser<'a> {
+    fn parse(rule: &'a str, body: &str) -> Rule {
+        let mut parser = Self {
+            chars: body.chars().collect(),
+            pos: 0,
+            rule,
+        };
+        parser.skip_spaces();
+        let (silent, atomic) = match parser.peek() {
+            Some('_') => (true, false),
+            Some('@') => (false, true),
+            _ => (false, false),
+        };
+        if silent || atomic {
+            parser.pos += 1;
+        }
+        parser.expect('{');
+        let expr = parser.choice();
+        parser.expect('}');
+        Rule {
+            silent,
+            atomic,
+            expr,
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, c: char) {
+        self.skip_spaces();
+        if self.peek() != Some(c) {
+            panic!("`{}` is expected in the rule `{}`", c, self.rule);
+        }
+        self.pos += 1;
+    }
+
+    fn choice(&mut self) -> Expr {
+        let mut items = vec![self.seq()];
+        self.skip_spaces();
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            items.push(self.seq());
+            self.skip_spaces();
+        }
+        if items.len() == 1 {
+            items.pop().unwrap()
+        } else {
+            Expr::Choice(items)
+        }
+    }
+
+    fn seq(&mut self) -> Expr {
+        let mut items = vec![self.term()];
+        self.skip_spaces();
+        while self.peek() == Some('~') {
+            self.pos += 1;
+            items.push(self.term());
+            self.skip_spaces();
+        }
+        if items.len() == 1 {
+            items.pop().unwrap()
+        } else {
+            Expr::Seq(items)
+        }
+    }
+
+    fn term(&mut self) -> Expr {
+        self.skip_spaces();
+        if self.peek() == Some('!') {
+            self.pos += 1;
+            self.term();
+            return Expr::Not;
+        }
+        if self.peek() == Some('&') {
+            self.pos += 1;
+            self.term();
+            return Expr::Peek;
+        }
+        let mut expr = self.primary();
+        loop {
+            expr = match self.peek() {
+                Some('*') => Expr::Repeat(Box::new(expr)),
+                Some('+') => Expr::Repeat1(Box::new(expr)),
+                Some('?') => Expr::Optional(Box::new(expr)),
+                _ => break expr,
+            };
+            self.pos += 1;
+        }
+    }
+
+    fn primary(&mut self) -> Expr {
+        self.skip_spaces();
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let expr = self.choice();
+                self.expect(')');
+                expr
+            }
+            Some('"') => Expr::Str(self.quoted('"')),
+            Some('\'') => {
+                let start = self.quoted('\'');
+                self.expect('.');
+                self.expect('.');
+                self.skip_spaces();
+                let end = self.quoted('\'');
+                Expr::Range(single(&start), single(&end))
+            }
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                let start = self.pos;
+                while self
+                    .peek()
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    self.pos += 1;
+                }
+                Expr::Ref(self.chars[start..self.pos].iter().collect())
+            }
+            _ => panic!("unsupported expression in the rule `{}`", self.rule),
+        }
+    }
+
+    fn quoted(&mut self, quote: char) -> String {
+        self.pos += 1;
+        let mut literal = String::new();
+        loop {
+            let c = match self.peek() {
+                Some(c) if c == quote => break,
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some(c) if c == '\\' || c == '"' || c == '\'' => c,
+                        _ => panic!("unsupported escape in the rule `{}`", self.rule),
+                    }
+                }
+                Some(c) => c,
+                None => panic!("unterminated literal in the rule `{}`", self.rule),
+            };
+            literal.push(c);
+            self.pos += 1;
+        }
+        self.pos += 1;
+        literal
+    }
+}
+
+fn single(literal: &str) -> char {
+    let mut chars = literal.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => c,
+        _ => panic!("a range should be between two characters"),
+    }
+}
+
+struct Translator {
+    rules: HashMap<&'static str, Rule>,
+}
+
+impl Translator {
+    fn name(&self, rule: &str) -> String {
+        if self.rules[rule].silent {
+            format!("_{}", rule)
+        } else {
+            rule.to_owned()
+        }
+    }
+
+    /// Translate an expression into the DSL of tree-sitter; the breaks are dropped.
+    ///
+    /// The references are inlined in atomic rules, since tokens can't refer to other rules.
+    fn translate(&self, expr: &Expr, atomic: bool, refs: &mut Vec<String>) -> Option<String> {
+        let list = |items: &[Expr], func: &str, refs: &mut Vec<String>| {
+            let mut items: Vec<_> = items
+                .iter()
+                .filter_map(|item| self.translate(item, atomic, refs))
+                .collect();
+            match items.len() {
+                0 => None,
+                1 => items.pop(),
+                _ => Some(format!("{}({})", func, items.join(", "))),
+            }
+        };
+        let wrap = |inner: &Expr, func: &str, refs: &mut Vec<String>| {
+            self.translate(inner, atomic, refs)
+                .map(|inner| format!("{}({})", func, inner))
+        };
+        match expr {
+            Expr::Str(literal) => Some(serde_json::to_string(literal).unwrap()),
+            Expr::Range(start, end) => Some(format!("/[{}-{}]/", start, end)),
+            Expr::Ref(name) if name == BREAK_RULE || name == "SOI" || name == "EOI" => None,
+            Expr::Ref(name) => {
+                let rule = self
+                    .rules
+                    .get(name.as_str())
+                    .unwrap_or_else(|| panic!("the rule `{}` is not in the grammar", name));
+                if atomic {
+                    self.translate(&rule.expr, atomic, refs)
+                } else {
+                    refs.push(name.to_owned());
+                    Some(format!("$.{}", self.name(name)))
+                }
+            }
+            Expr::Seq(items) => list(items, "seq", refs),
+            Expr::Choice(items) => list(items, "choice", refs),
+            Expr::Repeat(inner) => wrap(inner, "repeat", refs),
+            Expr::Repeat1(inner) => wrap(inner, "repeat1", refs),
+            Expr::Optional(inner) => wrap(inner, "optional", refs),
+            Expr::Not => panic!("negative predicates are only supported in the comments"),
+            Expr::Peek => None,
+        }
+    }
+}
+
+/// Translate the rules which are reachable from the root, skipping the breaks.
+pub(crate) fn grammar_js(syntax: &Syntax) -> String {
+    let translator = Translator {
+        rules: rules()
+            .into_iter()
+            .map(|(name, body)| (name, ExprParser::parse(name, body)))
+            .collect(),
+    };
+    let mut lines = Vec::new();
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+    queue.push_back(ROOT_RULE.to_owned());
+    while let Some(name) = queue.pop_front() {
+        if !visited.insert(name.clone()) {
+            continue;
+        }
+        let rule = &translator.rules[name.as_str()];
+        let mut refs = Vec::new();
+        let expr = translator
+            .translate(&rule.expr, rule.atomic, &mut refs)
+            .unwrap_or_else(|| panic!("the rule `{}` is empty", name));
+        let expr = if rule.atomic {
+            format!("token({})", expr)
+        } else {
+            expr
+        };
+        lines.push(format!("    {}: $ => {},", translator.name(&name), expr));
+        queue.extend(refs);
+    }
+    let line_comments = syntax
+        .line_comments
+        .iter()
+        .map(|x| serde_json::to_string(x).unwrap())
+        .collect::<Vec<_>>()
+        .join(", ");
+    lines.push(format!(
+        "    comment: $ => token(choice(seq(choice({}), /.*/), seq({}, /[^*]*\\*+([^/*][^*]*\\*+)*/, \"/\"))),",
+        line_comments,
+        serde_json::to_string(&syntax.block_comment.0).unwrap(),
+    ));
+    format!(
+        "// Generated by moleculec {} from the grammar of the compiler, don't edit it.\n\
+         module.exports = grammar({{\n  \
+           name: 'molecule',\n  \
+           word: $ => $.identifier,\n  \
+           extras: $ => [/[ \\t\\r\\n]/, $.comment],\n  \
+           rules: {{\n\
+         {}\n  \
+           }},\n\
+         }});\n",
+        VERSION,
+        lines.join("\n")
+    )
+}
+
+/// The highlight queries of the tree-sitter grammar.
+pub(crate) fn highlights_scm(syntax: &Syntax) -> String {
+    let keywords = syntax
+        .keywords
+        .iter()
+        .map(|x| format!("\"{}\"", x))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "[{}] @keyword\n\
+         (comment) @comment\n\
+         (number) @number\n\
+         (attribute (identifier) @attribute)\n\
+         (constraint \"assert\" @keyword)\n\
+         [(len_of \"len\") (item_id_of \"item_id\")] @function.builtin\n\
+         (comparison) @operator\n\
+         (field_decl (identifier) @property . (identifier))\n\
+         (identifier) @type\n",
+        keywords
+    )
+}
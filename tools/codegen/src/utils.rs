@@ -4,11 +4,69 @@ use pest::iterators::Pairs;
 
 use crate::{ast, parser};
 
+/// The attributes of a declaration, which are checked by the kind of it.
+#[derive(Debug, Default)]
+pub(crate) struct DeclAttributes {
+    pub(crate) count_size: Option<usize>,
+    pub(crate) invariant: Option<String>,
+    pub(crate) checksum: Option<ast::raw::Checksum>,
+    pub(crate) logical: Option<ast::raw::Logical>,
+}
+
+impl DeclAttributes {
+    pub(crate) fn no_count_size(&self) {
+        if self.count_size.is_some() {
+            panic!("the attribute `@count_size` is only for vectors");
+        }
+    }
+
+    pub(crate) fn no_checksum(&self) {
+        if self.checksum.is_some() {
+            panic!("the attribute `@checksum` is only for tables");
+        }
+    }
+
+    pub(crate) fn no_logical(&self) {
+        if self.logical.is_some() {
+            panic!("the attribute `@logical` is only for arrays");
+        }
+    }
+}
+
+fn parse_logical(value: &str) -> ast::raw::Logical {
+    use ast::raw::{Logical, TimeUnit};
+    if value == "uuid" {
+        return Logical::Uuid;
+    }
+    let (kind, unit) = value.split_at(value.find('_').unwrap_or(value.len()));
+    let unit = match unit {
+        "_seconds" => Some(TimeUnit::Seconds),
+        "_millis" => Some(TimeUnit::Millis),
+        "_micros" => Some(TimeUnit::Micros),
+        "_nanos" => Some(TimeUnit::Nanos),
+        _ => None,
+    };
+    match (kind, unit) {
+        ("unix", Some(unit)) => Logical::UnixTime(unit),
+        ("duration", Some(unit)) => Logical::Duration(unit),
+        _ => panic!(
+            "the logical type should be unix_{{seconds,millis,micros,nanos}}, \
+             duration_{{seconds,millis,micros,nanos}} or uuid, but it is {}",
+            value
+        ),
+    }
+}
+
 pub(crate) trait PairsUtils {
     fn next_string(&mut self) -> String;
     fn next_usize(&mut self) -> usize;
+    fn next_attributes(&mut self) -> Vec<(String, Option<String>)>;
+    fn next_decl_attributes(&mut self) -> DeclAttributes;
+    fn next_invariant(&mut self) -> Option<String>;
     fn next_items(&mut self) -> Vec<ast::raw::ItemDecl>;
     fn next_fields(&mut self) -> Vec<ast::raw::FieldDecl>;
+    fn next_constraints(&mut self) -> Vec<ast::raw::Constraint>;
+    fn next_operand(&mut self) -> ast::raw::Operand;
     fn next_import<P: AsRef<Path>>(
         &mut self,
         imported_base: &P,
@@ -26,6 +84,74 @@ impl<'i> PairsUtils for Pairs<'i, parser::Rule> {
         usize::from_str(self.next().unwrap().as_str()).unwrap()
     }
 
+    fn next_attributes(&mut self) -> Vec<(String, Option<String>)> {
+        let mut ret = Vec::new();
+        while let Some(attr) = self.peek() {
+            if attr.as_rule() != parser::Rule::attribute {
+                break;
+            }
+            self.next();
+            let mut pair = attr.into_inner();
+            let name = pair.next_string();
+            let value = pair.peek().is_some().then(|| pair.next_string());
+            pair.next_should_be_none();
+            if ret.iter().any(|(n, _)| *n == name) {
+                panic!("the attribute `@{}` is duplicated", name);
+            }
+            ret.push((name, value));
+        }
+        ret
+    }
+
+    fn next_decl_attributes(&mut self) -> DeclAttributes {
+        let mut attributes = DeclAttributes::default();
+        for (name, value) in self.next_attributes() {
+            match (name.as_str(), value) {
+                ("count_size", Some(value)) => {
+                    let value = usize::from_str(&value).unwrap_or_else(|_| {
+                        panic!("the count size should be a number, but it is {}", value)
+                    });
+                    if value > molecule::NUMBER_SIZE {
+                        panic!(
+                            "the count size should be between 1 and {}, but it is {}",
+                            molecule::NUMBER_SIZE,
+                            value
+                        );
+                    }
+                    attributes.count_size = Some(value);
+                }
+                ("count_size", None) => panic!("the attribute `@count_size` requires a size"),
+                ("validate", Some(value)) => {
+                    if usize::from_str(&value).is_ok() {
+                        panic!("the invariant should be a name, but it is {}", value);
+                    }
+                    attributes.invariant = Some(value);
+                }
+                ("validate", None) => panic!("the attribute `@validate` requires a name"),
+                ("checksum", Some(value)) => {
+                    attributes.checksum = Some(match value.as_str() {
+                        "crc32" => ast::raw::Checksum::Crc32,
+                        "xxh32" => ast::raw::Checksum::Xxh32,
+                        _ => panic!("the checksum should be crc32 or xxh32, but it is {}", value),
+                    });
+                }
+                ("checksum", None) => panic!("the attribute `@checksum` requires an algorithm"),
+                ("logical", Some(value)) => attributes.logical = Some(parse_logical(&value)),
+                ("logical", None) => panic!("the attribute `@logical` requires a type"),
+                _ => panic!("the attribute `@{}` is unknown", name),
+            }
+        }
+        attributes
+    }
+
+    fn next_invariant(&mut self) -> Option<String> {
+        let attributes = self.next_decl_attributes();
+        attributes.no_count_size();
+        attributes.no_checksum();
+        attributes.no_logical();
+        attributes.invariant
+    }
+
     fn next_items(&mut self) -> Vec<ast::raw::ItemDecl> {
         let mut ret = Vec::new();
         for item in self {
@@ -33,8 +159,25 @@ impl<'i> PairsUtils for Pairs<'i, parser::Rule> {
                 unreachable!()
             }
             let mut pair = item.into_inner();
+            let mut max_size = None;
+            for (name, value) in pair.next_attributes() {
+                match (name.as_str(), value) {
+                    ("max_size", Some(value)) => {
+                        let value = usize::from_str(&value).unwrap_or_else(|_| {
+                            panic!("the max size should be a number, but it is {}", value)
+                        });
+                        max_size = Some(value);
+                    }
+                    ("max_size", None) => panic!("the attribute `@max_size` requires a size"),
+                    _ => panic!("the attribute `@{}` is unknown", name),
+                }
+            }
+            let typ = pair.next_string();
+            let alias = pair.next().map(|alias| alias.into_inner().next_string());
             let node = ast::raw::ItemDecl {
-                typ: pair.next_string(),
+                typ,
+                alias,
+                max_size,
             };
             pair.next_should_be_none();
             ret.push(node);
@@ -44,14 +187,32 @@ impl<'i> PairsUtils for Pairs<'i, parser::Rule> {
 
     fn next_fields(&mut self) -> Vec<ast::raw::FieldDecl> {
         let mut ret = Vec::new();
-        for field in self {
+        while let Some(field) = self.peek() {
             if field.as_rule() != parser::Rule::field_decl {
-                unreachable!()
+                break;
             }
+            self.next();
             let mut pair = field.into_inner();
+            let mut flatten = false;
+            let mut display = None;
+            let mut required = false;
+            for (name, value) in pair.next_attributes() {
+                match (name.as_str(), value) {
+                    ("flatten", None) => flatten = true,
+                    ("flatten", Some(_)) => panic!("the attribute `@flatten` has no value"),
+                    ("display", Some(value)) => display = Some(value),
+                    ("display", None) => panic!("the attribute `@display` requires a formatter"),
+                    ("required", None) => required = true,
+                    ("required", Some(_)) => panic!("the attribute `@required` has no value"),
+                    _ => panic!("the attribute `@{}` is unknown", name),
+                }
+            }
             let node = ast::raw::FieldDecl {
                 name: pair.next_string(),
                 typ: pair.next_string(),
+                flatten,
+                display,
+                required,
             };
             pair.next_should_be_none();
             ret.push(node);
@@ -59,6 +220,52 @@ impl<'i> PairsUtils for Pairs<'i, parser::Rule> {
         ret
     }
 
+    fn next_constraints(&mut self) -> Vec<ast::raw::Constraint> {
+        let mut ret = Vec::new();
+        for constraint in self {
+            if constraint.as_rule() != parser::Rule::constraint {
+                unreachable!()
+            }
+            let mut pair = constraint.into_inner();
+            let lhs = pair.next_operand();
+            let comparison = match pair.next().unwrap().as_str() {
+                "==" => ast::raw::Comparison::Eq,
+                "!=" => ast::raw::Comparison::Ne,
+                "<" => ast::raw::Comparison::Lt,
+                "<=" => ast::raw::Comparison::Le,
+                ">" => ast::raw::Comparison::Gt,
+                ">=" => ast::raw::Comparison::Ge,
+                _ => unreachable!(),
+            };
+            let rhs = pair.next_operand();
+            pair.next_should_be_none();
+            ret.push(ast::raw::Constraint {
+                lhs,
+                comparison,
+                rhs,
+            });
+        }
+        ret
+    }
+
+    fn next_operand(&mut self) -> ast::raw::Operand {
+        let operand = self.next().unwrap().into_inner().next().unwrap();
+        match operand.as_rule() {
+            parser::Rule::len_of => ast::raw::Operand::Len(operand.into_inner().next_string()),
+            parser::Rule::item_id_of => {
+                ast::raw::Operand::ItemId(operand.into_inner().next_string())
+            }
+            parser::Rule::identifier => ast::raw::Operand::Value(operand.as_str().to_owned()),
+            parser::Rule::integer => {
+                let value = operand.as_str();
+                ast::raw::Operand::Integer(u64::from_str(value).unwrap_or_else(|_| {
+                    panic!("the integer {} in a constraint is too large", value)
+                }))
+            }
+            _ => unreachable!(),
+        }
+    }
+
     fn next_import<P: AsRef<Path>>(
         &mut self,
         imported_base: &P,
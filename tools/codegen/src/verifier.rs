@@ -0,0 +1,316 @@
+//! Verify data against a schema at runtime, without generating code.
+//!
+//! The rules and the errors are the same as the `verify` functions of the generated Rust readers.
+
+use std::{path::Path, rc::Rc};
+
+use molecule::{
+    error::{VerificationError, VerificationResult},
+    fixvec_total_size, header_size, pack_number, unpack_count, unpack_number, Number, NUMBER_SIZE,
+};
+
+use crate::{
+    ast::verified::{self as ast, HasName},
+    Parser,
+};
+
+/// Verify buffers as a type of a schema file.
+pub struct Verifier {
+    decl: Rc<ast::TopDecl>,
+    compatible: bool,
+}
+
+impl Verifier {
+    pub fn new<P: AsRef<Path>, S: AsRef<str>>(path: P, type_name: S) -> Self {
+        let ast = Parser::parse(&path);
+        let type_name = type_name.as_ref();
+        let decl = ast
+            .get_decl(type_name)
+            .unwrap_or_else(|| panic!("the type `{}` is not found", type_name));
+        Self {
+            decl,
+            compatible: false,
+        }
+    }
+
+    /// Verify the buffers as `from_compatible_slice` does.
+    pub fn compatible(&mut self, enable: bool) -> &mut Self {
+        self.compatible = enable;
+        self
+    }
+
+    pub fn verify(&self, data: &[u8]) -> VerificationResult<()> {
+        verify(&self.decl, data, self.compatible)
+    }
+
+    /// Verify the buffer, and re-encode it in the canonical form without the extra fields of the
+    /// tables, as `molecule::canonical::recanonicalize` of the generated Rust readers does.
+    pub fn recanonicalize(&self, data: &[u8]) -> VerificationResult<Vec<u8>> {
+        self.verify(data)?;
+        let mut out = Vec::with_capacity(data.len());
+        write_canonical(&self.decl, data, &mut out);
+        Ok(out)
+    }
+
+    /// Verify the buffer, and check if it's in the canonical form already.
+    pub fn is_canonical(&self, data: &[u8]) -> VerificationResult<bool> {
+        self.verify(data)?;
+        Ok(canonical_size(&self.decl, data) == data.len())
+    }
+}
+
+pub(crate) fn reader_name(decl: &ast::TopDecl) -> String {
+    if decl.is_atom() {
+        format!("{}Reader", ast::ATOM_PRIMITIVE_NAME)
+    } else {
+        format!("{}Reader", decl.name())
+    }
+}
+
+macro_rules! ve {
+    ($decl:ident, $err:ident $(, $args:expr )*) => {
+        Err(VerificationError::$err(reader_name($decl) $(, $args )*))
+    }
+}
+
+/// Unpack the offsets in the header and append the total size, or return `None` if they are
+/// not in order.
+fn unpack_offsets(slice: &[u8], count: usize) -> Option<Vec<usize>> {
+    let mut offsets: Vec<usize> = slice[NUMBER_SIZE..]
+        .chunks(NUMBER_SIZE)
+        .take(count)
+        .map(|x| unpack_number(x) as usize)
+        .collect();
+    offsets.push(slice.len());
+    if offsets.windows(2).any(|i| i[0] > i[1]) {
+        None
+    } else {
+        Some(offsets)
+    }
+}
+
+/// The slices of the items of a verified DynVec or Table, including the extra fields.
+fn items(slice: &[u8]) -> Vec<&[u8]> {
+    if slice.len() == NUMBER_SIZE {
+        return Vec::new();
+    }
+    let count = unpack_number(&slice[NUMBER_SIZE..]) as usize / NUMBER_SIZE - 1;
+    let offsets = unpack_offsets(slice, count).expect("the offsets are verified");
+    offsets
+        .windows(2)
+        .map(|pair| &slice[pair[0]..pair[1]])
+        .collect()
+}
+
+/// The size of a verified value in the canonical form.
+fn canonical_size(decl: &ast::TopDecl, slice: &[u8]) -> usize {
+    match decl {
+        ast::TopDecl::Option_(ref typ) if !slice.is_empty() => canonical_size(&typ.typ, slice),
+        ast::TopDecl::Union(ref typ) => {
+            let item = &typ.inner[unpack_number(slice) as usize];
+            NUMBER_SIZE + canonical_size(&item.typ, &slice[NUMBER_SIZE..])
+        }
+        ast::TopDecl::DynVec(ref typ) => {
+            let items = items(slice);
+            NUMBER_SIZE * (items.len() + 1)
+                + items
+                    .into_iter()
+                    .map(|item| canonical_size(&typ.typ, item))
+                    .sum::<usize>()
+        }
+        ast::TopDecl::Table(ref typ) => {
+            NUMBER_SIZE * (typ.inner.len() + 1)
+                + typ
+                    .inner
+                    .iter()
+                    .zip(items(slice))
+                    .map(|(field, item)| canonical_size(&field.typ, item))
+                    .sum::<usize>()
+        }
+        _ => slice.len(),
+    }
+}
+
+fn write_canonical_items<'a, I>(items: I, out: &mut Vec<u8>)
+where
+    I: Iterator<Item = (&'a ast::TopDecl, &'a [u8])> + Clone,
+{
+    let sizes = items
+        .clone()
+        .map(|(decl, slice)| canonical_size(decl, slice))
+        .collect::<Vec<_>>();
+    let header_size = NUMBER_SIZE * (sizes.len() + 1);
+    let total_size = header_size + sizes.iter().sum::<usize>();
+    out.extend_from_slice(&pack_number(total_size as Number));
+    let mut offset = header_size;
+    for size in sizes {
+        out.extend_from_slice(&pack_number(offset as Number));
+        offset += size;
+    }
+    for (decl, slice) in items {
+        write_canonical(decl, slice, out);
+    }
+}
+
+/// Write a verified value in the canonical form.
+fn write_canonical(decl: &ast::TopDecl, slice: &[u8], out: &mut Vec<u8>) {
+    match decl {
+        ast::TopDecl::Option_(ref typ) if !slice.is_empty() => {
+            write_canonical(&typ.typ, slice, out);
+        }
+        ast::TopDecl::Union(ref typ) => {
+            let (item_id, inner) = slice.split_at(NUMBER_SIZE);
+            out.extend_from_slice(item_id);
+            let item = &typ.inner[unpack_number(item_id) as usize];
+            write_canonical(&item.typ, inner, out);
+        }
+        ast::TopDecl::DynVec(ref typ) => {
+            let items = items(slice);
+            write_canonical_items(items.iter().map(|item| (typ.typ.as_ref(), *item)), out);
+        }
+        ast::TopDecl::Table(ref typ) => {
+            let items = items(slice);
+            let fields = typ
+                .inner
+                .iter()
+                .zip(items.iter())
+                .map(|(field, item)| (field.typ.as_ref(), *item));
+            write_canonical_items(fields, out);
+        }
+        _ => out.extend_from_slice(slice),
+    }
+}
+
+#[allow(clippy::manual_is_multiple_of)]
+pub(crate) fn verify(
+    decl: &ast::TopDecl,
+    slice: &[u8],
+    compatible: bool,
+) -> VerificationResult<()> {
+    let slice_len = slice.len();
+    match decl {
+        ast::TopDecl::Atom(ref typ) => {
+            if slice_len != typ.size {
+                return ve!(decl, TotalSizeNotMatch, typ.size, slice_len);
+            }
+        }
+        ast::TopDecl::Option_(ref typ) => {
+            if !slice.is_empty() {
+                verify(&typ.typ, slice, compatible)?;
+            }
+        }
+        ast::TopDecl::Union(ref typ) => {
+            if slice_len < NUMBER_SIZE {
+                return ve!(decl, HeaderIsBroken, NUMBER_SIZE, slice_len);
+            }
+            let item_id = unpack_number(slice);
+            if let Some(item) = typ.inner.get(item_id as usize) {
+                let inner = &slice[NUMBER_SIZE..];
+                if let Some(max_size) = item.max_size.filter(|max| inner.len() > *max) {
+                    return ve!(decl, ItemTooLarge, max_size, inner.len());
+                }
+                verify(&item.typ, inner, compatible)?;
+            } else {
+                return ve!(decl, UnknownItem, typ.inner.len(), item_id);
+            }
+        }
+        ast::TopDecl::Array(ref typ) => {
+            if slice_len != typ.total_size() {
+                return ve!(decl, TotalSizeNotMatch, typ.total_size(), slice_len);
+            }
+        }
+        ast::TopDecl::Struct(ref typ) => {
+            if slice_len != typ.total_size() {
+                return ve!(decl, TotalSizeNotMatch, typ.total_size(), slice_len);
+            }
+        }
+        ast::TopDecl::FixVec(ref typ) => {
+            if slice_len < typ.count_size {
+                return ve!(decl, HeaderIsBroken, typ.count_size, slice_len);
+            }
+            let item_count = unpack_count(slice, typ.count_size);
+            let total_size = match fixvec_total_size(typ.count_size, typ.item_size, item_count) {
+                Some(total_size) => total_size,
+                None => return ve!(decl, SizeOverflow),
+            };
+            if slice_len != total_size {
+                return ve!(decl, TotalSizeNotMatch, total_size, slice_len);
+            }
+        }
+        ast::TopDecl::DynVec(ref typ) => {
+            if slice_len < NUMBER_SIZE {
+                return ve!(decl, HeaderIsBroken, NUMBER_SIZE, slice_len);
+            }
+            let total_size = unpack_number(slice) as usize;
+            if slice_len != total_size {
+                return ve!(decl, TotalSizeNotMatch, total_size, slice_len);
+            }
+            if slice_len == NUMBER_SIZE {
+                return Ok(());
+            }
+            if slice_len < NUMBER_SIZE * 2 {
+                return ve!(decl, TotalSizeNotMatch, NUMBER_SIZE * 2, slice_len);
+            }
+            let offset_first = unpack_number(&slice[NUMBER_SIZE..]) as usize;
+            if offset_first % 4 != 0 || offset_first < NUMBER_SIZE * 2 {
+                return ve!(decl, OffsetsNotMatch);
+            }
+            let item_count = offset_first / 4 - 1;
+            let header_size = match header_size(item_count) {
+                Some(header_size) => header_size,
+                None => return ve!(decl, SizeOverflow),
+            };
+            if slice_len < header_size {
+                return ve!(decl, HeaderIsBroken, header_size, slice_len);
+            }
+            let offsets = if let Some(offsets) = unpack_offsets(slice, item_count) {
+                offsets
+            } else {
+                return ve!(decl, OffsetsNotMatch);
+            };
+            for pair in offsets.windows(2) {
+                verify(&typ.typ, &slice[pair[0]..pair[1]], compatible)?;
+            }
+        }
+        ast::TopDecl::Table(ref typ) => {
+            let expected = typ.inner.len();
+            if slice_len < NUMBER_SIZE {
+                return ve!(decl, HeaderIsBroken, NUMBER_SIZE, slice_len);
+            }
+            let total_size = unpack_number(slice) as usize;
+            if slice_len != total_size {
+                return ve!(decl, TotalSizeNotMatch, total_size, slice_len);
+            }
+            if slice_len == NUMBER_SIZE && expected == 0 {
+                return Ok(());
+            }
+            if slice_len < NUMBER_SIZE * 2 {
+                return ve!(decl, HeaderIsBroken, NUMBER_SIZE * 2, slice_len);
+            }
+            let offset_first = unpack_number(&slice[NUMBER_SIZE..]) as usize;
+            if offset_first % 4 != 0 || offset_first < NUMBER_SIZE * 2 {
+                return ve!(decl, OffsetsNotMatch);
+            }
+            let field_count = offset_first / 4 - 1;
+            if field_count < expected || (!compatible && field_count > expected) {
+                return ve!(decl, FieldCountNotMatch, expected, field_count);
+            }
+            let header_size = match header_size(field_count) {
+                Some(header_size) => header_size,
+                None => return ve!(decl, SizeOverflow),
+            };
+            if slice_len < header_size {
+                return ve!(decl, HeaderIsBroken, header_size, slice_len);
+            }
+            let offsets = if let Some(offsets) = unpack_offsets(slice, field_count) {
+                offsets
+            } else {
+                return ve!(decl, OffsetsNotMatch);
+            };
+            for (field, pair) in typ.inner.iter().zip(offsets.windows(2)) {
+                verify(&field.typ, &slice[pair[0]..pair[1]], compatible)?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,38 +1,557 @@
 use std::{convert::TryInto, path::PathBuf, process};
 
-use codegen::Language;
+use codegen::{
+    CProfile, Edition, ExampleLanguage, Flatten, Language, Level, LintLevels, Visibility,
+};
+
+pub(crate) enum Command {
+    Compile(AppConfig),
+    Bounds(PathBuf),
+    Stats(PathBuf),
+    Repl(PathBuf),
+    Explain(PathBuf, String),
+    Editor(PathBuf),
+    Suggest(SuggestConfig),
+    Lint(LintConfig),
+    Minimize(MinimizeConfig),
+    Mutate(MutateConfig),
+    Pack(PackConfig),
+    Unpack(UnpackConfig),
+    Migrate(MigrateConfig),
+    Examples(ExamplesConfig),
+    Infer(InferConfig),
+}
 
 pub(crate) struct AppConfig {
     pub(crate) schema_file: PathBuf,
     pub(crate) language: Language,
+    pub(crate) c_profile: CProfile,
+    pub(crate) dissector_port: Option<u16>,
+    pub(crate) dissector_heuristic: bool,
+    pub(crate) json_schema: bool,
+    pub(crate) arrow: bool,
+    pub(crate) rayon: bool,
+    pub(crate) small_vectors: Option<usize>,
+    pub(crate) transparent: bool,
+    pub(crate) source_readers: bool,
+    pub(crate) linear_verification: bool,
+    pub(crate) pyo3: bool,
+    pub(crate) compact_arrays: bool,
+    pub(crate) typestate_builders: bool,
+    pub(crate) byte_comparisons: bool,
+    pub(crate) builder_validation: bool,
+    pub(crate) checked_getters: bool,
+    pub(crate) field_access: bool,
+    pub(crate) max_encoded_size: bool,
+    pub(crate) types: Vec<String>,
+    pub(crate) flatten: Flatten,
+    pub(crate) extern_schemas: Vec<(String, String)>,
+    pub(crate) visibility: Visibility,
+    pub(crate) rust_edition: Edition,
+    pub(crate) rust_allows: Vec<String>,
+    pub(crate) rust_format: bool,
+    pub(crate) header_file: Option<PathBuf>,
+    pub(crate) hooks_dir: Option<PathBuf>,
+    pub(crate) templates_dir: Option<PathBuf>,
+    pub(crate) reproducible: bool,
+    pub(crate) verify_reproducible: bool,
+    pub(crate) lint_levels: LintLevels,
+    pub(crate) json_messages: bool,
+}
+
+pub(crate) struct LintConfig {
+    pub(crate) schema_file: PathBuf,
+    pub(crate) lint_levels: LintLevels,
+    pub(crate) json_messages: bool,
+}
+
+pub(crate) struct SuggestConfig {
+    pub(crate) schema_file: PathBuf,
+    pub(crate) json: bool,
+}
+
+pub(crate) struct MinimizeConfig {
+    pub(crate) schema_file: PathBuf,
+    pub(crate) type_name: String,
+    pub(crate) input: PathBuf,
+    pub(crate) output: Option<PathBuf>,
+    pub(crate) compatible: bool,
+}
+
+pub(crate) struct MutateConfig {
+    pub(crate) schema_file: PathBuf,
+    pub(crate) type_name: String,
+    pub(crate) input: PathBuf,
+}
+
+pub(crate) struct PackConfig {
+    pub(crate) schema_file: PathBuf,
+    pub(crate) type_name: String,
+    pub(crate) inputs: Vec<PathBuf>,
+    pub(crate) output: PathBuf,
+    pub(crate) append: bool,
+    pub(crate) compatible: bool,
+}
+
+pub(crate) struct UnpackConfig {
+    pub(crate) schema_file: PathBuf,
+    pub(crate) type_name: String,
+    pub(crate) input: PathBuf,
+    pub(crate) output_dir: PathBuf,
+    pub(crate) compatible: bool,
+}
+
+pub(crate) struct MigrateConfig {
+    pub(crate) old_schema_file: PathBuf,
+    pub(crate) new_schema_file: PathBuf,
+    pub(crate) mapping_file: Option<PathBuf>,
+    pub(crate) old_module: String,
+    pub(crate) new_module: String,
+    pub(crate) output: Option<PathBuf>,
 }
 
-pub(crate) fn build_commandline() -> AppConfig {
+pub(crate) struct ExamplesConfig {
+    pub(crate) schema_file: PathBuf,
+    pub(crate) language: ExampleLanguage,
+    pub(crate) module: Option<String>,
+    pub(crate) output_dir: PathBuf,
+}
+
+pub(crate) struct InferConfig {
+    pub(crate) type_name: String,
+    pub(crate) inputs: Vec<PathBuf>,
+    pub(crate) output: Option<PathBuf>,
+}
+
+pub(crate) fn build_commandline() -> Command {
     let yaml = clap::load_yaml!("cli.yaml");
     let matches = clap::App::from_yaml(yaml)
         .version(clap::crate_version!())
         .get_matches();
-    AppConfig::from(&matches)
+    match matches.subcommand() {
+        ("bounds", Some(matches)) => Command::Bounds(schema_file_from(matches)),
+        ("stats", Some(matches)) => Command::Stats(schema_file_from(matches)),
+        ("repl", Some(matches)) => Command::Repl(schema_file_from(matches)),
+        ("explain", Some(matches)) => {
+            let type_name = value_t!(matches, "type", String).unwrap_or_else(|e| e.exit());
+            Command::Explain(schema_file_from(matches), type_name)
+        }
+        ("editor", Some(matches)) => {
+            Command::Editor(value_t!(matches, "output-dir", PathBuf).unwrap_or_else(|e| e.exit()))
+        }
+        ("suggest", Some(matches)) => Command::Suggest(SuggestConfig::from(matches)),
+        ("lint", Some(matches)) => Command::Lint(LintConfig::from(matches)),
+        ("minimize", Some(matches)) => Command::Minimize(MinimizeConfig::from(matches)),
+        ("mutate", Some(matches)) => Command::Mutate(MutateConfig::from(matches)),
+        ("pack", Some(matches)) => Command::Pack(PackConfig::from(matches)),
+        ("unpack", Some(matches)) => Command::Unpack(UnpackConfig::from(matches)),
+        ("migrate", Some(matches)) => Command::Migrate(MigrateConfig::from(matches)),
+        ("examples", Some(matches)) => Command::Examples(ExamplesConfig::from(matches)),
+        ("infer", Some(matches)) => Command::Infer(InferConfig::from(matches)),
+        _ => Command::Compile(AppConfig::from(&matches)),
+    }
+}
+
+fn schema_file_from(matches: &clap::ArgMatches) -> PathBuf {
+    let schema_file = value_t!(matches, "schema-file", PathBuf).unwrap_or_else(|e| e.exit());
+    if !schema_file.as_path().is_file() {
+        eprintln!(
+            "Error: schema-file [{}] should be a file",
+            schema_file.to_str().unwrap()
+        );
+        process::exit(1);
+    }
+    schema_file
+}
+
+fn header_file_from(matches: &clap::ArgMatches) -> Option<PathBuf> {
+    let header_file = value_t!(matches, "header-file", PathBuf).ok()?;
+    if !header_file.as_path().is_file() {
+        eprintln!(
+            "Error: header-file [{}] should be a file",
+            header_file.to_str().unwrap()
+        );
+        process::exit(1);
+    }
+    Some(header_file)
+}
+
+fn hooks_dir_from(matches: &clap::ArgMatches) -> Option<PathBuf> {
+    let hooks_dir = value_t!(matches, "hooks-dir", PathBuf).ok()?;
+    if !hooks_dir.as_path().is_dir() {
+        eprintln!(
+            "Error: hooks-dir [{}] should be a directory",
+            hooks_dir.to_str().unwrap()
+        );
+        process::exit(1);
+    }
+    Some(hooks_dir)
+}
+
+fn templates_dir_from(matches: &clap::ArgMatches) -> Option<PathBuf> {
+    let templates_dir = value_t!(matches, "templates-dir", PathBuf).ok()?;
+    if !templates_dir.as_path().is_dir() {
+        eprintln!(
+            "Error: templates-dir [{}] should be a directory",
+            templates_dir.to_str().unwrap()
+        );
+        process::exit(1);
+    }
+    Some(templates_dir)
+}
+
+fn json_messages_from(matches: &clap::ArgMatches) -> bool {
+    value_t!(matches, "message-format", String).unwrap_or_else(|e| e.exit()) == "json"
+}
+
+fn lint_levels_from(matches: &clap::ArgMatches) -> LintLevels {
+    let mut levels = LintLevels::new();
+    for (arg, level) in &[
+        ("allow", Level::Allow),
+        ("warn", Level::Warn),
+        ("deny", Level::Deny),
+    ] {
+        for name in values_t!(matches, *arg, String).unwrap_or_default() {
+            if *level == Level::Deny && name == codegen::WARNINGS {
+                levels.deny_warnings(true);
+            } else if codegen::find_lint(&name).is_some() {
+                levels.set(&name, *level);
+            } else {
+                eprintln!("Error: lint [{}] is not found", name);
+                process::exit(1);
+            }
+        }
+    }
+    levels
 }
 
 impl<'a> From<&'a clap::ArgMatches<'a>> for AppConfig {
     fn from(matches: &'a clap::ArgMatches) -> Self {
-        let schema_file = value_t!(matches, "schema-file", PathBuf).unwrap_or_else(|e| e.exit());
+        match matches.subcommand() {
+            ("openapi", Some(matches)) => {
+                let schema_file = schema_file_from(matches);
+                let types = values_t!(matches, "type", String).unwrap_or_default();
+                return Self {
+                    schema_file,
+                    language: Language::OpenApi,
+                    c_profile: CProfile::default(),
+                    dissector_port: None,
+                    dissector_heuristic: false,
+                    json_schema: false,
+                    arrow: false,
+                    rayon: false,
+                    small_vectors: None,
+                    transparent: false,
+                    source_readers: false,
+                    linear_verification: false,
+                    pyo3: false,
+                    compact_arrays: false,
+                    typestate_builders: false,
+                    byte_comparisons: false,
+                    builder_validation: false,
+                    checked_getters: false,
+                    field_access: false,
+                    max_encoded_size: false,
+                    types,
+                    flatten: Flatten::default(),
+                    extern_schemas: Vec::new(),
+                    visibility: Visibility::default(),
+                    rust_edition: Edition::default(),
+                    rust_allows: Vec::new(),
+                    rust_format: false,
+                    header_file: header_file_from(matches),
+                    hooks_dir: None,
+                    templates_dir: None,
+                    reproducible: matches.is_present("reproducible"),
+                    verify_reproducible: matches.is_present("verify-reproducible"),
+                    lint_levels: LintLevels::new(),
+                    json_messages: false,
+                };
+            }
+            ("sql", Some(matches)) => {
+                let schema_file = schema_file_from(matches);
+                let types = values_t!(matches, "type", String).unwrap_or_default();
+                let flatten_string =
+                    value_t!(matches, "flatten", String).unwrap_or_else(|e| e.exit());
+                let flatten = flatten_string
+                    .as_str()
+                    .try_into()
+                    .unwrap_or_else(|_| unreachable!());
+                return Self {
+                    schema_file,
+                    language: Language::Sql,
+                    c_profile: CProfile::default(),
+                    dissector_port: None,
+                    dissector_heuristic: false,
+                    json_schema: false,
+                    arrow: false,
+                    rayon: false,
+                    small_vectors: None,
+                    transparent: false,
+                    source_readers: false,
+                    linear_verification: false,
+                    pyo3: false,
+                    compact_arrays: false,
+                    typestate_builders: false,
+                    byte_comparisons: false,
+                    builder_validation: false,
+                    checked_getters: false,
+                    field_access: false,
+                    max_encoded_size: false,
+                    types,
+                    flatten,
+                    extern_schemas: Vec::new(),
+                    visibility: Visibility::default(),
+                    rust_edition: Edition::default(),
+                    rust_allows: Vec::new(),
+                    rust_format: false,
+                    header_file: header_file_from(matches),
+                    hooks_dir: None,
+                    templates_dir: None,
+                    reproducible: matches.is_present("reproducible"),
+                    verify_reproducible: matches.is_present("verify-reproducible"),
+                    lint_levels: LintLevels::new(),
+                    json_messages: false,
+                };
+            }
+            _ => {}
+        }
+        let schema_file = schema_file_from(matches);
         let language_string = value_t!(matches, "language", String).unwrap_or_else(|e| e.exit());
-        if !schema_file.as_path().is_file() {
-            eprintln!(
-                "Error: schema-file [{}] should be a file",
-                schema_file.to_str().unwrap()
-            );
+        let language: Language = language_string
+            .as_str()
+            .try_into()
+            .unwrap_or_else(|_| unreachable!());
+        let c_profile_string = value_t!(matches, "c-profile", String).unwrap_or_else(|e| e.exit());
+        let c_profile = c_profile_string
+            .as_str()
+            .try_into()
+            .unwrap_or_else(|_| unreachable!());
+        let dissector_port = if matches.is_present("dissector-port") {
+            Some(value_t!(matches, "dissector-port", u16).unwrap_or_else(|e| e.exit()))
+        } else {
+            None
+        };
+        let dissector_heuristic = matches.is_present("dissector-heuristic");
+        let json_schema = matches.is_present("json-schema");
+        let arrow = matches.is_present("arrow");
+        let rayon = matches.is_present("rayon");
+        let small_vectors = if matches.is_present("small-vectors") {
+            Some(value_t!(matches, "small-vectors", usize).unwrap_or_else(|e| e.exit()))
+        } else {
+            None
+        };
+        let transparent = matches.is_present("transparent");
+        let source_readers = matches.is_present("source-readers");
+        let linear_verification = matches.is_present("linear-verification");
+        let pyo3 = matches.is_present("pyo3");
+        let compact_arrays = matches.is_present("compact-arrays");
+        let typestate_builders = matches.is_present("typestate-builders");
+        let byte_comparisons = matches.is_present("byte-comparisons");
+        let builder_validation = matches.is_present("builder-validation");
+        let checked_getters = matches.is_present("checked-getters");
+        let field_access = matches.is_present("field-access");
+        let max_encoded_size = matches.is_present("max-encoded-size");
+        let extern_schemas = values_t!(matches, "extern-schema", String)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|value| {
+                let mut parts = value.splitn(2, '=');
+                match (parts.next(), parts.next()) {
+                    (Some(name), Some(path)) if !name.is_empty() && !path.is_empty() => {
+                        (name.to_owned(), path.to_owned())
+                    }
+                    _ => {
+                        eprintln!("Error: extern-schema [{}] should be `<NAME>=<PATH>`", value);
+                        process::exit(1);
+                    }
+                }
+            })
+            .collect();
+        let visibility_string =
+            value_t!(matches, "visibility", String).unwrap_or_else(|e| e.exit());
+        let visibility = visibility_string.as_str().try_into().unwrap_or_else(|err| {
+            eprintln!("Error: {}", err);
             process::exit(1);
+        });
+        let rust_edition_string =
+            value_t!(matches, "rust-edition", String).unwrap_or_else(|e| e.exit());
+        let rust_edition = rust_edition_string
+            .as_str()
+            .try_into()
+            .unwrap_or_else(|_| unreachable!());
+        Self {
+            schema_file,
+            language,
+            c_profile,
+            dissector_port,
+            dissector_heuristic,
+            json_schema,
+            arrow,
+            rayon,
+            small_vectors,
+            transparent,
+            source_readers,
+            linear_verification,
+            pyo3,
+            compact_arrays,
+            typestate_builders,
+            byte_comparisons,
+            builder_validation,
+            checked_getters,
+            field_access,
+            max_encoded_size,
+            types: Vec::new(),
+            flatten: Flatten::default(),
+            extern_schemas,
+            visibility,
+            rust_edition,
+            rust_allows: values_t!(matches, "rust-allow", String).unwrap_or_default(),
+            rust_format: matches.is_present("rust-format"),
+            header_file: header_file_from(matches),
+            hooks_dir: hooks_dir_from(matches),
+            templates_dir: templates_dir_from(matches),
+            reproducible: matches.is_present("reproducible"),
+            verify_reproducible: matches.is_present("verify-reproducible"),
+            lint_levels: lint_levels_from(matches),
+            json_messages: json_messages_from(matches),
         }
-        let language: Language = language_string
+    }
+}
+
+impl<'a> From<&'a clap::ArgMatches<'a>> for LintConfig {
+    fn from(matches: &'a clap::ArgMatches) -> Self {
+        Self {
+            schema_file: schema_file_from(matches),
+            lint_levels: lint_levels_from(matches),
+            json_messages: json_messages_from(matches),
+        }
+    }
+}
+
+impl<'a> From<&'a clap::ArgMatches<'a>> for SuggestConfig {
+    fn from(matches: &'a clap::ArgMatches) -> Self {
+        let schema_file = schema_file_from(matches);
+        let json = matches.is_present("json");
+        Self { schema_file, json }
+    }
+}
+
+impl<'a> From<&'a clap::ArgMatches<'a>> for MinimizeConfig {
+    fn from(matches: &'a clap::ArgMatches) -> Self {
+        let schema_file = schema_file_from(matches);
+        let type_name = value_t!(matches, "type", String).unwrap_or_else(|e| e.exit());
+        let input = value_t!(matches, "input", PathBuf).unwrap_or_else(|e| e.exit());
+        let output = value_t!(matches, "output", PathBuf).ok();
+        let compatible = matches.is_present("compatible");
+        Self {
+            schema_file,
+            type_name,
+            input,
+            output,
+            compatible,
+        }
+    }
+}
+
+impl<'a> From<&'a clap::ArgMatches<'a>> for MutateConfig {
+    fn from(matches: &'a clap::ArgMatches) -> Self {
+        let schema_file = schema_file_from(matches);
+        let type_name = value_t!(matches, "type", String).unwrap_or_else(|e| e.exit());
+        let input = value_t!(matches, "input", PathBuf).unwrap_or_else(|e| e.exit());
+        Self {
+            schema_file,
+            type_name,
+            input,
+        }
+    }
+}
+
+impl<'a> From<&'a clap::ArgMatches<'a>> for PackConfig {
+    fn from(matches: &'a clap::ArgMatches) -> Self {
+        let schema_file = schema_file_from(matches);
+        let type_name = value_t!(matches, "type", String).unwrap_or_else(|e| e.exit());
+        let inputs = values_t!(matches, "input", PathBuf).unwrap_or_else(|e| e.exit());
+        let output = value_t!(matches, "output", PathBuf).unwrap_or_else(|e| e.exit());
+        let append = matches.is_present("append");
+        let compatible = matches.is_present("compatible");
+        Self {
+            schema_file,
+            type_name,
+            inputs,
+            output,
+            append,
+            compatible,
+        }
+    }
+}
+
+impl<'a> From<&'a clap::ArgMatches<'a>> for UnpackConfig {
+    fn from(matches: &'a clap::ArgMatches) -> Self {
+        let schema_file = schema_file_from(matches);
+        let type_name = value_t!(matches, "type", String).unwrap_or_else(|e| e.exit());
+        let input = value_t!(matches, "input", PathBuf).unwrap_or_else(|e| e.exit());
+        let output_dir = value_t!(matches, "output-dir", PathBuf).unwrap_or_else(|e| e.exit());
+        let compatible = matches.is_present("compatible");
+        Self {
+            schema_file,
+            type_name,
+            input,
+            output_dir,
+            compatible,
+        }
+    }
+}
+
+impl<'a> From<&'a clap::ArgMatches<'a>> for MigrateConfig {
+    fn from(matches: &'a clap::ArgMatches) -> Self {
+        let old_schema_file =
+            value_t!(matches, "old-schema-file", PathBuf).unwrap_or_else(|e| e.exit());
+        let new_schema_file =
+            value_t!(matches, "new-schema-file", PathBuf).unwrap_or_else(|e| e.exit());
+        let mapping_file = value_t!(matches, "mapping-file", PathBuf).ok();
+        let old_module = value_t!(matches, "old-module", String).unwrap_or_else(|e| e.exit());
+        let new_module = value_t!(matches, "new-module", String).unwrap_or_else(|e| e.exit());
+        let output = value_t!(matches, "output", PathBuf).ok();
+        Self {
+            old_schema_file,
+            new_schema_file,
+            mapping_file,
+            old_module,
+            new_module,
+            output,
+        }
+    }
+}
+
+impl<'a> From<&'a clap::ArgMatches<'a>> for ExamplesConfig {
+    fn from(matches: &'a clap::ArgMatches) -> Self {
+        let schema_file = schema_file_from(matches);
+        let language_string = value_t!(matches, "language", String).unwrap_or_else(|e| e.exit());
+        let language: ExampleLanguage = language_string
             .as_str()
             .try_into()
             .unwrap_or_else(|_| unreachable!());
+        let module = value_t!(matches, "module", String).ok();
+        let output_dir = value_t!(matches, "output-dir", PathBuf).unwrap_or_else(|e| e.exit());
         Self {
             schema_file,
             language,
+            module,
+            output_dir,
+        }
+    }
+}
+
+impl<'a> From<&'a clap::ArgMatches<'a>> for InferConfig {
+    fn from(matches: &'a clap::ArgMatches) -> Self {
+        let type_name = value_t!(matches, "type", String).unwrap_or_else(|e| e.exit());
+        let inputs = values_t!(matches, "input", PathBuf).unwrap_or_else(|e| e.exit());
+        let output = value_t!(matches, "output", PathBuf).ok();
+        Self {
+            type_name,
+            inputs,
+            output,
         }
     }
 }
@@ -2,14 +2,444 @@
 extern crate clap;
 
 pub(crate) mod config;
+pub(crate) mod repl;
 
-use codegen::Compiler;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
 
-fn main() {
-    let config = config::build_commandline();
+use codegen::{
+    Compiler, Examples, Explorer, Inferrer, Level, LintLevels, Migration, Minimizer, Mutator,
+    Verifier,
+};
+use molecule::molpack::{PackReader, PackWriter};
+
+fn compile(config: config::AppConfig) {
+    check_schema(
+        &config.schema_file,
+        &config.lint_levels,
+        config.json_messages,
+    );
     let mut compiler = Compiler::new();
     compiler
         .language(config.language)
         .file_path(&config.schema_file)
-        .run();
+        .c_profile(config.c_profile)
+        .dissector_heuristic(config.dissector_heuristic)
+        .json_schema(config.json_schema)
+        .arrow(config.arrow)
+        .rayon(config.rayon)
+        .transparent(config.transparent)
+        .source_readers(config.source_readers)
+        .linear_verification(config.linear_verification)
+        .pyo3(config.pyo3)
+        .compact_arrays(config.compact_arrays)
+        .typestate_builders(config.typestate_builders)
+        .byte_comparisons(config.byte_comparisons)
+        .builder_validation(config.builder_validation)
+        .checked_getters(config.checked_getters)
+        .field_access(config.field_access)
+        .max_encoded_size(config.max_encoded_size)
+        .flatten(config.flatten)
+        .visibility(config.visibility)
+        .rust_edition(config.rust_edition)
+        .rust_format(config.rust_format)
+        .reproducible(config.reproducible)
+        .verify_reproducible(config.verify_reproducible);
+    if let Some(port) = config.dissector_port {
+        compiler.dissector_port(port);
+    }
+    if let Some(inline_capacity) = config.small_vectors {
+        compiler.small_vectors(inline_capacity);
+    }
+    if let Some(ref header_file) = config.header_file {
+        compiler.header_file(header_file);
+    }
+    if let Some(ref hooks_dir) = config.hooks_dir {
+        compiler.hooks_dir(hooks_dir);
+    }
+    if let Some(ref templates_dir) = config.templates_dir {
+        compiler.templates_dir(templates_dir);
+    }
+    for lint in &config.rust_allows {
+        compiler.rust_allow(lint);
+    }
+    for name in &config.types {
+        compiler.export_type(name);
+    }
+    for (name, path) in &config.extern_schemas {
+        compiler.extern_schema(name, path);
+    }
+    if let Err(err) = compiler.check_types() {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    }
+    compiler.run();
+    if config.json_messages {
+        eprintln!("{}", codegen::build_finished_message(true));
+    }
+}
+
+/// Print the syntax errors and the diagnostics, and exit if there's any error.
+fn check_schema(schema_file: &Path, levels: &LintLevels, json_messages: bool) {
+    let syntax_errors = codegen::check_syntax(schema_file);
+    for error in &syntax_errors {
+        if json_messages {
+            eprintln!("{}", error.json_message());
+        } else {
+            eprintln!("{}\n", error);
+        }
+    }
+    let diagnostics = if syntax_errors.is_empty() {
+        codegen::lint(schema_file, levels)
+    } else {
+        Vec::new()
+    };
+    for diagnostic in &diagnostics {
+        if json_messages {
+            eprintln!("{}", diagnostic.json_message());
+        } else {
+            eprintln!("{}", diagnostic);
+        }
+    }
+    let denied = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.level == Level::Deny)
+        .count();
+    if !syntax_errors.is_empty() || denied > 0 {
+        if json_messages {
+            eprintln!("{}", codegen::build_finished_message(false));
+        } else if denied > 0 {
+            eprintln!("Error: aborting due to {} denied diagnostics", denied);
+        } else {
+            eprintln!(
+                "Error: aborting due to {} syntax errors",
+                syntax_errors.len()
+            );
+        }
+        process::exit(1);
+    }
+}
+
+fn lint(config: config::LintConfig) {
+    check_schema(
+        &config.schema_file,
+        &config.lint_levels,
+        config.json_messages,
+    );
+    if config.json_messages {
+        eprintln!("{}", codegen::build_finished_message(true));
+    }
+}
+
+fn bounds(schema_file: PathBuf) {
+    let sizes = codegen::max_encoded_sizes(&schema_file);
+    let width = sizes.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    for (name, size) in &sizes {
+        if let Some(size) = size {
+            println!("{:width$} {}", name, size, width = width);
+        } else {
+            println!("{:width$} unbounded", name, width = width);
+        }
+    }
+    let unbounded = sizes.iter().filter(|(_, size)| size.is_none()).count();
+    eprintln!("{} of {} types are unbounded", unbounded, sizes.len());
+}
+
+fn stats(schema_file: PathBuf) {
+    let rows = codegen::schema_stats(&schema_file)
+        .into_iter()
+        .map(|stats| {
+            let fixed_size = stats
+                .fixed_size
+                .map_or_else(|| "-".to_owned(), |size| size.to_string());
+            let max_size = stats
+                .max_size
+                .map_or_else(|| "unbounded".to_owned(), |size| size.to_string());
+            vec![
+                stats.name.clone(),
+                stats.kind.clone(),
+                fixed_size,
+                stats.min_size.to_string(),
+                max_size,
+                format!("{:.1}%", stats.header_overhead()),
+                stats.depth.to_string(),
+                stats.fan_out.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let header = [
+        "Type", "Kind", "Fixed", "Min", "Max", "Overhead", "Depth", "Fan-out",
+    ];
+    let widths = header
+        .iter()
+        .enumerate()
+        .map(|(i, title)| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .chain(Some(title.len()))
+                .max()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    let header = header.iter().map(|title| (*title).to_owned()).collect();
+    for row in Some(header).iter().chain(rows.iter()) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    }
+}
+
+fn suggest(config: config::SuggestConfig) {
+    let suggestions = codegen::suggest(&config.schema_file);
+    if config.json {
+        let values = suggestions
+            .iter()
+            .map(|x| {
+                serde_json::json!({
+                    "type": x.type_name,
+                    "rule": x.rule,
+                    "message": x.message,
+                    "saving": x.saving,
+                })
+            })
+            .collect::<Vec<_>>();
+        println!("{}", serde_json::to_string_pretty(&values).unwrap());
+    } else {
+        for x in &suggestions {
+            println!("{}: {} [{}]", x.type_name, x.message, x.rule);
+        }
+    }
+}
+
+fn explain(schema_file: PathBuf, type_name: String) {
+    let text = Explorer::new(&schema_file)
+        .explain(&type_name)
+        .unwrap_or_else(|err| {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        });
+    println!("{}", text);
+}
+
+fn editor(output_dir: PathBuf) {
+    for (path, content) in codegen::editor_files() {
+        let path = output_dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap_or_else(|err| {
+            eprintln!("Error: failed to write [{}]: {}", path.display(), err);
+            process::exit(1);
+        });
+    }
+}
+
+fn read_file(path: &Path) -> Vec<u8> {
+    fs::read(path).unwrap_or_else(|err| {
+        eprintln!("Error: failed to read [{}]: {}", path.display(), err);
+        process::exit(1);
+    })
+}
+
+fn minimize(config: config::MinimizeConfig) {
+    let input = read_file(&config.input);
+    let (output, err) = Minimizer::new()
+        .file_path(&config.schema_file)
+        .type_name(&config.type_name)
+        .compatible(config.compatible)
+        .minimize(&input)
+        .unwrap_or_else(|err| {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        });
+    eprintln!(
+        "Minimized from {} bytes to {} bytes: {}",
+        input.len(),
+        output.len(),
+        err
+    );
+    if let Some(path) = config.output {
+        fs::write(&path, &output).unwrap();
+    } else {
+        let hex: String = output.iter().map(|b| format!("{:02x}", b)).collect();
+        println!("0x{}", hex);
+    }
+}
+
+fn mutate(config: config::MutateConfig) {
+    let input = read_file(&config.input);
+    let mutations = Mutator::new()
+        .file_path(&config.schema_file)
+        .type_name(&config.type_name)
+        .mutate(&input)
+        .unwrap_or_else(|err| {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        });
+    // The same lines as the differential tests, with the labels as comments.
+    for mutation in &mutations {
+        let hex: String = mutation.data.iter().map(|b| format!("{:02x}", b)).collect();
+        let compatible = if mutation.compatible { "ok" } else { "err" };
+        println!(
+            "{} 0x{} err {} # {}: {}",
+            config.type_name, hex, compatible, mutation.label, mutation.error
+        );
+    }
+    eprintln!("Generated {} mutations", mutations.len());
+}
+
+fn pack(config: config::PackConfig) {
+    let schema_hash = codegen::schema_hash(&config.schema_file);
+    let mut verifier = Verifier::new(&config.schema_file, &config.type_name);
+    verifier.compatible(config.compatible);
+    let mut writer = if config.append {
+        let existing = read_file(&config.output);
+        let existing_hash = PackReader::new(&existing)
+            .map(|reader| reader.schema_hash())
+            .unwrap_or_else(|err| {
+                eprintln!("Error: [{}]: {}", config.output.display(), err);
+                process::exit(1);
+            });
+        if existing_hash != schema_hash {
+            eprintln!(
+                "Error: [{}] is packed with another schema",
+                config.output.display()
+            );
+            process::exit(1);
+        }
+        PackWriter::from_pack(existing).unwrap()
+    } else {
+        PackWriter::new(&schema_hash)
+    };
+    for input in &config.inputs {
+        let record = read_file(input);
+        if let Err(err) = verifier.verify(&record) {
+            eprintln!("Error: [{}]: {}", input.display(), err);
+            process::exit(1);
+        }
+        if let Err(err) = writer.append(&record) {
+            eprintln!("Error: [{}]: {}", input.display(), err);
+            process::exit(1);
+        }
+    }
+    eprintln!("Packed {} records", writer.len());
+    fs::write(&config.output, writer.finish()).unwrap();
+}
+
+fn unpack(config: config::UnpackConfig) {
+    let data = read_file(&config.input);
+    let reader = PackReader::new(&data).unwrap_or_else(|err| {
+        eprintln!("Error: [{}]: {}", config.input.display(), err);
+        process::exit(1);
+    });
+    if reader.schema_hash() != codegen::schema_hash(&config.schema_file) {
+        eprintln!(
+            "Error: [{}] is packed with another schema",
+            config.input.display()
+        );
+        process::exit(1);
+    }
+    let mut verifier = Verifier::new(&config.schema_file, &config.type_name);
+    verifier.compatible(config.compatible);
+    for (idx, record) in reader.iter().enumerate() {
+        if let Err(err) = verifier.verify(record) {
+            eprintln!("Error: record {}: {}", idx, err);
+            process::exit(1);
+        }
+    }
+    fs::create_dir_all(&config.output_dir).unwrap();
+    for (idx, record) in reader.iter().enumerate() {
+        let path = config.output_dir.join(format!("{}.bin", idx));
+        fs::write(&path, record).unwrap();
+    }
+    eprintln!("Unpacked {} records", reader.len());
+}
+
+fn migrate(config: config::MigrateConfig) {
+    let mut migration = Migration::new();
+    migration
+        .old_file_path(&config.old_schema_file)
+        .new_file_path(&config.new_schema_file)
+        .old_module(&config.old_module)
+        .new_module(&config.new_module);
+    if let Some(ref mapping_file) = config.mapping_file {
+        migration.mapping_file(mapping_file);
+    }
+    let mut output = Vec::new();
+    migration.generate(&mut output).unwrap();
+    if let Some(path) = config.output {
+        fs::write(&path, &output).unwrap_or_else(|err| {
+            eprintln!("Error: failed to write [{}]: {}", path.display(), err);
+            process::exit(1);
+        });
+    } else {
+        print!("{}", String::from_utf8(output).unwrap());
+    }
+}
+
+fn examples(config: config::ExamplesConfig) {
+    let mut examples = Examples::new();
+    examples
+        .file_path(&config.schema_file)
+        .language(config.language)
+        .out_dir(&config.output_dir);
+    if let Some(ref module) = config.module {
+        examples.module(module);
+    }
+    examples.generate().unwrap_or_else(|err| {
+        eprintln!(
+            "Error: failed to write [{}]: {}",
+            config.output_dir.display(),
+            err
+        );
+        process::exit(1);
+    });
+}
+
+fn infer(config: config::InferConfig) {
+    let samples = config
+        .inputs
+        .iter()
+        .map(|input| read_file(input))
+        .collect::<Vec<_>>();
+    let schema = Inferrer::new()
+        .type_name(&config.type_name)
+        .infer(&samples)
+        .unwrap_or_else(|err| {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        });
+    if let Some(path) = config.output {
+        fs::write(&path, &schema).unwrap_or_else(|err| {
+            eprintln!("Error: failed to write [{}]: {}", path.display(), err);
+            process::exit(1);
+        });
+    } else {
+        print!("{}", schema);
+    }
+}
+
+fn main() {
+    match config::build_commandline() {
+        config::Command::Compile(config) => compile(config),
+        config::Command::Bounds(schema_file) => bounds(schema_file),
+        config::Command::Stats(schema_file) => stats(schema_file),
+        config::Command::Repl(schema_file) => repl::run(&Explorer::new(&schema_file)),
+        config::Command::Explain(schema_file, type_name) => explain(schema_file, type_name),
+        config::Command::Editor(output_dir) => editor(output_dir),
+        config::Command::Suggest(config) => suggest(config),
+        config::Command::Lint(config) => lint(config),
+        config::Command::Minimize(config) => minimize(config),
+        config::Command::Mutate(config) => mutate(config),
+        config::Command::Pack(config) => pack(config),
+        config::Command::Unpack(config) => unpack(config),
+        config::Command::Migrate(config) => migrate(config),
+        config::Command::Examples(config) => examples(config),
+        config::Command::Infer(config) => infer(config),
+    }
 }
@@ -0,0 +1,91 @@
+use std::io::{self, BufRead, Write as _};
+
+use codegen::Explorer;
+
+const HELP: &str = "\
+types                          list the types
+fields <TYPE>                  print the declaration of a type
+size <TYPE>                    print the sizes of a type
+explain <TYPE>                 print the wire layout of a type, with an example
+decode <TYPE> <HEX> [compat]   verify the data and print it, in the compatible mode if `compat`
+salvage <TYPE> <HEX> [compat]  decode as much of the broken data as possible, with the errors
+help                           print this message
+quit                           exit";
+
+#[allow(clippy::manual_is_multiple_of)]
+fn parse_hex(input: &str) -> Result<Vec<u8>, String> {
+    let input = input.strip_prefix("0x").unwrap_or(input).replace('_', "");
+    if input.len() % 2 != 0 {
+        return Err("the hex should have an even length".to_owned());
+    }
+    (0..input.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&input[i..i + 2], 16)
+                .map_err(|_| format!("the hex is broken at {}", i))
+        })
+        .collect()
+}
+
+/// Run a command, and return the output; return `None` to exit.
+pub(crate) fn run_command(explorer: &Explorer, line: &str) -> Option<Result<String, String>> {
+    let words: Vec<_> = line.split_whitespace().collect();
+    let result = match words.as_slice() {
+        [] => Ok(String::new()),
+        ["quit"] | ["exit"] => return None,
+        ["help"] => Ok(HELP.to_owned()),
+        ["types"] => Ok(explorer.type_names().join("\n")),
+        ["fields", name] => explorer.describe(name),
+        ["size", name] => explorer.stats(name).map(|stats| {
+            let fixed_size = stats
+                .fixed_size
+                .map_or_else(|| "no".to_owned(), |size| size.to_string());
+            let max_size = stats
+                .max_size
+                .map_or_else(|| "unbounded".to_owned(), |size| size.to_string());
+            format!(
+                "fixed: {}, min: {}, max: {}",
+                fixed_size, stats.min_size, max_size
+            )
+        }),
+        ["explain", name] => explorer.explain(name),
+        ["decode", name, hex] => {
+            parse_hex(hex).and_then(|data| explorer.decode(name, &data, false))
+        }
+        ["decode", name, hex, "compat"] => {
+            parse_hex(hex).and_then(|data| explorer.decode(name, &data, true))
+        }
+        ["salvage", name, hex] => parse_hex(hex)
+            .and_then(|data| explorer.salvage(name, &data, false))
+            .map(|value| value.to_string()),
+        ["salvage", name, hex, "compat"] => parse_hex(hex)
+            .and_then(|data| explorer.salvage(name, &data, true))
+            .map(|value| value.to_string()),
+        _ => Err(format!("unknown command `{}`, try `help`", line.trim())),
+    };
+    Some(result)
+}
+
+pub(crate) fn run(explorer: &Explorer) {
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        // The prompt is not a part of the output.
+        eprint!("> ");
+        io::stderr().flush().unwrap();
+        let line = if let Some(line) = lines.next() {
+            line.unwrap()
+        } else {
+            break;
+        };
+        match run_command(explorer, &line) {
+            Some(Ok(output)) => {
+                if !output.is_empty() {
+                    println!("{}", output);
+                }
+            }
+            Some(Err(err)) => println!("Error: {}", err),
+            None => break,
+        }
+    }
+}